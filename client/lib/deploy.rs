@@ -9,7 +9,7 @@ use std::{
 use casper_execution_engine::core::engine_state::ExecutableDeployItem;
 use casper_node::{
     rpcs::{account::PutDeploy, chain::GetBlockResult, info::GetDeploy, RpcWithParams},
-    types::{Deploy, DeployHash, TimeDiff, Timestamp},
+    types::{json_compatibility::Account as JsonAccount, Deploy, DeployHash, TimeDiff, Timestamp},
 };
 use casper_types::{
     account::AccountHash, AsymmetricType, ProtocolVersion, PublicKey, RuntimeArgs, SecretKey,
@@ -28,6 +28,9 @@ use crate::{
 /// production chainspec.
 const MAX_SERIALIZED_SIZE: u32 = 1_024 * 1_024;
 
+/// The key under which a saved deploy file declares the signers required by the deploy.
+const REQUIRED_SIGNERS_KEY: &str = "required_signers";
+
 /// SendDeploy allows sending a deploy to the node.
 pub(crate) struct SendDeploy;
 
@@ -77,6 +80,158 @@ impl From<GetBlockResult> for ListDeploysResult {
     }
 }
 
+/// One of the associated keys of the account under which a deploy will be executed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredSigner {
+    /// The account hash of the associated key.
+    pub account_hash: AccountHash,
+    /// The weight of the associated key.
+    pub weight: u8,
+}
+
+/// The signers required for a deploy to be executed: the associated keys of the account under
+/// which it will be executed, and the weight which those signing it must meet or exceed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredSigners {
+    /// The deployment threshold of the account.
+    pub deployment_threshold: u8,
+    /// The associated keys of the account.
+    pub associated_keys: Vec<RequiredSigner>,
+}
+
+impl From<&JsonAccount> for RequiredSigners {
+    fn from(account: &JsonAccount) -> Self {
+        RequiredSigners {
+            deployment_threshold: account.deployment_threshold(),
+            associated_keys: account
+                .associated_keys()
+                .map(|(account_hash, weight)| RequiredSigner {
+                    account_hash: *account_hash,
+                    weight,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A saved deploy file: the JSON-encoded `Deploy`, optionally with the signers it requires
+/// declared under an extra top-level field.
+#[derive(Serialize)]
+struct DeployFile<'a> {
+    #[serde(flatten)]
+    deploy: &'a Deploy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required_signers: Option<&'a RequiredSigners>,
+}
+
+/// The signing status of one of an account's associated keys with respect to a given deploy.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AssociatedKeyApproval {
+    /// The account hash of the associated key.
+    pub account_hash: AccountHash,
+    /// The weight of the associated key.
+    pub weight: u8,
+    /// Whether the deploy has been signed by the associated key.
+    pub signed: bool,
+}
+
+/// A report of whether the approvals of a deploy satisfy the deployment threshold of the account
+/// under which it will be executed.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApprovalsReport {
+    /// The hash of the deploy.
+    pub deploy_hash: DeployHash,
+    /// The account under which the deploy will be executed.
+    pub account: PublicKey,
+    /// The weight which the deploy's signers must meet or exceed for it to be executed.
+    pub deployment_threshold: u8,
+    /// The total weight of the associated keys which have signed the deploy.
+    pub approved_weight: u8,
+    /// Whether `approved_weight` meets or exceeds `deployment_threshold`.
+    pub threshold_met: bool,
+    /// The associated keys of the account, i.e. the signers of which the deploy requires enough to
+    /// meet the threshold.
+    pub associated_keys: Vec<AssociatedKeyApproval>,
+    /// Signers of the deploy which are not associated keys of the account.  A deploy with any such
+    /// signers will be rejected by the network regardless of `threshold_met`.
+    pub unassociated_signers: Vec<PublicKey>,
+    /// Signers whose approvals are not valid signatures of the deploy hash.  These don't count
+    /// towards `approved_weight`, and a deploy with any such approvals will be rejected by the
+    /// network regardless of `threshold_met`.
+    pub invalid_signers: Vec<PublicKey>,
+}
+
+impl ApprovalsReport {
+    /// Constructs a new `ApprovalsReport` for `deploy` given the signers it requires.
+    ///
+    /// Only approvals which are valid signatures of the deploy hash are treated as signed.
+    pub(super) fn new(deploy: &Deploy, required_signers: &RequiredSigners) -> Self {
+        let (valid_approvals, invalid_approvals): (Vec<_>, Vec<_>) = deploy
+            .approvals()
+            .iter()
+            .partition(|approval| approval.verify(deploy.id()).is_ok());
+        let signers = valid_approvals
+            .into_iter()
+            .map(|approval| approval.signer().clone())
+            .collect::<Vec<_>>();
+        let invalid_signers = invalid_approvals
+            .into_iter()
+            .map(|approval| approval.signer().clone())
+            .collect();
+        let signer_hashes = signers
+            .iter()
+            .map(PublicKey::to_account_hash)
+            .collect::<Vec<_>>();
+
+        let associated_keys = required_signers
+            .associated_keys
+            .iter()
+            .map(|required_signer| AssociatedKeyApproval {
+                account_hash: required_signer.account_hash,
+                weight: required_signer.weight,
+                signed: signer_hashes.contains(&required_signer.account_hash),
+            })
+            .collect::<Vec<_>>();
+        let approved_weight = associated_keys
+            .iter()
+            .filter(|associated_key| associated_key.signed)
+            .fold(0u8, |total, associated_key| {
+                total.saturating_add(associated_key.weight)
+            });
+        let unassociated_signers = signers
+            .into_iter()
+            .filter(|signer| {
+                let account_hash = signer.to_account_hash();
+                !associated_keys
+                    .iter()
+                    .any(|associated_key| associated_key.account_hash == account_hash)
+            })
+            .collect();
+
+        let deployment_threshold = required_signers.deployment_threshold;
+        ApprovalsReport {
+            deploy_hash: *deploy.id(),
+            account: deploy.header().account().clone(),
+            deployment_threshold,
+            approved_weight,
+            threshold_met: approved_weight >= deployment_threshold,
+            associated_keys,
+            unassociated_signers,
+            invalid_signers,
+        }
+    }
+
+    /// Returns `true` if the deploy's approvals are all valid and from associated keys, and meet
+    /// the deployment threshold.
+    pub fn is_sufficient(&self) -> bool {
+        self.threshold_met
+            && self.unassociated_signers.is_empty()
+            && self.invalid_signers.is_empty()
+    }
+}
+
 /// An output abstraction for associating a Write with some metadata.
 pub(super) enum OutputKind<'a> {
     File {
@@ -204,16 +359,44 @@ pub(super) trait DeployExt {
     where
         W: Write;
 
+    /// Writes the `Deploy` to the `output`, declaring `maybe_required_signers` alongside it if
+    /// provided.
+    fn write_deploy_with_required_signers<W>(
+        &self,
+        maybe_required_signers: Option<&RequiredSigners>,
+        output: W,
+    ) -> Result<()>
+    where
+        W: Write;
+
     /// Reads a `Deploy` from the `input`.
     fn read_deploy<R>(input: R) -> Result<Deploy>
     where
         R: Read;
 
+    /// Reads a `Deploy` from the `input`, along with the signers it requires if they are declared.
+    fn read_deploy_with_required_signers<R>(input: R) -> Result<(Deploy, Option<RequiredSigners>)>
+    where
+        R: Read;
+
     /// Reads a `Deploy` from the reader at `input`, signs it, then writes it back to `output`.
+    ///
+    /// Any required signers declared in `input` are preserved.
     fn sign_and_write_deploy<R, W>(input: R, secret_key: SecretKey, output: W) -> Result<()>
     where
         R: Read,
         W: Write;
+
+    /// Reads a `Deploy` from each of the readers in `inputs`, merges all their approvals into a
+    /// single `Deploy`, then writes it to `output`.
+    ///
+    /// All of the inputs must be the same `Deploy`, differing only in their approvals.  Any
+    /// required signers declared in the inputs are preserved, and must be the same in all which
+    /// declare them.
+    fn merge_and_write_deploys<R, W>(inputs: Vec<R>, output: W) -> Result<()>
+    where
+        R: Read,
+        W: Write;
 }
 
 impl DeployExt for Deploy {
@@ -242,6 +425,8 @@ impl DeployExt for Deploy {
             session,
             &secret_key,
             session_account,
+            None,
+            None,
         );
         deploy.is_valid_size(MAX_SERIALIZED_SIZE)?;
         Ok(deploy)
@@ -299,11 +484,26 @@ impl DeployExt for Deploy {
         Deploy::with_payment_and_session(params, payment, session)
     }
 
-    fn write_deploy<W>(&self, mut output: W) -> Result<()>
+    fn write_deploy<W>(&self, output: W) -> Result<()>
+    where
+        W: Write,
+    {
+        self.write_deploy_with_required_signers(None, output)
+    }
+
+    fn write_deploy_with_required_signers<W>(
+        &self,
+        maybe_required_signers: Option<&RequiredSigners>,
+        mut output: W,
+    ) -> Result<()>
     where
         W: Write,
     {
-        let content = serde_json::to_string_pretty(self)?;
+        let deploy_file = DeployFile {
+            deploy: self,
+            required_signers: maybe_required_signers,
+        };
+        let content = serde_json::to_string_pretty(&deploy_file)?;
         output
             .write_all(content.as_bytes())
             .map_err(|error| Error::IoError {
@@ -313,13 +513,26 @@ impl DeployExt for Deploy {
     }
 
     fn read_deploy<R>(input: R) -> Result<Deploy>
+    where
+        R: Read,
+    {
+        Deploy::read_deploy_with_required_signers(input).map(|(deploy, _)| deploy)
+    }
+
+    fn read_deploy_with_required_signers<R>(input: R) -> Result<(Deploy, Option<RequiredSigners>)>
     where
         R: Read,
     {
         let reader = BufReader::new(input);
-        let deploy: Deploy = serde_json::from_reader(reader)?;
+        let mut content: serde_json::Value = serde_json::from_reader(reader)?;
+        let maybe_required_signers = content
+            .as_object_mut()
+            .and_then(|object| object.remove(REQUIRED_SIGNERS_KEY))
+            .map(serde_json::from_value)
+            .transpose()?;
+        let deploy: Deploy = serde_json::from_value(content)?;
         deploy.is_valid_size(MAX_SERIALIZED_SIZE)?;
-        Ok(deploy)
+        Ok((deploy, maybe_required_signers))
     }
 
     fn sign_and_write_deploy<R, W>(input: R, secret_key: SecretKey, output: W) -> Result<()>
@@ -327,12 +540,65 @@ impl DeployExt for Deploy {
         R: Read,
         W: Write,
     {
-        let mut deploy = Deploy::read_deploy(input)?;
+        let (mut deploy, maybe_required_signers) =
+            Deploy::read_deploy_with_required_signers(input)?;
         deploy.sign(&secret_key);
         deploy.is_valid_size(MAX_SERIALIZED_SIZE)?;
-        deploy.write_deploy(output)?;
+        deploy.write_deploy_with_required_signers(maybe_required_signers.as_ref(), output)?;
         Ok(())
     }
+
+    fn merge_and_write_deploys<R, W>(inputs: Vec<R>, output: W) -> Result<()>
+    where
+        R: Read,
+        W: Write,
+    {
+        let mut inputs = inputs.into_iter();
+        let (mut merged, mut maybe_required_signers) = match inputs.next() {
+            Some(input) => Deploy::read_deploy_with_required_signers(input)?,
+            None => {
+                return Err(Error::InvalidArgument {
+                    context: "merge_approvals",
+                    error: "at least one deploy is required".to_string(),
+                })
+            }
+        };
+
+        for input in inputs {
+            let (deploy, maybe_declared) = Deploy::read_deploy_with_required_signers(input)?;
+            if deploy.id() != merged.id() {
+                return Err(Error::InvalidArgument {
+                    context: "merge_approvals",
+                    error: format!(
+                        "deploy hashes differ: expected {}, got {}",
+                        merged.id(),
+                        deploy.id()
+                    ),
+                });
+            }
+            match (&maybe_required_signers, maybe_declared) {
+                (Some(required_signers), Some(declared)) if *required_signers != declared => {
+                    return Err(Error::InvalidArgument {
+                        context: "merge_approvals",
+                        error: format!(
+                            "declared required signers of deploy {} differ",
+                            merged.id()
+                        ),
+                    });
+                }
+                (None, Some(declared)) => maybe_required_signers = Some(declared),
+                _ => (),
+            }
+            merged.add_approvals(deploy.approvals().iter().cloned());
+        }
+
+        merged.is_valid().map_err(|error| Error::InvalidArgument {
+            context: "merge_approvals",
+            error: error.to_string(),
+        })?;
+        merged.is_valid_size(MAX_SERIALIZED_SIZE)?;
+        merged.write_deploy_with_required_signers(maybe_required_signers.as_ref(), output)
+    }
 }
 
 #[cfg(test)]
@@ -490,7 +756,7 @@ mod tests {
             PaymentStrParams::with_package_hash(PKG_HASH, VERSION, ENTRYPOINT, args_simple(), "");
         // Create a string arg of 1048576 letter 'a's to ensure the deploy is greater than 1048576
         // bytes.
-        let large_args_simple = format!("name_01:string='{:a<1048576}'", "");
+        let large_args_simple = format!("name_01:string='{}'", "a".repeat(1048576));
 
         let session_params = SessionStrParams::with_package_hash(
             PKG_HASH,
@@ -549,6 +815,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_merge_approvals() {
+        let secret_key_1 = SecretKey::generate_ed25519().unwrap();
+        let secret_key_2 = SecretKey::generate_ed25519().unwrap();
+
+        let mut signed_1 = Vec::new();
+        Deploy::sign_and_write_deploy(SAMPLE_DEPLOY.as_bytes(), secret_key_1, &mut signed_1)
+            .unwrap();
+        let mut signed_2 = Vec::new();
+        Deploy::sign_and_write_deploy(SAMPLE_DEPLOY.as_bytes(), secret_key_2, &mut signed_2)
+            .unwrap();
+
+        let mut result = Vec::new();
+        Deploy::merge_and_write_deploys(vec![&signed_1[..], &signed_2[..]], &mut result).unwrap();
+        let mut merged = Deploy::read_deploy(&result[..]).unwrap();
+
+        // The two approvals from the sample deploy are common to both inputs, so should only
+        // appear once each.
+        assert_eq!(merged.approvals().len(), 4);
+        merged
+            .is_valid()
+            .unwrap_or_else(|error| panic!("{} - {:#?}", error, merged));
+    }
+
+    #[test]
+    fn should_only_count_valid_approvals_towards_threshold() {
+        use casper_types::{
+            account::{Account, ActionThresholds, AssociatedKeys, Weight},
+            AccessRights,
+        };
+
+        let secret_key_1 = SecretKey::generate_ed25519().unwrap();
+        let public_key_1 = PublicKey::from(&secret_key_1);
+        let secret_key_2 = SecretKey::generate_ed25519().unwrap();
+        let public_key_2 = PublicKey::from(&secret_key_2);
+
+        let mut signed = Vec::new();
+        Deploy::sign_and_write_deploy(SAMPLE_DEPLOY.as_bytes(), secret_key_1, &mut signed).unwrap();
+
+        // Forge an approval claiming to be from the second key, but reusing the first key's
+        // signature.
+        let mut json: serde_json::Value = serde_json::from_slice(&signed).unwrap();
+        let approvals = json["approvals"].as_array_mut().unwrap();
+        let mut forged = approvals.last().unwrap().clone();
+        forged["signer"] = serde_json::Value::String(public_key_2.to_hex());
+        approvals.push(forged);
+        let deploy = Deploy::read_deploy(json.to_string().as_bytes()).unwrap();
+
+        let mut associated_keys =
+            AssociatedKeys::new(public_key_1.to_account_hash(), Weight::new(1));
+        associated_keys
+            .add_key(public_key_2.to_account_hash(), Weight::new(1))
+            .unwrap();
+        let account = Account::new(
+            deploy.header().account().to_account_hash(),
+            Default::default(),
+            URef::new([1; 32], AccessRights::READ_ADD_WRITE),
+            associated_keys,
+            ActionThresholds::new(Weight::new(2), Weight::new(2)).unwrap(),
+        );
+
+        let report = ApprovalsReport::new(
+            &deploy,
+            &RequiredSigners::from(&JsonAccount::from(&account)),
+        );
+        assert_eq!(report.approved_weight, 1);
+        assert!(!report.threshold_met);
+        assert_eq!(report.invalid_signers, vec![public_key_2]);
+        assert_eq!(report.unassociated_signers.len(), 2);
+    }
+
+    #[test]
+    fn should_preserve_declared_required_signers() {
+        let secret_key_1 = SecretKey::generate_ed25519().unwrap();
+        let public_key_1 = PublicKey::from(&secret_key_1);
+        let secret_key_2 = SecretKey::generate_ed25519().unwrap();
+        let public_key_2 = PublicKey::from(&secret_key_2);
+        let required_signers = RequiredSigners {
+            deployment_threshold: 2,
+            associated_keys: vec![
+                RequiredSigner {
+                    account_hash: public_key_1.to_account_hash(),
+                    weight: 1,
+                },
+                RequiredSigner {
+                    account_hash: public_key_2.to_account_hash(),
+                    weight: 1,
+                },
+            ],
+        };
+
+        // The approvals aren't covered by the deploy hash, so dropping the sample deploy's
+        // approvals leaves it valid.
+        let mut json: serde_json::Value = serde_json::from_str(SAMPLE_DEPLOY).unwrap();
+        json["approvals"] = serde_json::Value::Array(vec![]);
+        let deploy = Deploy::read_deploy(json.to_string().as_bytes()).unwrap();
+        let mut unsigned = Vec::new();
+        deploy
+            .write_deploy_with_required_signers(Some(&required_signers), &mut unsigned)
+            .unwrap();
+
+        let mut signed_1 = Vec::new();
+        Deploy::sign_and_write_deploy(&unsigned[..], secret_key_1, &mut signed_1).unwrap();
+        let mut signed_2 = Vec::new();
+        Deploy::sign_and_write_deploy(&unsigned[..], secret_key_2, &mut signed_2).unwrap();
+
+        let (partially_signed, maybe_declared) =
+            Deploy::read_deploy_with_required_signers(&signed_1[..]).unwrap();
+        assert_eq!(maybe_declared.as_ref(), Some(&required_signers));
+        assert!(!ApprovalsReport::new(&partially_signed, &required_signers).is_sufficient());
+
+        let mut merged = Vec::new();
+        Deploy::merge_and_write_deploys(vec![&signed_1[..], &signed_2[..]], &mut merged).unwrap();
+        let (fully_signed, maybe_declared) =
+            Deploy::read_deploy_with_required_signers(&merged[..]).unwrap();
+        assert_eq!(maybe_declared.as_ref(), Some(&required_signers));
+        assert!(ApprovalsReport::new(&fully_signed, &required_signers).is_sufficient());
+
+        // A file declaring required signers can still be read as a plain `Deploy`.
+        assert_eq!(Deploy::read_deploy(&merged[..]).unwrap(), fully_signed);
+    }
+
+    #[test]
+    fn should_fail_to_merge_approvals_of_different_deploys() {
+        let deploy_params = deploy_params();
+        let payment_params =
+            PaymentStrParams::with_package_hash(PKG_HASH, VERSION, ENTRYPOINT, args_simple(), "");
+        let session_params =
+            SessionStrParams::with_package_hash(PKG_HASH, VERSION, ENTRYPOINT, args_simple(), "");
+        let mut other = Vec::new();
+        Deploy::with_payment_and_session(
+            deploy_params.try_into().unwrap(),
+            payment_params.try_into().unwrap(),
+            session_params.try_into().unwrap(),
+        )
+        .unwrap()
+        .write_deploy(&mut other)
+        .unwrap();
+
+        let result = Deploy::merge_and_write_deploys(
+            vec![SAMPLE_DEPLOY.as_bytes(), &other[..]],
+            &mut Vec::new(),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument {
+                context: "merge_approvals",
+                error: _
+            })
+        ));
+    }
+
     #[test]
     fn should_create_transfer() {
         use casper_types::{AsymmetricType, PublicKey};
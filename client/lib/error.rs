@@ -9,7 +9,7 @@ use casper_types::{
     bytesrepr::Error as ToBytesError, CLValueError, UIntParseError, URefFromStrError,
 };
 
-use crate::validation::ValidateResponseError;
+use crate::{deploy::ApprovalsReport, validation::ValidateResponseError};
 
 /// Crate-wide Result type wrapper.
 pub(crate) type Result<T> = std::result::Result<T, Error>;
@@ -147,6 +147,10 @@ pub enum Error {
         error: String,
     },
 
+    /// The approvals of a deploy don't satisfy the required signers declared with it.
+    #[error("Insufficient approvals: {0:?}")]
+    InsufficientApprovals(Box<ApprovalsReport>),
+
     /// Conflicting arguments.
     #[error("Conflicting arguments passed '{context}' {args:?}")]
     ConflictingArguments {
@@ -0,0 +1,194 @@
+use std::{collections::BTreeSet, fs};
+
+use hex::FromHex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use casper_hashing::Digest;
+use casper_node::{crypto, types::JsonBlock, EraEvidence};
+use casper_types::{EraId, PublicKey};
+
+use crate::error::{Error, Result};
+
+/// The outcome of verifying a single item of evidence against a validator.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EvidenceReport {
+    /// The era in which the validator is alleged to have equivocated.
+    pub era_id: EraId,
+    /// The validator alleged to have equivocated.
+    pub perpetrator: PublicKey,
+    /// Whether the evidence proves that the validator equivocated.
+    pub valid: bool,
+    /// The reason the evidence was rejected, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl EvidenceReport {
+    /// Verifies `era_evidence` against the trusted validators of `trusted_era_id` and the trusted
+    /// chainspec hash, and records the outcome.
+    pub(crate) fn new(
+        era_evidence: &EraEvidence,
+        trusted_era_id: EraId,
+        trusted_validators: &BTreeSet<PublicKey>,
+        trusted_chainspec_hash: &Digest,
+    ) -> Self {
+        let error = if era_evidence.era_id() != trusted_era_id {
+            Some(format!(
+                "the evidence is for era {}, but the switch block determines the validators of \
+                era {}",
+                era_evidence.era_id(),
+                trusted_era_id
+            ))
+        } else {
+            casper_node::verify_evidence(era_evidence, trusted_validators, trusted_chainspec_hash)
+                .err()
+                .map(|error| error.to_string())
+        };
+        EvidenceReport {
+            era_id: era_evidence.era_id(),
+            perpetrator: era_evidence.perpetrator().clone(),
+            valid: error.is_none(),
+            error,
+        }
+    }
+}
+
+/// Parses the evidence in `input`, which may be a complete "info_get_evidence" response, the
+/// `result` of such a response, or a single item of evidence.
+pub(crate) fn parse_evidence(input: &str) -> Result<Vec<EraEvidence>> {
+    let mut json: Value = serde_json::from_str(input)?;
+    if let Some(result) = json.get_mut("result") {
+        json = result.take();
+    }
+    if let Some(evidence) = json.get_mut("evidence") {
+        return Ok(serde_json::from_value(evidence.take())?);
+    }
+    Ok(vec![serde_json::from_value(json)?])
+}
+
+/// Parses the block in `input`, which may be a complete "chain_get_block" response, the `result`
+/// of such a response, or the block itself, and returns the era whose validators it determines
+/// along with those validators.
+pub(crate) fn parse_switch_block(input: &str) -> Result<(EraId, BTreeSet<PublicKey>)> {
+    let mut json: Value = serde_json::from_str(input)?;
+    if let Some(result) = json.get_mut("result") {
+        json = result.take();
+    }
+    if let Some(block) = json.get_mut("block") {
+        json = block.take();
+    }
+    let block: JsonBlock = serde_json::from_value(json)?;
+    let era_end = block
+        .header
+        .era_end
+        .as_ref()
+        .ok_or_else(|| Error::InvalidArgument {
+            context: "verify_evidence",
+            error: format!("block {} is not a switch block", block.hash),
+        })?;
+    let validators = era_end
+        .next_era_validator_weights()
+        .map(|(validator, _weight)| validator.clone())
+        .collect();
+    Ok((block.header.era_id.successor(), validators))
+}
+
+/// Reads the evidence in the file at `input_path` and verifies each item of it against the
+/// validators determined by the switch block in the file at `switch_block_path` and against
+/// `chainspec_hash`.
+pub(crate) fn verify_evidence_file(
+    input_path: &str,
+    switch_block_path: &str,
+    chainspec_hash: &str,
+) -> Result<Vec<EvidenceReport>> {
+    let trusted_chainspec_hash =
+        Digest::from_hex(chainspec_hash).map_err(|error| Error::CryptoError {
+            context: "chainspec_hash",
+            error: crypto::Error::FromHex(error),
+        })?;
+    let switch_block = fs::read_to_string(switch_block_path).map_err(|error| Error::IoError {
+        context: format!(
+            "unable to read switch block file at '{}'",
+            switch_block_path
+        ),
+        error,
+    })?;
+    let (trusted_era_id, trusted_validators) = parse_switch_block(&switch_block)?;
+    let input = fs::read_to_string(input_path).map_err(|error| Error::IoError {
+        context: format!("unable to read evidence file at '{}'", input_path),
+        error,
+    })?;
+    Ok(parse_evidence(&input)?
+        .iter()
+        .map(|era_evidence| {
+            EvidenceReport::new(
+                era_evidence,
+                trusted_era_id,
+                &trusted_validators,
+                &trusted_chainspec_hash,
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_node::rpcs::docs::DocExample;
+
+    use super::*;
+
+    #[test]
+    fn should_reject_malformed_input() {
+        assert!(matches!(
+            parse_evidence("{\"evidence\": 3}"),
+            Err(Error::InvalidJson(_))
+        ));
+        assert!(matches!(
+            parse_evidence("not json"),
+            Err(Error::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_non_switch_block() {
+        let block = JsonBlock::doc_example();
+        assert!(block.header.era_end.is_some());
+        let mut json = serde_json::to_value(block).unwrap();
+        json["header"]["era_end"] = Value::Null;
+        assert!(matches!(
+            parse_switch_block(&json.to_string()),
+            Err(Error::InvalidArgument {
+                context: "verify_evidence",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn should_parse_switch_block_validators() {
+        let block = JsonBlock::doc_example();
+        let expected_validators: BTreeSet<PublicKey> = block
+            .header
+            .era_end
+            .as_ref()
+            .unwrap()
+            .next_era_validator_weights()
+            .map(|(validator, _weight)| validator.clone())
+            .collect();
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "api_version": "1.0.0", "block": block }
+        });
+        let (era_id, validators) = parse_switch_block(&response.to_string()).unwrap();
+        assert_eq!(era_id, block.header.era_id.successor());
+        assert_eq!(validators, expected_validators);
+    }
+
+    #[test]
+    fn should_accept_empty_result() {
+        let response = r#"{"jsonrpc":"2.0","id":1,"result":{"api_version":"1.0.0","evidence":[]}}"#;
+        assert!(parse_evidence(response).unwrap().is_empty());
+    }
+}
@@ -55,6 +55,7 @@ pub enum casper_error_t {
     CASPER_DEPLOY_SIZE_TOO_LARGE = -24,
     CASPER_FAILED_TO_CREATE_DICTIONARY_IDENTIFIER = -25,
     CASPER_FAILED_TO_PARSE_STATE_IDENTIFIER = -26,
+    CASPER_INSUFFICIENT_APPROVALS = -27,
 }
 
 trait AsFFIError {
@@ -96,6 +97,7 @@ impl AsFFIError for Error {
             Error::FailedToParseStateIdentifier => {
                 casper_error_t::CASPER_FAILED_TO_PARSE_STATE_IDENTIFIER
             }
+            Error::InsufficientApprovals(_) => casper_error_t::CASPER_INSUFFICIENT_APPROVALS,
         }
     }
 }
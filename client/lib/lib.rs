@@ -15,6 +15,7 @@
 mod cl_type;
 mod deploy;
 mod error;
+mod evidence;
 #[cfg(feature = "ffi")]
 pub mod ffi;
 pub mod keygen;
@@ -22,7 +23,11 @@ mod parsing;
 mod rpc;
 mod validation;
 
-use std::{convert::TryInto, fs, io::Cursor};
+use std::{
+    convert::TryInto,
+    fs::{self, File},
+    io::Cursor,
+};
 
 use hex::FromHex;
 use jsonrpc_lite::JsonRpc;
@@ -32,16 +37,19 @@ use casper_execution_engine::core::engine_state::ExecutableDeployItem;
 use casper_hashing::Digest;
 use casper_node::{
     crypto,
-    rpcs::state::{DictionaryIdentifier, GlobalStateIdentifier},
-    types::{BlockHash, Deploy},
+    rpcs::state::{DictionaryIdentifier, GetAccountInfoResult, GlobalStateIdentifier},
+    types::{BlockHash, Deploy, DeployConfigurationFailure},
 };
-use casper_types::Key;
+use casper_types::{AsymmetricType, Key, PublicKey};
 
 pub use cl_type::help;
-pub use deploy::ListDeploysResult;
+pub use deploy::{
+    ApprovalsReport, AssociatedKeyApproval, ListDeploysResult, RequiredSigner, RequiredSigners,
+};
 use deploy::{DeployExt, DeployParams, OutputKind};
 pub use error::Error;
 use error::Result;
+pub use evidence::EvidenceReport;
 use rpc::RpcCall;
 pub use validation::ValidateResponseError;
 
@@ -121,6 +129,72 @@ pub fn make_deploy(
     output.commit()
 }
 
+/// Creates a `Deploy` declaring the signers it requires, and outputs it to a file or stdout.
+///
+/// The required signers are the associated keys of the account under which the `Deploy` will be
+/// executed, along with the account's deployment threshold, as retrieved from the node via the
+/// "state_get_account_info" RPC.  They are preserved when the `Deploy` is signed using
+/// [`sign_deploy_file()`](fn.sign_deploy_file.html) or its approvals are merged using
+/// [`merge_approvals()`](fn.merge_approvals.html), and
+/// [`send_deploy_file()`](fn.send_deploy_file.html) refuses to send the `Deploy` until its
+/// approvals satisfy them.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `maybe_output_path` specifies the output file, or if empty, will print it to `stdout`.
+/// * `deploy_params` contains deploy-related options for this `Deploy`. See
+///   [`DeployStrParams`](struct.DeployStrParams.html) for more details.
+/// * `session_params` contains session-related options for this `Deploy`. See
+///   [`SessionStrParams`](struct.SessionStrParams.html) for more details.
+/// * `payment_params` contains payment-related options for this `Deploy`. See
+///   [`PaymentStrParams`](struct.PaymentStrParams.html) for more details.
+/// * If `force` is true, and a file exists at `maybe_output_path`, it will be overwritten. If
+///   `force` is false and a file exists at `maybe_output_path`,
+///   [`Error::FileAlreadyExists`](enum.Error.html#variant.FileAlreadyExists) is returned and a file
+///   will not be written.
+#[allow(clippy::too_many_arguments)]
+pub async fn make_deploy_with_required_signers(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_output_path: &str,
+    deploy_params: DeployStrParams<'_>,
+    session_params: SessionStrParams<'_>,
+    payment_params: PaymentStrParams<'_>,
+    force: bool,
+) -> Result<()> {
+    let output = if maybe_output_path.is_empty() {
+        OutputKind::Stdout
+    } else {
+        OutputKind::file(maybe_output_path, force)
+    };
+
+    let deploy = Deploy::with_payment_and_session(
+        deploy_params.try_into()?,
+        payment_params.try_into()?,
+        session_params.try_into()?,
+    )?;
+    let required_signers = get_required_signers(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        deploy.header().account(),
+        "",
+    )
+    .await?;
+    deploy.write_deploy_with_required_signers(Some(&required_signers), output.get()?)?;
+
+    output.commit()
+}
+
 /// Reads a previously-saved `Deploy` from a file, cryptographically signs it, and outputs it to a
 /// file or stdout.
 ///
@@ -155,8 +229,133 @@ pub fn sign_deploy_file(
     output.commit()
 }
 
+/// Reads several copies of a previously-saved `Deploy` from files, merges all of their approvals
+/// into a single `Deploy`, and outputs it to a file or stdout.
+///
+/// This allows the holders of an account's associated keys to each sign their own copy of a
+/// `Deploy` using [`sign_deploy_file()`](fn.sign_deploy_file.html), after which the copies can be
+/// combined into a single `Deploy` carrying all of the approvals.
+///
+/// * `input_paths` specifies the paths to the previously-saved `Deploy` files.  These must all be
+///   the same `Deploy`, differing only in their approvals, otherwise
+///   [`Error::InvalidArgument`](enum.Error.html#variant.InvalidArgument) is returned.
+/// * `maybe_output_path` specifies the output file, or if empty, will print it to `stdout`.
+/// * If `force` is true, and a file exists at `maybe_output_path`, it will be overwritten. If
+///   `force` is false and a file exists at `maybe_output_path`,
+///   [`Error::FileAlreadyExists`](enum.Error.html#variant.FileAlreadyExists) is returned and a file
+///   will not be written.
+pub fn merge_approvals(input_paths: &[&str], maybe_output_path: &str, force: bool) -> Result<()> {
+    let inputs = input_paths
+        .iter()
+        .map(|input_path| {
+            File::open(input_path).map_err(|error| Error::IoError {
+                context: format!("unable to read deploy file at '{}'", input_path),
+                error,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = if maybe_output_path.is_empty() {
+        OutputKind::Stdout
+    } else {
+        OutputKind::file(maybe_output_path, force)
+    };
+
+    Deploy::merge_and_write_deploys(inputs, output.get()?)?;
+
+    output.commit()
+}
+
+/// Reads a previously-saved `Deploy` from a file and reports whether its approvals meet the
+/// deployment threshold of the account under which it will be executed.
+///
+/// The associated keys and action thresholds of the account are retrieved from the node via the
+/// "state_get_account_info" RPC.  The returned [`ApprovalsReport`](struct.ApprovalsReport.html)
+/// lists each of the associated keys along with whether it has signed the `Deploy`, so it can
+/// also be used to find the signers still required before sending the `Deploy` using
+/// [`send_deploy_file()`](fn.send_deploy_file.html).
+///
+/// Each approval's signature is verified against the deploy hash; only valid approvals count as
+/// signed, and the signers of any invalid ones are listed separately in the report.  An error is
+/// returned if the `Deploy` itself is invalid, e.g. if its hash doesn't match its contents.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `input_path` specifies the path to the previously-saved `Deploy` file.
+/// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
+///   `Block` height or empty. If empty, the latest `Block` will be used to retrieve the account.
+pub async fn check_approvals(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    input_path: &str,
+    maybe_block_id: &str,
+) -> Result<ApprovalsReport> {
+    let input = File::open(input_path).map_err(|error| Error::IoError {
+        context: format!("unable to read deploy file at '{}'", input_path),
+        error,
+    })?;
+    let mut deploy = Deploy::read_deploy(input)?;
+    match deploy.is_valid() {
+        Ok(())
+        | Err(DeployConfigurationFailure::EmptyApprovals)
+        | Err(DeployConfigurationFailure::InvalidApproval { .. }) => (),
+        Err(error) => {
+            return Err(Error::InvalidArgument {
+                context: "check_approvals",
+                error: format!("invalid deploy: {}", error),
+            })
+        }
+    }
+
+    let required_signers = get_required_signers(
+        maybe_rpc_id,
+        node_address,
+        verbosity_level,
+        deploy.header().account(),
+        maybe_block_id,
+    )
+    .await?;
+
+    Ok(ApprovalsReport::new(&deploy, &required_signers))
+}
+
+/// Retrieves the associated keys and deployment threshold of `account` from the node.
+async fn get_required_signers(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    account: &PublicKey,
+    maybe_block_id: &str,
+) -> Result<RequiredSigners> {
+    let response = RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_account_info(&account.to_hex(), maybe_block_id)
+        .await?;
+    let result = response
+        .get_result()
+        .cloned()
+        .ok_or_else(|| Error::InvalidRpcResponse(response.clone()))?;
+    let account_info = serde_json::from_value::<GetAccountInfoResult>(result)?;
+
+    Ok(RequiredSigners::from(&account_info.account))
+}
+
 /// Reads a previously-saved `Deploy` from a file and sends it to the network for execution.
 ///
+/// If the file declares the signers required by the `Deploy`, e.g. if it was created using
+/// [`make_deploy_with_required_signers()`](fn.make_deploy_with_required_signers.html), the
+/// `Deploy` is only sent if its approvals are all valid, all from the declared signers, and meet
+/// the declared deployment threshold.  Otherwise
+/// [`Error::InsufficientApprovals`](enum.Error.html#variant.InsufficientApprovals) is returned.
+///
 /// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
 ///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
 ///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
@@ -322,6 +521,39 @@ pub async fn get_block(
         .await
 }
 
+/// Retrieves a range of consecutive `Block`s, or only their headers, from the network.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `start_height` must be a `u64` representing the height of the first `Block` to retrieve.
+/// * `maybe_max_count` must be a `u32` representing the maximum number of `Block`s to retrieve, or
+///   empty, in which case the node's limit is used.  The response contains `next_height` to use for
+///   retrieving the next range, if there may be more `Block`s.
+/// * If `headers_only` is true, only the `Block` headers are retrieved rather than the full
+///   `Block`s.
+/// * If `with_signatures` is true, the finality signatures of each `Block` are included.
+pub async fn get_blocks(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    start_height: &str,
+    maybe_max_count: &str,
+    headers_only: bool,
+    with_signatures: bool,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_blocks(start_height, maybe_max_count, headers_only, with_signatures)
+        .await
+}
+
 /// Retrieves all `Transfer` items for a `Block` from the network.
 ///
 /// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
@@ -640,6 +872,145 @@ pub async fn get_validator_changes(
         .await
 }
 
+/// Retrieves the deploys and transfers held by the node's block proposer, awaiting inclusion in a
+/// block.
+///
+/// For each pending deploy, the response includes its account, gas price, the time at which the
+/// node received it and any of its dependencies which have not yet been finalized, which can help
+/// to diagnose why a deploy is not being proposed.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+pub async fn get_pending_deploys(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_pending_deploys()
+        .await
+}
+
+/// Retrieves a snapshot of the node's consensus state in the current era, for debugging eras
+/// which are not making progress.
+///
+/// The snapshot includes the latest unit seen from each validator, the node's own round exponent
+/// if it is a validator, the last block finalized in the era and the missing dependencies the
+/// node is waiting for.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+pub async fn get_consensus_state(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_consensus_state()
+        .await
+}
+
+/// Retrieves the evidence held by the node that validators equivocated, i.e. signed conflicting
+/// consensus messages.
+///
+/// Each item of evidence can be verified independently of the node using
+/// [`verify_evidence_file()`](fn.verify_evidence_file.html).
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `maybe_era_id` must be a `u64` representing the era to retrieve evidence for, or empty, in
+///   which case evidence is retrieved for all eras the node still holds.
+/// * `maybe_public_key` must be a hex-encoded public key of the validator to retrieve evidence
+///   against, or empty, in which case evidence is retrieved against all faulty validators.
+pub async fn get_evidence(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    maybe_era_id: &str,
+    maybe_public_key: &str,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_evidence(maybe_era_id, maybe_public_key)
+        .await
+}
+
+/// Reads evidence from a file and verifies, without contacting a node, that each item of it proves
+/// its validator equivocated.
+///
+/// The file may contain the JSON response of [`get_evidence()`](fn.get_evidence.html), its
+/// `result`, or a single item of evidence.  Each item is checked to consist of two conflicting
+/// units, both signed by the validator for the era's consensus instance.  The validators and
+/// chainspec hash included in the evidence are not trusted: they must match the validators
+/// determined by the previous era's switch block and the given chainspec hash respectively, both
+/// of which should be obtained from a trusted source.
+///
+/// * `input_path` specifies the path to the file containing the evidence.
+/// * `switch_block_path` specifies the path to a file containing the switch block of the era
+///   preceding the one in which the validator equivocated, e.g. as retrieved by
+///   [`get_block()`](fn.get_block.html).  The file may contain the JSON response, its `result`, or
+///   the block itself.
+/// * `chainspec_hash` must be the hex-encoded hash of the chainspec of the network.
+pub fn verify_evidence_file(
+    input_path: &str,
+    switch_block_path: &str,
+    chainspec_hash: &str,
+) -> Result<Vec<EvidenceReport>> {
+    evidence::verify_evidence_file(input_path, switch_block_path, chainspec_hash)
+}
+
+/// Retrieves the deploys sent by an account, in order of the height of the blocks including them.
+///
+/// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
+///   response. If it can be parsed as an `i64` it will be used as a JSON integer. If empty, a
+///   random `i64` will be assigned. Otherwise the provided string will be used verbatim.
+/// * `node_address` is the hostname or IP and port of the node on which the HTTP service is
+///   running, e.g. `"http://127.0.0.1:7777"`.
+/// * When `verbosity_level` is `1`, the JSON-RPC request will be printed to `stdout` with long
+///   string fields (e.g. hex-formatted raw Wasm bytes) shortened to a string indicating the char
+///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
+///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
+///   will not be printed to `stdout`.
+/// * `account` must be a hex-encoded public key or a formatted account hash `"account-hash-<HEX
+///   STRING>"`.
+/// * `maybe_start_height` must be a `u64` representing the height of the first block to include, or
+///   empty, in which case deploys are listed from the genesis block onwards.  The response contains
+///   `next_height` to use for retrieving the next page, if there may be more deploys.
+pub async fn get_account_deploys(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    account: &str,
+    maybe_start_height: &str,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+        .get_account_deploys(account, maybe_start_height)
+        .await
+}
+
 /// Container for `Deploy` construction options.
 #[derive(Default, Debug)]
 pub struct DeployStrParams<'a> {
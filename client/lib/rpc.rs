@@ -16,29 +16,35 @@ use casper_node::{
         account::{PutDeploy, PutDeployParams},
         chain::{
             BlockIdentifier, GetBlock, GetBlockParams, GetBlockTransfers, GetBlockTransfersParams,
-            GetEraInfoBySwitchBlock, GetEraInfoParams, GetStateRootHash, GetStateRootHashParams,
+            GetBlocks, GetBlocksParams, GetEraInfoBySwitchBlock, GetEraInfoParams,
+            GetStateRootHash, GetStateRootHashParams,
         },
         docs::ListRpcs,
-        info::{GetDeploy, GetDeployParams, GetValidatorChanges},
+        info::{
+            AccountIdentifier, GetAccountDeploys, GetAccountDeploysParams, GetConsensusState,
+            GetDeploy, GetDeployParams, GetEvidence, GetEvidenceParams, GetPendingDeploys,
+            GetValidatorChanges,
+        },
         state::{
-            GetAccountInfo, GetAccountInfoParams, GetAuctionInfo, GetAuctionInfoParams, GetBalance,
-            GetBalanceParams, GetDictionaryItem, GetDictionaryItemParams, GetItem, GetItemParams,
-            GlobalStateIdentifier, QueryGlobalState, QueryGlobalStateParams,
+            DictionaryIdentifier, GetAccountInfo, GetAccountInfoParams, GetAuctionInfo,
+            GetAuctionInfoParams, GetBalance, GetBalanceParams, GetDictionaryItem,
+            GetDictionaryItemParams, GetItem, GetItemParams, GlobalStateIdentifier,
+            QueryGlobalState, QueryGlobalStateParams,
         },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams, RPC_API_PATH,
     },
     types::{BlockHash, Deploy, DeployHash},
 };
-use casper_types::{AsymmetricType, Key, PublicKey, URef};
+use casper_types::{account::AccountHash, AsymmetricType, EraId, Key, PublicKey, URef};
 
 use crate::{
-    deploy::{DeployExt, DeployParams, SendDeploy, Transfer},
+    deploy::{ApprovalsReport, DeployExt, DeployParams, SendDeploy, Transfer},
     error::{Error, Result},
     validation, DictionaryItemStrParams, GlobalStateStrParams,
 };
 
 /// Struct representing a single JSON-RPC call to the casper node.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct RpcCall {
     rpc_id: Id,
     node_address: String,
@@ -136,14 +142,66 @@ impl RpcCall {
                 error: crypto::Error::FromHex(error),
             })?;
 
-        let dictionary_identifier = dictionary_str_params.try_into()?;
+        let dictionary_identifier: DictionaryIdentifier = dictionary_str_params.try_into()?;
+
+        // For named-key lookups, the seed URef is read from the proven account or contract first,
+        // so that the node's response can be checked to refer to the requested dictionary item.
+        let expected_key = match &dictionary_identifier {
+            DictionaryIdentifier::URef {
+                seed_uref,
+                dictionary_item_key,
+            } => {
+                let seed_uref = URef::from_formatted_str(seed_uref).map_err(|error| {
+                    Error::FailedToParseURef {
+                        context: "seed_uref",
+                        error,
+                    }
+                })?;
+                Key::dictionary(seed_uref, dictionary_item_key.as_bytes())
+            }
+            DictionaryIdentifier::Dictionary(address) => Key::from_formatted_str(address)
+                .map_err(|_| Error::FailedToParseDictionaryIdentifier)?,
+            DictionaryIdentifier::AccountNamedKey {
+                key,
+                dictionary_name,
+                dictionary_item_key,
+            }
+            | DictionaryIdentifier::ContractNamedKey {
+                key,
+                dictionary_name,
+                dictionary_item_key,
+            } => {
+                let base_key = Key::from_formatted_str(key)
+                    .map_err(|_| Error::FailedToParseDictionaryIdentifier)?;
+                let params = QueryGlobalStateParams {
+                    state_identifier: GlobalStateIdentifier::StateRootHash(state_root_hash),
+                    key: base_key.to_formatted_string(),
+                    path: vec![],
+                };
+                let response =
+                    QueryGlobalState::request_with_map_params(self.clone(), params).await?;
+                let seed_uref = validation::validate_dictionary_seed_uref_response(
+                    &response,
+                    &state_root_hash,
+                    &base_key,
+                    dictionary_name,
+                )?;
+                Key::dictionary(seed_uref, dictionary_item_key.as_bytes())
+            }
+        };
 
         let params = GetDictionaryItemParams {
             state_root_hash,
             dictionary_identifier,
+            list: None,
         };
 
         let response = GetDictionaryItem::request_with_map_params(self, params).await?;
+        validation::validate_get_dictionary_item_response(
+            &response,
+            &state_root_hash,
+            &expected_key,
+        )?;
         Ok(response)
     }
 
@@ -213,6 +271,72 @@ impl RpcCall {
         GetValidatorChanges::request(self).await
     }
 
+    pub(crate) async fn get_pending_deploys(self) -> Result<JsonRpc> {
+        GetPendingDeploys::request(self).await
+    }
+
+    pub(crate) async fn get_consensus_state(self) -> Result<JsonRpc> {
+        GetConsensusState::request(self).await
+    }
+
+    pub(crate) async fn get_evidence(
+        self,
+        maybe_era_id: &str,
+        maybe_public_key: &str,
+    ) -> Result<JsonRpc> {
+        let era_id = if maybe_era_id.is_empty() {
+            None
+        } else {
+            let era_id = maybe_era_id
+                .parse()
+                .map_err(|error| Error::FailedToParseInt {
+                    context: "era_id",
+                    error,
+                })?;
+            Some(EraId::new(era_id))
+        };
+        let public_key = if maybe_public_key.is_empty() {
+            None
+        } else {
+            Some(PublicKey::from_hex(maybe_public_key).map_err(|_| Error::FailedToParseKey)?)
+        };
+        if era_id.is_none() && public_key.is_none() {
+            return GetEvidence::request(self).await;
+        }
+        let params = GetEvidenceParams { era_id, public_key };
+        GetEvidence::request_with_map_params(self, params).await
+    }
+
+    pub(crate) async fn get_account_deploys(
+        self,
+        account: &str,
+        maybe_start_height: &str,
+    ) -> Result<JsonRpc> {
+        let account_identifier = if let Ok(public_key) = PublicKey::from_hex(account) {
+            AccountIdentifier::PublicKey(public_key)
+        } else if let Ok(account_hash) = AccountHash::from_formatted_str(account) {
+            AccountIdentifier::AccountHash(account_hash)
+        } else {
+            return Err(Error::FailedToParseKey);
+        };
+        let start_height = if maybe_start_height.is_empty() {
+            0
+        } else {
+            maybe_start_height
+                .parse()
+                .map_err(|error| Error::FailedToParseInt {
+                    context: "start_height",
+                    error,
+                })?
+        };
+        let params = GetAccountDeploysParams {
+            account_identifier,
+            start_height,
+            max_count: None,
+        };
+        GetAccountDeploys::request_with_map_params(self, params).await
+    }
+
     pub(crate) async fn list_rpcs(self) -> Result<JsonRpc> {
         ListRpcs::request(self).await
     }
@@ -243,7 +367,13 @@ impl RpcCall {
             context: format!("unable to read input file '{}'", input_path),
             error,
         })?;
-        let deploy = Deploy::read_deploy(input)?;
+        let (deploy, maybe_required_signers) = Deploy::read_deploy_with_required_signers(input)?;
+        if let Some(required_signers) = maybe_required_signers {
+            let report = ApprovalsReport::new(&deploy, &required_signers);
+            if !report.is_sufficient() {
+                return Err(Error::InsufficientApprovals(Box::new(report)));
+            }
+        }
         let params = PutDeployParams { deploy };
         SendDeploy::request_with_map_params(self, params).await
     }
@@ -266,6 +396,40 @@ impl RpcCall {
         Ok(response)
     }
 
+    pub(crate) async fn get_blocks(
+        self,
+        start_height: &str,
+        maybe_max_count: &str,
+        headers_only: bool,
+        with_signatures: bool,
+    ) -> Result<JsonRpc> {
+        let start_height = start_height
+            .parse()
+            .map_err(|error| Error::FailedToParseInt {
+                context: "start_height",
+                error,
+            })?;
+        let max_count = if maybe_max_count.is_empty() {
+            None
+        } else {
+            Some(
+                maybe_max_count
+                    .parse()
+                    .map_err(|error| Error::FailedToParseInt {
+                        context: "max_count",
+                        error,
+                    })?,
+            )
+        };
+        let params = GetBlocksParams {
+            start_height,
+            max_count,
+            headers_only,
+            with_signatures,
+        };
+        GetBlocks::request_with_map_params(self, params).await
+    }
+
     pub(crate) async fn get_block_transfers(self, maybe_block_identifier: &str) -> Result<JsonRpc> {
         let maybe_block_identifier = Self::block_identifier(maybe_block_identifier)?;
         let response = match maybe_block_identifier {
@@ -437,6 +601,10 @@ impl RpcClient for GetBlock {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
 
+impl RpcClient for GetBlocks {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 impl RpcClient for GetBlockTransfers {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
@@ -477,6 +645,22 @@ impl RpcClient for GetValidatorChanges {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
 
+impl RpcClient for GetAccountDeploys {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
+impl RpcClient for GetPendingDeploys {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
+impl RpcClient for GetEvidence {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
+impl RpcClient for GetConsensusState {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 pub(crate) trait IntoJsonMap: Serialize {
     fn into_json_map(self) -> Map<String, Value>
     where
@@ -491,6 +675,7 @@ pub(crate) trait IntoJsonMap: Serialize {
 
 impl IntoJsonMap for PutDeployParams {}
 impl IntoJsonMap for GetBlockParams {}
+impl IntoJsonMap for GetBlocksParams {}
 impl IntoJsonMap for GetBlockTransfersParams {}
 impl IntoJsonMap for GetStateRootHashParams {}
 impl IntoJsonMap for GetDeployParams {}
@@ -502,3 +687,5 @@ impl IntoJsonMap for GetAuctionInfoParams {}
 impl IntoJsonMap for GetAccountInfoParams {}
 impl IntoJsonMap for GetDictionaryItemParams {}
 impl IntoJsonMap for QueryGlobalStateParams {}
+impl IntoJsonMap for GetAccountDeploysParams {}
+impl IntoJsonMap for GetEvidenceParams {}
@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
 
 use jsonrpc_lite::JsonRpc;
+use serde_json::{Map, Value};
 use thiserror::Error;
 
 use casper_execution_engine::{
@@ -16,12 +17,16 @@ use casper_node::{
         json_compatibility, Block, BlockHeader, BlockValidationError, JsonBlock, JsonBlockHeader,
     },
 };
-use casper_types::{bytesrepr, Key, StoredValue, U512};
+use casper_types::{
+    bytesrepr::{self, FromBytes},
+    Key, StoredValue, URef, U512,
+};
 
 const GET_ITEM_RESULT_BALANCE_VALUE: &str = "balance_value";
 const GET_ITEM_RESULT_STORED_VALUE: &str = "stored_value";
 const GET_ITEM_RESULT_MERKLE_PROOF: &str = "merkle_proof";
 const QUERY_GLOBAL_STATE_BLOCK_HEADER: &str = "block_header";
+const GET_DICTIONARY_ITEM_RESULT_DICTIONARY_KEY: &str = "dictionary_key";
 
 /// Error that can be returned when validating a block returned from a JSON-RPC method.
 #[derive(Error, Debug)]
@@ -65,6 +70,14 @@ pub enum ValidateResponseError {
     /// An invalid combination of state identifier and block header response
     #[error("Invalid combination of state identifier and block header in response")]
     InvalidGlobalStateResponse,
+
+    /// Dictionary key in response does not correspond to the requested dictionary item.
+    #[error("dictionary key in response does not correspond to the requested dictionary item")]
+    UnexpectedDictionaryKey,
+
+    /// The account or contract in the proof has no dictionary seed URef under the requested name.
+    #[error("no dictionary seed URef under the requested name in proof")]
+    DictionarySeedURefNotInProof,
 }
 
 impl From<bytesrepr::Error> for ValidateResponseError {
@@ -137,18 +150,122 @@ pub(crate) fn validate_query_response(
         .as_object()
         .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
 
-    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = {
-        let proof = object
-            .get(GET_ITEM_RESULT_MERKLE_PROOF)
+    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = decode_merkle_proof(object)?;
+
+    let proof_value: &StoredValue = {
+        let last_proof = proofs
+            .last()
+            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+        last_proof.value()
+    };
+
+    validate_stored_value_contained_in_proof(object, proof_value)?;
+
+    core::validate_query_proof(&state_root_hash.to_owned(), &proofs, key, path, proof_value)
+        .map_err(Into::into)
+}
+
+/// Decodes the hex-encoded, serialized merkle proof or proofs in the `merkle_proof` field of a
+/// response.
+fn decode_merkle_proof<T: FromBytes>(
+    object: &Map<String, Value>,
+) -> Result<T, ValidateResponseError> {
+    let proof_str = object
+        .get(GET_ITEM_RESULT_MERKLE_PROOF)
+        .and_then(Value::as_str)
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+    let proof_bytes =
+        hex::decode(proof_str).map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
+    bytesrepr::deserialize(proof_bytes).map_err(Into::into)
+}
+
+/// Validates that the JSON `stored_value` field of a response is the value contained in the proof.
+//
+// Possible to deserialize that field into a `StoredValue` and pass to `validate_query_proof`
+// instead of using this approach?
+fn validate_stored_value_contained_in_proof(
+    object: &Map<String, Value>,
+    proof_value: &StoredValue,
+) -> Result<(), ValidateResponseError> {
+    let value: json_compatibility::StoredValue = {
+        let value = object
+            .get(GET_ITEM_RESULT_STORED_VALUE)
+            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+        serde_json::from_value(value.to_owned())?
+    };
+    match json_compatibility::StoredValue::try_from(proof_value.clone()) {
+        Ok(json_proof_value) if json_proof_value == value => Ok(()),
+        _ => Err(ValidateResponseError::SerializedValueNotContainedInProof),
+    }
+}
+
+/// Validates the response to a query of the account or contract holding a dictionary's seed URef
+/// under `dictionary_name`, returning the seed URef from the proof.
+pub(crate) fn validate_dictionary_seed_uref_response(
+    response: &JsonRpc,
+    state_root_hash: &Digest,
+    base_key: &Key,
+    dictionary_name: &str,
+) -> Result<URef, ValidateResponseError> {
+    validate_query_global_state(
+        response,
+        GlobalStateIdentifier::StateRootHash(*state_root_hash),
+        base_key,
+        &[],
+    )?;
+
+    let value = response
+        .get_result()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let object = value
+        .as_object()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = decode_merkle_proof(object)?;
+
+    let named_keys = match proofs.last().map(TrieMerkleProof::value) {
+        Some(StoredValue::Account(account)) => account.named_keys(),
+        Some(StoredValue::Contract(contract)) => contract.named_keys(),
+        _ => return Err(ValidateResponseError::DictionarySeedURefNotInProof),
+    };
+    named_keys
+        .get(dictionary_name)
+        .and_then(Key::as_uref)
+        .copied()
+        .ok_or(ValidateResponseError::DictionarySeedURefNotInProof)
+}
+
+pub(crate) fn validate_get_dictionary_item_response(
+    response: &JsonRpc,
+    state_root_hash: &Digest,
+    expected_key: &Key,
+) -> Result<(), ValidateResponseError> {
+    let value = response
+        .get_result()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let object = value
+        .as_object()
+        .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
+
+    let dictionary_key = {
+        let key = object
+            .get(GET_DICTIONARY_ITEM_RESULT_DICTIONARY_KEY)
             .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-        let proof_str = proof
+        let key_str = key
             .as_str()
             .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-        let proof_bytes = hex::decode(proof_str)
-            .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
-        bytesrepr::deserialize(proof_bytes)?
+        Key::from_formatted_str(key_str)
+            .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?
     };
 
+    if *expected_key != dictionary_key {
+        return Err(ValidateResponseError::UnexpectedDictionaryKey);
+    }
+
+    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = decode_merkle_proof(object)?;
+
     let proof_value: &StoredValue = {
         let last_proof = proofs
             .last()
@@ -156,25 +273,21 @@ pub(crate) fn validate_query_response(
         last_proof.value()
     };
 
-    // Here we need to validate that JSON `stored_value` is contained in the proof.
-    //
-    // Possible to deserialize that field into a `StoredValue` and pass below to
-    // `validate_query_proof` instead of using this approach?
-    {
-        let value: json_compatibility::StoredValue = {
-            let value = object
-                .get(GET_ITEM_RESULT_STORED_VALUE)
-                .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-            serde_json::from_value(value.to_owned())?
-        };
-        match json_compatibility::StoredValue::try_from(proof_value.clone()) {
-            Ok(json_proof_value) if json_proof_value == value => (),
-            _ => return Err(ValidateResponseError::SerializedValueNotContainedInProof),
-        }
-    }
-
-    core::validate_query_proof(&state_root_hash.to_owned(), &proofs, key, path, proof_value)
-        .map_err(Into::into)
+    // The node unwraps the dictionary value before returning it, so the same is done to the value
+    // in the proof before comparing them.
+    let unwrapped_proof_value =
+        core::handle_stored_dictionary_value(dictionary_key, proof_value.clone())
+            .map_err(|_| ValidateResponseError::SerializedValueNotContainedInProof)?;
+    validate_stored_value_contained_in_proof(object, &unwrapped_proof_value)?;
+
+    core::validate_query_proof(
+        &state_root_hash.to_owned(),
+        &proofs,
+        &dictionary_key,
+        &[],
+        proof_value,
+    )
+    .map_err(Into::into)
 }
 
 pub(crate) fn validate_query_global_state(
@@ -191,17 +304,7 @@ pub(crate) fn validate_query_global_state(
         .as_object()
         .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
 
-    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = {
-        let proof = object
-            .get(GET_ITEM_RESULT_MERKLE_PROOF)
-            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-        let proof_str = proof
-            .as_str()
-            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-        let proof_bytes = hex::decode(proof_str)
-            .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
-        bytesrepr::deserialize(proof_bytes)?
-    };
+    let proofs: Vec<TrieMerkleProof<Key, StoredValue>> = decode_merkle_proof(object)?;
 
     let proof_value: &StoredValue = {
         let last_proof = proofs
@@ -210,6 +313,8 @@ pub(crate) fn validate_query_global_state(
         last_proof.value()
     };
 
+    validate_stored_value_contained_in_proof(object, proof_value)?;
+
     let json_block_header_value = object
         .get(QUERY_GLOBAL_STATE_BLOCK_HEADER)
         .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
@@ -221,8 +326,13 @@ pub(crate) fn validate_query_global_state(
         | (GlobalStateIdentifier::StateRootHash(_), Some(_)) => {
             return Err(ValidateResponseError::InvalidGlobalStateResponse);
         }
-        (GlobalStateIdentifier::BlockHash(_), Some(json_header)) => {
+        (GlobalStateIdentifier::BlockHash(block_hash), Some(json_header)) => {
+            // The header is only trustworthy if it hashes to the block hash we asked for;
+            // otherwise the node could supply a header committing to any state root it likes.
             let block_header = BlockHeader::from(json_header);
+            if block_header.hash() != block_hash {
+                return Err(ValidateResponseError::UnexpectedBlockHash);
+            }
             *block_header.state_root_hash()
         }
         (GlobalStateIdentifier::StateRootHash(hash), None) => hash,
@@ -245,17 +355,7 @@ pub(crate) fn validate_get_balance_response(
         .as_object()
         .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
 
-    let balance_proof: TrieMerkleProof<Key, StoredValue> = {
-        let proof = object
-            .get(GET_ITEM_RESULT_MERKLE_PROOF)
-            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-        let proof_str = proof
-            .as_str()
-            .ok_or(ValidateResponseError::ValidateResponseFailedToParse)?;
-        let proof_bytes = hex::decode(proof_str)
-            .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?;
-        bytesrepr::deserialize(proof_bytes)?
-    };
+    let balance_proof: TrieMerkleProof<Key, StoredValue> = decode_merkle_proof(object)?;
 
     let balance: U512 = {
         let value = object
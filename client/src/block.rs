@@ -1,2 +1,3 @@
 mod get;
+mod get_range;
 mod transfers;
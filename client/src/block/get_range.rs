@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use std::str;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use casper_client::Error;
+use casper_node::rpcs::chain::GetBlocks;
+
+use crate::{command::ClientCommand, common, Success};
+
+/// This struct defines the order in which the args are shown for this subcommand.
+enum DisplayOrder {
+    Verbose,
+    NodeAddress,
+    RpcId,
+    StartHeight,
+    MaxCount,
+    HeadersOnly,
+    WithSignatures,
+}
+
+/// Handles providing the arg for and retrieval of the height of the first block in the range.
+mod start_height {
+    use super::*;
+
+    const ARG_NAME: &str = "start-height";
+    const ARG_SHORT: &str = "s";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str = "Height of the first block to retrieve";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .short(ARG_SHORT)
+            .required(true)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::StartHeight as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+/// Handles providing the arg for and retrieval of the maximum number of blocks to retrieve.
+mod max_count {
+    use super::*;
+
+    const ARG_NAME: &str = "max-count";
+    const ARG_SHORT: &str = "c";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str =
+        "Maximum number of blocks to retrieve. If not given, or if greater than the node's limit, \
+        the node's limit is used";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .short(ARG_SHORT)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::MaxCount as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
+}
+
+/// Handles providing the arg for and retrieval of the headers-only flag.
+mod headers_only {
+    use super::*;
+
+    const ARG_NAME: &str = "headers-only";
+    const ARG_HELP: &str = "If passed, only the block headers are retrieved";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::HeadersOnly as usize)
+    }
+
+    pub(super) fn get(matches: &ArgMatches) -> bool {
+        matches.is_present(ARG_NAME)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the with-signatures flag.
+mod with_signatures {
+    use super::*;
+
+    const ARG_NAME: &str = "with-signatures";
+    const ARG_HELP: &str = "If passed, the finality signatures of each block are included";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::WithSignatures as usize)
+    }
+
+    pub(super) fn get(matches: &ArgMatches) -> bool {
+        matches.is_present(ARG_NAME)
+    }
+}
+
+#[async_trait]
+impl<'a, 'b> ClientCommand<'a, 'b> for GetBlocks {
+    const NAME: &'static str = "get-blocks";
+    const ABOUT: &'static str = "Retrieves a range of consecutive blocks from the network";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(common::verbose::arg(DisplayOrder::Verbose as usize))
+            .arg(common::node_address::arg(
+                DisplayOrder::NodeAddress as usize,
+            ))
+            .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
+            .arg(start_height::arg())
+            .arg(max_count::arg())
+            .arg(headers_only::arg())
+            .arg(with_signatures::arg())
+    }
+
+    async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
+        let maybe_rpc_id = common::rpc_id::get(matches);
+        let node_address = common::node_address::get(matches);
+        let verbosity_level = common::verbose::get(matches);
+        let start_height = start_height::get(matches);
+        let maybe_max_count = max_count::get(matches);
+        let headers_only = headers_only::get(matches);
+        let with_signatures = with_signatures::get(matches);
+
+        casper_client::get_blocks(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            start_height,
+            maybe_max_count,
+            headers_only,
+            with_signatures,
+        )
+        .await
+        .map(Success::from)
+    }
+}
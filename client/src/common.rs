@@ -274,6 +274,50 @@ pub(super) mod public_key {
     }
 }
 
+/// Handles providing the arg for and retrieval of the account whose deploys should be listed.
+pub(super) mod account {
+    use super::*;
+
+    pub const ARG_NAME: &str = "account";
+    const IS_REQUIRED: bool = false;
+    const ARG_HELP: &str =
+        "The account whose deploys should be listed. This must be a properly formatted public key \
+        or account hash \"account-hash-<HEX STRING>\". The public key may instead be read in from \
+        a file, in which case enter the path to the file as the --account argument. The file \
+        should be one of the two public key files generated via the `keygen` subcommand; \
+        \"public_key_hex\" or \"public_key.pem\"";
+
+    pub fn arg(order: usize) -> Arg<'static, 'static> {
+        sealed_public_key::arg(order, ARG_NAME, ARG_HELP, IS_REQUIRED)
+    }
+
+    pub fn get(matches: &ArgMatches) -> Result<String, Error> {
+        sealed_public_key::get(matches, ARG_NAME, IS_REQUIRED)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the validator whose evidence should be retrieved.
+pub(super) mod validator {
+    use super::*;
+
+    const ARG_NAME: &str = "validator";
+    const IS_REQUIRED: bool = false;
+    const ARG_HELP: &str =
+        "The validator to retrieve evidence against. If not given, evidence against all faulty \
+        validators is retrieved. This must be a properly formatted public key. The public key may \
+        instead be read in from a file, in which case enter the path to the file as the \
+        --validator argument. The file should be one of the two public key files generated via \
+        the `keygen` subcommand; \"public_key_hex\" or \"public_key.pem\"";
+
+    pub fn arg(order: usize) -> Arg<'static, 'static> {
+        sealed_public_key::arg(order, ARG_NAME, ARG_HELP, IS_REQUIRED)
+    }
+
+    pub fn get(matches: &ArgMatches) -> Result<String, Error> {
+        sealed_public_key::get(matches, ARG_NAME, IS_REQUIRED)
+    }
+}
+
 /// Handles providing the arg for and retrieval of the session account arg when specifying an
 /// account for a Deploy.
 pub(super) mod session_account {
@@ -1,16 +1,20 @@
+mod check_approvals;
 mod creation_common;
 mod get;
 mod list;
 mod make;
 mod make_transfer;
+mod merge_approvals;
 mod put;
 mod send;
 mod sign;
 mod transfer;
 
+pub use check_approvals::CheckApprovals;
 pub use list::ListDeploys;
 pub use make::MakeDeploy;
 pub use make_transfer::MakeTransfer;
+pub use merge_approvals::MergeApprovals;
 pub use send::SendDeploy;
 pub use sign::SignDeploy;
 pub use transfer::Transfer;
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use clap::{App, ArgMatches, SubCommand};
+
+use casper_client::Error;
+
+use super::creation_common::{self, DisplayOrder};
+use crate::{command::ClientCommand, common, Success};
+
+pub struct CheckApprovals;
+
+#[async_trait]
+impl<'a, 'b> ClientCommand<'a, 'b> for CheckApprovals {
+    const NAME: &'static str = "check-approvals";
+    const ABOUT: &'static str =
+        "Reads a previously-saved deploy from a file, verifies its approvals and reports which of \
+        its account's associated keys have signed it, and whether their combined weight meets the \
+        account's deployment threshold";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(common::verbose::arg(DisplayOrder::Verbose as usize))
+            .arg(common::node_address::arg(
+                DisplayOrder::NodeAddress as usize,
+            ))
+            .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
+            .arg(creation_common::input::arg())
+            .arg(common::block_identifier::arg(
+                DisplayOrder::BlockIdentifier as usize,
+            ))
+    }
+
+    async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
+        let maybe_rpc_id = common::rpc_id::get(matches);
+        let node_address = common::node_address::get(matches);
+        let verbosity_level = common::verbose::get(matches);
+        let input_path = creation_common::input::get(matches);
+        let maybe_block_id = common::block_identifier::get(matches);
+
+        let report = casper_client::check_approvals(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            input_path,
+            maybe_block_id,
+        )
+        .await?;
+        Ok(Success::Output(serde_json::to_string_pretty(&report)?))
+    }
+}
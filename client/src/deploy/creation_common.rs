@@ -18,6 +18,7 @@ pub(super) enum DisplayOrder {
     SecretKey,
     Input,
     Output,
+    BlockIdentifier,
     TransferAmount,
     TransferTargetAccount,
     TransferId,
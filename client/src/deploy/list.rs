@@ -1,7 +1,7 @@
 use std::str;
 
 use async_trait::async_trait;
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 
 use casper_client::{Error, ListDeploysResult};
 use casper_node::rpcs::chain::GetBlockResult;
@@ -14,6 +14,34 @@ enum DisplayOrder {
     NodeAddress,
     RpcId,
     BlockHash,
+    Account,
+    StartHeight,
+}
+
+/// Handles providing the arg for and retrieval of the start height when listing an account's
+/// deploys.
+mod start_height {
+    use super::*;
+
+    const ARG_NAME: &str = "start-height";
+    const ARG_VALUE_NAME: &str = "INTEGER";
+    const ARG_HELP: &str =
+        "Height of the first block from which to list the account's deploys. If not given, \
+        deploys are listed from the genesis block onwards";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .requires(common::account::ARG_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::StartHeight as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
 }
 
 pub struct ListDeploys;
@@ -21,7 +49,9 @@ pub struct ListDeploys;
 #[async_trait]
 impl<'a, 'b> ClientCommand<'a, 'b> for ListDeploys {
     const NAME: &'static str = "list-deploys";
-    const ABOUT: &'static str = "Retrieves the list of all deploy hashes in a given block";
+    const ABOUT: &'static str =
+        "Retrieves the list of all deploy hashes in a given block, or of the deploys sent by a \
+        given account";
 
     fn build(display_order: usize) -> App<'a, 'b> {
         SubCommand::with_name(Self::NAME)
@@ -32,15 +62,33 @@ impl<'a, 'b> ClientCommand<'a, 'b> for ListDeploys {
                 DisplayOrder::NodeAddress as usize,
             ))
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
-            .arg(common::block_identifier::arg(
-                DisplayOrder::BlockHash as usize,
-            ))
+            .arg(
+                common::block_identifier::arg(DisplayOrder::BlockHash as usize)
+                    .conflicts_with(common::account::ARG_NAME),
+            )
+            .arg(common::account::arg(DisplayOrder::Account as usize))
+            .arg(start_height::arg())
     }
 
     async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+
+        let account = common::account::get(matches)?;
+        if !account.is_empty() {
+            let maybe_start_height = start_height::get(matches);
+            return casper_client::get_account_deploys(
+                maybe_rpc_id,
+                node_address,
+                verbosity_level,
+                &account,
+                maybe_start_height,
+            )
+            .await
+            .map(Success::from);
+        }
+
         let maybe_block_id = common::block_identifier::get(matches);
 
         let result =
@@ -1,11 +1,35 @@
 use async_trait::async_trait;
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 
 use casper_client::{DeployStrParams, Error};
 
-use super::creation_common;
+use super::creation_common::{self, DisplayOrder};
 use crate::{command::ClientCommand, common, Success};
 
+/// Handles providing the arg for and retrieval of the flag to declare the required signers.
+mod declare_required_signers {
+    use super::*;
+
+    const ARG_NAME: &str = "declare-required-signers";
+    const ARG_HELP: &str =
+        "If this flag is passed, the associated keys and deployment threshold of the deploy's \
+        account are retrieved from the node at --node-address and declared in the output as the \
+        deploy's required signers. 'send-deploy' then refuses to send the deploy until its \
+        approvals meet the threshold";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::NodeAddress as usize)
+    }
+
+    pub(super) fn get(matches: &ArgMatches) -> bool {
+        matches.is_present(ARG_NAME)
+    }
+}
+
 pub struct MakeDeploy;
 
 #[async_trait]
@@ -19,11 +43,14 @@ impl<'a, 'b> ClientCommand<'a, 'b> for MakeDeploy {
     fn build(display_order: usize) -> App<'a, 'b> {
         let subcommand = SubCommand::with_name(Self::NAME)
             .about(Self::ABOUT)
-            .arg(creation_common::output::arg())
-            .arg(common::force::arg(
-                creation_common::DisplayOrder::Force as usize,
-                true,
+            .arg(common::verbose::arg(DisplayOrder::Verbose as usize))
+            .arg(declare_required_signers::arg())
+            .arg(common::node_address::arg(
+                DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
+            .arg(creation_common::output::arg())
+            .arg(common::force::arg(DisplayOrder::Force as usize, true))
             .display_order(display_order);
         let subcommand = creation_common::apply_common_session_options(subcommand);
         let subcommand = creation_common::apply_common_payment_options(subcommand);
@@ -48,21 +75,36 @@ impl<'a, 'b> ClientCommand<'a, 'b> for MakeDeploy {
 
         let force = common::force::get(matches);
 
-        casper_client::make_deploy(
-            maybe_output_path,
-            DeployStrParams {
-                secret_key,
-                timestamp,
-                ttl,
-                gas_price,
-                dependencies,
-                chain_name,
-                session_account: &session_account,
-            },
-            session_str_params,
-            payment_str_params,
-            force,
-        )
+        let deploy_params = DeployStrParams {
+            secret_key,
+            timestamp,
+            ttl,
+            gas_price,
+            dependencies,
+            chain_name,
+            session_account: &session_account,
+        };
+        if declare_required_signers::get(matches) {
+            casper_client::make_deploy_with_required_signers(
+                common::rpc_id::get(matches),
+                common::node_address::get(matches),
+                common::verbose::get(matches),
+                maybe_output_path,
+                deploy_params,
+                session_str_params,
+                payment_str_params,
+                force,
+            )
+            .await
+        } else {
+            casper_client::make_deploy(
+                maybe_output_path,
+                deploy_params,
+                session_str_params,
+                payment_str_params,
+                force,
+            )
+        }
         .map(|_| {
             Success::Output(if maybe_output_path.is_empty() {
                 String::new()
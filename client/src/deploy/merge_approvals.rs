@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use casper_client::Error;
+
+use super::creation_common::{self, DisplayOrder};
+use crate::{command::ClientCommand, common, Success};
+
+/// Handles providing the arg for and retrieval of the input deploy files.
+mod inputs {
+    use super::*;
+
+    const ARG_NAME: &str = "input";
+    const ARG_SHORT_NAME: &str = "i";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to a signed copy of the deploy. Pass this option once for each copy to be merged, \
+        e.g. '-i deploy-alice.json -i deploy-bob.json'";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .required(true)
+            .long(ARG_NAME)
+            .short(ARG_SHORT_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .multiple(true)
+            .number_of_values(1)
+            .display_order(DisplayOrder::Input as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> Vec<&'a str> {
+        matches
+            .values_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+            .collect()
+    }
+}
+
+pub struct MergeApprovals;
+
+#[async_trait]
+impl<'a, 'b> ClientCommand<'a, 'b> for MergeApprovals {
+    const NAME: &'static str = "merge-approvals";
+    const ABOUT: &'static str =
+        "Reads several separately-signed copies of a previously-saved deploy from files, merges \
+        their approvals into a single deploy, and outputs it to a file or stdout";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(inputs::arg())
+            .arg(creation_common::output::arg())
+            .arg(common::force::arg(DisplayOrder::Force as usize, true))
+    }
+
+    async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
+        let input_paths = inputs::get(matches);
+        let maybe_output_path = creation_common::output::get(matches).unwrap_or_default();
+        let force = common::force::get(matches);
+        casper_client::merge_approvals(&input_paths, maybe_output_path, force).map(|_| {
+            Success::Output(if maybe_output_path.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "Merged the approvals of {} deploys and wrote to {}",
+                    input_paths.len(),
+                    maybe_output_path
+                )
+            })
+        })
+    }
+}
@@ -0,0 +1,4 @@
+mod get;
+mod verify;
+
+pub use verify::VerifyEvidence;
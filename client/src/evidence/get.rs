@@ -0,0 +1,81 @@
+use std::str;
+
+use async_trait::async_trait;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use casper_client::Error;
+use casper_node::rpcs::info::GetEvidence;
+
+use crate::{command::ClientCommand, common, Success};
+
+/// This enum defines the order in which the args are shown for this subcommand's help message.
+enum DisplayOrder {
+    Verbose,
+    NodeAddress,
+    RpcId,
+    EraId,
+    Validator,
+}
+
+/// Handles providing the arg for and retrieval of the era to retrieve evidence for.
+mod era_id {
+    use super::*;
+
+    const ARG_NAME: &str = "era-id";
+    const ARG_VALUE_NAME: &str = common::ARG_INTEGER;
+    const ARG_HELP: &str =
+        "The era to retrieve evidence for. If not given, evidence is retrieved for all eras the \
+        node still holds";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::EraId as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches.value_of(ARG_NAME).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl<'a, 'b> ClientCommand<'a, 'b> for GetEvidence {
+    const NAME: &'static str = "get-evidence";
+    const ABOUT: &'static str =
+        "Retrieves the evidence held by the node that validators equivocated, which can be \
+        verified offline using the verify-evidence subcommand";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(common::verbose::arg(DisplayOrder::Verbose as usize))
+            .arg(common::node_address::arg(
+                DisplayOrder::NodeAddress as usize,
+            ))
+            .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
+            .arg(era_id::arg())
+            .arg(common::validator::arg(DisplayOrder::Validator as usize))
+    }
+
+    async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
+        let maybe_rpc_id = common::rpc_id::get(matches);
+        let node_address = common::node_address::get(matches);
+        let verbosity_level = common::verbose::get(matches);
+        let maybe_era_id = era_id::get(matches);
+        let maybe_public_key = common::validator::get(matches)?;
+
+        casper_client::get_evidence(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            maybe_era_id,
+            &maybe_public_key,
+        )
+        .await
+        .map(Success::from)
+    }
+}
@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use casper_client::Error;
+
+use crate::{command::ClientCommand, common, Success};
+
+/// This enum defines the order in which the args are shown for this subcommand's help message.
+enum DisplayOrder {
+    Verbose,
+    Input,
+    SwitchBlock,
+    ChainspecHash,
+}
+
+/// Handles providing the arg for and retrieval of the evidence file.
+mod input {
+    use super::*;
+
+    const ARG_NAME: &str = "input";
+    const ARG_SHORT_NAME: &str = "i";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to a file containing the output of the get-evidence subcommand, or a single item of \
+        evidence from it";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .required(true)
+            .long(ARG_NAME)
+            .short(ARG_SHORT_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Input as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+/// Handles providing the arg for and retrieval of the switch block file.
+mod switch_block {
+    use super::*;
+
+    const ARG_NAME: &str = "switch-block";
+    const ARG_SHORT_NAME: &str = "s";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to a file containing the output of the get-block subcommand for the switch block of \
+        the era preceding the one in which the validator equivocated, retrieved from a trusted \
+        source. The evidence's validators must match the validators it determines";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .required(true)
+            .long(ARG_NAME)
+            .short(ARG_SHORT_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::SwitchBlock as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+/// Handles providing the arg for and retrieval of the trusted chainspec hash.
+mod chainspec_hash {
+    use super::*;
+
+    const ARG_NAME: &str = "chainspec-hash";
+    const ARG_SHORT_NAME: &str = "c";
+    const ARG_VALUE_NAME: &str = common::ARG_HEX_STRING;
+    const ARG_HELP: &str =
+        "Hex-encoded hash of the network's chainspec, retrieved from a trusted source. The \
+        evidence's chainspec hash must match it";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .required(true)
+            .long(ARG_NAME)
+            .short(ARG_SHORT_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::ChainspecHash as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+pub struct VerifyEvidence;
+
+#[async_trait]
+impl<'a, 'b> ClientCommand<'a, 'b> for VerifyEvidence {
+    const NAME: &'static str = "verify-evidence";
+    const ABOUT: &'static str =
+        "Verifies, without contacting a node, that evidence retrieved via get-evidence proves its \
+        validator signed two conflicting units in the era. The era's validators are checked \
+        against the previous era's switch block and the chainspec hash against the given one";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(common::verbose::arg(DisplayOrder::Verbose as usize))
+            .arg(input::arg())
+            .arg(switch_block::arg())
+            .arg(chainspec_hash::arg())
+    }
+
+    async fn run(matches: &ArgMatches<'a>) -> Result<Success, Error> {
+        let input_path = input::get(matches);
+        let switch_block_path = switch_block::get(matches);
+        let chainspec_hash = chainspec_hash::get(matches);
+
+        let reports =
+            casper_client::verify_evidence_file(input_path, switch_block_path, chainspec_hash)?;
+        Ok(Success::Output(serde_json::to_string_pretty(&reports)?))
+    }
+}
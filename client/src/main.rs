@@ -4,15 +4,18 @@ mod command;
 mod common;
 mod deploy;
 mod docs;
+mod evidence;
 mod generate_completion;
 mod get_account_info;
 mod get_auction_info;
 mod get_balance;
+mod get_consensus_state;
 mod get_dictionary_item;
 mod get_era_info_by_switch_block;
 mod get_state_hash;
 mod get_validator_changes;
 mod keygen;
+mod list_pending;
 mod query_global_state;
 
 use std::process;
@@ -22,15 +25,19 @@ use clap::{crate_description, crate_version, App};
 use casper_client::Error;
 use casper_node::rpcs::{
     account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
+    chain::{GetBlock, GetBlockTransfers, GetBlocks, GetEraInfoBySwitchBlock, GetStateRootHash},
     docs::ListRpcs,
-    info::{GetDeploy, GetValidatorChanges},
+    info::{GetConsensusState, GetDeploy, GetEvidence, GetPendingDeploys, GetValidatorChanges},
     state::{GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, QueryGlobalState},
 };
 
 use account_address::GenerateAccountHash as AccountAddress;
 use command::{ClientCommand, Success};
-use deploy::{ListDeploys, MakeDeploy, MakeTransfer, SendDeploy, SignDeploy, Transfer};
+use deploy::{
+    CheckApprovals, ListDeploys, MakeDeploy, MakeTransfer, MergeApprovals, SendDeploy, SignDeploy,
+    Transfer,
+};
+use evidence::VerifyEvidence;
 use generate_completion::GenerateCompletion;
 use keygen::Keygen;
 
@@ -41,13 +48,17 @@ enum DisplayOrder {
     PutDeploy,
     MakeDeploy,
     SignDeploy,
+    MergeApprovals,
+    CheckApprovals,
     SendDeploy,
     Transfer,
     MakeTransfer,
     GetDeploy,
     GetBlock,
+    GetBlocks,
     GetBlockTransfers,
     ListDeploys,
+    ListPending,
     GetStateRootHash,
     QueryGlobalState,
     GetDictionaryItem,
@@ -56,6 +67,9 @@ enum DisplayOrder {
     GetEraInfo,
     GetAuctionInfo,
     GetValidatorChanges,
+    GetEvidence,
+    VerifyEvidence,
+    GetConsensusState,
     Keygen,
     GenerateCompletion,
     GetRpcs,
@@ -69,15 +83,19 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .subcommand(PutDeploy::build(DisplayOrder::PutDeploy as usize))
         .subcommand(MakeDeploy::build(DisplayOrder::MakeDeploy as usize))
         .subcommand(SignDeploy::build(DisplayOrder::SignDeploy as usize))
+        .subcommand(MergeApprovals::build(DisplayOrder::MergeApprovals as usize))
+        .subcommand(CheckApprovals::build(DisplayOrder::CheckApprovals as usize))
         .subcommand(SendDeploy::build(DisplayOrder::SendDeploy as usize))
         .subcommand(Transfer::build(DisplayOrder::Transfer as usize))
         .subcommand(MakeTransfer::build(DisplayOrder::MakeTransfer as usize))
         .subcommand(GetDeploy::build(DisplayOrder::GetDeploy as usize))
         .subcommand(GetBlock::build(DisplayOrder::GetBlock as usize))
+        .subcommand(GetBlocks::build(DisplayOrder::GetBlocks as usize))
         .subcommand(GetBlockTransfers::build(
             DisplayOrder::GetBlockTransfers as usize,
         ))
         .subcommand(ListDeploys::build(DisplayOrder::ListDeploys as usize))
+        .subcommand(GetPendingDeploys::build(DisplayOrder::ListPending as usize))
         .subcommand(GetBalance::build(DisplayOrder::GetBalance as usize))
         .subcommand(GetAccountInfo::build(DisplayOrder::GetAccountInfo as usize))
         .subcommand(GetStateRootHash::build(
@@ -90,6 +108,11 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .subcommand(GetValidatorChanges::build(
             DisplayOrder::GetValidatorChanges as usize,
         ))
+        .subcommand(GetEvidence::build(DisplayOrder::GetEvidence as usize))
+        .subcommand(VerifyEvidence::build(DisplayOrder::VerifyEvidence as usize))
+        .subcommand(GetConsensusState::build(
+            DisplayOrder::GetConsensusState as usize,
+        ))
         .subcommand(Keygen::build(DisplayOrder::Keygen as usize))
         .subcommand(GenerateCompletion::build(
             DisplayOrder::GenerateCompletion as usize,
@@ -111,15 +134,21 @@ async fn main() {
         (PutDeploy::NAME, Some(matches)) => (PutDeploy::run(matches).await, matches),
         (MakeDeploy::NAME, Some(matches)) => (MakeDeploy::run(matches).await, matches),
         (SignDeploy::NAME, Some(matches)) => (SignDeploy::run(matches).await, matches),
+        (MergeApprovals::NAME, Some(matches)) => (MergeApprovals::run(matches).await, matches),
+        (CheckApprovals::NAME, Some(matches)) => (CheckApprovals::run(matches).await, matches),
         (SendDeploy::NAME, Some(matches)) => (SendDeploy::run(matches).await, matches),
         (Transfer::NAME, Some(matches)) => (Transfer::run(matches).await, matches),
         (MakeTransfer::NAME, Some(matches)) => (MakeTransfer::run(matches).await, matches),
         (GetDeploy::NAME, Some(matches)) => (GetDeploy::run(matches).await, matches),
         (GetBlock::NAME, Some(matches)) => (GetBlock::run(matches).await, matches),
+        (GetBlocks::NAME, Some(matches)) => (GetBlocks::run(matches).await, matches),
         (GetBlockTransfers::NAME, Some(matches)) => {
             (GetBlockTransfers::run(matches).await, matches)
         }
         (ListDeploys::NAME, Some(matches)) => (ListDeploys::run(matches).await, matches),
+        (GetPendingDeploys::NAME, Some(matches)) => {
+            (GetPendingDeploys::run(matches).await, matches)
+        }
         (GetBalance::NAME, Some(matches)) => (GetBalance::run(matches).await, matches),
         (GetAccountInfo::NAME, Some(matches)) => (GetAccountInfo::run(matches).await, matches),
         (GetStateRootHash::NAME, Some(matches)) => (GetStateRootHash::run(matches).await, matches),
@@ -130,6 +159,11 @@ async fn main() {
         (GetValidatorChanges::NAME, Some(matches)) => {
             (GetValidatorChanges::run(matches).await, matches)
         }
+        (GetEvidence::NAME, Some(matches)) => (GetEvidence::run(matches).await, matches),
+        (VerifyEvidence::NAME, Some(matches)) => (VerifyEvidence::run(matches).await, matches),
+        (GetConsensusState::NAME, Some(matches)) => {
+            (GetConsensusState::run(matches).await, matches)
+        }
         (Keygen::NAME, Some(matches)) => (Keygen::run(matches).await, matches),
         (GenerateCompletion::NAME, Some(matches)) => {
             (GenerateCompletion::run(matches).await, matches)
@@ -10,11 +10,12 @@ use warp::{Filter, Rejection};
 use warp_json_rpc::Builder;
 
 use casper_node::crypto::Error as CryptoError;
+use casper_types::{AsymmetricType, PublicKey};
 use hex::FromHexError;
 
 use casper_client::{
     DeployStrParams, DictionaryItemStrParams, Error, GlobalStateStrParams, PaymentStrParams,
-    SessionStrParams,
+    RequiredSigner, RequiredSigners, SessionStrParams,
 };
 use casper_node::rpcs::{
     account::{PutDeploy, PutDeployParams},
@@ -236,6 +237,37 @@ impl MockServerHandle {
             .await
             .map(|_| ())
     }
+
+    async fn get_pending_deploys(&self) -> Result<(), Error> {
+        casper_client::get_pending_deploys("1", &self.url(), 0)
+            .await
+            .map(|_| ())
+    }
+
+    async fn get_account_deploys(&self, account: &str, start_height: &str) -> Result<(), Error> {
+        casper_client::get_account_deploys("1", &self.url(), 0, account, start_height)
+            .await
+            .map(|_| ())
+    }
+
+    async fn get_blocks(
+        &self,
+        start_height: &str,
+        max_count: &str,
+        headers_only: bool,
+    ) -> Result<(), Error> {
+        casper_client::get_blocks(
+            "1",
+            &self.url(),
+            0,
+            start_height,
+            max_count,
+            headers_only,
+            false,
+        )
+        .await
+        .map(|_| ())
+    }
 }
 
 impl Drop for MockServerHandle {
@@ -319,12 +351,18 @@ mod global_state_params {
 mod dictionary_item_str_params {
     use super::*;
 
-    const DICTIONARY_NAME: &str = "test-dictionary";
-    const DICTIONARY_ITEM_KEY: &str = "test-item";
+    pub const DICTIONARY_NAME: &str = "test-dictionary";
+    pub const DICTIONARY_ITEM_KEY: &str = "test-item";
+    pub const ACCOUNT_KEY: &str =
+        "account-hash-09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6";
+    pub const CONTRACT_KEY: &str =
+        "hash-09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6";
+    pub const DICTIONARY_ADDRESS: &str =
+        "dictionary-09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6";
 
     pub fn generate_valid_account_params() -> DictionaryItemStrParams<'static> {
         DictionaryItemStrParams::AccountNamedKey {
-            key: "account-hash-09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6",
+            key: ACCOUNT_KEY,
             dictionary_name: DICTIONARY_NAME,
             dictionary_item_key: DICTIONARY_ITEM_KEY,
         }
@@ -340,7 +378,7 @@ mod dictionary_item_str_params {
 
     pub fn generate_valid_contract_params() -> DictionaryItemStrParams<'static> {
         DictionaryItemStrParams::ContractNamedKey {
-            key: "hash-09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6",
+            key: CONTRACT_KEY,
             dictionary_name: DICTIONARY_NAME,
             dictionary_item_key: DICTIONARY_ITEM_KEY,
         }
@@ -369,9 +407,7 @@ mod dictionary_item_str_params {
     }
 
     pub fn generate_valid_dictionary_address() -> DictionaryItemStrParams<'static> {
-        DictionaryItemStrParams::Dictionary(
-            "dictionary-09dcee4b212cfd53642ab323fbef07dafafc6f945a80a00147f62910a915c4e6",
-        )
+        DictionaryItemStrParams::Dictionary(DICTIONARY_ADDRESS)
     }
 
     pub fn generate_invalid_dictionary_address() -> DictionaryItemStrParams<'static> {
@@ -379,6 +415,216 @@ mod dictionary_item_str_params {
     }
 }
 
+/// A global state holding a dictionary whose seed URef is stored under a named key of both an
+/// account and a contract, serving responses with valid Merkle proofs of its entries.
+mod dictionary_fixture {
+    use std::{collections::HashMap, convert::TryFrom};
+
+    use once_cell::sync::Lazy;
+
+    use casper_execution_engine::{
+        core::DictionaryValue,
+        shared::newtypes::CorrelationId,
+        storage::{
+            global_state::{in_memory::InMemoryGlobalState, StateProvider, StateReader},
+            trie::merkle_proof::TrieMerkleProof,
+        },
+    };
+    use casper_hashing::Digest;
+    use casper_node::{
+        rpcs::state::{
+            DictionaryIdentifier, GetDictionaryItemResult, QueryGlobalState,
+            QueryGlobalStateParams, QueryGlobalStateResult,
+        },
+        types::json_compatibility,
+    };
+    use casper_types::{
+        account::Account, bytesrepr::ToBytes, contracts::NamedKeys, CLValue, Contract,
+        ContractPackageHash, ContractWasmHash, EntryPoints, Key, ProtocolVersion, StoredValue,
+        URef,
+    };
+
+    use super::{dictionary_item_str_params::*, *};
+
+    /// How the fixture tampers with its responses.
+    #[derive(Clone, Copy)]
+    pub enum Tamper {
+        /// Responses are left untouched.
+        Nothing,
+        /// Dictionary item responses hold a different value than the proof.
+        StoredValue,
+        /// Dictionary item proofs hold a different value than the one in global state.
+        MerkleProof,
+    }
+
+    struct DictionaryFixture {
+        state_root_hash: Digest,
+        proofs: HashMap<Key, TrieMerkleProof<Key, StoredValue>>,
+    }
+
+    static DICTIONARY_FIXTURE: Lazy<DictionaryFixture> = Lazy::new(|| {
+        let seed_uref = URef::from_formatted_str(VALID_PURSE_UREF).unwrap();
+        let mut named_keys = NamedKeys::new();
+        named_keys.insert(DICTIONARY_NAME.to_string(), Key::URef(seed_uref));
+
+        let account_key = Key::from_formatted_str(ACCOUNT_KEY).unwrap();
+        let account = Account::create(
+            account_key.into_account().unwrap(),
+            named_keys.clone(),
+            seed_uref,
+        );
+        let contract = Contract::new(
+            ContractPackageHash::new([1; 32]),
+            ContractWasmHash::new([2; 32]),
+            named_keys,
+            EntryPoints::new(),
+            ProtocolVersion::V1_0_0,
+        );
+        let dictionary_value = |value: u64| {
+            let dictionary_value = DictionaryValue::new(
+                CLValue::from_t(value).unwrap(),
+                seed_uref.addr().to_vec(),
+                DICTIONARY_ITEM_KEY.as_bytes().to_vec(),
+            );
+            StoredValue::CLValue(CLValue::from_t(dictionary_value).unwrap())
+        };
+
+        let pairs = [
+            (account_key, StoredValue::Account(account)),
+            (
+                Key::from_formatted_str(CONTRACT_KEY).unwrap(),
+                StoredValue::Contract(contract),
+            ),
+            (
+                Key::dictionary(seed_uref, DICTIONARY_ITEM_KEY.as_bytes()),
+                dictionary_value(1),
+            ),
+            (
+                Key::from_formatted_str(DICTIONARY_ADDRESS).unwrap(),
+                dictionary_value(2),
+            ),
+        ];
+        let correlation_id = CorrelationId::new();
+        let (global_state, state_root_hash) =
+            InMemoryGlobalState::from_pairs(correlation_id, &pairs).unwrap();
+        let reader = global_state.checkout(state_root_hash).unwrap().unwrap();
+        let proofs = pairs
+            .iter()
+            .map(|(key, _)| {
+                let proof = reader
+                    .read_with_proof(correlation_id, key)
+                    .unwrap()
+                    .unwrap();
+                (*key, proof)
+            })
+            .collect();
+        DictionaryFixture {
+            state_root_hash,
+            proofs,
+        }
+    });
+
+    /// Returns the state root hash of the fixture's global state.
+    pub fn state_root_hash() -> String {
+        hex::encode(DICTIONARY_FIXTURE.state_root_hash)
+    }
+
+    fn json_stored_value(stored_value: StoredValue) -> json_compatibility::StoredValue {
+        json_compatibility::StoredValue::try_from(stored_value).unwrap()
+    }
+
+    fn get_dictionary_item_result(
+        params: GetDictionaryItemParams,
+        tamper: Tamper,
+    ) -> GetDictionaryItemResult {
+        let seed_uref = URef::from_formatted_str(VALID_PURSE_UREF).unwrap();
+        let dictionary_key = match params.dictionary_identifier {
+            DictionaryIdentifier::AccountNamedKey {
+                dictionary_item_key,
+                ..
+            }
+            | DictionaryIdentifier::ContractNamedKey {
+                dictionary_item_key,
+                ..
+            }
+            | DictionaryIdentifier::URef {
+                dictionary_item_key,
+                ..
+            } => Key::dictionary(seed_uref, dictionary_item_key.as_bytes()),
+            DictionaryIdentifier::Dictionary(address) => Key::from_formatted_str(&address).unwrap(),
+        };
+        let mut proof = DICTIONARY_FIXTURE.proofs[&dictionary_key].clone();
+        let mut unwrapped_value = match proof.value() {
+            StoredValue::CLValue(cl_value) => cl_value
+                .clone()
+                .into_t::<DictionaryValue>()
+                .unwrap()
+                .into_cl_value(),
+            _ => unreachable!("dictionary items are stored as CLValues"),
+        };
+        match tamper {
+            Tamper::Nothing => (),
+            Tamper::StoredValue => unwrapped_value = CLValue::from_t(u64::MAX).unwrap(),
+            Tamper::MerkleProof => {
+                let dictionary_value = DictionaryValue::new(
+                    CLValue::from_t(u64::MAX).unwrap(),
+                    seed_uref.addr().to_vec(),
+                    DICTIONARY_ITEM_KEY.as_bytes().to_vec(),
+                );
+                unwrapped_value = CLValue::from_t(u64::MAX).unwrap();
+                proof = TrieMerkleProof::new(
+                    dictionary_key,
+                    StoredValue::CLValue(CLValue::from_t(dictionary_value).unwrap()),
+                    proof.proof_steps().clone(),
+                );
+            }
+        }
+        GetDictionaryItemResult {
+            api_version: ProtocolVersion::V1_0_0,
+            dictionary_key: dictionary_key.to_formatted_string(),
+            stored_value: json_stored_value(StoredValue::CLValue(unwrapped_value)),
+            merkle_proof: hex::encode(vec![proof].to_bytes().unwrap()),
+            items: None,
+            next_cursor: None,
+        }
+    }
+
+    fn query_global_state_result(params: QueryGlobalStateParams) -> QueryGlobalStateResult {
+        let key = Key::from_formatted_str(&params.key).unwrap();
+        let proof = DICTIONARY_FIXTURE.proofs[&key].clone();
+        QueryGlobalStateResult {
+            api_version: ProtocolVersion::V1_0_0,
+            block_header: None,
+            stored_value: json_stored_value(proof.value().clone()),
+            merkle_proof: hex::encode(vec![proof].to_bytes().unwrap()),
+        }
+    }
+
+    /// Will spawn a server on localhost serving dictionary items and the accounts and contracts
+    /// holding their seed URefs from the fixture.
+    pub fn spawn(tamper: Tamper) -> MockServerHandle {
+        let get_dictionary_item = warp_json_rpc::filters::json_rpc()
+            .and(warp_json_rpc::filters::method(GetDictionaryItem::METHOD))
+            .and(warp_json_rpc::filters::params::<GetDictionaryItemParams>())
+            .map(move |builder: Builder, params: GetDictionaryItemParams| {
+                builder
+                    .success(get_dictionary_item_result(params, tamper))
+                    .unwrap()
+            });
+        let query_global_state = warp_json_rpc::filters::json_rpc()
+            .and(warp_json_rpc::filters::method(QueryGlobalState::METHOD))
+            .and(warp_json_rpc::filters::params::<QueryGlobalStateParams>())
+            .map(|builder: Builder, params: QueryGlobalStateParams| {
+                builder.success(query_global_state_result(params)).unwrap()
+            });
+        MockServerHandle::spawn_with_filter(
+            get_dictionary_item.or(query_global_state).unify(),
+            10,
+            DEFAULT_RATE_PER,
+        )
+    }
+}
+
 mod get_balance {
     use super::*;
 
@@ -619,17 +865,19 @@ mod get_item {
 }
 
 mod get_dictionary_item {
-    use super::*;
+    use casper_client::ValidateResponseError;
+
+    use super::{dictionary_fixture::Tamper, *};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn should_succeed_with_valid_dictionary_params() {
-        let server_handle =
-            MockServerHandle::spawn::<GetDictionaryItemParams>(GetDictionaryItem::METHOD);
+        let server_handle = dictionary_fixture::spawn(Tamper::Nothing);
+        let state_root_hash = dictionary_fixture::state_root_hash();
         let dictionary_str_account_params =
             dictionary_item_str_params::generate_valid_account_params();
         assert!(matches!(
             server_handle
-                .get_dictionary_item(VALID_STATE_ROOT_HASH, dictionary_str_account_params)
+                .get_dictionary_item(&state_root_hash, dictionary_str_account_params)
                 .await,
             Ok(())
         ));
@@ -638,7 +886,7 @@ mod get_dictionary_item {
             dictionary_item_str_params::generate_valid_contract_params();
         assert!(matches!(
             server_handle
-                .get_dictionary_item(VALID_STATE_ROOT_HASH, dictionary_contract_params)
+                .get_dictionary_item(&state_root_hash, dictionary_contract_params)
                 .await,
             Ok(())
         ));
@@ -646,7 +894,7 @@ mod get_dictionary_item {
         let dictionary_uref_params = dictionary_item_str_params::generate_valid_uref_params();
         assert!(matches!(
             server_handle
-                .get_dictionary_item(VALID_STATE_ROOT_HASH, dictionary_uref_params)
+                .get_dictionary_item(&state_root_hash, dictionary_uref_params)
                 .await,
             Ok(())
         ));
@@ -655,12 +903,47 @@ mod get_dictionary_item {
             dictionary_item_str_params::generate_valid_dictionary_address();
         assert!(matches!(
             server_handle
-                .get_dictionary_item(VALID_STATE_ROOT_HASH, dictionary_address_params)
+                .get_dictionary_item(&state_root_hash, dictionary_address_params)
                 .await,
             Ok(())
         ));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_tampered_stored_value() {
+        let server_handle = dictionary_fixture::spawn(Tamper::StoredValue);
+        let dictionary_uref_params = dictionary_item_str_params::generate_valid_uref_params();
+        assert!(matches!(
+            server_handle
+                .get_dictionary_item(
+                    &dictionary_fixture::state_root_hash(),
+                    dictionary_uref_params
+                )
+                .await,
+            Err(Error::InvalidResponse(
+                ValidateResponseError::SerializedValueNotContainedInProof
+            ))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_tampered_merkle_proof() {
+        let server_handle = dictionary_fixture::spawn(Tamper::MerkleProof);
+        let dictionary_str_account_params =
+            dictionary_item_str_params::generate_valid_account_params();
+        assert!(matches!(
+            server_handle
+                .get_dictionary_item(
+                    &dictionary_fixture::state_root_hash(),
+                    dictionary_str_account_params
+                )
+                .await,
+            Err(Error::InvalidResponse(
+                ValidateResponseError::ValidationError(_)
+            ))
+        ));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn should_fail_with_invalid_params() {
         let server_handle =
@@ -857,6 +1140,126 @@ mod get_validator_changes {
     }
 }
 
+mod get_pending_deploys {
+    use super::*;
+
+    use casper_node::rpcs::{info::GetPendingDeploys, RpcWithoutParams};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_succeed() {
+        let server_handle = MockServerHandle::spawn_without_params(GetPendingDeploys::METHOD);
+        assert!(matches!(server_handle.get_pending_deploys().await, Ok(())))
+    }
+}
+
+mod get_account_deploys {
+    use super::*;
+
+    use casper_node::rpcs::info::{GetAccountDeploys, GetAccountDeploysParams};
+
+    const VALID_PUBLIC_KEY: &str =
+        "01522ef6c89038019cb7af05c340623804392dd2bb1f4dab5e4a9c3ab752fc0179";
+    const VALID_ACCOUNT_HASH: &str =
+        "account-hash-0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_succeed_with_public_key() {
+        let server_handle =
+            MockServerHandle::spawn::<GetAccountDeploysParams>(GetAccountDeploys::METHOD);
+        assert!(matches!(
+            server_handle
+                .get_account_deploys(VALID_PUBLIC_KEY, "")
+                .await,
+            Ok(())
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_succeed_with_account_hash_and_start_height() {
+        let server_handle =
+            MockServerHandle::spawn::<GetAccountDeploysParams>(GetAccountDeploys::METHOD);
+        assert!(matches!(
+            server_handle
+                .get_account_deploys(VALID_ACCOUNT_HASH, "10")
+                .await,
+            Ok(())
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_invalid_account() {
+        let server_handle =
+            MockServerHandle::spawn::<GetAccountDeploysParams>(GetAccountDeploys::METHOD);
+        assert!(matches!(
+            server_handle.get_account_deploys("012345", "").await,
+            Err(Error::FailedToParseKey)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_invalid_start_height() {
+        let server_handle =
+            MockServerHandle::spawn::<GetAccountDeploysParams>(GetAccountDeploys::METHOD);
+        assert!(matches!(
+            server_handle
+                .get_account_deploys(VALID_PUBLIC_KEY, "not a height")
+                .await,
+            Err(Error::FailedToParseInt {
+                context: "start_height",
+                ..
+            })
+        ));
+    }
+}
+
+mod get_blocks {
+    use super::*;
+
+    use casper_node::rpcs::chain::{GetBlocks, GetBlocksParams};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_succeed_with_start_height() {
+        let server_handle = MockServerHandle::spawn::<GetBlocksParams>(GetBlocks::METHOD);
+        assert!(matches!(
+            server_handle.get_blocks("10", "", false).await,
+            Ok(())
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_succeed_with_max_count_and_headers_only() {
+        let server_handle = MockServerHandle::spawn::<GetBlocksParams>(GetBlocks::METHOD);
+        assert!(matches!(
+            server_handle.get_blocks("10", "5", true).await,
+            Ok(())
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_invalid_start_height() {
+        let server_handle = MockServerHandle::spawn::<GetBlocksParams>(GetBlocks::METHOD);
+        assert!(matches!(
+            server_handle.get_blocks("", "", false).await,
+            Err(Error::FailedToParseInt {
+                context: "start_height",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_fail_with_invalid_max_count() {
+        let server_handle = MockServerHandle::spawn::<GetBlocksParams>(GetBlocks::METHOD);
+        assert!(matches!(
+            server_handle.get_blocks("10", "-1", false).await,
+            Err(Error::FailedToParseInt {
+                context: "max_count",
+                ..
+            })
+        ));
+    }
+}
+
 mod make_deploy {
     use super::*;
 
@@ -976,6 +1379,48 @@ mod send_deploy {
             Ok(())
         ));
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_only_succeed_for_file_with_sufficient_approvals() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create temp dir with error: {}", err));
+        let file_path = temp_dir.path().join("test_send_deploy.json");
+        casper_client::make_deploy(
+            file_path.to_str().unwrap(),
+            deploy_params::test_data_valid(),
+            session_params::test_data_with_package_hash(),
+            payment_params::test_data_with_name(),
+            false,
+        )
+        .unwrap();
+        let mut deploy: serde_json::Value =
+            serde_json::from_slice(&fs::read(&file_path).unwrap()).unwrap();
+        let signer = PublicKey::from_hex(deploy["approvals"][0]["signer"].as_str().unwrap())
+            .unwrap()
+            .to_account_hash();
+
+        let server_handle = MockServerHandle::spawn::<PutDeployParams>(PutDeploy::METHOD);
+        for (deployment_threshold, should_succeed) in [(2, false), (1, true)] {
+            let required_signers = RequiredSigners {
+                deployment_threshold,
+                associated_keys: vec![RequiredSigner {
+                    account_hash: signer,
+                    weight: 1,
+                }],
+            };
+            deploy["required_signers"] = serde_json::to_value(&required_signers).unwrap();
+            fs::write(&file_path, deploy.to_string()).unwrap();
+
+            let result = server_handle
+                .send_deploy_file(file_path.to_str().unwrap())
+                .await;
+            if should_succeed {
+                assert!(matches!(result, Ok(())));
+            } else {
+                assert!(matches!(result, Err(Error::InsufficientApprovals(_))));
+            }
+        }
+    }
 }
 
 mod sign_deploy {
@@ -6,6 +6,9 @@ pub mod runtime;
 pub mod runtime_context;
 pub(crate) mod tracking_copy;
 
+pub use runtime_context::dictionary::{
+    handle_stored_value as handle_stored_dictionary_value, DictionaryValue,
+};
 pub use tracking_copy::{validate_balance_proof, validate_query_proof, ValidationError};
 
 /// The length of an address.
@@ -26,6 +26,13 @@ pub struct DeployItem {
     /// A unique identifier of the deploy.
     /// Currently it is the hash of the deploy header (see `DeployHeader` in the `types` crate).
     pub deploy_hash: DeployHash,
+    /// Optional sequence number of this deploy among those sent by `address`.  If provided, it
+    /// must be exactly one greater than the value stored under [`account_nonce_key`] for the
+    /// account.  Ignored under protocol versions earlier than [`DEPLOY_NONCES_PROTOCOL_VERSION`].
+    ///
+    /// [`account_nonce_key`]: super::account_nonce_key
+    /// [`DEPLOY_NONCES_PROTOCOL_VERSION`]: super::DEPLOY_NONCES_PROTOCOL_VERSION
+    pub nonce: Option<u64>,
 }
 
 impl DeployItem {
@@ -37,6 +44,7 @@ impl DeployItem {
         gas_price: GasPrice,
         authorization_keys: BTreeSet<AccountHash>,
         deploy_hash: DeployHash,
+        nonce: Option<u64>,
     ) -> Self {
         DeployItem {
             address,
@@ -45,6 +53,7 @@ impl DeployItem {
             gas_price,
             authorization_keys,
             deploy_hash,
+            nonce,
         }
     }
 }
@@ -78,6 +78,14 @@ pub enum Error {
     /// Missing system contract hash.
     #[error("Missing system contract hash: {0}")]
     MissingSystemContractHash(String),
+    /// Deploy nonce does not follow the latest nonce used by the account.
+    #[error("Invalid nonce: expected {expected}, got {got}")]
+    InvalidNonce {
+        /// The nonce which the deploy should have provided.
+        expected: u64,
+        /// The nonce provided by the deploy.
+        got: u64,
+    },
 }
 
 impl Error {
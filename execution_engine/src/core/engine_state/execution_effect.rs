@@ -1,9 +1,20 @@
 //! Effects that are produced as part of execution.
-use casper_types::Key;
+use casper_types::{CLValue, Key};
 
 use super::op::Op;
 use crate::shared::{additive_map::AdditiveMap, transform::Transform};
 
+/// An event emitted by a contract during execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractEvent {
+    /// The key of the account or contract which emitted the event.
+    pub emitter: Key,
+    /// The topic of the event.
+    pub topic: String,
+    /// The payload of the event.
+    pub payload: CLValue,
+}
+
 /// Represents the effects of executing a single [`crate::core::engine_state::DeployItem`].
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ExecutionEffect {
@@ -12,12 +23,32 @@ pub struct ExecutionEffect {
     /// Transformations on the keys that occurred during the execution of a contract. Those
     /// [`Transform`]s need to be applied in a separate commit step.
     pub transforms: AdditiveMap<Key, Transform>,
+    /// Events emitted during the execution, in the order in which they were emitted.
+    pub events: Vec<ContractEvent>,
 }
 
 impl ExecutionEffect {
     /// Creates a new [`ExecutionEffect`].
-    pub fn new(ops: AdditiveMap<Key, Op>, transforms: AdditiveMap<Key, Transform>) -> Self {
-        ExecutionEffect { ops, transforms }
+    pub fn new(
+        ops: AdditiveMap<Key, Op>,
+        transforms: AdditiveMap<Key, Transform>,
+        events: Vec<ContractEvent>,
+    ) -> Self {
+        ExecutionEffect {
+            ops,
+            transforms,
+            events,
+        }
+    }
+}
+
+impl From<&ContractEvent> for casper_types::ContractEvent {
+    fn from(event: &ContractEvent) -> Self {
+        casper_types::ContractEvent {
+            emitter: event.emitter,
+            topic: event.topic.clone(),
+            payload: event.payload.clone(),
+        }
     }
 }
 
@@ -5,7 +5,11 @@ use casper_types::{
     bytesrepr::FromBytes, CLTyped, CLValue, Gas, Key, Motes, StoredValue, TransferAddr,
 };
 
-use super::{error, execution_effect::ExecutionEffect, op::Op};
+use super::{
+    error,
+    execution_effect::{ContractEvent, ExecutionEffect},
+    op::Op,
+};
 use crate::{
     core::execution::Error as ExecError,
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
@@ -43,7 +47,7 @@ fn make_payment_error_effects(
         Transform::AddUInt512(max_payment_cost.value()),
     );
 
-    Ok(ExecutionEffect::new(ops, transforms))
+    Ok(ExecutionEffect::new(ops, transforms, Vec::new()))
 }
 
 /// Represents the result of an execution specified by
@@ -481,6 +485,7 @@ impl ExecutionResultBuilder {
         let cost = self.total_cost();
         let mut ops = AdditiveMap::new();
         let mut transforms = AdditiveMap::new();
+        let mut events = Vec::new();
 
         let mut ret: ExecutionResult = ExecutionResult::Success {
             execution_effect: Default::default(),
@@ -493,7 +498,7 @@ impl ExecutionResultBuilder {
                 if result.is_failure() {
                     return Ok(result);
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(&mut ops, &mut transforms, &mut events, result.effect());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingPaymentExecutionResult),
@@ -506,7 +511,7 @@ impl ExecutionResultBuilder {
                 if result.is_failure() {
                     ret = result.with_cost(cost);
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(&mut ops, &mut transforms, &mut events, result.effect());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
@@ -520,14 +525,15 @@ impl ExecutionResultBuilder {
                         error::Error::Finalization,
                     ));
                 } else {
-                    Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    Self::add_effects(&mut ops, &mut transforms, &mut events, result.effect());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingFinalizeExecutionResult),
         }
 
         // Remove redundant writes to allow more opportunity to commute
-        let reduced_effect = Self::reduce_identity_writes(ops, transforms, reader, correlation_id);
+        let reduced_effect =
+            Self::reduce_identity_writes(ops, transforms, events, reader, correlation_id);
 
         Ok(ret.with_effect(reduced_effect))
     }
@@ -535,6 +541,7 @@ impl ExecutionResultBuilder {
     fn add_effects(
         ops: &mut AdditiveMap<Key, Op>,
         transforms: &mut AdditiveMap<Key, Transform>,
+        events: &mut Vec<ContractEvent>,
         effect: &ExecutionEffect,
     ) {
         for (k, op) in effect.ops.iter() {
@@ -543,6 +550,7 @@ impl ExecutionResultBuilder {
         for (k, t) in effect.transforms.iter() {
             transforms.insert_add(*k, t.clone())
         }
+        events.extend(effect.events.iter().cloned());
     }
 
     /// In the case we are writing the same value as was there originally,
@@ -551,6 +559,7 @@ impl ExecutionResultBuilder {
     fn reduce_identity_writes<R: StateReader<Key, StoredValue>>(
         mut ops: AdditiveMap<Key, Op>,
         mut transforms: AdditiveMap<Key, Transform>,
+        events: Vec<ContractEvent>,
         reader: &R,
         correlation_id: CorrelationId,
     ) -> ExecutionEffect {
@@ -576,6 +585,6 @@ impl ExecutionResultBuilder {
             }
         }
 
-        ExecutionEffect::new(ops, transforms)
+        ExecutionEffect::new(ops, transforms, events)
     }
 }
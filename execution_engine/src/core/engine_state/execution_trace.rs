@@ -0,0 +1,313 @@
+//! Tracing of the execution of a deploy, for debugging contracts.
+use std::{cell::RefCell, mem, rc::Rc};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use wasmi::RuntimeValue;
+
+use casper_types::{ApiError, Key, NamedArg, RuntimeArgs};
+
+/// The maximum number of events recorded in a trace.  Later events are omitted.
+pub const MAX_TRACE_EVENTS: usize = 10_000;
+/// The maximum serialized size in bytes of a call frame argument recorded in a trace.  Larger
+/// arguments are omitted.
+pub const MAX_TRACE_ARG_SIZE: usize = 1_024;
+
+/// A trace of the execution of a deploy.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutionTrace {
+    /// The events of the execution, in the order they occurred.
+    events: Vec<TraceEvent>,
+    /// Where the execution was last reverted, if at all.
+    revert: Option<Revert>,
+    /// Whether any events or call frame arguments were omitted for exceeding
+    /// [`MAX_TRACE_EVENTS`] or [`MAX_TRACE_ARG_SIZE`].
+    #[serde(default)]
+    truncated: bool,
+}
+
+impl ExecutionTrace {
+    /// Returns the events of the execution, in the order they occurred.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Returns where the execution was last reverted, if at all.
+    pub fn revert(&self) -> Option<&Revert> {
+        self.revert.as_ref()
+    }
+
+    /// Returns whether any events or call frame arguments were omitted from the trace.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// An event of the execution of a deploy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum TraceEvent {
+    /// A call frame was entered, either for executing a phase of the deploy or for calling a
+    /// contract.
+    CallFrameEntered {
+        /// The name of the call frame.
+        name: String,
+        /// The arguments passed to the call frame.
+        args: RuntimeArgs,
+    },
+    /// The current call frame was exited.
+    CallFrameExited,
+    /// A host function was called.
+    HostFunctionCalled {
+        /// The name of the host function.
+        name: String,
+        /// The arguments passed to the host function.
+        args: Vec<WasmValue>,
+        /// The value returned by the host function, if any.
+        result: Option<WasmValue>,
+        /// The error trapping execution, if the host function failed.
+        error: Option<String>,
+    },
+    /// A named key of the current context was read.
+    NamedKeyRead {
+        /// The name of the key.
+        name: String,
+        /// The key, if there is one with that name.
+        #[schemars(with = "Option<String>")]
+        key: Option<Key>,
+    },
+    /// A named key was written to the current context.
+    NamedKeyWritten {
+        /// The name of the key.
+        name: String,
+        /// The key.
+        #[schemars(with = "String")]
+        key: Key,
+    },
+    /// A named key was removed from the current context.
+    NamedKeyRemoved {
+        /// The name of the key.
+        name: String,
+    },
+}
+
+/// A value passed to or returned from a host function by Wasm code.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum WasmValue {
+    /// A 32-bit integer.
+    I32(i32),
+    /// A 64-bit integer.
+    I64(i64),
+    /// A 32-bit float.
+    F32(f32),
+    /// A 64-bit float.
+    F64(f64),
+}
+
+impl From<RuntimeValue> for WasmValue {
+    fn from(value: RuntimeValue) -> Self {
+        match value {
+            RuntimeValue::I32(value) => WasmValue::I32(value),
+            RuntimeValue::I64(value) => WasmValue::I64(value),
+            RuntimeValue::F32(value) => WasmValue::F32(value.to_float()),
+            RuntimeValue::F64(value) => WasmValue::F64(value.to_float()),
+        }
+    }
+}
+
+/// Where the execution of a deploy was reverted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Revert {
+    /// The names of the call frames entered when reverting, outermost first.
+    call_stack: Vec<String>,
+    /// The code of the [`ApiError`] reverted with.
+    error_code: u32,
+    /// The description of the [`ApiError`] reverted with.
+    error: String,
+}
+
+impl Revert {
+    /// Returns the names of the call frames entered when reverting, outermost first.
+    pub fn call_stack(&self) -> &[String] {
+        &self.call_stack
+    }
+
+    /// Returns the [`ApiError`] reverted with.
+    pub fn error(&self) -> ApiError {
+        ApiError::from(self.error_code)
+    }
+}
+
+/// Records a trace of the execution of a deploy.
+#[derive(Debug, Default)]
+pub(crate) struct ExecutionTracer {
+    trace: ExecutionTrace,
+    call_stack: Vec<String>,
+}
+
+impl ExecutionTracer {
+    /// Takes the recorded trace, leaving an empty one.
+    pub(crate) fn take_trace(&mut self) -> ExecutionTrace {
+        self.call_stack.clear();
+        mem::take(&mut self.trace)
+    }
+
+    fn enter_call_frame(&mut self, name: String, args: &RuntimeArgs) {
+        self.call_stack.push(name.clone());
+        let mut traced_args = Vec::new();
+        for arg in args.named_args() {
+            if arg.cl_value().serialized_length() <= MAX_TRACE_ARG_SIZE {
+                traced_args.push(NamedArg::new(
+                    arg.name().to_string(),
+                    arg.cl_value().clone(),
+                ));
+            } else {
+                self.trace.truncated = true;
+            }
+        }
+        self.record(TraceEvent::CallFrameEntered {
+            name,
+            args: RuntimeArgs::from(traced_args),
+        });
+    }
+
+    fn exit_call_frame(&mut self) {
+        self.call_stack.pop();
+        self.record(TraceEvent::CallFrameExited);
+    }
+
+    /// Records an event in the current call frame, unless the trace already holds
+    /// [`MAX_TRACE_EVENTS`] events.
+    pub(crate) fn record(&mut self, event: TraceEvent) {
+        if self.trace.events.len() < MAX_TRACE_EVENTS {
+            self.trace.events.push(event);
+        } else {
+            self.trace.truncated = true;
+        }
+    }
+
+    /// Records a revert with the given error in the current call frame.
+    pub(crate) fn revert(&mut self, error: ApiError) {
+        self.trace.revert = Some(Revert {
+            call_stack: self.call_stack.clone(),
+            error_code: error.into(),
+            error: error.to_string(),
+        });
+    }
+}
+
+/// A call frame entered in an execution trace, exited when dropped.
+pub(crate) struct TraceCallFrame(Option<Rc<RefCell<ExecutionTracer>>>);
+
+impl TraceCallFrame {
+    /// Enters a call frame named by `name` and called with `args` if tracing is enabled.
+    pub(crate) fn enter<F>(
+        execution_tracer: Option<&Rc<RefCell<ExecutionTracer>>>,
+        name: F,
+        args: &RuntimeArgs,
+    ) -> Self
+    where
+        F: FnOnce() -> String,
+    {
+        let execution_tracer = execution_tracer.map(|execution_tracer| {
+            execution_tracer.borrow_mut().enter_call_frame(name(), args);
+            Rc::clone(execution_tracer)
+        });
+        TraceCallFrame(execution_tracer)
+    }
+}
+
+impl Drop for TraceCallFrame {
+    fn drop(&mut self) {
+        if let Some(execution_tracer) = &self.0 {
+            execution_tracer.borrow_mut().exit_call_frame();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{runtime_args, URef};
+
+    use super::*;
+
+    #[test]
+    fn should_record_revert_location_in_nested_call_frames() {
+        let execution_tracer = Rc::new(RefCell::new(ExecutionTracer::default()));
+        let key = Key::URef(URef::default());
+        {
+            let _session_frame = TraceCallFrame::enter(
+                Some(&execution_tracer),
+                || "session".to_string(),
+                &RuntimeArgs::new(),
+            );
+            execution_tracer
+                .borrow_mut()
+                .record(TraceEvent::NamedKeyWritten {
+                    name: "counter".to_string(),
+                    key,
+                });
+            let contract_args = runtime_args! { "amount" => 1u64 };
+            let _contract_frame = TraceCallFrame::enter(
+                Some(&execution_tracer),
+                || "contract::increment".to_string(),
+                &contract_args,
+            );
+            execution_tracer.borrow_mut().revert(ApiError::User(7));
+        }
+
+        let trace = execution_tracer.borrow_mut().take_trace();
+        assert_eq!(trace.events().len(), 5);
+        assert_eq!(
+            trace.events()[1],
+            TraceEvent::NamedKeyWritten {
+                name: "counter".to_string(),
+                key
+            }
+        );
+        assert_eq!(trace.events()[4], TraceEvent::CallFrameExited);
+
+        let revert = trace.revert().expect("should have reverted");
+        assert_eq!(revert.call_stack(), ["session", "contract::increment"]);
+        assert_eq!(revert.error(), ApiError::User(7));
+
+        assert!(!trace.is_truncated());
+
+        let json = serde_json::to_string(&trace).expect("should serialize");
+        let deserialized: ExecutionTrace = serde_json::from_str(&json).expect("should deserialize");
+        assert_eq!(deserialized, trace);
+    }
+
+    #[test]
+    fn should_truncate_large_traces() {
+        let execution_tracer = Rc::new(RefCell::new(ExecutionTracer::default()));
+        let args = runtime_args! {
+            "small" => 1u64,
+            "large" => vec![0u8; MAX_TRACE_ARG_SIZE],
+        };
+        let _session_frame =
+            TraceCallFrame::enter(Some(&execution_tracer), || "session".to_string(), &args);
+        let trace = execution_tracer.borrow_mut().take_trace();
+        assert!(trace.is_truncated());
+        assert_eq!(
+            trace.events(),
+            [TraceEvent::CallFrameEntered {
+                name: "session".to_string(),
+                args: runtime_args! { "small" => 1u64 },
+            }]
+        );
+
+        for _ in 0..=MAX_TRACE_EVENTS {
+            execution_tracer
+                .borrow_mut()
+                .record(TraceEvent::NamedKeyRemoved {
+                    name: "counter".to_string(),
+                });
+        }
+        let trace = execution_tracer.borrow_mut().take_trace();
+        assert!(trace.is_truncated());
+        assert_eq!(trace.events().len(), MAX_TRACE_EVENTS);
+    }
+}
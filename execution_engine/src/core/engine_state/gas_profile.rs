@@ -0,0 +1,486 @@
+//! Profiling of the gas consumed by the execution of a deploy.
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    io::{self, Write},
+    mem,
+    rc::Rc,
+};
+
+use parity_wasm::elements::Module;
+
+use casper_types::{Gas, Phase, U512};
+
+use crate::shared::{
+    opcode_attribution::{self, MeteredBlockCost, DYNAMIC_GAS_MARKER},
+    opcode_costs::OpcodeCategory,
+    wasm_config::WasmConfig,
+};
+
+/// The label of the gas charged for opcodes in folded stacks.
+const OPCODES_LABEL: &str = "opcodes";
+/// The label of the gas charged for opcodes which couldn't be attributed to a category in folded
+/// stacks.
+const UNATTRIBUTED_OPCODES_LABEL: &str = "unattributed";
+/// The label of the gas charged for storage in folded stacks.
+const STORAGE_LABEL: &str = "storage";
+/// The label of the gas charged for system contract calls in folded stacks.
+const SYSTEM_CONTRACT_CALL_LABEL: &str = "system_contract_call";
+
+/// A profile of the gas consumed by the execution of a deploy, by call frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasProfile {
+    frames: Vec<CallFrameGasProfile>,
+}
+
+impl GasProfile {
+    /// Returns the profiles of the call frames, in the order they were entered.
+    pub fn frames(&self) -> &[CallFrameGasProfile] {
+        &self.frames
+    }
+
+    /// Returns the total gas charged in all call frames.
+    pub fn total_cost(&self) -> Gas {
+        self.frames
+            .iter()
+            .fold(Gas::default(), |total, frame| total + frame.total_cost())
+    }
+
+    /// Returns the profile as folded stacks, one line per distinct stack, each consisting of the
+    /// names of the call frames and the charge separated by semicolons, followed by the gas
+    /// charged.
+    ///
+    /// This is the input format of flame graph tools like `flamegraph.pl` and `inferno`.
+    pub fn folded_stacks(&self) -> String {
+        let mut folded_stacks: BTreeMap<String, U512> = BTreeMap::new();
+        for frame in &self.frames {
+            let stack = frame.stack.join(";");
+            for (label, cost) in frame.labelled_costs() {
+                if cost.value().is_zero() {
+                    continue;
+                }
+                *folded_stacks
+                    .entry(format!("{};{}", stack, label))
+                    .or_default() += cost.value();
+            }
+        }
+        folded_stacks
+            .into_iter()
+            .map(|(stack, cost)| format!("{} {}\n", stack, cost))
+            .collect()
+    }
+
+    /// Writes the profile as folded stacks, as returned by [`GasProfile::folded_stacks`].
+    pub fn write_folded_stacks<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.folded_stacks().as_bytes())
+    }
+}
+
+/// A profile of the gas charged within a single call frame, excluding the frames it called.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallFrameGasProfile {
+    stack: Vec<String>,
+    opcodes: BTreeMap<OpcodeCategory, Gas>,
+    unattributed_opcodes: Gas,
+    host_function_calls: Vec<HostFunctionCharge>,
+    storage_charges: Vec<StorageCharge>,
+    system_contract_calls: Vec<Gas>,
+}
+
+impl CallFrameGasProfile {
+    /// Returns the names of the call frames from the outermost one to this one.
+    pub fn stack(&self) -> &[String] {
+        &self.stack
+    }
+
+    /// Returns the gas charged for opcodes, by category.
+    pub fn opcodes(&self) -> &BTreeMap<OpcodeCategory, Gas> {
+        &self.opcodes
+    }
+
+    /// Returns the gas charged for opcodes which couldn't be attributed to a category, e.g.
+    /// because the executed module was preprocessed with different opcode costs.
+    pub fn unattributed_opcodes(&self) -> Gas {
+        self.unattributed_opcodes
+    }
+
+    /// Returns the host function calls, in the order they were made.
+    pub fn host_function_calls(&self) -> &[HostFunctionCharge] {
+        &self.host_function_calls
+    }
+
+    /// Returns the charges for storage, in the order they were made.
+    pub fn storage_charges(&self) -> &[StorageCharge] {
+        &self.storage_charges
+    }
+
+    /// Returns the charges for system contract calls, in the order they were made.
+    pub fn system_contract_calls(&self) -> &[Gas] {
+        &self.system_contract_calls
+    }
+
+    /// Returns the total gas charged in this call frame.
+    pub fn total_cost(&self) -> Gas {
+        self.labelled_costs()
+            .fold(Gas::default(), |total, (_, cost)| total + cost)
+    }
+
+    fn labelled_costs(&self) -> impl Iterator<Item = (String, Gas)> + '_ {
+        let opcodes = self
+            .opcodes
+            .iter()
+            .map(|(category, cost)| (format!("{};{}", OPCODES_LABEL, category), *cost))
+            .chain(Some((
+                format!("{};{}", OPCODES_LABEL, UNATTRIBUTED_OPCODES_LABEL),
+                self.unattributed_opcodes,
+            )));
+        let host_function_calls = self
+            .host_function_calls
+            .iter()
+            .map(|charge| (charge.name.to_string(), charge.cost));
+        let storage_charges = self
+            .storage_charges
+            .iter()
+            .map(|charge| (STORAGE_LABEL.to_string(), charge.cost));
+        let system_contract_calls = self
+            .system_contract_calls
+            .iter()
+            .map(|cost| (SYSTEM_CONTRACT_CALL_LABEL.to_string(), *cost));
+        opcodes
+            .chain(host_function_calls)
+            .chain(storage_charges)
+            .chain(system_contract_calls)
+    }
+}
+
+/// The gas charged for a host function call, as specified by
+/// [`HostFunctionCosts`](crate::shared::host_function_costs::HostFunctionCosts).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostFunctionCharge {
+    name: &'static str,
+    cost: Gas,
+}
+
+impl HostFunctionCharge {
+    /// Returns the name of the host function.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the gas charged.
+    pub fn cost(&self) -> Gas {
+        self.cost
+    }
+}
+
+/// The gas charged for storing bytes, as specified by
+/// [`StorageCosts`](crate::shared::storage_costs::StorageCosts).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageCharge {
+    bytes: usize,
+    cost: Gas,
+}
+
+impl StorageCharge {
+    /// Returns the number of bytes charged for.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the gas charged.
+    pub fn cost(&self) -> Gas {
+        self.cost
+    }
+}
+
+/// What gas is charged for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum GasCharge {
+    /// A metered block of Wasm code, with the cost of its opcodes by category if known.
+    Opcodes(Option<BTreeMap<OpcodeCategory, u64>>),
+    /// Growing the Wasm memory, charged per page.
+    GrowMemory,
+    /// Calling a host function.
+    HostFunction,
+    /// Storing bytes.
+    Storage { bytes: usize },
+    /// Calling a system contract's entry point.
+    SystemContractCall,
+}
+
+/// The state of an entered call frame.
+#[derive(Debug)]
+struct CallFrame {
+    /// The index of the frame's profile.
+    profile_index: usize,
+    /// The costs of the metered blocks of the executed profiled module, by call site index.
+    metered_blocks: Option<Vec<MeteredBlockCost>>,
+    /// Whether the next value passed to the `gas` host function is a cost computed at runtime.
+    dynamic_gas_pending: bool,
+    /// The name of the host function being called.
+    host_function: &'static str,
+}
+
+/// Records the gas charged during the execution of a deploy.
+#[derive(Debug, Default)]
+pub(crate) struct GasProfiler {
+    profile: GasProfile,
+    call_frames: Vec<CallFrame>,
+}
+
+impl GasProfiler {
+    /// Takes the recorded profile, leaving an empty one.
+    pub(crate) fn take_profile(&mut self) -> GasProfile {
+        mem::take(&mut self.profile)
+    }
+
+    fn enter_frame(&mut self, name: String) {
+        let mut stack = self
+            .call_frames
+            .last()
+            .map(|call_frame| self.profile.frames[call_frame.profile_index].stack.clone())
+            .unwrap_or_default();
+        stack.push(name.replace(';', "_"));
+        self.call_frames.push(CallFrame {
+            profile_index: self.profile.frames.len(),
+            metered_blocks: None,
+            dynamic_gas_pending: false,
+            host_function: "",
+        });
+        self.profile.frames.push(CallFrameGasProfile {
+            stack,
+            ..Default::default()
+        });
+    }
+
+    fn exit_frame(&mut self) {
+        self.call_frames.pop();
+    }
+
+    /// Sets the name of the host function being called in the current call frame.
+    pub(crate) fn set_host_function(&mut self, name: &'static str) {
+        if let Some(call_frame) = self.call_frames.last_mut() {
+            call_frame.host_function = name;
+        }
+    }
+
+    /// Returns the gas to charge for the given value passed to the `gas` host function by Wasm
+    /// code in the current call frame, and what it is charged for.
+    ///
+    /// Returns `None` if nothing is to be charged.
+    pub(crate) fn wasm_gas_charge(&mut self, value: u32) -> Option<(Gas, GasCharge)> {
+        let raw_charge = Some((Gas::new(value.into()), GasCharge::Opcodes(None)));
+        let call_frame = match self.call_frames.last_mut() {
+            Some(call_frame) => call_frame,
+            None => return raw_charge,
+        };
+        let metered_blocks = match &call_frame.metered_blocks {
+            Some(metered_blocks) => metered_blocks,
+            None => return raw_charge,
+        };
+        if call_frame.dynamic_gas_pending {
+            call_frame.dynamic_gas_pending = false;
+            return Some((Gas::new(value.into()), GasCharge::GrowMemory));
+        }
+        if value == DYNAMIC_GAS_MARKER {
+            call_frame.dynamic_gas_pending = true;
+            return None;
+        }
+        match metered_blocks.get(value as usize) {
+            Some(metered_block) => Some((
+                Gas::new(metered_block.cost.into()),
+                GasCharge::Opcodes(metered_block.categories.clone()),
+            )),
+            None => raw_charge,
+        }
+    }
+
+    /// Records a charge in the current call frame.
+    ///
+    /// `charged` is less than `amount` if the charge exceeded the gas limit, in which case only
+    /// what was charged is recorded.
+    pub(crate) fn record(&mut self, charge: GasCharge, amount: Gas, charged: Gas) {
+        let call_frame = match self.call_frames.last() {
+            Some(call_frame) => call_frame,
+            None => return,
+        };
+        let host_function = call_frame.host_function;
+        let frame = &mut self.profile.frames[call_frame.profile_index];
+        match charge {
+            GasCharge::Opcodes(Some(categories)) if charged == amount => {
+                for (category, cost) in categories {
+                    *frame.opcodes.entry(category).or_default() += Gas::new(cost.into());
+                }
+            }
+            GasCharge::Opcodes(_) => frame.unattributed_opcodes += charged,
+            GasCharge::GrowMemory => {
+                *frame.opcodes.entry(OpcodeCategory::GrowMemory).or_default() += charged
+            }
+            GasCharge::HostFunction => frame.host_function_calls.push(HostFunctionCharge {
+                name: host_function,
+                cost: charged,
+            }),
+            GasCharge::Storage { bytes } => frame.storage_charges.push(StorageCharge {
+                bytes,
+                cost: charged,
+            }),
+            GasCharge::SystemContractCall => frame.system_contract_calls.push(charged),
+        }
+    }
+}
+
+/// A call frame entered in a gas profile, exited when dropped.
+pub(crate) struct GasProfileFrame(Option<Rc<RefCell<GasProfiler>>>);
+
+impl GasProfileFrame {
+    /// Enters a call frame named by `name` if gas profiling is enabled.
+    pub(crate) fn enter<F>(gas_profiler: Option<&Rc<RefCell<GasProfiler>>>, name: F) -> Self
+    where
+        F: FnOnce() -> String,
+    {
+        let gas_profiler = gas_profiler.map(|gas_profiler| {
+            gas_profiler.borrow_mut().enter_frame(name());
+            Rc::clone(gas_profiler)
+        });
+        GasProfileFrame(gas_profiler)
+    }
+}
+
+impl Drop for GasProfileFrame {
+    fn drop(&mut self) {
+        if let Some(gas_profiler) = &self.0 {
+            gas_profiler.borrow_mut().exit_frame();
+        }
+    }
+}
+
+/// Returns the module to instantiate for executing the given preprocessed module in the current
+/// call frame.
+///
+/// If gas profiling is enabled, this is a copy of the module passing call site indices to the
+/// `gas` host function, as the gas profiler expects.
+pub(crate) fn module_to_execute(
+    gas_profiler: Option<&Rc<RefCell<GasProfiler>>>,
+    module: &Module,
+    wasm_config: &WasmConfig,
+) -> Module {
+    let gas_profiler = match gas_profiler {
+        Some(gas_profiler) => gas_profiler,
+        None => return module.clone(),
+    };
+    match opcode_attribution::profiled_module(module, &wasm_config.opcode_costs()) {
+        Some((profiled_module, metered_blocks)) => {
+            if let Some(call_frame) = gas_profiler.borrow_mut().call_frames.last_mut() {
+                call_frame.metered_blocks = Some(metered_blocks);
+                return profiled_module;
+            }
+            module.clone()
+        }
+        None => module.clone(),
+    }
+}
+
+/// Returns the name of the outermost call frame executed in the given phase.
+pub(crate) fn phase_frame_name(phase: Phase) -> String {
+    match phase {
+        Phase::System => "system",
+        Phase::Payment => "payment",
+        Phase::Session => "session",
+        Phase::FinalizePayment => "finalize_payment",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_fold_stacks_of_nested_frames() {
+        let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+        {
+            let _session = GasProfileFrame::enter(Some(&gas_profiler), || "session".to_string());
+            let mut categories = BTreeMap::new();
+            categories.insert(OpcodeCategory::Add, 20);
+            categories.insert(OpcodeCategory::Nop, 10);
+            gas_profiler.borrow_mut().record(
+                GasCharge::Opcodes(Some(categories)),
+                Gas::new(30.into()),
+                Gas::new(30.into()),
+            );
+            {
+                let _contract =
+                    GasProfileFrame::enter(Some(&gas_profiler), || "contract;call".to_string());
+                gas_profiler
+                    .borrow_mut()
+                    .set_host_function("host_function_write");
+                gas_profiler.borrow_mut().record(
+                    GasCharge::HostFunction,
+                    Gas::new(100.into()),
+                    Gas::new(100.into()),
+                );
+                gas_profiler.borrow_mut().record(
+                    GasCharge::Storage { bytes: 5 },
+                    Gas::new(50.into()),
+                    Gas::new(50.into()),
+                );
+                gas_profiler.borrow_mut().record(
+                    GasCharge::HostFunction,
+                    Gas::new(100.into()),
+                    Gas::new(100.into()),
+                );
+            }
+            gas_profiler.borrow_mut().record(
+                GasCharge::Opcodes(Some(BTreeMap::new())),
+                Gas::new(40.into()),
+                Gas::new(7.into()),
+            );
+        }
+        let gas_profile = gas_profiler.borrow_mut().take_profile();
+
+        assert_eq!(gas_profile.frames().len(), 2);
+        assert_eq!(
+            gas_profile.frames()[1].stack(),
+            ["session", "contract_call"]
+        );
+        assert_eq!(
+            gas_profile.frames()[1].storage_charges(),
+            [StorageCharge {
+                bytes: 5,
+                cost: Gas::new(50.into())
+            }]
+        );
+        assert_eq!(gas_profile.total_cost(), Gas::new(287.into()));
+        assert_eq!(
+            gas_profile.folded_stacks(),
+            "session;contract_call;host_function_write 200\n\
+             session;contract_call;storage 50\n\
+             session;opcodes;add 20\n\
+             session;opcodes;nop 10\n\
+             session;opcodes;unattributed 7\n"
+        );
+    }
+
+    #[test]
+    fn should_translate_call_site_indices_to_metered_block_costs() {
+        let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+        let _session = GasProfileFrame::enter(Some(&gas_profiler), || "session".to_string());
+        let mut categories = BTreeMap::new();
+        categories.insert(OpcodeCategory::Const, 110);
+        gas_profiler.borrow_mut().call_frames[0].metered_blocks = Some(vec![MeteredBlockCost {
+            cost: 110,
+            categories: Some(categories.clone()),
+        }]);
+
+        let mut gas_profiler = gas_profiler.borrow_mut();
+        assert_eq!(
+            gas_profiler.wasm_gas_charge(0),
+            Some((Gas::new(110.into()), GasCharge::Opcodes(Some(categories))))
+        );
+        assert_eq!(gas_profiler.wasm_gas_charge(DYNAMIC_GAS_MARKER), None);
+        assert_eq!(
+            gas_profiler.wasm_gas_charge(240_000),
+            Some((Gas::new(240_000.into()), GasCharge::GrowMemory))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Support for enumerating the items of a dictionary one page at a time.
+use casper_hashing::Digest;
+use casper_types::{Key, StoredValue, URef};
+
+use crate::core::runtime_context::dictionary::DictionaryIndex;
+
+/// Returns the key under which the index of the item keys of the dictionary with the given seed
+/// [`URef`] is stored.
+pub fn dictionary_index_key(seed_uref: URef) -> Key {
+    Key::dictionary(seed_uref, &DictionaryIndex::root_item_key())
+}
+
+/// Represents a request to obtain a page of the items of a dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetDictionaryItemsRequest {
+    state_hash: Digest,
+    seed_uref: URef,
+    start_after: Option<String>,
+    limit: usize,
+}
+
+impl GetDictionaryItemsRequest {
+    /// Creates new request.
+    ///
+    /// A `limit` of zero is raised to one, so that a page of items is never empty unless there are
+    /// no more items.
+    pub fn new(
+        state_hash: Digest,
+        seed_uref: URef,
+        start_after: Option<String>,
+        limit: usize,
+    ) -> Self {
+        GetDictionaryItemsRequest {
+            state_hash,
+            seed_uref,
+            start_after,
+            limit: limit.max(1),
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns the seed [`URef`] of the dictionary.
+    pub fn seed_uref(&self) -> URef {
+        self.seed_uref
+    }
+
+    /// Returns the item key after which to start enumerating, if any.
+    pub fn start_after(&self) -> Option<&str> {
+        self.start_after.as_deref()
+    }
+
+    /// Returns the maximum number of items to return.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// Represents a result of a `get_dictionary_items` request.
+#[derive(Debug)]
+pub enum GetDictionaryItemsResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// The dictionary has no index of its item keys, either because it doesn't exist or because
+    /// it was created before dictionaries were indexed.
+    NotEnumerable,
+    /// Contains a page of items returned from the global state.
+    Success {
+        /// The item keys in ascending order, with the keys and values they are stored under.
+        items: Vec<(String, Key, StoredValue)>,
+        /// The item key to start after for the next page, if there are more items.
+        next_cursor: Option<String>,
+    },
+}
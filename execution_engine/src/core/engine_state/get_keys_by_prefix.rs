@@ -0,0 +1,96 @@
+//! Support for enumerating the keys in global state one page at a time.
+use casper_hashing::Digest;
+use casper_types::{Key, KeyTag, StoredValue, URef};
+
+/// The keys to enumerate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyPrefix {
+    /// All keys of the given variant.
+    Tag(KeyTag),
+    /// All dictionary items of the dictionary with the given seed [`URef`].
+    ///
+    /// Dictionary keys are hashes, so this scans every `Key::Dictionary` and pages may hold fewer
+    /// items than the limit even when more follow.
+    DictionaryItems(URef),
+}
+
+impl KeyPrefix {
+    /// Returns the prefix of the serialized keys to enumerate.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            KeyPrefix::Tag(key_tag) => vec![*key_tag as u8],
+            KeyPrefix::DictionaryItems(_) => vec![KeyTag::Dictionary as u8],
+        }
+    }
+}
+
+/// Represents a request to obtain a page of the keys in global state matching a prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetKeysByPrefixRequest {
+    state_hash: Digest,
+    prefix: KeyPrefix,
+    start_after: Option<Key>,
+    limit: usize,
+    include_values: bool,
+}
+
+impl GetKeysByPrefixRequest {
+    /// Creates new request.
+    ///
+    /// A `limit` of zero is raised to one, so that a page is never empty and without a cursor
+    /// unless there are no more keys.
+    pub fn new(
+        state_hash: Digest,
+        prefix: KeyPrefix,
+        start_after: Option<Key>,
+        limit: usize,
+        include_values: bool,
+    ) -> Self {
+        GetKeysByPrefixRequest {
+            state_hash,
+            prefix,
+            start_after,
+            limit: limit.max(1),
+            include_values,
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns the prefix of the keys to enumerate.
+    pub fn prefix(&self) -> &KeyPrefix {
+        &self.prefix
+    }
+
+    /// Returns the key after which to start enumerating, if any.
+    pub fn start_after(&self) -> Option<&Key> {
+        self.start_after.as_ref()
+    }
+
+    /// Returns the maximum number of keys to scan.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns `true` if the values stored under the keys should be returned as well.
+    pub fn include_values(&self) -> bool {
+        self.include_values
+    }
+}
+
+/// Represents a result of a `get_keys_by_prefix` request.
+#[derive(Debug)]
+pub enum GetKeysByPrefixResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// Contains a page of keys returned from the global state.
+    Success {
+        /// The keys, in ascending order of their serialized bytes, with their values if requested.
+        entries: Vec<(Key, Option<StoredValue>)>,
+        /// The key to start after for the next page, if there are more keys to scan.
+        next_cursor: Option<Key>,
+    },
+}
@@ -8,8 +8,12 @@ pub mod executable_deploy_item;
 pub mod execute_request;
 pub mod execution_effect;
 pub mod execution_result;
+pub mod execution_trace;
+pub mod gas_profile;
 pub mod genesis;
 pub mod get_bids;
+pub mod get_dictionary_items;
+pub mod get_keys_by_prefix;
 pub mod op;
 pub mod query;
 pub mod run_genesis_request;
@@ -33,7 +37,7 @@ use tracing::{debug, error};
 
 use casper_hashing::Digest;
 use casper_types::{
-    account::{Account, AccountHash},
+    account::{self, Account, AccountHash},
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::{
@@ -61,8 +65,12 @@ pub use self::{
     execute_request::ExecuteRequest,
     execution::Error as ExecError,
     execution_result::{ExecutionResult, ExecutionResults, ForcedTransferResult},
+    execution_trace::ExecutionTrace,
+    gas_profile::GasProfile,
     genesis::{ExecConfig, GenesisAccount, GenesisSuccess, SystemContractRegistry},
     get_bids::{GetBidsRequest, GetBidsResult},
+    get_dictionary_items::{GetDictionaryItemsRequest, GetDictionaryItemsResult},
+    get_keys_by_prefix::{GetKeysByPrefixRequest, GetKeysByPrefixResult, KeyPrefix},
     query::{QueryRequest, QueryResult},
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     system_contract_cache::SystemContractCache,
@@ -74,10 +82,13 @@ use crate::{
         engine_state::{
             executable_deploy_item::DeployKind,
             execution_result::ExecutionResultBuilder,
+            execution_trace::ExecutionTracer,
+            gas_profile::GasProfiler,
             genesis::GenesisInstaller,
             upgrade::{ProtocolUpgradeError, SystemUpgrader},
         },
         execution::{self, DirectSystemContractCall, Executor},
+        runtime_context::dictionary::{self, DictionaryIndex, DictionaryValue, IndexNode},
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
     shared::{
@@ -85,7 +96,7 @@ use crate::{
         wasm_prep::Preprocessor,
     },
     storage::{
-        global_state::{lmdb::LmdbGlobalState, StateProvider},
+        global_state::{lmdb::LmdbGlobalState, StateProvider, StateReader},
         trie::Trie,
     },
 };
@@ -103,6 +114,28 @@ pub static MAX_PAYMENT: Lazy<U512> = Lazy::new(|| U512::from(MAX_PAYMENT_AMOUNT)
 /// pay.
 pub const WASMLESS_TRANSFER_FIXED_GAS_PRICE: u64 = 1;
 
+/// The first protocol version in which deploy nonces are checked and recorded under
+/// [`account_nonce_key`].  Under earlier versions, a deploy's nonce is ignored.
+pub const DEPLOY_NONCES_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::from_parts(1, 5, 0);
+
+/// The prefix hashed along with an account hash to derive the address of the account's nonce.
+const ACCOUNT_NONCE_KEY_PREFIX: &[u8] = b"account-nonce";
+
+/// Returns the key under which the nonce of the latest deploy executed by the given account is
+/// recorded.
+///
+/// This is a [`Key::Hash`] whose address is derived from the account hash, so no contract can be
+/// stored under it.
+pub fn account_nonce_key(account_hash: AccountHash) -> Key {
+    let mut input = ACCOUNT_NONCE_KEY_PREFIX.to_vec();
+    input.extend_from_slice(account_hash.as_bytes());
+    Key::Hash(account::blake2b(input))
+}
+
+/// The first protocol version in which new dictionaries get an index of their item keys, which is
+/// updated by `dictionary_put`.  Under earlier versions, dictionaries are not indexed.
+pub const DICTIONARY_INDEX_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::from_parts(1, 5, 0);
+
 /// Main implementation of an execution engine state.
 ///
 /// Takes an engine's configuration and a provider of a state (aka the global state) to operate on.
@@ -480,26 +513,8 @@ where
         let mut results = ExecutionResults::with_capacity(deploys.len());
 
         for deploy_item in deploys {
-            let result = match deploy_item.session {
-                ExecutableDeployItem::Transfer { .. } => self.transfer(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-                _ => self.deploy(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-            };
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item);
             match result {
                 Ok(result) => results.push_back(result),
                 Err(error) => {
@@ -511,6 +526,95 @@ where
         Ok(results)
     }
 
+    /// Runs a deploy execution request like [`EngineState::run_execute`], recording a trace of
+    /// the execution of each deploy.
+    ///
+    /// Returns execution results and traces, in the order of the deploys in the request.
+    pub fn run_execute_with_execution_traces(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<(ExecutionResults, Vec<ExecutionTrace>), Error> {
+        let deploys = exec_request.take_deploys();
+        let mut results = ExecutionResults::with_capacity(deploys.len());
+        let mut traces = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let execution_tracer = Rc::new(RefCell::new(ExecutionTracer::default()));
+            let executor =
+                Executor::new(*self.config()).with_execution_tracer(Rc::clone(&execution_tracer));
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item)?;
+            results.push_back(result);
+            traces.push(execution_tracer.borrow_mut().take_trace());
+        }
+
+        Ok((results, traces))
+    }
+
+    /// Executes a single deploy of a deploy execution request, taking the shortcut for a native
+    /// transfer.
+    fn execute_deploy_item(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        exec_request: &ExecuteRequest,
+        deploy_item: DeployItem,
+    ) -> Result<ExecutionResult, Error> {
+        match deploy_item.session {
+            ExecutableDeployItem::Transfer { .. } => self.transfer(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+            _ => self.deploy(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+        }
+    }
+
+    /// Executes a deploy, recording the gas charged in each call frame of the execution.
+    ///
+    /// The execution result, including the gas cost, is the same as that of
+    /// [`EngineState::deploy`], although the Wasm executed is instrumented differently to attribute
+    /// the gas charged to opcode categories.  The returned profile records every charge made in
+    /// each call frame, and is deterministic for a given prestate.  The gas charged by modules
+    /// which import the `gas` host function themselves is not attributed to opcode categories.
+    #[allow(clippy::too_many_arguments)]
+    pub fn deploy_with_gas_profile(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Digest,
+        blocktime: BlockTime,
+        deploy_item: DeployItem,
+        proposer: PublicKey,
+    ) -> Result<(ExecutionResult, GasProfile), Error> {
+        let gas_profiler = Rc::new(RefCell::new(GasProfiler::default()));
+        let executor = Executor::new(*self.config()).with_gas_profiler(Rc::clone(&gas_profiler));
+        let execution_result = self.deploy(
+            correlation_id,
+            &executor,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            deploy_item,
+            proposer,
+        )?;
+        let gas_profile = gas_profiler.borrow_mut().take_profile();
+        Ok((execution_result, gas_profile))
+    }
+
     fn get_authorized_account(
         &self,
         correlation_id: CorrelationId,
@@ -541,6 +645,41 @@ where
         Ok(account)
     }
 
+    /// Checks that `maybe_nonce`, if provided, directly follows the latest nonce recorded for the
+    /// account, and returns the global state write which records it as the new latest nonce.
+    ///
+    /// Nonces are ignored under protocol versions earlier than [`DEPLOY_NONCES_PROTOCOL_VERSION`].
+    fn get_nonce_update(
+        &self,
+        correlation_id: CorrelationId,
+        protocol_version: ProtocolVersion,
+        account_hash: AccountHash,
+        maybe_nonce: Option<u64>,
+        tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
+    ) -> Result<Option<(Key, StoredValue)>, Error> {
+        let nonce = match maybe_nonce {
+            Some(nonce) if protocol_version >= DEPLOY_NONCES_PROTOCOL_VERSION => nonce,
+            Some(_) | None => return Ok(None),
+        };
+
+        let latest_nonce = tracking_copy
+            .borrow_mut()
+            .get_nonce(correlation_id, account_hash)?;
+        let expected = latest_nonce.saturating_add(1);
+        if nonce != expected {
+            return Err(Error::InvalidNonce {
+                expected,
+                got: nonce,
+            });
+        }
+
+        let cl_value = CLValue::from_t(nonce).map_err(execution::Error::from)?;
+        Ok(Some((
+            account_nonce_key(account_hash),
+            StoredValue::CLValue(cl_value),
+        )))
+    }
+
     /// Get the balance of a passed purse referenced by its [`URef`].
     pub fn get_purse_balance(
         &self,
@@ -752,6 +891,17 @@ where
         // All wasmless transfer preconditions are met.
         // Any error that occurs in logic below this point would result in a charge for user error.
 
+        let nonce_update = match self.get_nonce_update(
+            correlation_id,
+            protocol_version,
+            account_public_key,
+            deploy_item.nonce,
+            Rc::clone(&tracking_copy),
+        ) {
+            Ok(nonce_update) => nonce_update,
+            Err(error) => return Ok(make_charged_execution_failure(error)),
+        };
+
         let mut runtime_args_builder =
             TransferRuntimeArgsBuilder::new(deploy_item.session.args().clone());
 
@@ -1078,6 +1228,11 @@ where
             let tc = tracking_copy.borrow();
             let finalization_tc = Rc::new(RefCell::new(tc.fork()));
 
+            // The nonce is consumed even if the transfer itself failed.
+            if let Some((nonce_key, nonce_value)) = nonce_update {
+                finalization_tc.borrow_mut().write(nonce_key, nonce_value);
+            }
+
             let finalize_payment_call_stack = {
                 let system = CallStackElement::session(PublicKey::System.to_account_hash());
                 let handle_payment = CallStackElement::stored_contract(
@@ -1499,6 +1654,40 @@ where
             }
         };
 
+        // A deploy whose nonce doesn't directly follow the latest one used by the account is
+        // charged as though its payment code had failed.
+        let nonce_update = match self.get_nonce_update(
+            correlation_id,
+            protocol_version,
+            account.account_hash(),
+            deploy_item.nonce,
+            Rc::clone(&tracking_copy),
+        ) {
+            Ok(nonce_update) => nonce_update,
+            Err(error) => {
+                let gas_cost = match Gas::from_motes(max_payment_cost, deploy_item.gas_price) {
+                    Some(gas) => gas,
+                    None => {
+                        return Ok(ExecutionResult::precondition_failure(
+                            Error::GasConversionOverflow,
+                        ))
+                    }
+                };
+
+                match ExecutionResult::new_payment_code_error(
+                    error,
+                    max_payment_cost,
+                    account_main_purse_balance,
+                    gas_cost,
+                    account_main_purse_balance_key,
+                    proposer_main_purse_balance_key,
+                ) {
+                    Ok(execution_result) => return Ok(execution_result),
+                    Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
+                }
+            }
+        };
+
         // Transfer the contents of the rewards purse to block proposer
         execution_result_builder.set_payment_execution_result(payment_result);
 
@@ -1618,6 +1807,11 @@ where
             let post_session_tc = post_session_rc.borrow();
             let finalization_tc = Rc::new(RefCell::new(post_session_tc.fork()));
 
+            // The nonce is consumed even if session code failed.
+            if let Some((nonce_key, nonce_value)) = nonce_update {
+                finalization_tc.borrow_mut().write(nonce_key, nonce_value);
+            }
+
             let handle_payment_args = {
                 //((gas spent during payment code execution) + (gas spent during session code execution)) * gas_price
                 let finalize_cost_motes = match Motes::from_gas(
@@ -1787,6 +1981,22 @@ where
             .map_err(Error::from)
     }
 
+    /// Deletes all tries which are not reachable from the given state roots, returning the number
+    /// of deleted tries.
+    pub fn prune_trie_store(
+        &self,
+        correlation_id: CorrelationId,
+        retained_state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<usize, Error>
+    where
+        Error: From<S::Error>,
+    {
+        self.state
+            .prune_trie_store(correlation_id, retained_state_roots, batch_size)
+            .map_err(Error::from)
+    }
+
     /// Obtains validator weights for given era.
     pub fn get_era_validators(
         &self,
@@ -1919,6 +2129,165 @@ where
         Ok(GetBidsResult::Success { bids })
     }
 
+    /// Gets a page of the keys in global state matching a prefix, optionally with their values.
+    ///
+    /// At most [`GetKeysByPrefixRequest::limit`] keys are scanned, in ascending order of their
+    /// serialized bytes, starting after [`GetKeysByPrefixRequest::start_after`] if given.
+    pub fn get_keys_by_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        get_keys_by_prefix_request: GetKeysByPrefixRequest,
+    ) -> Result<GetKeysByPrefixResult, Error> {
+        let reader = match self
+            .state
+            .checkout(get_keys_by_prefix_request.state_hash())
+            .map_err(Into::into)?
+        {
+            Some(reader) => reader,
+            None => return Ok(GetKeysByPrefixResult::RootNotFound),
+        };
+
+        let limit = get_keys_by_prefix_request.limit();
+        let mut keys = reader
+            .keys_with_prefix_after(
+                correlation_id,
+                &get_keys_by_prefix_request.prefix().to_bytes(),
+                get_keys_by_prefix_request.start_after(),
+                limit.saturating_add(1),
+            )
+            .map_err(Into::into)?;
+        let next_cursor = if keys.len() > limit {
+            keys.truncate(limit);
+            keys.last().copied()
+        } else {
+            None
+        };
+
+        let seed_uref = match get_keys_by_prefix_request.prefix() {
+            KeyPrefix::Tag(_) => None,
+            KeyPrefix::DictionaryItems(seed_uref) => Some(seed_uref),
+        };
+        let mut entries = Vec::with_capacity(keys.len());
+        for key in keys {
+            if seed_uref.is_none() && !get_keys_by_prefix_request.include_values() {
+                entries.push((key, None));
+                continue;
+            }
+            let stored_value = match reader.read(correlation_id, &key).map_err(Into::into)? {
+                Some(stored_value) => stored_value,
+                None => continue,
+            };
+            if let Some(seed_uref) = seed_uref {
+                // Skip anything stored under a `Key::Dictionary` which isn't an item of the
+                // requested dictionary, rather than failing the whole page.
+                let dictionary_value = match &stored_value {
+                    StoredValue::CLValue(cl_value) => {
+                        match cl_value.clone().into_t::<DictionaryValue>() {
+                            Ok(dictionary_value) => dictionary_value,
+                            Err(_) => continue,
+                        }
+                    }
+                    _ => continue,
+                };
+                if dictionary_value.seed_uref_addr() != &seed_uref.addr()[..]
+                    || DictionaryIndex::is_index_item_key(
+                        dictionary_value.dictionary_item_key_bytes(),
+                    )
+                {
+                    continue;
+                }
+            }
+            let value = if get_keys_by_prefix_request.include_values() {
+                let value = dictionary::handle_stored_value(key, stored_value)
+                    .map_err(|error| Error::Exec(error.into()))?;
+                Some(value)
+            } else {
+                None
+            };
+            entries.push((key, value));
+        }
+
+        Ok(GetKeysByPrefixResult::Success {
+            entries,
+            next_cursor,
+        })
+    }
+
+    /// Gets a page of the items of a dictionary, in ascending order of their item keys.
+    ///
+    /// Only dictionaries holding an index of their item keys can be enumerated.
+    pub fn get_dictionary_items(
+        &self,
+        correlation_id: CorrelationId,
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+    ) -> Result<GetDictionaryItemsResult, Error> {
+        let reader = match self
+            .state
+            .checkout(get_dictionary_items_request.state_hash())
+            .map_err(Into::into)?
+        {
+            Some(reader) => reader,
+            None => return Ok(GetDictionaryItemsResult::RootNotFound),
+        };
+
+        let seed_uref = get_dictionary_items_request.seed_uref();
+        let read_cl_value = |item_key_bytes: &[u8]| -> Result<Option<CLValue>, Error> {
+            let key = Key::dictionary(seed_uref, item_key_bytes);
+            let stored_value = match reader.read(correlation_id, &key).map_err(Into::into)? {
+                Some(stored_value) => stored_value,
+                None => return Ok(None),
+            };
+            let cl_value_indirect = CLValue::try_from(stored_value)
+                .map_err(|error| Error::Exec(execution::Error::TypeMismatch(error)))?;
+            let dictionary_value: DictionaryValue = cl_value_indirect
+                .into_t()
+                .map_err(|error| Error::Exec(error.into()))?;
+            Ok(Some(dictionary_value.into_cl_value()))
+        };
+
+        let dictionary_index: DictionaryIndex =
+            match read_cl_value(&DictionaryIndex::root_item_key())? {
+                Some(cl_value) => cl_value
+                    .into_t()
+                    .map_err(|error| Error::Exec(error.into()))?,
+                None => return Ok(GetDictionaryItemsResult::NotEnumerable),
+            };
+
+        let limit = get_dictionary_items_request.limit();
+        let mut item_keys = dictionary_index.item_keys_after(
+            get_dictionary_items_request.start_after(),
+            limit.saturating_add(1),
+            |node_id| {
+                let node_item_key = DictionaryIndex::node_item_key(node_id);
+                match read_cl_value(&node_item_key)? {
+                    Some(cl_value) => cl_value
+                        .into_t::<IndexNode>()
+                        .map_err(|error| Error::Exec(error.into())),
+                    None => Err(Error::Exec(execution::Error::KeyNotFound(Key::dictionary(
+                        seed_uref,
+                        &node_item_key,
+                    )))),
+                }
+            },
+        )?;
+        let next_cursor = if item_keys.len() > limit {
+            item_keys.truncate(limit);
+            item_keys.last().cloned()
+        } else {
+            None
+        };
+
+        let mut items = Vec::with_capacity(item_keys.len());
+        for item_key in item_keys {
+            let key = Key::dictionary(seed_uref, item_key.as_bytes());
+            if let Some(cl_value) = read_cl_value(item_key.as_bytes())? {
+                items.push((item_key, key, StoredValue::CLValue(cl_value)));
+            }
+        }
+
+        Ok(GetDictionaryItemsResult::Success { items, next_cursor })
+    }
+
     /// Executes a step request.
     pub fn commit_step(
         &self,
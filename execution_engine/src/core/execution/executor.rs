@@ -16,8 +16,12 @@ use casper_types::{
 use crate::{
     core::{
         engine_state::{
-            execution_effect::ExecutionEffect, execution_result::ExecutionResult,
-            system_contract_cache::SystemContractCache, EngineConfig,
+            execution_effect::ExecutionEffect,
+            execution_result::ExecutionResult,
+            execution_trace::{ExecutionTracer, TraceCallFrame},
+            gas_profile::{self, GasProfileFrame, GasProfiler},
+            system_contract_cache::SystemContractCache,
+            EngineConfig,
         },
         execution::{address_generator::AddressGenerator, Error},
         runtime::{extract_access_rights_from_keys, instance_and_memory, Runtime},
@@ -74,13 +78,34 @@ macro_rules! on_fail_charge {
 /// Executor object deals with execution of WASM modules.
 pub struct Executor {
     config: EngineConfig,
+    gas_profiler: Option<Rc<RefCell<GasProfiler>>>,
+    execution_tracer: Option<Rc<RefCell<ExecutionTracer>>>,
 }
 
 #[allow(clippy::too_many_arguments)]
 impl Executor {
     /// Creates new executor object.
     pub fn new(config: EngineConfig) -> Self {
-        Executor { config }
+        Executor {
+            config,
+            gas_profiler: None,
+            execution_tracer: None,
+        }
+    }
+
+    /// Records the gas charged during execution in the given gas profiler.
+    pub(crate) fn with_gas_profiler(mut self, gas_profiler: Rc<RefCell<GasProfiler>>) -> Self {
+        self.gas_profiler = Some(gas_profiler);
+        self
+    }
+
+    /// Records a trace of the execution in the given execution tracer.
+    pub(crate) fn with_execution_tracer(
+        mut self,
+        execution_tracer: Rc<RefCell<ExecutionTracer>>,
+    ) -> Self {
+        self.execution_tracer = Some(execution_tracer);
+        self
     }
 
     /// Returns config.
@@ -117,12 +142,26 @@ impl Executor {
         R: StateReader<Key, StoredValue>,
         R::Error: Into<Error>,
     {
+        let _gas_profile_frame = GasProfileFrame::enter(self.gas_profiler.as_ref(), || {
+            gas_profile::phase_frame_name(phase)
+        });
+        let _trace_call_frame = TraceCallFrame::enter(
+            self.execution_tracer.as_ref(),
+            || gas_profile::phase_frame_name(phase),
+            &args,
+        );
+
         let entry_point_name = entry_point.name();
         let entry_point_type = entry_point.entry_point_type();
         let entry_point_access = entry_point.access();
 
+        let module_to_execute = gas_profile::module_to_execute(
+            self.gas_profiler.as_ref(),
+            &module,
+            self.config.wasm_config(),
+        );
         let (instance, memory) = on_fail_charge!(instance_and_memory(
-            module.clone(),
+            module_to_execute,
             protocol_version,
             self.config.wasm_config()
         ));
@@ -172,7 +211,9 @@ impl Executor {
             phase,
             self.config,
             transfers,
-        );
+        )
+        .with_gas_profiler(self.gas_profiler.clone())
+        .with_execution_tracer(self.execution_tracer.clone());
 
         let mut runtime = Runtime::new(
             self.config,
@@ -309,6 +350,15 @@ impl Executor {
         R: StateReader<Key, StoredValue>,
         R::Error: Into<Error>,
     {
+        let _gas_profile_frame = GasProfileFrame::enter(self.gas_profiler.as_ref(), || {
+            gas_profile::phase_frame_name(phase)
+        });
+        let _trace_call_frame = TraceCallFrame::enter(
+            self.execution_tracer.as_ref(),
+            || gas_profile::phase_frame_name(phase),
+            &payment_args,
+        );
+
         // use host side standard payment
         let hash_address_generator = {
             let generator = AddressGenerator::new(deploy_hash.as_bytes(), phase);
@@ -404,6 +454,15 @@ impl Executor {
         R::Error: Into<Error>,
         T: FromBytes + CLTyped,
     {
+        let _gas_profile_frame = GasProfileFrame::enter(self.gas_profiler.as_ref(), || {
+            gas_profile::phase_frame_name(phase)
+        });
+        let _trace_call_frame = TraceCallFrame::enter(
+            self.execution_tracer.as_ref(),
+            || gas_profile::phase_frame_name(phase),
+            &runtime_args,
+        );
+
         // TODO See if these panics can be removed.
         let system_contract_registry = tracking_copy
             .borrow_mut()
@@ -561,6 +620,15 @@ impl Executor {
         R::Error: Into<Error>,
         T: FromBytes + CLTyped,
     {
+        let _gas_profile_frame = GasProfileFrame::enter(self.gas_profiler.as_ref(), || {
+            gas_profile::phase_frame_name(phase)
+        });
+        let _trace_call_frame = TraceCallFrame::enter(
+            self.execution_tracer.as_ref(),
+            || gas_profile::phase_frame_name(phase),
+            &args,
+        );
+
         let mut named_keys: NamedKeys = account.named_keys().clone();
         let base_key = account.account_hash().into();
 
@@ -683,10 +751,20 @@ impl Executor {
             phase,
             self.config,
             transfers,
+        )
+        .with_gas_profiler(self.gas_profiler.clone())
+        .with_execution_tracer(self.execution_tracer.clone());
+
+        let module_to_execute = gas_profile::module_to_execute(
+            self.gas_profiler.as_ref(),
+            &module,
+            self.config.wasm_config(),
         );
-
-        let (instance, memory) =
-            instance_and_memory(module.clone(), protocol_version, self.config.wasm_config())?;
+        let (instance, memory) = instance_and_memory(
+            module_to_execute,
+            protocol_version,
+            self.config.wasm_config(),
+        )?;
 
         let runtime = Runtime::new(
             self.config,
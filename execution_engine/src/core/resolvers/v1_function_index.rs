@@ -56,6 +56,8 @@ pub(crate) enum FunctionIndex {
     DictionaryGetFuncIndex,
     DictionaryPutFuncIndex,
     LoadCallStack,
+    EmitEventFuncIndex,
+    DictionaryIterFuncIndex,
 }
 
 impl From<FunctionIndex> for usize {
@@ -9,13 +9,19 @@ use casper_types::{
     bytesrepr::{self, ToBytes},
     contracts::{ContractPackageStatus, EntryPoints, NamedKeys},
     system::auction::EraInfo,
-    ContractHash, ContractPackageHash, ContractVersion, EraId, Gas, Group, Key, StoredValue, URef,
-    U512,
+    ContractHash, ContractPackageHash, ContractVersion, EraId, Group, Key, StoredValue, URef, U512,
 };
 
-use super::{args::Args, scoped_instrumenter::ScopedInstrumenter, Error, Runtime};
+use super::{
+    args::Args,
+    scoped_instrumenter::{self, ScopedInstrumenter},
+    Error, Runtime,
+};
 use crate::{
-    core::resolvers::v1_function_index::FunctionIndex,
+    core::{
+        engine_state::execution_trace::{TraceEvent, WasmValue},
+        resolvers::v1_function_index::FunctionIndex,
+    },
     shared::host_function_costs::{Cost, HostFunction, DEFAULT_HOST_FUNCTION_NEW_DICTIONARY},
     storage::global_state::StateReader,
 };
@@ -31,6 +37,41 @@ where
         args: RuntimeArgs,
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
+        let host_function = match scoped_instrumenter::host_function_name(func) {
+            Some(host_function) => host_function,
+            None => return self.invoke_host_function(func, args),
+        };
+
+        if let Some(gas_profiler) = self.context.gas_profiler() {
+            gas_profiler.borrow_mut().set_host_function(host_function);
+        }
+
+        if self.context.execution_tracer().is_none() {
+            return self.invoke_host_function(func, args);
+        }
+        let traced_args: Vec<WasmValue> =
+            args.as_ref().iter().copied().map(WasmValue::from).collect();
+        let result = self.invoke_host_function(func, args);
+        self.context.trace(|| TraceEvent::HostFunctionCalled {
+            name: host_function.to_string(),
+            args: traced_args,
+            result: result.as_ref().ok().copied().flatten().map(WasmValue::from),
+            error: result.as_ref().err().map(Trap::to_string),
+        });
+        result
+    }
+}
+
+impl<'a, R> Runtime<'a, R>
+where
+    R: StateReader<Key, StoredValue>,
+    R::Error: Into<Error>,
+{
+    fn invoke_host_function(
+        &mut self,
+        func: FunctionIndex,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
         let mut scoped_instrumenter = ScopedInstrumenter::new(func);
 
         let host_function_costs = self.config.wasm_config().take_host_function_costs();
@@ -201,7 +242,7 @@ where
                 let (gas_arg,): (u32,) = Args::parse(args)?;
                 // Gas is special cased internal host function and for accounting purposes it isn't
                 // represented in protocol data.
-                self.gas(Gas::new(gas_arg.into()))?;
+                self.charge_wasm_gas(gas_arg)?;
                 Ok(None)
             }
 
@@ -1040,6 +1081,60 @@ where
                 let ret = self.load_call_stack(call_stack_len_ptr, result_size_ptr)?;
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
             }
+            FunctionIndex::EmitEventFuncIndex => {
+                // args(0) = pointer to topic bytes in Wasm memory
+                // args(1) = size of topic bytes in Wasm memory
+                // args(2) = pointer to serialized payload in Wasm memory
+                // args(3) = size of serialized payload in Wasm memory
+                let (topic_ptr, topic_size, payload_ptr, payload_size): (_, u32, _, u32) =
+                    Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_event,
+                    [topic_ptr, topic_size, payload_ptr, payload_size],
+                )?;
+                scoped_instrumenter.add_property("topic_size", topic_size);
+                scoped_instrumenter.add_property("payload_size", payload_size);
+                let ret = self.emit_event(topic_ptr, topic_size, payload_ptr, payload_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+            FunctionIndex::DictionaryIterFuncIndex => {
+                // args(0) = pointer to uref in Wasm memory
+                // args(1) = size of uref in Wasm memory
+                // args(2) = pointer to serialized cursor in Wasm memory
+                // args(3) = size of serialized cursor in Wasm memory
+                // args(4) = maximum number of item keys to return
+                // args(5) = pointer to output size (output param)
+                let (uref_ptr, uref_size, cursor_ptr, cursor_size, limit, output_size_ptr): (
+                    _,
+                    u32,
+                    _,
+                    u32,
+                    u32,
+                    _,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.dictionary_iter,
+                    [
+                        uref_ptr,
+                        uref_size,
+                        cursor_ptr,
+                        cursor_size,
+                        limit,
+                        output_size_ptr,
+                    ],
+                )?;
+                scoped_instrumenter.add_property("cursor_size", cursor_size);
+                scoped_instrumenter.add_property("limit", limit);
+                let ret = self.dictionary_iter(
+                    uref_ptr,
+                    uref_size,
+                    cursor_ptr,
+                    cursor_size,
+                    limit,
+                    output_size_ptr,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
         }
     }
 }
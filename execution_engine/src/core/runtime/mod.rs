@@ -37,12 +37,18 @@ use casper_types::{
     AccessRights, ApiError, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash,
     ContractVersionKey, ContractWasm, DeployHash, EntryPointType, EraId, Gas, Key, NamedArg,
     Parameter, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, Transfer,
-    TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U128, U256, U512,
+    TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, EVENT_TOPIC_MAX_LENGTH,
+    U128, U256, U512,
 };
 
 use crate::{
     core::{
-        engine_state::{system_contract_cache::SystemContractCache, EngineConfig},
+        engine_state::{
+            execution_trace::TraceCallFrame,
+            gas_profile::{self, GasCharge, GasProfileFrame},
+            system_contract_cache::SystemContractCache,
+            EngineConfig,
+        },
         execution::{self, Error},
         resolvers::{create_module_resolver, memory_resolver::MemoryResolver},
         runtime::scoped_instrumenter::ScopedInstrumenter,
@@ -1017,6 +1023,18 @@ where
         self.context.charge_gas(amount)
     }
 
+    /// Charges for a value passed to the `gas` host function by the instrumented Wasm code.
+    fn charge_wasm_gas(&mut self, value: u32) -> Result<(), Error> {
+        let maybe_charge = match self.context.gas_profiler() {
+            Some(gas_profiler) => gas_profiler.borrow_mut().wasm_gas_charge(value),
+            None => return self.gas(Gas::new(value.into())),
+        };
+        match maybe_charge {
+            Some((amount, charge)) => self.context.charge_gas_for(amount, charge),
+            None => Ok(()),
+        }
+    }
+
     /// Returns current gas counter.
     fn gas_counter(&self) -> Gas {
         self.context.gas_counter()
@@ -1456,7 +1474,9 @@ where
             phase,
             self.config,
             transfers,
-        );
+        )
+        .with_gas_profiler(self.context.gas_profiler().cloned())
+        .with_execution_tracer(self.context.execution_tracer().cloned());
 
         let mut mint_runtime = Runtime::new(
             self.config,
@@ -1603,7 +1623,9 @@ where
             phase,
             self.config,
             transfers,
-        );
+        )
+        .with_gas_profiler(self.context.gas_profiler().cloned())
+        .with_execution_tracer(self.context.execution_tracer().cloned());
 
         let mut runtime = Runtime::new(
             self.config,
@@ -1734,7 +1756,9 @@ where
             phase,
             self.config,
             transfers,
-        );
+        )
+        .with_gas_profiler(self.context.gas_profiler().cloned())
+        .with_execution_tracer(self.context.execution_tracer().cloned());
 
         let mut runtime = Runtime::new(
             self.config,
@@ -2098,6 +2122,18 @@ where
             });
         }
 
+        let call_frame_name = || {
+            format!(
+                "{}::{}",
+                contract_hash.to_formatted_string(),
+                entry_point.name()
+            )
+        };
+        let _gas_profile_frame =
+            GasProfileFrame::enter(self.context.gas_profiler(), call_frame_name);
+        let _trace_call_frame =
+            TraceCallFrame::enter(self.context.execution_tracer(), call_frame_name, &args);
+
         // TODO: should we be using named_keys_mut() instead?
         let mut named_keys = match entry_point.entry_point_type() {
             EntryPointType::Session => self.context.account().named_keys().clone(),
@@ -2190,8 +2226,16 @@ where
 
         let entry_point_name = entry_point.name();
 
-        let (instance, memory) =
-            instance_and_memory(module.clone(), protocol_version, self.config.wasm_config())?;
+        let module_to_execute = gas_profile::module_to_execute(
+            self.context.gas_profiler(),
+            &module,
+            self.config.wasm_config(),
+        );
+        let (instance, memory) = instance_and_memory(
+            module_to_execute,
+            protocol_version,
+            self.config.wasm_config(),
+        )?;
 
         let access_rights = {
             let mut keys: Vec<Key> = named_keys.values().cloned().collect();
@@ -2228,7 +2272,9 @@ where
             self.context.phase(),
             self.config,
             self.context.transfers().to_owned(),
-        );
+        )
+        .with_gas_profiler(self.context.gas_profiler().cloned())
+        .with_execution_tracer(self.context.execution_tracer().cloned());
 
         let mut call_stack = self.call_stack.to_owned();
 
@@ -2789,7 +2835,11 @@ where
 
     /// Reverts contract execution with a status specified.
     fn revert(&mut self, status: u32) -> Trap {
-        Error::Revert(status.into()).into()
+        let error = ApiError::from(status);
+        if let Some(execution_tracer) = self.context.execution_tracer() {
+            execution_tracer.borrow_mut().revert(error);
+        }
+        Error::Revert(error).into()
     }
 
     fn add_associated_key(
@@ -3534,7 +3584,7 @@ where
         T: AsRef<[Cost]> + Copy,
     {
         let cost = host_function.calculate_gas_cost(weights);
-        self.gas(cost)?;
+        self.context.charge_gas_for(cost, GasCharge::HostFunction)?;
         Ok(())
     }
 
@@ -3547,6 +3597,7 @@ where
 
         // Create new URef
         let new_uref = self.context.new_unit_uref()?;
+        self.context.init_dictionary_index(new_uref)?;
 
         // create CLValue for return value
         let new_uref_value = CLValue::from_t(new_uref)?;
@@ -3639,6 +3690,70 @@ where
         }
         Ok(Ok(()))
     }
+
+    /// Reads up to `limit` item keys of a dictionary, in ascending order after a cursor.
+    fn dictionary_iter(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        cursor_ptr: u32,
+        cursor_size: u32,
+        limit: u32,
+        output_size_ptr: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        // check we can write to the host buffer
+        if let Err(err) = self.check_host_buffer() {
+            return Ok(Err(err));
+        }
+
+        let uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        let cursor: Option<String> = self.t_from_mem(cursor_ptr, cursor_size)?;
+
+        let item_keys =
+            match self
+                .context
+                .dictionary_item_keys(uref, cursor.as_deref(), limit as usize)?
+            {
+                Some(item_keys) => item_keys,
+                None => return Ok(Err(ApiError::DictionaryNotEnumerable)),
+            };
+
+        let cl_value = CLValue::from_t(item_keys).map_err(Error::CLValue)?;
+        let value_size = cl_value.inner_bytes().len() as u32;
+        if let Err(error) = self.write_host_buffer(cl_value) {
+            return Ok(Err(error));
+        }
+
+        let value_bytes = value_size.to_le_bytes(); // Wasm is little-endian
+        if let Err(error) = self.memory.set(output_size_ptr, &value_bytes) {
+            return Err(Error::Interpreter(error.into()).into());
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Records an event with a topic and payload read from Wasm memory.
+    fn emit_event(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        payload_ptr: u32,
+        payload_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let topic_bytes = self.bytes_from_mem(topic_ptr, topic_size as usize)?;
+        if topic_bytes.len() > EVENT_TOPIC_MAX_LENGTH {
+            return Ok(Err(ApiError::EventTopicExceedsLength));
+        }
+        let topic = match String::from_utf8(topic_bytes) {
+            Ok(topic) if !topic.is_empty() => topic,
+            _ => return Ok(Err(ApiError::InvalidEventTopic)),
+        };
+        let payload = self.cl_value_from_mem(payload_ptr, payload_size)?;
+        if let Err(e) = self.context.emit_event(topic, payload) {
+            return Err(Trap::from(e));
+        }
+        Ok(Ok(()))
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,22 @@
 use casper_types::{
-    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    bytesrepr::{self, Bytes, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     CLType, CLTyped, CLValue, CLValueError, Key, StoredValue,
 };
 
+/// The maximum number of entries held by a node of a [`DictionaryIndex`].
+const INDEX_NODE_MAX_LENGTH: usize = 64;
+
+/// The first byte of the item keys under which a [`DictionaryIndex`] and its nodes are stored.
+///
+/// No UTF-8 string starts with this byte, so these can't collide with the item keys of the
+/// dictionary.
+const INDEX_ITEM_KEY_TAG: u8 = 0xff;
+
+/// The tag of a serialized [`IndexNode::Leaf`].
+const INDEX_LEAF_TAG: u8 = 0;
+/// The tag of a serialized [`IndexNode::Internal`].
+const INDEX_INTERNAL_TAG: u8 = 1;
+
 /// Wraps a [`CLValue`] for storage in a dictionary.
 ///
 /// Note that we include the dictionary [`casper_types::URef`] and key used to create the
@@ -19,6 +33,7 @@ pub struct DictionaryValue {
 }
 
 impl DictionaryValue {
+    /// Creates a new [`DictionaryValue`].
     pub fn new(
         cl_value: CLValue,
         seed_uref_addr: Vec<u8>,
@@ -35,6 +50,16 @@ impl DictionaryValue {
     pub fn into_cl_value(self) -> CLValue {
         self.cl_value
     }
+
+    /// Returns the address of the seed [`casper_types::URef`] of the dictionary holding this value.
+    pub fn seed_uref_addr(&self) -> &[u8] {
+        self.seed_uref_addr.as_ref()
+    }
+
+    /// Returns the item key under which this value is stored in its dictionary.
+    pub fn dictionary_item_key_bytes(&self) -> &[u8] {
+        self.dictionary_item_key_bytes.as_ref()
+    }
 }
 
 impl CLTyped for DictionaryValue {
@@ -73,6 +98,299 @@ impl ToBytes for DictionaryValue {
     }
 }
 
+/// An ordered index of the item keys of a dictionary.
+///
+/// The item keys are held in a B-tree of [`IndexNode`]s with at most [`INDEX_NODE_MAX_LENGTH`]
+/// entries each, stored as entries of the dictionary under [`DictionaryIndex::node_item_key`].
+/// The index itself is stored under [`DictionaryIndex::root_item_key`] and holds the id of the
+/// root node.  Dictionaries created before indexes were introduced have none.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DictionaryIndex {
+    root_node_id: Option<u32>,
+    next_node_id: u32,
+}
+
+/// A node of a [`DictionaryIndex`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexNode {
+    /// Item keys in ascending order.
+    Leaf(Vec<String>),
+    /// The first item key and id of each child node, in ascending order.
+    Internal(Vec<(String, u32)>),
+}
+
+impl DictionaryIndex {
+    /// Returns the item key under which the index of a dictionary is stored.
+    pub fn root_item_key() -> Vec<u8> {
+        vec![INDEX_ITEM_KEY_TAG]
+    }
+
+    /// Returns the item key under which the node with the given id is stored.
+    pub fn node_item_key(node_id: u32) -> Vec<u8> {
+        let mut item_key = vec![INDEX_ITEM_KEY_TAG];
+        item_key.extend_from_slice(&node_id.to_le_bytes());
+        item_key
+    }
+
+    /// Returns `true` if `item_key_bytes` is the item key of an index or one of its nodes.
+    pub fn is_index_item_key(item_key_bytes: &[u8]) -> bool {
+        item_key_bytes.first() == Some(&INDEX_ITEM_KEY_TAG)
+    }
+
+    /// Inserts `item_key` into the index, reading nodes with `read_node`.
+    ///
+    /// Returns the ids and contents of the nodes to write, which are empty if `item_key` was
+    /// already indexed.  Only the nodes on the path from the root to `item_key` are read or
+    /// written.
+    pub fn insert<F, E>(
+        &mut self,
+        item_key: &str,
+        mut read_node: F,
+    ) -> Result<Vec<(u32, IndexNode)>, E>
+    where
+        F: FnMut(u32) -> Result<IndexNode, E>,
+    {
+        let root_node_id = match self.root_node_id {
+            Some(root_node_id) => root_node_id,
+            None => {
+                let node_id = self.new_node_id();
+                self.root_node_id = Some(node_id);
+                return Ok(vec![(node_id, IndexNode::Leaf(vec![item_key.to_string()]))]);
+            }
+        };
+
+        // Descend to the leaf which should hold `item_key`, remembering the internal nodes on the
+        // way and the positions of the children taken.
+        let mut path = Vec::new();
+        let mut node_id = root_node_id;
+        let mut leaf = loop {
+            match read_node(node_id)? {
+                IndexNode::Leaf(item_keys) => break item_keys,
+                IndexNode::Internal(children) => {
+                    let position = child_position(&children, item_key);
+                    let child_id = children[position].1;
+                    path.push((node_id, children, position));
+                    node_id = child_id;
+                }
+            }
+        };
+        let index = match leaf.binary_search_by(|key| key.as_str().cmp(item_key)) {
+            Ok(_) => return Ok(Vec::new()),
+            Err(index) => index,
+        };
+        leaf.insert(index, item_key.to_string());
+
+        let mut nodes = Vec::new();
+        let mut first_item_key = leaf[0].clone();
+        let mut maybe_sibling = split_off_upper_half(&mut leaf).map(|upper_half| {
+            let sibling_id = self.new_node_id();
+            let sibling_first_item_key = upper_half[0].clone();
+            nodes.push((sibling_id, IndexNode::Leaf(upper_half)));
+            (sibling_first_item_key, sibling_id)
+        });
+        nodes.push((node_id, IndexNode::Leaf(leaf)));
+
+        // Update the first item keys and add split off siblings up the path.
+        while let Some((parent_id, mut children, position)) = path.pop() {
+            if children[position].0 == first_item_key && maybe_sibling.is_none() {
+                return Ok(nodes);
+            }
+            children[position].0 = first_item_key;
+            if let Some(sibling) = maybe_sibling.take() {
+                children.insert(position + 1, sibling);
+            }
+            first_item_key = children[0].0.clone();
+            maybe_sibling = split_off_upper_half(&mut children).map(|upper_half| {
+                let sibling_id = self.new_node_id();
+                let sibling_first_item_key = upper_half[0].0.clone();
+                nodes.push((sibling_id, IndexNode::Internal(upper_half)));
+                (sibling_first_item_key, sibling_id)
+            });
+            nodes.push((parent_id, IndexNode::Internal(children)));
+            node_id = parent_id;
+        }
+
+        // The root was split, so the tree grows by one level.
+        if let Some(sibling) = maybe_sibling {
+            let new_root_id = self.new_node_id();
+            nodes.push((
+                new_root_id,
+                IndexNode::Internal(vec![(first_item_key, node_id), sibling]),
+            ));
+            self.root_node_id = Some(new_root_id);
+        }
+        Ok(nodes)
+    }
+
+    /// Returns up to `limit` indexed item keys in ascending order, starting after `start_after`
+    /// if given, reading nodes with `read_node`.
+    pub fn item_keys_after<F, E>(
+        &self,
+        start_after: Option<&str>,
+        limit: usize,
+        mut read_node: F,
+    ) -> Result<Vec<String>, E>
+    where
+        F: FnMut(u32) -> Result<IndexNode, E>,
+    {
+        let mut item_keys = Vec::new();
+        if let Some(root_node_id) = self.root_node_id {
+            collect_item_keys(
+                root_node_id,
+                start_after,
+                limit,
+                &mut read_node,
+                &mut item_keys,
+            )?;
+        }
+        Ok(item_keys)
+    }
+
+    fn new_node_id(&mut self) -> u32 {
+        let node_id = self.next_node_id;
+        self.next_node_id += 1;
+        node_id
+    }
+}
+
+/// Appends the item keys under the node with the given id to `item_keys`, in ascending order,
+/// starting after `start_after` if given, until `item_keys` holds `limit` keys.
+fn collect_item_keys<F, E>(
+    node_id: u32,
+    start_after: Option<&str>,
+    limit: usize,
+    read_node: &mut F,
+    item_keys: &mut Vec<String>,
+) -> Result<(), E>
+where
+    F: FnMut(u32) -> Result<IndexNode, E>,
+{
+    match read_node(node_id)? {
+        IndexNode::Leaf(leaf_item_keys) => {
+            let start = match start_after {
+                Some(start_after) => {
+                    match leaf_item_keys.binary_search_by(|key| key.as_str().cmp(start_after)) {
+                        Ok(index) => index + 1,
+                        Err(index) => index,
+                    }
+                }
+                None => 0,
+            };
+            let remaining = limit.saturating_sub(item_keys.len());
+            item_keys.extend(leaf_item_keys.into_iter().skip(start).take(remaining));
+        }
+        IndexNode::Internal(children) => {
+            let first_position = start_after
+                .map(|start_after| child_position(&children, start_after))
+                .unwrap_or_default();
+            for (_, child_id) in children.iter().skip(first_position) {
+                if item_keys.len() >= limit {
+                    break;
+                }
+                collect_item_keys(*child_id, start_after, limit, read_node, item_keys)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the position of the child of an internal node which holds `item_key` or would hold it
+/// if inserted.
+fn child_position(children: &[(String, u32)], item_key: &str) -> usize {
+    match children.binary_search_by(|(first_item_key, _)| first_item_key.as_str().cmp(item_key)) {
+        Ok(position) => position,
+        Err(0) => 0,
+        Err(position) => position - 1,
+    }
+}
+
+/// Splits off and returns the upper half of the entries of a node if it has too many.
+fn split_off_upper_half<T>(entries: &mut Vec<T>) -> Option<Vec<T>> {
+    if entries.len() <= INDEX_NODE_MAX_LENGTH {
+        return None;
+    }
+    Some(entries.split_off(entries.len() / 2))
+}
+
+impl CLTyped for DictionaryIndex {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl FromBytes for DictionaryIndex {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (root_node_id, remainder) = FromBytes::from_bytes(bytes)?;
+        let (next_node_id, remainder) = FromBytes::from_bytes(remainder)?;
+        let dictionary_index = DictionaryIndex {
+            root_node_id,
+            next_node_id,
+        };
+        Ok((dictionary_index, remainder))
+    }
+}
+
+impl ToBytes for DictionaryIndex {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.root_node_id.to_bytes()?);
+        buffer.extend(self.next_node_id.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.root_node_id.serialized_length() + self.next_node_id.serialized_length()
+    }
+}
+
+impl CLTyped for IndexNode {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl FromBytes for IndexNode {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            INDEX_LEAF_TAG => {
+                let (item_keys, remainder) = FromBytes::from_bytes(remainder)?;
+                Ok((IndexNode::Leaf(item_keys), remainder))
+            }
+            INDEX_INTERNAL_TAG => {
+                let (children, remainder) = FromBytes::from_bytes(remainder)?;
+                Ok((IndexNode::Internal(children), remainder))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+impl ToBytes for IndexNode {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            IndexNode::Leaf(item_keys) => {
+                buffer.push(INDEX_LEAF_TAG);
+                buffer.extend(item_keys.to_bytes()?);
+            }
+            IndexNode::Internal(children) => {
+                buffer.push(INDEX_INTERNAL_TAG);
+                buffer.extend(children.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                IndexNode::Leaf(item_keys) => item_keys.serialized_length(),
+                IndexNode::Internal(children) => children.serialized_length(),
+            }
+    }
+}
+
 /// Inspects `key` argument whether it contains a dictionary variant, and checks if `stored_value`
 /// contains a [`CLValue`], then it will attempt a conversion from the held clvalue into
 /// [`DictionaryValue`] and returns the real [`CLValue`] held by it.
@@ -110,3 +428,65 @@ pub fn handle_stored_value_into(
         (_, stored_value) => Ok(stored_value),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, convert::Infallible};
+
+    use super::*;
+
+    #[test]
+    fn should_keep_item_keys_ordered_across_split_nodes() {
+        let mut dictionary_index = DictionaryIndex::default();
+        let mut nodes: BTreeMap<u32, IndexNode> = BTreeMap::new();
+
+        let item_keys: Vec<String> = (0..10_000).map(|index| format!("{:05}", index)).collect();
+        for item_key in item_keys.iter().rev().chain(item_keys.iter()) {
+            let written_nodes = dictionary_index
+                .insert(item_key, |node_id| -> Result<_, Infallible> {
+                    Ok(nodes[&node_id].clone())
+                })
+                .unwrap();
+            nodes.extend(written_nodes);
+        }
+        assert!(nodes.values().all(|node| match node {
+            IndexNode::Leaf(item_keys) => item_keys.len() <= INDEX_NODE_MAX_LENGTH,
+            IndexNode::Internal(children) => children.len() <= INDEX_NODE_MAX_LENGTH,
+        }));
+        let root_node_id = dictionary_index.root_node_id.unwrap();
+        match &nodes[&root_node_id] {
+            IndexNode::Internal(children) => {
+                assert!(matches!(nodes[&children[0].1], IndexNode::Internal(_)))
+            }
+            IndexNode::Leaf(_) => panic!("root should have grown beyond a leaf"),
+        }
+
+        let read_node = |node_id: u32| -> Result<_, Infallible> { Ok(nodes[&node_id].clone()) };
+        let all_item_keys = dictionary_index
+            .item_keys_after(None, usize::MAX, read_node)
+            .unwrap();
+        assert_eq!(all_item_keys, item_keys);
+
+        let page = dictionary_index
+            .item_keys_after(Some("04999x"), 100, read_node)
+            .unwrap();
+        assert_eq!(page, item_keys[5_000..5_100].to_vec());
+        assert!(dictionary_index
+            .item_keys_after(Some("09999"), 10, read_node)
+            .unwrap()
+            .is_empty());
+
+        let bytes = dictionary_index.to_bytes().unwrap();
+        assert_eq!(bytes.len(), dictionary_index.serialized_length());
+        let (deserialized, remainder) = DictionaryIndex::from_bytes(&bytes).unwrap();
+        assert!(remainder.is_empty());
+        assert_eq!(deserialized, dictionary_index);
+
+        let root_node = &nodes[&root_node_id];
+        let bytes = root_node.to_bytes().unwrap();
+        assert_eq!(bytes.len(), root_node.serialized_length());
+        let (deserialized, remainder) = IndexNode::from_bytes(&bytes).unwrap();
+        assert!(remainder.is_empty());
+        assert_eq!(&deserialized, root_node);
+    }
+}
@@ -25,9 +25,14 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::{execution_effect::ExecutionEffect, EngineConfig, SystemContractRegistry},
+        engine_state::{
+            execution_effect::{ContractEvent, ExecutionEffect},
+            execution_trace::{ExecutionTracer, TraceEvent},
+            gas_profile::{GasCharge, GasProfiler},
+            EngineConfig, SystemContractRegistry, DICTIONARY_INDEX_PROTOCOL_VERSION,
+        },
         execution::{AddressGenerator, Error},
-        runtime_context::dictionary::DictionaryValue,
+        runtime_context::dictionary::{DictionaryIndex, DictionaryValue, IndexNode},
         tracking_copy::{AddResult, TrackingCopy, TrackingCopyExt},
         Address,
     },
@@ -120,6 +125,8 @@ pub struct RuntimeContext<'a, R> {
     engine_config: EngineConfig,
     entry_point_type: EntryPointType,
     transfers: Vec<TransferAddr>,
+    gas_profiler: Option<Rc<RefCell<GasProfiler>>>,
+    execution_tracer: Option<Rc<RefCell<ExecutionTracer>>>,
 }
 
 impl<'a, R> RuntimeContext<'a, R>
@@ -172,6 +179,46 @@ where
             phase,
             engine_config,
             transfers,
+            gas_profiler: None,
+            execution_tracer: None,
+        }
+    }
+
+    /// Sets the gas profiler recording the gas charged, if gas profiling is enabled.
+    pub(crate) fn with_gas_profiler(
+        mut self,
+        gas_profiler: Option<Rc<RefCell<GasProfiler>>>,
+    ) -> Self {
+        self.gas_profiler = gas_profiler;
+        self
+    }
+
+    /// Returns the gas profiler, if gas profiling is enabled.
+    pub(crate) fn gas_profiler(&self) -> Option<&Rc<RefCell<GasProfiler>>> {
+        self.gas_profiler.as_ref()
+    }
+
+    /// Sets the execution tracer recording a trace of the execution, if tracing is enabled.
+    pub(crate) fn with_execution_tracer(
+        mut self,
+        execution_tracer: Option<Rc<RefCell<ExecutionTracer>>>,
+    ) -> Self {
+        self.execution_tracer = execution_tracer;
+        self
+    }
+
+    /// Returns the execution tracer, if tracing is enabled.
+    pub(crate) fn execution_tracer(&self) -> Option<&Rc<RefCell<ExecutionTracer>>> {
+        self.execution_tracer.as_ref()
+    }
+
+    /// Records the event returned by `event` in the execution trace, if tracing is enabled.
+    pub(crate) fn trace<F>(&self, event: F)
+    where
+        F: FnOnce() -> TraceEvent,
+    {
+        if let Some(execution_tracer) = &self.execution_tracer {
+            execution_tracer.borrow_mut().record(event());
         }
     }
 
@@ -182,7 +229,12 @@ where
 
     /// Returns a named key by a name if it exists.
     pub fn named_keys_get(&self, name: &str) -> Option<&Key> {
-        self.named_keys.get(name)
+        let key = self.named_keys.get(name);
+        self.trace(|| TraceEvent::NamedKeyRead {
+            name: name.to_string(),
+            key: key.copied(),
+        });
+        key
     }
 
     /// Returns named keys.
@@ -197,7 +249,7 @@ where
 
     /// Checks if named keys contains a key referenced by name.
     pub fn named_keys_contains_key(&self, name: &str) -> bool {
-        self.named_keys.contains_key(name)
+        self.named_keys_get(name).is_some()
     }
 
     /// Helper function to avoid duplication in `remove_uref`.
@@ -219,6 +271,14 @@ where
     /// also persistable map (one that is found in the
     /// TrackingCopy/GlobalState).
     pub fn remove_key(&mut self, name: &str) -> Result<(), Error> {
+        self.remove_named_key(name)?;
+        self.trace(|| TraceEvent::NamedKeyRemoved {
+            name: name.to_string(),
+        });
+        Ok(())
+    }
+
+    fn remove_named_key(&mut self, name: &str) -> Result<(), Error> {
         match self.base_key() {
             account_hash @ Key::Account(_) => {
                 let account: Account = {
@@ -422,6 +482,10 @@ where
         let named_key_value = StoredValue::CLValue(CLValue::from_t((name.clone(), key))?);
         self.validate_value(&named_key_value)?;
         self.metered_add_gs_unsafe(self.base_key(), named_key_value)?;
+        self.trace(|| TraceEvent::NamedKeyWritten {
+            name: name.clone(),
+            key,
+        });
         self.insert_key(name, key);
         Ok(())
     }
@@ -809,6 +873,20 @@ where
         }
     }
 
+    /// Charges gas as [`RuntimeContext::charge_gas`] does, recording what it is charged for if gas
+    /// profiling is enabled.
+    pub(crate) fn charge_gas_for(&mut self, amount: Gas, charge: GasCharge) -> Result<(), Error> {
+        let gas_profiler = match &self.gas_profiler {
+            Some(gas_profiler) => Rc::clone(gas_profiler),
+            None => return self.charge_gas(amount),
+        };
+        let prev = self.gas_counter();
+        let result = self.charge_gas(amount);
+        let charged = self.gas_counter().checked_sub(prev).unwrap_or_default();
+        gas_profiler.borrow_mut().record(charge, amount, charged);
+        result
+    }
+
     /// Checks if we are calling a system contract.
     pub(crate) fn is_system_contract(&self) -> Result<bool, Error> {
         if let Some(hash) = self.base_key().into_hash() {
@@ -832,7 +910,7 @@ where
 
         let gas_cost = storage_costs.calculate_gas_cost(bytes_count);
 
-        self.charge_gas(gas_cost)
+        self.charge_gas_for(gas_cost, GasCharge::Storage { bytes: bytes_count })
     }
 
     /// Charges gas for using a host system contract's entrypoint.
@@ -847,7 +925,7 @@ where
             return Ok(());
         }
         let amount: Gas = call_cost.into();
-        self.charge_gas(amount)
+        self.charge_gas_for(amount, GasCharge::SystemContractCall)
     }
 
     /// Writes data to global state with a measurement.
@@ -1145,7 +1223,132 @@ where
             return Err(Error::DictionaryItemKeyExceedsLength);
         }
 
-        let dictionary_key = Key::dictionary(uref, dictionary_item_key_bytes);
+        self.read_dictionary_entry(uref, dictionary_item_key_bytes)
+    }
+
+    /// Puts a dictionary item key from a dictionary referenced by a `uref`.
+    pub fn dictionary_put(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+        cl_value: CLValue,
+    ) -> Result<(), Error> {
+        let dictionary_item_key_bytes = dictionary_item_key.as_bytes();
+
+        if dictionary_item_key_bytes.len() > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            return Err(Error::DictionaryItemKeyExceedsLength);
+        }
+
+        self.validate_writeable(&seed_uref.into())?;
+        self.validate_uref(&seed_uref)?;
+
+        self.validate_cl_value(&cl_value)?;
+
+        if self.protocol_version() < DICTIONARY_INDEX_PROTOCOL_VERSION {
+            return self.write_dictionary_entry(seed_uref, dictionary_item_key_bytes, cl_value);
+        }
+
+        let is_new_item = self
+            .read_dictionary_entry(seed_uref, dictionary_item_key_bytes)?
+            .is_none();
+        self.write_dictionary_entry(seed_uref, dictionary_item_key_bytes, cl_value)?;
+        if is_new_item {
+            self.index_dictionary_item(seed_uref, dictionary_item_key)?;
+        }
+        Ok(())
+    }
+
+    /// Writes an empty index of item keys for the dictionary referenced by a `seed_uref`, making
+    /// it enumerable.
+    ///
+    /// Does nothing under protocol versions earlier than [`DICTIONARY_INDEX_PROTOCOL_VERSION`].
+    pub(crate) fn init_dictionary_index(&mut self, seed_uref: URef) -> Result<(), Error> {
+        if self.protocol_version() < DICTIONARY_INDEX_PROTOCOL_VERSION {
+            return Ok(());
+        }
+        let cl_value = CLValue::from_t(DictionaryIndex::default()).map_err(Error::from)?;
+        self.write_dictionary_entry(seed_uref, &DictionaryIndex::root_item_key(), cl_value)
+    }
+
+    /// Gets up to `limit` item keys of the dictionary referenced by a `uref` in ascending order,
+    /// starting after `start_after` if given.
+    ///
+    /// Returns `None` if the dictionary has no index of its item keys.
+    pub(crate) fn dictionary_item_keys(
+        &mut self,
+        uref: URef,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Option<Vec<String>>, Error> {
+        self.validate_readable(&uref.into())?;
+        self.validate_key(&uref.into())?;
+
+        let dictionary_index = match self.read_dictionary_index(uref)? {
+            Some(dictionary_index) => dictionary_index,
+            None => return Ok(None),
+        };
+        let item_keys = dictionary_index.item_keys_after(start_after, limit, |node_id| {
+            self.read_dictionary_index_node(uref, node_id)
+        })?;
+        Ok(Some(item_keys))
+    }
+
+    /// Adds a new item key to the index of the dictionary referenced by a `seed_uref`, if it has
+    /// one.
+    fn index_dictionary_item(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<(), Error> {
+        let mut dictionary_index = match self.read_dictionary_index(seed_uref)? {
+            Some(dictionary_index) => dictionary_index,
+            None => return Ok(()),
+        };
+        let original_index = dictionary_index.clone();
+        let nodes = dictionary_index.insert(dictionary_item_key, |node_id| {
+            self.read_dictionary_index_node(seed_uref, node_id)
+        })?;
+        for (node_id, node) in nodes {
+            let cl_value = CLValue::from_t(node).map_err(Error::from)?;
+            self.write_dictionary_entry(
+                seed_uref,
+                &DictionaryIndex::node_item_key(node_id),
+                cl_value,
+            )?;
+        }
+        if dictionary_index != original_index {
+            let cl_value = CLValue::from_t(dictionary_index).map_err(Error::from)?;
+            self.write_dictionary_entry(seed_uref, &DictionaryIndex::root_item_key(), cl_value)?;
+        }
+        Ok(())
+    }
+
+    fn read_dictionary_index(&mut self, seed_uref: URef) -> Result<Option<DictionaryIndex>, Error> {
+        self.read_dictionary_entry(seed_uref, &DictionaryIndex::root_item_key())?
+            .map(|cl_value| cl_value.into_t().map_err(Error::from))
+            .transpose()
+    }
+
+    fn read_dictionary_index_node(
+        &mut self,
+        seed_uref: URef,
+        node_id: u32,
+    ) -> Result<IndexNode, Error> {
+        let node_item_key = DictionaryIndex::node_item_key(node_id);
+        let dictionary_key = Key::dictionary(seed_uref, &node_item_key);
+        let cl_value = self
+            .read_dictionary_entry(seed_uref, &node_item_key)?
+            .ok_or(Error::KeyNotFound(dictionary_key))?;
+        cl_value.into_t().map_err(Error::from)
+    }
+
+    /// Reads the value stored under an item key of the dictionary referenced by a `seed_uref`.
+    fn read_dictionary_entry(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key_bytes: &[u8],
+    ) -> Result<Option<CLValue>, Error> {
+        let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key_bytes);
 
         let maybe_stored_value = self
             .tracking_copy
@@ -1165,24 +1368,13 @@ where
         }
     }
 
-    /// Puts a dictionary item key from a dictionary referenced by a `uref`.
-    pub fn dictionary_put(
+    /// Writes a value under an item key of the dictionary referenced by a `seed_uref`.
+    fn write_dictionary_entry(
         &mut self,
         seed_uref: URef,
-        dictionary_item_key: &str,
+        dictionary_item_key_bytes: &[u8],
         cl_value: CLValue,
     ) -> Result<(), Error> {
-        let dictionary_item_key_bytes = dictionary_item_key.as_bytes();
-
-        if dictionary_item_key_bytes.len() > DICTIONARY_ITEM_KEY_MAX_LENGTH {
-            return Err(Error::DictionaryItemKeyExceedsLength);
-        }
-
-        self.validate_writeable(&seed_uref.into())?;
-        self.validate_uref(&seed_uref)?;
-
-        self.validate_cl_value(&cl_value)?;
-
         let wrapped_cl_value = {
             let dictionary_value = DictionaryValue::new(
                 cl_value,
@@ -1193,7 +1385,23 @@ where
         };
 
         let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key_bytes);
-        self.metered_write_gs_unsafe(dictionary_key, wrapped_cl_value)?;
+        self.metered_write_gs_unsafe(dictionary_key, wrapped_cl_value)
+    }
+
+    /// Records an event with the given topic and payload, emitted by the current context.
+    ///
+    /// Events are not written to global state, but they are stored by the node as part of the
+    /// execution results, so their size is charged at the storage rate.
+    pub(crate) fn emit_event(&mut self, topic: String, payload: CLValue) -> Result<(), Error> {
+        self.validate_cl_value(&payload)?;
+        self.charge_gas_storage(topic.len() + payload.serialized_length())?;
+
+        let event = ContractEvent {
+            emitter: self.base_key(),
+            topic,
+            payload,
+        };
+        self.tracking_copy.borrow_mut().emit_event(event);
         Ok(())
     }
 
@@ -9,7 +9,11 @@ use casper_types::{
 };
 
 use crate::{
-    core::{engine_state::SystemContractRegistry, execution, tracking_copy::TrackingCopy},
+    core::{
+        engine_state::{account_nonce_key, SystemContractRegistry},
+        execution,
+        tracking_copy::TrackingCopy,
+    },
     shared::{newtypes::CorrelationId, wasm, wasm_prep::Preprocessor},
     storage::{global_state::StateReader, trie::merkle_proof::TrieMerkleProof},
 };
@@ -87,6 +91,13 @@ pub trait TrackingCopyExt<R> {
         &mut self,
         correlation_id: CorrelationId,
     ) -> Result<SystemContractRegistry, Self::Error>;
+
+    /// Gets the nonce of the latest deploy executed by the account, or 0 if there is none.
+    fn get_nonce(
+        &mut self,
+        correlation_id: CorrelationId,
+        account_hash: AccountHash,
+    ) -> Result<u64, Self::Error>;
 }
 
 impl<R> TrackingCopyExt<R> for TrackingCopy<R>
@@ -265,4 +276,23 @@ where
             None => Err(execution::Error::KeyNotFound(Key::SystemContractRegistry)),
         }
     }
+
+    fn get_nonce(
+        &mut self,
+        correlation_id: CorrelationId,
+        account_hash: AccountHash,
+    ) -> Result<u64, Self::Error> {
+        match self
+            .read(correlation_id, &account_nonce_key(account_hash))
+            .map_err(Into::into)?
+        {
+            Some(StoredValue::CLValue(cl_value)) => {
+                Ok(CLValue::into_t(cl_value).map_err(Self::Error::from)?)
+            }
+            Some(other) => Err(execution::Error::TypeMismatch(
+                StoredValueTypeMismatch::new("CLValue".to_string(), other.type_name()),
+            )),
+            None => Ok(0),
+        }
+    }
 }
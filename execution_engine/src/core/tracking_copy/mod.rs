@@ -24,7 +24,10 @@ use self::meter::{heap_meter::HeapSize, Meter};
 use super::engine_state::EngineConfig;
 use crate::{
     core::{
-        engine_state::{execution_effect::ExecutionEffect, op::Op},
+        engine_state::{
+            execution_effect::{ContractEvent, ExecutionEffect},
+            op::Op,
+        },
         runtime_context::dictionary,
     },
     shared::{
@@ -215,6 +218,7 @@ pub struct TrackingCopy<R> {
     cache: TrackingCopyCache<HeapSize>,
     ops: AdditiveMap<Key, Op>,
     fns: AdditiveMap<Key, Transform>,
+    events: Vec<ContractEvent>,
 }
 
 #[derive(Debug)]
@@ -248,6 +252,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
              * limit? */
             ops: AdditiveMap::new(),
             fns: AdditiveMap::new(),
+            events: Vec::new(),
         }
     }
 
@@ -409,8 +414,13 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         }
     }
 
+    /// Records an event emitted by a contract.
+    pub fn emit_event(&mut self, event: ContractEvent) {
+        self.events.push(event);
+    }
+
     pub fn effect(&self) -> ExecutionEffect {
-        ExecutionEffect::new(self.ops.clone(), self.fns.clone())
+        ExecutionEffect::new(self.ops.clone(), self.fns.clone(), self.events.clone())
     }
 
     /// Calling `query()` avoids calling into `self.cache`, so this will not return any values
@@ -573,6 +583,17 @@ impl<R: StateReader<Key, StoredValue>> StateReader<Key, StoredValue> for &Tracki
     ) -> Result<Vec<Key>, Self::Error> {
         self.reader.keys_with_prefix(correlation_id, prefix)
     }
+
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start_after: Option<&Key>,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        self.reader
+            .keys_with_prefix_after(correlation_id, prefix, start_after, limit)
+    }
 }
 
 /// Error conditions of a proof validation.
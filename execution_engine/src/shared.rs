@@ -4,6 +4,7 @@ pub mod additive_map;
 pub mod host_function_costs;
 pub mod logging;
 pub mod newtypes;
+pub(crate) mod opcode_attribution;
 pub mod opcode_costs;
 pub mod storage_costs;
 pub mod system_config;
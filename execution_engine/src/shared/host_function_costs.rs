@@ -81,6 +81,46 @@ const DEFAULT_NEW_DICTIONARY_COST: u32 = DEFAULT_NEW_UREF_COST;
 pub(crate) const DEFAULT_HOST_FUNCTION_NEW_DICTIONARY: HostFunction<[Cost; 1]> =
     HostFunction::new(DEFAULT_NEW_DICTIONARY_COST, [NOT_USED]);
 
+const DEFAULT_EMIT_EVENT_COST: u32 = 9_500;
+const DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT: u32 = 1_800;
+const DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT: u32 = 520;
+
+const DEFAULT_HOST_FUNCTION_EMIT_EVENT: HostFunction<[Cost; 4]> = HostFunction::new(
+    DEFAULT_EMIT_EVENT_COST,
+    [
+        NOT_USED,
+        DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT,
+        NOT_USED,
+        DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT,
+    ],
+);
+
+/// Default cost of the `emit_event` host function, used by cost tables which predate it.
+fn default_emit_event() -> HostFunction<[Cost; 4]> {
+    DEFAULT_HOST_FUNCTION_EMIT_EVENT
+}
+
+const DEFAULT_DICTIONARY_ITER_COST: u32 = DEFAULT_DICTIONARY_GET_COST;
+const DEFAULT_DICTIONARY_ITER_CURSOR_SIZE_WEIGHT: u32 = DEFAULT_DICTIONARY_GET_KEY_SIZE_WEIGHT;
+const DEFAULT_DICTIONARY_ITER_LIMIT_WEIGHT: u32 = DEFAULT_DICTIONARY_GET_COST;
+
+const DEFAULT_HOST_FUNCTION_DICTIONARY_ITER: HostFunction<[Cost; 6]> = HostFunction::new(
+    DEFAULT_DICTIONARY_ITER_COST,
+    [
+        NOT_USED,
+        NOT_USED,
+        NOT_USED,
+        DEFAULT_DICTIONARY_ITER_CURSOR_SIZE_WEIGHT,
+        DEFAULT_DICTIONARY_ITER_LIMIT_WEIGHT,
+        NOT_USED,
+    ],
+);
+
+/// Default cost of the `dictionary_iter` host function, used by cost tables which predate it.
+fn default_dictionary_iter() -> HostFunction<[Cost; 6]> {
+    DEFAULT_HOST_FUNCTION_DICTIONARY_ITER
+}
+
 /// Representation of a host function cost.
 ///
 /// The total gas cost is equal to `cost` + sum of each argument weight multiplied by the byte size
@@ -284,6 +324,12 @@ pub struct HostFunctionCosts {
     pub print: HostFunction<[Cost; 2]>,
     /// Cost of calling the `blake2b` host function.
     pub blake2b: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `emit_event` host function.
+    #[serde(default = "default_emit_event")]
+    pub emit_event: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `dictionary_iter` host function.
+    #[serde(default = "default_dictionary_iter")]
+    pub dictionary_iter: HostFunction<[Cost; 6]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -401,6 +447,8 @@ impl Default for HostFunctionCosts {
                 [NOT_USED, DEFAULT_PRINT_TEXT_SIZE_WEIGHT],
             ),
             blake2b: HostFunction::default(),
+            emit_event: DEFAULT_HOST_FUNCTION_EMIT_EVENT,
+            dictionary_iter: DEFAULT_HOST_FUNCTION_DICTIONARY_ITER,
         }
     }
 }
@@ -450,6 +498,8 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.remove_contract_user_group_urefs.to_bytes()?);
         ret.append(&mut self.print.to_bytes()?);
         ret.append(&mut self.blake2b.to_bytes()?);
+        ret.append(&mut self.emit_event.to_bytes()?);
+        ret.append(&mut self.dictionary_iter.to_bytes()?);
         Ok(ret)
     }
 
@@ -496,6 +546,8 @@ impl ToBytes for HostFunctionCosts {
             + self.remove_contract_user_group_urefs.serialized_length()
             + self.print.serialized_length()
             + self.blake2b.serialized_length()
+            + self.emit_event.serialized_length()
+            + self.dictionary_iter.serialized_length()
     }
 }
 
@@ -543,6 +595,8 @@ impl FromBytes for HostFunctionCosts {
         let (remove_contract_user_group_urefs, rem) = FromBytes::from_bytes(rem)?;
         let (print, rem) = FromBytes::from_bytes(rem)?;
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
+        let (emit_event, rem) = FromBytes::from_bytes(rem)?;
+        let (dictionary_iter, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -587,6 +641,8 @@ impl FromBytes for HostFunctionCosts {
                 remove_contract_user_group_urefs,
                 print,
                 blake2b,
+                emit_event,
+                dictionary_iter,
             },
             rem,
         ))
@@ -638,6 +694,8 @@ impl Distribution<HostFunctionCosts> for Standard {
             remove_contract_user_group_urefs: rng.gen(),
             print: rng.gen(),
             blake2b: rng.gen(),
+            emit_event: rng.gen(),
+            dictionary_iter: rng.gen(),
         }
     }
 }
@@ -697,6 +755,8 @@ pub mod gens {
             remove_contract_user_group_urefs in host_function_cost_arb(),
             print in host_function_cost_arb(),
             blake2b in host_function_cost_arb(),
+            emit_event in host_function_cost_arb(),
+            dictionary_iter in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -741,6 +801,8 @@ pub mod gens {
                 remove_contract_user_group_urefs,
                 print,
                 blake2b,
+                emit_event,
+                dictionary_iter,
             }
         }
     }
@@ -0,0 +1,623 @@
+//! Attribution of the gas charged by preprocessed Wasm code to opcode categories.
+//!
+//! Preprocessing injects a call to the `gas` host function at the start of every metered block of
+//! code, passing the total cost of the block's instructions.  For gas profiling, a copy of the
+//! module is executed in which each of these calls passes the index of its call site instead, so
+//! that the charge can be attributed to the categories of the block's instructions.
+//!
+//! Modules are analyzed as preprocessed since stored contracts are stored that way, so the metered
+//! blocks are determined the same way the gas counter injection does, skipping the instructions
+//! injected by preprocessing.
+//!
+//! Modules which import the `gas` host function themselves, i.e. in addition to the import
+//! injected by preprocessing, are not profiled: their own calls can't be told apart from the
+//! injected ones at runtime, so all the gas charged by their code is left unattributed.
+use std::{cmp, collections::BTreeMap, mem};
+
+use parity_wasm::elements::{BlockType, External, ImportCountType, Instruction, Module};
+
+use super::opcode_costs::{OpcodeCategory, OpcodeCosts};
+
+/// The module from which the `gas` host function is imported by preprocessed modules.
+const GAS_MODULE_NAME: &str = "env";
+/// The name of the `gas` host function.
+const GAS_FUNCTION_NAME: &str = "gas";
+/// The number of instructions injected by the stack height limiter around each call.
+const STACK_LIMITER_CALL_LENGTH: usize = 15;
+
+/// The value passed to the `gas` host function by a profiled module to announce that the next call
+/// passes a cost computed at runtime, i.e. the cost of growing memory by a number of pages.
+pub(crate) const DYNAMIC_GAS_MARKER: u32 = u32::MAX;
+
+/// The number of instructions of each category in a metered block.
+type CategoryCounts = BTreeMap<OpcodeCategory, u64>;
+
+/// The cost charged for a metered block of a preprocessed module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MeteredBlockCost {
+    /// The cost charged for the block.
+    pub(crate) cost: u32,
+    /// The cost of the block's instructions by category, or `None` if they don't add up to the
+    /// charged cost, e.g. because the module was preprocessed with different opcode costs.
+    pub(crate) categories: Option<BTreeMap<OpcodeCategory, u64>>,
+}
+
+/// Returns a copy of the given preprocessed module whose injected calls to the `gas` host function
+/// pass the index of their call site instead of a cost, along with the costs of the metered blocks
+/// by call site index.
+///
+/// Calls passing a cost computed at runtime are preceded by a call passing
+/// [`DYNAMIC_GAS_MARKER`].  Returns `None` if the module doesn't import the `gas` host function
+/// exactly once.
+pub(crate) fn profiled_module(
+    module: &Module,
+    opcode_costs: &OpcodeCosts,
+) -> Option<(Module, Vec<MeteredBlockCost>)> {
+    let gas_function = gas_function_index(module)?;
+    let imported_function_count = module.import_count(ImportCountType::Function);
+
+    let mut module = module.clone();
+    let bodies = match module.code_section_mut() {
+        Some(code_section) => code_section.bodies_mut(),
+        None => return Some((module, Vec::new())),
+    };
+
+    // The function injected to charge for growing memory is the one passing a computed cost.
+    let grow_function = bodies
+        .iter()
+        .position(|body| {
+            let instructions = body.code().elements();
+            (0..instructions.len())
+                .any(|position| is_dynamic_gas_call(instructions, position, gas_function))
+        })
+        .map(|index| (imported_function_count + index) as u32);
+
+    let mut metered_blocks = Vec::new();
+    for body in bodies.iter_mut() {
+        let instructions = body.code_mut().elements_mut();
+        let analysis = analyze(instructions, gas_function, grow_function);
+
+        let mut profiled_instructions = Vec::with_capacity(instructions.len());
+        let mut call_sites = analysis.call_sites.iter();
+        let mut next_call_site = call_sites.next();
+        for (position, instruction) in instructions.iter().enumerate() {
+            if let Some(call_site) =
+                next_call_site.filter(|call_site| call_site.position == position)
+            {
+                let index = metered_blocks.len() as u32;
+                if index == DYNAMIC_GAS_MARKER {
+                    return None;
+                }
+                profiled_instructions.push(Instruction::I32Const(index as i32));
+                metered_blocks.push(MeteredBlockCost {
+                    cost: call_site.cost,
+                    categories: analysis
+                        .block_counts
+                        .get(&call_site.start_pos)
+                        .and_then(|counts| categories(counts, call_site.cost, opcode_costs)),
+                });
+                next_call_site = call_sites.next();
+                continue;
+            }
+            if is_dynamic_gas_call(instructions, position, gas_function) {
+                profiled_instructions.push(Instruction::I32Const(DYNAMIC_GAS_MARKER as i32));
+                profiled_instructions.push(Instruction::Call(gas_function));
+            }
+            profiled_instructions.push(instruction.clone());
+        }
+        *instructions = profiled_instructions;
+    }
+
+    Some((module, metered_blocks))
+}
+
+/// Returns the index of the imported `gas` host function, or `None` unless it's imported exactly
+/// once.
+fn gas_function_index(module: &Module) -> Option<u32> {
+    let mut gas_function_indices = module
+        .import_section()?
+        .entries()
+        .iter()
+        .filter(|entry| matches!(entry.external(), External::Function(_)))
+        .enumerate()
+        .filter(|(_, entry)| {
+            entry.module() == GAS_MODULE_NAME && entry.field() == GAS_FUNCTION_NAME
+        })
+        .map(|(index, _)| index as u32);
+    let gas_function_index = gas_function_indices.next()?;
+    if gas_function_indices.next().is_some() {
+        return None;
+    }
+    Some(gas_function_index)
+}
+
+/// Returns whether the instruction at the given position calls the `gas` host function passing a
+/// cost computed at runtime rather than a constant.
+fn is_dynamic_gas_call(instructions: &[Instruction], position: usize, gas_function: u32) -> bool {
+    instructions[position] == Instruction::Call(gas_function)
+        && !matches!(
+            position
+                .checked_sub(1)
+                .map(|previous| &instructions[previous]),
+            Some(Instruction::I32Const(_))
+        )
+}
+
+/// An injected call to the `gas` host function passing a constant cost.
+struct CallSite {
+    /// The position of the `i32.const` instruction passing the cost.
+    position: usize,
+    /// The position of the metered block's first instruction, among the original instructions.
+    start_pos: usize,
+    /// The cost passed.
+    cost: u32,
+}
+
+/// The result of analyzing the code of a function.
+struct Analysis {
+    /// The injected calls to the `gas` host function passing a constant cost.
+    call_sites: Vec<CallSite>,
+    /// The number of instructions by category of the metered blocks, by the position of their
+    /// first instruction among the original instructions.
+    block_counts: BTreeMap<usize, CategoryCounts>,
+}
+
+/// Separates the original instructions of a function from the ones injected by preprocessing, and
+/// determines its metered blocks.
+fn analyze(
+    instructions: &[Instruction],
+    gas_function: u32,
+    grow_function: Option<u32>,
+) -> Analysis {
+    let mut call_sites = Vec::new();
+    let mut original_instructions = Vec::with_capacity(instructions.len());
+    let mut position = 0;
+    while position < instructions.len() {
+        let remaining = &instructions[position..];
+        match remaining {
+            [Instruction::I32Const(cost), Instruction::Call(function), ..]
+                if *function == gas_function =>
+            {
+                call_sites.push(CallSite {
+                    position,
+                    start_pos: original_instructions.len(),
+                    cost: *cost as u32,
+                });
+                position += 2;
+            }
+            [Instruction::Call(function), ..] if *function == gas_function => position += 1,
+            _ => match stack_limiter_call(remaining) {
+                Some(call) => {
+                    original_instructions.push(call);
+                    position += STACK_LIMITER_CALL_LENGTH;
+                }
+                None => {
+                    original_instructions.push(&remaining[0]);
+                    position += 1;
+                }
+            },
+        }
+    }
+
+    let block_counts = metered_blocks(&original_instructions, grow_function)
+        .map(|blocks| {
+            let mut block_counts = BTreeMap::<usize, CategoryCounts>::new();
+            for block in blocks {
+                let counts = block_counts.entry(block.start_pos).or_default();
+                add_counts(counts, block.counts);
+            }
+            block_counts
+        })
+        .unwrap_or_default();
+
+    Analysis {
+        call_sites,
+        block_counts,
+    }
+}
+
+/// Returns the original call wrapped by the instructions injected by the stack height limiter, if
+/// the given instructions start with such a wrapped call.
+fn stack_limiter_call(instructions: &[Instruction]) -> Option<&Instruction> {
+    use Instruction::*;
+
+    match instructions {
+        [GetGlobal(global_1), I32Const(cost_1), I32Add, SetGlobal(global_2), GetGlobal(global_3), I32Const(_), I32GtU, If(BlockType::NoResult), Unreachable, End, call @ Call(_), GetGlobal(global_4), I32Const(cost_2), I32Sub, SetGlobal(global_5), ..]
+            if [global_2, global_3, global_4, global_5]
+                .iter()
+                .all(|global| *global == global_1)
+                && cost_1 == cost_2 =>
+        {
+            Some(call)
+        }
+        _ => None,
+    }
+}
+
+/// A metered block of code.
+struct MeteredBlock {
+    /// The position of the block's first instruction.
+    start_pos: usize,
+    /// The number of instructions by category in the block.
+    counts: CategoryCounts,
+}
+
+/// A control flow block of code.
+struct ControlBlock {
+    /// The lowest index on the control stack targeted by a forward branch from within the block.
+    lowest_forward_br_target: usize,
+    /// The metered block the instructions currently count towards.
+    active_metered_block: MeteredBlock,
+    /// Whether branches to the block jump to its start rather than its end.
+    is_loop: bool,
+}
+
+/// Splits code into metered blocks, mirroring the gas counter injection of preprocessing.
+#[derive(Default)]
+struct Counter {
+    stack: Vec<ControlBlock>,
+    finalized_blocks: Vec<MeteredBlock>,
+}
+
+impl Counter {
+    fn begin_control_block(&mut self, start_pos: usize, is_loop: bool) {
+        let index = self.stack.len();
+        self.stack.push(ControlBlock {
+            lowest_forward_br_target: index,
+            active_metered_block: MeteredBlock {
+                start_pos,
+                counts: CategoryCounts::new(),
+            },
+            is_loop,
+        })
+    }
+
+    fn finalize_control_block(&mut self, cursor: usize) -> Option<()> {
+        self.finalize_metered_block(cursor)?;
+
+        let closing_control_block = self.stack.pop()?;
+        let closing_control_index = self.stack.len();
+        let control_block = match self.stack.last_mut() {
+            Some(control_block) => control_block,
+            None => return Some(()),
+        };
+        control_block.lowest_forward_br_target = cmp::min(
+            control_block.lowest_forward_br_target,
+            closing_control_block.lowest_forward_br_target,
+        );
+
+        // Code following a block which may have been branched out of starts a new metered block.
+        if closing_control_block.lowest_forward_br_target < closing_control_index {
+            self.finalize_metered_block(cursor)?;
+        }
+        Some(())
+    }
+
+    fn finalize_metered_block(&mut self, cursor: usize) -> Option<()> {
+        let closing_metered_block = mem::replace(
+            &mut self.stack.last_mut()?.active_metered_block,
+            MeteredBlock {
+                start_pos: cursor + 1,
+                counts: CategoryCounts::new(),
+            },
+        );
+
+        // A metered block opened by a `block` instruction is merged into the enclosing one.
+        let last_index = self.stack.len() - 1;
+        if last_index > 0 {
+            let previous_metered_block = &mut self.stack[last_index - 1].active_metered_block;
+            if closing_metered_block.start_pos == previous_metered_block.start_pos {
+                add_counts(
+                    &mut previous_metered_block.counts,
+                    closing_metered_block.counts,
+                );
+                return Some(());
+            }
+        }
+
+        if !closing_metered_block.counts.is_empty() {
+            self.finalized_blocks.push(closing_metered_block);
+        }
+        Some(())
+    }
+
+    fn branch(&mut self, cursor: usize, target_indices: &[usize]) -> Option<()> {
+        self.finalize_metered_block(cursor)?;
+
+        for &target_index in target_indices {
+            if self.stack.get(target_index)?.is_loop {
+                continue;
+            }
+            let control_block = self.stack.last_mut()?;
+            control_block.lowest_forward_br_target =
+                cmp::min(control_block.lowest_forward_br_target, target_index);
+        }
+        Some(())
+    }
+
+    fn branch_targets(&self, labels: impl IntoIterator<Item = u32>) -> Option<Vec<usize>> {
+        let active_index = self.stack.len().checked_sub(1)?;
+        labels
+            .into_iter()
+            .map(|label| active_index.checked_sub(label as usize))
+            .collect()
+    }
+
+    fn increment(&mut self, category: OpcodeCategory) -> Option<()> {
+        let counts = &mut self.stack.last_mut()?.active_metered_block.counts;
+        *counts.entry(category).or_default() += 1;
+        Some(())
+    }
+}
+
+/// Splits the given original instructions of a function into metered blocks.
+///
+/// Returns `None` if the control flow of the instructions is malformed.
+fn metered_blocks(
+    instructions: &[&Instruction],
+    grow_function: Option<u32>,
+) -> Option<Vec<MeteredBlock>> {
+    let mut counter = Counter::default();
+    counter.begin_control_block(0, false);
+
+    for (cursor, instruction) in instructions.iter().enumerate() {
+        let category = match instruction {
+            // Growing memory is replaced by a call to the function charging for it.
+            Instruction::Call(function) if Some(*function) == grow_function => {
+                OpcodeCategory::GrowMemory
+            }
+            _ => OpcodeCategory::of(instruction),
+        };
+        match instruction {
+            Instruction::Block(_) => {
+                counter.increment(category)?;
+                let start_pos = counter.stack.last()?.active_metered_block.start_pos;
+                counter.begin_control_block(start_pos, false);
+            }
+            Instruction::If(_) => {
+                counter.increment(category)?;
+                counter.begin_control_block(cursor + 1, false);
+            }
+            Instruction::Loop(_) => {
+                counter.increment(category)?;
+                counter.begin_control_block(cursor + 1, true);
+            }
+            Instruction::End => counter.finalize_control_block(cursor)?,
+            Instruction::Else => counter.finalize_metered_block(cursor)?,
+            Instruction::Br(label) | Instruction::BrIf(label) => {
+                counter.increment(category)?;
+                let target_indices = counter.branch_targets(vec![*label])?;
+                counter.branch(cursor, &target_indices)?;
+            }
+            Instruction::BrTable(br_table_data) => {
+                counter.increment(category)?;
+                let labels = br_table_data
+                    .table
+                    .iter()
+                    .copied()
+                    .chain(Some(br_table_data.default));
+                let target_indices = counter.branch_targets(labels)?;
+                counter.branch(cursor, &target_indices)?;
+            }
+            Instruction::Return => {
+                counter.increment(category)?;
+                counter.branch(cursor, &[0])?;
+            }
+            _ => counter.increment(category)?,
+        }
+    }
+
+    Some(counter.finalized_blocks)
+}
+
+fn add_counts(counts: &mut CategoryCounts, other: CategoryCounts) {
+    for (category, count) in other {
+        *counts.entry(category).or_default() += count;
+    }
+}
+
+/// Returns the cost of the given instruction counts by category, if it adds up to the charged cost.
+fn categories(
+    counts: &CategoryCounts,
+    charged_cost: u32,
+    opcode_costs: &OpcodeCosts,
+) -> Option<BTreeMap<OpcodeCategory, u64>> {
+    let categories: BTreeMap<OpcodeCategory, u64> = counts
+        .iter()
+        .map(|(category, count)| {
+            (
+                *category,
+                count.saturating_mul(u64::from(opcode_costs.cost(*category))),
+            )
+        })
+        .filter(|(_, cost)| *cost > 0)
+        .collect();
+    let total = categories
+        .values()
+        .try_fold(0u64, |total, cost| total.checked_add(*cost))?;
+    if total == u64::from(charged_cost) {
+        Some(categories)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_wasm::{
+        builder,
+        elements::{BlockType, Instruction, Instructions, ValueType},
+    };
+
+    use super::*;
+    use crate::shared::{wasm_config::WasmConfig, wasm_prep::Preprocessor};
+
+    fn preprocessed_module(instructions: Vec<Instruction>) -> Module {
+        let module = builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(instructions))
+            .build()
+            .build()
+            .export()
+            .field("call")
+            .build()
+            .memory()
+            .build()
+            .build();
+        let module_bytes = parity_wasm::serialize(module).expect("should serialize");
+        Preprocessor::new(WasmConfig::default())
+            .preprocess(&module_bytes)
+            .expect("should preprocess")
+    }
+
+    #[test]
+    fn should_attribute_metered_blocks_to_categories() {
+        let opcode_costs = WasmConfig::default().opcode_costs();
+        let module = preprocessed_module(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::If(BlockType::NoResult),
+            Instruction::Nop,
+            Instruction::Else,
+            Instruction::Nop,
+            Instruction::Nop,
+            Instruction::End,
+            Instruction::I32Const(1),
+            Instruction::GrowMemory(0),
+            Instruction::Drop,
+            Instruction::End,
+        ]);
+
+        let (profiled_module, metered_blocks) =
+            profiled_module(&module, &opcode_costs).expect("should profile module");
+
+        let costs: Vec<u64> = metered_blocks
+            .iter()
+            .map(|metered_block| u64::from(metered_block.cost))
+            .collect();
+        let attributed: Vec<u64> = metered_blocks
+            .iter()
+            .map(|metered_block| {
+                metered_block
+                    .categories
+                    .as_ref()
+                    .expect("should attribute metered block")
+                    .values()
+                    .sum()
+            })
+            .collect();
+        assert_eq!(costs, attributed);
+
+        let mut totals = BTreeMap::new();
+        for metered_block in &metered_blocks {
+            for (category, cost) in metered_block.categories.as_ref().unwrap() {
+                *totals.entry(*category).or_insert(0u64) += cost;
+            }
+        }
+        assert_eq!(
+            totals.get(&OpcodeCategory::Const),
+            Some(&(3 * u64::from(opcode_costs.op_const)))
+        );
+        assert_eq!(
+            totals.get(&OpcodeCategory::Add),
+            Some(&u64::from(opcode_costs.add))
+        );
+        assert_eq!(
+            totals.get(&OpcodeCategory::Nop),
+            Some(&(3 * u64::from(opcode_costs.nop)))
+        );
+        assert_eq!(
+            totals.get(&OpcodeCategory::GrowMemory),
+            Some(&u64::from(opcode_costs.grow_memory))
+        );
+
+        // Every constant cost passed to the `gas` host function is replaced by a call site index.
+        let gas_function = gas_function_index(&profiled_module).unwrap();
+        let mut call_site_indices = Vec::new();
+        for body in profiled_module.code_section().unwrap().bodies() {
+            for pair in body.code().elements().windows(2) {
+                if let [Instruction::I32Const(value), Instruction::Call(function)] = pair {
+                    if *function == gas_function {
+                        call_site_indices.push(*value as u32);
+                    }
+                }
+            }
+        }
+        let dynamic_gas_markers = call_site_indices
+            .iter()
+            .filter(|value| **value == DYNAMIC_GAS_MARKER)
+            .count();
+        assert_eq!(dynamic_gas_markers, 1);
+        call_site_indices.retain(|value| *value != DYNAMIC_GAS_MARKER);
+        assert_eq!(
+            call_site_indices,
+            (0..metered_blocks.len() as u32).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_not_profile_module_importing_gas_function() {
+        let mut module_builder = builder::module();
+        let gas_signature = module_builder
+            .push_signature(builder::signature().with_param(ValueType::I32).build_sig());
+        let module = module_builder
+            .with_import(
+                builder::import()
+                    .module(GAS_MODULE_NAME)
+                    .field(GAS_FUNCTION_NAME)
+                    .external()
+                    .func(gas_signature)
+                    .build(),
+            )
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(1),
+                Instruction::Call(0),
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .export()
+            .field("call")
+            .internal()
+            .func(1)
+            .build()
+            .memory()
+            .build()
+            .build();
+        let module_bytes = parity_wasm::serialize(module).expect("should serialize");
+        let module = Preprocessor::new(WasmConfig::default())
+            .preprocess(&module_bytes)
+            .expect("should preprocess");
+
+        assert!(profiled_module(&module, &WasmConfig::default().opcode_costs()).is_none());
+    }
+
+    #[test]
+    fn should_not_attribute_metered_blocks_preprocessed_with_other_costs() {
+        let module = preprocessed_module(vec![
+            Instruction::I32Const(1),
+            Instruction::Drop,
+            Instruction::End,
+        ]);
+        let other_opcode_costs = OpcodeCosts {
+            op_const: 1,
+            ..WasmConfig::default().opcode_costs()
+        };
+
+        let (_, metered_blocks) =
+            profiled_module(&module, &other_opcode_costs).expect("should profile module");
+
+        assert!(!metered_blocks.is_empty());
+        assert!(metered_blocks
+            .iter()
+            .all(|metered_block| metered_block.categories.is_none()));
+    }
+}
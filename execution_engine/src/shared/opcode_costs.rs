@@ -1,7 +1,11 @@
 //! Support for Wasm opcode costs.
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
 
 use datasize::DataSize;
+use parity_wasm::elements::Instruction;
 use pwasm_utils::rules::{InstructionType, Metering, Set};
 use rand::{distributions::Standard, prelude::*, Rng};
 use serde::{Deserialize, Serialize};
@@ -90,6 +94,29 @@ pub struct OpcodeCosts {
 }
 
 impl OpcodeCosts {
+    /// Returns the cost of an opcode of the given category.
+    pub fn cost(&self, category: OpcodeCategory) -> u32 {
+        match category {
+            OpcodeCategory::Bit => self.bit,
+            OpcodeCategory::Add => self.add,
+            OpcodeCategory::Mul => self.mul,
+            OpcodeCategory::Div => self.div,
+            OpcodeCategory::Load => self.load,
+            OpcodeCategory::Store => self.store,
+            OpcodeCategory::Const => self.op_const,
+            OpcodeCategory::Local => self.local,
+            OpcodeCategory::Global => self.global,
+            OpcodeCategory::ControlFlow => self.control_flow,
+            OpcodeCategory::IntegerComparison => self.integer_comparison,
+            OpcodeCategory::Conversion => self.conversion,
+            OpcodeCategory::Unreachable => self.unreachable,
+            OpcodeCategory::Nop => self.nop,
+            OpcodeCategory::CurrentMemory => self.current_memory,
+            OpcodeCategory::GrowMemory => self.grow_memory,
+            OpcodeCategory::Regular => self.regular,
+        }
+    }
+
     /// Creates a set of charging rules for the Wasm executor.
     pub(crate) fn to_set(self) -> Set {
         let meterings = {
@@ -140,6 +167,103 @@ impl OpcodeCosts {
     }
 }
 
+/// A category of Wasm opcodes sharing a cost in [`OpcodeCosts`].
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OpcodeCategory {
+    /// Bit operations.
+    Bit,
+    /// Arithmetic add operations.
+    Add,
+    /// Mul operations.
+    Mul,
+    /// Div operations.
+    Div,
+    /// Memory load operations.
+    Load,
+    /// Memory store operations.
+    Store,
+    /// Const operations.
+    #[serde(rename = "const")]
+    Const,
+    /// Local operations.
+    Local,
+    /// Global operations.
+    Global,
+    /// Control flow operations.
+    ControlFlow,
+    /// Integer comparison operations.
+    IntegerComparison,
+    /// Conversion operations.
+    Conversion,
+    /// Unreachable operation.
+    Unreachable,
+    /// Nop operation.
+    Nop,
+    /// Get current memory operation.
+    CurrentMemory,
+    /// Grow memory operation.
+    GrowMemory,
+    /// All other operations.
+    Regular,
+}
+
+impl OpcodeCategory {
+    /// Returns the category of the given instruction.
+    pub fn of(instruction: &Instruction) -> Self {
+        match InstructionType::op(instruction) {
+            InstructionType::Bit => OpcodeCategory::Bit,
+            InstructionType::Add => OpcodeCategory::Add,
+            InstructionType::Mul => OpcodeCategory::Mul,
+            InstructionType::Div => OpcodeCategory::Div,
+            InstructionType::Load => OpcodeCategory::Load,
+            InstructionType::Store => OpcodeCategory::Store,
+            InstructionType::Const => OpcodeCategory::Const,
+            InstructionType::Local => OpcodeCategory::Local,
+            InstructionType::Global => OpcodeCategory::Global,
+            InstructionType::ControlFlow => OpcodeCategory::ControlFlow,
+            InstructionType::IntegerComparison => OpcodeCategory::IntegerComparison,
+            InstructionType::Conversion => OpcodeCategory::Conversion,
+            InstructionType::Unreachable => OpcodeCategory::Unreachable,
+            InstructionType::Nop => OpcodeCategory::Nop,
+            InstructionType::CurrentMemory => OpcodeCategory::CurrentMemory,
+            InstructionType::GrowMemory => OpcodeCategory::GrowMemory,
+            // Instructions without a cost of their own are charged the regular cost.
+            _ => OpcodeCategory::Regular,
+        }
+    }
+
+    /// Returns the name of the category, as used for the corresponding field of serialized
+    /// [`OpcodeCosts`].
+    pub fn name(self) -> &'static str {
+        match self {
+            OpcodeCategory::Bit => "bit",
+            OpcodeCategory::Add => "add",
+            OpcodeCategory::Mul => "mul",
+            OpcodeCategory::Div => "div",
+            OpcodeCategory::Load => "load",
+            OpcodeCategory::Store => "store",
+            OpcodeCategory::Const => "const",
+            OpcodeCategory::Local => "local",
+            OpcodeCategory::Global => "global",
+            OpcodeCategory::ControlFlow => "control_flow",
+            OpcodeCategory::IntegerComparison => "integer_comparison",
+            OpcodeCategory::Conversion => "conversion",
+            OpcodeCategory::Unreachable => "unreachable",
+            OpcodeCategory::Nop => "nop",
+            OpcodeCategory::CurrentMemory => "current_memory",
+            OpcodeCategory::GrowMemory => "grow_memory",
+            OpcodeCategory::Regular => "regular",
+        }
+    }
+}
+
+impl Display for OpcodeCategory {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl Default for OpcodeCosts {
     fn default() -> Self {
         OpcodeCosts {
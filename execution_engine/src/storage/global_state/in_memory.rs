@@ -1,13 +1,13 @@
 use std::{ops::Deref, sync::Arc};
 
 use casper_hashing::Digest;
-use casper_types::{Key, StoredValue};
+use casper_types::{bytesrepr::ToBytes, Key, StoredValue};
 
 use crate::{
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::{
         error::{self, in_memory},
-        global_state::{commit, StateProvider, StateReader},
+        global_state::{commit, prune, PruneGuard, StateProvider, StateReader},
         store::Store,
         transaction_source::{
             in_memory::{
@@ -17,10 +17,10 @@ use crate::{
         },
         trie::{merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie},
         trie_store::{
-            in_memory::InMemoryTrieStore,
+            in_memory::{InMemoryMarkStore, InMemoryTrieStore},
             operations::{
-                self, keys_with_prefix, missing_trie_keys, put_trie, read, read_with_proof,
-                ReadResult, WriteResult,
+                self, keys_with_prefix, keys_with_prefix_after, missing_trie_keys, put_trie, read,
+                read_with_proof, ReadResult, WriteResult,
             },
         },
     },
@@ -36,6 +36,8 @@ pub struct InMemoryGlobalState {
     pub(crate) trie_store: Arc<InMemoryTrieStore>,
     /// Empty state root hash.
     pub(crate) empty_root_hash: Digest,
+    /// Tracks the tries written while the trie store is being pruned.
+    pub(crate) prune_guard: PruneGuard,
 }
 
 /// Represents a "view" of global state at a particular root hash.
@@ -75,6 +77,7 @@ impl InMemoryGlobalState {
             trie_store,
 
             empty_root_hash,
+            prune_guard: PruneGuard::default(),
         }
     }
 
@@ -195,6 +198,28 @@ impl StateReader<Key, StoredValue> for InMemoryGlobalStateView {
         txn.commit()?;
         Ok(ret)
     }
+
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start_after: Option<&Key>,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let start_after = start_after.map(ToBytes::to_bytes).transpose()?;
+        let txn = self.environment.create_read_txn()?;
+        let ret = keys_with_prefix_after::<Key, StoredValue, _, _>(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            start_after.as_deref(),
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(ret)
+    }
 }
 
 impl StateProvider for InMemoryGlobalState {
@@ -221,14 +246,15 @@ impl StateProvider for InMemoryGlobalState {
         prestate_hash: Digest,
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
-        commit::<InMemoryEnvironment, InMemoryTrieStore, _, Self::Error>(
-            &self.environment,
-            &self.trie_store,
-            correlation_id,
-            prestate_hash,
-            effects,
-        )
-        .map_err(Into::into)
+        self.prune_guard.guard_write(|| {
+            commit::<InMemoryEnvironment, InMemoryTrieStore, _, Self::Error>(
+                &self.environment,
+                &self.trie_store,
+                correlation_id,
+                prestate_hash,
+                effects,
+            )
+        })
     }
 
     fn empty_root(&self) -> Digest {
@@ -251,16 +277,18 @@ impl StateProvider for InMemoryGlobalState {
         correlation_id: CorrelationId,
         trie: &Trie<Key, StoredValue>,
     ) -> Result<Digest, Self::Error> {
-        let mut txn = self.environment.create_read_write_txn()?;
-        let trie_hash = put_trie::<
-            Key,
-            StoredValue,
-            InMemoryReadWriteTransaction,
-            InMemoryTrieStore,
-            Self::Error,
-        >(correlation_id, &mut txn, &self.trie_store, trie)?;
-        txn.commit()?;
-        Ok(trie_hash)
+        self.prune_guard.guard_write(|| {
+            let mut txn = self.environment.create_read_write_txn()?;
+            let trie_hash = put_trie::<
+                Key,
+                StoredValue,
+                InMemoryReadWriteTransaction,
+                InMemoryTrieStore,
+                Self::Error,
+            >(correlation_id, &mut txn, &self.trie_store, trie)?;
+            txn.commit()?;
+            Ok(trie_hash)
+        })
     }
 
     /// Finds all of the keys of missing descendant `Trie<Key,StoredValue>` values
@@ -281,6 +309,26 @@ impl StateProvider for InMemoryGlobalState {
         txn.commit()?;
         Ok(missing_descendants)
     }
+
+    fn prune_trie_store(
+        &self,
+        correlation_id: CorrelationId,
+        mut retained_state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<usize, Self::Error> {
+        // The empty root is needed to commit genesis.
+        retained_state_roots.push(self.empty_root_hash);
+        let mark_store = InMemoryMarkStore::new(&self.environment);
+        prune::<InMemoryEnvironment, InMemoryTrieStore, InMemoryMarkStore, Self::Error>(
+            &self.environment,
+            &self.trie_store,
+            &mark_store,
+            &self.prune_guard,
+            correlation_id,
+            retained_state_roots,
+            batch_size,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -422,6 +470,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn prune_trie_store_deletes_state_not_retained() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        let deleted_count = state
+            .prune_trie_store(correlation_id, vec![updated_hash], 1)
+            .unwrap();
+        assert!(deleted_count > 0);
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(state.empty_root()).unwrap().is_some());
+        assert_eq!(
+            state
+                .missing_trie_keys(correlation_id, vec![updated_hash])
+                .unwrap(),
+            Vec::new()
+        );
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        let deleted_count = state
+            .prune_trie_store(correlation_id, vec![updated_hash], 1)
+            .unwrap();
+        assert_eq!(deleted_count, 0);
+    }
+
     #[test]
     fn initial_state_has_the_expected_hash() {
         let correlation_id = CorrelationId::new();
@@ -1,20 +1,23 @@
 use std::{ops::Deref, sync::Arc};
 
+use lmdb::DatabaseFlags;
+
 use casper_hashing::Digest;
-use casper_types::{Key, StoredValue};
+use casper_types::{bytesrepr::ToBytes, Key, StoredValue};
 
 use crate::{
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::{
         error,
-        global_state::{commit, StateProvider, StateReader},
+        global_state::{commit, prune, PruneGuard, StateProvider, StateReader},
         store::Store,
         transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
         trie::{merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie},
         trie_store::{
-            lmdb::LmdbTrieStore,
+            lmdb::{LmdbMarkStore, LmdbTrieStore},
             operations::{
-                keys_with_prefix, missing_trie_keys, put_trie, read, read_with_proof, ReadResult,
+                keys_with_prefix, keys_with_prefix_after, missing_trie_keys, put_trie, read,
+                read_with_proof, ReadResult,
             },
         },
     },
@@ -29,6 +32,8 @@ pub struct LmdbGlobalState {
     // TODO: make this a lazy-static
     /// Empty root hash used for a new trie.
     pub(crate) empty_root_hash: Digest,
+    /// Tracks the tries written while the trie store is being pruned.
+    pub(crate) prune_guard: PruneGuard,
 }
 
 /// Represents a "view" of global state at a particular root hash.
@@ -69,6 +74,7 @@ impl LmdbGlobalState {
             environment,
             trie_store,
             empty_root_hash,
+            prune_guard: PruneGuard::default(),
         }
     }
 }
@@ -147,6 +153,28 @@ impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
         txn.commit()?;
         Ok(ret)
     }
+
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start_after: Option<&Key>,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let start_after = start_after.map(ToBytes::to_bytes).transpose()?;
+        let txn = self.environment.create_read_txn()?;
+        let ret = keys_with_prefix_after::<Key, StoredValue, _, _>(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            start_after.as_deref(),
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(ret)
+    }
 }
 
 impl StateProvider for LmdbGlobalState {
@@ -172,14 +200,15 @@ impl StateProvider for LmdbGlobalState {
         prestate_hash: Digest,
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
-        commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
-            &self.environment,
-            &self.trie_store,
-            correlation_id,
-            prestate_hash,
-            effects,
-        )
-        .map_err(Into::into)
+        self.prune_guard.guard_write(|| {
+            commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
+                &self.environment,
+                &self.trie_store,
+                correlation_id,
+                prestate_hash,
+                effects,
+            )
+        })
     }
 
     fn empty_root(&self) -> Digest {
@@ -202,16 +231,18 @@ impl StateProvider for LmdbGlobalState {
         correlation_id: CorrelationId,
         trie: &Trie<Key, StoredValue>,
     ) -> Result<Digest, Self::Error> {
-        let mut txn = self.environment.create_read_write_txn()?;
-        let trie_hash = put_trie::<
-            Key,
-            StoredValue,
-            lmdb::RwTransaction,
-            LmdbTrieStore,
-            Self::Error,
-        >(correlation_id, &mut txn, &self.trie_store, trie)?;
-        txn.commit()?;
-        Ok(trie_hash)
+        self.prune_guard.guard_write(|| {
+            let mut txn = self.environment.create_read_write_txn()?;
+            let trie_hash = put_trie::<
+                Key,
+                StoredValue,
+                lmdb::RwTransaction,
+                LmdbTrieStore,
+                Self::Error,
+            >(correlation_id, &mut txn, &self.trie_store, trie)?;
+            txn.commit()?;
+            Ok(trie_hash)
+        })
     }
 
     /// Finds all of the keys of missing descendant `Trie<K,V>` values
@@ -231,6 +262,30 @@ impl StateProvider for LmdbGlobalState {
         txn.commit()?;
         Ok(missing_descendants)
     }
+
+    fn prune_trie_store(
+        &self,
+        correlation_id: CorrelationId,
+        mut retained_state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<usize, Self::Error> {
+        // The empty root is needed to commit genesis.
+        retained_state_roots.push(self.empty_root_hash);
+        let mark_store = LmdbMarkStore::new(&self.environment, DatabaseFlags::empty())?;
+        let deleted_count = prune::<LmdbEnvironment, LmdbTrieStore, LmdbMarkStore, Self::Error>(
+            &self.environment,
+            &self.trie_store,
+            &mark_store,
+            &self.prune_guard,
+            correlation_id,
+            retained_state_roots,
+            batch_size,
+        )?;
+        if self.environment.is_manual_sync_enabled() {
+            self.environment.sync()?;
+        }
+        Ok(deleted_count)
+    }
 }
 
 #[cfg(test)]
@@ -411,4 +466,54 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn prune_trie_store_deletes_state_not_retained() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        // A mark left behind by an interrupted prune doesn't retain the original state.
+        let mark_store = LmdbMarkStore::new(&state.environment, DatabaseFlags::empty()).unwrap();
+        let mut txn = state.environment.create_read_write_txn().unwrap();
+        mark_store.put(&mut txn, &root_hash, &()).unwrap();
+        txn.commit().unwrap();
+
+        let deleted_count = state
+            .prune_trie_store(correlation_id, vec![updated_hash], 1)
+            .unwrap();
+        assert!(deleted_count > 0);
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(state.empty_root()).unwrap().is_some());
+        assert_eq!(
+            state
+                .missing_trie_keys(correlation_id, vec![updated_hash])
+                .unwrap(),
+            Vec::new()
+        );
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        let deleted_count = state
+            .prune_trie_store(correlation_id, vec![updated_hash], 1)
+            .unwrap();
+        assert_eq!(deleted_count, 0);
+    }
 }
@@ -6,7 +6,11 @@ pub mod in_memory;
 /// Lmdb implementation of global state.
 pub mod lmdb;
 
-use std::hash::BuildHasher;
+use std::{
+    hash::BuildHasher,
+    mem,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
 
 use tracing::error;
 
@@ -20,11 +24,14 @@ use crate::{
         transform::{self, Transform},
     },
     storage::{
-        transaction_source::{Transaction, TransactionSource},
+        transaction_source::{Transaction, TransactionSource, Writable},
         trie::{merkle_proof::TrieMerkleProof, Trie},
         trie_store::{
-            operations::{read, write, ReadResult, WriteResult},
-            TrieStore,
+            operations::{
+                mark_reachable_trie_keys, read, sweep_unreachable_tries, write, ReadResult,
+                SweepResult, WriteResult,
+            },
+            MarkStore, TrieStore,
         },
     },
 };
@@ -50,6 +57,16 @@ pub trait StateReader<K, V> {
         correlation_id: CorrelationId,
         prefix: &[u8],
     ) -> Result<Vec<K>, Self::Error>;
+
+    /// Returns up to `limit` keys in the trie matching `prefix`, in ascending order of their
+    /// serialized bytes, starting after `start_after` if given.
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        start_after: Option<&K>,
+        limit: usize,
+    ) -> Result<Vec<K>, Self::Error>;
 }
 
 /// An error emitted by the execution engine on commit
@@ -115,6 +132,185 @@ pub trait StateProvider {
         correlation_id: CorrelationId,
         trie_keys: Vec<Digest>,
     ) -> Result<Vec<Digest>, Self::Error>;
+
+    /// Deletes all tries which are not reachable from `retained_state_roots`, visiting up to
+    /// `batch_size` trie keys per write transaction.  Returns the number of deleted tries.
+    fn prune_trie_store(
+        &self,
+        correlation_id: CorrelationId,
+        retained_state_roots: Vec<Digest>,
+        batch_size: usize,
+    ) -> Result<usize, Self::Error>;
+}
+
+/// Keeps track of the tries written to the store while it is being pruned, so that the sweep
+/// retains them even if they were unreachable when marking started.
+#[derive(Debug, Default)]
+pub struct PruneGuard {
+    /// Held for the duration of a prune, so that only one prune runs at a time.
+    prune_lock: Mutex<()>,
+    /// The keys of the tries written since the current prune started, or `None` if not pruning.
+    written_trie_keys: Mutex<Option<Vec<Digest>>>,
+}
+
+impl PruneGuard {
+    /// Runs `write`, which writes a trie and its descendants to the store and returns its key,
+    /// recording the key if a prune is in progress.
+    ///
+    /// Sweeping a batch of the store waits for `write` to finish, and vice versa.
+    pub fn guard_write<E, F>(&self, write: F) -> Result<Digest, E>
+    where
+        F: FnOnce() -> Result<Digest, E>,
+    {
+        let mut written_trie_keys = self.lock_written_trie_keys();
+        let trie_key = write()?;
+        if let Some(trie_keys) = written_trie_keys.as_mut() {
+            trie_keys.push(trie_key);
+        }
+        Ok(trie_key)
+    }
+
+    // The guarded data stays consistent even if a writer panicked, so poisoning is ignored.
+    fn lock_written_trie_keys(&self) -> MutexGuard<'_, Option<Vec<Digest>>> {
+        self.written_trie_keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Deletes all tries from `store` which are not reachable from `retained_state_roots`.
+///
+/// The reachable tries are marked in `mark_store`, and the store is then swept, both in batches of
+/// `batch_size` trie keys, each in its own write transaction, so that commits can proceed between
+/// batches and no transaction is held open for the whole prune.  Tries written by guarded commits
+/// since marking started are marked before each batch is swept.  The marks are cleared before and
+/// after pruning.
+pub fn prune<'a, R, S, M, E>(
+    environment: &'a R,
+    store: &S,
+    mark_store: &M,
+    prune_guard: &PruneGuard,
+    correlation_id: CorrelationId,
+    retained_state_roots: Vec<Digest>,
+    batch_size: usize,
+) -> Result<usize, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<R::Error>,
+    M: MarkStore<Handle = S::Handle>,
+    M::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<M::Error> + From<bytesrepr::Error>,
+{
+    let _prune_lock = prune_guard
+        .prune_lock
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    *prune_guard.lock_written_trie_keys() = Some(Vec::new());
+    // Marks left behind by an interrupted prune could retain unreachable tries.
+    let result = clear_marks::<R, M, E>(environment, mark_store).and_then(|()| {
+        mark_and_sweep::<R, S, M, E>(
+            environment,
+            store,
+            mark_store,
+            prune_guard,
+            correlation_id,
+            retained_state_roots,
+            batch_size.max(1),
+        )
+    });
+    *prune_guard.lock_written_trie_keys() = None;
+    let cleared = clear_marks::<R, M, E>(environment, mark_store);
+    let deleted_count = result?;
+    cleared?;
+    Ok(deleted_count)
+}
+
+fn clear_marks<'a, R, M, E>(environment: &'a R, mark_store: &M) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = M::Handle>,
+    M: MarkStore,
+    E: From<R::Error>,
+{
+    let mut txn = environment.create_read_write_txn()?;
+    txn.clear(mark_store.handle())?;
+    txn.commit()?;
+    Ok(())
+}
+
+fn mark_and_sweep<'a, R, S, M, E>(
+    environment: &'a R,
+    store: &S,
+    mark_store: &M,
+    prune_guard: &PruneGuard,
+    correlation_id: CorrelationId,
+    retained_state_roots: Vec<Digest>,
+    batch_size: usize,
+) -> Result<usize, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<R::Error>,
+    M: MarkStore<Handle = S::Handle>,
+    M::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<M::Error> + From<bytesrepr::Error>,
+{
+    let mut trie_keys_to_visit = retained_state_roots;
+    while !trie_keys_to_visit.is_empty() {
+        let mut txn = environment.create_read_write_txn()?;
+        mark_reachable_trie_keys::<Key, StoredValue, _, _, _, E>(
+            correlation_id,
+            &mut txn,
+            store,
+            mark_store,
+            &mut trie_keys_to_visit,
+            batch_size,
+        )?;
+        txn.commit()?;
+    }
+
+    let mut deleted_count = 0;
+    let mut after = None;
+    loop {
+        let mut written_trie_keys = prune_guard.lock_written_trie_keys();
+        let mut trie_keys_to_visit = written_trie_keys
+            .as_mut()
+            .map(mem::take)
+            .unwrap_or_default();
+        let mut txn = environment.create_read_write_txn()?;
+        // Most descendants of the newly written tries are marked already, so these are marked in
+        // the same transaction as the batch they must be retained from.
+        while !trie_keys_to_visit.is_empty() {
+            mark_reachable_trie_keys::<Key, StoredValue, _, _, _, E>(
+                correlation_id,
+                &mut txn,
+                store,
+                mark_store,
+                &mut trie_keys_to_visit,
+                batch_size,
+            )?;
+        }
+        let SweepResult {
+            last_visited,
+            deleted_count: batch_deleted_count,
+        } = sweep_unreachable_tries::<Key, StoredValue, _, _, _, E>(
+            correlation_id,
+            &mut txn,
+            store,
+            mark_store,
+            after,
+            batch_size,
+        )?;
+        txn.commit()?;
+        drop(written_trie_keys);
+
+        deleted_count += batch_deleted_count;
+        match last_visited {
+            Some(trie_key) => after = Some(trie_key),
+            None => break,
+        }
+    }
+    Ok(deleted_count)
 }
 
 /// Commit `effects` to the store.
@@ -51,4 +51,16 @@ pub trait Store<K, V> {
         txn.write(handle, &key.to_bytes()?, &value.to_bytes()?)
             .map_err(Into::into)
     }
+
+    /// Deletes the value at `key` within a transaction, returning whether it was present, or an
+    /// error of type `Self::Error` if that fails.
+    fn delete<T>(&self, txn: &mut T, key: &K) -> Result<bool, Self::Error>
+    where
+        T: Writable<Handle = Self::Handle>,
+        K: ToBytes,
+        Self::Error: From<T::Error>,
+    {
+        let handle = self.handle();
+        txn.delete(handle, &key.to_bytes()?).map_err(Into::into)
+    }
 }
@@ -55,6 +55,15 @@ impl Readable for InMemoryReadTransaction {
         };
         Ok(sub_view.get(&Bytes::from(key)).cloned())
     }
+
+    fn read_keys(
+        &self,
+        handle: Self::Handle,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Bytes>, Self::Error> {
+        Ok(read_keys(&self.view, handle, after, limit))
+    }
 }
 
 /// A read-write transaction for the in-memory trie store.
@@ -102,6 +111,15 @@ impl<'a> Readable for InMemoryReadWriteTransaction<'a> {
         };
         Ok(sub_view.get(&Bytes::from(key)).cloned())
     }
+
+    fn read_keys(
+        &self,
+        handle: Self::Handle,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Bytes>, Self::Error> {
+        Ok(read_keys(&self.view, handle, after, limit))
+    }
 }
 
 impl<'a> Writable for InMemoryReadWriteTransaction<'a> {
@@ -110,6 +128,43 @@ impl<'a> Writable for InMemoryReadWriteTransaction<'a> {
         sub_view.insert(Bytes::from(key), Bytes::from(value));
         Ok(())
     }
+
+    fn delete(&mut self, handle: Self::Handle, key: &[u8]) -> Result<bool, Self::Error> {
+        let removed = self
+            .view
+            .get_mut(&handle)
+            .and_then(|sub_view| sub_view.remove(&Bytes::from(key)));
+        Ok(removed.is_some())
+    }
+
+    fn clear(&mut self, handle: Self::Handle) -> Result<(), Self::Error> {
+        // The sub-view is emptied rather than removed, so that committing replaces the stored one.
+        if let Some(sub_view) = self.view.get_mut(&handle) {
+            sub_view.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Returns up to `limit` keys of the given sub-view in ascending order, starting after `after`.
+fn read_keys(
+    view: &HashMap<Option<String>, BytesMap>,
+    handle: Option<String>,
+    after: Option<&[u8]>,
+    limit: usize,
+) -> Vec<Bytes> {
+    let sub_view = match view.get(&handle) {
+        Some(view) => view,
+        None => return Vec::new(),
+    };
+    let mut keys: Vec<Bytes> = sub_view
+        .keys()
+        .filter(|key| after.map_or(true, |after| key.as_slice() > after))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.truncate(limit);
+    keys
 }
 
 /// An environment for the in-memory trie store.
@@ -2,7 +2,7 @@ use std::path::Path;
 
 use casper_types::bytesrepr::Bytes;
 use lmdb::{
-    self, Database, Environment, EnvironmentFlags, RoTransaction, RwTransaction, WriteFlags,
+    self, Cursor, Database, Environment, EnvironmentFlags, RoTransaction, RwTransaction, WriteFlags,
 };
 
 use crate::storage::{
@@ -32,6 +32,15 @@ impl<'a> Readable for RoTransaction<'a> {
             Err(e) => Err(e),
         }
     }
+
+    fn read_keys(
+        &self,
+        handle: Self::Handle,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Bytes>, Self::Error> {
+        read_keys(self, handle, after, limit)
+    }
 }
 
 impl<'a> Transaction for RwTransaction<'a> {
@@ -52,6 +61,15 @@ impl<'a> Readable for RwTransaction<'a> {
             Err(e) => Err(e),
         }
     }
+
+    fn read_keys(
+        &self,
+        handle: Self::Handle,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Bytes>, Self::Error> {
+        read_keys(self, handle, after, limit)
+    }
 }
 
 impl<'a> Writable for RwTransaction<'a> {
@@ -59,6 +77,45 @@ impl<'a> Writable for RwTransaction<'a> {
         self.put(handle, &key, &value, WriteFlags::empty())
             .map_err(Into::into)
     }
+
+    fn delete(&mut self, handle: Self::Handle, key: &[u8]) -> Result<bool, Self::Error> {
+        match self.del(handle, &key, None) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn clear(&mut self, handle: Self::Handle) -> Result<(), Self::Error> {
+        self.clear_db(handle)
+    }
+}
+
+/// Returns up to `limit` keys of the given database in ascending order, starting after `after`.
+fn read_keys<T: lmdb::Transaction>(
+    txn: &T,
+    handle: Database,
+    after: Option<&[u8]>,
+    limit: usize,
+) -> Result<Vec<Bytes>, lmdb::Error> {
+    let mut cursor = txn.open_ro_cursor(handle)?;
+    // Note: `iter_start` and `iter_from` have undocumented panics if there is no key to start at,
+    //       so we rely on a fresh cursor's iterator being at the start instead, and check for a key
+    //       at or after `after` before calling `iter_from`.
+    let iter = match after {
+        Some(after) => match cursor.get(Some(after), None, lmdb_sys::MDB_SET_RANGE) {
+            Ok(_) => cursor.iter_from(after),
+            Err(lmdb::Error::NotFound) => return Ok(vec![]),
+            Err(error) => return Err(error),
+        },
+        None => cursor.iter(),
+    };
+    Ok(iter
+        .map(|(key, _value)| key)
+        .filter(|key| after.map_or(true, |after| *key > after))
+        .take(limit)
+        .map(Bytes::from)
+        .collect())
 }
 
 /// The environment for an LMDB-backed trie store.
@@ -29,12 +29,28 @@ pub trait Transaction: Sized {
 pub trait Readable: Transaction {
     /// Returns the value from the corresponding key from a given [`Transaction::Handle`].
     fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Bytes>, Self::Error>;
+
+    /// Returns up to `limit` keys from a given [`Transaction::Handle`] in ascending order, starting
+    /// with the first key greater than `after`, or with the first key if `after` is `None`.
+    fn read_keys(
+        &self,
+        handle: Self::Handle,
+        after: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Vec<Bytes>, Self::Error>;
 }
 
 /// A transaction with the capability to write to a given [`Handle`](Transaction::Handle).
 pub trait Writable: Transaction {
     /// Inserts a key-value pair into a given [`Transaction::Handle`].
     fn write(&mut self, handle: Self::Handle, key: &[u8], value: &[u8]) -> Result<(), Self::Error>;
+
+    /// Removes the key-value pair under `key` from a given [`Transaction::Handle`], returning
+    /// whether it was present.
+    fn delete(&mut self, handle: Self::Handle, key: &[u8]) -> Result<bool, Self::Error>;
+
+    /// Removes all key-value pairs from a given [`Transaction::Handle`].
+    fn clear(&mut self, handle: Self::Handle) -> Result<(), Self::Error>;
 }
 
 /// A source of transactions e.g. values that implement [`Readable`]
@@ -1,6 +1,6 @@
 //! An in-memory trie store, intended to be used for testing.
 
-use super::{Digest, Store, Trie, TrieStore, NAME};
+use super::{Digest, MarkStore, Store, Trie, TrieStore, MARK_STORE_NAME, NAME};
 use crate::storage::{error::in_memory::Error, transaction_source::in_memory::InMemoryEnvironment};
 
 /// An in-memory trie store.
@@ -31,6 +31,31 @@ impl<K, V> Store<Digest, Trie<K, V>> for InMemoryTrieStore {
 
 impl<K, V> TrieStore<K, V> for InMemoryTrieStore {}
 
+/// An in-memory store of the hashes of the tries marked as reachable while pruning.
+pub struct InMemoryMarkStore {
+    name: String,
+}
+
+impl InMemoryMarkStore {
+    pub(crate) fn new(_env: &InMemoryEnvironment) -> Self {
+        InMemoryMarkStore {
+            name: String::from(MARK_STORE_NAME),
+        }
+    }
+}
+
+impl Store<Digest, ()> for InMemoryMarkStore {
+    type Error = Error;
+
+    type Handle = Option<String>;
+
+    fn handle(&self) -> Self::Handle {
+        Some(self.name.to_owned())
+    }
+}
+
+impl MarkStore for InMemoryMarkStore {}
+
 #[cfg(test)]
 mod test {
     use casper_hashing::Digest;
@@ -113,7 +113,7 @@ use crate::storage::{
     store::Store,
     transaction_source::lmdb::LmdbEnvironment,
     trie::Trie,
-    trie_store::{self, TrieStore},
+    trie_store::{self, MarkStore, TrieStore},
 };
 
 /// An LMDB-backed trie store.
@@ -161,3 +161,31 @@ impl<K, V> Store<Digest, Trie<K, V>> for LmdbTrieStore {
 }
 
 impl<K, V> TrieStore<K, V> for LmdbTrieStore {}
+
+/// An LMDB-backed store of the hashes of the tries marked as reachable while pruning.
+#[derive(Debug, Clone)]
+pub struct LmdbMarkStore {
+    db: Database,
+}
+
+impl LmdbMarkStore {
+    /// Constructor for `LmdbMarkStore`, which opens the existing store if there is one.
+    pub fn new(env: &LmdbEnvironment, flags: DatabaseFlags) -> Result<Self, error::Error> {
+        let db = env
+            .env()
+            .create_db(Some(trie_store::MARK_STORE_NAME), flags)?;
+        Ok(LmdbMarkStore { db })
+    }
+}
+
+impl Store<Digest, ()> for LmdbMarkStore {
+    type Error = error::Error;
+
+    type Handle = Database;
+
+    fn handle(&self) -> Self::Handle {
+        self.db
+    }
+}
+
+impl MarkStore for LmdbMarkStore {}
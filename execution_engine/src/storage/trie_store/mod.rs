@@ -13,6 +13,11 @@ use casper_hashing::Digest;
 use crate::storage::{store::Store, trie::Trie};
 
 const NAME: &str = "TRIE_STORE";
+const MARK_STORE_NAME: &str = "TRIE_STORE_PRUNE_MARKS";
 
 /// An entity which persists [`Trie`] values at their hashes.
 pub trait TrieStore<K, V>: Store<Digest, Trie<K, V>> {}
+
+/// An entity which persists the hashes of the [`Trie`] values found to be reachable while pruning
+/// a [`TrieStore`].
+pub trait MarkStore: Store<Digest, ()> {}
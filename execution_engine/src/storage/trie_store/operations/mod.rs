@@ -21,7 +21,7 @@ use crate::{
             merkle_proof::{TrieMerkleProof, TrieMerkleProofStep},
             Parents, Pointer, PointerBlock, Trie, RADIX, USIZE_EXCEEDS_U8,
         },
-        trie_store::TrieStore,
+        trie_store::{MarkStore, TrieStore},
     },
 };
 
@@ -296,6 +296,98 @@ where
     Ok(missing_descendants)
 }
 
+/// Marks the keys of the tries reachable from `trie_keys_to_visit` and present in the store, by
+/// writing them to `mark_store`, visiting up to `batch_size` trie keys.
+///
+/// Visited tries have their children added to `trie_keys_to_visit`, so marking can be resumed in a
+/// new transaction until it is empty.  Tries already marked are not descended into again.
+pub fn mark_reachable_trie_keys<K, V, T, S, M, E>(
+    _correlation_id: CorrelationId,
+    txn: &mut T,
+    store: &S,
+    mark_store: &M,
+    trie_keys_to_visit: &mut Vec<Digest>,
+    batch_size: usize,
+) -> Result<(), E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + std::fmt::Debug,
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    M: MarkStore<Handle = S::Handle>,
+    M::Error: From<T::Error>,
+    E: From<S::Error> + From<M::Error> + From<bytesrepr::Error>,
+{
+    for _ in 0..batch_size {
+        let trie_key = match trie_keys_to_visit.pop() {
+            Some(trie_key) => trie_key,
+            None => break,
+        };
+        if mark_store.get(txn, &trie_key)?.is_some() {
+            continue;
+        }
+        let maybe_retrieved_trie: Option<Trie<K, V>> = store.get(txn, &trie_key)?;
+        match maybe_retrieved_trie {
+            // Missing tries have nothing to retain
+            None => continue,
+            Some(Trie::Leaf { .. }) => (),
+            Some(Trie::Node { pointer_block }) => {
+                for (_, pointer) in pointer_block.as_indexed_pointers() {
+                    trie_keys_to_visit.push(pointer.into_hash())
+                }
+            }
+            Some(Trie::Extension { pointer, .. }) => trie_keys_to_visit.push(pointer.into_hash()),
+        }
+        mark_store.put(txn, &trie_key, &())?;
+    }
+    Ok(())
+}
+
+/// The outcome of sweeping a batch of the trie store.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SweepResult {
+    /// The greatest trie key visited in this batch, or `None` if the sweep is complete.
+    pub last_visited: Option<Digest>,
+    /// The number of tries deleted in this batch.
+    pub deleted_count: usize,
+}
+
+/// Visits up to `batch_size` trie keys in ascending order, starting after `after`, and deletes
+/// the tries whose keys are not marked in `mark_store`.
+pub fn sweep_unreachable_tries<K, V, T, S, M, E>(
+    _correlation_id: CorrelationId,
+    txn: &mut T,
+    store: &S,
+    mark_store: &M,
+    after: Option<Digest>,
+    batch_size: usize,
+) -> Result<SweepResult, E>
+where
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    M: MarkStore<Handle = S::Handle>,
+    M::Error: From<T::Error>,
+    E: From<S::Error> + From<M::Error> + From<T::Error> + From<bytesrepr::Error>,
+{
+    let after_bytes = after.as_ref().map(|after| after.as_ref());
+    let keys = txn.read_keys(store.handle(), after_bytes, batch_size)?;
+    let mut last_visited = None;
+    let mut deleted_count = 0;
+    for key_bytes in keys {
+        let trie_key: Digest = bytesrepr::deserialize(key_bytes.into())?;
+        if mark_store.get(txn, &trie_key)?.is_none() && store.delete(txn, &trie_key)? {
+            deleted_count += 1;
+        }
+        last_visited = Some(trie_key);
+    }
+    Ok(SweepResult {
+        last_visited,
+        deleted_count,
+    })
+}
+
 #[cfg(test)]
 pub fn check_integrity<K, V, T, S, E>(
     _correlation_id: CorrelationId,
@@ -1258,3 +1350,155 @@ where
         state: init_state,
     }
 }
+
+/// Returns the iterator over the keys in the trie which serialize to bytes greater than
+/// `start_after`, in ascending order of their serialized bytes.
+///
+/// Rather than skipping over the keys up to `start_after`, the iterator starts by descending the
+/// trie along `start_after`, so resuming an iteration from its last key is cheap.
+///
+/// The root should be the apex of the trie.
+pub fn keys_after<'a, 'b, K, V, T, S>(
+    _correlation_id: CorrelationId,
+    txn: &'b T,
+    store: &'a S,
+    root: &Digest,
+    start_after: &[u8],
+) -> KeysIterator<'a, 'b, K, V, T, S>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error> + From<bytesrepr::Error>,
+{
+    let mut visited = vec![];
+    let mut state = KeysIteratorState::Ok;
+    let mut path = vec![];
+    let mut maybe_current = match store.get(txn, root) {
+        Ok(maybe_root) => maybe_root,
+        Err(e) => {
+            state = KeysIteratorState::ReturnError(e);
+            None
+        }
+    };
+
+    // Every subtrie which lies entirely after `start_after` is left on the visited stack, in the
+    // order the iterator pops them.
+    while let Some(current) = maybe_current.take() {
+        let maybe_next_hash = match &current {
+            Trie::Leaf { key, .. } => match key.to_bytes() {
+                Ok(key_bytes) => {
+                    if key_bytes.as_slice() > start_after {
+                        visited.push(VisitedTrieNode {
+                            trie: current,
+                            maybe_index: None,
+                            path,
+                        });
+                    }
+                    break;
+                }
+                Err(e) => {
+                    state = KeysIteratorState::ReturnError(e.into());
+                    break;
+                }
+            },
+            Trie::Node { pointer_block } => {
+                let index = match start_after.get(path.len()) {
+                    Some(index) => *index as usize,
+                    None => {
+                        // All keys in this subtrie have `start_after` as a proper prefix.
+                        visited.push(VisitedTrieNode {
+                            trie: current,
+                            maybe_index: None,
+                            path,
+                        });
+                        break;
+                    }
+                };
+                let maybe_next_hash = pointer_block[index].map(|pointer| *pointer.hash());
+                visited.push(VisitedTrieNode {
+                    trie: current,
+                    maybe_index: Some(index + 1),
+                    path: path.clone(),
+                });
+                path.push(index as u8);
+                maybe_next_hash
+            }
+            Trie::Extension { affix, pointer } => {
+                let start_after_affix = &start_after[cmp::min(path.len(), start_after.len())..];
+                let compared_len = cmp::min(affix.len(), start_after_affix.len());
+                match affix[..compared_len].cmp(&start_after_affix[..compared_len]) {
+                    cmp::Ordering::Less => break,
+                    cmp::Ordering::Equal if compared_len == affix.len() => {
+                        let next_hash = *pointer.hash();
+                        path.extend(affix.iter());
+                        Some(next_hash)
+                    }
+                    // Either the affix is greater, or `start_after` is a prefix of it.
+                    cmp::Ordering::Equal | cmp::Ordering::Greater => {
+                        visited.push(VisitedTrieNode {
+                            trie: current,
+                            maybe_index: None,
+                            path,
+                        });
+                        break;
+                    }
+                }
+            }
+        };
+        maybe_current = match maybe_next_hash.map(|hash| store.get(txn, &hash)) {
+            None => None,
+            Some(Ok(maybe_next)) => maybe_next,
+            Some(Err(e)) => {
+                state = KeysIteratorState::ReturnError(e);
+                None
+            }
+        };
+    }
+
+    KeysIterator {
+        initial_descend: VecDeque::new(),
+        visited,
+        store,
+        txn,
+        state,
+    }
+}
+
+/// Returns up to `limit` keys in the trie matching `prefix`, in ascending order of their serialized
+/// bytes, starting after the key serialized to `start_after` if given.
+///
+/// The root should be the apex of the trie.
+pub fn keys_with_prefix_after<K, V, T, S>(
+    correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    root: &Digest,
+    prefix: &[u8],
+    start_after: Option<&[u8]>,
+    limit: usize,
+) -> Result<Vec<K>, S::Error>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error> + From<bytesrepr::Error>,
+{
+    let keys_iter = match start_after {
+        Some(start_after) if start_after >= prefix => {
+            keys_after(correlation_id, txn, store, root, start_after)
+        }
+        _ => keys_with_prefix(correlation_id, txn, store, root, prefix),
+    };
+    let mut keys = Vec::new();
+    for result in keys_iter.take(limit) {
+        let key = result?;
+        if !key.to_bytes()?.starts_with(prefix) {
+            break;
+        }
+        keys.push(key);
+    }
+    Ok(keys)
+}
@@ -314,3 +314,104 @@ mod keys_with_prefix_iterator {
         test_prefix(&[0, 0, 0, 0, 0, 0, 1]); // 1 leaf
     }
 }
+
+mod keys_after_iterator {
+    use crate::{
+        shared::newtypes::CorrelationId,
+        storage::{
+            transaction_source::TransactionSource,
+            trie::Trie,
+            trie_store::operations::{
+                self,
+                tests::{create_6_leaf_trie, InMemoryTestContext, TestKey, TestValue, TEST_LEAVES},
+            },
+        },
+    };
+
+    fn expected_keys(start_after: &[u8]) -> Vec<TestKey> {
+        let mut tmp = TEST_LEAVES
+            .iter()
+            .filter_map(Trie::key)
+            .filter(|key| &key.0[..] > start_after)
+            .cloned()
+            .collect::<Vec<TestKey>>();
+        tmp.sort();
+        tmp
+    }
+
+    fn test_start_after(start_after: &[u8]) {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_6_leaf_trie().expect("should create a trie");
+        let context = InMemoryTestContext::new(&tries).expect("should create a new context");
+        let txn = context
+            .environment
+            .create_read_txn()
+            .expect("should create a read txn");
+        let expected = expected_keys(start_after);
+        // The keys should already be in ascending order.
+        let actual = operations::keys_after::<TestKey, TestValue, _, _>(
+            correlation_id,
+            &txn,
+            &context.store,
+            &root_hash,
+            start_after,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .expect("should iterate keys");
+        assert_eq!(expected, actual, "start after {:?}", start_after);
+    }
+
+    #[test]
+    fn test_start_after_leaves() {
+        for leaf in TEST_LEAVES.iter() {
+            let key = leaf.key().expect("should be a leaf");
+            test_start_after(&key.0);
+        }
+    }
+
+    #[test]
+    fn test_start_after_bytes_between_leaves() {
+        test_start_after(&[]); // 6 leaves
+        test_start_after(&[0]); // 6 leaves
+        test_start_after(&[0, 0, 0, 0]); // 6 leaves
+        test_start_after(&[0, 0, 0, 0, 0, 0]); // 6 leaves
+        test_start_after(&[0, 0, 0, 0, 0, 1]); // 4 leaves
+        test_start_after(&[0, 0, 0, 1]); // 3 leaves
+        test_start_after(&[0, 0, 1]); // 2 leaves
+        test_start_after(&[0, 0, 0, 0, 0, 0, 0, 0]); // 5 leaves, longer than the keys
+        test_start_after(&[0, 255]); // 0 leaves
+        test_start_after(&[1]); // 0 leaves
+    }
+
+    #[test]
+    fn should_resume_iteration_from_last_key() {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_6_leaf_trie().expect("should create a trie");
+        let context = InMemoryTestContext::new(&tries).expect("should create a new context");
+        let txn = context
+            .environment
+            .create_read_txn()
+            .expect("should create a read txn");
+
+        let mut actual = vec![];
+        let mut start_after = vec![];
+        loop {
+            let page = operations::keys_after::<TestKey, TestValue, _, _>(
+                correlation_id,
+                &txn,
+                &context.store,
+                &root_hash,
+                &start_after,
+            )
+            .take(2)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should iterate keys");
+            match page.last() {
+                Some(last) => start_after = last.0.to_vec(),
+                None => break,
+            }
+            actual.extend(page);
+        }
+        assert_eq!(expected_keys(&[]), actual);
+    }
+}
@@ -2,6 +2,7 @@ mod delete;
 mod ee_699;
 mod keys;
 mod proptests;
+mod prune;
 mod read;
 mod scan;
 mod synchronize;
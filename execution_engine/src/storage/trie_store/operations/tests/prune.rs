@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use lmdb::DatabaseFlags;
+
+use casper_hashing::Digest;
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
+use crate::{
+    shared::newtypes::CorrelationId,
+    storage::{
+        error,
+        error::in_memory,
+        transaction_source::{Transaction, TransactionSource, Writable},
+        trie_store::{
+            in_memory::InMemoryMarkStore,
+            lmdb::LmdbMarkStore,
+            operations::{
+                self,
+                tests::{HashedTrie, InMemoryTestContext, LmdbTestContext, TestKey, TestValue},
+                SweepResult,
+            },
+            MarkStore, TrieStore,
+        },
+    },
+};
+
+const BATCH_SIZE: usize = 2;
+
+fn prune<'a, K, V, R, S, M, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    mark_store: &M,
+    retained_root: &Digest,
+) -> Result<usize, E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + std::fmt::Debug,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    M: MarkStore<Handle = S::Handle>,
+    M::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<M::Error> + From<bytesrepr::Error>,
+{
+    let mut trie_keys_to_visit = vec![*retained_root];
+    while !trie_keys_to_visit.is_empty() {
+        let mut txn: R::ReadWriteTransaction = environment.create_read_write_txn()?;
+        operations::mark_reachable_trie_keys::<K, V, _, _, _, E>(
+            correlation_id,
+            &mut txn,
+            store,
+            mark_store,
+            &mut trie_keys_to_visit,
+            BATCH_SIZE,
+        )?;
+        txn.commit()?;
+    }
+
+    let mut deleted_count = 0;
+    let mut after = None;
+    loop {
+        let mut txn: R::ReadWriteTransaction = environment.create_read_write_txn()?;
+        let SweepResult {
+            last_visited,
+            deleted_count: batch_deleted_count,
+        } = operations::sweep_unreachable_tries::<K, V, _, _, _, E>(
+            correlation_id,
+            &mut txn,
+            store,
+            mark_store,
+            after,
+            BATCH_SIZE,
+        )?;
+        txn.commit()?;
+        deleted_count += batch_deleted_count;
+        match last_visited {
+            Some(trie_key) => after = Some(trie_key),
+            None => break,
+        }
+    }
+
+    let mut txn: R::ReadWriteTransaction = environment.create_read_write_txn()?;
+    txn.clear(mark_store.handle())?;
+    txn.commit()?;
+    Ok(deleted_count)
+}
+
+fn prune_should_delete_only_unreachable_tries<'a, K, V, R, S, M, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    mark_store: &M,
+    retained_root: &Digest,
+    retained_tries: &[HashedTrie<K, V>],
+    unreachable_tries: &[HashedTrie<K, V>],
+) -> Result<(), E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<R::Error>,
+    M: MarkStore<Handle = S::Handle>,
+    M::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<M::Error> + From<bytesrepr::Error>,
+{
+    let retained_keys: HashSet<Digest> = retained_tries.iter().map(|trie| trie.hash).collect();
+    let unreachable_keys: HashSet<Digest> = unreachable_tries
+        .iter()
+        .map(|trie| trie.hash)
+        .filter(|trie_key| !retained_keys.contains(trie_key))
+        .collect();
+
+    let deleted_count = prune::<K, V, R, S, M, E>(
+        correlation_id,
+        environment,
+        store,
+        mark_store,
+        retained_root,
+    )?;
+    assert_eq!(deleted_count, unreachable_keys.len());
+
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    for HashedTrie { hash, trie } in retained_tries {
+        assert_eq!(store.get(&txn, hash)?.as_ref(), Some(trie));
+    }
+    for trie_key in &unreachable_keys {
+        assert_eq!(store.get(&txn, trie_key)?, None);
+    }
+    let missing = operations::missing_trie_keys::<K, V, _, _, E>(
+        correlation_id,
+        &txn,
+        store,
+        vec![*retained_root],
+    )?;
+    assert_eq!(missing, Vec::new());
+    txn.commit()?;
+
+    // Pruning again finds nothing to delete.
+    let deleted_count = prune::<K, V, R, S, M, E>(
+        correlation_id,
+        environment,
+        store,
+        mark_store,
+        retained_root,
+    )?;
+    assert_eq!(deleted_count, 0);
+    Ok(())
+}
+
+#[test]
+fn lmdb_prune_should_delete_only_unreachable_tries() {
+    let correlation_id = CorrelationId::new();
+    let (retained_root, retained_tries) = super::create_3_leaf_trie().unwrap();
+    let (_, unreachable_tries) = super::create_6_leaf_trie().unwrap();
+    let context = LmdbTestContext::new(&retained_tries).unwrap();
+    context.update(&unreachable_tries).unwrap();
+
+    let mark_store = LmdbMarkStore::new(&context.environment, DatabaseFlags::empty()).unwrap();
+
+    prune_should_delete_only_unreachable_tries::<TestKey, TestValue, _, _, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &mark_store,
+        &retained_root,
+        &retained_tries,
+        &unreachable_tries,
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_prune_should_delete_only_unreachable_tries() {
+    let correlation_id = CorrelationId::new();
+    let (retained_root, retained_tries) = super::create_3_leaf_trie().unwrap();
+    let (_, unreachable_tries) = super::create_6_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&retained_tries).unwrap();
+    context.update(&unreachable_tries).unwrap();
+
+    let mark_store = InMemoryMarkStore::new(&context.environment);
+
+    prune_should_delete_only_unreachable_tries::<TestKey, TestValue, _, _, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &mark_store,
+        &retained_root,
+        &retained_tries,
+        &unreachable_tries,
+    )
+    .unwrap();
+}
+
+#[test]
+fn lmdb_prune_should_retain_all_tries_of_retained_root() {
+    let correlation_id = CorrelationId::new();
+    let (retained_root, retained_tries) = super::create_6_leaf_trie().unwrap();
+    let context = LmdbTestContext::new(&retained_tries).unwrap();
+
+    let mark_store = LmdbMarkStore::new(&context.environment, DatabaseFlags::empty()).unwrap();
+
+    prune_should_delete_only_unreachable_tries::<TestKey, TestValue, _, _, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &mark_store,
+        &retained_root,
+        &retained_tries,
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn lmdb_sweep_should_finish_when_resuming_after_last_key() {
+    let correlation_id = CorrelationId::new();
+    let (_, tries) = super::create_3_leaf_trie().unwrap();
+    let context = LmdbTestContext::new(&tries).unwrap();
+
+    let mark_store = LmdbMarkStore::new(&context.environment, DatabaseFlags::empty()).unwrap();
+
+    let mut txn = context.environment.create_read_write_txn().unwrap();
+    let SweepResult {
+        last_visited,
+        deleted_count,
+    } = operations::sweep_unreachable_tries::<TestKey, TestValue, _, _, _, error::Error>(
+        correlation_id,
+        &mut txn,
+        &context.store,
+        &mark_store,
+        Some(Digest::from([u8::MAX; Digest::LENGTH])),
+        BATCH_SIZE,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+    assert_eq!(last_visited, None);
+    assert_eq!(deleted_count, 0);
+}
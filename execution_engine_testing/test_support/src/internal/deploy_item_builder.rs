@@ -19,6 +19,7 @@ struct DeployItemData {
     pub gas_price: u64,
     pub authorization_keys: BTreeSet<AccountHash>,
     pub deploy_hash: DeployHash,
+    pub nonce: Option<u64>,
 }
 
 pub struct DeployItemBuilder {
@@ -236,6 +237,11 @@ impl DeployItemBuilder {
         self
     }
 
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.deploy_item.nonce = Some(nonce);
+        self
+    }
+
     pub fn build(self) -> DeployItem {
         DeployItem {
             address: self
@@ -253,6 +259,7 @@ impl DeployItemBuilder {
             gas_price: self.deploy_item.gas_price,
             authorization_keys: self.deploy_item.authorization_keys,
             deploy_hash: self.deploy_item.deploy_hash,
+            nonce: self.deploy_item.nonce,
         }
     }
 }
@@ -19,11 +19,13 @@ use casper_execution_engine::{
         engine_state::{
             era_validators::GetEraValidatorsRequest,
             execute_request::ExecuteRequest,
-            execution_result::ExecutionResult,
+            execution_result::{ExecutionResult, ExecutionResults},
             run_genesis_request::RunGenesisRequest,
             step::{StepRequest, StepSuccess},
-            BalanceResult, EngineConfig, EngineState, GenesisSuccess, GetBidsRequest, QueryRequest,
-            QueryResult, SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
+            BalanceResult, DeployItem, EngineConfig, EngineState, ExecutionTrace, GasProfile,
+            GenesisSuccess, GetBidsRequest, GetDictionaryItemsRequest, GetDictionaryItemsResult,
+            GetKeysByPrefixRequest, GetKeysByPrefixResult, KeyPrefix, QueryRequest, QueryResult,
+            SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
         },
         execution,
     },
@@ -55,13 +57,14 @@ use casper_types::{
         mint::TOTAL_SUPPLY_KEY,
         AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
-    CLTyped, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash, ContractWasm,
-    DeployHash, DeployInfo, EraId, Gas, Key, KeyTag, PublicKey, RuntimeArgs, StoredValue, Transfer,
-    TransferAddr, URef, U512,
+    BlockTime, CLTyped, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash,
+    ContractWasm, DeployHash, DeployInfo, EraId, Gas, Key, KeyTag, PublicKey, RuntimeArgs,
+    StoredValue, Transfer, TransferAddr, URef, U512,
 };
 
 use crate::internal::{
-    utils, ExecuteRequestBuilder, DEFAULT_PROPOSER_ADDR, DEFAULT_PROTOCOL_VERSION, SYSTEM_ADDR,
+    utils, ExecuteRequestBuilder, DEFAULT_BLOCK_TIME, DEFAULT_PROPOSER_ADDR,
+    DEFAULT_PROPOSER_PUBLIC_KEY, DEFAULT_PROTOCOL_VERSION, SYSTEM_ADDR,
 };
 
 /// LMDB initial map size is calculated based on DEFAULT_LMDB_PAGES and systems page size.
@@ -490,22 +493,52 @@ where
             .engine_state
             .run_execute(CorrelationId::new(), exec_request);
         assert!(maybe_exec_results.is_ok());
-        // Parse deploy results
-        let execution_results = maybe_exec_results.as_ref().unwrap();
+        self.push_exec_results(maybe_exec_results.unwrap());
+        self
+    }
+
+    /// Like [`exec`](Self::exec), but also returns a trace of the execution of each deploy.
+    pub fn exec_with_execution_traces(
+        &mut self,
+        mut exec_request: ExecuteRequest,
+    ) -> Vec<ExecutionTrace> {
+        exec_request.parent_state_hash = self.post_state_hash.expect("expected post_state_hash");
+        let (execution_results, execution_traces) = self
+            .engine_state
+            .run_execute_with_execution_traces(CorrelationId::new(), exec_request)
+            .expect("should execute with execution traces");
+        self.push_exec_results(execution_results);
+        execution_traces
+    }
+
+    fn push_exec_results(&mut self, execution_results: ExecutionResults) {
         // Cache transformations
         self.transforms.extend(
             execution_results
                 .iter()
                 .map(|res| res.effect().transforms.clone()),
         );
-        self.exec_results.push(
-            maybe_exec_results
-                .unwrap()
-                .into_iter()
-                .map(Rc::new)
-                .collect(),
-        );
-        self
+        self.exec_results
+            .push(execution_results.into_iter().map(Rc::new).collect());
+    }
+
+    /// Executes a deploy on the latest post-state hash without committing its effects, and returns
+    /// its result along with the gas charged in each call frame of its execution.
+    pub fn deploy_with_gas_profile(
+        &self,
+        deploy_item: DeployItem,
+    ) -> (ExecutionResult, GasProfile) {
+        let prestate_hash = self.post_state_hash.expect("expected post_state_hash");
+        self.engine_state
+            .deploy_with_gas_profile(
+                CorrelationId::new(),
+                *DEFAULT_PROTOCOL_VERSION,
+                prestate_hash,
+                BlockTime::new(DEFAULT_BLOCK_TIME),
+                deploy_item,
+                DEFAULT_PROPOSER_PUBLIC_KEY.clone(),
+            )
+            .expect("should execute deploy with gas profile")
     }
 
     /// Commit effects of previous exec call on the latest post-state hash.
@@ -911,6 +944,62 @@ where
         get_bids_result.into_success().unwrap()
     }
 
+    /// Gets a page of the keys matching `prefix` in the post-state, with their values if
+    /// `include_values` is `true`, and the cursor for the next page.
+    pub fn get_keys_by_prefix(
+        &self,
+        prefix: KeyPrefix,
+        start_after: Option<Key>,
+        limit: usize,
+        include_values: bool,
+    ) -> (Vec<(Key, Option<StoredValue>)>, Option<Key>) {
+        let get_keys_by_prefix_request = GetKeysByPrefixRequest::new(
+            self.get_post_state_hash(),
+            prefix,
+            start_after,
+            limit,
+            include_values,
+        );
+
+        match self
+            .engine_state
+            .get_keys_by_prefix(CorrelationId::new(), get_keys_by_prefix_request)
+            .expect("should get keys by prefix")
+        {
+            GetKeysByPrefixResult::Success {
+                entries,
+                next_cursor,
+            } => (entries, next_cursor),
+            GetKeysByPrefixResult::RootNotFound => panic!("post-state root should exist"),
+        }
+    }
+
+    /// Gets a page of the items of the dictionary with the given seed `URef` in the post-state,
+    /// and the cursor for the next page, or `None` if the dictionary isn't enumerable.
+    pub fn get_dictionary_items(
+        &self,
+        seed_uref: URef,
+        start_after: Option<String>,
+        limit: usize,
+    ) -> Option<(Vec<(String, Key, StoredValue)>, Option<String>)> {
+        let get_dictionary_items_request = GetDictionaryItemsRequest::new(
+            self.get_post_state_hash(),
+            seed_uref,
+            start_after,
+            limit,
+        );
+
+        match self
+            .engine_state
+            .get_dictionary_items(CorrelationId::new(), get_dictionary_items_request)
+            .expect("should get dictionary items")
+        {
+            GetDictionaryItemsResult::Success { items, next_cursor } => Some((items, next_cursor)),
+            GetDictionaryItemsResult::NotEnumerable => None,
+            GetDictionaryItemsResult::RootNotFound => panic!("post-state root should exist"),
+        }
+    }
+
     pub fn get_withdraws(&mut self) -> UnbondingPurses {
         let correlation_id = CorrelationId::new();
         let state_root_hash = self.get_post_state_hash();
@@ -43,6 +43,7 @@ pub struct Session {
     pub(crate) expect_success: bool,
     pub(crate) check_transfer_success: Option<SessionTransferInfo>,
     pub(crate) commit: bool,
+    pub(crate) execution_trace: bool,
 }
 
 /// Builder for a [`Session`].
@@ -52,6 +53,7 @@ pub struct SessionBuilder {
     expect_failure: bool,
     check_transfer_success: Option<SessionTransferInfo>,
     without_commit: bool,
+    with_execution_trace: bool,
 }
 
 impl SessionBuilder {
@@ -73,12 +75,14 @@ impl SessionBuilder {
         let expect_failure = false;
         let check_transfer_success = None;
         let without_commit = false;
+        let with_execution_trace = false;
         Self {
             er_builder: Default::default(),
             di_builder,
             expect_failure,
             check_transfer_success,
             without_commit,
+            with_execution_trace,
         }
     }
 
@@ -149,6 +153,14 @@ impl SessionBuilder {
         self
     }
 
+    /// Record a trace of the execution within the [`TestContext::run()`](crate::TestContext::run)
+    /// method, available afterwards from
+    /// [`TestContext::execution_trace()`](crate::TestContext::execution_trace).
+    pub fn with_execution_trace(mut self) -> Self {
+        self.with_execution_trace = true;
+        self
+    }
+
     /// Builds the [`Session`].
     pub fn build(self) -> Session {
         let mut rng = rand::thread_rng();
@@ -161,6 +173,7 @@ impl SessionBuilder {
             expect_success: !self.expect_failure,
             check_transfer_success: self.check_transfer_success,
             commit: !self.without_commit,
+            execution_trace: self.with_execution_trace,
         }
     }
 }
@@ -1,6 +1,8 @@
 use casper_execution_engine::core::engine_state::{
+    execute_request::ExecuteRequest,
     genesis::{GenesisAccount, GenesisConfig},
     run_genesis_request::RunGenesisRequest,
+    ExecutionTrace,
 };
 use casper_types::{AccessRights, Key, Motes, PublicKey, StoredValue, URef, U512};
 
@@ -12,6 +14,7 @@ use crate::{
 /// Context in which to run a test of a Wasm smart contract.
 pub struct TestContext {
     inner: InMemoryWasmTestBuilder,
+    execution_trace: Option<ExecutionTrace>,
 }
 
 impl TestContext {
@@ -25,6 +28,23 @@ impl TestContext {
         }
     }
 
+    fn exec(
+        &mut self,
+        execute_request: ExecuteRequest,
+        execution_trace: bool,
+    ) -> &mut InMemoryWasmTestBuilder {
+        self.execution_trace = if execution_trace {
+            self.inner
+                .exec_with_execution_traces(execute_request)
+                .into_iter()
+                .next()
+        } else {
+            self.inner.exec(execute_request);
+            None
+        };
+        &mut self.inner
+    }
+
     /// Runs the supplied [`Session`] checking specified expectations of the execution and
     /// subsequent commit of transforms are met.
     ///
@@ -39,6 +59,11 @@ impl TestContext {
     /// If `session` was built without
     /// [`without_commit()`](crate::SessionBuilder::without_commit) (the default), then `run()` will
     /// commit the resulting transforms.
+    ///
+    /// If `session` was built with
+    /// [`with_execution_trace()`](crate::SessionBuilder::with_execution_trace) (not the default),
+    /// then a trace of the execution is available from [`execution_trace()`](Self::execution_trace)
+    /// afterwards.
     pub fn run(&mut self, session: Session) -> &mut Self {
         match session.check_transfer_success {
             Some(session_transfer_info) => {
@@ -51,7 +76,7 @@ impl TestContext {
                 let maybe_target_initial_balance =
                     self.maybe_purse_balance(session_transfer_info.maybe_target_purse);
 
-                let builder = self.inner.exec(session.inner);
+                let builder = self.exec(session.inner, session.execution_trace);
 
                 if session.expect_success {
                     builder.expect_success();
@@ -95,7 +120,7 @@ impl TestContext {
                 }
             }
             None => {
-                let builder = self.inner.exec(session.inner);
+                let builder = self.exec(session.inner, session.execution_trace);
                 if session.expect_success {
                     builder.expect_success();
                 }
@@ -107,6 +132,16 @@ impl TestContext {
         self
     }
 
+    /// Returns the trace of the execution of the last [`Session`] run, if it was built with
+    /// [`with_execution_trace()`](crate::SessionBuilder::with_execution_trace).
+    ///
+    /// The trace records the host functions called with their arguments and return values, the
+    /// named keys read and written, the contracts called and where execution was reverted, if at
+    /// all.
+    pub fn execution_trace(&self) -> Option<&ExecutionTrace> {
+        self.execution_trace.as_ref()
+    }
+
     /// Queries for a [`Value`] stored under the given `key` and `path`.
     ///
     /// Returns an [`Error`] if not found.
@@ -233,7 +268,10 @@ impl TestContextBuilder {
             self.genesis_config.take_ee_config(),
         );
         inner.run_genesis(&run_genesis_request);
-        TestContext { inner }
+        TestContext {
+            inner,
+            execution_trace: None,
+        }
     }
 }
 
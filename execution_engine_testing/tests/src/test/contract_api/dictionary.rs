@@ -6,10 +6,16 @@ use casper_engine_test_support::{
     AccountHash, Code, SessionBuilder, TestContextBuilder, DEFAULT_ACCOUNT_ADDR,
     DEFAULT_ACCOUNT_INITIAL_BALANCE, MINIMUM_ACCOUNT_CREATION_BALANCE,
 };
-use casper_execution_engine::core::{engine_state::Error as EngineError, execution::Error};
+use casper_execution_engine::{
+    core::{
+        engine_state::{Error as EngineError, KeyPrefix},
+        execution::Error,
+    },
+    shared::{additive_map::AdditiveMap, transform::Transform},
+};
 use casper_types::{
-    runtime_args, system::mint, AccessRights, ApiError, CLType, ContractHash, Key, RuntimeArgs,
-    U512,
+    runtime_args, system::mint, AccessRights, ApiError, CLType, CLValue, ContractHash, Key, KeyTag,
+    RuntimeArgs, StoredValue, Transfer, U512,
 };
 
 use dictionary_call::{NEW_DICTIONARY_ITEM_KEY, NEW_DICTIONARY_VALUE};
@@ -592,3 +598,146 @@ fn should_query_dictionary_items_with_test_context() {
         assert_eq!(value, dictionary::DEFAULT_DICTIONARY_VALUE);
     }
 }
+
+#[ignore]
+#[test]
+fn should_page_through_dictionary_items_by_seed_uref() {
+    let (mut builder, contract_hash) = setup();
+
+    let modify_write_request = ExecuteRequestBuilder::contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        dictionary::MODIFY_WRITE_ENTRYPOINT,
+        RuntimeArgs::default(),
+    )
+    .build();
+    builder.exec(modify_write_request).commit().expect_success();
+
+    let contract = builder
+        .get_contract(contract_hash)
+        .expect("should have contract");
+    let dictionary_seed_uref = contract
+        .named_keys()
+        .get(dictionary::DICTIONARY_NAME)
+        .and_then(Key::as_uref)
+        .cloned()
+        .expect("should have dictionary uref");
+
+    let mut entries = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (page, next_cursor) = builder.get_keys_by_prefix(
+            KeyPrefix::DictionaryItems(dictionary_seed_uref),
+            cursor,
+            1,
+            true,
+        );
+        assert!(page.len() <= 1);
+        entries.extend(page);
+        cursor = match next_cursor {
+            Some(next_cursor) => Some(next_cursor),
+            None => break,
+        };
+    }
+
+    let mut values = entries
+        .into_iter()
+        .map(|(key, value)| {
+            assert!(matches!(key, Key::Dictionary(_)));
+            let cl_value = value
+                .expect("should have value")
+                .as_cl_value()
+                .cloned()
+                .expect("should have cl value");
+            cl_value.into_t::<String>().expect("should be a string")
+        })
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            dictionary::DEFAULT_DICTIONARY_VALUE.to_string(),
+            "Hello, world!".to_string()
+        ]
+    );
+
+    let (dictionary_keys, _) =
+        builder.get_keys_by_prefix(KeyPrefix::Tag(KeyTag::Dictionary), None, 100, false);
+    assert!(dictionary_keys.len() >= 2);
+    assert!(dictionary_keys.iter().all(|(_, value)| value.is_none()));
+}
+
+#[ignore]
+#[test]
+fn should_skip_non_dictionary_values_when_paging_through_dictionary_items() {
+    let (mut builder, contract_hash) = setup();
+
+    let contract = builder
+        .get_contract(contract_hash)
+        .expect("should have contract");
+    let dictionary_seed_uref = contract
+        .named_keys()
+        .get(dictionary::DICTIONARY_NAME)
+        .and_then(Key::as_uref)
+        .cloned()
+        .expect("should have dictionary uref");
+
+    // Store values which aren't dictionary items under `Key::Dictionary` keys sorting before and
+    // after the items of the dictionary.
+    let effects: AdditiveMap<Key, Transform> = vec![
+        (
+            Key::Dictionary([0; 32]),
+            Transform::Write(StoredValue::CLValue(CLValue::from_t(1u64).unwrap())),
+        ),
+        (
+            Key::Dictionary([u8::MAX; 32]),
+            Transform::Write(StoredValue::Transfer(Transfer::default())),
+        ),
+    ]
+    .into_iter()
+    .collect();
+    let post_state_hash = builder.get_post_state_hash();
+    builder.commit_transforms(post_state_hash, effects);
+
+    let mut values = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (page, next_cursor) = builder.get_keys_by_prefix(
+            KeyPrefix::DictionaryItems(dictionary_seed_uref),
+            cursor,
+            1,
+            true,
+        );
+        values.extend(page.into_iter().map(|(_, value)| {
+            value
+                .expect("should have value")
+                .as_cl_value()
+                .cloned()
+                .expect("should have cl value")
+                .into_t::<String>()
+                .expect("should be a string")
+        }));
+        cursor = match next_cursor {
+            Some(next_cursor) => Some(next_cursor),
+            None => break,
+        };
+    }
+    assert_eq!(
+        values,
+        vec![dictionary::DEFAULT_DICTIONARY_VALUE.to_string()]
+    );
+}
+
+#[ignore]
+#[test]
+fn should_raise_zero_keys_by_prefix_limit_to_one() {
+    let (builder, _contract_hash) = setup();
+
+    let zero_limit_page =
+        builder.get_keys_by_prefix(KeyPrefix::Tag(KeyTag::Dictionary), None, 0, false);
+    assert_eq!(zero_limit_page.0.len(), 1);
+    assert_eq!(
+        zero_limit_page,
+        builder.get_keys_by_prefix(KeyPrefix::Tag(KeyTag::Dictionary), None, 1, false)
+    );
+}
@@ -0,0 +1,177 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    internal::{
+        ExecuteRequestBuilder, InMemoryWasmTestBuilder, UpgradeRequestBuilder,
+        DEFAULT_PROTOCOL_VERSION, DEFAULT_RUN_GENESIS_REQUEST,
+    },
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::core::{
+    engine_state::{self, KeyPrefix, DICTIONARY_INDEX_PROTOCOL_VERSION},
+    execution,
+};
+use casper_types::{runtime_args, ApiError, EraId, Key, ProtocolVersion, RuntimeArgs, URef};
+
+const DICTIONARY_ITER_WASM: &str = "dictionary_iter.wasm";
+const DICTIONARY_NAME: &str = "dictionary";
+const ITEM_KEYS_NAME: &str = "item_keys";
+const ARG_ITEM_COUNT: &str = "item_count";
+const ARG_PAGE_SIZE: &str = "page_size";
+
+// Enough items to split the index into several nodes.
+const ITEM_COUNT: u32 = 150;
+const PAGE_SIZE: u32 = 40;
+const DEFAULT_ACTIVATION_POINT: EraId = EraId::new(1);
+
+/// Runs genesis, upgrading to the protocol version which indexes dictionaries if it is given, then
+/// runs the contract under the resulting version.
+fn exec_dictionary_iter(
+    protocol_version: ProtocolVersion,
+    item_count: u32,
+    page_size: u32,
+) -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    if protocol_version != *DEFAULT_PROTOCOL_VERSION {
+        let mut upgrade_request = UpgradeRequestBuilder::new()
+            .with_current_protocol_version(*DEFAULT_PROTOCOL_VERSION)
+            .with_new_protocol_version(protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .build();
+        builder
+            .upgrade_with_upgrade_request(
+                *builder.get_engine_state().config(),
+                &mut upgrade_request,
+            )
+            .expect_upgrade_success();
+    }
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        DICTIONARY_ITER_WASM,
+        runtime_args! {
+            ARG_ITEM_COUNT => item_count,
+            ARG_PAGE_SIZE => page_size,
+        },
+    )
+    .with_protocol_version(protocol_version)
+    .build();
+
+    builder.exec(exec_request).commit();
+    builder
+}
+
+fn dictionary_iter(item_count: u32, page_size: u32) -> InMemoryWasmTestBuilder {
+    let mut builder =
+        exec_dictionary_iter(DICTIONARY_INDEX_PROTOCOL_VERSION, item_count, page_size);
+    builder.expect_success();
+    builder
+}
+
+fn get_named_key(builder: &InMemoryWasmTestBuilder, name: &str) -> Key {
+    *builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(name)
+        .expect("should have named key")
+}
+
+fn expected_item_keys(item_count: u32) -> Vec<String> {
+    (0..item_count)
+        .map(|index| format!("item-{:04}", index))
+        .collect()
+}
+
+#[ignore]
+#[test]
+fn should_iterate_dictionary_item_keys_in_order_from_contract() {
+    let builder = dictionary_iter(ITEM_COUNT, PAGE_SIZE);
+
+    let item_keys_key = get_named_key(&builder, ITEM_KEYS_NAME);
+    let item_keys: Vec<String> = builder
+        .query(None, item_keys_key, &[])
+        .expect("should query item keys")
+        .as_cl_value()
+        .cloned()
+        .expect("should be a cl value")
+        .into_t()
+        .expect("should be a list of strings");
+    assert_eq!(item_keys, expected_item_keys(ITEM_COUNT));
+}
+
+#[ignore]
+#[test]
+fn should_page_through_dictionary_items_by_item_key() {
+    let builder = dictionary_iter(ITEM_COUNT, PAGE_SIZE);
+
+    let dictionary_seed_uref = get_named_key(&builder, DICTIONARY_NAME)
+        .into_uref()
+        .expect("should have dictionary uref");
+
+    let mut items = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (page, next_cursor) = builder
+            .get_dictionary_items(dictionary_seed_uref, cursor, PAGE_SIZE as usize)
+            .expect("should be enumerable");
+        assert!(page.len() <= PAGE_SIZE as usize);
+        items.extend(page);
+        cursor = match next_cursor {
+            Some(next_cursor) => Some(next_cursor),
+            None => break,
+        };
+    }
+
+    assert_eq!(items.len(), ITEM_COUNT as usize);
+    for ((item_key, key, value), (index, expected_item_key)) in items
+        .into_iter()
+        .zip(expected_item_keys(ITEM_COUNT).into_iter().enumerate())
+    {
+        assert_eq!(item_key, expected_item_key);
+        assert_eq!(
+            key,
+            Key::dictionary(dictionary_seed_uref, item_key.as_bytes())
+        );
+        let value: u32 = value
+            .as_cl_value()
+            .cloned()
+            .expect("should be a cl value")
+            .into_t()
+            .expect("should be a u32");
+        assert_eq!(value, index as u32);
+    }
+
+    // The index is stored in the dictionary but isn't one of its items.
+    let (entries, _) = builder.get_keys_by_prefix(
+        KeyPrefix::DictionaryItems(dictionary_seed_uref),
+        None,
+        usize::MAX,
+        false,
+    );
+    assert_eq!(entries.len(), ITEM_COUNT as usize);
+}
+
+#[ignore]
+#[test]
+fn should_not_enumerate_unknown_dictionary() {
+    let builder = dictionary_iter(1, 1);
+
+    assert!(builder
+        .get_dictionary_items(URef::default(), None, 10)
+        .is_none());
+}
+
+#[ignore]
+#[test]
+fn should_not_index_dictionaries_before_protocol_version() {
+    let builder = exec_dictionary_iter(*DEFAULT_PROTOCOL_VERSION, 1, 1);
+
+    assert_matches!(
+        builder.get_error(),
+        Some(engine_state::Error::Exec(execution::Error::Revert(
+            ApiError::DictionaryNotEnumerable
+        )))
+    );
+}
@@ -0,0 +1,99 @@
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::core::{
+    engine_state::{execution_effect::ContractEvent, Error as EngineError},
+    execution::Error,
+};
+use casper_types::{
+    runtime_args, ApiError, CLValue, Key, RuntimeArgs, EVENT_TOPIC_MAX_LENGTH, U512,
+};
+
+const EMIT_EVENT_WASM: &str = "emit_event.wasm";
+const ARG_TOPIC: &str = "topic";
+const ARG_PAYLOAD: &str = "payload";
+
+fn emit_event(topic: &str, payload: U512) -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        EMIT_EVENT_WASM,
+        runtime_args! {
+            ARG_TOPIC => topic,
+            ARG_PAYLOAD => payload,
+        },
+    )
+    .build();
+
+    builder.exec(exec_request).commit();
+    builder
+}
+
+fn get_error(builder: &InMemoryWasmTestBuilder) -> EngineError {
+    let exec_results = builder
+        .get_exec_results()
+        .last()
+        .expect("should have results");
+    assert_eq!(exec_results.len(), 1);
+    exec_results[0]
+        .as_error()
+        .cloned()
+        .expect("should have error")
+}
+
+#[ignore]
+#[test]
+fn should_record_emitted_event_in_execution_effect() {
+    const TOPIC: &str = "transfer";
+    let payload = U512::from(1_000);
+
+    let mut builder = emit_event(TOPIC, payload);
+    builder.expect_success();
+
+    let exec_results = builder
+        .get_exec_results()
+        .last()
+        .expect("should have results");
+    assert_eq!(exec_results.len(), 1);
+
+    let expected_event = ContractEvent {
+        emitter: Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        topic: TOPIC.to_string(),
+        payload: CLValue::from_t(payload).unwrap(),
+    };
+    assert_eq!(exec_results[0].effect().events, vec![expected_event]);
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_emit_event_with_empty_topic() {
+    let builder = emit_event("", U512::one());
+    let error = get_error(&builder);
+    assert!(
+        matches!(
+            error,
+            EngineError::Exec(Error::Revert(ApiError::InvalidEventTopic))
+        ),
+        "Received error {:?}",
+        error
+    );
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_emit_event_with_long_topic() {
+    let topic = "a".repeat(EVENT_TOPIC_MAX_LENGTH + 1);
+    let builder = emit_event(&topic, U512::one());
+    let error = get_error(&builder);
+    assert!(
+        matches!(
+            error,
+            EngineError::Exec(Error::Revert(ApiError::EventTopicExceedsLength))
+        ),
+        "Received error {:?}",
+        error
+    );
+}
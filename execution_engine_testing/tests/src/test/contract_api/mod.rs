@@ -2,6 +2,8 @@ mod account;
 mod blake2b;
 mod create_purse;
 mod dictionary;
+mod dictionary_iter;
+mod emit_event;
 mod get_arg;
 mod get_blocktime;
 mod get_call_stack;
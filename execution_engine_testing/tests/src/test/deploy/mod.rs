@@ -1,5 +1,6 @@
 mod context_association;
 mod non_standard_payment;
+mod nonces;
 mod preconditions;
 mod receipts;
 mod stored_contracts;
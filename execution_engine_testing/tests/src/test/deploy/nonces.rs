@@ -0,0 +1,188 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    internal::{
+        utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder,
+        UpgradeRequestBuilder, DEFAULT_PAYMENT, DEFAULT_PROTOCOL_VERSION,
+        DEFAULT_RUN_GENESIS_REQUEST,
+    },
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::core::engine_state::{
+    self, Error, ExecuteRequest, DEPLOY_NONCES_PROTOCOL_VERSION,
+};
+use casper_types::{
+    account::AccountHash,
+    runtime_args,
+    system::{mint, standard_payment},
+    EraId, ProtocolVersion, RuntimeArgs, U512,
+};
+
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42u8; 32]);
+const DEFAULT_ACTIVATION_POINT: EraId = EraId::new(1);
+
+/// Runs genesis, then upgrades to the protocol version which activates deploy nonces.
+fn setup() -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let mut upgrade_request = UpgradeRequestBuilder::new()
+        .with_current_protocol_version(*DEFAULT_PROTOCOL_VERSION)
+        .with_new_protocol_version(DEPLOY_NONCES_PROTOCOL_VERSION)
+        .with_activation_point(DEFAULT_ACTIVATION_POINT)
+        .build();
+    builder
+        .upgrade_with_upgrade_request(*builder.get_engine_state().config(), &mut upgrade_request)
+        .expect_upgrade_success();
+    builder
+}
+
+fn transfer_request(deploy_hash: [u8; 32], nonce: u64) -> ExecuteRequest {
+    transfer_request_at(DEPLOY_NONCES_PROTOCOL_VERSION, deploy_hash, nonce)
+}
+
+fn transfer_request_at(
+    protocol_version: ProtocolVersion,
+    deploy_hash: [u8; 32],
+    nonce: u64,
+) -> ExecuteRequest {
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_deploy_hash(deploy_hash)
+        .with_empty_payment_bytes(runtime_args! {})
+        .with_transfer_args(runtime_args! {
+            mint::ARG_TARGET => ACCOUNT_1_ADDR,
+            mint::ARG_AMOUNT => U512::from(1000),
+            mint::ARG_ID => <Option<u64>>::None
+        })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_nonce(nonce)
+        .build();
+    ExecuteRequestBuilder::from_deploy_item(deploy_item)
+        .with_protocol_version(protocol_version)
+        .build()
+}
+
+fn session_request(deploy_hash: [u8; 32], nonce: u64) -> ExecuteRequest {
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_deploy_hash(deploy_hash)
+        .with_empty_payment_bytes(runtime_args! {
+            standard_payment::ARG_AMOUNT => *DEFAULT_PAYMENT
+        })
+        .with_session_code("do_nothing.wasm", RuntimeArgs::default())
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_nonce(nonce)
+        .build();
+    ExecuteRequestBuilder::from_deploy_item(deploy_item)
+        .with_protocol_version(DEPLOY_NONCES_PROTOCOL_VERSION)
+        .build()
+}
+
+fn account_nonce(builder: &InMemoryWasmTestBuilder) -> u64 {
+    builder
+        .query(
+            None,
+            engine_state::account_nonce_key(*DEFAULT_ACCOUNT_ADDR),
+            &[],
+        )
+        .expect("should have nonce")
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+        .into_t()
+        .expect("should be u64")
+}
+
+fn default_account_balance(builder: &InMemoryWasmTestBuilder) -> U512 {
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    builder.get_purse_balance(account.main_purse())
+}
+
+/// Executes a deploy with an unexpected nonce, and asserts that it fails but is still charged for.
+fn assert_charged_for_invalid_nonce(
+    builder: &mut InMemoryWasmTestBuilder,
+    exec_request: ExecuteRequest,
+    expected_nonce: u64,
+    nonce: u64,
+) {
+    let balance_before = default_account_balance(builder);
+
+    builder.exec(exec_request).commit();
+    let response = builder
+        .get_exec_results()
+        .last()
+        .expect("there should be a response");
+    let execution_result = utils::get_success_result(response);
+    let error = execution_result.as_error().expect("should have error");
+    assert_matches!(
+        error,
+        Error::InvalidNonce { expected, got } if *expected == expected_nonce && *got == nonce
+    );
+    assert!(default_account_balance(builder) < balance_before);
+}
+
+#[ignore]
+#[test]
+fn should_require_consecutive_nonces() {
+    let mut builder = setup();
+
+    builder
+        .exec(transfer_request([1; 32], 1))
+        .expect_success()
+        .commit();
+    assert_eq!(account_nonce(&builder), 1);
+
+    // A nonce can't be reused...
+    assert_charged_for_invalid_nonce(&mut builder, transfer_request([2; 32], 1), 2, 1);
+    // ...and can't be skipped either.
+    assert_charged_for_invalid_nonce(&mut builder, transfer_request([3; 32], 3), 2, 3);
+    assert_eq!(account_nonce(&builder), 1);
+
+    builder
+        .exec(transfer_request([4; 32], 2))
+        .expect_success()
+        .commit();
+    assert_eq!(account_nonce(&builder), 2);
+}
+
+#[ignore]
+#[test]
+fn should_charge_session_deploy_with_invalid_nonce() {
+    let mut builder = setup();
+
+    builder
+        .exec(session_request([1; 32], 1))
+        .expect_success()
+        .commit();
+    assert_eq!(account_nonce(&builder), 1);
+
+    assert_charged_for_invalid_nonce(&mut builder, session_request([2; 32], 1), 2, 1);
+    assert_eq!(account_nonce(&builder), 1);
+}
+
+#[ignore]
+#[test]
+fn should_ignore_nonces_before_activation() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    // Neither a skipped nor a repeated nonce is an error, and no nonce is recorded.
+    builder
+        .exec(transfer_request_at(*DEFAULT_PROTOCOL_VERSION, [1; 32], 3))
+        .expect_success()
+        .commit();
+    builder
+        .exec(transfer_request_at(*DEFAULT_PROTOCOL_VERSION, [2; 32], 3))
+        .expect_success()
+        .commit();
+    assert!(builder
+        .query(
+            None,
+            engine_state::account_nonce_key(*DEFAULT_ACCOUNT_ADDR),
+            &[]
+        )
+        .is_err());
+}
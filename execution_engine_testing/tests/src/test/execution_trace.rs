@@ -0,0 +1,112 @@
+use casper_engine_test_support::{
+    internal::DEFAULT_ACCOUNT_PUBLIC_KEY, Code, SessionBuilder, TestContextBuilder,
+    DEFAULT_ACCOUNT_ADDR, DEFAULT_ACCOUNT_INITIAL_BALANCE,
+};
+use casper_execution_engine::core::engine_state::execution_trace::TraceEvent;
+use casper_types::{runtime_args, ApiError, RuntimeArgs, U512};
+
+const ARG_AMOUNT: &str = "amount";
+const ARG_DESTINATION: &str = "destination";
+const REVERT_WASM: &str = "revert.wasm";
+const TRANSFER_WASM: &str = "transfer_main_purse_to_new_purse.wasm";
+const NEW_PURSE_NAME: &str = "test_purse";
+const TRANSFER_AMOUNT: u64 = 142;
+const SESSION_CALL_FRAME: &str = "session";
+
+#[ignore]
+#[test]
+fn should_trace_revert_location() {
+    let mut test_context = TestContextBuilder::new()
+        .with_public_key(
+            DEFAULT_ACCOUNT_PUBLIC_KEY.clone(),
+            U512::from(DEFAULT_ACCOUNT_INITIAL_BALANCE),
+        )
+        .build();
+
+    let session = SessionBuilder::new(Code::from(REVERT_WASM), RuntimeArgs::default())
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .without_expect_success()
+        .with_execution_trace()
+        .build();
+    test_context.run(session);
+
+    let execution_trace = test_context
+        .execution_trace()
+        .expect("should have execution trace");
+    let revert = execution_trace.revert().expect("should have reverted");
+    assert_eq!(revert.call_stack(), [SESSION_CALL_FRAME]);
+    assert_eq!(revert.error(), ApiError::User(100));
+
+    let reverting_call = execution_trace
+        .events()
+        .iter()
+        .find_map(|event| match event {
+            TraceEvent::HostFunctionCalled { name, error, .. }
+                if name == "host_function_revert" =>
+            {
+                Some(error)
+            }
+            _ => None,
+        })
+        .expect("should have called revert");
+    assert!(reverting_call.is_some());
+}
+
+#[ignore]
+#[test]
+fn should_trace_named_key_writes_in_session_call_frame() {
+    let mut test_context = TestContextBuilder::new()
+        .with_public_key(
+            DEFAULT_ACCOUNT_PUBLIC_KEY.clone(),
+            U512::from(DEFAULT_ACCOUNT_INITIAL_BALANCE),
+        )
+        .build();
+
+    let session_args = runtime_args! {
+        ARG_DESTINATION => NEW_PURSE_NAME,
+        ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+    };
+    let session = SessionBuilder::new(Code::from(TRANSFER_WASM), session_args.clone())
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_execution_trace()
+        .build();
+    test_context.run(session);
+
+    let execution_trace = test_context
+        .execution_trace()
+        .expect("should have execution trace");
+    assert!(execution_trace.revert().is_none());
+
+    let events = execution_trace.events();
+    let session_start = events
+        .iter()
+        .position(|event| {
+            *event
+                == TraceEvent::CallFrameEntered {
+                    name: SESSION_CALL_FRAME.to_string(),
+                    args: session_args.clone(),
+                }
+        })
+        .expect("should have entered session call frame");
+    let session_end = session_start
+        + events[session_start..]
+            .iter()
+            .position(|event| *event == TraceEvent::CallFrameExited)
+            .expect("should have exited session call frame");
+
+    assert!(events[session_start..session_end]
+        .iter()
+        .any(|event| matches!(
+            event,
+            TraceEvent::NamedKeyWritten { name, .. } if name == NEW_PURSE_NAME
+        )));
+    assert!(events[session_start..session_end]
+        .iter()
+        .any(|event| matches!(
+            event,
+            TraceEvent::HostFunctionCalled { name, error: None, .. }
+                if name == "host_function_put_key"
+        )));
+}
@@ -1,7 +1,7 @@
 use assert_matches::assert_matches;
 use parity_wasm::{
     builder,
-    elements::{BlockType, Instruction, Instructions},
+    elements::{BlockType, Instruction, Instructions, ValueType},
 };
 
 use casper_engine_test_support::{
@@ -11,8 +11,16 @@ use casper_engine_test_support::{
     },
     DEFAULT_ACCOUNT_ADDR,
 };
-use casper_execution_engine::{core::engine_state::Error, shared::wasm_prep::PreprocessingError};
-use casper_types::{contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, Gas, RuntimeArgs};
+use casper_execution_engine::{
+    core::engine_state::{gas_profile::GasProfile, DeployItem, Error},
+    shared::{opcode_costs::OpcodeCategory, wasm_prep::PreprocessingError},
+};
+use casper_types::{
+    account::AccountHash, contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, Gas, RuntimeArgs, U512,
+};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const ARG_TARGET: &str = "target";
 
 /// Prepare malicious payload with amount of opcodes that could potentially overflow injected gas
 /// counter.
@@ -210,3 +218,188 @@ fn should_correctly_measure_gas_for_opcodes() {
         accounted_opcodes
     );
 }
+
+#[ignore]
+#[test]
+fn should_profile_gas_of_session_opcodes_by_category() {
+    let opcode_costs = DEFAULT_WASM_CONFIG.opcode_costs();
+
+    const GROW_PAGES: u32 = 1;
+
+    let instructions = vec![
+        Instruction::Nop,
+        Instruction::I32Const(1),
+        Instruction::I32Const(2),
+        Instruction::I32Add,
+        Instruction::Drop,
+        Instruction::I32Const(GROW_PAGES as i32),
+        Instruction::GrowMemory(0),
+        Instruction::Drop,
+        Instruction::End,
+    ];
+    let session_bytes = make_session_code_with(instructions);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_session_bytes(session_bytes, RuntimeArgs::default())
+        .with_empty_payment_bytes(runtime_args! {
+            ARG_AMOUNT => *DEFAULT_PAYMENT
+        })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash([42; 32])
+        .build();
+
+    let (execution_result, gas_profile) = builder.deploy_with_gas_profile(deploy_item);
+    assert!(execution_result.is_success());
+
+    let session_frame = gas_profile
+        .frames()
+        .iter()
+        .find(|frame| frame.stack() == ["session"])
+        .expect("should have session frame");
+    let opcodes = session_frame.opcodes();
+    assert_eq!(
+        opcodes.get(&OpcodeCategory::Nop),
+        Some(&Gas::from(opcode_costs.nop))
+    );
+    assert_eq!(
+        opcodes.get(&OpcodeCategory::Const),
+        Some(&Gas::from(opcode_costs.op_const * 3))
+    );
+    assert_eq!(
+        opcodes.get(&OpcodeCategory::Add),
+        Some(&Gas::from(opcode_costs.add))
+    );
+    assert_eq!(
+        opcodes.get(&OpcodeCategory::GrowMemory),
+        Some(&Gas::from(opcode_costs.grow_memory * (GROW_PAGES + 1)))
+    );
+    assert_eq!(session_frame.unattributed_opcodes(), Gas::default());
+
+    let expected_session_cost = Gas::from(
+        opcode_costs.nop
+            + opcode_costs.op_const * 3
+            + opcode_costs.add
+            + opcode_costs.control_flow * 2
+            + opcode_costs.grow_memory * (GROW_PAGES + 1),
+    );
+    assert_eq!(session_frame.total_cost(), expected_session_cost);
+
+    assert!(gas_profile
+        .folded_stacks()
+        .lines()
+        .any(|line| line.starts_with("session;opcodes;nop ")));
+}
+
+/// Executes `deploy_item` both with and without gas profiling, asserting that the costs are equal,
+/// and returns the gas profile.
+fn profile_gas_and_compare_cost(deploy_item: DeployItem) -> GasProfile {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let (profiled_result, gas_profile) = builder.deploy_with_gas_profile(deploy_item.clone());
+    assert!(profiled_result.is_success());
+
+    let exec_request = ExecuteRequestBuilder::from_deploy_item(deploy_item).build();
+    builder.exec(exec_request).expect_success();
+    assert_eq!(profiled_result.cost(), builder.last_exec_gas_cost());
+
+    gas_profile
+}
+
+#[ignore]
+#[test]
+fn should_charge_same_gas_with_gas_profiling() {
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_session_code(
+            CONTRACT_TRANSFER_TO_ACCOUNT,
+            runtime_args! {
+                ARG_TARGET => AccountHash::new([42; 32]),
+                ARG_AMOUNT => U512::from(1_000_000_000u64),
+            },
+        )
+        .with_empty_payment_bytes(runtime_args! {
+            ARG_AMOUNT => *DEFAULT_PAYMENT
+        })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash([42; 32])
+        .build();
+
+    let gas_profile = profile_gas_and_compare_cost(deploy_item);
+
+    let session_frame = gas_profile
+        .frames()
+        .iter()
+        .find(|frame| frame.stack() == ["session"])
+        .expect("should have session frame");
+    assert!(!session_frame.opcodes().is_empty());
+    assert!(!session_frame.host_function_calls().is_empty());
+}
+
+#[ignore]
+#[test]
+fn should_charge_same_gas_with_gas_profiling_when_session_imports_gas_function() {
+    const DIRECT_GAS_CHARGE: i32 = 1_000;
+
+    // The session imports the `gas` host function itself and charges gas directly.
+    let mut module_builder = builder::module();
+    let gas_signature =
+        module_builder.push_signature(builder::signature().with_param(ValueType::I32).build_sig());
+    let module = module_builder
+        .with_import(
+            builder::import()
+                .module("env")
+                .field("gas")
+                .external()
+                .func(gas_signature)
+                .build(),
+        )
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![
+            Instruction::I32Const(DIRECT_GAS_CHARGE),
+            Instruction::Call(0),
+            Instruction::Nop,
+            Instruction::End,
+        ]))
+        .build()
+        .build()
+        .export()
+        .field(DEFAULT_ENTRY_POINT_NAME)
+        .internal()
+        .func(1)
+        .build()
+        .memory()
+        .build()
+        .build();
+    let session_bytes = parity_wasm::serialize(module).expect("should serialize");
+
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_session_bytes(session_bytes, RuntimeArgs::default())
+        .with_empty_payment_bytes(runtime_args! {
+            ARG_AMOUNT => *DEFAULT_PAYMENT
+        })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash([42; 32])
+        .build();
+
+    let gas_profile = profile_gas_and_compare_cost(deploy_item);
+
+    // The session's charges can't be attributed to opcode categories.
+    let session_frame = gas_profile
+        .frames()
+        .iter()
+        .find(|frame| frame.stack() == ["session"])
+        .expect("should have session frame");
+    assert!(session_frame.opcodes().is_empty());
+    assert!(session_frame.unattributed_opcodes() > Gas::from(DIRECT_GAS_CHARGE as u32));
+}
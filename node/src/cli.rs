@@ -18,6 +18,7 @@ use toml::{value::Table, Value};
 use tracing::{error, info, warn};
 
 use crate::{
+    components::{consensus, contract_runtime},
     logging,
     reactor::{initializer, joiner, participating, ReactorExit, Runner},
     setup_signal_hooks,
@@ -75,6 +76,41 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Export the validator's slashing protection records, i.e. all units it signed in eras that
+    /// are still bonded, so that they can be imported before moving the key to another machine.
+    ExportSlashingProtection {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Path to the export file to be created.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Import slashing protection records that were exported on another machine.
+    ///
+    /// This must be done before the node is started with the imported validator key, to prevent
+    /// it from equivocating.
+    ImportSlashingProtection {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Path to the file created by `export-slashing-protection`.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+    /// Prune the global state, deleting all of it except the state of the blocks from the switch
+    /// block `retained-eras` eras back onwards.
+    ///
+    /// The node must not be running.  Set `contract_runtime.prune_retained_eras` to at least the
+    /// same value in the configuration file, so that the integrity check on restarting after a
+    /// crash doesn't look for pruned state.
+    PruneGlobalState {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Number of most recent eras whose global state is retained.  Defaults to
+        /// `contract_runtime.prune_retained_eras` in the configuration file.  Must be at least
+        /// `2 * (unbonding_delay - auction_delay) + 1` and `auction_delay + 1`.
+        #[structopt(long)]
+        retained_eras: Option<u64>,
+    },
 }
 
 #[derive(Debug)]
@@ -279,6 +315,58 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::ExportSlashingProtection { config, output } => {
+                let config = Self::init(&config, vec![])?;
+                let consensus_config = config.map_ref(|cfg| cfg.consensus.clone());
+                let count = consensus::export_slashing_protection(&consensus_config, &output)
+                    .with_context(|| output.display().to_string())?;
+                info!(%count, "exported slashing protection records");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportSlashingProtection { config, input } => {
+                let config = Self::init(&config, vec![])?;
+                let consensus_config = config.map_ref(|cfg| cfg.consensus.clone());
+                let count = consensus::import_slashing_protection(&consensus_config, &input)
+                    .with_context(|| input.display().to_string())?;
+                info!(%count, "imported slashing protection records");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::PruneGlobalState {
+                config,
+                retained_eras,
+            } => {
+                let config = Self::init(&config, vec![])?;
+                let retained_eras = retained_eras
+                    .or_else(|| config.value().contract_runtime.prune_retained_eras())
+                    .context(
+                        "--retained-eras must be given if contract_runtime.prune_retained_eras \
+                         is not set",
+                    )?;
+
+                let storage_config = config.map_ref(|cfg| cfg.storage.clone());
+                let root = storage_config.with_dir(storage_config.value().path.clone());
+                // Note: Do not change `_pidfile` to `_`, or it will be dropped prematurely.
+                let _pidfile = match PidFile::acquire(root.join("initializer.pid")) {
+                    PidFileOutcome::AnotherNodeRunning(_) => {
+                        anyhow::bail!("another node instance is running (pidfile is locked)");
+                    }
+                    PidFileOutcome::Crashed(_) => {
+                        anyhow::bail!(
+                            "previous node instance seems to have crashed, run the node to \
+                             check the integrity of the global state first"
+                        );
+                    }
+                    PidFileOutcome::Clean(pidfile) => pidfile,
+                    PidFileOutcome::PidFileError(err) => {
+                        return Err(anyhow::anyhow!(err));
+                    }
+                };
+
+                info!(%retained_eras, "pruning global state");
+                let deleted_count = contract_runtime::prune_global_state(&config, retained_eras)?;
+                info!(%deleted_count, "pruned global state");
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
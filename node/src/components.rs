@@ -12,6 +12,7 @@ pub(crate) mod event_stream_server;
 pub(crate) mod fetcher;
 pub(crate) mod gossiper;
 pub(crate) mod linear_chain;
+pub(crate) mod linear_chain_fast_sync;
 pub(crate) mod linear_chain_sync;
 pub(crate) mod rest_server;
 pub mod rpc_server;
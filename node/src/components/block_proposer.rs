@@ -22,7 +22,8 @@ use datasize::DataSize;
 use prometheus::{self, Registry};
 use tracing::{debug, error, info, trace, warn};
 
-use casper_types::PublicKey;
+use casper_execution_engine::core::engine_state::DEPLOY_NONCES_PROTOCOL_VERSION;
+use casper_types::{ProtocolVersion, PublicKey};
 
 use crate::{
     components::{
@@ -37,12 +38,13 @@ use crate::{
     types::{
         appendable_block::{AddError, AppendableBlock},
         chainspec::DeployConfig,
-        BlockPayload, Chainspec, DeployHash, DeployHeader, DeployOrTransferHash, Timestamp,
+        BlockPayload, Chainspec, DeployHash, DeployHeader, DeployOrTransferHash, Supersession,
+        Timestamp, DEPLOY_SUPERSESSION_PROTOCOL_VERSION,
     },
     NodeRng,
 };
-use deploy_sets::{BlockProposerDeploySets, PruneResult};
-pub(crate) use event::{DeployInfo, Event};
+use deploy_sets::{BlockProposerDeploySets, PruneResult, SupersessionRecord};
+pub(crate) use event::{DeployInfo, Event, PendingDeploy, PendingDeploys};
 use metrics::BlockProposerMetrics;
 
 /// Block proposer component.
@@ -86,6 +88,8 @@ enum BlockProposerState {
         pending: Vec<Event>,
         /// The deploy config from the current chainspec.
         deploy_config: DeployConfig,
+        /// The protocol version from the current chainspec.
+        protocol_version: ProtocolVersion,
         /// The configuration, containing local settings for deploy selection.
         local_config: Config,
     },
@@ -117,6 +121,7 @@ impl BlockProposer {
             state: BlockProposerState::Initializing {
                 pending: Vec::new(),
                 deploy_config: chainspec.deploy_config,
+                protocol_version: chainspec.protocol_version(),
                 local_config,
             },
             metrics: BlockProposerMetrics::new(registry)?,
@@ -149,6 +154,7 @@ where
                 BlockProposerState::Initializing {
                     ref mut pending,
                     deploy_config,
+                    protocol_version,
                     local_config,
                 },
                 Event::Loaded {
@@ -163,6 +169,7 @@ where
                     ),
                     unhandled_finalized: Default::default(),
                     deploy_config: *deploy_config,
+                    protocol_version: *protocol_version,
                     request_queue: Default::default(),
                     local_config: local_config.clone(),
                 };
@@ -216,14 +223,91 @@ struct BlockProposerReady {
     /// seen but were reported as reported to `finalized_deploys()`. They are used to
     /// filter deploys for proposal, similar to `self.sets.finalized_deploys`.
     unhandled_finalized: HashSet<DeployHash>,
-    /// We don't need the whole Chainspec here, just the deploy config.
+    /// We don't need the whole Chainspec here, just the deploy config...
     deploy_config: DeployConfig,
+    /// ...and the protocol version.
+    protocol_version: ProtocolVersion,
     /// The queue of requests awaiting being handled.
     request_queue: RequestQueue,
     /// The block proposer configuration, containing local settings for selecting deploys.
     local_config: Config,
 }
 
+/// A block payload in the process of being proposed.
+struct Proposal<'a> {
+    /// The block being assembled.
+    appendable_block: AppendableBlock,
+    /// The timestamp of the proposed block.
+    block_timestamp: Timestamp,
+    /// The deploys and transfers included in the ancestors of the proposed block which are not
+    /// finalized yet.
+    past_deploys: HashSet<DeployHash>,
+    /// The number of deploys and transfers from each account included so far.
+    account_counts: HashMap<PublicKey, u32>,
+    /// The nonce which the next deploy or transfer from each account has to use.
+    next_nonces: HashMap<PublicKey, u64>,
+    /// The accounts of which a transfer with a nonce was included so far.
+    nonced_transfer_accounts: HashSet<PublicKey>,
+    /// The pending deploys and transfers with a nonce, by account and nonce.
+    pending_by_nonce: HashMap<(PublicKey, u64), (DeployOrTransferHash, &'a DeployInfo, Timestamp)>,
+}
+
+impl<'a> Proposal<'a> {
+    /// Checks if a deploy's nonce, if any, is the next one of its account.
+    ///
+    /// All deploys of a block are executed before its transfers, so a deploy with a nonce also has
+    /// to wait for the next block if a transfer with a lower nonce from the same account is
+    /// already included.
+    fn nonce_resolved(&self, hash: &DeployOrTransferHash, deploy_info: &DeployInfo) -> bool {
+        let nonce = match deploy_info.nonce {
+            Some(nonce) => nonce,
+            None => return true,
+        };
+        let account = deploy_info.header.account();
+        if !hash.is_transfer() && self.nonced_transfer_accounts.contains(account) {
+            return false;
+        }
+        self.next_nonces.get(account) == Some(&nonce)
+    }
+
+    /// Returns the pending deploy or transfer with the next nonce of the given account, if any.
+    fn next_nonced(
+        &self,
+        account: &PublicKey,
+    ) -> Option<(DeployOrTransferHash, &'a DeployInfo, Timestamp)> {
+        let next_nonce = *self.next_nonces.get(account)?;
+        self.pending_by_nonce
+            .get(&(account.clone(), next_nonce))
+            .copied()
+    }
+
+    /// Adds a deploy or transfer to the block.
+    fn add(
+        &mut self,
+        hash: DeployOrTransferHash,
+        deploy_info: &DeployInfo,
+    ) -> Result<(), AddError> {
+        match hash {
+            DeployOrTransferHash::Deploy(hash) => {
+                self.appendable_block.add_deploy(hash, deploy_info)?
+            }
+            DeployOrTransferHash::Transfer(hash) => {
+                self.appendable_block.add_transfer(hash, deploy_info)?
+            }
+        }
+        let account = deploy_info.header.account();
+        *self.account_counts.entry(account.clone()).or_default() += 1;
+        if let Some(nonce) = deploy_info.nonce {
+            self.next_nonces
+                .insert(account.clone(), nonce.saturating_add(1));
+            if hash.is_transfer() {
+                self.nonced_transfer_accounts.insert(account.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
 impl BlockProposerReady {
     fn handle_event<REv>(
         &mut self,
@@ -258,9 +342,16 @@ impl BlockProposerReady {
                         .ignore()
                 }
             }
+            Event::Request(BlockProposerRequest::GetPendingDeploys(responder)) => {
+                responder.respond(self.pending_deploys()).ignore()
+            }
             Event::BufferDeploy { hash, deploy_info } => {
-                self.add_deploy(Timestamp::now(), hash, *deploy_info);
-                Effects::new()
+                match self.add_deploy(Timestamp::now(), hash, *deploy_info) {
+                    Some((superseded, superseded_by)) => effect_builder
+                        .announce_deploy_superseded(superseded, superseded_by)
+                        .ignore(),
+                    None => Effects::new(),
+                }
             }
             Event::Prune => {
                 // Re-trigger timer after `PRUNE_INTERVAL`.
@@ -328,40 +419,181 @@ impl BlockProposerReady {
     }
 
     /// Adds a deploy or a transfer to the block proposer.
+    ///
+    /// Returns the hashes of the superseded deploy and of the deploy superseding it, if the new
+    /// deploy supersedes a pending one or was itself superseded before we received it.
     fn add_deploy(
         &mut self,
         current_instant: Timestamp,
         hash: DeployOrTransferHash,
-        deploy_info: DeployInfo,
-    ) {
+        mut deploy_info: DeployInfo,
+    ) -> Option<(DeployHash, DeployHash)> {
+        if self.protocol_version < DEPLOY_NONCES_PROTOCOL_VERSION {
+            deploy_info.nonce = None;
+        }
+        if self.protocol_version < DEPLOY_SUPERSESSION_PROTOCOL_VERSION {
+            deploy_info.supersession = None;
+        }
         if deploy_info.header.expired(current_instant) {
             trace!(%hash, "expired deploy rejected from the buffer");
-            return;
+            return None;
         }
         if self.unhandled_finalized.remove(hash.deploy_hash()) {
             info!(%hash, "deploy was previously marked as finalized, storing header");
             self.sets
                 .finalized_deploys
                 .insert(hash.into(), deploy_info.header);
-            return;
+            return None;
         }
         // only add the deploy if it isn't contained in a finalized block
         if self.sets.finalized_deploys.contains_key(hash.deploy_hash()) {
             info!(%hash, "deploy rejected from the buffer");
-            return;
+            return None;
         }
+        if let Some(superseded_by) = self.superseded_on_arrival(&hash, &deploy_info) {
+            return Some((*hash.deploy_hash(), superseded_by));
+        }
+
+        let superseded = match deploy_info.supersession {
+            Some(supersession) => match self.supersede(&hash, &deploy_info, supersession) {
+                Ok(superseded) => superseded,
+                Err(()) => return None,
+            },
+            None => None,
+        };
 
         if hash.is_transfer() {
             self.sets
                 .pending_transfers
-                .insert(*hash.deploy_hash(), (deploy_info, current_instant));
+                .insert(*hash.deploy_hash(), deploy_info, current_instant);
         } else {
             self.sets
                 .pending_deploys
-                .insert(*hash.deploy_hash(), (deploy_info, current_instant));
+                .insert(*hash.deploy_hash(), deploy_info, current_instant);
         }
 
         info!(%hash, "added deploy to the buffer");
+        superseded.map(|superseded| (superseded, *hash.deploy_hash()))
+    }
+
+    /// Checks whether a newly received deploy was superseded by a deploy we received earlier, and
+    /// returns the hash of the superseding deploy if so.
+    ///
+    /// If the earlier deploy turns out to be a replacement not offering a higher gas price, the
+    /// earlier deploy is dropped instead, just as if the deploys had arrived in order.
+    fn superseded_on_arrival(
+        &mut self,
+        hash: &DeployOrTransferHash,
+        deploy_info: &DeployInfo,
+    ) -> Option<DeployHash> {
+        let record = self.sets.superseded_deploys.remove(hash.deploy_hash())?;
+        let superseded_by = record.superseded_by;
+        if &record.account != deploy_info.header.account() {
+            info!(%hash, %superseded_by, "ignoring supersession of deploy from another account");
+            return None;
+        }
+        if let Some(gas_price) = record.replacement_gas_price {
+            if gas_price <= deploy_info.header.gas_price() {
+                info!(
+                    %hash,
+                    %superseded_by,
+                    "replacement deploy dropped: gas price not higher than superseded deploy"
+                );
+                self.sets.pending_deploys.remove(&superseded_by);
+                self.sets.pending_transfers.remove(&superseded_by);
+                return None;
+            }
+        }
+        // Keep the record until the superseded deploy expires, in case we receive it again.
+        self.sets.superseded_deploys.insert(
+            *hash.deploy_hash(),
+            SupersessionRecord {
+                expires: deploy_info.header.expires(),
+                ..record
+            },
+        );
+        info!(%hash, %superseded_by, "deploy was superseded before we received it");
+        Some(superseded_by)
+    }
+
+    /// Removes the pending deploy named by `supersession` if it was sent from the same account as
+    /// the new deploy, and, in the case of a replacement, offers a strictly lower gas price.
+    ///
+    /// If the superseded deploy isn't pending yet, the supersession is recorded and applied once
+    /// it arrives, so that the outcome doesn't depend on the order in which we receive them.
+    ///
+    /// Returns `Err(())` if the new deploy should be dropped, i.e. it attempts to replace a pending
+    /// deploy without offering a higher gas price, or one which has already been finalized.
+    fn supersede(
+        &mut self,
+        hash: &DeployOrTransferHash,
+        deploy_info: &DeployInfo,
+        supersession: Supersession,
+    ) -> Result<Option<DeployHash>, ()> {
+        let superseded_hash = *supersession.superseded();
+        let replacement_gas_price = match supersession {
+            Supersession::Replace(_) => Some(deploy_info.header.gas_price()),
+            Supersession::Cancel(_) => None,
+        };
+        if replacement_gas_price.is_some() && self.contains_finalized(&superseded_hash) {
+            info!(
+                %hash,
+                %superseded_hash,
+                "replacement deploy rejected: superseded deploy already finalized"
+            );
+            return Err(());
+        }
+        let pending = if self.sets.pending_deploys.contains_key(&superseded_hash) {
+            &mut self.sets.pending_deploys
+        } else if self.sets.pending_transfers.contains_key(&superseded_hash) {
+            &mut self.sets.pending_transfers
+        } else {
+            debug!(%hash, %superseded_hash, "superseded deploy is not pending yet");
+            // The superseded deploy was created before the new one, so it has expired at the
+            // latest once the new one's timestamp is the maximum TTL in the past.
+            let expires = deploy_info.header.timestamp() + self.deploy_config.max_ttl;
+            self.sets
+                .superseded_deploys
+                .entry(superseded_hash)
+                .or_insert_with(|| SupersessionRecord {
+                    superseded_by: *hash.deploy_hash(),
+                    account: deploy_info.header.account().clone(),
+                    replacement_gas_price,
+                    expires,
+                });
+            return Ok(None);
+        };
+        let superseded_header = match pending.get(&superseded_hash) {
+            Some((superseded_info, _)) => &superseded_info.header,
+            None => return Ok(None),
+        };
+        if superseded_header.account() != deploy_info.header.account() {
+            info!(%hash, %superseded_hash, "ignoring supersession of deploy from another account");
+            return Ok(None);
+        }
+        if let Some(gas_price) = replacement_gas_price {
+            if gas_price <= superseded_header.gas_price() {
+                info!(
+                    %hash,
+                    %superseded_hash,
+                    "replacement deploy rejected: gas price not higher than superseded deploy"
+                );
+                return Err(());
+            }
+        }
+        let expires = superseded_header.expires();
+        pending.remove(&superseded_hash);
+        self.sets.superseded_deploys.insert(
+            superseded_hash,
+            SupersessionRecord {
+                superseded_by: *hash.deploy_hash(),
+                account: deploy_info.header.account().clone(),
+                replacement_gas_price,
+                expires,
+            },
+        );
+        info!(%hash, %superseded_hash, "superseded pending deploy");
+        Ok(Some(superseded_hash))
     }
 
     /// Notifies the block proposer that a block has been finalized.
@@ -380,6 +612,9 @@ impl BlockProposerReady {
             };
             match remove_result {
                 Some((deploy_info, _)) => {
+                    if let Some(nonce) = deploy_info.nonce {
+                        self.sets.record_finalized_nonce(&deploy_info.header, nonce);
+                    }
                     self.sets.finalized_deploys.insert(hash, deploy_info.header);
                 }
                 // If we haven't seen this deploy before, we still need to take note of it.
@@ -432,6 +667,143 @@ impl BlockProposerReady {
             .all(|dep| past_deploys.contains(dep) || self.contains_finalized(dep))
     }
 
+    /// Returns the nonce which the next deploy or transfer has to use, for each account with
+    /// pending deploys or transfers with a nonce not yet included in an ancestor block.
+    ///
+    /// This is one more than the account's highest nonce in finalized blocks and `past_deploys`.
+    /// If we don't know of any, e.g. after a restart, it is the account's lowest pending nonce.
+    fn next_nonces(&self, past_deploys: &HashSet<DeployHash>) -> HashMap<PublicKey, u64> {
+        let mut latest_nonces: HashMap<PublicKey, u64> = self
+            .sets
+            .finalized_nonces
+            .iter()
+            .map(|(account, (nonce, _))| (account.clone(), *nonce))
+            .collect();
+        let mut lowest_pending_nonces: HashMap<PublicKey, u64> = HashMap::new();
+        let pending = self
+            .sets
+            .pending_transfers
+            .iter()
+            .chain(self.sets.pending_deploys.iter());
+        for (hash, (deploy_info, _)) in pending {
+            let nonce = match deploy_info.nonce {
+                Some(nonce) => nonce,
+                None => continue,
+            };
+            let account = deploy_info.header.account().clone();
+            if past_deploys.contains(hash) {
+                let latest = latest_nonces.entry(account).or_insert(nonce);
+                *latest = (*latest).max(nonce);
+            } else {
+                let lowest = lowest_pending_nonces.entry(account).or_insert(nonce);
+                *lowest = (*lowest).min(nonce);
+            }
+        }
+        lowest_pending_nonces
+            .into_iter()
+            .map(|(account, lowest_pending)| {
+                let next = latest_nonces
+                    .get(&account)
+                    .map_or(lowest_pending, |latest| latest.saturating_add(1));
+                (account, next)
+            })
+            .collect()
+    }
+
+    /// Returns the pending deploys and transfers with a nonce which are not yet included in an
+    /// ancestor block, by account and nonce.
+    ///
+    /// If an account used a nonce more than once, the deploy or transfer which would be tried
+    /// first for inclusion is kept.
+    fn pending_by_nonce(
+        &self,
+        past_deploys: &HashSet<DeployHash>,
+    ) -> HashMap<(PublicKey, u64), (DeployOrTransferHash, &DeployInfo, Timestamp)> {
+        let transfers = self
+            .sets
+            .pending_transfers
+            .by_priority()
+            .map(|(hash, entry)| (DeployOrTransferHash::Transfer(*hash), entry));
+        let deploys = self
+            .sets
+            .pending_deploys
+            .by_priority()
+            .map(|(hash, entry)| (DeployOrTransferHash::Deploy(*hash), entry));
+        let mut pending_by_nonce = HashMap::new();
+        for (hash, (deploy_info, received_time)) in transfers.chain(deploys) {
+            if past_deploys.contains(hash.deploy_hash()) {
+                continue;
+            }
+            if let Some(nonce) = deploy_info.nonce {
+                pending_by_nonce
+                    .entry((deploy_info.header.account().clone(), nonce))
+                    .or_insert((hash, deploy_info, *received_time));
+            }
+        }
+        pending_by_nonce
+    }
+
+    /// Checks if a pending deploy or transfer is eligible for inclusion in the proposed block.
+    fn is_eligible(
+        &self,
+        proposal: &Proposal,
+        hash: &DeployOrTransferHash,
+        deploy_info: &DeployInfo,
+        received_time: Timestamp,
+    ) -> bool {
+        let account = deploy_info.header.account();
+        self.deps_resolved(&deploy_info.header, &proposal.past_deploys)
+            && proposal.nonce_resolved(hash, deploy_info)
+            && !proposal.past_deploys.contains(hash.deploy_hash())
+            && !self.contains_finalized(hash.deploy_hash())
+            && !self.replaces_included(proposal, deploy_info)
+            && proposal.block_timestamp.saturating_diff(received_time)
+                >= self.local_config.deploy_delay
+            && proposal
+                .account_counts
+                .get(account)
+                .copied()
+                .unwrap_or_default()
+                < self.local_config.max_deploys_per_account
+    }
+
+    /// Checks if a deploy replaces one which was already included in an ancestor of the proposed
+    /// block or in a finalized block.
+    fn replaces_included(&self, proposal: &Proposal, deploy_info: &DeployInfo) -> bool {
+        match deploy_info.supersession {
+            Some(Supersession::Replace(superseded)) => {
+                proposal.past_deploys.contains(&superseded) || self.contains_finalized(&superseded)
+            }
+            Some(Supersession::Cancel(_)) | None => false,
+        }
+    }
+
+    /// Adds a pending deploy or transfer to the proposed block.
+    ///
+    /// If it has a nonce, the account's pending deploys and transfers with the following nonces
+    /// are added right after it, for as long as they are eligible and fit into the block.
+    fn add_to_block(
+        &self,
+        proposal: &mut Proposal,
+        hash: DeployOrTransferHash,
+        deploy_info: &DeployInfo,
+    ) -> Result<(), AddError> {
+        proposal.add(hash, deploy_info)?;
+        if deploy_info.nonce.is_none() {
+            return Ok(());
+        }
+        let account = deploy_info.header.account();
+        while let Some((next_hash, next_deploy_info, received_time)) = proposal.next_nonced(account)
+        {
+            if !self.is_eligible(proposal, &next_hash, next_deploy_info, received_time)
+                || proposal.add(next_hash, next_deploy_info).is_err()
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns a list of candidates for inclusion into a block.
     fn propose_block_payload(
         &mut self,
@@ -448,26 +820,35 @@ impl BlockProposerReady {
             .take_while(|hash| !self.contains_finalized(hash))
             .collect();
         let block_timestamp = context.timestamp();
-        let mut appendable_block = AppendableBlock::new(deploy_config, block_timestamp);
-
-        // We prioritize transfers over deploys, so we try to include them first.
-        for (hash, (deploy_info, received_time)) in &self.sets.pending_transfers {
-            if !self.deps_resolved(&deploy_info.header, &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
-            {
+        let mut proposal = Proposal {
+            appendable_block: AppendableBlock::new(deploy_config, block_timestamp),
+            block_timestamp,
+            next_nonces: self.next_nonces(&past_deploys),
+            pending_by_nonce: self.pending_by_nonce(&past_deploys),
+            past_deploys,
+            account_counts: HashMap::new(),
+            nonced_transfer_accounts: HashSet::new(),
+        };
+
+        // We prioritize transfers over deploys, so we try to include them first.  Within each
+        // collection, candidates are tried in order of descending gas price, then of age.
+        for (hash, (deploy_info, received_time)) in self.sets.pending_transfers.by_priority() {
+            let hash = DeployOrTransferHash::Transfer(*hash);
+            if !self.is_eligible(&proposal, &hash, deploy_info, *received_time) {
                 continue;
             }
 
-            if let Err(err) = appendable_block.add_transfer(*hash, deploy_info) {
+            if let Err(err) = self.add_to_block(&mut proposal, hash, deploy_info) {
                 match err {
                     // We added the maximum number of transfers.
                     AddError::TransferCount | AddError::GasLimit | AddError::BlockSize => break,
                     // The deploy is not valid in this block, but might be valid in another.
-                    AddError::InvalidDeploy => (),
+                    AddError::InvalidDeploy | AddError::SupersessionConflict => (),
                     // These errors should never happen when adding a transfer.
-                    AddError::InvalidGasAmount | AddError::DeployCount | AddError::Duplicate => {
+                    AddError::InvalidGasAmount
+                    | AddError::DeployCount
+                    | AddError::Duplicate
+                    | AddError::DuplicateNonce => {
                         error!(?err, "unexpected error when adding transfer")
                     }
                 }
@@ -475,21 +856,18 @@ impl BlockProposerReady {
         }
 
         // Now we try to add other deploys to the block.
-        for (hash, (deploy_info, received_time)) in &self.sets.pending_deploys {
-            if !self.deps_resolved(&deploy_info.header, &past_deploys)
-                || past_deploys.contains(hash)
-                || self.contains_finalized(hash)
-                || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
-            {
+        for (hash, (deploy_info, received_time)) in self.sets.pending_deploys.by_priority() {
+            let hash = DeployOrTransferHash::Deploy(*hash);
+            if !self.is_eligible(&proposal, &hash, deploy_info, *received_time) {
                 continue;
             }
 
-            if let Err(err) = appendable_block.add_deploy(*hash, deploy_info) {
+            if let Err(err) = self.add_to_block(&mut proposal, hash, deploy_info) {
                 match err {
                     // We added the maximum number of deploys.
                     AddError::DeployCount => break,
                     AddError::BlockSize => {
-                        if appendable_block.total_size() + DEPLOY_APPROX_MIN_SIZE
+                        if proposal.appendable_block.total_size() + DEPLOY_APPROX_MIN_SIZE
                             > deploy_config.block_gas_limit as usize
                         {
                             break; // Probably no deploy will fit in this block anymore.
@@ -497,9 +875,11 @@ impl BlockProposerReady {
                     }
                     // The deploy is not valid in this block, but might be valid in another.
                     // TODO: Do something similar to DEPLOY_APPROX_MIN_SIZE for gas.
-                    AddError::InvalidDeploy | AddError::GasLimit => (),
+                    AddError::InvalidDeploy
+                    | AddError::GasLimit
+                    | AddError::SupersessionConflict => (),
                     // These errors should never happen when adding a deploy.
-                    AddError::TransferCount | AddError::Duplicate => {
+                    AddError::TransferCount | AddError::Duplicate | AddError::DuplicateNonce => {
                         error!(?err, "unexpected error when adding deploy")
                     }
                     AddError::InvalidGasAmount => {
@@ -509,7 +889,57 @@ impl BlockProposerReady {
             }
         }
 
-        Arc::new(appendable_block.into_block_payload(accusations, random_bit))
+        Arc::new(
+            proposal
+                .appendable_block
+                .into_block_payload(accusations, random_bit),
+        )
+    }
+
+    /// Returns a snapshot of the deploys currently held by the block proposer.
+    fn pending_deploys(&self) -> PendingDeploys {
+        let pending_transfers = self
+            .sets
+            .pending_transfers
+            .by_priority()
+            .map(|(hash, (deploy_info, received_time))| {
+                self.pending_deploy(*hash, deploy_info, *received_time)
+            })
+            .collect();
+        let pending_deploys = self
+            .sets
+            .pending_deploys
+            .by_priority()
+            .map(|(hash, (deploy_info, received_time))| {
+                self.pending_deploy(*hash, deploy_info, *received_time)
+            })
+            .collect();
+        PendingDeploys {
+            pending_transfers,
+            pending_deploys,
+            finalized_deploy_count: self.sets.finalized_deploys.len(),
+        }
+    }
+
+    fn pending_deploy(
+        &self,
+        hash: DeployHash,
+        deploy_info: &DeployInfo,
+        received_time: Timestamp,
+    ) -> PendingDeploy {
+        let unresolved_dependencies = deploy_info
+            .header
+            .dependencies()
+            .iter()
+            .filter(|dep| !self.contains_finalized(dep))
+            .copied()
+            .collect();
+        PendingDeploy {
+            hash,
+            header: deploy_info.header.clone(),
+            received_time,
+            unresolved_dependencies,
+        }
     }
 
     /// Prunes expired deploy information from the BlockProposer, returns the hashes of deploys
@@ -13,12 +13,18 @@ pub struct Config {
     /// other nodes, and don't have to be requested from the proposer afterwards.
     #[serde(default = "default_deploy_delay")]
     pub deploy_delay: TimeDiff,
+    /// The maximum number of deploys and transfers from any single account which are proposed in
+    /// a new block.  This prevents a single account from crowding out all others by flooding the
+    /// network with deploys.
+    #[serde(default = "default_max_deploys_per_account")]
+    pub max_deploys_per_account: u32,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             deploy_delay: default_deploy_delay(),
+            max_deploys_per_account: default_max_deploys_per_account(),
         }
     }
 }
@@ -26,3 +32,7 @@ impl Default for Config {
 fn default_deploy_delay() -> TimeDiff {
     "1min".parse().unwrap()
 }
+
+fn default_max_deploys_per_account() -> u32 {
+    50
+}
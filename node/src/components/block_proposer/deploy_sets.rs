@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
     fmt::{self, Display, Formatter},
     hash::Hash,
 };
@@ -8,6 +9,8 @@ use itertools::{Either, Itertools};
 
 use datasize::DataSize;
 
+use casper_types::PublicKey;
+
 use super::{event::DeployInfo, BlockHeight, FinalizationQueue};
 use crate::types::{DeployHash, DeployHeader, Timestamp};
 
@@ -30,12 +33,15 @@ impl PruneResult {
 pub(super) struct BlockProposerDeploySets {
     /// The collection of deploys pending for inclusion in a block, with a timestamp of when we
     /// received them.
-    pub(super) pending_deploys: HashMap<DeployHash, (DeployInfo, Timestamp)>,
+    pub(super) pending_deploys: PendingDeploySet,
     /// The collection of transfers pending for inclusion in a block, with a timestamp of when we
     /// received them.
-    pub(super) pending_transfers: HashMap<DeployHash, (DeployInfo, Timestamp)>,
+    pub(super) pending_transfers: PendingDeploySet,
     /// The deploys that have already been included in a finalized block.
     pub(super) finalized_deploys: HashMap<DeployHash, DeployHeader>,
+    /// The highest nonce of each account among the finalized deploys we know, together with the
+    /// expiry time of the deploy using it.
+    pub(super) finalized_nonces: HashMap<PublicKey, (u64, Timestamp)>,
     /// The next block height we expect to be finalized.
     /// If we receive a notification of finalization of a later block, we will store it in
     /// finalization_queue.
@@ -44,6 +50,24 @@ pub(super) struct BlockProposerDeploySets {
     pub(super) next_finalized: BlockHeight,
     /// The queue of finalized block contents awaiting inclusion in `self.finalized_deploys`.
     pub(super) finalization_queue: FinalizationQueue,
+    /// The deploys superseded by a later deploy, whether or not we have received them yet.  They
+    /// are remembered until they expire, so that receiving them again or only after the
+    /// superseding deploy doesn't make them pending.
+    pub(super) superseded_deploys: HashMap<DeployHash, SupersessionRecord>,
+}
+
+/// A deploy's supersession by a later deploy.
+#[derive(Clone, DataSize, Debug)]
+pub(super) struct SupersessionRecord {
+    /// The hash of the superseding deploy.
+    pub(super) superseded_by: DeployHash,
+    /// The account of the superseding deploy.
+    pub(super) account: PublicKey,
+    /// The gas price of the superseding deploy if it is a replacement, or `None` if it is a
+    /// cancellation.
+    pub(super) replacement_gas_price: Option<u64>,
+    /// The time after which the superseded deploy can't be executed anymore.
+    pub(super) expires: Timestamp,
 }
 
 impl BlockProposerDeploySets {
@@ -72,16 +96,33 @@ impl Display for BlockProposerDeploySets {
 }
 
 impl BlockProposerDeploySets {
+    /// Records the nonce of a finalized deploy, if it is the highest one of its account.
+    pub(super) fn record_finalized_nonce(&mut self, header: &DeployHeader, nonce: u64) {
+        let expires = header.expires();
+        self.finalized_nonces
+            .entry(header.account().clone())
+            .and_modify(|latest| {
+                if nonce > latest.0 {
+                    *latest = (nonce, expires);
+                }
+            })
+            .or_insert((nonce, expires));
+    }
+
     /// Prunes expired deploy information from the BlockProposerState, returns the
     /// hashes of deploys pruned.
     pub(crate) fn prune(&mut self, current_instant: Timestamp) -> PruneResult {
-        let pending_deploys = prune_pending_deploys(&mut self.pending_deploys, current_instant);
-        let pending_transfers = prune_pending_deploys(&mut self.pending_transfers, current_instant);
+        let pending_deploys = self.pending_deploys.prune(current_instant);
+        let pending_transfers = self.pending_transfers.prune(current_instant);
 
         // We prune from finalized deploys collection because expired deploys
         // can never be proposed again. This makes this collection smaller for
         // later iterations.
         let finalized = prune_deploys(&mut self.finalized_deploys, current_instant);
+        self.finalized_nonces
+            .retain(|_, (_, expires)| *expires >= current_instant);
+        self.superseded_deploys
+            .retain(|_, record| record.expires >= current_instant);
 
         // We return a total of pruned deploys, but for the deploys pruned
         // from the `finalized` collection we don't want to send
@@ -93,6 +134,129 @@ impl BlockProposerDeploySets {
     }
 }
 
+/// The priority of a pending deploy when proposing a block.
+///
+/// Deploys offering a higher gas price come first; among those with equal gas price, the ones we
+/// received earlier come first.  The deploy hash is used as a final tie-breaker so that the order
+/// is total and deterministic.
+#[derive(Clone, Copy, DataSize, Debug, Eq, PartialEq)]
+struct DeployPriority {
+    gas_price: u64,
+    received_time: Timestamp,
+    hash: DeployHash,
+}
+
+impl DeployPriority {
+    fn new(hash: DeployHash, deploy_info: &DeployInfo, received_time: Timestamp) -> Self {
+        DeployPriority {
+            gas_price: deploy_info.header.gas_price(),
+            received_time,
+            hash,
+        }
+    }
+}
+
+impl Ord for DeployPriority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.gas_price
+            .cmp(&other.gas_price)
+            .then_with(|| other.received_time.cmp(&self.received_time))
+            .then_with(|| other.hash.cmp(&self.hash))
+    }
+}
+
+impl PartialOrd for DeployPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A collection of deploys pending for inclusion in a block, with a timestamp of when we received
+/// them.
+///
+/// The deploys' priorities are kept in an ordered set alongside them, updated whenever a deploy is
+/// added or removed, so that they can be iterated in priority order without sorting them anew for
+/// each proposal.
+#[derive(Clone, DataSize, Debug, Default)]
+pub(super) struct PendingDeploySet {
+    /// The pending deploys, with a timestamp of when we received them.
+    deploys: HashMap<DeployHash, (DeployInfo, Timestamp)>,
+    /// The priorities of the pending deploys, lowest first.
+    by_priority: BTreeSet<DeployPriority>,
+}
+
+impl PendingDeploySet {
+    /// Returns the number of pending deploys.
+    pub(super) fn len(&self) -> usize {
+        self.deploys.len()
+    }
+
+    /// Returns whether the deploy is pending.
+    pub(super) fn contains_key(&self, hash: &DeployHash) -> bool {
+        self.deploys.contains_key(hash)
+    }
+
+    /// Returns the pending deploy and the time we received it.
+    pub(super) fn get(&self, hash: &DeployHash) -> Option<&(DeployInfo, Timestamp)> {
+        self.deploys.get(hash)
+    }
+
+    /// Adds a pending deploy, replacing any previous entry for the same hash.
+    pub(super) fn insert(
+        &mut self,
+        hash: DeployHash,
+        deploy_info: DeployInfo,
+        received_time: Timestamp,
+    ) {
+        let priority = DeployPriority::new(hash, &deploy_info, received_time);
+        if let Some((old_deploy_info, old_received_time)) =
+            self.deploys.insert(hash, (deploy_info, received_time))
+        {
+            let old_priority = DeployPriority::new(hash, &old_deploy_info, old_received_time);
+            self.by_priority.remove(&old_priority);
+        }
+        self.by_priority.insert(priority);
+    }
+
+    /// Removes a pending deploy, returning it and the time we received it.
+    pub(super) fn remove(&mut self, hash: &DeployHash) -> Option<(DeployInfo, Timestamp)> {
+        let (deploy_info, received_time) = self.deploys.remove(hash)?;
+        self.by_priority
+            .remove(&DeployPriority::new(*hash, &deploy_info, received_time));
+        Some((deploy_info, received_time))
+    }
+
+    /// Returns the pending deploys in arbitrary order.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (&DeployHash, &(DeployInfo, Timestamp))> {
+        self.deploys.iter()
+    }
+
+    /// Returns the pending deploys in the order in which they should be considered for inclusion
+    /// in a block, i.e. by descending gas price, then by ascending receipt time.
+    pub(super) fn by_priority(
+        &self,
+    ) -> impl Iterator<Item = (&DeployHash, &(DeployInfo, Timestamp))> {
+        self.by_priority
+            .iter()
+            .rev()
+            .filter_map(move |priority| self.deploys.get_key_value(&priority.hash))
+    }
+
+    /// Prunes expired deploys, returns the hashes of deploys pruned.
+    pub(super) fn prune(&mut self, current_instant: Timestamp) -> Vec<DeployHash> {
+        let expired: Vec<DeployHash> = self
+            .deploys
+            .iter()
+            .filter(|(_, (deploy_info, _))| deploy_info.header.expired(current_instant))
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in &expired {
+            self.remove(hash);
+        }
+        expired
+    }
+}
+
 /// Drains items that satisfy the given predicate from the hash map and retains the rest.
 /// Returns keys of the drained elements.
 ///
@@ -121,27 +285,16 @@ fn prune_deploys(
     hashmap_drain_filter_in_place(deploys, |header| header.expired(current_instant))
 }
 
-/// Prunes expired deploy information from an individual pending deploy collection, returns the
-/// hashes of deploys pruned.
-pub(super) fn prune_pending_deploys(
-    deploys: &mut HashMap<DeployHash, (DeployInfo, Timestamp)>,
-    current_instant: Timestamp,
-) -> Vec<DeployHash> {
-    hashmap_drain_filter_in_place(deploys, |(deploy_info, _)| {
-        deploy_info.header.expired(current_instant)
-    })
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::{testing, testing::TestRng};
+    use crate::{testing, testing::TestRng, types::TimeDiff};
 
     use super::*;
 
     #[test]
     fn prunes_pending_deploys() {
         let mut test_rng = TestRng::new();
-        let mut deploys: HashMap<DeployHash, (DeployInfo, Timestamp)> = HashMap::new();
+        let mut deploys = PendingDeploySet::default();
         let now = Timestamp::now();
 
         let deploy_1 = testing::create_not_expired_deploy(now, &mut test_rng);
@@ -150,16 +303,16 @@ mod tests {
         let deploy_4 = testing::create_not_expired_deploy(now, &mut test_rng);
         let deploy_5 = testing::create_expired_deploy(now, &mut test_rng);
 
-        deploys.insert(*deploy_1.id(), (deploy_1.deploy_info().unwrap(), now));
-        deploys.insert(*deploy_2.id(), (deploy_2.deploy_info().unwrap(), now));
-        deploys.insert(*deploy_3.id(), (deploy_3.deploy_info().unwrap(), now));
-        deploys.insert(*deploy_4.id(), (deploy_4.deploy_info().unwrap(), now));
-        deploys.insert(*deploy_5.id(), (deploy_5.deploy_info().unwrap(), now));
+        deploys.insert(*deploy_1.id(), deploy_1.deploy_info().unwrap(), now);
+        deploys.insert(*deploy_2.id(), deploy_2.deploy_info().unwrap(), now);
+        deploys.insert(*deploy_3.id(), deploy_3.deploy_info().unwrap(), now);
+        deploys.insert(*deploy_4.id(), deploy_4.deploy_info().unwrap(), now);
+        deploys.insert(*deploy_5.id(), deploy_5.deploy_info().unwrap(), now);
 
         // We expect deploys created with `create_expired_deploy` to be drained
         let mut expected_drained = vec![*deploy_2.id(), *deploy_3.id(), *deploy_5.id()];
         expected_drained.sort();
-        let mut drained = prune_pending_deploys(&mut deploys, now);
+        let mut drained = deploys.prune(now);
         drained.sort();
         assert_eq!(expected_drained, drained);
 
@@ -167,11 +320,67 @@ mod tests {
         let mut expected_retained = vec![*deploy_1.id(), *deploy_4.id()];
         expected_retained.sort();
         let mut retained = deploys
-            .into_iter()
-            .map(|(deploy_hash, _)| deploy_hash)
+            .iter()
+            .map(|(deploy_hash, _)| *deploy_hash)
             .collect::<Vec<_>>();
         retained.sort();
         assert_eq!(expected_retained, retained);
+
+        // The pruned deploys should also have been removed from the priority order.
+        let mut retained_by_priority = deploys
+            .by_priority()
+            .map(|(deploy_hash, _)| *deploy_hash)
+            .collect::<Vec<_>>();
+        retained_by_priority.sort();
+        assert_eq!(expected_retained, retained_by_priority);
+    }
+
+    #[test]
+    fn keeps_pending_deploys_in_priority_order() {
+        let mut test_rng = TestRng::new();
+        let mut deploys = PendingDeploySet::default();
+        let now = Timestamp::now();
+
+        // The order maintained on insertion and removal should match sorting all pending deploys.
+        let assert_priority_order = |deploys: &PendingDeploySet| {
+            let mut priorities = deploys
+                .iter()
+                .map(|(hash, (deploy_info, received_time))| {
+                    DeployPriority::new(*hash, deploy_info, *received_time)
+                })
+                .collect::<Vec<_>>();
+            priorities.sort_by(|a, b| b.cmp(a));
+            let expected = priorities
+                .into_iter()
+                .map(|priority| priority.hash)
+                .collect::<Vec<_>>();
+            let actual = deploys
+                .by_priority()
+                .map(|(hash, _)| *hash)
+                .collect::<Vec<_>>();
+            assert_eq!(expected, actual);
+        };
+
+        let pending = (0..10)
+            .map(|_| testing::create_not_expired_deploy(now, &mut test_rng))
+            .collect::<Vec<_>>();
+        for deploy in &pending {
+            deploys.insert(*deploy.id(), deploy.deploy_info().unwrap(), now);
+        }
+        assert_eq!(deploys.len(), 10);
+        assert_priority_order(&deploys);
+
+        // Re-inserting a deploy with a later receipt time replaces its previous priority.
+        let later = now + TimeDiff::from_seconds(1);
+        deploys.insert(*pending[0].id(), pending[0].deploy_info().unwrap(), later);
+        assert_eq!(deploys.len(), 10);
+        assert_eq!(deploys.by_priority().count(), 10);
+        assert_priority_order(&deploys);
+
+        assert!(deploys.remove(pending[1].id()).is_some());
+        assert!(deploys.remove(pending[1].id()).is_none());
+        assert_eq!(deploys.by_priority().count(), 9);
+        assert_priority_order(&deploys);
     }
 
     mod hash_map_drain_filter_in_place {
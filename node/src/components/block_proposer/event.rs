@@ -10,7 +10,9 @@ use casper_types::Motes;
 use super::BlockHeight;
 use crate::{
     effect::requests::BlockProposerRequest,
-    types::{DeployHash, DeployHeader, DeployOrTransferHash, FinalizedBlock},
+    types::{
+        DeployHash, DeployHeader, DeployOrTransferHash, FinalizedBlock, Supersession, Timestamp,
+    },
 };
 
 /// Information about a deploy.
@@ -19,6 +21,32 @@ pub struct DeployInfo {
     pub header: DeployHeader,
     pub payment_amount: Motes,
     pub size: usize,
+    pub supersession: Option<Supersession>,
+    pub nonce: Option<u64>,
+}
+
+/// A deploy or transfer held by the block proposer, awaiting inclusion in a block.
+#[derive(Clone, DataSize, Debug)]
+pub(crate) struct PendingDeploy {
+    /// The hash of the deploy.
+    pub(crate) hash: DeployHash,
+    /// The header of the deploy.
+    pub(crate) header: DeployHeader,
+    /// The time at which the block proposer received the deploy.
+    pub(crate) received_time: Timestamp,
+    /// The dependencies of the deploy which have not yet been finalized.
+    pub(crate) unresolved_dependencies: Vec<DeployHash>,
+}
+
+/// A snapshot of the deploys held by the block proposer.
+#[derive(Clone, DataSize, Debug, Default)]
+pub(crate) struct PendingDeploys {
+    /// The pending transfers, in the order in which they are considered for inclusion in a block.
+    pub(crate) pending_transfers: Vec<PendingDeploy>,
+    /// The pending deploys, in the order in which they are considered for inclusion in a block.
+    pub(crate) pending_deploys: Vec<PendingDeploy>,
+    /// The number of deploys and transfers known to have been included in a finalized block.
+    pub(crate) finalized_deploy_count: usize,
 }
 
 /// An event for when using the block proposer as a component.
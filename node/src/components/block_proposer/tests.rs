@@ -53,6 +53,8 @@ fn generate_transfer(
         session,
         &secret_key,
         None,
+        None,
+        None,
     )
 }
 
@@ -65,13 +67,30 @@ fn generate_deploy(
     gas_price: u64,
 ) -> Deploy {
     let secret_key = SecretKey::random(rng);
+    generate_deploy_signed_by(
+        &secret_key,
+        timestamp,
+        ttl,
+        dependencies,
+        payment_amount,
+        gas_price,
+        None,
+    )
+}
+
+fn generate_deploy_signed_by(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    dependencies: Vec<DeployHash>,
+    payment_amount: Gas,
+    gas_price: u64,
+    supersession: Option<Supersession>,
+) -> Deploy {
     let chain_name = "chain".to_string();
-    let args = runtime_args! {
-        ARG_AMOUNT => payment_amount.value()
-    };
     let payment = ExecutableDeployItem::ModuleBytes {
         module_bytes: Bytes::new(),
-        args,
+        args: runtime_args! { ARG_AMOUNT => payment_amount.value() },
     };
     let session = ExecutableDeployItem::ModuleBytes {
         module_bytes: Bytes::new(),
@@ -86,14 +105,68 @@ fn generate_deploy(
         chain_name,
         payment,
         session,
-        &secret_key,
+        secret_key,
+        None,
+        None,
+        supersession,
+    )
+}
+
+fn generate_superseding_deploy(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    gas_price: u64,
+    supersession: Supersession,
+) -> Deploy {
+    generate_deploy_signed_by(
+        secret_key,
+        timestamp,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        gas_price,
+        Some(supersession),
+    )
+}
+
+fn generate_deploy_with_nonce(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    nonce: u64,
+) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { ARG_AMOUNT => default_gas_payment().value() },
+    };
+    let session = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: RuntimeArgs::new(),
+    };
+
+    Deploy::new(
+        timestamp,
+        ttl,
+        DEFAULT_TEST_GAS_PRICE,
+        vec![],
+        "chain".to_string(),
+        payment,
+        session,
+        secret_key,
+        None,
+        Some(nonce),
         None,
     )
 }
 
 fn create_test_proposer(deploy_delay: TimeDiff) -> BlockProposerReady {
     BlockProposerReady {
-        local_config: Config { deploy_delay },
+        local_config: Config {
+            deploy_delay,
+            ..Default::default()
+        },
+        protocol_version: DEPLOY_NONCES_PROTOCOL_VERSION,
         ..Default::default()
     }
 }
@@ -725,6 +798,136 @@ fn should_return_deploy_dependencies() {
     assert!(deploys2.contains(deploy2.id()));
 }
 
+#[test]
+fn should_propose_consecutive_nonces_in_one_block() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let deploy1 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 1);
+    let deploy2 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 2);
+    let deploy3 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 3);
+    // a deploy from another account is not held back by the first account's nonces
+    let other_deploy =
+        generate_deploy_with_nonce(&SecretKey::random(&mut rng), creation_time, ttl, 7);
+
+    let mut proposer = create_test_proposer(0.into());
+    for deploy in &[&deploy3, &deploy1, &other_deploy, &deploy2] {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    let deploys = block.deploy_hashes();
+    assert_eq!(deploys.len(), 4);
+    assert!(deploys.contains(other_deploy.id()));
+    // the account's deploys are proposed in the order of their nonces
+    let account_deploys: Vec<_> = deploys
+        .iter()
+        .filter(|hash| *hash != other_deploy.id())
+        .collect();
+    assert_eq!(
+        account_deploys,
+        vec![deploy1.id(), deploy2.id(), deploy3.id()]
+    );
+}
+
+#[test]
+fn should_not_propose_nonce_gaps() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let deploy1 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 1);
+    let deploy2 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 2);
+    let deploy3 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 3);
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy(
+        creation_time,
+        deploy1.deploy_or_transfer_hash(),
+        deploy1.deploy_info().unwrap(),
+    );
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes(), &vec![*deploy1.id()]);
+    proposer.finalized_deploys(block.deploys_and_transfers_iter());
+
+    // nonce 2 is missing, so the deploy with nonce 3 must not be proposed
+    proposer.add_deploy(
+        creation_time,
+        deploy3.deploy_or_transfer_hash(),
+        deploy3.deploy_info().unwrap(),
+    );
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert!(block.deploy_hashes().is_empty());
+
+    // once the gap is filled, both are proposed
+    proposer.add_deploy(
+        creation_time,
+        deploy2.deploy_or_transfer_hash(),
+        deploy2.deploy_info().unwrap(),
+    );
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes(), &vec![*deploy2.id(), *deploy3.id()]);
+}
+
+#[test]
+fn should_ignore_nonces_before_activation() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let deploy1 = generate_deploy_with_nonce(&secret_key, creation_time, ttl, 3);
+    let deploy2 = generate_deploy_with_nonce(&secret_key, Timestamp::from(101), ttl, 3);
+
+    let mut proposer = create_test_proposer(0.into());
+    proposer.protocol_version = ProtocolVersion::V1_0_0;
+    for deploy in &[&deploy1, &deploy2] {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes().len(), 2);
+}
+
 #[test]
 fn should_respect_deploy_delay() {
     let mut rng = crate::new_rng();
@@ -762,3 +965,469 @@ fn should_respect_deploy_delay() {
     );
     assert_eq!(&vec![*deploy.id()], block.deploy_hashes());
 }
+
+#[test]
+fn should_propose_deploys_by_gas_price_then_age() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+    let mut proposer = create_test_proposer(0.into());
+    let deploy_config = DeployConfig {
+        block_max_deploy_count: 2,
+        ..Default::default()
+    };
+
+    let mut add_deploy = |gas_price: u64, received_time: u64| {
+        let deploy = generate_deploy(
+            &mut rng,
+            creation_time,
+            ttl,
+            vec![],
+            default_gas_payment(),
+            gas_price,
+        );
+        proposer.add_deploy(
+            received_time.into(),
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+        *deploy.id()
+    };
+    let _cheap_old = add_deploy(1, 100);
+    let _medium_new = add_deploy(2, 110);
+    let medium_old = add_deploy(2, 105);
+    let expensive_new = add_deploy(3, 110);
+
+    let block = proposer.propose_block_payload(
+        deploy_config,
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(&vec![expensive_new, medium_old], block.deploy_hashes());
+}
+
+#[test]
+fn should_respect_limit_of_deploys_per_account() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+    let mut proposer = BlockProposerReady {
+        local_config: Config {
+            deploy_delay: 0.into(),
+            max_deploys_per_account: 2,
+        },
+        ..Default::default()
+    };
+
+    // A spammer offering a high gas price should only get two deploys into the block.  The
+    // deploys' timestamps differ so that their hashes do.
+    let spammer = SecretKey::random(&mut rng);
+    for i in 0..5 {
+        let deploy = generate_deploy_signed_by(
+            &spammer,
+            creation_time + TimeDiff::from(i),
+            ttl,
+            vec![],
+            default_gas_payment(),
+            10,
+            None,
+        );
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+    let other = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    proposer.add_deploy(
+        creation_time,
+        other.deploy_or_transfer_hash(),
+        other.deploy_info().unwrap(),
+    );
+
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes().len(), 3);
+    assert!(block.deploy_hashes().contains(other.id()));
+}
+
+#[test]
+fn should_report_pending_deploys_with_unresolved_dependencies() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let mut proposer = create_test_proposer(0.into());
+
+    let deploy1 = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    // let deploy2 depend on deploy1
+    let deploy2 = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![*deploy1.id()],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    let transfer = generate_transfer(&mut rng, creation_time, ttl, vec![], default_gas_payment());
+    for deploy in &[&deploy1, &deploy2, &transfer] {
+        proposer.add_deploy(
+            creation_time,
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+
+    let pending = proposer.pending_deploys();
+    assert_eq!(pending.pending_transfers.len(), 1);
+    assert_eq!(pending.pending_transfers[0].hash, *transfer.id());
+    assert_eq!(pending.pending_deploys.len(), 2);
+    assert_eq!(pending.finalized_deploy_count, 0);
+    let pending_deploy2 = pending
+        .pending_deploys
+        .iter()
+        .find(|pending_deploy| pending_deploy.hash == *deploy2.id())
+        .unwrap();
+    assert_eq!(pending_deploy2.received_time, creation_time);
+    assert_eq!(pending_deploy2.unresolved_dependencies, vec![*deploy1.id()]);
+
+    // Once deploy1 is finalized, deploy2 no longer has unresolved dependencies.
+    proposer.finalized_deploys(vec![deploy1.deploy_or_transfer_hash()]);
+    let pending = proposer.pending_deploys();
+    assert_eq!(pending.pending_deploys.len(), 1);
+    assert_eq!(pending.finalized_deploy_count, 1);
+    assert!(pending.pending_deploys[0]
+        .unresolved_dependencies
+        .is_empty());
+}
+
+#[test]
+fn should_replace_pending_deploy_only_with_higher_gas_price() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let mut proposer = create_test_proposer(0.into());
+
+    let secret_key = SecretKey::random(&mut rng);
+    let original = generate_deploy_signed_by(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+        None,
+    );
+    assert!(proposer
+        .add_deploy(
+            creation_time,
+            original.deploy_or_transfer_hash(),
+            original.deploy_info().unwrap(),
+        )
+        .is_none());
+
+    // A replacement not offering a higher gas price is dropped.
+    let cheap_replacement = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        2,
+        Supersession::Replace(*original.id()),
+    );
+    assert!(proposer
+        .add_deploy(
+            creation_time,
+            cheap_replacement.deploy_or_transfer_hash(),
+            cheap_replacement.deploy_info().unwrap(),
+        )
+        .is_none());
+    assert!(proposer.sets.pending_deploys.contains_key(original.id()));
+    assert!(!proposer
+        .sets
+        .pending_deploys
+        .contains_key(cheap_replacement.id()));
+
+    let replacement = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        3,
+        Supersession::Replace(*original.id()),
+    );
+    assert_eq!(
+        proposer.add_deploy(
+            creation_time,
+            replacement.deploy_or_transfer_hash(),
+            replacement.deploy_info().unwrap(),
+        ),
+        Some((*original.id(), *replacement.id()))
+    );
+    assert!(!proposer.sets.pending_deploys.contains_key(original.id()));
+    assert!(proposer.sets.pending_deploys.contains_key(replacement.id()));
+}
+
+#[test]
+fn should_cancel_pending_deploy() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let mut proposer = create_test_proposer(0.into());
+
+    let secret_key = SecretKey::random(&mut rng);
+    let original = generate_deploy_signed_by(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+        None,
+    );
+    proposer.add_deploy(
+        creation_time,
+        original.deploy_or_transfer_hash(),
+        original.deploy_info().unwrap(),
+    );
+
+    let cancellation = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        DEFAULT_TEST_GAS_PRICE,
+        Supersession::Cancel(*original.id()),
+    );
+    assert_eq!(
+        proposer.add_deploy(
+            creation_time,
+            cancellation.deploy_or_transfer_hash(),
+            cancellation.deploy_info().unwrap(),
+        ),
+        Some((*original.id(), *cancellation.id()))
+    );
+    // The cancellation itself is proposed, so that the account is charged for it.
+    assert!(!proposer.sets.pending_deploys.contains_key(original.id()));
+    assert!(proposer
+        .sets
+        .pending_deploys
+        .contains_key(cancellation.id()));
+
+    // The cancelled deploy stays dropped if we receive it again.
+    assert_eq!(
+        proposer.add_deploy(
+            creation_time,
+            original.deploy_or_transfer_hash(),
+            original.deploy_info().unwrap(),
+        ),
+        Some((*original.id(), *cancellation.id()))
+    );
+    assert!(!proposer.sets.pending_deploys.contains_key(original.id()));
+}
+
+#[test]
+fn should_not_supersede_deploy_from_another_account() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let mut proposer = create_test_proposer(0.into());
+
+    let original = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    proposer.add_deploy(
+        creation_time,
+        original.deploy_or_transfer_hash(),
+        original.deploy_info().unwrap(),
+    );
+
+    let other_account = SecretKey::random(&mut rng);
+    let cancellation = generate_superseding_deploy(
+        &other_account,
+        creation_time,
+        ttl,
+        DEFAULT_TEST_GAS_PRICE,
+        Supersession::Cancel(*original.id()),
+    );
+    assert!(proposer
+        .add_deploy(
+            creation_time,
+            cancellation.deploy_or_transfer_hash(),
+            cancellation.deploy_info().unwrap(),
+        )
+        .is_none());
+    assert!(proposer.sets.pending_deploys.contains_key(original.id()));
+    assert!(proposer
+        .sets
+        .pending_deploys
+        .contains_key(cancellation.id()));
+}
+
+#[test]
+fn should_supersede_deploy_received_after_its_replacement() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let mut proposer = create_test_proposer(0.into());
+
+    let secret_key = SecretKey::random(&mut rng);
+    let original = generate_deploy_signed_by(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+        None,
+    );
+    let replacement = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        3,
+        Supersession::Replace(*original.id()),
+    );
+    assert!(proposer
+        .add_deploy(
+            creation_time,
+            replacement.deploy_or_transfer_hash(),
+            replacement.deploy_info().unwrap(),
+        )
+        .is_none());
+    assert_eq!(
+        proposer.add_deploy(
+            creation_time,
+            original.deploy_or_transfer_hash(),
+            original.deploy_info().unwrap(),
+        ),
+        Some((*original.id(), *replacement.id()))
+    );
+    assert!(!proposer.sets.pending_deploys.contains_key(original.id()));
+    assert!(proposer.sets.pending_deploys.contains_key(replacement.id()));
+
+    // A replacement not offering a higher gas price is dropped once the original arrives.
+    let original = generate_deploy_signed_by(
+        &secret_key,
+        Timestamp::from(101),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+        None,
+    );
+    let cheap_replacement = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        2,
+        Supersession::Replace(*original.id()),
+    );
+    assert!(proposer
+        .add_deploy(
+            creation_time,
+            cheap_replacement.deploy_or_transfer_hash(),
+            cheap_replacement.deploy_info().unwrap(),
+        )
+        .is_none());
+    assert!(proposer
+        .add_deploy(
+            creation_time,
+            original.deploy_or_transfer_hash(),
+            original.deploy_info().unwrap(),
+        )
+        .is_none());
+    assert!(proposer.sets.pending_deploys.contains_key(original.id()));
+    assert!(!proposer
+        .sets
+        .pending_deploys
+        .contains_key(cheap_replacement.id()));
+}
+
+#[test]
+fn should_propose_cancellation_but_not_replacement_of_included_deploy() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+    let mut proposer = create_test_proposer(0.into());
+
+    let secret_key = SecretKey::random(&mut rng);
+    let original = generate_deploy_signed_by(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+        None,
+    );
+    proposer.add_deploy(
+        creation_time,
+        original.deploy_or_transfer_hash(),
+        original.deploy_info().unwrap(),
+    );
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes(), &[*original.id()]);
+
+    // The original is already included in the proposed block, so its replacement must not be.
+    let replacement = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        3,
+        Supersession::Replace(*original.id()),
+    );
+    proposer.add_deploy(
+        creation_time,
+        replacement.deploy_or_transfer_hash(),
+        replacement.deploy_info().unwrap(),
+    );
+    let cancellation = generate_superseding_deploy(
+        &secret_key,
+        creation_time,
+        ttl,
+        DEFAULT_TEST_GAS_PRICE,
+        Supersession::Cancel(DeployHash::random(&mut rng)),
+    );
+    proposer.add_deploy(
+        creation_time,
+        cancellation.deploy_or_transfer_hash(),
+        cancellation.deploy_info().unwrap(),
+    );
+
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![block]),
+        vec![],
+        true,
+    );
+    assert_eq!(block.deploy_hashes(), &[*cancellation.id()]);
+}
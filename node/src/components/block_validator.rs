@@ -25,6 +25,8 @@ use itertools::Itertools;
 use smallvec::{smallvec, SmallVec};
 use tracing::info;
 
+use casper_execution_engine::core::engine_state::DEPLOY_NONCES_PROTOCOL_VERSION;
+
 use crate::{
     components::{
         block_proposer::DeployInfo,
@@ -33,11 +35,11 @@ use crate::{
     },
     effect::{
         requests::{BlockValidationRequest, FetcherRequest, StorageRequest},
-        EffectBuilder, EffectExt, EffectOptionExt, Effects, Responder,
+        EffectBuilder, EffectExt, Effects, Responder,
     },
     types::{
         appendable_block::AppendableBlock, Block, Chainspec, Deploy, DeployHash,
-        DeployOrTransferHash, Timestamp,
+        DeployOrTransferHash, Timestamp, DEPLOY_SUPERSESSION_PROTOCOL_VERSION,
     },
     NodeRng,
 };
@@ -73,6 +75,34 @@ impl ValidatingBlock {
         }
     }
 
+    /// Returns the block's height, if known.
+    fn height(&self) -> Option<u64> {
+        match self {
+            ValidatingBlock::Block(block) => Some(block.height()),
+            ValidatingBlock::ProposedBlock(_) => None,
+        }
+    }
+
+    /// Returns the hashes of the deploys and transfers in the proposed block's ancestors which
+    /// haven't been finalized yet.
+    fn ancestor_deploy_hashes(&self) -> HashSet<DeployHash> {
+        match self {
+            ValidatingBlock::Block(_) => HashSet::new(),
+            ValidatingBlock::ProposedBlock(pb) => pb
+                .context()
+                .ancestor_values()
+                .iter()
+                .flat_map(|payload| {
+                    payload
+                        .deploy_hashes()
+                        .iter()
+                        .chain(payload.transfer_hashes())
+                        .copied()
+                })
+                .collect(),
+        }
+    }
+
     fn deploy_hashes(&self) -> &[DeployHash] {
         match self {
             ValidatingBlock::Block(block) => block.deploy_hashes(),
@@ -112,6 +142,9 @@ pub(crate) enum Event<I> {
     DeployFound {
         dt_hash: DeployOrTransferHash,
         deploy_info: Box<DeployInfo>,
+        /// The stored deploys which supersede this one or which it replaces, together with the
+        /// heights of the blocks including them, if any.
+        supersession_conflicts: Vec<(DeployHash, Option<u64>)>,
     },
 
     /// A request to find a specific deploy, potentially from a peer, failed.
@@ -136,6 +169,10 @@ pub(crate) struct BlockValidationState<I> {
     responders: SmallVec<[Responder<bool>; 2]>,
     /// Peers that should have the data.
     sources: VecDeque<I>,
+    /// The deploys in the block's ancestors which are not yet finalized.
+    ancestor_deploys: HashSet<DeployHash>,
+    /// The block's height, if known.
+    height: Option<u64>,
 }
 
 impl<I> BlockValidationState<I>
@@ -158,6 +195,20 @@ where
         self.sources.pop_front()
     }
 
+    /// Returns `true` if one of the given deploys superseding or replaced by a deploy in this block
+    /// is included in an ancestor, or in a block below this one.  If the block's height is not
+    /// known, any block including them counts.
+    fn includes_supersession_conflict(&self, conflicts: &[(DeployHash, Option<u64>)]) -> bool {
+        conflicts.iter().any(|(deploy_hash, maybe_block_height)| {
+            self.ancestor_deploys.contains(deploy_hash)
+                || match (maybe_block_height, self.height) {
+                    (Some(block_height), Some(height)) => *block_height < height,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+        })
+    }
+
     fn respond<REv>(&mut self, value: bool) -> Effects<REv> {
         self.responders
             .drain(..)
@@ -190,6 +241,11 @@ where
         }
     }
 
+    /// Returns `true` if the protocol version activating deploy supersession is in effect.
+    fn supersession_active(&self) -> bool {
+        self.chainspec.protocol_version() >= DEPLOY_SUPERSESSION_PROTOCOL_VERSION
+    }
+
     /// Prints a log message about an invalid block with duplicated deploys.
     fn log_block_with_replay(&self, sender: I, block: &ValidatingBlock) {
         let mut deploy_counts = BTreeMap::new();
@@ -246,6 +302,7 @@ where
                     return responder.respond(false).ignore();
                 }
 
+                let check_supersession = self.supersession_active();
                 match self.validation_states.entry(block) {
                     Entry::Occupied(mut entry) => {
                         // The entry already exists.
@@ -269,28 +326,48 @@ where
                                 // For every request, increase the number of in-flight...
                                 in_flight.inc(&dt_hash.into());
                                 // ...then request it.
-                                fetch_deploy(effect_builder, dt_hash, sender.clone())
+                                fetch_deploy(
+                                    effect_builder,
+                                    dt_hash,
+                                    sender.clone(),
+                                    check_supersession,
+                                )
                             },
                         ));
                         let block_timestamp = entry.key().timestamp();
                         let deploy_config = self.chainspec.deploy_config;
+                        let ancestor_deploys = entry.key().ancestor_deploy_hashes();
+                        let height = entry.key().height();
                         entry.insert(BlockValidationState {
                             appendable_block: AppendableBlock::new(deploy_config, block_timestamp),
                             missing_deploys: block_deploys,
                             responders: smallvec![responder],
                             sources: VecDeque::new(), /* This is empty b/c we create the first
                                                        * request using `sender`. */
+                            ancestor_deploys,
+                            height,
                         });
                     }
                 }
             }
             Event::DeployFound {
                 dt_hash,
-                deploy_info,
+                mut deploy_info,
+                supersession_conflicts,
             } => {
                 // We successfully found a hash. Decrease the number of outstanding requests.
                 self.in_flight.dec(&dt_hash.into());
 
+                // Nonces are only taken into account once the protocol version activating them is
+                // in effect.
+                if self.chainspec.protocol_version() < DEPLOY_NONCES_PROTOCOL_VERSION {
+                    deploy_info.nonce = None;
+                }
+                // Likewise for supersessions.
+                if !self.supersession_active() {
+                    deploy_info.supersession = None;
+                }
+
                 // If a deploy is received for a given block that makes that block invalid somehow,
                 // mark it for removal.
                 let mut invalid = Vec::new();
@@ -311,6 +388,12 @@ where
                         if let Err(err) = add_result {
                             info!(block = ?key, %dt_hash, ?deploy_info, ?err, "block invalid");
                             invalid.push(key.clone());
+                        } else if state.includes_supersession_conflict(&supersession_conflicts) {
+                            info!(
+                                block = ?key, %dt_hash, ?supersession_conflicts,
+                                "block invalid: deploy conflicts with an included supersession"
+                            );
+                            invalid.push(key.clone());
                         }
                     }
                 }
@@ -339,6 +422,7 @@ where
 
                 // Flag indicating whether we've retried fetching the deploy.
                 let mut retried = false;
+                let check_supersession = self.supersession_active();
 
                 self.validation_states.retain(|key, state| {
                     if !state.missing_deploys.contains(&dt_hash) {
@@ -352,7 +436,12 @@ where
                         Some(peer) => {
                             info!(%dt_hash, ?peer, "trying the next peer");
                             // There's still hope to download the deploy.
-                            effects.extend(fetch_deploy(effect_builder, dt_hash, peer));
+                            effects.extend(fetch_deploy(
+                                effect_builder,
+                                dt_hash,
+                                peer,
+                                check_supersession,
+                            ));
                             retried = true;
                             true
                         }
@@ -402,10 +491,14 @@ where
 }
 
 /// Returns effects that fetch the deploy and validate it.
+///
+/// If `check_supersession` is set, the deploys superseding the fetched deploy, or replaced by it,
+/// are looked up in storage as well.
 fn fetch_deploy<REv, I>(
     effect_builder: EffectBuilder<REv>,
     dt_hash: DeployOrTransferHash,
     sender: I,
+    check_supersession: bool,
 ) -> Effects<Event<I>>
 where
     REv: From<Event<I>>
@@ -415,21 +508,32 @@ where
         + Send,
     I: Clone + Send + PartialEq + Eq + 'static,
 {
-    let validate_deploy = move |result: FetchResult<Deploy, I>| match result {
-        FetchResult::FromStorage(deploy) | FetchResult::FromPeer(deploy, _) => {
-            (deploy.deploy_or_transfer_hash() == dt_hash)
-                .then(|| deploy)
-                .and_then(|deploy| deploy.deploy_info().ok())
-                .map_or(Event::CannotConvertDeploy(dt_hash), |deploy_info| {
-                    Event::DeployFound {
-                        dt_hash,
-                        deploy_info: Box::new(deploy_info),
-                    }
-                })
+    async move {
+        let deploy = match effect_builder.fetch_deploy(dt_hash.into(), sender).await {
+            Some(FetchResult::FromStorage(deploy)) | Some(FetchResult::FromPeer(deploy, _)) => {
+                deploy
+            }
+            None => return Event::DeployMissing(dt_hash),
+        };
+        let deploy_info = match (deploy.deploy_or_transfer_hash() == dt_hash)
+            .then(|| deploy.deploy_info().ok())
+            .flatten()
+        {
+            Some(deploy_info) => deploy_info,
+            None => return Event::CannotConvertDeploy(dt_hash),
+        };
+        let supersession_conflicts = if check_supersession {
+            effect_builder
+                .get_supersession_conflicts_from_storage(*deploy.id(), deploy.header().clone())
+                .await
+        } else {
+            vec![]
+        };
+        Event::DeployFound {
+            dt_hash,
+            deploy_info: Box::new(deploy_info),
+            supersession_conflicts,
         }
-    };
-
-    effect_builder
-        .fetch_deploy(dt_hash.into(), sender)
-        .map_or_else(validate_deploy, move || Event::DeployMissing(dt_hash))
+    }
+    .event(|event| event)
 }
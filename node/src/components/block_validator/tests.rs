@@ -1,19 +1,21 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::Bytes, runtime_args, system::standard_payment::ARG_AMOUNT, RuntimeArgs, SecretKey,
-    U512,
+    bytesrepr::Bytes, runtime_args, system::standard_payment::ARG_AMOUNT, EraId, PublicKey,
+    RuntimeArgs, SecretKey, U512,
 };
 use derive_more::From;
 use itertools::Itertools;
+use rand::Rng;
 
 use crate::{
     components::{consensus::BlockContext, fetcher::FetchResult},
     crypto::AsymmetricKeyExt,
     reactor::{EventQueueHandle, QueueKind, Scheduler},
     testing::TestRng,
-    types::{BlockPayload, TimeDiff},
+    types::{BlockHash, BlockPayload, FinalizedBlock, Supersession, TimeDiff},
     utils::{self, Loadable},
 };
 
@@ -57,27 +59,39 @@ impl MockReactor {
         }
     }
 
-    async fn expect_fetch_deploy<T>(&self, deploy: T)
-    where
-        T: Into<Option<Deploy>>,
-    {
-        let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
-        if let ReactorEvent::Fetcher(FetcherRequest::Fetch {
-            id,
-            peer,
-            responder,
-        }) = reactor_event
-        {
-            match deploy.into() {
-                None => responder.respond(None).await,
-                Some(deploy) => {
-                    assert_eq!(id, *deploy.id());
-                    let response = FetchResult::FromPeer(Box::new(deploy), peer);
-                    responder.respond(Some(response)).await;
+    /// Answers deploy fetch requests with the given deploys, and storage requests for
+    /// supersession conflicts with the given conflicts, until dropped.
+    async fn answer_requests(
+        &self,
+        deploys: HashMap<DeployHash, Deploy>,
+        supersession_conflicts: HashMap<DeployHash, Vec<(DeployHash, Option<u64>)>>,
+    ) {
+        loop {
+            let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
+            match reactor_event {
+                ReactorEvent::Fetcher(FetcherRequest::Fetch {
+                    id,
+                    peer,
+                    responder,
+                }) => {
+                    let response = deploys
+                        .get(&id)
+                        .map(|deploy| FetchResult::FromPeer(Box::new(deploy.clone()), peer));
+                    responder.respond(response).await;
+                }
+                ReactorEvent::Storage(StorageRequest::GetSupersessionConflicts {
+                    deploy_hash,
+                    responder,
+                    ..
+                }) => {
+                    let conflicts = supersession_conflicts
+                        .get(&deploy_hash)
+                        .cloned()
+                        .unwrap_or_default();
+                    responder.respond(conflicts).await;
                 }
+                _ => panic!("unexpected event: {:?}", reactor_event),
             }
-        } else {
-            panic!("unexpected event: {:?}", reactor_event);
         }
     }
 }
@@ -86,14 +100,42 @@ fn new_proposed_block(
     timestamp: Timestamp,
     deploy_hashes: Vec<DeployHash>,
     transfer_hashes: Vec<DeployHash>,
+    ancestor_deploy_hashes: Vec<DeployHash>,
 ) -> ProposedBlock<ClContext> {
-    // Accusations and ancestors are empty, and the random bit is always true:
-    // These values are not checked by the block validator.
-    let block_context = BlockContext::new(timestamp, vec![]);
+    // Accusations are empty, and the random bit is always true: These values are not checked by
+    // the block validator.
+    let ancestor_payload = BlockPayload::new(ancestor_deploy_hashes, vec![], vec![], true);
+    let block_context = BlockContext::new(timestamp, vec![Arc::new(ancestor_payload)]);
     let block_payload = BlockPayload::new(deploy_hashes, transfer_hashes, vec![], true);
     ProposedBlock::new(Arc::new(block_payload), block_context)
 }
 
+fn new_block(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    height: u64,
+    deploy_hashes: Vec<DeployHash>,
+) -> Block {
+    let block_payload = BlockPayload::new(deploy_hashes, vec![], vec![], true);
+    let finalized_block = FinalizedBlock::new(
+        block_payload,
+        None,
+        timestamp,
+        EraId::new(1),
+        height,
+        PublicKey::from(&SecretKey::random(rng)),
+    );
+    Block::new(
+        BlockHash::random(rng),
+        rng.gen::<[u8; Digest::LENGTH]>().into(),
+        rng.gen::<[u8; Digest::LENGTH]>().into(),
+        finalized_block,
+        None,
+        DEPLOY_NONCES_PROTOCOL_VERSION,
+    )
+    .expect("could not create block")
+}
+
 fn new_deploy(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff) -> Deploy {
     let secret_key = SecretKey::random(rng);
     let chain_name = "chain".to_string();
@@ -118,6 +160,74 @@ fn new_deploy(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff) -> Deploy
         session,
         &secret_key,
         None,
+        None,
+        None,
+    )
+}
+
+fn new_deploy_with_nonce(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    nonce: u64,
+) -> Deploy {
+    let chain_name = "chain".to_string();
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { ARG_AMOUNT => U512::from(1) },
+    };
+    let session = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: RuntimeArgs::new(),
+    };
+    let dependencies = vec![];
+    let gas_price = 1;
+
+    Deploy::new(
+        timestamp,
+        ttl,
+        gas_price,
+        dependencies,
+        chain_name,
+        payment,
+        session,
+        secret_key,
+        None,
+        Some(nonce),
+        None,
+    )
+}
+
+fn new_deploy_with_supersession(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    gas_price: u64,
+    supersession: Option<Supersession>,
+) -> Deploy {
+    let chain_name = "chain".to_string();
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { ARG_AMOUNT => U512::from(1) },
+    };
+    let session = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: RuntimeArgs::new(),
+    };
+    let dependencies = vec![];
+
+    Deploy::new(
+        timestamp,
+        ttl,
+        gas_price,
+        dependencies,
+        chain_name,
+        payment,
+        session,
+        secret_key,
+        None,
+        None,
+        supersession,
     )
 }
 
@@ -144,6 +254,8 @@ fn new_transfer(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff) -> Deplo
         session,
         &secret_key,
         None,
+        None,
+        None,
     )
 }
 
@@ -157,18 +269,30 @@ async fn validate_block(
     // Assemble the block to be validated.
     let deploy_hashes = deploys.iter().map(|deploy| *deploy.id()).collect_vec();
     let transfer_hashes = transfers.iter().map(|deploy| *deploy.id()).collect_vec();
-    let proposed_block = new_proposed_block(timestamp, deploy_hashes, transfer_hashes);
+    let proposed_block = new_proposed_block(timestamp, deploy_hashes, transfer_hashes, vec![]);
+    let deploys = deploys.into_iter().chain(transfers).collect();
+    validate(rng, proposed_block.into(), deploys, HashMap::new()).await
+}
 
+/// Validates the given block using a `BlockValidator` component, and returns the result.
+///
+/// The mock reactor answers with the given deploys and supersession conflicts.
+async fn validate(
+    rng: &mut TestRng,
+    block: ValidatingBlock,
+    deploys: Vec<Deploy>,
+    supersession_conflicts: HashMap<DeployHash, Vec<(DeployHash, Option<u64>)>>,
+) -> bool {
     // Create the reactor and component.
     let reactor = MockReactor::new();
     let effect_builder = EffectBuilder::new(EventQueueHandle::new(reactor.scheduler));
-    let chainspec = Arc::new(Chainspec::from_resources("local"));
-    let mut block_validator = BlockValidator::<NodeId>::new(chainspec);
+    let mut chainspec = Chainspec::from_resources("local");
+    chainspec.protocol_config.version = DEPLOY_NONCES_PROTOCOL_VERSION;
+    let mut block_validator = BlockValidator::<NodeId>::new(Arc::new(chainspec));
 
     // Pass the block to the component. This future will eventually resolve to the result, i.e.
     // whether the block is valid or not.
-    let validation_result =
-        tokio::spawn(effect_builder.validate_block("Bob", proposed_block.clone()));
+    let validation_result = tokio::spawn(effect_builder.validate_block("Bob", block));
     let event = reactor.expect_block_validator_event().await;
     let effects = block_validator.handle_event(effect_builder, rng, event);
 
@@ -181,13 +305,21 @@ async fn validate_block(
         return validation_result.await.unwrap();
     }
 
-    // Otherwise the effects must be requests to fetch the block's deploys.
+    // Otherwise the effects must be requests to fetch the block's deploys.  We make our mock
+    // reactor answer with the expected deploys and transfers, and their supersession conflicts.
     let fetch_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
-
-    // We make our mock reactor answer with the expected deploys and transfers:
-    for deploy in deploys.into_iter().chain(transfers) {
-        reactor.expect_fetch_deploy(deploy).await;
-    }
+    let deploys = deploys
+        .into_iter()
+        .map(|deploy| (*deploy.id(), deploy))
+        .collect();
+    let answering_reactor = MockReactor {
+        scheduler: reactor.scheduler,
+    };
+    let answers = tokio::spawn(async move {
+        answering_reactor
+            .answer_requests(deploys, supersession_conflicts)
+            .await
+    });
 
     // The resulting `FetchResult`s are passed back into the component. When any deploy turns out
     // to be invalid, or once all of them have been validated, the component will respond.
@@ -199,6 +331,7 @@ async fn validate_block(
             block_validator.handle_event(effect_builder, rng, found_deploy)
         }));
     }
+    answers.abort();
 
     // We expect exactly one effect: the validation response. This will resolve the result.
     assert_eq!(1, effects.len());
@@ -282,3 +415,121 @@ async fn transfer_deploy_mixup_and_replay() {
     let transfers = vec![transfer1.clone(), transfer2.clone(), transfer2.clone()];
     assert!(!validate_block(&mut rng, timestamp, deploys, transfers).await);
 }
+
+/// Verifies that a block is invalid if an account uses the same nonce in more than one deploy.
+#[tokio::test]
+async fn duplicate_nonce() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let secret_key = SecretKey::random(&mut rng);
+    let deploy1 = new_deploy_with_nonce(&secret_key, timestamp, ttl, 1);
+    let deploy2 = new_deploy_with_nonce(&secret_key, timestamp, ttl, 2);
+    let deploy3 = new_deploy_with_nonce(&secret_key, 999.into(), ttl, 2);
+    let other_deploy = new_deploy_with_nonce(&SecretKey::random(&mut rng), timestamp, ttl, 2);
+
+    // Distinct nonces from one account, and equal nonces from different accounts, are fine.
+    let deploys = vec![deploy1.clone(), deploy2.clone(), other_deploy];
+    assert!(validate_block(&mut rng, timestamp, deploys, vec![]).await);
+
+    // But the same account can't use a nonce twice.
+    let deploys = vec![deploy1, deploy2, deploy3];
+    assert!(!validate_block(&mut rng, timestamp, deploys, vec![]).await);
+}
+
+/// Verifies that a block is invalid if it contains both a deploy and one superseding it.
+#[tokio::test]
+async fn supersession_in_block() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let secret_key = SecretKey::random(&mut rng);
+    let deploy = new_deploy_with_supersession(&secret_key, timestamp, ttl, 1, None);
+    let supersession = Supersession::Cancel(*deploy.id());
+    let canceller =
+        new_deploy_with_supersession(&secret_key, timestamp, ttl, 1, Some(supersession));
+    let other_key = SecretKey::random(&mut rng);
+    let other_canceller =
+        new_deploy_with_supersession(&other_key, timestamp, ttl, 1, Some(supersession));
+
+    // Another account's deploy can't supersede the deploy.
+    let deploys = vec![deploy.clone(), other_canceller];
+    assert!(validate_block(&mut rng, timestamp, deploys, vec![]).await);
+
+    // But the deploy can't be included together with a deploy from its account cancelling it.
+    let deploys = vec![deploy.clone(), canceller.clone()];
+    assert!(!validate_block(&mut rng, timestamp, deploys, vec![]).await);
+    let deploys = vec![canceller, deploy];
+    assert!(!validate_block(&mut rng, timestamp, deploys, vec![]).await);
+}
+
+/// Verifies that a block is invalid if it contains a deploy conflicting with a supersession in an
+/// ancestor or in an earlier block.
+#[tokio::test]
+async fn supersession_across_blocks() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let secret_key = SecretKey::random(&mut rng);
+    let deploy = new_deploy_with_supersession(&secret_key, timestamp, ttl, 1, None);
+    let supersession = Supersession::Replace(*deploy.id());
+    let replacement =
+        new_deploy_with_supersession(&secret_key, timestamp, ttl, 2, Some(supersession));
+    let deploy_hash = *deploy.id();
+    let replacement_hash = *replacement.id();
+
+    // Without any conflicts, a proposed block with the deploy is valid.
+    let proposed_block = new_proposed_block(timestamp, vec![deploy_hash], vec![], vec![]);
+    let valid = validate(
+        &mut rng,
+        proposed_block.into(),
+        vec![deploy.clone()],
+        HashMap::new(),
+    )
+    .await;
+    assert!(valid);
+
+    // If the replacement is in an ancestor, the deploy can't be proposed anymore.
+    let conflicts: HashMap<_, _> = vec![(deploy_hash, vec![(replacement_hash, None)])]
+        .into_iter()
+        .collect();
+    let proposed_block =
+        new_proposed_block(timestamp, vec![deploy_hash], vec![], vec![replacement_hash]);
+    let valid = validate(
+        &mut rng,
+        proposed_block.into(),
+        vec![deploy.clone()],
+        conflicts,
+    )
+    .await;
+    assert!(!valid);
+
+    // Nor if the replacement was included in a stored block.
+    let conflicts: HashMap<_, _> = vec![(deploy_hash, vec![(replacement_hash, Some(5))])]
+        .into_iter()
+        .collect();
+    let proposed_block = new_proposed_block(timestamp, vec![deploy_hash], vec![], vec![]);
+    let valid = validate(
+        &mut rng,
+        proposed_block.into(),
+        vec![deploy.clone()],
+        conflicts.clone(),
+    )
+    .await;
+    assert!(!valid);
+
+    // A block including the deploy is only invalid if it is above the one including the
+    // replacement.
+    let block = new_block(&mut rng, timestamp, 4, vec![deploy_hash]);
+    let valid = validate(
+        &mut rng,
+        block.into(),
+        vec![deploy.clone()],
+        conflicts.clone(),
+    )
+    .await;
+    assert!(valid);
+    let block = new_block(&mut rng, timestamp, 6, vec![deploy_hash]);
+    let valid = validate(&mut rng, block.into(), vec![deploy], conflicts).await;
+    assert!(!valid);
+}
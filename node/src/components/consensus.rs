@@ -5,6 +5,7 @@
 mod cl_context;
 mod config;
 mod consensus_protocol;
+mod era_evidence;
 mod era_supervisor;
 #[macro_use]
 mod highway_core;
@@ -29,7 +30,8 @@ use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
-use casper_types::{EraId, PublicKey, U512};
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey, Signature, U512};
 
 use crate::{
     components::Component,
@@ -52,8 +54,11 @@ use crate::{
 pub(crate) use cl_context::ClContext;
 pub(crate) use config::Config;
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
-pub(crate) use era_supervisor::EraSupervisor;
-pub(crate) use protocols::highway::HighwayProtocol;
+pub use era_evidence::{verify_evidence, EraEvidence, EvidenceVerificationError};
+pub(crate) use era_supervisor::{
+    export_slashing_protection, import_slashing_protection, EraSupervisor,
+};
+pub(crate) use protocols::highway::{HighwayProtocol, HighwaySnapshot};
 use traits::NodeIdT;
 pub(crate) use validator_change::ValidatorChange;
 
@@ -106,6 +111,13 @@ pub(crate) enum Event<I> {
     Action { era_id: EraId, action_id: ActionId },
     /// We are receiving the data we require to propose a new block.
     NewBlockPayload(NewBlockPayload),
+    /// A signature requested by the consensus protocol of the specified era has been created, or
+    /// `None` if signing failed.
+    SignatureCreated {
+        era_id: EraId,
+        hash: Digest,
+        maybe_signature: Option<Signature>,
+    },
     #[from]
     ConsensusRequest(ConsensusRequest),
     /// A new block has been added to the linear chain.
@@ -193,6 +205,21 @@ impl<I: Debug> Display for Event<I> {
                 "New proto-block for era {:?}: {:?}, {:?}",
                 era_id, block_payload, block_context
             ),
+            Event::SignatureCreated {
+                era_id,
+                hash,
+                maybe_signature,
+            } => write!(
+                f,
+                "signature of {} for {} {}",
+                hash,
+                era_id,
+                if maybe_signature.is_some() {
+                    "created"
+                } else {
+                    "failed"
+                },
+            ),
             Event::ConsensusRequest(request) => write!(
                 f,
                 "A request for consensus component hash been received: {:?}",
@@ -301,6 +328,11 @@ where
             Event::NewBlockPayload(new_block_payload) => {
                 handling_es.handle_new_block_payload(new_block_payload)
             }
+            Event::SignatureCreated {
+                era_id,
+                hash,
+                maybe_signature,
+            } => handling_es.handle_signature(era_id, hash, maybe_signature),
             Event::BlockAdded(block_header) => handling_es.handle_block_added(*block_header),
             Event::ResolveValidity(resolve_validity) => {
                 handling_es.resolve_validity(resolve_validity)
@@ -346,6 +378,17 @@ where
                 let validator_changes = self.get_validator_changes();
                 responder.respond(validator_changes).ignore()
             }
+            Event::ConsensusRequest(ConsensusRequest::Evidence {
+                era_id,
+                public_key,
+                responder,
+            }) => {
+                let evidence = self.get_evidence(era_id, public_key.as_ref());
+                responder.respond(evidence).ignore()
+            }
+            Event::ConsensusRequest(ConsensusRequest::Snapshot(responder)) => {
+                responder.respond(self.get_snapshot()).ignore()
+            }
         }
     }
 }
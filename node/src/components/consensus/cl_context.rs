@@ -8,29 +8,25 @@ use casper_types::{PublicKey, SecretKey, Signature};
 
 use crate::{
     components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
-    crypto,
+    crypto::{self, InProcessSigner, Signer},
     types::BlockPayload,
 };
 
 #[derive(DataSize)]
 pub struct Keypair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(crate) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
     }
 }
 
 impl From<Arc<SecretKey>> for Keypair {
     fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+        Self::new(Arc::new(InProcessSigner::new(secret_key)))
     }
 }
 
@@ -38,8 +34,8 @@ impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn try_sign(&self, hash: &Digest) -> Option<Signature> {
+        self.signer.try_sign_now(hash.as_ref())
     }
 }
 
@@ -1,4 +1,7 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use serde::Deserialize;
@@ -7,8 +10,18 @@ use casper_hashing::Digest;
 use casper_types::{ProtocolVersion, PublicKey, SecretKey};
 
 use crate::{
-    components::consensus::{protocols::highway::config::Config as HighwayConfig, EraId},
-    types::{chainspec::HighwayConfig as HighwayProtocolConfig, Chainspec, TimeDiff, Timestamp},
+    components::consensus::{
+        protocols::{
+            highway::config::Config as HighwayConfig,
+            round_robin::config::Config as RoundRobinConfig,
+        },
+        EraId,
+    },
+    crypto::{InProcessSigner, RemoteSigner, Signer},
+    types::{
+        chainspec::{ConsensusProtocolName, HighwayConfig as HighwayProtocolConfig},
+        Chainspec, TimeDiff, Timestamp,
+    },
     utils::{External, LoadError, Loadable},
 };
 
@@ -17,37 +30,75 @@ use crate::{
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub(crate) struct Config {
-    /// Path to secret key file.
+    /// Path to secret key file. Not needed if a remote signer is configured.
+    #[serde(default)]
     pub(crate) secret_key_path: External,
+    /// If set, all signatures are created by an external signing daemon, and no secret key is
+    /// loaded.
+    #[serde(default)]
+    pub(crate) remote_signer: Option<RemoteSignerConfig>,
     /// Highway-specific node configuration.
     pub(crate) highway: HighwayConfig,
+    /// Round-robin-specific node configuration.
+    #[serde(default)]
+    pub(crate) round_robin: RoundRobinConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            remote_signer: None,
             highway: HighwayConfig::default(),
+            round_robin: RoundRobinConfig::default(),
         }
     }
 }
 
 impl Config {
-    /// Loads the secret key from the configuration file and derives the public key.
-    #[allow(clippy::type_complexity)]
-    pub(crate) fn load_keys<P: AsRef<Path>>(
+    /// Creates the signer for our validator key: a remote signer if one is configured, otherwise
+    /// an in-process signer using the secret key loaded from the configured file.
+    pub(crate) fn load_signer<P: AsRef<Path>>(
         &self,
         root: P,
-    ) -> Result<(Arc<SecretKey>, PublicKey), LoadError<<Arc<SecretKey> as Loadable>::Error>> {
-        let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
-        let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
-        Ok((secret_signing_key, public_key))
+    ) -> Result<Arc<dyn Signer>, LoadError<<Arc<SecretKey> as Loadable>::Error>> {
+        match &self.remote_signer {
+            Some(remote_signer) => {
+                let public_key: PublicKey = remote_signer.public_key_path.clone().load(&root)?;
+                Ok(Arc::new(RemoteSigner::new(
+                    root.as_ref().join(&remote_signer.socket_path),
+                    public_key,
+                    remote_signer.request_timeout.into(),
+                    remote_signer.max_attempts,
+                )))
+            }
+            None => {
+                let secret_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
+                Ok(Arc::new(InProcessSigner::new(secret_key)))
+            }
+        }
     }
 }
 
+/// Configuration of the external signing daemon.
+#[derive(DataSize, Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RemoteSignerConfig {
+    /// Path to the Unix socket the signing daemon listens on.
+    pub(crate) socket_path: PathBuf,
+    /// Path to the validator's public key file, in PEM format.
+    pub(crate) public_key_path: External,
+    /// The timeout for a single signing request.
+    pub(crate) request_timeout: TimeDiff,
+    /// The number of times a signing request is attempted before giving up.
+    pub(crate) max_attempts: u32,
+}
+
 /// Consensus protocol configuration.
 #[derive(DataSize, Debug)]
 pub(crate) struct ProtocolConfig {
+    /// The consensus protocol run in each era.
+    pub(crate) consensus_protocol: ConsensusProtocolName,
     pub(crate) highway: HighwayProtocolConfig,
     pub(crate) era_duration: TimeDiff,
     pub(crate) minimum_era_height: u64,
@@ -72,6 +123,7 @@ pub(crate) struct ProtocolConfig {
 impl From<&Chainspec> for ProtocolConfig {
     fn from(chainspec: &Chainspec) -> Self {
         ProtocolConfig {
+            consensus_protocol: chainspec.core_config.consensus_protocol,
             highway: chainspec.highway_config,
             era_duration: chainspec.core_config.era_duration,
             minimum_era_height: chainspec.core_config.minimum_era_height,
@@ -13,7 +13,9 @@ use casper_hashing::Digest;
 use casper_types::bytesrepr::ToBytes;
 
 use crate::{
-    components::consensus::{traits::Context, ActionId, TimerId},
+    components::consensus::{
+        highway_core::slashing_protection::SlashingProtection, traits::Context, ActionId, TimerId,
+    },
     types::{TimeDiff, Timestamp},
 };
 
@@ -204,6 +206,9 @@ pub(crate) enum ProtocolOutcome<I, C: Context> {
     QueueAction(ActionId),
     /// Request deploys for a new block, providing the necessary context.
     CreateNewBlock(BlockContext<C>),
+    /// Request a signature of the given hash, which our validator secret can't create
+    /// synchronously. The result must be passed to `ConsensusProtocol::handle_signature`.
+    CreateSignature(C::Hash),
     /// A block was finalized.
     FinalizedBlock(FinalizedBlock<C>),
     /// Request validation of the consensus value, contained in a message received from the given
@@ -260,6 +265,15 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
         now: Timestamp,
     ) -> ProtocolOutcomes<I, C>;
 
+    /// Handles a signature requested via `ProtocolOutcome::CreateSignature`, or `None` if it could
+    /// not be created.
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        maybe_signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C>;
+
     /// Marks the `value` as valid or invalid, based on validation requested via
     /// `ProtocolOutcome::ValidateConsensusvalue`.
     fn resolve_validity(
@@ -276,6 +290,7 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
         secret: C::ValidatorSecret,
         timestamp: Timestamp,
         unit_hash_file: Option<PathBuf>,
+        slashing_protection: Option<SlashingProtection<C>>,
     ) -> ProtocolOutcomes<I, C>;
 
     /// Turns this instance into a passive observer, that does not create any new vertices.
@@ -293,6 +308,9 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
     /// Sends evidence for a faulty of validator `vid` to the `sender` of the request.
     fn request_evidence(&self, sender: I, vid: &C::ValidatorId) -> ProtocolOutcomes<I, C>;
 
+    /// Returns the serialized direct evidence against the validator `vid`, if any.
+    fn serialized_evidence(&self, vid: &C::ValidatorId) -> Option<Vec<u8>>;
+
     /// Sets the pause status: While paused we don't create consensus messages other than pings.
     fn set_paused(&mut self, paused: bool);
 
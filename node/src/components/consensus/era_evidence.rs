@@ -0,0 +1,285 @@
+//! Evidence against faulty validators, in a form that can be verified outside of the node.
+
+use std::collections::BTreeSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey};
+
+use super::{
+    cl_context::ClContext,
+    era_supervisor::instance_id,
+    highway_core::{validators::Validators, Evidence},
+};
+
+/// An error returned when verifying evidence against a validator.
+#[derive(Debug, Error)]
+pub enum EvidenceVerificationError {
+    /// The evidence is not hex-encoded.
+    #[error("failed to decode evidence: {0}")]
+    Decoding(#[from] hex::FromHexError),
+    /// The evidence is not a serialized Highway `Evidence`.
+    #[error("failed to deserialize evidence: {0}")]
+    Deserialization(#[from] bincode::Error),
+    /// The evidence's validator set differs from the trusted validator set of the era.
+    #[error("the validators of era {0} don't match the trusted validators")]
+    ValidatorsMismatch(EraId),
+    /// The evidence's chainspec hash differs from the trusted chainspec hash.
+    #[error("the chainspec hash {claimed} doesn't match the trusted chainspec hash {trusted}")]
+    ChainspecHashMismatch {
+        /// The chainspec hash included in the evidence.
+        claimed: Digest,
+        /// The trusted chainspec hash.
+        trusted: Digest,
+    },
+    /// The faulty validator is not in the era's validator set.
+    #[error("the perpetrator is not a validator in era {0}")]
+    UnknownPerpetrator(EraId),
+    /// The evidence is against a validator other than the claimed perpetrator.
+    #[error("the evidence is against {actual}, not {claimed}")]
+    WrongPerpetrator {
+        /// The validator named as perpetrator.
+        claimed: Box<PublicKey>,
+        /// The validator actually proven faulty by the evidence.
+        actual: Box<PublicKey>,
+    },
+    /// The evidence does not prove the validator faulty.
+    #[error("invalid evidence: {0}")]
+    InvalidEvidence(String),
+}
+
+/// Evidence that a validator equivocated in an era, together with the era's validator set it has
+/// to be verified against.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EraEvidence {
+    /// The era in which the validator equivocated.
+    era_id: EraId,
+    /// The hash of the chainspec, which together with the era ID identifies the consensus
+    /// instance.
+    chainspec_hash: Digest,
+    /// The faulty validator.
+    perpetrator: PublicKey,
+    /// The validators of the era.
+    validators: BTreeSet<PublicKey>,
+    /// The hex-encoded, bincode-serialized Highway evidence.
+    evidence: String,
+}
+
+impl EraEvidence {
+    pub(crate) fn new(
+        era_id: EraId,
+        chainspec_hash: Digest,
+        perpetrator: PublicKey,
+        validators: BTreeSet<PublicKey>,
+        serialized_evidence: &[u8],
+    ) -> Self {
+        EraEvidence {
+            era_id,
+            chainspec_hash,
+            perpetrator,
+            validators,
+            evidence: hex::encode(serialized_evidence),
+        }
+    }
+
+    /// Returns the era in which the validator equivocated.
+    pub fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    /// Returns the hash of the chainspec.
+    pub fn chainspec_hash(&self) -> &Digest {
+        &self.chainspec_hash
+    }
+
+    /// Returns the faulty validator.
+    pub fn perpetrator(&self) -> &PublicKey {
+        &self.perpetrator
+    }
+
+    /// Returns the validators of the era.
+    pub fn validators(&self) -> &BTreeSet<PublicKey> {
+        &self.validators
+    }
+}
+
+/// Verifies that the evidence proves its perpetrator faulty, i.e. that it contains two conflicting
+/// units, both signed by the perpetrator for the era's consensus instance.
+///
+/// The validator set and chainspec hash included in `era_evidence` are untrusted, so they must
+/// match `trusted_validators` and `trusted_chainspec_hash`, which should be obtained independently
+/// of the node which provided the evidence, e.g. from the switch block of the previous era.
+pub fn verify_evidence(
+    era_evidence: &EraEvidence,
+    trusted_validators: &BTreeSet<PublicKey>,
+    trusted_chainspec_hash: &Digest,
+) -> Result<(), EvidenceVerificationError> {
+    if era_evidence.validators != *trusted_validators {
+        return Err(EvidenceVerificationError::ValidatorsMismatch(
+            era_evidence.era_id,
+        ));
+    }
+    if era_evidence.chainspec_hash != *trusted_chainspec_hash {
+        return Err(EvidenceVerificationError::ChainspecHashMismatch {
+            claimed: era_evidence.chainspec_hash,
+            trusted: *trusted_chainspec_hash,
+        });
+    }
+    let serialized_evidence = hex::decode(&era_evidence.evidence)?;
+    let evidence: Evidence<ClContext> = bincode::deserialize(&serialized_evidence)?;
+    // Only the validators' order matters for verification, not their weights.
+    let validators: Validators<PublicKey> = era_evidence
+        .validators
+        .iter()
+        .map(|public_key| (public_key.clone(), 1u64))
+        .collect();
+    let actual = validators.id(evidence.perpetrator()).ok_or(
+        EvidenceVerificationError::UnknownPerpetrator(era_evidence.era_id),
+    )?;
+    if *actual != era_evidence.perpetrator {
+        return Err(EvidenceVerificationError::WrongPerpetrator {
+            claimed: Box::new(era_evidence.perpetrator.clone()),
+            actual: Box::new(actual.clone()),
+        });
+    }
+    let instance_id = instance_id(era_evidence.chainspec_hash, era_evidence.era_id);
+    evidence
+        .validate_proof(&validators, &instance_id)
+        .map_err(|error| EvidenceVerificationError::InvalidEvidence(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        components::consensus::{
+            cl_context::Keypair,
+            highway_core::{
+                highway::{SignedWireUnit, WireUnit},
+                state::{Observation, Panorama},
+            },
+            tests::utils::{ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
+        },
+        types::Timestamp,
+    };
+
+    /// Returns a unit signed by Alice, with the given timestamp.
+    fn alice_unit(
+        validators: &BTreeSet<PublicKey>,
+        instance_id: Digest,
+        timestamp: u64,
+    ) -> SignedWireUnit<ClContext> {
+        let validator_indices: Validators<PublicKey> =
+            validators.iter().map(|pk| (pk.clone(), 1u64)).collect();
+        let wunit: WireUnit<ClContext> = WireUnit {
+            panorama: Panorama::from(vec![Observation::None; validators.len()]),
+            creator: validator_indices
+                .get_index(&*ALICE_PUBLIC_KEY)
+                .expect("Alice should be a validator"),
+            instance_id,
+            value: None,
+            seq_number: 0,
+            timestamp: Timestamp::from(timestamp),
+            round_exp: 14,
+            endorsed: BTreeSet::new(),
+        };
+        let alice_keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair)
+    }
+
+    /// Returns the validators of the era in which Alice equivocates.
+    fn validators() -> BTreeSet<PublicKey> {
+        vec![ALICE_PUBLIC_KEY.clone(), BOB_PUBLIC_KEY.clone()]
+            .into_iter()
+            .collect()
+    }
+
+    fn era_evidence(
+        chainspec_hash: Digest,
+        perpetrator: PublicKey,
+        timestamps: (u64, u64),
+    ) -> EraEvidence {
+        let era_id = EraId::new(3);
+        let validators = validators();
+        let instance_id = instance_id(chainspec_hash, era_id);
+        let evidence = Evidence::Equivocation(
+            alice_unit(&validators, instance_id, timestamps.0),
+            alice_unit(&validators, instance_id, timestamps.1),
+        );
+        EraEvidence::new(
+            era_id,
+            chainspec_hash,
+            perpetrator,
+            validators,
+            &bincode::serialize(&evidence).unwrap(),
+        )
+    }
+
+    #[test]
+    fn should_verify_equivocation() {
+        let chainspec_hash = Digest::hash(b"chainspec");
+        let evidence = era_evidence(chainspec_hash, ALICE_PUBLIC_KEY.clone(), (1, 2));
+        assert!(verify_evidence(&evidence, &validators(), &chainspec_hash).is_ok());
+
+        // The evidence also survives the round trip through JSON.
+        let json = serde_json::to_string(&evidence).unwrap();
+        let decoded: EraEvidence = serde_json::from_str(&json).unwrap();
+        assert!(verify_evidence(&decoded, &validators(), &chainspec_hash).is_ok());
+    }
+
+    #[test]
+    fn should_reject_evidence_against_wrong_validator() {
+        let chainspec_hash = Digest::hash(b"chainspec");
+        let evidence = era_evidence(chainspec_hash, BOB_PUBLIC_KEY.clone(), (1, 2));
+        assert!(matches!(
+            verify_evidence(&evidence, &validators(), &chainspec_hash),
+            Err(EvidenceVerificationError::WrongPerpetrator { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_identical_units() {
+        let chainspec_hash = Digest::hash(b"chainspec");
+        let evidence = era_evidence(chainspec_hash, ALICE_PUBLIC_KEY.clone(), (1, 1));
+        assert!(matches!(
+            verify_evidence(&evidence, &validators(), &chainspec_hash),
+            Err(EvidenceVerificationError::InvalidEvidence(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_evidence_for_another_chain() {
+        let chainspec_hash = Digest::hash(b"chainspec");
+        let other_chainspec_hash = Digest::hash(b"other chainspec");
+        let mut evidence = era_evidence(chainspec_hash, ALICE_PUBLIC_KEY.clone(), (1, 2));
+        assert!(matches!(
+            verify_evidence(&evidence, &validators(), &other_chainspec_hash),
+            Err(EvidenceVerificationError::ChainspecHashMismatch { .. })
+        ));
+
+        // Even if the evidence claims the other chainspec hash, the units were signed for the
+        // original chain's consensus instance.
+        evidence.chainspec_hash = other_chainspec_hash;
+        assert!(matches!(
+            verify_evidence(&evidence, &validators(), &other_chainspec_hash),
+            Err(EvidenceVerificationError::InvalidEvidence(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_evidence_with_untrusted_validators() {
+        let chainspec_hash = Digest::hash(b"chainspec");
+        let evidence = era_evidence(chainspec_hash, ALICE_PUBLIC_KEY.clone(), (1, 2));
+        let trusted_validators = vec![ALICE_PUBLIC_KEY.clone()].into_iter().collect();
+        assert!(matches!(
+            verify_evidence(&evidence, &trusted_validators, &chainspec_hash),
+            Err(EvidenceVerificationError::ValidatorsMismatch(_))
+        ));
+    }
+}
@@ -12,7 +12,7 @@ use std::{
     convert::TryInto,
     fmt::{self, Debug, Formatter},
     fs, io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -26,7 +26,7 @@ use rand::Rng;
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, U512};
+use casper_types::{AsymmetricType, EraId, PublicKey, Signature, U512};
 
 pub use self::era::Era;
 use crate::{
@@ -37,12 +37,18 @@ use crate::{
             ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, ProposedBlock,
             ProtocolOutcome, ProtocolOutcomes,
         },
+        era_evidence::EraEvidence,
+        highway_core::slashing_protection::{
+            self, SlashingProtection, SlashingProtectionInterchange,
+        },
         metrics::ConsensusMetrics,
+        protocols,
         traits::NodeIdT,
         validator_change::ValidatorChanges,
-        ActionId, Config, ConsensusMessage, Event, NewBlockPayload, ReactorEventT, ResolveValidity,
-        TimerId, ValidatorChange,
+        ActionId, Config, ConsensusMessage, Event, HighwayProtocol, HighwaySnapshot,
+        NewBlockPayload, ReactorEventT, ResolveValidity, TimerId, ValidatorChange,
     },
+    crypto::Signer,
     effect::{
         announcements::ControlAnnouncement,
         requests::{BlockValidationRequest, ContractRuntimeRequest, StorageRequest},
@@ -61,7 +67,7 @@ use crate::{
 /// fault tolerance threshold.
 const FTT_EXCEEDED_SHUTDOWN_DELAY_MILLIS: u64 = 60 * 1000;
 
-type ConsensusConstructor<I> = dyn Fn(
+pub(crate) type ConsensusConstructor<I> = dyn Fn(
         Digest,                    // the era's unique instance ID
         BTreeMap<PublicKey, U512>, // validator weights
         &HashSet<PublicKey>,       /* faulty validators that are banned in
@@ -86,7 +92,8 @@ pub struct EraSupervisor<I> {
     /// This map always contains exactly `2 * bonded_eras + 1` entries, with the last one being the
     /// current one.
     active_eras: HashMap<EraId, Era<I>>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_signing_key: PublicKey,
     current_era: EraId,
     protocol_config: ProtocolConfig,
@@ -104,7 +111,8 @@ pub struct EraSupervisor<I> {
     next_executed_height: u64,
     #[data_size(skip)]
     metrics: ConsensusMetrics,
-    /// The path to the folder where unit hash files will be stored.
+    /// The path to the folder where unit hash files and the slashing protection store will be
+    /// stored.
     unit_hashes_folder: PathBuf,
     /// The next upgrade activation point. When the era immediately before the activation point is
     /// deactivated, the era supervisor indicates that the node should stop running to allow an
@@ -137,7 +145,6 @@ where
         maybe_latest_block_header: Option<&BlockHeader>,
         next_upgrade_activation_point: Option<ActivationPoint>,
         registry: &Registry,
-        new_consensus: Box<ConsensusConstructor<I>>,
     ) -> Result<(Self, Effects<Event<I>>), Error> {
         if current_era < protocol_config.last_activation_point {
             panic!(
@@ -148,7 +155,8 @@ where
         }
         let unit_hashes_folder = config.with_dir(config.value().highway.unit_hashes_folder.clone());
         let (root, config) = config.into_parts();
-        let (secret_signing_key, public_signing_key) = config.load_keys(root)?;
+        let signer = config.load_signer(root)?;
+        let public_signing_key = signer.public_key().clone();
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics = ConsensusMetrics::new(registry)
             .expect("failure to setup and register ConsensusMetrics");
@@ -156,10 +164,11 @@ where
         let auction_delay = protocol_config.auction_delay;
         #[allow(clippy::integer_arithmetic)] // Block height should never reach u64::MAX.
         let next_height = maybe_latest_block_header.map_or(0, |hdr| hdr.height() + 1);
+        let new_consensus = protocols::consensus_constructor(protocol_config.consensus_protocol);
 
         let era_supervisor = Self {
             active_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             current_era,
             protocol_config,
@@ -246,6 +255,47 @@ where
         result
     }
 
+    /// Returns the evidence against faulty validators in the active eras, optionally restricted
+    /// to a single era and validator.
+    pub(super) fn get_evidence(
+        &self,
+        maybe_era_id: Option<EraId>,
+        maybe_public_key: Option<&PublicKey>,
+    ) -> Vec<EraEvidence> {
+        let chainspec_hash = self.protocol_config.chainspec_hash;
+        self.active_eras
+            .iter()
+            .filter(|(era_id, _)| maybe_era_id.map_or(true, |id| id == **era_id))
+            .flat_map(|(era_id, era)| {
+                era.consensus
+                    .validators_with_evidence()
+                    .into_iter()
+                    .filter(|pub_key| maybe_public_key.map_or(true, |key| key == *pub_key))
+                    .filter_map(move |pub_key| {
+                        let evidence = era.consensus.serialized_evidence(pub_key)?;
+                        Some(EraEvidence::new(
+                            *era_id,
+                            chainspec_hash,
+                            pub_key.clone(),
+                            era.validators().keys().cloned().collect(),
+                            &evidence,
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns a snapshot of the current era's Highway protocol state, or `None` if there is no
+    /// current era or it doesn't run Highway.
+    pub(super) fn get_snapshot(&self) -> Option<HighwaySnapshot> {
+        let era = self.active_eras.get(&self.current_era)?;
+        let highway = era
+            .consensus
+            .as_any()
+            .downcast_ref::<HighwayProtocol<I, ClContext>>()?;
+        Some(HighwaySnapshot::new(self.current_era, highway))
+    }
+
     fn era_seed(booking_block_hash: BlockHash, key_block_seed: Digest) -> u64 {
         let result = Digest::hash_pair(booking_block_hash, key_block_seed).value();
         u64::from_le_bytes(result[0..std::mem::size_of::<u64>()].try_into().unwrap())
@@ -297,7 +347,7 @@ where
         if self.active_eras.contains_key(&era_id) {
             panic!("{} already exists", era_id);
         }
-        let instance_id = instance_id(&self.protocol_config, era_id);
+        let instance_id = instance_id(self.protocol_config.chainspec_hash, era_id);
 
         info!(
             ?validators,
@@ -349,13 +399,21 @@ where
         );
 
         if should_activate {
-            let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
-            outcomes.extend(consensus.activate_validator(
-                our_id.clone(),
-                secret,
-                now,
-                Some(self.unit_hash_file(&instance_id)),
-            ))
+            let secret = Keypair::new(Arc::clone(&self.signer));
+            match SlashingProtection::open(self.slashing_protection_file(), era_id, instance_id) {
+                Ok(slashing_protection) => outcomes.extend(consensus.activate_validator(
+                    our_id.clone(),
+                    secret,
+                    now,
+                    Some(self.unit_hash_file(&instance_id)),
+                    Some(slashing_protection),
+                )),
+                Err(err) => error!(
+                    %err,
+                    era = era_id.value(),
+                    "could not open slashing protection store; not activating validator"
+                ),
+            }
         }
 
         let mut era = Era::new(
@@ -408,6 +466,12 @@ where
                         err => warn!(?err, "could not delete unit hash file"),
                     },
                 }
+                if let Err(err) = slashing_protection::prune::<ClContext>(
+                    &self.slashing_protection_file(),
+                    oldest_evidence_era_id,
+                ) {
+                    warn!(%err, "could not prune slashing protection store");
+                }
             }
         }
 
@@ -573,6 +637,58 @@ where
             self.public_signing_key.to_hex()
         ))
     }
+
+    /// Returns the path to our validator's slashing protection store.
+    fn slashing_protection_file(&self) -> PathBuf {
+        slashing_protection_file(&self.unit_hashes_folder, &self.public_signing_key)
+    }
+}
+
+/// Returns the path to the slashing protection store of the validator with the given public key.
+fn slashing_protection_file(unit_hashes_folder: &Path, public_key: &PublicKey) -> PathBuf {
+    unit_hashes_folder.join(format!("slashing_protection_{}.jsonl", public_key.to_hex()))
+}
+
+/// Returns the path to the configured validator's slashing protection store, and its public key
+/// in hex format.
+fn configured_slashing_protection_file(
+    config: &WithDir<Config>,
+) -> Result<(PathBuf, String), Error> {
+    let public_key = config
+        .value()
+        .load_signer(config.dir())?
+        .public_key()
+        .clone();
+    let unit_hashes_folder = config.with_dir(config.value().highway.unit_hashes_folder.clone());
+    let path = slashing_protection_file(&unit_hashes_folder, &public_key);
+    Ok((path, public_key.to_hex()))
+}
+
+/// Writes all records from the configured validator's slashing protection store to `output`.
+///
+/// Returns the number of exported records.
+pub(crate) fn export_slashing_protection(
+    config: &WithDir<Config>,
+    output: &Path,
+) -> Result<usize, Error> {
+    let (path, validator) = configured_slashing_protection_file(config)?;
+    let interchange = SlashingProtectionInterchange::<ClContext>::export(&path, validator)?;
+    fs::write(output, serde_json::to_vec_pretty(&interchange)?)?;
+    Ok(interchange.signed_units.len())
+}
+
+/// Adds the records exported to `input` to the configured validator's slashing protection store.
+///
+/// Nothing is imported if the records belong to a different validator, or if any of them
+/// conflicts with an existing record. Returns the number of newly added records.
+pub(crate) fn import_slashing_protection(
+    config: &WithDir<Config>,
+    input: &Path,
+) -> Result<usize, Error> {
+    let (path, validator) = configured_slashing_protection_file(config)?;
+    let interchange: SlashingProtectionInterchange<ClContext> =
+        serde_json::from_slice(&fs::read(input)?)?;
+    Ok(interchange.import(&path, &validator)?)
 }
 
 #[cfg(test)]
@@ -729,6 +845,17 @@ where
         })
     }
 
+    pub(super) fn handle_signature(
+        &mut self,
+        era_id: EraId,
+        hash: Digest,
+        maybe_signature: Option<Signature>,
+    ) -> Effects<Event<I>> {
+        self.delegate_to_era(era_id, move |consensus| {
+            consensus.handle_signature(hash, maybe_signature, Timestamp::now())
+        })
+    }
+
     pub(super) fn handle_action(
         &mut self,
         era_id: EraId,
@@ -787,18 +914,23 @@ where
 
     pub(super) fn handle_block_added(&mut self, block_header: BlockHeader) -> Effects<Event<I>> {
         let our_pk = self.era_supervisor.public_signing_key.clone();
-        let our_sk = self.era_supervisor.secret_signing_key.clone();
         let era_id = block_header.era_id();
         self.era_supervisor.executed_block(&block_header);
         let mut effects = if self.era_supervisor.is_validator_in(&our_pk, era_id) {
-            self.effect_builder
-                .announce_created_finality_signature(FinalitySignature::new(
-                    block_header.hash(),
-                    era_id,
-                    &our_sk,
-                    our_pk,
-                ))
-                .ignore()
+            let signer = Arc::clone(&self.era_supervisor.signer);
+            let effect_builder = self.effect_builder;
+            let block_hash = block_header.hash();
+            async move {
+                match FinalitySignature::create(block_hash, era_id, &*signer).await {
+                    Ok(finality_signature) => {
+                        effect_builder
+                            .announce_created_finality_signature(finality_signature)
+                            .await
+                    }
+                    Err(err) => error!(%err, %block_hash, "failed to sign block"),
+                }
+            }
+            .ignore()
         } else {
             Effects::new()
         };
@@ -1069,6 +1201,21 @@ where
                         timer_id,
                     })
             }
+            ProtocolOutcome::CreateSignature(hash) => {
+                let signer = Arc::clone(&self.era_supervisor.signer);
+                async move {
+                    signer
+                        .sign(hash.as_ref())
+                        .await
+                        .map_err(|err| error!(%err, %hash, "failed to sign consensus message"))
+                        .ok()
+                }
+                .event(move |maybe_signature| Event::SignatureCreated {
+                    era_id,
+                    hash,
+                    maybe_signature,
+                })
+            }
             ProtocolOutcome::QueueAction(action_id) => self
                 .effect_builder
                 .immediately()
@@ -1357,8 +1504,8 @@ async fn execute_finalized_block<REv>(
 }
 
 /// Computes the instance ID for an era, given the era ID and the chainspec hash.
-fn instance_id(protocol_config: &ProtocolConfig, era_id: EraId) -> Digest {
-    Digest::hash_pair(protocol_config.chainspec_hash, era_id.to_le_bytes())
+pub(super) fn instance_id(chainspec_hash: Digest, era_id: EraId) -> Digest {
+    Digest::hash_pair(chainspec_hash, era_id.to_le_bytes())
         .value()
         .into()
 }
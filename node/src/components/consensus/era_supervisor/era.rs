@@ -14,7 +14,7 @@ use crate::{
     components::consensus::{
         cl_context::ClContext,
         consensus_protocol::{ConsensusProtocol, ProposedBlock},
-        protocols::highway::HighwayProtocol,
+        protocols::{highway::HighwayProtocol, round_robin::RoundRobinProtocol},
     },
     types::Timestamp,
 };
@@ -207,10 +207,14 @@ where
                 } else {
                     (*highway).estimate_heap_size()
                 }
+            } else if let Some(round_robin) =
+                any_ref.downcast_ref::<RoundRobinProtocol<I, ClContext>>()
+            {
+                (*round_robin).estimate_heap_size()
             } else {
                 warn!(
-                    "could not downcast consensus protocol to \
-                    HighwayProtocol<I, ClContext> to determine heap allocation size"
+                    "could not downcast consensus protocol to a known protocol \
+                    to determine heap allocation size"
                 );
                 0
             }
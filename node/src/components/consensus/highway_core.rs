@@ -35,6 +35,7 @@ mod test_macros;
 pub(crate) mod active_validator;
 pub(crate) mod finality_detector;
 pub(crate) mod highway;
+pub(crate) mod slashing_protection;
 pub(crate) mod state;
 pub(super) mod synchronizer;
 pub(crate) mod validators;
@@ -44,4 +45,5 @@ mod evidence;
 #[cfg(test)]
 pub(crate) mod highway_testing;
 
+pub(crate) use evidence::Evidence;
 pub(crate) use state::{State, Weight};
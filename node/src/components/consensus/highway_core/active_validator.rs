@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -12,7 +13,8 @@ use tracing::{error, info, trace, warn};
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Ping, ValidVertex, Vertex, WireUnit},
+    highway::{HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
+    slashing_protection::{MessageRecord, SlashingProtection},
     state::{self, Panorama, State, Unit, Weight},
     validators::ValidatorIndex,
 };
@@ -40,6 +42,20 @@ pub(crate) enum Effect<C: Context> {
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
+    /// The secret cannot sign synchronously: `on_signature` needs to be called with a signature
+    /// of the specified hash.
+    RequestSignature(C::Hash),
+}
+
+/// Our own unit that has been recorded by slashing protection but is still waiting to be signed.
+#[derive(DataSize, Debug)]
+struct UnsignedUnit<C>
+where
+    C: Context,
+{
+    hwunit: HashedWireUnit<C>,
+    /// Whether a signature has been requested and neither created nor failed yet.
+    awaiting_signature: bool,
 }
 
 /// A validator that actively participates in consensus by creating new vertices.
@@ -75,11 +91,21 @@ where
     unit_file: Option<PathBuf>,
     /// The last known unit created by us.
     own_last_unit: Option<SignedWireUnit<C>>,
+    /// The record of all units we signed in this era, if any. Units that conflict with it are
+    /// never signed.
+    slashing_protection: Option<SlashingProtection<C>>,
     /// The target fault tolerance threshold. The validator pauses (i.e. doesn't create new units)
     /// if not enough validators are online to finalize values at this FTT.
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// Our latest unit, if its signature has not been created yet. No other unit is created until
+    /// it is signed, since the next one would have to cite it.
+    unsigned_unit: Option<UnsignedUnit<C>>,
+    /// Pings waiting for a signature, by hash, with their timestamp and instance ID.
+    unsigned_pings: HashMap<C::Hash, (Timestamp, C::InstanceId)>,
+    /// Endorsements waiting for a signature, by hash.
+    unsigned_endorsements: HashMap<C::Hash, Endorsement<C>>,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -103,6 +129,7 @@ impl<C: Context> ActiveValidator<C> {
         start_time: Timestamp,
         state: &State<C>,
         unit_file: Option<PathBuf>,
+        slashing_protection: Option<SlashingProtection<C>>,
         target_ftt: Weight,
         instance_id: C::InstanceId,
     ) -> (Self, Vec<Effect<C>>) {
@@ -124,8 +151,12 @@ impl<C: Context> ActiveValidator<C> {
             next_proposal: None,
             unit_file,
             own_last_unit,
+            slashing_protection,
             target_ftt,
             paused: false,
+            unsigned_unit: None,
+            unsigned_pings: HashMap::new(),
+            unsigned_endorsements: HashMap::new(),
         };
         let mut effects = av.schedule_timer(start_time, state);
         effects.push(av.send_ping(current_time, instance_id));
@@ -137,11 +168,19 @@ impl<C: Context> ActiveValidator<C> {
     ///
     /// If validator restarted within an era, it most likely had created units before that event. It
     /// cannot start creating new units until its state is fully synchronized, otherwise it will
-    /// most likely equivocate.
+    /// most likely equivocate. The same applies if the validator's key was used on a different
+    /// machine before, and its units were imported into the slashing protection store.
     fn can_vote(&self, state: &State<C>) -> bool {
+        let last_signed_hash = self
+            .slashing_protection
+            .as_ref()
+            .and_then(SlashingProtection::last_unit_hash);
         self.own_last_unit
             .as_ref()
-            .map_or(true, |swunit| state.has_unit(&swunit.hash()))
+            .map(SignedWireUnit::hash)
+            .into_iter()
+            .chain(last_signed_hash)
+            .all(|hash| state.has_unit(&hash))
     }
 
     /// Returns whether validator's protocol state is synchronized up until the panorama of its own
@@ -183,6 +222,7 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         let mut effects = self.schedule_timer(timestamp, state);
+        effects.extend(self.retry_unsigned_unit());
         if self.earliest_unit_time(state) > timestamp {
             warn!(%timestamp, "skipping outdated timer event");
             return effects;
@@ -197,7 +237,7 @@ impl<C: Context> ActiveValidator<C> {
                 return effects;
             } else if timestamp == r_id + self.witness_offset(r_len) {
                 let panorama = self.panorama_at(state, timestamp);
-                if let Some(witness_unit) =
+                if let Some(witness_effect) =
                     self.new_unit(panorama, timestamp, None, state, instance_id)
                 {
                     if self
@@ -206,7 +246,7 @@ impl<C: Context> ActiveValidator<C> {
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
-                    effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                    effects.push(witness_effect);
                     return effects;
                 }
             }
@@ -221,10 +261,91 @@ impl<C: Context> ActiveValidator<C> {
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, or requests its signature if the secret can't sign synchronously.
+    pub(crate) fn send_ping(
+        &mut self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Effect<C> {
+        let hash = Ping::<C>::hash(self.vidx, timestamp, instance_id);
+        match self.secret.try_sign(&hash) {
+            Some(signature) => {
+                let ping = Ping::with_signature(self.vidx, timestamp, instance_id, signature);
+                Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+            }
+            None => {
+                let _ = self.unsigned_pings.insert(hash, (timestamp, instance_id));
+                Effect::RequestSignature(hash)
+            }
+        }
+    }
+
+    /// Returns actions a validator needs to take once the signature of `hash` has been created.
+    ///
+    /// If the signature could not be created, pings and endorsements are dropped. Our own unit is
+    /// kept, since slashing protection has already recorded it, and requested again on the next
+    /// timer event.
+    pub(crate) fn on_signature(
+        &mut self,
+        hash: &C::Hash,
+        maybe_signature: Option<C::Signature>,
+    ) -> Vec<Effect<C>> {
+        if let Some(unsigned_unit) = self
+            .unsigned_unit
+            .as_mut()
+            .filter(|unsigned_unit| unsigned_unit.hwunit.hash() == *hash)
+        {
+            return match maybe_signature {
+                Some(signature) => {
+                    let hwunit = unsigned_unit.hwunit.clone();
+                    self.unsigned_unit = None;
+                    vec![self.own_unit_effect(SignedWireUnit::with_signature(hwunit, signature))]
+                }
+                None => {
+                    error!(%hash, "failed to sign own unit; retrying on next timer event");
+                    unsigned_unit.awaiting_signature = false;
+                    vec![]
+                }
+            };
+        }
+        if let Some((timestamp, instance_id)) = self.unsigned_pings.remove(hash) {
+            return match maybe_signature {
+                Some(signature) => {
+                    let ping = Ping::with_signature(self.vidx, timestamp, instance_id, signature);
+                    vec![Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))]
+                }
+                None => {
+                    error!(%hash, %timestamp, "failed to sign ping; skipping it");
+                    vec![]
+                }
+            };
+        }
+        if let Some(endorsement) = self.unsigned_endorsements.remove(hash) {
+            return match maybe_signature {
+                Some(signature) => {
+                    let endorsements = SignedEndorsement::new(endorsement, signature).into();
+                    vec![Effect::NewVertex(ValidVertex(Vertex::Endorsements(
+                        endorsements,
+                    )))]
+                }
+                None => {
+                    error!(%hash, "failed to sign endorsement; skipping it");
+                    vec![]
+                }
+            };
+        }
+        warn!(%hash, "received signature that was not requested");
+        vec![]
+    }
+
+    /// Requests the signature of our unsigned unit again, if the previous attempt failed.
+    fn retry_unsigned_unit(&mut self) -> Option<Effect<C>> {
+        let unsigned_unit = self
+            .unsigned_unit
+            .as_mut()
+            .filter(|unsigned_unit| !unsigned_unit.awaiting_signature)?;
+        unsigned_unit.awaiting_signature = true;
+        Some(Effect::RequestSignature(unsigned_unit.hwunit.hash()))
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -258,17 +379,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.extend(self.endorse(uhash, state));
         }
         effects
     }
@@ -288,8 +403,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
+            .filter_map(|v| self.endorse(v, state))
             .collect()
     }
 
@@ -311,9 +425,7 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -356,7 +468,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -405,7 +516,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns a `NewVertex` effect with a new unit with the given data and the correct sequence
+    /// number, or a `RequestSignature` effect if the secret can't sign it synchronously.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -415,10 +527,14 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if value.is_none() && !panorama.has_correct() {
             return None; // Wait for the first proposal before creating a unit without a value.
         }
+        if let Some(unsigned_unit) = &self.unsigned_unit {
+            info!(hash = %unsigned_unit.hwunit.hash(), "not voting - own unit not signed yet");
+            return None;
+        }
         if !self.can_vote(state) {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
@@ -455,14 +571,39 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
+        if let Some(slashing_protection) = self.slashing_protection.as_mut() {
+            let wunit = hwunit.wire_unit();
+            if let Err(err) =
+                slashing_protection.record_unit(wunit.seq_number, &wunit.panorama, hwunit.hash())
+            {
+                error!(%err, "canceling unit creation");
+                return None;
+            }
+        }
+        match self.secret.try_sign(&hwunit.hash()) {
+            Some(signature) => {
+                Some(self.own_unit_effect(SignedWireUnit::with_signature(hwunit, signature)))
+            }
+            None => {
+                let hash = hwunit.hash();
+                self.unsigned_unit = Some(UnsignedUnit {
+                    hwunit,
+                    awaiting_signature: true,
+                });
+                Some(Effect::RequestSignature(hash))
+            }
+        }
+    }
+
+    /// Records the signed unit as our latest one and returns the effect to add and gossip it.
+    fn own_unit_effect(&mut self, swunit: SignedWireUnit<C>) -> Effect<C> {
         write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
             panic!(
                 "should successfully write unit's hash to {:?}, got {:?}",
                 self.unit_file, err
             )
         });
-        Some(swunit)
+        Effect::NewVertex(ValidVertex(Vertex::Unit(swunit)))
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -553,11 +694,37 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
+    /// Creates endorsement of the `vhash`, or requests its signature if the secret can't sign
+    /// synchronously.
+    ///
+    /// Returns `None` if slashing protection refuses to sign the endorsement.
+    fn endorse(&mut self, vhash: &C::Hash, state: &State<C>) -> Option<Effect<C>> {
+        if let Some(slashing_protection) = self.slashing_protection.as_mut() {
+            let unit = state.unit(vhash);
+            let message = MessageRecord::Endorsement {
+                creator: unit.creator,
+                seq_number: unit.seq_number,
+                unit_hash: *vhash,
+            };
+            if let Err(err) = slashing_protection.record_message(message) {
+                error!(%err, "canceling endorsement");
+                return None;
+            }
+        }
         let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+        let hash = endorsement.hash();
+        match self.secret.try_sign(&hash) {
+            Some(signature) => {
+                let endorsements = SignedEndorsement::new(endorsement, signature).into();
+                Some(Effect::NewVertex(ValidVertex(Vertex::Endorsements(
+                    endorsements,
+                ))))
+            }
+            None => {
+                let _ = self.unsigned_endorsements.insert(hash, endorsement);
+                Some(Effect::RequestSignature(hash))
+            }
+        }
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -610,6 +777,11 @@ impl<C: Context> ActiveValidator<C> {
     pub(crate) fn next_round_length(&self) -> TimeDiff {
         state::round_len(self.next_round_exp)
     }
+
+    /// Returns the round exponent this validator will use for its next round.
+    pub(crate) fn next_round_exp(&self) -> u8 {
+        self.next_round_exp
+    }
 }
 
 pub(crate) fn read_last_unit<C, P>(path: P) -> io::Result<SignedWireUnit<C>>
@@ -652,6 +824,8 @@ mod tests {
     use std::{collections::BTreeSet, fmt::Debug};
     use tempfile::tempdir;
 
+    use casper_types::EraId;
+
     use crate::components::consensus::highway_core::{
         highway_testing::TEST_INSTANCE_ID, validators::ValidatorMap,
     };
@@ -718,6 +892,7 @@ mod tests {
                     start_time,
                     &state,
                     None,
+                    None,
                     target_ftt,
                     TEST_INSTANCE_ID,
                 );
@@ -955,6 +1130,7 @@ mod tests {
             410.into(),
             &state,
             None,
+            None,
             Weight(2),
             TEST_INSTANCE_ID,
         );
@@ -978,6 +1154,7 @@ mod tests {
             410.into(),
             &state,
             None,
+            None,
             Weight(2),
             TEST_INSTANCE_ID,
         );
@@ -1029,6 +1206,7 @@ mod tests {
             410.into(),
             &state,
             unit_file,
+            None,
             Weight(2),
             TEST_INSTANCE_ID,
         );
@@ -1072,6 +1250,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn refuses_conflicting_endorsements() -> Result<(), AddUnitError<TestContext>> {
+        let mut state = State::new_test(&[Weight(3), Weight(4)], 0);
+        let b0 = add_unit!(state, BOB, 0xB0; N, N)?;
+        // Bob equivocates: `b0_prime` has the same sequence number as `b0`.
+        let b0_prime = add_unit!(state, BOB, 0xB1; N, N)?;
+
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("slashing_protection.jsonl");
+        let new_alice = || {
+            let slashing_protection =
+                SlashingProtection::open(path.clone(), EraId::from(0), TEST_INSTANCE_ID)
+                    .expect("opening slashing protection store should succeed");
+            let (alice, _) = ActiveValidator::new(
+                ALICE,
+                TestSecret(ALICE.0),
+                410.into(),
+                410.into(),
+                &state,
+                None,
+                Some(slashing_protection),
+                Weight(2),
+                TEST_INSTANCE_ID,
+            );
+            alice
+        };
+
+        let mut alice = new_alice();
+        match alice.endorse(&b0, &state) {
+            Some(Eff::NewVertex(ValidVertex(Vertex::Endorsements(endorsements)))) => {
+                assert_eq!(&b0, endorsements.unit())
+            }
+            effect => panic!("unexpected effect {:?}", effect),
+        }
+        // Endorsing the same unit again is fine, but endorsing both sides of an equivocation is
+        // refused, also after a restart.
+        assert!(alice.endorse(&b0, &state).is_some());
+        assert!(alice.endorse(&b0_prime, &state).is_none());
+        let mut alice = new_alice();
+        assert!(alice.endorse(&b0_prime, &state).is_none());
+        Ok(())
+    }
+
     // Triggers new proposal by `validator` and verifies that it's empty – no block was proposed.
     // Captures the next witness timer and calls the `validator` with that to return the timer for
     // the next proposal.
@@ -87,6 +87,21 @@ impl<C: Context> Evidence<C> {
         validators: &Validators<C::ValidatorId>,
         instance_id: &C::InstanceId,
         params: &Params,
+    ) -> Result<(), EvidenceError> {
+        if let Evidence::Endorsements { swimlane2, .. } = self {
+            if swimlane2.len() as u64 > params.endorsement_evidence_limit() {
+                return Err(EvidenceError::EndorsementTooManyUnits);
+            }
+        }
+        self.validate_proof(validators, instance_id)
+    }
+
+    /// Validates the evidence like `validate`, but without enforcing the protocol's limit on the
+    /// number of units it may contain. This is useful to verify evidence outside of consensus.
+    pub(crate) fn validate_proof(
+        &self,
+        validators: &Validators<C::ValidatorId>,
+        instance_id: &C::InstanceId,
     ) -> Result<(), EvidenceError> {
         match self {
             Evidence::Equivocation(unit1, unit2) => {
@@ -99,9 +114,6 @@ impl<C: Context> Evidence<C> {
                 unit2,
                 swimlane2,
             } => {
-                if swimlane2.len() as u64 > params.endorsement_evidence_limit() {
-                    return Err(EvidenceError::EndorsementTooManyUnits);
-                }
                 let v_id = validators
                     .id(endorsement1.validator_idx())
                     .ok_or(EvidenceError::UnknownPerpetrator)?;
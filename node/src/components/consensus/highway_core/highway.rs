@@ -17,6 +17,7 @@ use crate::{
         highway_core::{
             active_validator::{ActiveValidator, Effect},
             evidence::EvidenceError,
+            slashing_protection::SlashingProtection,
             state::{Fault, State, UnitError, Weight},
             validators::{Validator, Validators},
         },
@@ -196,6 +197,7 @@ impl<C: Context> Highway<C> {
         secret: C::ValidatorSecret,
         current_time: Timestamp,
         unit_hash_file: Option<PathBuf>,
+        slashing_protection: Option<SlashingProtection<C>>,
         target_ftt: Weight,
     ) -> Vec<Effect<C>> {
         if self.active_validator.is_some() {
@@ -217,6 +219,7 @@ impl<C: Context> Highway<C> {
             start_time,
             &self.state,
             unit_hash_file,
+            slashing_protection,
             target_ftt,
             self.instance_id,
         );
@@ -428,6 +431,20 @@ impl<C: Context> Highway<C> {
         })
     }
 
+    /// Passes a requested signature to the active validator, if any.
+    pub(crate) fn on_signature(
+        &mut self,
+        hash: &C::Hash,
+        maybe_signature: Option<C::Signature>,
+        timestamp: Timestamp,
+    ) -> Vec<Effect<C>> {
+        self.map_active_validator(|av, _| av.on_signature(hash, maybe_signature), timestamp)
+            .unwrap_or_else(|| {
+                debug!(%hash, "Ignoring signature: only an observer node.");
+                vec![]
+            })
+    }
+
     pub(crate) fn propose(
         &mut self,
         value: C::ConsensusValue,
@@ -474,6 +491,14 @@ impl<C: Context> Highway<C> {
             .map(|(_, v_id)| v_id)
     }
 
+    /// Returns the direct evidence against the given validator, if we have any.
+    pub(crate) fn evidence(&self, vid: &C::ValidatorId) -> Option<&Evidence<C>> {
+        match self.state.maybe_fault(self.validators.get_index(vid)?)? {
+            Fault::Direct(evidence) => Some(evidence),
+            Fault::Banned | Fault::Indirect => None,
+        }
+    }
+
     pub(crate) fn state(&self) -> &State<C> {
         &self.state
     }
@@ -552,7 +577,9 @@ impl<C: Context> Highway<C> {
                     result.extend(self.add_valid_vertex(vv.clone(), timestamp))
                 }
                 Effect::WeAreFaulty(_) => self.deactivate_validator(),
-                Effect::ScheduleTimer(_) | Effect::RequestNewBlock(_) => (),
+                Effect::ScheduleTimer(_)
+                | Effect::RequestNewBlock(_)
+                | Effect::RequestSignature(_) => (),
             }
         }
         result.extend(effects);
@@ -710,6 +737,11 @@ impl<C: Context> Highway<C> {
             .map(|av| av.next_round_length())
     }
 
+    /// Returns our own next round exponent, if we are an active validator.
+    pub(crate) fn next_round_exp(&self) -> Option<u8> {
+        self.active_validator.as_ref().map(|av| av.next_round_exp())
+    }
+
     /// Logs a message if this is a block and any previous blocks were skipped.
     fn log_if_missing_proposal(&self, unit_hash: &C::Hash) {
         let state = &self.state;
@@ -783,7 +815,6 @@ pub(crate) mod tests {
                 state::{tests::*, Panorama, State},
                 validators::Validators,
             },
-            traits::ValidatorSecret,
         },
         types::Timestamp,
     };
@@ -1046,7 +1077,7 @@ pub(crate) mod tests {
         };
 
         let _effects =
-            highway.activate_validator(ALICE.0, ALICE_SEC.clone(), now, None, target_ftt);
+            highway.activate_validator(ALICE.0, ALICE_SEC.clone(), now, None, None, target_ftt);
 
         let ping = Vertex::Ping(Ping::new(ALICE, now, TEST_INSTANCE_ID, &ALICE_SEC));
         assert!(!highway.is_doppelganger_vertex(&ping));
@@ -12,11 +12,14 @@ use crate::{
             state::{self, Panorama},
             validators::{ValidatorIndex, Validators},
         },
-        traits::{Context, ValidatorSecret},
+        traits::Context,
     },
     types::Timestamp,
 };
 
+#[cfg(test)]
+use crate::components::consensus::traits::ValidatorSecret;
+
 /// A dependency of a `Vertex` that can be satisfied by one or more other vertices.
 #[derive(Clone, DataSize, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(bound(
@@ -147,11 +150,23 @@ where
 }
 
 impl<C: Context> SignedWireUnit<C> {
+    /// Signs the unit with a secret that can produce signatures synchronously.
+    #[cfg(test)]
     pub(crate) fn new(
         hashed_wire_unit: HashedWireUnit<C>,
         secret_key: &C::ValidatorSecret,
     ) -> Self {
-        let signature = secret_key.sign(&hashed_wire_unit.hash);
+        let signature = secret_key
+            .try_sign(&hashed_wire_unit.hash)
+            .expect("test secret should sign synchronously");
+        Self::with_signature(hashed_wire_unit, signature)
+    }
+
+    /// Creates a signed unit from a signature over its hash.
+    pub(crate) fn with_signature(
+        hashed_wire_unit: HashedWireUnit<C>,
+        signature: C::Signature,
+    ) -> Self {
         SignedWireUnit {
             hashed_wire_unit,
             signature,
@@ -336,14 +351,27 @@ where
 }
 
 impl<C: Context> Ping<C> {
-    /// Creates a new signed ping.
+    /// Creates a new ping signed with a secret that can produce signatures synchronously.
+    #[cfg(test)]
     pub(crate) fn new(
         creator: ValidatorIndex,
         timestamp: Timestamp,
         instance_id: C::InstanceId,
         sk: &C::ValidatorSecret,
     ) -> Self {
-        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id));
+        let signature = sk
+            .try_sign(&Self::hash(creator, timestamp, instance_id))
+            .expect("test secret should sign synchronously");
+        Self::with_signature(creator, timestamp, instance_id, signature)
+    }
+
+    /// Creates a ping from a signature over `Ping::hash(creator, timestamp, instance_id)`.
+    pub(crate) fn with_signature(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+        signature: C::Signature,
+    ) -> Self {
         Ping {
             creator,
             timestamp,
@@ -386,7 +414,11 @@ impl<C: Context> Ping<C> {
     }
 
     /// Computes the hash of a ping, i.e. of the creator and timestamp.
-    fn hash(creator: ValidatorIndex, timestamp: Timestamp, instance_id: C::InstanceId) -> C::Hash {
+    pub(crate) fn hash(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> C::Hash {
         let bytes = bincode::serialize(&(creator, timestamp, instance_id)).expect("serialize Ping");
         <C as Context>::hash(&bytes)
     }
@@ -115,6 +115,9 @@ impl From<Effect<TestContext>> for HighwayMessage {
             Effect::ScheduleTimer(t) => HighwayMessage::Timer(t),
             Effect::RequestNewBlock(block_context) => HighwayMessage::RequestBlock(block_context),
             Effect::WeAreFaulty(fault) => HighwayMessage::WeAreFaulty(Box::new(fault)),
+            Effect::RequestSignature(hash) => {
+                unreachable!("test secrets sign synchronously; requested {:?}", hash)
+            }
         }
     }
 }
@@ -933,7 +936,8 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
                 let v_sec = secrets.remove(&vid).expect("Secret key should exist.");
 
                 let mut highway = Highway::new(instance_id, validators.clone(), params.clone());
-                let effects = highway.activate_validator(vid, v_sec, start_time, None, Weight(ftt));
+                let effects =
+                    highway.activate_validator(vid, v_sec, start_time, None, None, Weight(ftt));
 
                 let finality_detector = FinalityDetector::new(Weight(ftt));
 
@@ -1030,8 +1034,8 @@ impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn try_sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
@@ -0,0 +1,706 @@
+//! A persistent record of the units signed by our validator, to prevent double-signing.
+//!
+//! Every unit is recorded _before_ it is signed, together with its era, sequence number and the
+//! hash of its panorama. A unit is refused if we already signed a different unit with the same
+//! or a higher sequence number in the same protocol instance, since that would be an
+//! equivocation.
+//!
+//! Highway endorsements and the round-robin protocol's echoes and votes are recorded the same
+//! way. Such a message is refused if we already signed a conflicting one in the same protocol
+//! instance: an endorsement of a different unit with the same creator and sequence number, or a
+//! different echo or vote in the same round.
+//!
+//! The store is a flat file with one JSON record per line, shared by all eras. Records are only
+//! ever appended, except when obsolete eras are pruned. The records can be exported to and
+//! imported from an interchange file, so that a validator key can be moved to a different machine
+//! without risking an equivocation.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    iter,
+    path::{Path, PathBuf},
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_types::EraId;
+
+use super::{state::Panorama, validators::ValidatorIndex};
+use crate::components::consensus::{protocols::round_robin::RoundId, traits::Context};
+
+/// A unit that was signed by our validator.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) struct SignedUnitRecord<C>
+where
+    C: Context,
+{
+    /// The era in which the unit was created.
+    pub(crate) era_id: EraId,
+    /// The ID of the protocol instance the unit belongs to.
+    pub(crate) instance_id: C::InstanceId,
+    /// The unit's sequence number.
+    pub(crate) seq_number: u64,
+    /// The hash of the unit's panorama.
+    pub(crate) panorama_hash: C::Hash,
+    /// The hash of the unit itself.
+    pub(crate) unit_hash: C::Hash,
+}
+
+impl<C: Context> SignedUnitRecord<C> {
+    /// Returns whether the two records are different units with the same sequence number.
+    fn conflicts_with(&self, other: &SignedUnitRecord<C>) -> bool {
+        self.instance_id == other.instance_id
+            && self.seq_number == other.seq_number
+            && self.unit_hash != other.unit_hash
+    }
+}
+
+/// An endorsement, echo or vote that was signed by our validator.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) struct SignedMessageRecord<C>
+where
+    C: Context,
+{
+    /// The era in which the message was created.
+    pub(crate) era_id: EraId,
+    /// The ID of the protocol instance the message belongs to.
+    pub(crate) instance_id: C::InstanceId,
+    /// The message itself.
+    pub(crate) message: MessageRecord<C>,
+}
+
+impl<C: Context> SignedMessageRecord<C> {
+    /// Returns whether the two records are conflicting messages in the same instance.
+    fn conflicts_with(&self, other: &SignedMessageRecord<C>) -> bool {
+        self.instance_id == other.instance_id && self.message.conflicts_with(&other.message)
+    }
+}
+
+/// The parts of a signed endorsement, echo or vote that determine what it conflicts with.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum MessageRecord<C>
+where
+    C: Context,
+{
+    /// A Highway endorsement of a unit.
+    Endorsement {
+        /// The creator of the endorsed unit.
+        creator: ValidatorIndex,
+        /// The endorsed unit's sequence number.
+        seq_number: u64,
+        /// The hash of the endorsed unit.
+        unit_hash: C::Hash,
+    },
+    /// A round-robin echo of the proposal with the given hash.
+    Echo {
+        round_id: RoundId,
+        proposal_hash: C::Hash,
+    },
+    /// A round-robin vote to accept (`true`) or skip (`false`) the round.
+    Vote { round_id: RoundId, vote: bool },
+}
+
+impl<C: Context> MessageRecord<C> {
+    /// Returns whether an honest validator can't sign both `self` and `other`.
+    ///
+    /// Endorsing two units by the same creator with the same sequence number is endorsing an
+    /// equivocation. Echoes and votes conflict with different ones in the same round.
+    fn conflicts_with(&self, other: &MessageRecord<C>) -> bool {
+        match (self, other) {
+            (
+                MessageRecord::Endorsement {
+                    creator: creator0,
+                    seq_number: seq_number0,
+                    unit_hash: unit_hash0,
+                },
+                MessageRecord::Endorsement {
+                    creator: creator1,
+                    seq_number: seq_number1,
+                    unit_hash: unit_hash1,
+                },
+            ) => creator0 == creator1 && seq_number0 == seq_number1 && unit_hash0 != unit_hash1,
+            (
+                MessageRecord::Echo {
+                    round_id: round_id0,
+                    proposal_hash: proposal_hash0,
+                },
+                MessageRecord::Echo {
+                    round_id: round_id1,
+                    proposal_hash: proposal_hash1,
+                },
+            ) => round_id0 == round_id1 && proposal_hash0 != proposal_hash1,
+            (
+                MessageRecord::Vote {
+                    round_id: round_id0,
+                    vote: vote0,
+                },
+                MessageRecord::Vote {
+                    round_id: round_id1,
+                    vote: vote1,
+                },
+            ) => round_id0 == round_id1 && vote0 != vote1,
+            (MessageRecord::Endorsement { .. }, _)
+            | (MessageRecord::Echo { .. }, _)
+            | (MessageRecord::Vote { .. }, _) => false,
+        }
+    }
+}
+
+impl<C: Context> Display for MessageRecord<C> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MessageRecord::Endorsement {
+                creator,
+                seq_number,
+                unit_hash,
+            } => write!(
+                f,
+                "endorsement of unit {} with sequence number {} by validator {}",
+                unit_hash, seq_number, creator.0
+            ),
+            MessageRecord::Echo {
+                round_id,
+                proposal_hash,
+            } => write!(
+                f,
+                "echo of proposal {} in round {}",
+                proposal_hash, round_id
+            ),
+            MessageRecord::Vote { round_id, vote } => {
+                write!(f, "vote {} in round {}", vote, round_id)
+            }
+        }
+    }
+}
+
+/// A line in the store file: either a unit or a message record.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(
+    untagged,
+    bound(
+        serialize = "C::Hash: Serialize",
+        deserialize = "C::Hash: Deserialize<'de>",
+    )
+)]
+enum Record<C>
+where
+    C: Context,
+{
+    Unit(SignedUnitRecord<C>),
+    Message(SignedMessageRecord<C>),
+}
+
+impl<C: Context> Record<C> {
+    fn era_id(&self) -> EraId {
+        match self {
+            Record::Unit(record) => record.era_id,
+            Record::Message(record) => record.era_id,
+        }
+    }
+}
+
+/// An error in the slashing protection store.
+#[derive(Debug, Error)]
+pub(crate) enum SlashingProtectionError {
+    /// Reading or writing the store failed.
+    #[error("slashing protection store I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A record could not be (de)serialized.
+    #[error("invalid slashing protection record: {0}")]
+    Json(#[from] serde_json::Error),
+    /// We already signed a unit that conflicts with the one we are about to sign.
+    #[error(
+        "refusing to sign unit {unit_hash} with sequence number {seq_number}: conflicts with \
+         signed unit {signed_hash} with sequence number {signed_seq_number}"
+    )]
+    Conflict {
+        seq_number: u64,
+        unit_hash: String,
+        signed_seq_number: u64,
+        signed_hash: String,
+    },
+    /// We already signed an endorsement, echo or vote that conflicts with the one we are about to
+    /// sign.
+    #[error("refusing to sign {message}: conflicts with signed {signed}")]
+    ConflictingMessage { message: String, signed: String },
+    /// The interchange file belongs to a different validator.
+    #[error("interchange file is for validator {found}, expected {expected}")]
+    WrongValidator { expected: String, found: String },
+}
+
+impl SlashingProtectionError {
+    fn conflict<C: Context>(
+        record: &SignedUnitRecord<C>,
+        signed: &SignedUnitRecord<C>,
+    ) -> SlashingProtectionError {
+        SlashingProtectionError::Conflict {
+            seq_number: record.seq_number,
+            unit_hash: record.unit_hash.to_string(),
+            signed_seq_number: signed.seq_number,
+            signed_hash: signed.unit_hash.to_string(),
+        }
+    }
+
+    fn conflicting_message<C: Context>(
+        message: &MessageRecord<C>,
+        signed: &MessageRecord<C>,
+    ) -> SlashingProtectionError {
+        SlashingProtectionError::ConflictingMessage {
+            message: message.to_string(),
+            signed: signed.to_string(),
+        }
+    }
+}
+
+/// The slashing protection store, restricted to a single era's protocol instance.
+#[derive(DataSize, Debug)]
+pub(crate) struct SlashingProtection<C>
+where
+    C: Context,
+{
+    /// The path to the store file, shared by all eras.
+    path: PathBuf,
+    era_id: EraId,
+    instance_id: C::InstanceId,
+    /// The units we signed in this instance, by sequence number.
+    signed_units: BTreeMap<u64, SignedUnitRecord<C>>,
+    /// The endorsements, echoes and votes we signed in this instance.
+    signed_messages: BTreeSet<MessageRecord<C>>,
+}
+
+impl<C: Context> SlashingProtection<C> {
+    /// Opens the store at `path` for the given era, loading all units and messages that were
+    /// signed in it.
+    pub(crate) fn open(
+        path: PathBuf,
+        era_id: EraId,
+        instance_id: C::InstanceId,
+    ) -> Result<Self, SlashingProtectionError> {
+        let (units, messages) = split_records(read_records::<C>(&path)?);
+        let signed_units = units
+            .into_iter()
+            .filter(|record| record.instance_id == instance_id)
+            .map(|record| (record.seq_number, record))
+            .collect();
+        let signed_messages = messages
+            .into_iter()
+            .filter(|record| record.instance_id == instance_id)
+            .map(|record| record.message)
+            .collect();
+        Ok(SlashingProtection {
+            path,
+            era_id,
+            instance_id,
+            signed_units,
+            signed_messages,
+        })
+    }
+
+    /// Returns the hash of the latest unit we signed in this era, if any.
+    pub(crate) fn last_unit_hash(&self) -> Option<C::Hash> {
+        self.signed_units
+            .values()
+            .next_back()
+            .map(|record| record.unit_hash)
+    }
+
+    /// Returns whether we signed an echo in the given round-robin round.
+    pub(crate) fn has_echoed(&self, round_id: RoundId) -> bool {
+        self.signed_messages.iter().any(|message| match message {
+            MessageRecord::Echo {
+                round_id: echo_round_id,
+                ..
+            } => *echo_round_id == round_id,
+            MessageRecord::Endorsement { .. } | MessageRecord::Vote { .. } => false,
+        })
+    }
+
+    /// Persists the unit with the given sequence number, panorama and hash, so that it can be
+    /// signed.
+    ///
+    /// Returns an error and doesn't record anything if signing it would be an equivocation, or if
+    /// the record could not be persisted.
+    pub(crate) fn record_unit(
+        &mut self,
+        seq_number: u64,
+        panorama: &Panorama<C>,
+        unit_hash: C::Hash,
+    ) -> Result<(), SlashingProtectionError> {
+        let panorama_bytes = bincode::serialize(panorama).expect("serialize Panorama");
+        let record = SignedUnitRecord {
+            era_id: self.era_id,
+            instance_id: self.instance_id,
+            seq_number,
+            panorama_hash: <C as Context>::hash(&panorama_bytes),
+            unit_hash,
+        };
+        // A unit must cite our previous one, so it must have a greater sequence number than all
+        // units we signed before, unless it is one of them.
+        if let Some((_, signed)) = self.signed_units.range(seq_number..).next() {
+            if signed.seq_number == seq_number && signed.unit_hash == unit_hash {
+                return Ok(()); // We already signed exactly this unit.
+            }
+            return Err(SlashingProtectionError::conflict(&record, signed));
+        }
+        append_records(&self.path, iter::once(&Record::Unit(record.clone())))?;
+        let _ = self.signed_units.insert(seq_number, record);
+        Ok(())
+    }
+
+    /// Persists the endorsement, echo or vote, so that it can be signed.
+    ///
+    /// Returns an error and doesn't record anything if it conflicts with a message we already
+    /// signed, or if the record could not be persisted.
+    pub(crate) fn record_message(
+        &mut self,
+        message: MessageRecord<C>,
+    ) -> Result<(), SlashingProtectionError> {
+        if self.signed_messages.contains(&message) {
+            return Ok(()); // We already signed exactly this message.
+        }
+        if let Some(signed) = self
+            .signed_messages
+            .iter()
+            .find(|signed| message.conflicts_with(signed))
+        {
+            return Err(SlashingProtectionError::conflicting_message(
+                &message, signed,
+            ));
+        }
+        let record = SignedMessageRecord {
+            era_id: self.era_id,
+            instance_id: self.instance_id,
+            message,
+        };
+        append_records(&self.path, iter::once(&Record::Message(record.clone())))?;
+        let _ = self.signed_messages.insert(record.message);
+        Ok(())
+    }
+}
+
+/// Reads all records from the store file. A missing file is treated as an empty store.
+fn read_records<C: Context>(path: &Path) -> Result<Vec<Record<C>>, SlashingProtectionError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            records.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(records)
+}
+
+/// Splits the records into unit and message records.
+fn split_records<C: Context>(
+    records: Vec<Record<C>>,
+) -> (Vec<SignedUnitRecord<C>>, Vec<SignedMessageRecord<C>>) {
+    let mut units = Vec::new();
+    let mut messages = Vec::new();
+    for record in records {
+        match record {
+            Record::Unit(record) => units.push(record),
+            Record::Message(record) => messages.push(record),
+        }
+    }
+    (units, messages)
+}
+
+/// Appends the records to the store file, creating it and its parent directories as necessary,
+/// and waits until they are written to disk.
+fn append_records<'a, C, I>(path: &Path, records: I) -> Result<(), SlashingProtectionError>
+where
+    C: Context + 'a,
+    I: IntoIterator<Item = &'a Record<C>>,
+{
+    if let Some(parent_directory) = path.parent() {
+        fs::create_dir_all(parent_directory)?;
+    }
+    let mut bytes = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut bytes, record)?;
+        bytes.push(b'\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&bytes)?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// Removes all records from eras before `min_era_id` from the store file.
+///
+/// Only call this for eras in which our validator cannot sign any more units.
+pub(crate) fn prune<C: Context>(
+    path: &Path,
+    min_era_id: EraId,
+) -> Result<(), SlashingProtectionError> {
+    let records = read_records::<C>(path)?;
+    if records.iter().all(|record| record.era_id() >= min_era_id) {
+        return Ok(());
+    }
+    let tmp_path = path.with_extension("tmp");
+    let _ = fs::remove_file(&tmp_path);
+    append_records(
+        &tmp_path,
+        records
+            .iter()
+            .filter(|record| record.era_id() >= min_era_id),
+    )?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// The content of a slashing protection export file.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) struct SlashingProtectionInterchange<C>
+where
+    C: Context,
+{
+    /// The validator who signed the units, in hex format.
+    pub(crate) validator: String,
+    /// All units signed by the validator that are still recorded in the store.
+    pub(crate) signed_units: Vec<SignedUnitRecord<C>>,
+    /// All endorsements, echoes and votes signed by the validator that are still recorded in the
+    /// store.
+    #[serde(default)]
+    pub(crate) signed_messages: Vec<SignedMessageRecord<C>>,
+}
+
+impl<C: Context> SlashingProtectionInterchange<C> {
+    /// Exports all records from the store file at `path`.
+    pub(crate) fn export(path: &Path, validator: String) -> Result<Self, SlashingProtectionError> {
+        let (signed_units, signed_messages) = split_records(read_records(path)?);
+        Ok(SlashingProtectionInterchange {
+            validator,
+            signed_units,
+            signed_messages,
+        })
+    }
+
+    /// Adds the exported records to the store file at `path`, for the given validator.
+    ///
+    /// Fails without modifying the store if any of the records conflicts with a unit or message
+    /// that is already recorded. Returns the number of newly added records.
+    pub(crate) fn import(
+        &self,
+        path: &Path,
+        validator: &str,
+    ) -> Result<usize, SlashingProtectionError> {
+        if self.validator != validator {
+            return Err(SlashingProtectionError::WrongValidator {
+                expected: validator.to_string(),
+                found: self.validator.clone(),
+            });
+        }
+        let (existing_units, existing_messages) = split_records(read_records::<C>(path)?);
+        let all_units = || existing_units.iter().chain(&self.signed_units);
+        for record in &self.signed_units {
+            if let Some(signed) = all_units().find(|signed| record.conflicts_with(signed)) {
+                return Err(SlashingProtectionError::conflict(record, signed));
+            }
+        }
+        let all_messages = || existing_messages.iter().chain(&self.signed_messages);
+        for record in &self.signed_messages {
+            if let Some(signed) = all_messages().find(|signed| record.conflicts_with(signed)) {
+                return Err(SlashingProtectionError::conflicting_message(
+                    &record.message,
+                    &signed.message,
+                ));
+            }
+        }
+        let mut known_units: BTreeSet<(C::InstanceId, u64)> = existing_units
+            .iter()
+            .map(|record| (record.instance_id, record.seq_number))
+            .collect();
+        let mut known_messages: BTreeSet<&SignedMessageRecord<C>> =
+            existing_messages.iter().collect();
+        let new_records: Vec<_> = self
+            .signed_units
+            .iter()
+            .filter(|record| known_units.insert((record.instance_id, record.seq_number)))
+            .cloned()
+            .map(Record::Unit)
+            .chain(
+                self.signed_messages
+                    .iter()
+                    .filter(|record| known_messages.insert(*record))
+                    .cloned()
+                    .map(Record::Message),
+            )
+            .collect();
+        append_records(path, &new_records)?;
+        Ok(new_records.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::components::consensus::highway_core::highway_testing::{
+        TestContext, TEST_INSTANCE_ID,
+    };
+
+    const OTHER_INSTANCE_ID: u64 = TEST_INSTANCE_ID + 1;
+
+    fn hash(n: u8) -> <TestContext as Context>::Hash {
+        TestContext::hash(&[n])
+    }
+
+    #[test]
+    fn refuses_conflicting_units() -> Result<(), SlashingProtectionError> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("slashing_protection.jsonl");
+        let era_id = EraId::from(1);
+        let panorama = Panorama::new(2);
+
+        let mut store =
+            SlashingProtection::<TestContext>::open(path.clone(), era_id, TEST_INSTANCE_ID)?;
+        store.record_unit(0, &panorama, hash(0))?;
+        store.record_unit(1, &panorama, hash(1))?;
+        // Signing the same unit again is fine.
+        store.record_unit(1, &panorama, hash(1))?;
+        // A different unit with the same or a lower sequence number is an equivocation.
+        assert!(store.record_unit(1, &panorama, hash(2)).is_err());
+        assert!(store.record_unit(0, &panorama, hash(2)).is_err());
+        assert_eq!(Some(hash(1)), store.last_unit_hash());
+
+        // The records survive a restart, and other eras are not affected.
+        let mut store =
+            SlashingProtection::<TestContext>::open(path.clone(), era_id, TEST_INSTANCE_ID)?;
+        assert_eq!(Some(hash(1)), store.last_unit_hash());
+        assert!(store.record_unit(1, &panorama, hash(2)).is_err());
+        store.record_unit(2, &panorama, hash(2))?;
+        let mut other =
+            SlashingProtection::<TestContext>::open(path, era_id.successor(), OTHER_INSTANCE_ID)?;
+        assert_eq!(None, other.last_unit_hash());
+        other.record_unit(0, &panorama, hash(3))?;
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_conflicting_messages() -> Result<(), SlashingProtectionError> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("slashing_protection.jsonl");
+        let era_id = EraId::from(1);
+        let endorsement = |seq_number, n| MessageRecord::Endorsement {
+            creator: ValidatorIndex(1),
+            seq_number,
+            unit_hash: hash(n),
+        };
+        let echo = |round_id, n| MessageRecord::Echo {
+            round_id,
+            proposal_hash: hash(n),
+        };
+        let vote = |round_id, vote| MessageRecord::Vote { round_id, vote };
+
+        let mut store =
+            SlashingProtection::<TestContext>::open(path.clone(), era_id, TEST_INSTANCE_ID)?;
+        store.record_message(endorsement(0, 0))?;
+        store.record_message(echo(0, 1))?;
+        store.record_message(vote(0, true))?;
+        // Signing the same messages again is fine, and so are different rounds and units.
+        store.record_message(echo(0, 1))?;
+        store.record_message(vote(0, true))?;
+        store.record_message(endorsement(1, 2))?;
+        store.record_message(echo(1, 2))?;
+        store.record_message(vote(1, false))?;
+        // Conflicting messages are refused.
+        assert!(store.record_message(endorsement(0, 3)).is_err());
+        assert!(store.record_message(echo(0, 3)).is_err());
+        assert!(store.record_message(vote(0, false)).is_err());
+
+        // The records survive a restart, and other eras are not affected.
+        let mut store =
+            SlashingProtection::<TestContext>::open(path.clone(), era_id, TEST_INSTANCE_ID)?;
+        assert!(store.record_message(echo(0, 3)).is_err());
+        assert!(store.record_message(vote(1, true)).is_err());
+        let mut other =
+            SlashingProtection::<TestContext>::open(path, era_id.successor(), OTHER_INSTANCE_ID)?;
+        other.record_message(echo(0, 3))?;
+        other.record_message(vote(1, true))?;
+        Ok(())
+    }
+
+    #[test]
+    fn export_import_and_prune() -> Result<(), SlashingProtectionError> {
+        let dir = tempdir().unwrap();
+        let old_path = dir.path().join("old.jsonl");
+        let new_path = dir.path().join("new.jsonl");
+        let (era1, era2) = (EraId::from(1), EraId::from(2));
+        let panorama = Panorama::new(2);
+
+        let mut store =
+            SlashingProtection::<TestContext>::open(old_path.clone(), era1, TEST_INSTANCE_ID)?;
+        store.record_unit(0, &panorama, hash(0))?;
+        let mut store =
+            SlashingProtection::<TestContext>::open(old_path.clone(), era2, OTHER_INSTANCE_ID)?;
+        store.record_unit(0, &panorama, hash(1))?;
+        store.record_unit(1, &panorama, hash(2))?;
+        store.record_message(MessageRecord::Vote {
+            round_id: 0,
+            vote: true,
+        })?;
+
+        let interchange =
+            SlashingProtectionInterchange::<TestContext>::export(&old_path, "alice".to_string())?;
+        assert_eq!(3, interchange.signed_units.len());
+        assert_eq!(1, interchange.signed_messages.len());
+        assert!(interchange.import(&new_path, "bob").is_err());
+        assert_eq!(4, interchange.import(&new_path, "alice")?);
+        // Importing again doesn't duplicate any records.
+        assert_eq!(0, interchange.import(&new_path, "alice")?);
+
+        // The new machine refuses to equivocate.
+        let mut store =
+            SlashingProtection::<TestContext>::open(new_path.clone(), era2, OTHER_INSTANCE_ID)?;
+        assert_eq!(Some(hash(2)), store.last_unit_hash());
+        assert!(store.record_unit(1, &panorama, hash(3)).is_err());
+        let conflicting_vote = MessageRecord::Vote {
+            round_id: 0,
+            vote: false,
+        };
+        assert!(store.record_message(conflicting_vote.clone()).is_err());
+
+        // An interchange file with a conflicting unit or message is rejected as a whole.
+        let mut conflicting = interchange.clone();
+        conflicting.signed_units[2].unit_hash = hash(3);
+        assert!(conflicting.import(&new_path, "alice").is_err());
+        let mut conflicting = interchange.clone();
+        conflicting.signed_messages[0].message = conflicting_vote;
+        assert!(conflicting.import(&new_path, "alice").is_err());
+
+        prune::<TestContext>(&new_path, era2)?;
+        let exported =
+            SlashingProtectionInterchange::<TestContext>::export(&new_path, "alice".to_string())?;
+        assert_eq!(interchange.signed_units[1..], exported.signed_units[..]);
+        assert_eq!(interchange.signed_messages, exported.signed_messages);
+        Ok(())
+    }
+}
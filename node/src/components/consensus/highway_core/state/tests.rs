@@ -44,12 +44,18 @@ pub(crate) struct TestContext;
 #[derive(Clone, DataSize, Debug, Eq, PartialEq)]
 pub(crate) struct TestSecret(pub(crate) u32);
 
+impl TestSecret {
+    pub(crate) fn sign(&self, data: &u64) -> u64 {
+        data + u64::from(self.0)
+    }
+}
+
 impl ValidatorSecret for TestSecret {
     type Hash = u64;
     type Signature = u64;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        data + u64::from(self.0)
+    fn try_sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(self.sign(data))
     }
 }
 
@@ -244,6 +244,13 @@ impl<I: NodeIdT, C: Context + 'static> Synchronizer<I, C> {
         self.vertices_no_deps.len()
     }
 
+    /// Returns the missing dependencies, together with the number of vertices waiting for each.
+    pub(crate) fn pending_dependencies(&self) -> impl Iterator<Item = (&Dependency<C>, u64)> {
+        self.vertices_awaiting_deps
+            .iter()
+            .map(|(dep, pv)| (dep, pv.len()))
+    }
+
     pub(crate) fn log_len(&self) {
         debug!(
             era_id = ?self.instance_id,
@@ -1 +1,17 @@
 pub(crate) mod highway;
+pub(crate) mod round_robin;
+
+use super::{era_supervisor::ConsensusConstructor, traits::NodeIdT};
+use crate::types::chainspec::ConsensusProtocolName;
+
+use self::{highway::HighwayProtocol, round_robin::RoundRobinProtocol};
+
+/// Returns the constructor for new instances of the named consensus protocol.
+pub(crate) fn consensus_constructor<I: NodeIdT>(
+    name: ConsensusProtocolName,
+) -> Box<ConsensusConstructor<I>> {
+    match name {
+        ConsensusProtocolName::Highway => Box::new(HighwayProtocol::new_boxed),
+        ConsensusProtocolName::RoundRobin => Box::new(RoundRobinProtocol::new_boxed),
+    }
+}
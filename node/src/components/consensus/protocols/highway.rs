@@ -1,6 +1,7 @@
 pub(crate) mod config;
 mod participation;
 mod round_success_meter;
+mod snapshot;
 #[cfg(test)]
 mod tests;
 
@@ -34,6 +35,7 @@ use crate::{
                 Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, ValidVertex,
                 Vertex, VertexError,
             },
+            slashing_protection::SlashingProtection,
             state::{self, IndexObservation, IndexPanorama, Observation, Panorama},
             synchronizer::Synchronizer,
             validators::{ValidatorIndex, Validators},
@@ -45,6 +47,7 @@ use crate::{
 };
 
 use self::round_success_meter::RoundSuccessMeter;
+pub(crate) use self::snapshot::HighwaySnapshot;
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
 /// even if eras are longer than this.
@@ -261,6 +264,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             AvEffect::RequestNewBlock(block_context) => {
                 vec![ProtocolOutcome::CreateNewBlock(block_context)]
             }
+            AvEffect::RequestSignature(hash) => vec![ProtocolOutcome::CreateSignature(hash)],
             AvEffect::WeAreFaulty(fault) => {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
@@ -956,6 +960,16 @@ where
         self.process_av_effects(effects, now)
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        maybe_signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let effects = self.highway.on_signature(&hash, maybe_signature, now);
+        self.process_av_effects(effects, now)
+    }
+
     fn resolve_validity(
         &mut self,
         proposed_block: ProposedBlock<C>,
@@ -1001,11 +1015,17 @@ where
         secret: C::ValidatorSecret,
         now: Timestamp,
         unit_hash_file: Option<PathBuf>,
+        slashing_protection: Option<SlashingProtection<C>>,
     ) -> ProtocolOutcomes<I, C> {
         let ftt = self.finality_detector.fault_tolerance_threshold();
-        let av_effects = self
-            .highway
-            .activate_validator(our_id, secret, now, unit_hash_file, ftt);
+        let av_effects = self.highway.activate_validator(
+            our_id,
+            secret,
+            now,
+            unit_hash_file,
+            slashing_protection,
+            ftt,
+        );
         self.process_av_effects(av_effects, now)
     }
 
@@ -1050,6 +1070,12 @@ where
             .collect()
     }
 
+    fn serialized_evidence(&self, vid: &C::ValidatorId) -> Option<Vec<u8>> {
+        self.highway
+            .evidence(vid)
+            .map(|evidence| bincode::serialize(evidence).expect("should serialize evidence"))
+    }
+
     /// Sets the pause status: While paused we don't create any new units, just pings.
     fn set_paused(&mut self, paused: bool) {
         self.highway.set_paused(paused);
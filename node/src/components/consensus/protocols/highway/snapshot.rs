@@ -0,0 +1,220 @@
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey};
+
+use super::HighwayProtocol;
+use crate::{
+    components::consensus::{
+        cl_context::ClContext,
+        highway_core::{
+            highway::{Dependency, Highway},
+            state::Observation,
+        },
+        traits::NodeIdT,
+    },
+    crypto::AsymmetricKeyExt,
+    rpcs::docs::DocExample,
+    types::Timestamp,
+};
+
+static HIGHWAY_SNAPSHOT: Lazy<HighwaySnapshot> = Lazy::new(|| {
+    let unit_hash = Digest::from([7; Digest::LENGTH]);
+    let block_hash = Digest::from([8; Digest::LENGTH]);
+    let public_key = PublicKey::doc_example().clone();
+    let unit = UnitSummary {
+        hash: unit_hash,
+        seq_number: 12,
+        timestamp: Timestamp::from(1_605_573_564_072),
+        round_exp: 14,
+        block: block_hash,
+    };
+    HighwaySnapshot {
+        era_id: EraId::new(1),
+        own_round_exp: Some(14),
+        last_finalized_block: Some(block_hash),
+        last_finalized_height: Some(10),
+        fault_tolerance_threshold: 1,
+        total_weight: 10,
+        panorama: vec![PanoramaEntry {
+            public_key: public_key.clone(),
+            weight: 10,
+            last_seen: Timestamp::from(1_605_573_564_072),
+            observation: ValidatorObservation::Correct(unit),
+        }],
+        pending_dependencies: vec![PendingDependency {
+            dependency: MissingVertex::Evidence(public_key),
+            waiting_vertices: 1,
+        }],
+    }
+});
+
+/// A read-only snapshot of the state of the Highway protocol instance of an era.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HighwaySnapshot {
+    /// The era of the protocol instance.
+    pub era_id: EraId,
+    /// Our own next round exponent, if we are an active validator in this era.
+    pub own_round_exp: Option<u8>,
+    /// The hash of the last block finalized by the finality detector, if any.
+    pub last_finalized_block: Option<Digest>,
+    /// The height of the last block finalized by the finality detector, if any.
+    pub last_finalized_height: Option<u64>,
+    /// The fault tolerance threshold used by the finality detector.
+    pub fault_tolerance_threshold: u64,
+    /// The total weight of all validators in the era.
+    pub total_weight: u64,
+    /// The latest observation of each validator, in the order of their indices.
+    pub panorama: Vec<PanoramaEntry>,
+    /// The dependencies the synchronizer is waiting for before it can add pending vertices.
+    pub pending_dependencies: Vec<PendingDependency>,
+}
+
+/// The latest observation of a single validator.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PanoramaEntry {
+    /// The validator's public key.
+    pub public_key: PublicKey,
+    /// The validator's weight.
+    pub weight: u64,
+    /// The time at which we last saw a unit or ping by the validator.
+    pub last_seen: Timestamp,
+    /// What we know about the validator's latest unit.
+    pub observation: ValidatorObservation,
+}
+
+/// What is known about a validator's latest unit.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum ValidatorObservation {
+    /// No unit by the validator has been seen yet.
+    None,
+    /// The validator's latest unit.
+    Correct(UnitSummary),
+    /// The validator has been seen equivocating.
+    Faulty,
+}
+
+/// A summary of a unit.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UnitSummary {
+    /// The hash of the unit.
+    pub hash: Digest,
+    /// The number of earlier units by the same validator.
+    pub seq_number: u64,
+    /// The time at which the unit was created.
+    pub timestamp: Timestamp,
+    /// The round exponent of the unit's creator at the time the unit was created.
+    pub round_exp: u8,
+    /// The hash of the block the unit votes for.
+    pub block: Digest,
+}
+
+/// A vertex the synchronizer is missing, together with the number of vertices waiting for it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PendingDependency {
+    /// The missing vertex.
+    pub dependency: MissingVertex,
+    /// The number of received vertices which cannot be added until the dependency is.
+    pub waiting_vertices: u64,
+}
+
+/// An identifier of a vertex the synchronizer is missing.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum MissingVertex {
+    /// A unit, by hash.
+    Unit(Digest),
+    /// Evidence against the given validator.
+    Evidence(PublicKey),
+    /// Endorsements of the unit with the given hash.
+    Endorsement(Digest),
+    /// A ping by the given validator.
+    Ping {
+        /// The validator who created the ping.
+        creator: PublicKey,
+        /// The ping's timestamp.
+        timestamp: Timestamp,
+    },
+}
+
+impl MissingVertex {
+    /// Converts a Highway dependency, or returns `None` if it refers to an unknown validator.
+    fn new(highway: &Highway<ClContext>, dependency: &Dependency<ClContext>) -> Option<Self> {
+        let public_key = |vidx| highway.validators().id(vidx).cloned();
+        Some(match dependency {
+            Dependency::Unit(hash) => MissingVertex::Unit(*hash),
+            Dependency::Evidence(vidx) => MissingVertex::Evidence(public_key(*vidx)?),
+            Dependency::Endorsement(hash) => MissingVertex::Endorsement(*hash),
+            Dependency::Ping(vidx, timestamp) => MissingVertex::Ping {
+                creator: public_key(*vidx)?,
+                timestamp: *timestamp,
+            },
+        })
+    }
+}
+
+impl DocExample for HighwaySnapshot {
+    fn doc_example() -> &'static Self {
+        &*HIGHWAY_SNAPSHOT
+    }
+}
+
+impl HighwaySnapshot {
+    /// Creates a snapshot of the given era's Highway protocol instance.
+    pub(crate) fn new<I: NodeIdT>(era_id: EraId, hw_proto: &HighwayProtocol<I, ClContext>) -> Self {
+        let highway = &hw_proto.highway;
+        let state = highway.state();
+        let panorama = highway
+            .validators()
+            .enumerate_ids()
+            .map(|(vidx, public_key)| {
+                let observation = match &state.panorama()[vidx] {
+                    Observation::None => ValidatorObservation::None,
+                    Observation::Faulty => ValidatorObservation::Faulty,
+                    Observation::Correct(hash) => {
+                        let unit = state.unit(hash);
+                        ValidatorObservation::Correct(UnitSummary {
+                            hash: *hash,
+                            seq_number: unit.seq_number,
+                            timestamp: unit.timestamp,
+                            round_exp: unit.round_exp,
+                            block: unit.block,
+                        })
+                    }
+                };
+                PanoramaEntry {
+                    public_key: public_key.clone(),
+                    weight: state.weight(vidx).0,
+                    last_seen: state.last_seen(vidx),
+                    observation,
+                }
+            })
+            .collect();
+        let pending_dependencies = hw_proto
+            .synchronizer
+            .pending_dependencies()
+            .filter_map(|(dependency, waiting_vertices)| {
+                Some(PendingDependency {
+                    dependency: MissingVertex::new(highway, dependency)?,
+                    waiting_vertices,
+                })
+            })
+            .collect();
+        let last_finalized_block = hw_proto.finality_detector.last_finalized().copied();
+        HighwaySnapshot {
+            era_id,
+            own_round_exp: highway.next_round_exp(),
+            last_finalized_block,
+            last_finalized_height: last_finalized_block.map(|hash| state.block(&hash).height),
+            fault_tolerance_threshold: hw_proto.finality_detector.fault_tolerance_threshold().0,
+            total_weight: state.total_weight().0,
+            panorama,
+            pending_dependencies,
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::BTreeSet, sync::Arc};
 use datasize::DataSize;
 use derive_more::Display;
 
-use casper_types::{PublicKey, U512};
+use casper_types::{EraId, PublicKey, U512};
 
 use crate::{
     components::consensus::{
@@ -18,8 +18,9 @@ use crate::{
             State,
         },
         protocols::highway::{
-            config::Config as HighwayConfig, HighwayMessage, ACTION_ID_VERTEX,
-            TIMER_ID_STANDSTILL_ALERT,
+            config::Config as HighwayConfig,
+            snapshot::{MissingVertex, PendingDependency, ValidatorObservation},
+            HighwayMessage, HighwaySnapshot, ACTION_ID_VERTEX, TIMER_ID_STANDSTILL_ALERT,
         },
         tests::utils::{new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
         traits::Context,
@@ -72,6 +73,7 @@ where
     let chainspec = new_test_chainspec(weights.clone());
     let config = Config {
         secret_key_path: Default::default(),
+        remote_signer: None,
         highway: HighwayConfig {
             pending_vertex_timeout: "1min".parse().unwrap(),
             standstill_timeout: Some(STANDSTILL_TIMEOUT.parse().unwrap()),
@@ -79,6 +81,7 @@ where
             max_execution_delay: 3,
             ..HighwayConfig::default()
         },
+        round_robin: Default::default(),
     };
     // Timestamp of the genesis era start and test start.
     let start_timestamp: Timestamp = 0.into();
@@ -277,7 +280,13 @@ fn detect_doppelganger() {
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
-    let _ = highway_protocol.activate_validator(ALICE_PUBLIC_KEY.clone(), alice_keypair, now, None);
+    let _ = highway_protocol.activate_validator(
+        ALICE_PUBLIC_KEY.clone(),
+        alice_keypair,
+        now,
+        None,
+        None,
+    );
     assert!(highway_protocol.is_active());
     let sender = NodeId(123);
     let msg = bincode::serialize(&highway_message).unwrap();
@@ -296,3 +305,74 @@ fn detect_doppelganger() {
     }
     panic!("failed to return DoppelgangerDetected effect");
 }
+
+#[test]
+fn snapshot_shows_latest_units_and_missing_dependencies() {
+    let creator: ValidatorIndex = ValidatorIndex(0);
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let now = Timestamp::zero();
+    let wunit = |panorama: Panorama<ClContext>, seq_number: u64| WireUnit {
+        value: (seq_number == 0)
+            .then(|| Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+        panorama,
+        creator,
+        instance_id: ClContext::hash(INSTANCE_ID_DATA),
+        seq_number,
+        timestamp: now,
+        round_exp: 14,
+        endorsed: BTreeSet::new(),
+    };
+
+    let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
+    let mut send_unit = |wunit: WireUnit<ClContext>| {
+        let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
+            SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        ));
+        let msg = bincode::serialize(&highway_message).unwrap();
+        let mut outcomes = highway_protocol.handle_message(NodeId(123), msg, now);
+        while let Some(outcome) = outcomes.pop() {
+            if let ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) = outcome {
+                outcomes.extend(highway_protocol.handle_action(ACTION_ID_VERTEX, now))
+            }
+        }
+    };
+
+    // Alice's first unit can be added, but her third one cites a second unit we don't know.
+    let wunit0 = wunit(Panorama::from(vec![N]), 0);
+    let hash0 = wunit0.clone().into_hashed().hash();
+    send_unit(wunit0);
+    let unknown_hash = ClContext::hash(b"unknown unit");
+    send_unit(wunit(
+        Panorama::from(vec![Observation::Correct(unknown_hash)]),
+        2,
+    ));
+
+    let highway_protocol = highway_protocol
+        .as_any()
+        .downcast_ref::<HighwayProtocol<NodeId, ClContext>>()
+        .unwrap();
+    let snapshot = HighwaySnapshot::new(EraId::new(0), highway_protocol);
+    assert_eq!(None, snapshot.own_round_exp);
+    assert_eq!(100, snapshot.total_weight);
+    match &*snapshot.panorama {
+        [entry] => {
+            assert_eq!(*ALICE_PUBLIC_KEY, entry.public_key);
+            match &entry.observation {
+                ValidatorObservation::Correct(unit) => {
+                    assert_eq!(hash0, unit.hash);
+                    assert_eq!(0, unit.seq_number);
+                }
+                observation => panic!("unexpected observation: {:?}", observation),
+            }
+        }
+        panorama => panic!("unexpected panorama: {:?}", panorama),
+    }
+    assert_eq!(
+        vec![PendingDependency {
+            dependency: MissingVertex::Unit(unknown_hash),
+            waiting_vertices: 1,
+        }],
+        snapshot.pending_dependencies
+    );
+}
@@ -0,0 +1,1607 @@
+//! A simple round-robin, single-leader BFT protocol, intended for small private networks.
+//!
+//! Each round has a single leader, chosen in turn among the validators that are allowed to propose.
+//! The leader proposes a block, together with the round of its parent. Every validator echoes the
+//! first valid proposal it sees in the current round. Once a quorum has echoed a proposal, the
+//! round is _accepted_ and validators vote `true`. A validator that hasn't seen the round accepted
+//! before the proposal timeout votes `false` instead. A round in which a quorum voted `false` is
+//! _skippable_, and an accepted round in which a quorum voted `true` is _committed_: Its proposal
+//! is finalized, together with all its ancestors.
+//!
+//! A quorum is a set of validators with more than half of the total weight plus the fault
+//! tolerance threshold, so any two quorums have an honest validator in common. Honest validators
+//! echo at most one proposal and cast at most one vote per round, so at most one proposal per
+//! round can be accepted, and a committed round can never become skippable. A proposal is only
+//! valid if its parent is accepted and all rounds between the parent and the proposal are
+//! skippable, so every accepted proposal in a later round descends from the committed one.
+
+pub(crate) mod config;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Debug},
+    iter, mem,
+    path::PathBuf,
+};
+
+use datasize::DataSize;
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, error, info, trace, warn};
+
+use casper_types::{system::auction::BLOCK_REWARD, U512};
+
+use crate::{
+    components::consensus::{
+        config::{Config, ProtocolConfig},
+        consensus_protocol::{
+            BlockContext, ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome,
+            ProtocolOutcomes, TerminalBlockData,
+        },
+        highway_core::{
+            slashing_protection::{MessageRecord, SlashingProtection},
+            validators::{Validator, ValidatorIndex, ValidatorMap, Validators},
+            Weight,
+        },
+        traits::{ConsensusValueT, Context, NodeIdT, ValidatorSecret},
+        ActionId, TimerId,
+    },
+    types::{TimeDiff, Timestamp},
+};
+
+/// The timer for re-evaluating the protocol state, e.g. once a proposal's timestamp is reached.
+const TIMER_ID_UPDATE: TimerId = TimerId(0);
+/// The timer for voting to skip the current round if it hasn't been accepted in time.
+const TIMER_ID_ROUND_TIMEOUT: TimerId = TimerId(1);
+/// The timer to request the latest state from a random peer.
+const TIMER_ID_SYNC: TimerId = TimerId(2);
+
+/// Messages for rounds this far ahead of our current round are dropped.
+const MAX_FUTURE_ROUNDS: RoundId = 1_000;
+
+/// The index of a round in an era.
+pub(crate) type RoundId = u32;
+
+/// A leader's proposal for a round.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::ConsensusValue: Serialize",
+    deserialize = "C::ConsensusValue: Deserialize<'de>",
+))]
+pub(crate) struct Proposal<C>
+where
+    C: Context,
+{
+    /// The proposed block's timestamp.
+    pub(crate) timestamp: Timestamp,
+    /// The proposed block, or `None` if the parent chain already contains the terminal block.
+    pub(crate) maybe_block: Option<C::ConsensusValue>,
+    /// The round of the parent proposal, or `None` if this is the first proposal in the era.
+    pub(crate) maybe_parent_round_id: Option<RoundId>,
+}
+
+impl<C: Context> Proposal<C> {
+    fn hash(&self) -> C::Hash {
+        <C as Context>::hash(&bincode::serialize(self).expect("should serialize proposal"))
+    }
+}
+
+/// The content of a validator's signed message.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum Content<C>
+where
+    C: Context,
+{
+    /// The validator considers the proposal with the given hash valid.
+    Echo(C::Hash),
+    /// `true` if the validator considers the round accepted, `false` if it wants to skip it.
+    Vote(bool),
+}
+
+impl<C: Context> Content<C> {
+    /// Returns whether an honest validator can't sign both `self` and `other` in the same round.
+    fn conflicts_with(&self, other: &Content<C>) -> bool {
+        match (self, other) {
+            (Content::Echo(hash0), Content::Echo(hash1)) => hash0 != hash1,
+            (Content::Vote(vote0), Content::Vote(vote1)) => vote0 != vote1,
+            (Content::Echo(_), Content::Vote(_)) | (Content::Vote(_), Content::Echo(_)) => false,
+        }
+    }
+
+    /// Returns the slashing protection record for this content in the given round.
+    fn message_record(&self, round_id: RoundId) -> MessageRecord<C> {
+        match self {
+            Content::Echo(proposal_hash) => MessageRecord::Echo {
+                round_id,
+                proposal_hash: *proposal_hash,
+            },
+            Content::Vote(vote) => MessageRecord::Vote {
+                round_id,
+                vote: *vote,
+            },
+        }
+    }
+}
+
+/// An echo or vote, signed by a validator.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) struct SignedMessage<C>
+where
+    C: Context,
+{
+    pub(crate) round_id: RoundId,
+    pub(crate) instance_id: C::InstanceId,
+    pub(crate) content: Content<C>,
+    pub(crate) validator_idx: ValidatorIndex,
+    pub(crate) signature: C::Signature,
+}
+
+impl<C: Context> SignedMessage<C> {
+    /// Creates a new message with the given content, signed with a secret that can produce
+    /// signatures synchronously.
+    #[cfg(test)]
+    fn sign(
+        round_id: RoundId,
+        instance_id: C::InstanceId,
+        content: Content<C>,
+        validator_idx: ValidatorIndex,
+        secret: &C::ValidatorSecret,
+    ) -> Self {
+        let hash = Self::hash_fields(round_id, &instance_id, &content, validator_idx);
+        SignedMessage {
+            round_id,
+            instance_id,
+            content,
+            validator_idx,
+            signature: secret
+                .try_sign(&hash)
+                .expect("test secret should sign synchronously"),
+        }
+    }
+
+    /// Returns whether the message was signed by `validator_id`.
+    fn verify_signature(&self, validator_id: &C::ValidatorId) -> bool {
+        let hash = Self::hash_fields(
+            self.round_id,
+            &self.instance_id,
+            &self.content,
+            self.validator_idx,
+        );
+        C::verify_signature(&hash, validator_id, &self.signature)
+    }
+
+    fn hash_fields(
+        round_id: RoundId,
+        instance_id: &C::InstanceId,
+        content: &Content<C>,
+        validator_idx: ValidatorIndex,
+    ) -> C::Hash {
+        let serialized = bincode::serialize(&(round_id, instance_id, content, validator_idx))
+            .expect("should serialize signed message fields");
+        <C as Context>::hash(&serialized)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(bound(
+    serialize = "C::Hash: Serialize",
+    deserialize = "C::Hash: Deserialize<'de>",
+))]
+pub(crate) enum Message<C>
+where
+    C: Context,
+{
+    /// A leader's proposal, together with the leader's echo, which doubles as its signature.
+    Proposal {
+        proposal: Proposal<C>,
+        echo: SignedMessage<C>,
+    },
+    /// An echo or vote.
+    Signed(SignedMessage<C>),
+    /// Two conflicting messages signed by the same validator in the same round.
+    Evidence(SignedMessage<C>, SignedMessage<C>),
+    /// A request for all messages in the given round and later ones.
+    SyncRequest {
+        instance_id: C::InstanceId,
+        first_round_id: RoundId,
+    },
+}
+
+impl<C: Context> Message<C> {
+    pub(crate) fn serialize(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("should serialize message")
+    }
+}
+
+/// An error in an incoming message.
+#[derive(Debug, Error)]
+pub(crate) enum MessageError {
+    #[error("the message belongs to a different consensus instance")]
+    WrongInstance,
+    #[error("the signer is not a validator in this era")]
+    UnknownValidator,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("the proposal was not signed by the round's leader")]
+    NotLeader,
+    #[error("the leader's echo doesn't match the proposal")]
+    EchoMismatch,
+    #[error("the evidence doesn't consist of two conflicting messages")]
+    NotConflicting,
+}
+
+/// Whether a received proposal's consensus value is known to be valid.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq)]
+enum Validation<I> {
+    /// We can't validate the value until we know all of the proposal's ancestors.
+    AwaitingAncestors(I),
+    /// We requested validation of the value and are waiting for the result.
+    Validating,
+    Valid,
+    Invalid,
+}
+
+/// A proposal we received in a round, together with the leader's echo signature.
+#[derive(Clone, DataSize, Debug)]
+struct RoundProposal<I, C>
+where
+    C: Context,
+{
+    proposal: Proposal<C>,
+    echo_signature: C::Signature,
+    validation: Validation<I>,
+}
+
+/// The proposals and signed messages we received in a single round.
+#[derive(Clone, DataSize, Debug)]
+struct Round<I, C>
+where
+    C: Context,
+{
+    /// The proposals by the round's leader, by hash. Unless the leader is faulty, there is at most
+    /// one.
+    proposals: BTreeMap<C::Hash, RoundProposal<I, C>>,
+    /// The echoes' signatures, by proposal hash and validator.
+    echoes: BTreeMap<C::Hash, BTreeMap<ValidatorIndex, C::Signature>>,
+    /// The votes' signatures, by vote and validator.
+    votes: BTreeMap<bool, BTreeMap<ValidatorIndex, C::Signature>>,
+}
+
+impl<I, C: Context> Default for Round<I, C> {
+    fn default() -> Self {
+        Round {
+            proposals: BTreeMap::new(),
+            echoes: BTreeMap::new(),
+            votes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<I, C: Context> Round<I, C> {
+    /// Returns the message the validator signed in this round that conflicts with `content`, if
+    /// any.
+    fn conflicting_signature(
+        &self,
+        validator_idx: ValidatorIndex,
+        content: &Content<C>,
+    ) -> Option<(Content<C>, C::Signature)> {
+        match content {
+            Content::Echo(hash) => self
+                .echoes
+                .iter()
+                .filter(|(other_hash, _)| *other_hash != hash)
+                .find_map(|(other_hash, signatures)| {
+                    let signature = signatures.get(&validator_idx)?;
+                    Some((Content::Echo(*other_hash), *signature))
+                }),
+            Content::Vote(vote) => self
+                .votes
+                .get(&!vote)
+                .and_then(|signatures| signatures.get(&validator_idx))
+                .map(|signature| (Content::Vote(!vote), *signature)),
+        }
+    }
+
+    /// Returns whether the validator has echoed any proposal in this round.
+    fn has_echoed(&self, validator_idx: ValidatorIndex) -> bool {
+        self.echoes
+            .values()
+            .any(|signatures| signatures.contains_key(&validator_idx))
+    }
+
+    /// Returns whether the validator has voted in this round.
+    fn has_voted(&self, validator_idx: ValidatorIndex) -> bool {
+        self.votes
+            .values()
+            .any(|signatures| signatures.contains_key(&validator_idx))
+    }
+}
+
+/// Why a validator is considered faulty.
+#[derive(Clone, DataSize, Debug)]
+enum Fault<C>
+where
+    C: Context,
+{
+    /// The validator was banned because it was faulty in a recent era.
+    Banned,
+    /// Two conflicting messages the validator signed in this era.
+    Direct(SignedMessage<C>, SignedMessage<C>),
+    /// The validator is known to be faulty, based on evidence from another era.
+    Indirect,
+}
+
+/// Our own validator index and secret key, if we are an active validator.
+#[derive(DataSize)]
+struct ActiveValidator<C>
+where
+    C: Context,
+{
+    idx: ValidatorIndex,
+    secret: C::ValidatorSecret,
+    /// Our messages whose signatures have been requested but not created yet, by hash.
+    unsigned: HashMap<C::Hash, UnsignedMessage<C>>,
+    /// The record of all echoes and votes we signed in this era, if any. Messages that conflict
+    /// with it are never signed.
+    slashing_protection: Option<SlashingProtection<C>>,
+}
+
+/// An echo or vote waiting for our signature. For the leader's echo, this includes the proposal.
+#[derive(DataSize)]
+struct UnsignedMessage<C>
+where
+    C: Context,
+{
+    round_id: RoundId,
+    content: Content<C>,
+    maybe_proposal: Option<Proposal<C>>,
+    /// Whether a signature has been requested and neither created nor failed yet.
+    awaiting_signature: bool,
+}
+
+impl<C: Context> Debug for ActiveValidator<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ActiveValidator")
+            .field("idx", &self.idx)
+            .finish()
+    }
+}
+
+#[derive(DataSize, Debug)]
+pub(crate) struct RoundRobinProtocol<I, C>
+where
+    I: DataSize,
+    C: Context,
+{
+    instance_id: C::InstanceId,
+    validators: Validators<C::ValidatorId>,
+    weights: ValidatorMap<Weight>,
+    /// The validators that are allowed to propose, in the order in which they lead rounds.
+    leaders: Vec<ValidatorIndex>,
+    /// The offset into `leaders` of round 0's leader.
+    first_leader_offset: usize,
+    /// The fault tolerance threshold.
+    ftt: Weight,
+    /// The validators known to be faulty. Their weight counts towards every quorum.
+    faults: HashMap<ValidatorIndex, Fault<C>>,
+    active_validator: Option<ActiveValidator<C>>,
+    era_start_time: Timestamp,
+    era_end_time: Timestamp,
+    minimum_era_height: u64,
+    rounds: BTreeMap<RoundId, Round<I, C>>,
+    current_round_id: RoundId,
+    /// The time at which we entered the current round.
+    current_round_start: Timestamp,
+    /// The context of the block we requested from the block proposer, with the round we want to
+    /// propose it in and the round of its parent.
+    pending_proposal: Option<(BlockContext<C>, RoundId, Option<RoundId>)>,
+    /// Incoming proposals whose values are being validated.
+    pending_values: HashMap<ProposedBlock<C>, HashSet<(RoundId, C::Hash)>>,
+    /// The first round after the latest committed one.
+    first_non_finalized_round_id: RoundId,
+    finalized_switch_block: bool,
+    evidence_only: bool,
+    paused: bool,
+    config: config::Config,
+}
+
+impl<I: NodeIdT, C: Context + 'static> RoundRobinProtocol<I, C> {
+    /// Creates a new boxed `RoundRobinProtocol` instance.
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub(crate) fn new_boxed(
+        instance_id: C::InstanceId,
+        validator_stakes: BTreeMap<C::ValidatorId, U512>,
+        faulty: &HashSet<C::ValidatorId>,
+        inactive: &HashSet<C::ValidatorId>,
+        protocol_config: &ProtocolConfig,
+        config: &Config,
+        _prev_cp: Option<&dyn ConsensusProtocol<I, C>>,
+        era_start_time: Timestamp,
+        seed: u64,
+        now: Timestamp,
+    ) -> (Box<dyn ConsensusProtocol<I, C>>, ProtocolOutcomes<I, C>) {
+        let sum_stakes: U512 = validator_stakes.iter().map(|(_, stake)| *stake).sum();
+        assert!(
+            !sum_stakes.is_zero(),
+            "cannot start era with total weight 0"
+        );
+        // Scale down by  sum / u64::MAX,  rounded up, so that the total weight fits into a u64.
+        let scaling_factor = (sum_stakes + U512::from(u64::MAX) - 1) / U512::from(u64::MAX);
+        let scale_stake = |(key, stake): (C::ValidatorId, U512)| {
+            (key, AsPrimitive::<u64>::as_(stake / scaling_factor))
+        };
+        let mut validators: Validators<C::ValidatorId> =
+            validator_stakes.into_iter().map(scale_stake).collect();
+
+        for vid in faulty {
+            validators.ban(vid);
+        }
+        for vid in inactive {
+            validators.set_cannot_propose(vid);
+        }
+
+        assert!(
+            validators.ensure_nonzero_proposing_stake(),
+            "cannot start era with total weight 0"
+        );
+
+        let total_weight = u128::from(validators.total_weight());
+        let ftt_fraction = protocol_config.highway.finality_threshold_fraction;
+        assert!(
+            ftt_fraction < 1.into(),
+            "finality threshold must be less than 100%"
+        );
+        #[allow(clippy::integer_arithmetic)] // FTT is less than 1, so this can't overflow.
+        let ftt = total_weight * *ftt_fraction.numer() as u128 / *ftt_fraction.denom() as u128;
+
+        let weights: ValidatorMap<Weight> = validators
+            .iter()
+            .map(Validator::weight)
+            .collect::<Vec<_>>()
+            .into();
+        let cannot_propose: HashSet<ValidatorIndex> =
+            validators.iter_cannot_propose_idx().collect();
+        let leaders: Vec<ValidatorIndex> = (0..validators.iter().count() as u32)
+            .map(ValidatorIndex::from)
+            .filter(|idx| !cannot_propose.contains(idx))
+            .collect();
+        let faults = validators
+            .iter_banned_idx()
+            .map(|idx| (idx, Fault::Banned))
+            .collect();
+
+        info!(leaders = leaders.len(), "initializing round-robin instance");
+
+        let rr_proto = Box::new(RoundRobinProtocol {
+            instance_id,
+            first_leader_offset: (seed % leaders.len() as u64) as usize,
+            validators,
+            weights,
+            leaders,
+            ftt: Weight(ftt as u64),
+            faults,
+            active_validator: None,
+            era_start_time,
+            era_end_time: era_start_time + protocol_config.era_duration,
+            minimum_era_height: protocol_config.minimum_era_height,
+            rounds: BTreeMap::new(),
+            current_round_id: 0,
+            current_round_start: era_start_time.max(now),
+            pending_proposal: None,
+            pending_values: HashMap::new(),
+            first_non_finalized_round_id: 0,
+            finalized_switch_block: false,
+            evidence_only: false,
+            paused: false,
+            config: config.round_robin.clone(),
+        });
+
+        (rr_proto, vec![])
+    }
+
+    /// Returns the leader of the given round.
+    fn leader(&self, round_id: RoundId) -> ValidatorIndex {
+        let offset = (self.first_leader_offset + round_id as usize) % self.leaders.len();
+        self.leaders[offset]
+    }
+
+    /// Returns the total weight of the validators known to be faulty.
+    fn faulty_weight(&self) -> u128 {
+        self.faults
+            .keys()
+            .map(|idx| u128::from(self.weights[*idx].0))
+            .sum()
+    }
+
+    /// Returns whether the given validators, together with all faulty ones, have more than half
+    /// of the total weight plus the fault tolerance threshold.
+    fn is_quorum<'a>(&self, signers: impl Iterator<Item = &'a ValidatorIndex>) -> bool {
+        let correct_weight: u128 = signers
+            .filter(|idx| !self.faults.contains_key(idx))
+            .map(|idx| u128::from(self.weights[*idx].0))
+            .sum();
+        let weight = correct_weight + self.faulty_weight();
+        2 * weight > u128::from(self.validators.total_weight().0) + u128::from(self.ftt.0)
+    }
+
+    /// Returns the proposal a quorum has echoed in the given round, with its hash, if we know it.
+    fn accepted_proposal(&self, round_id: RoundId) -> Option<(C::Hash, &Proposal<C>)> {
+        let round = self.rounds.get(&round_id)?;
+        round.proposals.iter().find_map(|(hash, round_proposal)| {
+            let signatures = round.echoes.get(hash)?;
+            self.is_quorum(signatures.keys())
+                .then(|| (*hash, &round_proposal.proposal))
+        })
+    }
+
+    /// Returns whether a quorum has voted `vote` in the given round.
+    fn has_quorum_votes(&self, round_id: RoundId, vote: bool) -> bool {
+        let maybe_signatures = self
+            .rounds
+            .get(&round_id)
+            .and_then(|round| round.votes.get(&vote));
+        match maybe_signatures {
+            Some(signatures) => self.is_quorum(signatures.keys()),
+            None => self.is_quorum(iter::empty()),
+        }
+    }
+
+    fn is_skippable(&self, round_id: RoundId) -> bool {
+        self.has_quorum_votes(round_id, false)
+    }
+
+    fn is_committed(&self, round_id: RoundId) -> bool {
+        self.accepted_proposal(round_id).is_some() && self.has_quorum_votes(round_id, true)
+    }
+
+    /// Returns the accepted proposals in the given round and all its ancestors, latest first, or
+    /// `None` if we haven't seen all of them accepted yet.
+    fn ancestry(&self, maybe_round_id: Option<RoundId>) -> Option<Vec<(RoundId, &Proposal<C>)>> {
+        let mut ancestry = Vec::new();
+        let mut maybe_next = maybe_round_id;
+        while let Some(round_id) = maybe_next {
+            let (_, proposal) = self.accepted_proposal(round_id)?;
+            maybe_next = proposal.maybe_parent_round_id;
+            ancestry.push((round_id, proposal));
+        }
+        Some(ancestry)
+    }
+
+    /// Returns whether a block with the given relative height and timestamp is the era's last.
+    fn is_terminal_block(&self, height: u64, timestamp: Timestamp) -> bool {
+        height.saturating_add(1) >= self.minimum_era_height && timestamp >= self.era_end_time
+    }
+
+    /// Returns whether the ancestry contains the terminal block.
+    fn contains_terminal_block(&self, ancestry: &[(RoundId, &Proposal<C>)]) -> bool {
+        ancestry
+            .iter()
+            .rev()
+            .filter(|(_, proposal)| proposal.maybe_block.is_some())
+            .enumerate()
+            .any(|(height, (_, proposal))| {
+                self.is_terminal_block(height as u64, proposal.timestamp)
+            })
+    }
+
+    /// Returns the block context for a proposal with the given timestamp and ancestry.
+    fn block_context(
+        timestamp: Timestamp,
+        ancestry: &[(RoundId, &Proposal<C>)],
+    ) -> BlockContext<C> {
+        let ancestor_values = ancestry
+            .iter()
+            .filter_map(|(_, proposal)| proposal.maybe_block.clone())
+            .collect();
+        BlockContext::new(timestamp, ancestor_values)
+    }
+
+    /// Returns whether the proposal in the given round satisfies all validity conditions except
+    /// for the validity of its consensus value.
+    fn is_valid_proposal(&self, round_id: RoundId, proposal: &Proposal<C>) -> bool {
+        let ancestry = match self.ancestry(proposal.maybe_parent_round_id) {
+            Some(ancestry) => ancestry,
+            None => return false,
+        };
+        let (first_skipped_round_id, parent_timestamp) = match ancestry.first() {
+            Some((parent_round_id, _)) if *parent_round_id >= round_id => return false,
+            Some((parent_round_id, parent)) => (parent_round_id + 1, parent.timestamp),
+            None => (0, self.era_start_time),
+        };
+        proposal.timestamp >= parent_timestamp
+            && proposal.maybe_block.is_some() != self.contains_terminal_block(&ancestry)
+            && (first_skipped_round_id..round_id).all(|skipped| self.is_skippable(skipped))
+    }
+
+    /// Returns the round of the parent for our proposal in the given round: the latest accepted
+    /// round such that all later ones are skippable, or `Some(None)` if all earlier rounds are
+    /// skippable. Returns `None` if we can't tell yet.
+    fn parent_round_id(&self, round_id: RoundId) -> Option<Option<RoundId>> {
+        for earlier_round_id in (0..round_id).rev() {
+            if self.accepted_proposal(earlier_round_id).is_some() {
+                return Some(Some(earlier_round_id));
+            }
+            if !self.is_skippable(earlier_round_id) {
+                return None;
+            }
+        }
+        Some(None)
+    }
+
+    /// Returns the round with the given ID, creating it if necessary.
+    fn round_mut(&mut self, round_id: RoundId) -> &mut Round<I, C> {
+        self.rounds.entry(round_id).or_default()
+    }
+
+    /// Returns whether messages for the given round are irrelevant: either the round is committed
+    /// or earlier than a committed one, or it is too far in the future.
+    fn is_irrelevant_round(&self, round_id: RoundId) -> bool {
+        round_id < self.first_non_finalized_round_id
+            || round_id > self.current_round_id.saturating_add(MAX_FUTURE_ROUNDS)
+    }
+
+    /// Checks the message's instance ID and signature.
+    fn validate_signed_message(&self, signed_msg: &SignedMessage<C>) -> Result<(), MessageError> {
+        if signed_msg.instance_id != self.instance_id {
+            return Err(MessageError::WrongInstance);
+        }
+        let validator_id = self
+            .validators
+            .id(signed_msg.validator_idx)
+            .ok_or(MessageError::UnknownValidator)?;
+        if !signed_msg.verify_signature(validator_id) {
+            return Err(MessageError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Adds a validated signed message to the round, and records evidence if it conflicts with
+    /// an earlier one.
+    fn add_signed_message(&mut self, signed_msg: SignedMessage<C>) -> ProtocolOutcomes<I, C> {
+        let round_id = signed_msg.round_id;
+        let validator_idx = signed_msg.validator_idx;
+        let round = self.round_mut(round_id);
+        let maybe_conflict = round.conflicting_signature(validator_idx, &signed_msg.content);
+        let signatures = match &signed_msg.content {
+            Content::Echo(hash) => round.echoes.entry(*hash).or_default(),
+            Content::Vote(vote) => round.votes.entry(*vote).or_default(),
+        };
+        signatures.insert(validator_idx, signed_msg.signature);
+        match maybe_conflict {
+            None => vec![],
+            Some((content, signature)) => {
+                let other_msg = SignedMessage {
+                    round_id,
+                    instance_id: self.instance_id,
+                    content,
+                    validator_idx,
+                    signature,
+                };
+                self.add_evidence(other_msg, signed_msg)
+            }
+        }
+    }
+
+    /// Records direct evidence against a validator, unless we already have some.
+    fn add_evidence(
+        &mut self,
+        signed_msg0: SignedMessage<C>,
+        signed_msg1: SignedMessage<C>,
+    ) -> ProtocolOutcomes<I, C> {
+        let validator_idx = signed_msg0.validator_idx;
+        if let Some(Fault::Direct(..)) = self.faults.get(&validator_idx) {
+            return vec![];
+        }
+        let validator_id = match self.validators.id(validator_idx) {
+            Some(validator_id) => validator_id.clone(),
+            None => return vec![], // We already validated the messages.
+        };
+        let msg = Message::Evidence(signed_msg0.clone(), signed_msg1.clone());
+        self.faults
+            .insert(validator_idx, Fault::Direct(signed_msg0, signed_msg1));
+        let mut outcomes = vec![
+            ProtocolOutcome::CreatedGossipMessage(msg.serialize()),
+            ProtocolOutcome::NewEvidence(validator_id),
+        ];
+        if self.active_validator.as_ref().map(|av| av.idx) == Some(validator_idx) {
+            error!("this validator is faulty");
+            outcomes.push(ProtocolOutcome::WeAreFaulty);
+        }
+        if self.faulty_weight() > u128::from(self.ftt.0) {
+            error!(
+                faulty_weight = %self.faulty_weight(),
+                total_weight = %self.validators.total_weight().0,
+                "too many faulty validators"
+            );
+            outcomes.push(ProtocolOutcome::FttExceeded);
+        }
+        outcomes
+    }
+
+    fn handle_proposal(
+        &mut self,
+        sender: I,
+        proposal: Proposal<C>,
+        echo: SignedMessage<C>,
+        now: Timestamp,
+    ) -> Result<ProtocolOutcomes<I, C>, MessageError> {
+        self.validate_signed_message(&echo)?;
+        let hash = proposal.hash();
+        if echo.content != Content::Echo(hash) {
+            return Err(MessageError::EchoMismatch);
+        }
+        let round_id = echo.round_id;
+        let leader = self.leader(round_id);
+        if echo.validator_idx != leader {
+            return Err(MessageError::NotLeader);
+        }
+        if self.is_irrelevant_round(round_id) {
+            trace!(%round_id, "received a proposal for an irrelevant round");
+            return Ok(vec![]);
+        }
+        let leader_is_faulty = self.faults.contains_key(&leader);
+        let round = self.round_mut(round_id);
+        // A faulty leader could send us any number of proposals. Only keep the ones someone
+        // echoed, in case that was a quorum.
+        if round.proposals.contains_key(&hash)
+            || (leader_is_faulty && !round.echoes.contains_key(&hash))
+        {
+            return Ok(vec![]);
+        }
+        debug!(%round_id, timestamp = %proposal.timestamp, "received a proposal");
+        round.proposals.insert(
+            hash,
+            RoundProposal {
+                proposal,
+                echo_signature: echo.signature,
+                validation: Validation::AwaitingAncestors(sender),
+            },
+        );
+        let mut outcomes = self.add_signed_message(echo);
+        outcomes.extend(self.update(now));
+        Ok(outcomes)
+    }
+
+    fn handle_signed_message(
+        &mut self,
+        signed_msg: SignedMessage<C>,
+        now: Timestamp,
+    ) -> Result<ProtocolOutcomes<I, C>, MessageError> {
+        self.validate_signed_message(&signed_msg)?;
+        if self.is_irrelevant_round(signed_msg.round_id) {
+            trace!(round_id = %signed_msg.round_id, "received a message for an irrelevant round");
+            return Ok(vec![]);
+        }
+        let mut outcomes = self.add_signed_message(signed_msg);
+        outcomes.extend(self.update(now));
+        Ok(outcomes)
+    }
+
+    fn handle_evidence(
+        &mut self,
+        signed_msg0: SignedMessage<C>,
+        signed_msg1: SignedMessage<C>,
+        now: Timestamp,
+    ) -> Result<ProtocolOutcomes<I, C>, MessageError> {
+        self.validate_signed_message(&signed_msg0)?;
+        self.validate_signed_message(&signed_msg1)?;
+        if signed_msg0.round_id != signed_msg1.round_id
+            || signed_msg0.validator_idx != signed_msg1.validator_idx
+            || !signed_msg0.content.conflicts_with(&signed_msg1.content)
+        {
+            return Err(MessageError::NotConflicting);
+        }
+        let mut outcomes = self.add_evidence(signed_msg0, signed_msg1);
+        outcomes.extend(self.update(now));
+        Ok(outcomes)
+    }
+
+    /// Returns all our evidence, and all proposals and signed messages from the given round on.
+    fn handle_sync_request(
+        &self,
+        sender: I,
+        instance_id: C::InstanceId,
+        first_round_id: RoundId,
+    ) -> Result<ProtocolOutcomes<I, C>, MessageError> {
+        if instance_id != self.instance_id {
+            return Err(MessageError::WrongInstance);
+        }
+        let mut messages = Vec::new();
+        for fault in self.faults.values() {
+            if let Fault::Direct(signed_msg0, signed_msg1) = fault {
+                messages.push(Message::Evidence(signed_msg0.clone(), signed_msg1.clone()));
+            }
+        }
+        let signed_msg = |round_id, content, validator_idx, signature| SignedMessage {
+            round_id,
+            instance_id,
+            content,
+            validator_idx,
+            signature,
+        };
+        for (round_id, round) in self.rounds.range(first_round_id..) {
+            for (hash, signatures) in &round.echoes {
+                for (validator_idx, signature) in signatures {
+                    let echo =
+                        signed_msg(*round_id, Content::Echo(*hash), *validator_idx, *signature);
+                    messages.push(Message::Signed(echo));
+                }
+            }
+            for (vote, signatures) in &round.votes {
+                for (validator_idx, signature) in signatures {
+                    let vote =
+                        signed_msg(*round_id, Content::Vote(*vote), *validator_idx, *signature);
+                    messages.push(Message::Signed(vote));
+                }
+            }
+            // Proposals come last, so that the recipient already knows they were echoed.
+            for (hash, round_proposal) in &round.proposals {
+                let leader = self.leader(*round_id);
+                let echo = signed_msg(
+                    *round_id,
+                    Content::Echo(*hash),
+                    leader,
+                    round_proposal.echo_signature,
+                );
+                messages.push(Message::Proposal {
+                    proposal: round_proposal.proposal.clone(),
+                    echo,
+                });
+            }
+        }
+        Ok(messages
+            .into_iter()
+            .map(|msg| ProtocolOutcome::CreatedTargetedMessage(msg.serialize(), sender.clone()))
+            .collect())
+    }
+
+    fn sync_request(&self) -> Message<C> {
+        Message::SyncRequest {
+            instance_id: self.instance_id,
+            first_round_id: self.first_non_finalized_round_id,
+        }
+    }
+
+    /// Signs a message as the active validator, adds it to our state and gossips it.
+    fn sign_and_gossip(
+        &mut self,
+        round_id: RoundId,
+        content: Content<C>,
+    ) -> ProtocolOutcomes<I, C> {
+        self.sign(round_id, content, None)
+    }
+
+    /// Signs the proposal as the round's leader, adds it to our state and gossips it.
+    fn create_proposal(
+        &mut self,
+        round_id: RoundId,
+        proposal: Proposal<C>,
+    ) -> ProtocolOutcomes<I, C> {
+        let hash = proposal.hash();
+        self.sign(round_id, Content::Echo(hash), Some(proposal))
+    }
+
+    /// Signs the message as the active validator and gossips it, or requests the signature if
+    /// the secret can't sign synchronously. Nothing is signed if a message of the same kind in
+    /// the same round is still waiting for its signature, or if slashing protection refuses it.
+    fn sign(
+        &mut self,
+        round_id: RoundId,
+        content: Content<C>,
+        maybe_proposal: Option<Proposal<C>>,
+    ) -> ProtocolOutcomes<I, C> {
+        let instance_id = self.instance_id;
+        let av = match &mut self.active_validator {
+            Some(av) => av,
+            None => return vec![],
+        };
+        if av.unsigned.values().any(|unsigned| {
+            unsigned.round_id == round_id
+                && mem::discriminant(&unsigned.content) == mem::discriminant(&content)
+        }) {
+            debug!(%round_id, ?content, "still waiting for signature of previous message");
+            return vec![];
+        }
+        if let Some(slashing_protection) = av.slashing_protection.as_mut() {
+            if let Err(err) = slashing_protection.record_message(content.message_record(round_id)) {
+                error!(%err, "canceling message creation");
+                return vec![];
+            }
+        }
+        let hash = SignedMessage::hash_fields(round_id, &instance_id, &content, av.idx);
+        let signature = match av.secret.try_sign(&hash) {
+            Some(signature) => signature,
+            None => {
+                let unsigned = UnsignedMessage {
+                    round_id,
+                    content,
+                    maybe_proposal,
+                    awaiting_signature: true,
+                };
+                av.unsigned.insert(hash, unsigned);
+                return vec![ProtocolOutcome::CreateSignature(hash)];
+            }
+        };
+        let signed_msg = SignedMessage {
+            round_id,
+            instance_id,
+            content,
+            validator_idx: av.idx,
+            signature,
+        };
+        self.gossip_own_message(signed_msg, maybe_proposal)
+    }
+
+    /// Requests the signatures of our messages again, if the previous attempts failed.
+    fn retry_unsigned_messages(&mut self) -> ProtocolOutcomes<I, C> {
+        let av = match &mut self.active_validator {
+            Some(av) => av,
+            None => return vec![],
+        };
+        av.unsigned
+            .iter_mut()
+            .filter(|(_, unsigned)| !unsigned.awaiting_signature)
+            .map(|(hash, unsigned)| {
+                unsigned.awaiting_signature = true;
+                ProtocolOutcome::CreateSignature(*hash)
+            })
+            .collect()
+    }
+
+    /// Returns whether our echo in the given round is still waiting for its signature.
+    fn is_awaiting_echo_signature(&self, round_id: RoundId) -> bool {
+        self.active_validator.as_ref().map_or(false, |av| {
+            av.unsigned.values().any(|unsigned| {
+                unsigned.round_id == round_id && matches!(unsigned.content, Content::Echo(_))
+            })
+        })
+    }
+
+    /// Adds our own signed message to our state and gossips it, together with the proposal if it
+    /// is the leader's echo.
+    fn gossip_own_message(
+        &mut self,
+        signed_msg: SignedMessage<C>,
+        maybe_proposal: Option<Proposal<C>>,
+    ) -> ProtocolOutcomes<I, C> {
+        let proposal = match maybe_proposal {
+            Some(proposal) => proposal,
+            None => {
+                let mut outcomes = vec![ProtocolOutcome::CreatedGossipMessage(
+                    Message::Signed(signed_msg.clone()).serialize(),
+                )];
+                outcomes.extend(self.add_signed_message(signed_msg));
+                return outcomes;
+            }
+        };
+        let round_id = signed_msg.round_id;
+        let hash = proposal.hash();
+        let echo = signed_msg;
+        debug!(%round_id, timestamp = %proposal.timestamp, "proposing");
+        self.round_mut(round_id).proposals.insert(
+            hash,
+            RoundProposal {
+                proposal: proposal.clone(),
+                echo_signature: echo.signature,
+                validation: Validation::Valid,
+            },
+        );
+        let msg = Message::Proposal {
+            proposal,
+            echo: echo.clone(),
+        };
+        let mut outcomes = vec![ProtocolOutcome::CreatedGossipMessage(msg.serialize())];
+        outcomes.extend(self.add_signed_message(echo));
+        outcomes
+    }
+
+    /// Requests validation of the values of all proposals whose ancestors are now known.
+    fn request_validations(&mut self) -> ProtocolOutcomes<I, C> {
+        let mut to_validate = Vec::new();
+        let mut valid = Vec::new();
+        for (round_id, round) in self.rounds.range(self.first_non_finalized_round_id..) {
+            for (hash, round_proposal) in &round.proposals {
+                let sender = match &round_proposal.validation {
+                    Validation::AwaitingAncestors(sender) => sender,
+                    Validation::Validating | Validation::Valid | Validation::Invalid => continue,
+                };
+                let proposal = &round_proposal.proposal;
+                let ancestry = match self.ancestry(proposal.maybe_parent_round_id) {
+                    Some(ancestry) => ancestry,
+                    None => continue,
+                };
+                match &proposal.maybe_block {
+                    Some(block) if block.needs_validation() => {
+                        let block_context = Self::block_context(proposal.timestamp, &ancestry);
+                        let proposed_block = ProposedBlock::new(block.clone(), block_context);
+                        to_validate.push((*round_id, *hash, sender.clone(), proposed_block));
+                    }
+                    Some(_) | None => valid.push((*round_id, *hash)),
+                }
+            }
+        }
+        let mut outcomes = Vec::new();
+        for (round_id, hash) in valid {
+            self.set_validation(round_id, hash, Validation::Valid);
+        }
+        for (round_id, hash, sender, proposed_block) in to_validate {
+            self.set_validation(round_id, hash, Validation::Validating);
+            let pending = self
+                .pending_values
+                .entry(proposed_block.clone())
+                .or_default();
+            if pending.is_empty() {
+                outcomes.push(ProtocolOutcome::ValidateConsensusValue {
+                    sender,
+                    proposed_block,
+                });
+            }
+            pending.insert((round_id, hash));
+        }
+        outcomes
+    }
+
+    fn set_validation(&mut self, round_id: RoundId, hash: C::Hash, validation: Validation<I>) {
+        if let Some(round_proposal) = self
+            .rounds
+            .get_mut(&round_id)
+            .and_then(|round| round.proposals.get_mut(&hash))
+        {
+            round_proposal.validation = validation;
+        }
+    }
+
+    /// Finalizes the latest committed round and its ancestors, if all of their values are valid.
+    fn finalize_rounds(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        let maybe_committed_round_id = self
+            .rounds
+            .range(self.first_non_finalized_round_id..)
+            .rev()
+            .map(|(round_id, _)| *round_id)
+            .find(|round_id| self.is_committed(*round_id));
+        let committed_round_id = match maybe_committed_round_id {
+            Some(committed_round_id) => committed_round_id,
+            None => return vec![],
+        };
+        let mut ancestry = match self.ancestry(Some(committed_round_id)) {
+            Some(ancestry) => ancestry,
+            None => return vec![],
+        };
+        ancestry.reverse();
+        let all_valid = ancestry
+            .iter()
+            .filter(|(round_id, _)| *round_id >= self.first_non_finalized_round_id)
+            .all(|(round_id, proposal)| {
+                let round = &self.rounds[round_id];
+                round.proposals[&proposal.hash()].validation == Validation::Valid
+            });
+        if !all_valid {
+            return vec![];
+        }
+        let mut finalized_blocks = Vec::new();
+        let mut height = 0;
+        for (round_id, proposal) in ancestry {
+            let block = match &proposal.maybe_block {
+                Some(block) => block,
+                None => continue,
+            };
+            if round_id >= self.first_non_finalized_round_id {
+                let terminal = self.is_terminal_block(height, proposal.timestamp);
+                let proposer = self
+                    .validators
+                    .id(self.leader(round_id))
+                    .expect("leader must be a validator")
+                    .clone();
+                finalized_blocks.push(FinalizedBlock {
+                    value: block.clone(),
+                    timestamp: proposal.timestamp,
+                    relative_height: height,
+                    equivocators: vec![],
+                    terminal_block_data: terminal.then(|| self.terminal_block_data(height)),
+                    proposer,
+                });
+                if terminal {
+                    break;
+                }
+            }
+            height += 1;
+        }
+        if finalized_blocks
+            .last()
+            .map_or(false, |block| block.terminal_block_data.is_some())
+        {
+            info!("finalized the switch block");
+            self.finalized_switch_block = true;
+        }
+        self.first_non_finalized_round_id = committed_round_id + 1;
+        let mut outcomes: ProtocolOutcomes<I, C> = finalized_blocks
+            .into_iter()
+            .map(ProtocolOutcome::FinalizedBlock)
+            .collect();
+        if self.current_round_id < self.first_non_finalized_round_id {
+            outcomes.extend(self.enter_round(self.first_non_finalized_round_id, now));
+        }
+        outcomes
+    }
+
+    /// Returns the rewards for an era with the given number of blocks: For each block, every
+    /// validator who isn't banned receives a share of the block reward proportional to its weight.
+    fn terminal_block_data(&self, terminal_height: u64) -> TerminalBlockData<C> {
+        let total_weight = u128::from(self.validators.total_weight().0);
+        let era_reward = u128::from(BLOCK_REWARD) * u128::from(terminal_height + 1);
+        let rewards = self
+            .validators
+            .enumerate_ids()
+            .filter(|(idx, _)| !matches!(self.faults.get(idx), Some(Fault::Banned)))
+            .map(|(idx, validator_id)| {
+                let reward = era_reward * u128::from(self.weights[idx].0) / total_weight;
+                (validator_id.clone(), reward as u64)
+            })
+            .collect();
+        TerminalBlockData {
+            rewards,
+            inactive_validators: vec![],
+        }
+    }
+
+    /// Moves on to the given round, and schedules the timeout for it.
+    fn enter_round(&mut self, round_id: RoundId, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        trace!(%round_id, "entering round");
+        self.current_round_id = round_id;
+        self.current_round_start = now.max(self.era_start_time);
+        if self.active_validator.is_none() {
+            return vec![];
+        }
+        vec![ProtocolOutcome::ScheduleTimer(
+            self.current_round_start + self.config.proposal_timeout,
+            TIMER_ID_ROUND_TIMEOUT,
+        )]
+    }
+
+    /// Proposes, echoes or votes in the current round, as an active validator, if we can.
+    fn act_in_current_round(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        let (our_idx, echoed_elsewhere) = match &self.active_validator {
+            Some(av) if !self.paused && now >= self.era_start_time => {
+                // If slashing protection has an echo in this round that we don't know about, e.g.
+                // signed before a restart, it would refuse to sign a new proposal.
+                let echoed_elsewhere = av
+                    .slashing_protection
+                    .as_ref()
+                    .map_or(false, |sp| sp.has_echoed(self.current_round_id));
+                (av.idx, echoed_elsewhere)
+            }
+            Some(_) | None => return vec![],
+        };
+        let round_id = self.current_round_id;
+        let mut outcomes = Vec::new();
+        if self.leader(round_id) == our_idx
+            && !echoed_elsewhere
+            && self.round_mut(round_id).proposals.is_empty()
+            && self.pending_proposal.as_ref().map(|(_, id, _)| *id) != Some(round_id)
+            && !self.is_awaiting_echo_signature(round_id)
+        {
+            outcomes.extend(self.propose_in_current_round(now));
+        }
+        if !self.round_mut(round_id).has_echoed(our_idx) {
+            let mut maybe_echo = None;
+            for (hash, round_proposal) in &self.rounds[&round_id].proposals {
+                let proposal = &round_proposal.proposal;
+                if round_proposal.validation != Validation::Valid
+                    || !self.is_valid_proposal(round_id, proposal)
+                {
+                    continue;
+                }
+                if proposal.timestamp > now {
+                    outcomes.push(ProtocolOutcome::ScheduleTimer(
+                        proposal.timestamp,
+                        TIMER_ID_UPDATE,
+                    ));
+                    continue;
+                }
+                maybe_echo = Some(*hash);
+                break;
+            }
+            if let Some(hash) = maybe_echo {
+                outcomes.extend(self.sign_and_gossip(round_id, Content::Echo(hash)));
+            }
+        }
+        if !self.round_mut(round_id).has_voted(our_idx)
+            && self.accepted_proposal(round_id).is_some()
+        {
+            outcomes.extend(self.sign_and_gossip(round_id, Content::Vote(true)));
+        }
+        outcomes
+    }
+
+    /// Creates a proposal without a block if the terminal block is already among the ancestors,
+    /// or otherwise requests a new block from the block proposer.
+    fn propose_in_current_round(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        let round_id = self.current_round_id;
+        let maybe_parent_round_id = match self.parent_round_id(round_id) {
+            Some(maybe_parent_round_id) => maybe_parent_round_id,
+            None => return vec![],
+        };
+        let ancestry = match self.ancestry(maybe_parent_round_id) {
+            Some(ancestry) => ancestry,
+            None => return vec![],
+        };
+        let parent_timestamp = ancestry
+            .first()
+            .map_or(self.era_start_time, |(_, parent)| parent.timestamp);
+        let timestamp = now.max(parent_timestamp);
+        if self.contains_terminal_block(&ancestry) {
+            let proposal = Proposal {
+                timestamp,
+                maybe_block: None,
+                maybe_parent_round_id,
+            };
+            return self.create_proposal(round_id, proposal);
+        }
+        let block_context = Self::block_context(timestamp, &ancestry);
+        self.pending_proposal = Some((block_context.clone(), round_id, maybe_parent_round_id));
+        vec![ProtocolOutcome::CreateNewBlock(block_context)]
+    }
+
+    /// Votes to skip the current round if it hasn't been accepted before the timeout.
+    fn handle_round_timeout(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        let our_idx = match &self.active_validator {
+            Some(av) => av.idx,
+            None => return vec![],
+        };
+        let round_id = self.current_round_id;
+        if self.evidence_only
+            || self.finalized_switch_block
+            || self.paused
+            || now < self.current_round_start + self.config.proposal_timeout
+            || self.round_mut(round_id).has_voted(our_idx)
+            || self.accepted_proposal(round_id).is_some()
+        {
+            return vec![];
+        }
+        info!(%round_id, "proposal timed out; voting to skip the round");
+        let mut outcomes = self.sign_and_gossip(round_id, Content::Vote(false));
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
+    /// Validates proposals, finalizes committed rounds, acts in the current round, and moves on
+    /// to the next round as long as the current one is accepted or skippable.
+    fn update(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        // If the faulty validators alone form a quorum, every round looks skippable.
+        if self.evidence_only
+            || self.finalized_switch_block
+            || self.faulty_weight() > u128::from(self.ftt.0)
+        {
+            return vec![];
+        }
+        let mut outcomes = self.retry_unsigned_messages();
+        outcomes.extend(self.request_validations());
+        outcomes.extend(self.finalize_rounds(now));
+        while !self.finalized_switch_block {
+            outcomes.extend(self.act_in_current_round(now));
+            let round_id = self.current_round_id;
+            let accepted = self.accepted_proposal(round_id).is_some();
+            if !accepted && !self.is_skippable(round_id) {
+                break;
+            }
+            // A skippable round can never be committed, so voting `false` is safe, and helps
+            // others move on, too.
+            let has_voted = match self.active_validator.as_ref().map(|av| av.idx) {
+                Some(our_idx) => self.round_mut(round_id).has_voted(our_idx),
+                None => true,
+            };
+            if !has_voted && !self.paused {
+                outcomes.extend(self.sign_and_gossip(round_id, Content::Vote(accepted)));
+            }
+            outcomes.extend(self.enter_round(round_id + 1, now));
+            outcomes.extend(self.finalize_rounds(now));
+        }
+        outcomes
+    }
+}
+
+impl<I, C> ConsensusProtocol<I, C> for RoundRobinProtocol<I, C>
+where
+    I: NodeIdT,
+    C: Context + 'static,
+{
+    fn handle_message(
+        &mut self,
+        sender: I,
+        msg: Vec<u8>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let message: Message<C> = match bincode::deserialize(msg.as_slice()) {
+            Err(err) => {
+                return vec![ProtocolOutcome::InvalidIncomingMessage(
+                    msg,
+                    sender,
+                    err.into(),
+                )]
+            }
+            Ok(message) => message,
+        };
+        let result = match message {
+            Message::Evidence(signed_msg0, signed_msg1) => {
+                self.handle_evidence(signed_msg0, signed_msg1, now)
+            }
+            _ if self.evidence_only => {
+                trace!("received an irrelevant message");
+                Ok(vec![])
+            }
+            Message::Proposal { proposal, echo } => {
+                self.handle_proposal(sender.clone(), proposal, echo, now)
+            }
+            Message::Signed(signed_msg) => self.handle_signed_message(signed_msg, now),
+            Message::SyncRequest {
+                instance_id,
+                first_round_id,
+            } => self.handle_sync_request(sender.clone(), instance_id, first_round_id),
+        };
+        match result {
+            Ok(outcomes) => outcomes,
+            Err(err) => vec![ProtocolOutcome::InvalidIncomingMessage(
+                msg,
+                sender,
+                err.into(),
+            )],
+        }
+    }
+
+    fn handle_timer(&mut self, now: Timestamp, timer_id: TimerId) -> ProtocolOutcomes<I, C> {
+        match timer_id {
+            TIMER_ID_UPDATE => self.update(now),
+            TIMER_ID_ROUND_TIMEOUT => self.handle_round_timeout(now),
+            TIMER_ID_SYNC => {
+                if self.evidence_only || self.finalized_switch_block {
+                    return vec![];
+                }
+                let mut outcomes = vec![ProtocolOutcome::CreatedMessageToRandomPeer(
+                    self.sync_request().serialize(),
+                )];
+                if let Some(interval) = self.config.sync_interval {
+                    outcomes.push(ProtocolOutcome::ScheduleTimer(now + interval, timer_id));
+                }
+                outcomes
+            }
+            _ => unreachable!("unexpected timer ID"),
+        }
+    }
+
+    fn handle_is_current(&self, now: Timestamp) -> ProtocolOutcomes<I, C> {
+        // Request latest protocol state of the current era.
+        let mut outcomes = vec![ProtocolOutcome::CreatedMessageToRandomPeer(
+            self.sync_request().serialize(),
+        )];
+        // If configured, schedule periodic sync requests.
+        if let Some(interval) = self.config.sync_interval {
+            outcomes.push(ProtocolOutcome::ScheduleTimer(
+                now + interval,
+                TIMER_ID_SYNC,
+            ));
+        }
+        outcomes
+    }
+
+    fn handle_action(&mut self, _action_id: ActionId, _now: Timestamp) -> ProtocolOutcomes<I, C> {
+        unreachable!("the round-robin protocol doesn't queue actions")
+    }
+
+    fn propose(
+        &mut self,
+        proposed_block: ProposedBlock<C>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let (value, block_context) = proposed_block.destructure();
+        let (round_id, maybe_parent_round_id) = match self.pending_proposal.take() {
+            Some((pending_context, round_id, maybe_parent_round_id))
+                if pending_context == block_context && round_id == self.current_round_id =>
+            {
+                (round_id, maybe_parent_round_id)
+            }
+            maybe_pending_proposal => {
+                debug!(timestamp = %block_context.timestamp(), "dropping outdated proposal");
+                self.pending_proposal = maybe_pending_proposal;
+                return vec![];
+            }
+        };
+        let proposal = Proposal {
+            timestamp: block_context.timestamp(),
+            maybe_block: Some(value),
+            maybe_parent_round_id,
+        };
+        let mut outcomes = self.create_proposal(round_id, proposal);
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        maybe_signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let av = match &mut self.active_validator {
+            Some(av) => av,
+            None => return vec![],
+        };
+        let signature = match maybe_signature {
+            Some(signature) => signature,
+            None => {
+                if let Some(unsigned) = av.unsigned.get_mut(&hash) {
+                    error!(
+                        %hash, round_id = %unsigned.round_id,
+                        "failed to sign message; retrying on next update"
+                    );
+                    unsigned.awaiting_signature = false;
+                }
+                return vec![];
+            }
+        };
+        let unsigned = match av.unsigned.remove(&hash) {
+            Some(unsigned) => unsigned,
+            None => {
+                warn!(%hash, "received signature that was not requested");
+                return vec![];
+            }
+        };
+        let signed_msg = SignedMessage {
+            round_id: unsigned.round_id,
+            instance_id: self.instance_id,
+            content: unsigned.content,
+            validator_idx: av.idx,
+            signature,
+        };
+        let mut outcomes = self.gossip_own_message(signed_msg, unsigned.maybe_proposal);
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
+    fn resolve_validity(
+        &mut self,
+        proposed_block: ProposedBlock<C>,
+        valid: bool,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<I, C> {
+        let proposals = self
+            .pending_values
+            .remove(&proposed_block)
+            .unwrap_or_default();
+        if !valid {
+            warn!(?proposed_block, "proposal is invalid");
+        }
+        for (round_id, hash) in proposals {
+            let validation = if valid {
+                Validation::Valid
+            } else {
+                Validation::Invalid
+            };
+            self.set_validation(round_id, hash, validation);
+        }
+        self.update(now)
+    }
+
+    fn activate_validator(
+        &mut self,
+        our_id: C::ValidatorId,
+        secret: C::ValidatorSecret,
+        now: Timestamp,
+        _unit_hash_file: Option<PathBuf>,
+        slashing_protection: Option<SlashingProtection<C>>,
+    ) -> ProtocolOutcomes<I, C> {
+        let idx = match self.validators.get_index(&our_id) {
+            Some(idx) => idx,
+            None => {
+                error!(%our_id, "not a validator in this era");
+                return vec![];
+            }
+        };
+        self.active_validator = Some(ActiveValidator {
+            idx,
+            secret,
+            unsigned: HashMap::new(),
+            slashing_protection,
+        });
+        let mut outcomes = vec![
+            ProtocolOutcome::ScheduleTimer(self.era_start_time.max(now), TIMER_ID_UPDATE),
+            ProtocolOutcome::ScheduleTimer(
+                self.current_round_start + self.config.proposal_timeout,
+                TIMER_ID_ROUND_TIMEOUT,
+            ),
+        ];
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
+    fn deactivate_validator(&mut self) {
+        self.active_validator = None;
+    }
+
+    fn set_evidence_only(&mut self) {
+        self.rounds.clear();
+        self.pending_values.clear();
+        self.pending_proposal = None;
+        self.evidence_only = true;
+    }
+
+    fn has_evidence(&self, vid: &C::ValidatorId) -> bool {
+        self.validators.get_index(vid).map_or(false, |idx| {
+            matches!(self.faults.get(&idx), Some(Fault::Direct(..)))
+        })
+    }
+
+    fn mark_faulty(&mut self, vid: &C::ValidatorId) {
+        if let Some(idx) = self.validators.get_index(vid) {
+            self.faults.entry(idx).or_insert(Fault::Indirect);
+        }
+    }
+
+    fn request_evidence(&self, sender: I, vid: &C::ValidatorId) -> ProtocolOutcomes<I, C> {
+        self.validators
+            .get_index(vid)
+            .and_then(|idx| match self.faults.get(&idx) {
+                Some(Fault::Direct(signed_msg0, signed_msg1)) => {
+                    let msg = Message::Evidence(signed_msg0.clone(), signed_msg1.clone());
+                    Some(ProtocolOutcome::CreatedTargetedMessage(
+                        msg.serialize(),
+                        sender,
+                    ))
+                }
+                Some(Fault::Banned) | Some(Fault::Indirect) | None => None,
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Always returns `None`: `EraEvidence` only supports Highway evidence.
+    fn serialized_evidence(&self, _vid: &C::ValidatorId) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Sets the pause status: While paused we don't propose, echo or vote.
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn validators_with_evidence(&self) -> Vec<&C::ValidatorId> {
+        self.faults
+            .iter()
+            .filter(|(_, fault)| matches!(fault, Fault::Direct(..)))
+            .filter_map(|(idx, _)| self.validators.id(*idx))
+            .collect()
+    }
+
+    fn has_received_messages(&self) -> bool {
+        !self.rounds.is_empty()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn is_active(&self) -> bool {
+        self.active_validator.is_some()
+    }
+
+    fn instance_id(&self) -> &C::InstanceId {
+        &self.instance_id
+    }
+
+    fn next_round_length(&self) -> Option<TimeDiff> {
+        self.active_validator
+            .as_ref()
+            .map(|_| self.config.proposal_timeout)
+    }
+}
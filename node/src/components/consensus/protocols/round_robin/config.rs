@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use datasize::DataSize;
+
+use crate::types::TimeDiff;
+
+/// Round-robin-specific configuration.
+/// NOTE: This is *NOT* protocol configuration that has to be the same on all nodes.
+#[derive(DataSize, Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// If a round's proposal has not been accepted after this long, vote to skip the round.
+    pub proposal_timeout: TimeDiff,
+    /// Request the latest protocol state from a random peer periodically, with this interval.
+    pub sync_interval: Option<TimeDiff>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            proposal_timeout: "10sec".parse().unwrap(),
+            sync_interval: Some("5sec".parse().unwrap()),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+use datasize::DataSize;
+use derive_more::Display;
+use tempfile::tempdir;
+
+use casper_hashing::Digest;
+use casper_types::{system::auction::BLOCK_REWARD, EraId, PublicKey, SecretKey, U512};
+
+use super::{Content, Message, RoundRobinProtocol, SignedMessage, TIMER_ID_UPDATE};
+use crate::{
+    components::consensus::{
+        cl_context::{ClContext, Keypair},
+        config::Config,
+        consensus_protocol::{
+            ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+        },
+        highway_core::slashing_protection::{MessageRecord, SlashingProtection},
+        tests::utils::{new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
+        traits::Context,
+    },
+    crypto::{self, RemoteSigner},
+    types::{BlockPayload, Timestamp},
+};
+
+#[derive(DataSize, Debug, Ord, PartialOrd, Copy, Clone, Display, Hash, Eq, PartialEq)]
+pub(crate) struct NodeId(pub usize);
+
+const INSTANCE_ID_DATA: &[u8; 1] = &[123u8; 1];
+
+fn bob_secret_key() -> Arc<SecretKey> {
+    Arc::new(SecretKey::ed25519_from_bytes([1; SecretKey::ED25519_LENGTH]).unwrap())
+}
+
+fn new_test_round_robin<I, T>(weights: I) -> Box<dyn ConsensusProtocol<NodeId, ClContext>>
+where
+    I: IntoIterator<Item = (PublicKey, T)>,
+    T: Into<U512>,
+{
+    let weights = weights
+        .into_iter()
+        .map(|(pk, w)| (pk, w.into()))
+        .collect::<Vec<_>>();
+    let chainspec = new_test_chainspec(weights.clone());
+    let (rr_proto, outcomes) = RoundRobinProtocol::<NodeId, ClContext>::new_boxed(
+        ClContext::hash(INSTANCE_ID_DATA),
+        weights.into_iter().collect(),
+        &None.into_iter().collect(),
+        &None.into_iter().collect(),
+        &(&chainspec).into(),
+        &Config::default(),
+        None,
+        Timestamp::zero(),
+        0,
+        Timestamp::zero(),
+    );
+    assert!(outcomes.is_empty());
+    rr_proto
+}
+
+/// Handles all outcomes, delivering messages between the instances and proposing empty blocks
+/// whenever requested, until nothing is left to do. Returns each instance's finalized blocks.
+fn run_network(
+    instances: &mut [Box<dyn ConsensusProtocol<NodeId, ClContext>>],
+    initial_outcomes: Vec<(usize, ProtocolOutcomes<NodeId, ClContext>)>,
+    now: Timestamp,
+) -> Vec<Vec<FinalizedBlock<ClContext>>> {
+    let mut finalized = vec![vec![]; instances.len()];
+    let mut queue: VecDeque<(usize, ProtocolOutcome<NodeId, ClContext>)> = initial_outcomes
+        .into_iter()
+        .flat_map(|(idx, outcomes)| outcomes.into_iter().map(move |outcome| (idx, outcome)))
+        .collect();
+    while let Some((idx, outcome)) = queue.pop_front() {
+        let new_outcomes: Vec<(usize, ProtocolOutcomes<NodeId, ClContext>)> = match outcome {
+            ProtocolOutcome::CreatedGossipMessage(msg) => (0..instances.len())
+                .filter(|other_idx| *other_idx != idx)
+                .map(|other_idx| {
+                    let outcomes =
+                        instances[other_idx].handle_message(NodeId(idx), msg.clone(), now);
+                    (other_idx, outcomes)
+                })
+                .collect(),
+            ProtocolOutcome::CreatedTargetedMessage(msg, NodeId(recipient)) => {
+                vec![(
+                    recipient,
+                    instances[recipient].handle_message(NodeId(idx), msg, now),
+                )]
+            }
+            ProtocolOutcome::CreateNewBlock(block_context) => {
+                let random_bit = block_context.height() % 2 == 1;
+                let block_payload = BlockPayload::new(vec![], vec![], vec![], random_bit);
+                let proposed_block = ProposedBlock::new(Arc::new(block_payload), block_context);
+                vec![(idx, instances[idx].propose(proposed_block, now))]
+            }
+            ProtocolOutcome::ValidateConsensusValue { proposed_block, .. } => {
+                vec![(
+                    idx,
+                    instances[idx].resolve_validity(proposed_block, true, now),
+                )]
+            }
+            ProtocolOutcome::FinalizedBlock(finalized_block) => {
+                finalized[idx].push(finalized_block);
+                vec![]
+            }
+            ProtocolOutcome::InvalidIncomingMessage(_, sender, err) => {
+                panic!("invalid message from {}: {}", sender, err)
+            }
+            ProtocolOutcome::ScheduleTimer(..) | ProtocolOutcome::CreatedMessageToRandomPeer(_) => {
+                vec![]
+            }
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        };
+        queue.extend(
+            new_outcomes.into_iter().flat_map(|(idx, outcomes)| {
+                outcomes.into_iter().map(move |outcome| (idx, outcome))
+            }),
+        );
+    }
+    finalized
+}
+
+#[test]
+fn round_robin_handle_message_parse_error() {
+    let mut rr_proto = new_test_round_robin(vec![(ALICE_PUBLIC_KEY.clone(), 100)]);
+    let sender = NodeId(123);
+    let msg = vec![];
+    let mut outcomes = rr_proto.handle_message(sender, msg.clone(), Timestamp::zero());
+    assert_eq!(outcomes.len(), 1);
+    match outcomes.pop() {
+        Some(ProtocolOutcome::InvalidIncomingMessage(invalid_msg, offending_sender, _err)) => {
+            assert_eq!(invalid_msg, msg);
+            assert_eq!(offending_sender, sender);
+        }
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+}
+
+#[test]
+fn single_validator_finalizes_era() {
+    let mut instances = vec![new_test_round_robin(vec![(ALICE_PUBLIC_KEY.clone(), 100)])];
+    let now = Timestamp::zero();
+    let keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let outcomes =
+        instances[0].activate_validator(ALICE_PUBLIC_KEY.clone(), keypair, now, None, None);
+    let finalized = run_network(&mut instances, vec![(0, outcomes)], now);
+
+    // The test chainspec has eras with exactly two blocks.
+    let heights: Vec<u64> = finalized[0].iter().map(|fb| fb.relative_height).collect();
+    assert_eq!(vec![0, 1], heights);
+    assert!(finalized[0][0].terminal_block_data.is_none());
+    let terminal_block_data = finalized[0][1]
+        .terminal_block_data
+        .as_ref()
+        .expect("second block should be the switch block");
+    assert_eq!(
+        Some(&(2 * BLOCK_REWARD)),
+        terminal_block_data.rewards.get(&*ALICE_PUBLIC_KEY)
+    );
+}
+
+#[test]
+fn slashing_protection_prevents_conflicting_messages() {
+    let tmp_dir = tempdir().unwrap();
+    let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+    let open_store = |file_name: &str| {
+        let path = tmp_dir.path().join(file_name);
+        SlashingProtection::<ClContext>::open(path, EraId::from(0), instance_id)
+            .expect("opening slashing protection store should succeed")
+    };
+    let run_alice = |slashing_protection: SlashingProtection<ClContext>| {
+        let mut instances = vec![new_test_round_robin(vec![(ALICE_PUBLIC_KEY.clone(), 100)])];
+        let keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+        let now = Timestamp::zero();
+        let outcomes = instances[0].activate_validator(
+            ALICE_PUBLIC_KEY.clone(),
+            keypair,
+            now,
+            None,
+            Some(slashing_protection),
+        );
+        run_network(&mut instances, vec![(0, outcomes)], now).remove(0)
+    };
+
+    // Alice finalizes the era, and her votes are recorded.
+    assert_eq!(2, run_alice(open_store("alice.jsonl")).len());
+    let mut store = open_store("alice.jsonl");
+    for round_id in 0..2 {
+        let vote = MessageRecord::Vote {
+            round_id,
+            vote: false,
+        };
+        assert!(store.record_message(vote).is_err());
+    }
+
+    // If Alice's key already echoed a different proposal in the first round, e.g. on another
+    // machine, she refuses to echo her own proposal, so nothing is finalized.
+    let mut store = open_store("other_machine.jsonl");
+    let echo = MessageRecord::Echo {
+        round_id: 0,
+        proposal_hash: ClContext::hash(&[0]),
+    };
+    store.record_message(echo).unwrap();
+    assert!(run_alice(open_store("other_machine.jsonl")).is_empty());
+}
+
+#[test]
+fn two_validators_agree_on_blocks() {
+    let weights = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let mut instances = vec![
+        new_test_round_robin(weights.clone()),
+        new_test_round_robin(weights),
+    ];
+    let now = Timestamp::zero();
+    let alice_keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let bob_keypair = Keypair::from(bob_secret_key());
+    let outcomes = vec![
+        (
+            0,
+            instances[0].activate_validator(
+                ALICE_PUBLIC_KEY.clone(),
+                alice_keypair,
+                now,
+                None,
+                None,
+            ),
+        ),
+        (
+            1,
+            instances[1].activate_validator(BOB_PUBLIC_KEY.clone(), bob_keypair, now, None, None),
+        ),
+    ];
+    let finalized = run_network(&mut instances, outcomes, now);
+
+    assert_eq!(2, finalized[0].len());
+    assert_eq!(finalized[0], finalized[1]);
+    let terminal_block_data = finalized[0][1]
+        .terminal_block_data
+        .as_ref()
+        .expect("second block should be the switch block");
+    assert_eq!(
+        Some(&BLOCK_REWARD),
+        terminal_block_data.rewards.get(&*BOB_PUBLIC_KEY)
+    );
+}
+
+#[test]
+fn conflicting_votes_are_evidence() {
+    let weights = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let mut rr_proto = new_test_round_robin(weights);
+    let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+    let bob_keypair = Keypair::from(bob_secret_key());
+    let bob_idx = rr_proto
+        .as_any()
+        .downcast_ref::<RoundRobinProtocol<NodeId, ClContext>>()
+        .unwrap()
+        .validators
+        .get_index(&*BOB_PUBLIC_KEY)
+        .unwrap();
+    let vote = |vote| {
+        let content = Content::Vote(vote);
+        let signed_msg = SignedMessage::sign(0, instance_id, content, bob_idx, &bob_keypair);
+        Message::<ClContext>::Signed(signed_msg).serialize()
+    };
+    let now = Timestamp::zero();
+
+    let outcomes = rr_proto.handle_message(NodeId(1), vote(true), now);
+    assert!(outcomes.is_empty());
+    assert!(!rr_proto.has_evidence(&*BOB_PUBLIC_KEY));
+
+    let outcomes = rr_proto.handle_message(NodeId(1), vote(false), now);
+    assert!(outcomes.iter().any(
+        |outcome| matches!(outcome, ProtocolOutcome::NewEvidence(vid) if *vid == *BOB_PUBLIC_KEY)
+    ));
+    assert!(rr_proto.has_evidence(&*BOB_PUBLIC_KEY));
+    assert_eq!(vec![&*BOB_PUBLIC_KEY], rr_proto.validators_with_evidence());
+}
+
+/// Returns the hashes of all requested signatures, and panics if anything is gossiped.
+fn requested_signatures(outcomes: ProtocolOutcomes<NodeId, ClContext>) -> Vec<Digest> {
+    outcomes
+        .into_iter()
+        .filter_map(|outcome| match outcome {
+            ProtocolOutcome::CreateSignature(hash) => Some(hash),
+            ProtocolOutcome::CreatedGossipMessage(_) => panic!("gossiped unsigned message"),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn defers_messages_until_signed() {
+    let mut rr_proto = new_test_round_robin(vec![(ALICE_PUBLIC_KEY.clone(), 100)]);
+    let now = Timestamp::zero();
+    // The remote signer can never sign synchronously, so every signature must be requested.
+    let signer = RemoteSigner::new(
+        "/nonexistent/signer.sock".into(),
+        ALICE_PUBLIC_KEY.clone(),
+        Duration::from_secs(1),
+        1,
+    );
+    let keypair = Keypair::new(Arc::new(signer));
+    let outcomes = rr_proto.activate_validator(ALICE_PUBLIC_KEY.clone(), keypair, now, None, None);
+    let block_context = outcomes
+        .into_iter()
+        .find_map(|outcome| match outcome {
+            ProtocolOutcome::CreateNewBlock(block_context) => Some(block_context),
+            _ => None,
+        })
+        .expect("should request a block");
+    let block_payload = BlockPayload::new(vec![], vec![], vec![], false);
+    let proposed_block = ProposedBlock::new(Arc::new(block_payload), block_context);
+
+    // The proposal is only gossiped once its echo is signed, and no other block is requested.
+    let outcomes = rr_proto.propose(proposed_block, now);
+    assert!(!outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::CreateNewBlock(_))));
+    let echo_hashes = requested_signatures(outcomes);
+    assert_eq!(1, echo_hashes.len());
+    let echo_signature = crypto::sign(echo_hashes[0], &ALICE_SECRET_KEY, &ALICE_PUBLIC_KEY);
+    let outcomes = rr_proto.handle_signature(echo_hashes[0], Some(echo_signature), now);
+    let mut gossiped = 0;
+    let mut vote_hashes = vec![];
+    for outcome in outcomes {
+        match outcome {
+            ProtocolOutcome::CreatedGossipMessage(msg) => {
+                let message: Message<ClContext> = bincode::deserialize(&msg).unwrap();
+                assert!(matches!(message, Message::Proposal { .. }));
+                gossiped += 1;
+            }
+            ProtocolOutcome::CreateSignature(hash) => vote_hashes.push(hash),
+            _ => (),
+        }
+    }
+    assert_eq!(1, gossiped);
+    assert_eq!(1, vote_hashes.len());
+
+    // If signing the vote fails, it is requested again on the next update.
+    assert!(rr_proto
+        .handle_signature(vote_hashes[0], None, now)
+        .is_empty());
+    let outcomes = rr_proto.handle_timer(now, TIMER_ID_UPDATE);
+    assert_eq!(vote_hashes, requested_signatures(outcomes));
+
+    // Signatures that were not requested are ignored.
+    let unrequested_signature = crypto::sign(echo_hashes[0], &ALICE_SECRET_KEY, &ALICE_PUBLIC_KEY);
+    assert!(rr_proto
+        .handle_signature(echo_hashes[0], Some(unrequested_signature), now)
+        .is_empty());
+}
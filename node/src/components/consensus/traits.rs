@@ -48,7 +48,9 @@ pub trait ValidatorSecret: Send + DataSize {
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the hash if that is possible without waiting. Otherwise returns `None`, and the
+    /// signature has to be requested via `ProtocolOutcome::CreateSignature`.
+    fn try_sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
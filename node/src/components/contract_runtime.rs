@@ -3,6 +3,9 @@ pub(crate) mod announcements;
 mod config;
 mod error;
 mod operations;
+mod pruning;
+#[cfg(test)]
+mod tests;
 mod types;
 
 use std::{
@@ -17,7 +20,7 @@ use datasize::DataSize;
 use lmdb::DatabaseFlags;
 use prometheus::{self, Histogram, HistogramOpts, IntGauge, Registry};
 use serde::Serialize;
-use tracing::{debug, info, trace};
+use tracing::{debug, error, info, trace};
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -34,10 +37,14 @@ use casper_hashing::Digest;
 use casper_types::ProtocolVersion;
 
 use crate::{
-    components::{contract_runtime::types::StepEffectAndUpcomingEraValidators, Component},
+    components::{
+        contract_runtime::{pruning::TrieStorePruning, types::StepEffectAndUpcomingEraValidators},
+        Component,
+    },
     effect::{
-        announcements::ControlAnnouncement, requests::ContractRuntimeRequest, EffectBuilder,
-        EffectExt, Effects,
+        announcements::ControlAnnouncement,
+        requests::{ContractRuntimeRequest, StorageRequest},
+        EffectBuilder, EffectExt, Effects,
     },
     fatal,
     types::{BlockHash, BlockHeader, Chainspec, Deploy, FinalizedBlock},
@@ -45,7 +52,8 @@ use crate::{
 };
 pub(crate) use announcements::ContractRuntimeAnnouncement;
 pub(crate) use config::Config;
-pub(crate) use error::{BlockExecutionError, ConfigError};
+pub(crate) use error::{BlockExecutionError, ConfigError, PruneError};
+pub(crate) use pruning::{check_prune_retained_eras, prune_global_state};
 pub(crate) use types::{BlockAndExecutionEffects, EraValidatorsRequest};
 
 /// State to use to construct the next block in the blockchain. Includes the state root hash for the
@@ -108,6 +116,9 @@ pub(crate) struct ContractRuntime {
 
     /// Finalized blocks waiting for their pre-state hash to start executing.
     exec_queue: ExecQueue,
+
+    /// Background pruning of the trie store, if enabled.
+    trie_store_pruning: Option<Arc<TrieStorePruning>>,
 }
 
 impl Debug for ContractRuntime {
@@ -128,6 +139,8 @@ pub(crate) struct ContractRuntimeMetrics {
     get_validator_weights: Histogram,
     get_era_validators: Histogram,
     get_bids: Histogram,
+    get_keys_by_prefix: Histogram,
+    get_dictionary_items: Histogram,
     missing_trie_keys: Histogram,
     put_trie: Histogram,
     get_trie: Histogram,
@@ -166,6 +179,11 @@ const GET_ERA_VALIDATORS_NAME: &str = "contract_runtime_get_era_validators";
 const GET_ERA_VALIDATORS_HELP: &str = "tracking run of engine_state.get_era_validators in seconds.";
 const GET_BIDS_NAME: &str = "contract_runtime_get_bids";
 const GET_BIDS_HELP: &str = "tracking run of engine_state.get_bids in seconds.";
+const GET_KEYS_BY_PREFIX_NAME: &str = "contract_runtime_get_keys_by_prefix";
+const GET_KEYS_BY_PREFIX_HELP: &str = "tracking run of engine_state.get_keys_by_prefix in seconds.";
+const GET_DICTIONARY_ITEMS_NAME: &str = "contract_runtime_get_dictionary_items";
+const GET_DICTIONARY_ITEMS_HELP: &str =
+    "tracking run of engine_state.get_dictionary_items in seconds.";
 const GET_TRIE_NAME: &str = "contract_runtime_get_trie";
 const GET_TRIE_HELP: &str = "tracking run of engine_state.get_trie in seconds.";
 const PUT_TRIE_NAME: &str = "contract_runtime_put_trie";
@@ -224,6 +242,16 @@ impl ContractRuntimeMetrics {
                 GET_ERA_VALIDATORS_HELP,
             )?,
             get_bids: register_histogram_metric(registry, GET_BIDS_NAME, GET_BIDS_HELP)?,
+            get_keys_by_prefix: register_histogram_metric(
+                registry,
+                GET_KEYS_BY_PREFIX_NAME,
+                GET_KEYS_BY_PREFIX_HELP,
+            )?,
+            get_dictionary_items: register_histogram_metric(
+                registry,
+                GET_DICTIONARY_ITEMS_NAME,
+                GET_DICTIONARY_ITEMS_HELP,
+            )?,
             get_trie: register_histogram_metric(registry, GET_TRIE_NAME, GET_TRIE_HELP)?,
             put_trie: register_histogram_metric(registry, PUT_TRIE_NAME, PUT_TRIE_HELP)?,
             missing_trie_keys: register_histogram_metric(
@@ -241,6 +269,7 @@ where
     REv: From<ContractRuntimeRequest>
         + From<ContractRuntimeAnnouncement>
         + From<ControlAnnouncement>
+        + From<StorageRequest>
         + Send,
 {
     type Event = ContractRuntimeRequest;
@@ -435,6 +464,7 @@ where
                 let metrics = Arc::clone(&self.metrics);
                 let exec_queue = Arc::clone(&self.exec_queue);
                 let execution_pre_state = Arc::clone(&self.execution_pre_state);
+                let trie_store_pruning = self.trie_store_pruning.clone();
                 let protocol_version = self.protocol_version;
                 if self.execution_pre_state.lock().unwrap().next_block_height
                     == finalized_block.height()
@@ -445,6 +475,7 @@ where
                             metrics,
                             exec_queue,
                             execution_pre_state,
+                            trie_store_pruning,
                             effect_builder,
                             protocol_version,
                             finalized_block,
@@ -461,6 +492,40 @@ where
                 }
                 effects
             }
+            ContractRuntimeRequest::SpeculativeDeployExecution {
+                state_root_hash,
+                block_time,
+                protocol_version,
+                deploy,
+                trace,
+                responder,
+            } => {
+                trace!(%state_root_hash, deploy_hash = %deploy.id(), "speculative execution");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    // Execution is blocking, so it mustn't run on the async executor's threads.
+                    let result = tokio::task::spawn_blocking(move || {
+                        operations::execute_only(
+                            engine_state.as_ref(),
+                            metrics.as_ref(),
+                            state_root_hash,
+                            block_time,
+                            protocol_version,
+                            *deploy,
+                            trace,
+                        )
+                    })
+                    .await
+                    .unwrap_or_else(|error| {
+                        error!(%error, "speculative execution task failed");
+                        Ok(None)
+                    });
+                    trace!(?result, "speculative execution result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::GetBids {
                 get_bids_request,
                 responder,
@@ -478,6 +543,49 @@ where
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                responder,
+            } => {
+                trace!(?get_keys_by_prefix_request, "get keys by prefix request");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    let correlation_id = CorrelationId::new();
+                    let start = Instant::now();
+                    let result =
+                        engine_state.get_keys_by_prefix(correlation_id, get_keys_by_prefix_request);
+                    metrics
+                        .get_keys_by_prefix
+                        .observe(start.elapsed().as_secs_f64());
+                    trace!(?result, "get keys by prefix result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                responder,
+            } => {
+                trace!(
+                    ?get_dictionary_items_request,
+                    "get dictionary items request"
+                );
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    let correlation_id = CorrelationId::new();
+                    let start = Instant::now();
+                    let result = engine_state
+                        .get_dictionary_items(correlation_id, get_dictionary_items_request);
+                    metrics
+                        .get_dictionary_items
+                        .observe(start.elapsed().as_secs_f64());
+                    trace!(?result, "get dictionary items result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
         }
     }
 }
@@ -525,12 +633,23 @@ impl ContractRuntime {
 
         let metrics = Arc::new(ContractRuntimeMetrics::new(registry)?);
 
+        let trie_store_pruning =
+            contract_runtime_config
+                .prune_retained_eras()
+                .map(|retained_eras| {
+                    Arc::new(TrieStorePruning::new(
+                        retained_eras,
+                        contract_runtime_config.prune_batch_size(),
+                    ))
+                });
+
         Ok(ContractRuntime {
             execution_pre_state,
             protocol_version,
             exec_queue: Arc::new(Mutex::new(BTreeMap::new())),
             engine_state,
             metrics,
+            trie_store_pruning,
         })
     }
 
@@ -588,6 +707,7 @@ impl ContractRuntime {
         metrics: Arc<ContractRuntimeMetrics>,
         exec_queue: ExecQueue,
         execution_pre_state: Arc<Mutex<ExecutionPreState>>,
+        trie_store_pruning: Option<Arc<TrieStorePruning>>,
         effect_builder: EffectBuilder<REv>,
         protocol_version: ProtocolVersion,
         finalized_block: FinalizedBlock,
@@ -597,12 +717,15 @@ impl ContractRuntime {
         REv: From<ContractRuntimeRequest>
             + From<ContractRuntimeAnnouncement>
             + From<ControlAnnouncement>
+            + From<StorageRequest>
             + Send,
     {
         let current_execution_pre_state = execution_pre_state.lock().unwrap().clone();
+        let engine_state_for_pruning = Arc::clone(&engine_state);
         let BlockAndExecutionEffects {
             block,
             execution_results,
+            contract_events,
             maybe_step_effect_and_upcoming_era_validators,
         } = match tokio::task::unconstrained(async move {
             operations::execute_finalized_block(
@@ -624,9 +747,21 @@ impl ContractRuntime {
         let new_execution_pre_state = ExecutionPreState::from(block.header());
         *execution_pre_state.lock().unwrap() = new_execution_pre_state.clone();
 
+        let maybe_pruning = trie_store_pruning.and_then(|trie_store_pruning| {
+            trie_store_pruning
+                .block_executed(block.header())
+                .map(|recent_state_root_hashes| (trie_store_pruning, recent_state_root_hashes))
+        });
+
         let current_era_id = block.header().era_id();
 
-        announcements::linear_chain_block(effect_builder, block, execution_results).await;
+        announcements::linear_chain_block(
+            effect_builder,
+            block,
+            execution_results,
+            contract_events,
+        )
+        .await;
 
         if let Some(StepEffectAndUpcomingEraValidators {
             step_execution_effect,
@@ -655,6 +790,18 @@ impl ContractRuntime {
                 .enqueue_block_for_execution(finalized_block, deploys, transfers)
                 .await
         }
+
+        // Prune after a switch block, once the next block is enqueued so its execution isn't held
+        // up.
+        if let Some((trie_store_pruning, recent_state_root_hashes)) = maybe_pruning {
+            trie_store_pruning
+                .prune(
+                    effect_builder,
+                    engine_state_for_pruning,
+                    recent_state_root_hashes,
+                )
+                .await
+        }
     }
 
     /// Returns the engine state, for testing only.
@@ -6,7 +6,7 @@ use std::{
 use serde::Serialize;
 
 use casper_execution_engine::core::engine_state::execution_effect::ExecutionEffect as ExecutionEngineExecutionEffect;
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey, U512};
+use casper_types::{ContractEvent, EraId, ExecutionEffect, ExecutionResult, PublicKey, U512};
 
 use crate::{
     effect::{announcements::LinearChainBlock, EffectBuilder},
@@ -40,10 +40,12 @@ impl ContractRuntimeAnnouncement {
     pub(crate) fn linear_chain_block(
         block: Block,
         execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+        contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
     ) -> Self {
         Self::LinearChainBlock(Box::new(LinearChainBlock {
             block,
             execution_results,
+            contract_events,
         }))
     }
 }
@@ -95,6 +97,7 @@ pub(crate) async fn linear_chain_block<REv>(
     effect_builder: EffectBuilder<REv>,
     block: Block,
     execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+    contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
 ) where
     REv: From<ContractRuntimeAnnouncement>,
 {
@@ -103,6 +106,7 @@ pub(crate) async fn linear_chain_block<REv>(
             LinearChainBlock {
                 block,
                 execution_results,
+                contract_events,
             },
         )))
         .await
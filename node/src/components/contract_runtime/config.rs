@@ -6,6 +6,7 @@ use casper_execution_engine::shared::utils;
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+const DEFAULT_PRUNE_BATCH_SIZE: usize = 10_000;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -30,6 +31,16 @@ pub struct Config {
     ///
     /// Defaults to `false`.
     enable_manual_sync: Option<bool>,
+    /// The number of most recent eras whose global state is retained when pruning the trie store
+    /// in the background after each switch block.  Must be at least
+    /// `2 * (unbonding_delay - auction_delay) + 1` and `auction_delay + 1`.
+    ///
+    /// Defaults to `None`, i.e. the trie store is not pruned.
+    prune_retained_eras: Option<u64>,
+    /// The number of trie keys visited per write transaction when pruning the trie store.
+    ///
+    /// Defaults to 10,000.
+    prune_batch_size: Option<usize>,
 }
 
 impl Config {
@@ -52,6 +63,14 @@ impl Config {
     pub(crate) fn manual_sync_enabled(&self) -> bool {
         self.enable_manual_sync.unwrap_or(false)
     }
+
+    pub(crate) fn prune_retained_eras(&self) -> Option<u64> {
+        self.prune_retained_eras
+    }
+
+    pub(crate) fn prune_batch_size(&self) -> usize {
+        self.prune_batch_size.unwrap_or(DEFAULT_PRUNE_BATCH_SIZE)
+    }
 }
 
 impl Default for Config {
@@ -61,6 +80,8 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(false),
+            prune_retained_eras: None,
+            prune_batch_size: Some(DEFAULT_PRUNE_BATCH_SIZE),
         }
     }
 }
@@ -6,8 +6,8 @@ use casper_execution_engine::{
 };
 
 use crate::{
-    components::contract_runtime::ExecutionPreState,
-    types::{error::BlockCreationError, FinalizedBlock},
+    components::{contract_runtime::ExecutionPreState, storage},
+    types::{chainspec, error::BlockCreationError, FinalizedBlock},
 };
 use casper_execution_engine::core::engine_state::GetEraValidatorsError;
 
@@ -20,6 +20,34 @@ pub(crate) enum ConfigError {
     /// Error initializing metrics.
     #[error("failed to initialize metrics for contract runtime: {0}")]
     Prometheus(#[from] prometheus::Error),
+    /// Fewer eras are retained when pruning than the node needs the global state of.
+    #[error(
+        "pruning must retain at least {min_retained_eras} eras of global state, but only \
+         {retained_eras} are configured"
+    )]
+    TooFewPruneRetainedEras {
+        /// The configured number of retained eras.
+        retained_eras: u64,
+        /// The minimum number of retained eras.
+        min_retained_eras: u64,
+    },
+}
+
+/// Error returned from pruning the global state of a node which is not running.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum PruneError {
+    /// Error loading the chainspec.
+    #[error("chainspec error: {0}")]
+    Chainspec(#[from] chainspec::Error),
+    /// Error opening or reading the linear chain storage.
+    #[error("storage error: {0}")]
+    Storage(#[from] storage::Error),
+    /// Error opening the global state.
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    /// Error pruning the trie store.
+    #[error(transparent)]
+    EngineState(#[from] EngineStateError),
 }
 
 /// An error raised by a contract runtime variant.
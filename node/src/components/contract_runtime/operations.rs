@@ -9,14 +9,14 @@ use tracing::{debug, trace};
 use casper_execution_engine::{
     core::engine_state::{
         self, step::EvictItem, DeployItem, EngineState, ExecuteRequest,
-        ExecutionResult as EngineExecutionResult, ExecutionResults, GetEraValidatorsRequest,
-        RewardItem, StepError, StepRequest, StepSuccess,
+        ExecutionResult as EngineExecutionResult, ExecutionResults, ExecutionTrace,
+        GetEraValidatorsRequest, RewardItem, StepError, StepRequest, StepSuccess,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::global_state::lmdb::LmdbGlobalState,
 };
 use casper_hashing::Digest;
-use casper_types::{EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, U512};
+use casper_types::{ContractEvent, EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, U512};
 
 use crate::{
     components::{
@@ -53,6 +53,7 @@ pub(super) fn execute_finalized_block(
     let mut state_root_hash = pre_state_root_hash;
     let mut execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)> =
         HashMap::new();
+    let mut contract_events: HashMap<DeployHash, Vec<ContractEvent>> = HashMap::new();
     // Run any deploys that must be executed
     let block_time = finalized_block.timestamp().millis();
     let start = Instant::now();
@@ -76,9 +77,12 @@ pub(super) fn execute_finalized_block(
 
         trace!(?deploy_hash, ?result, "deploy execution result");
         // As for now a given state is expected to exist.
-        let (state_hash, execution_result) =
+        let (state_hash, execution_result, events) =
             commit_execution_effects(engine_state, metrics, state_root_hash, deploy_hash, result)?;
         execution_results.insert(deploy_hash, (deploy_header, execution_result));
+        if !events.is_empty() {
+            contract_events.insert(deploy_hash, events);
+        }
         state_root_hash = state_hash;
     }
 
@@ -145,18 +149,20 @@ pub(super) fn execute_finalized_block(
     Ok(BlockAndExecutionEffects {
         block,
         execution_results,
+        contract_events,
         maybe_step_effect_and_upcoming_era_validators,
     })
 }
 
-/// Commits the execution effects.
+/// Commits the execution effects, returning the new state root hash, the execution result and the
+/// events emitted by contracts during execution.
 fn commit_execution_effects(
     engine_state: &EngineState<LmdbGlobalState>,
     metrics: &ContractRuntimeMetrics,
     state_root_hash: Digest,
     deploy_hash: DeployHash,
     execution_results: ExecutionResults,
-) -> Result<(Digest, ExecutionResult), BlockExecutionError> {
+) -> Result<(Digest, ExecutionResult, Vec<ContractEvent>), BlockExecutionError> {
     let ee_execution_result = execution_results
         .into_iter()
         .exactly_one()
@@ -187,13 +193,14 @@ fn commit_execution_effects(
             execution_effect
         }
     };
+    let contract_events = execution_effect.events.iter().map(Into::into).collect();
     let new_state_root = commit_transforms(
         engine_state,
         metrics,
         state_root_hash,
         execution_effect.transforms,
     )?;
-    Ok((new_state_root, execution_result))
+    Ok((new_state_root, execution_result, contract_events))
 }
 
 fn commit_transforms(
@@ -225,6 +232,57 @@ fn execute(
     result
 }
 
+/// Executes the given deploy against `state_root_hash` and returns the result without committing
+/// any of its effects.
+///
+/// If the deploy carries no approvals, the deploy's account is used as the sole authorization key
+/// so that unsigned deploys can be dry-run as if signed by their account's main key.
+///
+/// If `trace` is true, a trace of the execution is returned along with the result.
+pub(super) fn execute_only(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: &ContractRuntimeMetrics,
+    state_root_hash: Digest,
+    block_time: u64,
+    protocol_version: ProtocolVersion,
+    deploy: Deploy,
+    trace: bool,
+) -> Result<Option<(ExecutionResult, Option<ExecutionTrace>)>, engine_state::Error> {
+    let account_hash = deploy.header().account().to_account_hash();
+    let mut deploy_item = DeployItem::from(deploy);
+    if deploy_item.authorization_keys.is_empty() {
+        deploy_item.authorization_keys.insert(account_hash);
+    }
+    let execute_request = ExecuteRequest::new(
+        state_root_hash,
+        block_time,
+        vec![deploy_item],
+        protocol_version,
+        PublicKey::System,
+    );
+    if !trace {
+        let results = execute(engine_state, metrics, execute_request)?;
+        return Ok(results
+            .into_iter()
+            .exactly_one()
+            .ok()
+            .map(|ee_execution_result| (ExecutionResult::from(&ee_execution_result), None)));
+    }
+
+    let start = Instant::now();
+    let (results, traces) =
+        engine_state.run_execute_with_execution_traces(CorrelationId::new(), execute_request)?;
+    metrics.run_execute.observe(start.elapsed().as_secs_f64());
+    Ok(results.into_iter().zip(traces).exactly_one().ok().map(
+        |(ee_execution_result, execution_trace)| {
+            (
+                ExecutionResult::from(&ee_execution_result),
+                Some(execution_trace),
+            )
+        },
+    ))
+}
+
 fn commit_step(
     engine_state: &EngineState<LmdbGlobalState>,
     metrics: &ContractRuntimeMetrics,
@@ -0,0 +1,228 @@
+//! Pruning of the global state trie store.
+//!
+//! Every executed block adds trie nodes to the trie store, and nothing is reclaimed otherwise.
+//! Pruning deletes all trie nodes which are not reachable from the state roots of the blocks of
+//! the most recent eras, either in the background after each switch block, or offline via the
+//! `prune-global-state` subcommand.
+
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use datasize::DataSize;
+use prometheus::Registry;
+use tracing::{debug, error, info};
+
+use casper_execution_engine::{
+    core::engine_state::EngineState, shared::newtypes::CorrelationId,
+    storage::global_state::lmdb::LmdbGlobalState,
+};
+use casper_hashing::Digest;
+
+use super::{ConfigError, ContractRuntime, PruneError};
+use crate::{
+    components::storage::Storage,
+    effect::{requests::StorageRequest, EffectBuilder},
+    reactor::participating,
+    types::{BlockHeader, Chainspec},
+    utils::{Loadable, WithDir},
+};
+
+/// Returns the minimum number of most recent eras whose global state must be retained.
+///
+/// The validators of an era are queried from the global state of its key block, i.e. the switch
+/// block of the era before.  The era supervisor looks up the validators of eras up to twice the
+/// number of bonded eras, `unbonding_delay - auction_delay`, back, and the auction needs the state
+/// of the booking blocks `auction_delay` eras back.
+fn min_retained_eras(chainspec: &Chainspec) -> u64 {
+    let auction_delay = chainspec.core_config.auction_delay;
+    let bonded_eras = chainspec
+        .core_config
+        .unbonding_delay
+        .saturating_sub(auction_delay);
+    bonded_eras
+        .saturating_mul(2)
+        .max(auction_delay)
+        .saturating_add(1)
+}
+
+/// Returns an error if fewer than the minimum number of eras the node needs the global state of
+/// are retained.
+pub(crate) fn check_prune_retained_eras(
+    retained_eras: u64,
+    chainspec: &Chainspec,
+) -> Result<(), ConfigError> {
+    let min_retained_eras = min_retained_eras(chainspec);
+    if retained_eras < min_retained_eras {
+        return Err(ConfigError::TooFewPruneRetainedEras {
+            retained_eras,
+            min_retained_eras,
+        });
+    }
+    Ok(())
+}
+
+/// Prunes the trie store in the background after each switch block, retaining the global state
+/// of the most recent eras.
+#[derive(DataSize, Debug)]
+pub(super) struct TrieStorePruning {
+    /// The number of most recent eras whose global state is retained.
+    retained_eras: u64,
+    /// The number of trie keys visited per write transaction.
+    batch_size: usize,
+    /// The state root hashes of the blocks executed since the previous switch block, which might
+    /// not be in storage yet when pruning starts.
+    #[data_size(skip)]
+    recent_state_root_hashes: Mutex<Vec<Digest>>,
+    /// Whether a prune is in progress.
+    #[data_size(skip)]
+    in_progress: AtomicBool,
+}
+
+impl TrieStorePruning {
+    pub(super) fn new(retained_eras: u64, batch_size: usize) -> Self {
+        TrieStorePruning {
+            retained_eras,
+            batch_size,
+            recent_state_root_hashes: Mutex::new(Vec::new()),
+            in_progress: AtomicBool::new(false),
+        }
+    }
+
+    /// Records the state root hash of an executed block.
+    ///
+    /// If the block is a switch block, returns the state root hashes of the blocks executed since
+    /// the previous switch block, which need to be retained when pruning now.
+    pub(super) fn block_executed(&self, block_header: &BlockHeader) -> Option<Vec<Digest>> {
+        let mut recent_state_root_hashes = self
+            .recent_state_root_hashes
+            .lock()
+            .expect("mutex poisoned");
+        recent_state_root_hashes.push(*block_header.state_root_hash());
+        if !block_header.is_switch_block() {
+            return None;
+        }
+        let new_recent_state_root_hashes = vec![*block_header.state_root_hash()];
+        Some(mem::replace(
+            &mut *recent_state_root_hashes,
+            new_recent_state_root_hashes,
+        ))
+    }
+
+    /// Prunes the trie store, retaining the global state of the blocks in storage from the switch
+    /// block `retained_eras` eras back onwards, and the given recently executed blocks.
+    ///
+    /// Does nothing if a prune is already in progress.
+    pub(super) async fn prune<REv>(
+        self: Arc<Self>,
+        effect_builder: EffectBuilder<REv>,
+        engine_state: Arc<EngineState<LmdbGlobalState>>,
+        recent_state_root_hashes: Vec<Digest>,
+    ) where
+        REv: From<StorageRequest>,
+    {
+        if self.in_progress.swap(true, Ordering::SeqCst) {
+            debug!("trie store pruning already in progress");
+            return;
+        }
+        let mut retained_state_root_hashes = effect_builder
+            .get_state_root_hashes_to_retain_from_storage(self.retained_eras)
+            .await;
+        retained_state_root_hashes.extend(recent_state_root_hashes);
+        let retained_count = retained_state_root_hashes.len();
+        info!(retained_count, "pruning trie store");
+
+        let batch_size = self.batch_size;
+        let start = Instant::now();
+        let result = tokio::task::spawn_blocking(move || {
+            engine_state.prune_trie_store(
+                CorrelationId::new(),
+                retained_state_root_hashes,
+                batch_size,
+            )
+        })
+        .await;
+        match result {
+            Ok(Ok(deleted_count)) => info!(
+                deleted_count,
+                elapsed_secs = start.elapsed().as_secs_f64(),
+                "finished pruning trie store"
+            ),
+            Ok(Err(error)) => error!(%error, "failed to prune trie store"),
+            Err(error) => error!(%error, "trie store pruning task failed"),
+        }
+        self.in_progress.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Prunes the trie store of a node which is not running, retaining the global state of the
+/// blocks from the switch block `retained_eras` eras back onwards.
+///
+/// Returns the number of deleted trie nodes.
+pub(crate) fn prune_global_state(
+    config: &WithDir<participating::Config>,
+    retained_eras: u64,
+) -> Result<usize, PruneError> {
+    let chainspec = Chainspec::from_path(config.dir())?;
+    check_prune_retained_eras(retained_eras, &chainspec)?;
+    let storage_config = config.map_ref(|cfg| cfg.storage.clone());
+    let storage = Storage::new(
+        &storage_config,
+        None,
+        chainspec.protocol_config.version,
+        false,
+        &chainspec.network_config.name,
+    )?;
+    let contract_runtime = ContractRuntime::new(
+        chainspec.protocol_config.version,
+        storage.root_path(),
+        &config.value().contract_runtime,
+        chainspec.wasm_config,
+        chainspec.system_costs_config,
+        chainspec.core_config.max_associated_keys,
+        &Registry::new(),
+    )?;
+
+    let retained_state_root_hashes = storage.read_state_root_hashes_to_retain(retained_eras)?;
+    info!(
+        retained_count = retained_state_root_hashes.len(),
+        "pruning trie store"
+    );
+    let deleted_count = contract_runtime.engine_state.prune_trie_store(
+        CorrelationId::new(),
+        retained_state_root_hashes,
+        config.value().contract_runtime.prune_batch_size(),
+    )?;
+    Ok(deleted_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_require_the_state_of_bonded_eras_to_be_retained() {
+        let mut chainspec = Chainspec::from_resources("local");
+        chainspec.core_config.auction_delay = 3;
+        chainspec.core_config.unbonding_delay = 14;
+
+        assert!(check_prune_retained_eras(23, &chainspec).is_ok());
+        assert!(matches!(
+            check_prune_retained_eras(22, &chainspec),
+            Err(ConfigError::TooFewPruneRetainedEras {
+                retained_eras: 22,
+                min_retained_eras: 23,
+            })
+        ));
+
+        // The booking blocks are needed even if validators unbond immediately.
+        chainspec.core_config.unbonding_delay = 3;
+        assert!(check_prune_retained_eras(4, &chainspec).is_ok());
+        assert!(check_prune_retained_eras(3, &chainspec).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use num::Zero;
+use prometheus::Registry;
+use tempfile::TempDir;
+
+use casper_execution_engine::core::engine_state;
+use casper_hashing::Digest;
+use casper_types::{
+    system::auction::DelegationRate, ExecutionResult, Motes, PublicKey, SecretKey, U512,
+};
+
+use super::{operations, Config, ContractRuntime};
+use crate::{
+    crypto::AsymmetricKeyExt,
+    testing::TestRng,
+    types::{
+        chainspec::{AccountConfig, AccountsConfig, ValidatorConfig},
+        Chainspec, Deploy, Timestamp,
+    },
+    utils::Loadable,
+};
+
+/// The amount transferred by the test deploys, which is the minimum for native transfers.
+const TRANSFER_AMOUNT: u64 = 2_500_000_000;
+
+/// A contract runtime with the genesis state committed, in which a single funded account exists.
+struct Fixture {
+    _tmp_dir: TempDir,
+    chainspec: Chainspec,
+    contract_runtime: ContractRuntime,
+    secret_key: SecretKey,
+    genesis_state_root_hash: Digest,
+}
+
+impl Fixture {
+    fn new(rng: &mut TestRng) -> Self {
+        let secret_key = SecretKey::random(rng);
+        let mut chainspec = Chainspec::from_resources("local");
+        let account = AccountConfig::new(
+            PublicKey::from(&secret_key),
+            Motes::new(U512::from(1_000 * TRANSFER_AMOUNT)),
+            Some(ValidatorConfig::new(
+                Motes::new(U512::from(100)),
+                DelegationRate::zero(),
+            )),
+        );
+        chainspec.network_config.accounts_config = AccountsConfig::new(vec![account], vec![]);
+
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let contract_runtime = ContractRuntime::new(
+            chainspec.protocol_config.version,
+            tmp_dir.path(),
+            &Config::default(),
+            chainspec.wasm_config,
+            chainspec.system_costs_config,
+            chainspec.core_config.max_associated_keys,
+            &Registry::new(),
+        )
+        .expect("should create contract runtime");
+        let genesis_state_root_hash = contract_runtime
+            .commit_genesis(&chainspec)
+            .expect("should commit genesis")
+            .post_state_hash;
+
+        Fixture {
+            _tmp_dir: tmp_dir,
+            chainspec,
+            contract_runtime,
+            secret_key,
+            genesis_state_root_hash,
+        }
+    }
+
+    /// Returns a native transfer from the funded account.
+    fn transfer(&self, rng: &mut TestRng) -> Deploy {
+        Deploy::random_native_transfer_from(
+            rng,
+            &self.secret_key,
+            &self.chainspec.network_config.name,
+            U512::from(TRANSFER_AMOUNT),
+        )
+    }
+
+    /// Speculatively executes the deploy against the given state root.
+    fn execute_only(
+        &self,
+        state_root_hash: Digest,
+        deploy: Deploy,
+    ) -> Result<Option<ExecutionResult>, engine_state::Error> {
+        let maybe_result = operations::execute_only(
+            &self.contract_runtime.engine_state,
+            &self.contract_runtime.metrics,
+            state_root_hash,
+            Timestamp::now().millis(),
+            self.chainspec.protocol_config.version,
+            deploy,
+            false,
+        )?;
+        Ok(maybe_result.map(|(execution_result, _)| execution_result))
+    }
+}
+
+fn assert_success(result: Result<Option<ExecutionResult>, engine_state::Error>) {
+    match result {
+        Ok(Some(ExecutionResult::Success { transfers, .. })) => assert_eq!(1, transfers.len()),
+        result => panic!("expected a successful execution, got {:?}", result),
+    }
+}
+
+#[test]
+fn should_speculatively_execute_deploy() {
+    let mut rng = TestRng::new();
+    let fixture = Fixture::new(&mut rng);
+    let deploy = fixture.transfer(&mut rng);
+
+    assert_success(fixture.execute_only(fixture.genesis_state_root_hash, deploy.clone()));
+    // Nothing was committed, so executing the same deploy again has the same result.
+    assert_success(fixture.execute_only(fixture.genesis_state_root_hash, deploy));
+}
+
+#[test]
+fn should_fail_to_speculatively_execute_against_unknown_state_root() {
+    let mut rng = TestRng::new();
+    let fixture = Fixture::new(&mut rng);
+    let deploy = fixture.transfer(&mut rng);
+    let unknown_state_root_hash = Digest::hash(b"unknown state root");
+
+    match fixture.execute_only(unknown_state_root_hash, deploy) {
+        Err(engine_state::Error::RootNotFound(state_root_hash)) => {
+            assert_eq!(unknown_state_root_hash, state_root_hash)
+        }
+        result => panic!("expected RootNotFound, got {:?}", result),
+    }
+}
+
+#[test]
+fn should_speculatively_execute_unsigned_deploy_as_its_account() {
+    let mut rng = TestRng::new();
+    let fixture = Fixture::new(&mut rng);
+    let mut deploy = fixture.transfer(&mut rng);
+    deploy.remove_approvals();
+    assert!(deploy.approvals().is_empty());
+
+    assert_success(fixture.execute_only(fixture.genesis_state_root_hash, deploy));
+}
@@ -4,7 +4,7 @@ use casper_execution_engine::core::engine_state::{
     execution_effect::ExecutionEffect, GetEraValidatorsRequest,
 };
 use casper_hashing::Digest;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, U512};
+use casper_types::{ContractEvent, EraId, ExecutionResult, ProtocolVersion, PublicKey, U512};
 
 use crate::types::{Block, DeployHash, DeployHeader};
 
@@ -98,6 +98,9 @@ pub struct BlockAndExecutionEffects {
     pub block: Block,
     /// The results from executing the deploys in the block.
     pub execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+    /// The events emitted by contracts while executing the deploys in the block, omitting deploys
+    /// which emitted none.
+    pub contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
     /// The [`ExecutionEffect`] and the upcoming validator sets determined by the `step`
     pub maybe_step_effect_and_upcoming_era_validators: Option<StepEffectAndUpcomingEraValidators>,
 }
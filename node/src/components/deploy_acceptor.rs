@@ -15,7 +15,7 @@ use casper_execution_engine::core::engine_state::{
     executable_deploy_item::{
         ContractIdentifier, ContractPackageIdentifier, ExecutableDeployItemIdentifier,
     },
-    ExecutableDeployItem, MAX_PAYMENT,
+    ExecutableDeployItem, DEPLOY_NONCES_PROTOCOL_VERSION, MAX_PAYMENT,
 };
 use casper_hashing::Digest;
 use casper_types::{
@@ -34,7 +34,7 @@ use crate::{
     },
     types::{
         chainspec::DeployConfig, Block, Chainspec, Deploy, DeployConfigurationFailure, NodeId,
-        Timestamp,
+        Timestamp, DEPLOY_SUPERSESSION_PROTOCOL_VERSION,
     },
     utils::Source,
     NodeRng,
@@ -117,6 +117,9 @@ pub(crate) enum DeployParameterFailure {
     /// Module bytes for session code cannot be empty.
     #[error("module bytes for session code cannot be empty")]
     MissingModuleBytes,
+    /// The deploy's nonce has already been used by its account.
+    #[error("nonce {nonce} is not greater than latest nonce {latest_nonce} of the account")]
+    StaleNonce { nonce: u64, latest_nonce: u64 },
 }
 
 /// A helper trait constraining `DeployAcceptor` compatible reactor events.
@@ -181,11 +184,30 @@ impl DeployAcceptor {
         maybe_responder: Option<Responder<Result<(), Error>>>,
     ) -> Effects<Event> {
         let verification_start_timestamp = Timestamp::now();
-        let acceptable_result = deploy.is_config_compliant(
-            &self.chain_name,
-            &self.deploy_config,
-            self.max_associated_keys,
-        );
+        let acceptable_result = deploy
+            .is_config_compliant(
+                &self.chain_name,
+                &self.deploy_config,
+                self.max_associated_keys,
+            )
+            .and_then(|()| {
+                // Nonces can't be used until the protocol version activating them is in effect.
+                if self.protocol_version < DEPLOY_NONCES_PROTOCOL_VERSION
+                    && deploy.header().nonce().is_some()
+                {
+                    debug!(deploy_hash = %deploy.id(), "deploy nonce not yet supported");
+                    return Err(DeployConfigurationFailure::NonceNotSupported);
+                }
+                // Likewise, a deploy can't supersede another one until the protocol version
+                // activating supersession is in effect.
+                if self.protocol_version < DEPLOY_SUPERSESSION_PROTOCOL_VERSION
+                    && deploy.header().supersession().is_some()
+                {
+                    debug!(deploy_hash = %deploy.id(), "deploy supersession not yet supported");
+                    return Err(DeployConfigurationFailure::SupersessionNotSupported);
+                }
+                Ok(())
+            });
         // checks chainspec values
         // DOES NOT check cryptographic security
         if let Err(error) = acceptable_result {
@@ -376,10 +398,11 @@ impl DeployAcceptor {
                         verification_start_timestamp,
                     )
                 } else {
-                    self.verify_payment_logic(
+                    self.verify_nonce(
                         effect_builder,
                         event_metadata,
                         prestate_hash,
+                        account_hash,
                         verification_start_timestamp,
                     )
                 }
@@ -387,6 +410,68 @@ impl DeployAcceptor {
         }
     }
 
+    fn verify_nonce<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: EventMetadata,
+        prestate_hash: Digest,
+        account_hash: AccountHash,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        match event_metadata.deploy.header().nonce() {
+            Some(nonce) => effect_builder
+                .get_account_nonce_from_global_state(prestate_hash, account_hash)
+                .event(move |latest_nonce| Event::GetNonceResult {
+                    event_metadata,
+                    prestate_hash,
+                    nonce,
+                    latest_nonce,
+                    verification_start_timestamp,
+                }),
+            None => self.verify_payment_logic(
+                effect_builder,
+                event_metadata,
+                prestate_hash,
+                verification_start_timestamp,
+            ),
+        }
+    }
+
+    fn handle_get_nonce_result<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: EventMetadata,
+        prestate_hash: Digest,
+        nonce: u64,
+        latest_nonce: u64,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        // Nonces beyond the next expected one are accepted; the block proposer holds them until
+        // their predecessors have been included in a block.
+        if nonce <= latest_nonce {
+            let error = Error::InvalidDeployParameters {
+                prestate_hash,
+                failure: DeployParameterFailure::StaleNonce {
+                    nonce,
+                    latest_nonce,
+                },
+            };
+            debug!(nonce, latest_nonce, "stale deploy nonce");
+            return self.handle_invalid_deploy_result(
+                effect_builder,
+                event_metadata,
+                error,
+                verification_start_timestamp,
+            );
+        }
+        self.verify_payment_logic(
+            effect_builder,
+            event_metadata,
+            prestate_hash,
+            verification_start_timestamp,
+        )
+    }
+
     fn verify_payment_logic<REv: ReactorEventT>(
         &self,
         effect_builder: EffectBuilder<REv>,
@@ -919,6 +1004,20 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 account_hash,
                 verification_start_timestamp,
             ),
+            Event::GetNonceResult {
+                event_metadata,
+                prestate_hash,
+                nonce,
+                latest_nonce,
+                verification_start_timestamp,
+            } => self.handle_get_nonce_result(
+                effect_builder,
+                event_metadata,
+                prestate_hash,
+                nonce,
+                latest_nonce,
+                verification_start_timestamp,
+            ),
             Event::GetContractResult {
                 event_metadata,
                 prestate_hash,
@@ -76,6 +76,15 @@ pub(crate) enum Event {
         account_hash: AccountHash,
         verification_start_timestamp: Timestamp,
     },
+    /// The result of querying global state for the latest nonce used by the `Account` associated
+    /// with the `Deploy`.
+    GetNonceResult {
+        event_metadata: EventMetadata,
+        prestate_hash: Digest,
+        nonce: u64,
+        latest_nonce: u64,
+        verification_start_timestamp: Timestamp,
+    },
     /// The result of querying global state for a `Contract` to verify the executable logic.
     GetContractResult {
         event_metadata: EventMetadata,
@@ -159,6 +168,13 @@ impl Display for Event {
                     event_metadata.deploy.id()
                 )
             }
+            Event::GetNonceResult { event_metadata, .. } => {
+                write!(
+                    formatter,
+                    "verifying account nonce to validate deploy with hash {}.",
+                    event_metadata.deploy.id()
+                )
+            }
             Event::GetContractResult {
                 event_metadata,
                 prestate_hash,
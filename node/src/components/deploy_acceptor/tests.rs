@@ -43,6 +43,7 @@ use crate::{
 const VERIFY_ACCOUNTS: bool = true;
 const POLL_INTERVAL: Duration = Duration::from_millis(10);
 const TIMEOUT: Duration = Duration::from_secs(10);
+const LATEST_NONCE: u64 = 5;
 
 /// Top-level event for the reactor.
 #[derive(Debug, From, Serialize)]
@@ -144,6 +145,10 @@ enum TestScenario {
     DeployWithMangledTransferAmount,
     DeployWithoutTransferTarget,
     DeployWithoutTransferAmount,
+    DeployWithStaleNonce,
+    DeployWithFutureNonce,
+    DeployWithNonceBeforeActivation,
+    DeployWithSupersessionBeforeActivation,
     BalanceCheckForDeploySentByPeer,
 }
 
@@ -177,7 +182,11 @@ impl TestScenario {
             | TestScenario::DeployWithSessionContract(_)
             | TestScenario::DeployWithSessionContractPackage(_)
             | TestScenario::DeployWithEmptySessionModuleBytes
-            | TestScenario::DeployWithNativeTransferInPayment => Source::Client,
+            | TestScenario::DeployWithNativeTransferInPayment
+            | TestScenario::DeployWithStaleNonce
+            | TestScenario::DeployWithFutureNonce
+            | TestScenario::DeployWithNonceBeforeActivation
+            | TestScenario::DeployWithSupersessionBeforeActivation => Source::Client,
         }
     }
 
@@ -216,6 +225,14 @@ impl TestScenario {
             TestScenario::DeployWithMangledTransferAmount => {
                 Deploy::random_with_mangled_transfer_amount(rng)
             }
+            TestScenario::DeployWithStaleNonce => Deploy::random_with_nonce(rng, LATEST_NONCE),
+            TestScenario::DeployWithFutureNonce => Deploy::random_with_nonce(rng, LATEST_NONCE + 1),
+            TestScenario::DeployWithNonceBeforeActivation => {
+                Deploy::random_with_nonce(rng, LATEST_NONCE + 1)
+            }
+            TestScenario::DeployWithSupersessionBeforeActivation => {
+                Deploy::random_with_supersession(rng)
+            }
 
             TestScenario::DeployWithCustomPaymentContract(contract_scenario) => {
                 match contract_scenario {
@@ -286,7 +303,8 @@ impl TestScenario {
             | TestScenario::FromPeerAccountWithInsufficientWeight // account check skipped if from peer
             | TestScenario::FromPeerAccountWithInvalidAssociatedKeys // account check skipped if from peer
             | TestScenario::FromClientRepeatedValidDeploy
-            | TestScenario::FromClientValidDeploy => true,
+            | TestScenario::FromClientValidDeploy
+            | TestScenario::DeployWithFutureNonce => true,
             TestScenario::FromPeerInvalidDeploy
             | TestScenario::FromClientInsufficientBalance
             | TestScenario::FromClientMissingAccount
@@ -301,6 +319,9 @@ impl TestScenario {
             | TestScenario::DeployWithMangledTransferAmount
             | TestScenario::DeployWithoutTransferAmount
             | TestScenario::DeployWithoutTransferTarget
+            | TestScenario::DeployWithStaleNonce
+            | TestScenario::DeployWithNonceBeforeActivation
+            | TestScenario::DeployWithSupersessionBeforeActivation
             | TestScenario::BalanceCheckForDeploySentByPeer => false,
             TestScenario::DeployWithCustomPaymentContract(contract_scenario)
             | TestScenario::DeployWithSessionContract(contract_scenario) => match contract_scenario
@@ -382,12 +403,17 @@ impl reactor::Reactor for Reactor {
         )
         .unwrap();
 
-        let deploy_acceptor = DeployAcceptor::new(
-            super::Config::new(VERIFY_ACCOUNTS),
-            &Chainspec::from_resources("local"),
-            registry,
-        )
-        .unwrap();
+        let mut chainspec = Chainspec::from_resources("local");
+        chainspec.protocol_config.version = if config
+            == TestScenario::DeployWithNonceBeforeActivation
+            || config == TestScenario::DeployWithSupersessionBeforeActivation
+        {
+            ProtocolVersion::from_parts(1, 4, 0)
+        } else {
+            DEPLOY_NONCES_PROTOCOL_VERSION
+        };
+        let deploy_acceptor =
+            DeployAcceptor::new(super::Config::new(VERIFY_ACCOUNTS), &chainspec, registry).unwrap();
 
         let reactor = Reactor {
             storage,
@@ -510,6 +536,14 @@ impl reactor::Reactor for Reactor {
                                     QueryResult::ValueNotFound(String::new())
                                 }
                             },
+                            // The account's latest nonce is recorded under a `Key::Hash`.
+                            TestScenario::DeployWithStaleNonce
+                            | TestScenario::DeployWithFutureNonce => QueryResult::Success {
+                                value: Box::new(StoredValue::CLValue(
+                                    CLValue::from_t(LATEST_NONCE).expect("should get CLValue"),
+                                )),
+                                proofs: vec![],
+                            },
                             _ => QueryResult::ValueNotFound(String::new()),
                         }
                     } else {
@@ -722,7 +756,10 @@ async fn run_deploy_acceptor_without_timeout(
             | TestScenario::DeployWithMangledPaymentAmount
             | TestScenario::DeployWithMangledTransferAmount
             | TestScenario::DeployWithoutTransferTarget
-            | TestScenario::DeployWithoutTransferAmount => {
+            | TestScenario::DeployWithoutTransferAmount
+            | TestScenario::DeployWithStaleNonce
+            | TestScenario::DeployWithNonceBeforeActivation
+            | TestScenario::DeployWithSupersessionBeforeActivation => {
                 matches!(
                     event,
                     Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::InvalidDeploy {
@@ -803,7 +840,7 @@ async fn run_deploy_acceptor_without_timeout(
             }
             // Check that a, new and valid, deploy sent by a client raises an `AcceptedNewDeploy`
             // announcement with the appropriate source.
-            TestScenario::FromClientValidDeploy => {
+            TestScenario::FromClientValidDeploy | TestScenario::DeployWithFutureNonce => {
                 matches!(
                     event,
                     Event::DeployAcceptorAnnouncement(
@@ -1286,3 +1323,50 @@ async fn should_panic_when_balance_checking_for_deploy_sent_by_peer() {
     let result = run_deploy_acceptor(test_scenario).await;
     assert!(result.is_ok())
 }
+
+#[tokio::test]
+async fn should_reject_deploy_with_stale_nonce() {
+    let test_scenario = TestScenario::DeployWithStaleNonce;
+    let result = run_deploy_acceptor(test_scenario).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::InvalidDeployParameters {
+            failure: DeployParameterFailure::StaleNonce {
+                nonce: LATEST_NONCE,
+                latest_nonce: LATEST_NONCE,
+            },
+            ..
+        })
+    ))
+}
+
+#[tokio::test]
+async fn should_accept_deploy_with_future_nonce() {
+    let test_scenario = TestScenario::DeployWithFutureNonce;
+    let result = run_deploy_acceptor(test_scenario).await;
+    assert!(result.is_ok())
+}
+
+#[tokio::test]
+async fn should_reject_deploy_with_nonce_before_activation() {
+    let test_scenario = TestScenario::DeployWithNonceBeforeActivation;
+    let result = run_deploy_acceptor(test_scenario).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::InvalidDeployConfiguration(
+            DeployConfigurationFailure::NonceNotSupported
+        ))
+    ))
+}
+
+#[tokio::test]
+async fn should_reject_deploy_with_supersession_before_activation() {
+    let test_scenario = TestScenario::DeployWithSupersessionBeforeActivation;
+    let result = run_deploy_acceptor(test_scenario).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::InvalidDeployConfiguration(
+            DeployConfigurationFailure::SupersessionNotSupported
+        ))
+    ))
+}
@@ -1,13 +1,14 @@
 //! Event stream server
 //!
 //! The event stream server provides clients with an event-stream returning Server-Sent Events
-//! (SSEs) holding JSON-encoded data.
+//! (SSEs) holding JSON-encoded data.  The same events are also available over a WebSocket, whose
+//! subscribers can narrow down the events they receive by sending filter messages.
 //!
 //! The actual server is run in backgrounded tasks.
 //!
 //! This module currently provides both halves of what is required for an API server:
 //! a component implementation that interfaces with other components via being plugged into a
-//! reactor, and an external facing http server that manages SSE subscriptions on a single endpoint.
+//! reactor, and an external facing http server that manages SSE and WebSocket subscriptions.
 //!
 //! This component is passive and receives announcements made by other components while never making
 //! a request of other components itself. The handled announcements are serialized to JSON and
@@ -28,6 +29,7 @@ mod http_server;
 mod sse_server;
 #[cfg(test)]
 mod tests;
+mod ws_server;
 
 use std::{convert::Infallible, fmt::Debug, net::SocketAddr, path::PathBuf};
 
@@ -108,7 +110,7 @@ impl EventStreamServer {
         let ChannelsAndFilter {
             event_broadcaster,
             new_subscriber_info_receiver,
-            sse_filter,
+            filter,
         } = ChannelsAndFilter::new(
             broadcast_channel_size as usize,
             config.max_concurrent_subscribers,
@@ -117,7 +119,7 @@ impl EventStreamServer {
 
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-        let (listening_address, server_with_shutdown) = warp::serve(sse_filter)
+        let (listening_address, server_with_shutdown) = warp::serve(filter)
             .try_bind_with_graceful_shutdown(required_address, async {
                 shutdown_receiver.await.ok();
             })
@@ -185,6 +187,7 @@ where
                 deploy_header,
                 block_hash,
                 execution_result,
+                events,
             } => self.broadcast(SseData::DeployProcessed {
                 deploy_hash: Box::new(deploy_hash),
                 account: Box::new(deploy_header.account().clone()),
@@ -193,12 +196,20 @@ where
                 dependencies: deploy_header.dependencies().clone(),
                 block_hash: Box::new(block_hash),
                 execution_result,
+                events,
             }),
             Event::DeploysExpired(deploy_hashes) => deploy_hashes
                 .into_iter()
                 .map(|deploy_hash| self.broadcast(SseData::DeployExpired { deploy_hash }))
                 .flatten()
                 .collect(),
+            Event::DeploySuperseded {
+                superseded,
+                superseded_by,
+            } => self.broadcast(SseData::DeploySuperseded {
+                deploy_hash: superseded,
+                superseded_by,
+            }),
             Event::Fault {
                 era_id,
                 public_key,
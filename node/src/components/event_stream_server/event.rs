@@ -1,6 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey};
+use casper_types::{ContractEvent, EraId, ExecutionEffect, ExecutionResult, PublicKey};
 use itertools::Itertools;
 
 use crate::types::{Block, BlockHash, DeployHash, DeployHeader, FinalitySignature, Timestamp};
@@ -14,8 +14,13 @@ pub enum Event {
         deploy_header: Box<DeployHeader>,
         block_hash: BlockHash,
         execution_result: Box<ExecutionResult>,
+        events: Vec<ContractEvent>,
     },
     DeploysExpired(Vec<DeployHash>),
+    DeploySuperseded {
+        superseded: DeployHash,
+        superseded_by: DeployHash,
+    },
     Fault {
         era_id: EraId,
         public_key: PublicKey,
@@ -42,6 +47,14 @@ impl Display for Event {
                     deploy_hashes.iter().join(", ")
                 )
             }
+            Event::DeploySuperseded {
+                superseded,
+                superseded_by,
+            } => write!(
+                formatter,
+                "deploy {} superseded by {}",
+                superseded, superseded_by
+            ),
             Event::DeployProcessed { deploy_hash, .. } => {
                 write!(formatter, "deploy processed {}", deploy_hash)
             }
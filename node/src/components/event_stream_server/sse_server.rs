@@ -34,9 +34,12 @@ use warp::{
     Filter, Reply,
 };
 
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_types::{
+    AsymmetricType, ContractEvent, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion,
+    PublicKey,
+};
 
-use super::DeployGetter;
+use super::{ws_server::create_ws_filter, DeployGetter};
 use crate::types::{
     BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock, TimeDiff, Timestamp,
 };
@@ -54,12 +57,21 @@ pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name for the comma-separated event types to receive.
+pub const EVENT_TYPE_QUERY_FIELD: &str = "event_type";
+/// The URL query string field name for the comma-separated, hex-encoded public keys of the accounts
+/// whose deploys' events should be received.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name for the comma-separated, formatted keys of which at least one
+/// must be written to by an event's execution effects for the event to be received.
+pub const KEY_QUERY_FIELD: &str = "key";
 
 /// The filter associated with `/events/main` path.
-const MAIN_FILTER: [EventFilter; 5] = [
+const MAIN_FILTER: [EventFilter; 6] = [
     EventFilter::BlockAdded,
     EventFilter::DeployProcessed,
     EventFilter::DeployExpired,
+    EventFilter::DeploySuperseded,
     EventFilter::Fault,
     EventFilter::Step,
 ];
@@ -99,11 +111,18 @@ pub enum SseData {
         ttl: TimeDiff,
         dependencies: Vec<DeployHash>,
         block_hash: Box<BlockHash>,
+        events: Vec<ContractEvent>,
         #[data_size(skip)]
         execution_result: Box<ExecutionResult>,
     },
     /// The given deploy has expired.
     DeployExpired { deploy_hash: DeployHash },
+    /// The given pending deploy has been replaced or cancelled by a later deploy from the same
+    /// account.
+    DeploySuperseded {
+        deploy_hash: DeployHash,
+        superseded_by: DeployHash,
+    },
     /// Generic representation of validator's fault in an era.
     Fault {
         era_id: EraId,
@@ -127,6 +146,7 @@ impl SseData {
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
             SseData::DeployExpired { .. } => filter.contains(&EventFilter::DeployExpired),
+            SseData::DeploySuperseded { .. } => filter.contains(&EventFilter::DeploySuperseded),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
             SseData::Step { .. } => filter.contains(&EventFilter::Step),
@@ -175,6 +195,7 @@ impl SseData {
             dependencies: deploy.header().dependencies().clone(),
             block_hash: Box::new(BlockHash::random(rng)),
             execution_result: Box::new(rng.gen()),
+            events: Vec::new(),
         }
     }
 
@@ -186,6 +207,14 @@ impl SseData {
         }
     }
 
+    /// Returns a random `SseData::DeploySuperseded`.
+    pub(super) fn random_deploy_superseded(rng: &mut TestRng) -> Self {
+        SseData::DeploySuperseded {
+            deploy_hash: DeployHash::random(rng),
+            superseded_by: DeployHash::random(rng),
+        }
+    }
+
     /// Returns a random `SseData::Fault`.
     pub(super) fn random_fault(rng: &mut TestRng) -> Self {
         SseData::Fault {
@@ -264,24 +293,111 @@ pub(super) struct NewSubscriberInfo {
 }
 
 /// A filter for event types a client has subscribed to receive.
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize)]
 pub(super) enum EventFilter {
     BlockAdded,
     DeployAccepted,
     DeployProcessed,
     DeployExpired,
+    DeploySuperseded,
     Fault,
     FinalitySignature,
     Step,
 }
 
+impl EventFilter {
+    /// Parses an event type as named in the `SseData` variants.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "BlockAdded" => Some(EventFilter::BlockAdded),
+            "DeployAccepted" => Some(EventFilter::DeployAccepted),
+            "DeployProcessed" => Some(EventFilter::DeployProcessed),
+            "DeployExpired" => Some(EventFilter::DeployExpired),
+            "DeploySuperseded" => Some(EventFilter::DeploySuperseded),
+            "Fault" => Some(EventFilter::Fault),
+            "FinalitySignature" => Some(EventFilter::FinalitySignature),
+            "Step" => Some(EventFilter::Step),
+            _ => None,
+        }
+    }
+}
+
+/// The filtering requested by a subscriber, applied in addition to the event types implied by the
+/// URL path.
+///
+/// An empty set of accounts or keys means events are not filtered on that criterion.  Events which
+/// are not associated with an account or with execution effects are never filtered out by these.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub(super) struct SubscriptionFilter {
+    /// The event types to receive, or `None` for all of them.
+    pub(super) event_types: Option<Vec<EventFilter>>,
+    /// The accounts whose `DeployAccepted` and `DeployProcessed` events should be received.
+    pub(super) accounts: HashSet<PublicKey>,
+    /// The formatted keys, at least one of which must be written to by the execution effects of a
+    /// `DeployProcessed` or `Step` event for it to be received.
+    pub(super) keys: HashSet<String>,
+}
+
+impl SubscriptionFilter {
+    /// Returns whether the given event should be sent to the subscriber.
+    ///
+    /// Note that the account of a `DeployAccepted` event isn't checked here, as the deploy needs to
+    /// be retrieved first; use `includes_account()` for that.
+    pub(super) fn should_include(&self, data: &SseData) -> bool {
+        if let Some(event_types) = &self.event_types {
+            if !data.should_include(event_types) {
+                return false;
+            }
+        }
+        match data {
+            SseData::DeployProcessed {
+                account,
+                execution_result,
+                events,
+                ..
+            } => {
+                let effect = match execution_result.as_ref() {
+                    ExecutionResult::Success { effect, .. }
+                    | ExecutionResult::Failure { effect, .. } => effect,
+                };
+                self.includes_account(account) && self.includes_effect(effect, events)
+            }
+            SseData::Step {
+                execution_effect, ..
+            } => self.includes_effect(execution_effect, &[]),
+            _ => true,
+        }
+    }
+
+    /// Returns whether events for deploys from the given account should be sent to the subscriber.
+    pub(super) fn includes_account(&self, account: &PublicKey) -> bool {
+        self.accounts.is_empty() || self.accounts.contains(account)
+    }
+
+    /// Returns whether the effect touches any of the filtered keys, either by transforming the
+    /// value under the key or by one of the events having been emitted by the contract or account
+    /// at the key.
+    fn includes_effect(&self, effect: &ExecutionEffect, events: &[ContractEvent]) -> bool {
+        self.keys.is_empty()
+            || effect
+                .transforms
+                .iter()
+                .any(|transform_entry| self.keys.contains(&transform_entry.key))
+            || events
+                .iter()
+                .any(|event| self.keys.contains(&event.emitter.to_formatted_string()))
+    }
+}
+
 /// Filters the `event`, mapping it to a warp event, or `None` if it should be filtered out.
 async fn filter_map_server_sent_event(
     event: &ServerSentEvent,
     event_filter: &[EventFilter],
+    subscription_filter: &SubscriptionFilter,
     deploy_getter: DeployGetter,
 ) -> Option<Result<WarpServerSentEvent, RecvError>> {
-    if !event.data.should_include(event_filter) {
+    if !event.data.should_include(event_filter) || !subscription_filter.should_include(&event.data)
+    {
         return None;
     }
 
@@ -313,6 +429,7 @@ async fn filter_map_server_sent_event(
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
         | &SseData::DeployExpired { .. }
+        | &SseData::DeploySuperseded { .. }
         | &SseData::Fault { .. }
         | &SseData::Step { .. }
         | &SseData::FinalitySignature(_) => Some(Ok(WarpServerSentEvent::default()
@@ -326,18 +443,10 @@ async fn filter_map_server_sent_event(
         &SseData::DeployAccepted {
             deploy: deploy_hash,
         } => {
-            // We try twice to get the deploy since there's a chance that the first attempt could be
-            // lost when the joiner reactor's event queue is purged as we transition to the
-            // participating reactor.  This workaround should no longer be required once the reactor
-            // transitions are handled properly.
-            let deploy_accepted =
-                match time::timeout(GET_DEPLOY_TIMEOUT, deploy_getter.get(deploy_hash)).await {
-                    Ok(maybe_deploy) => maybe_deploy?,
-                    Err(_) => {
-                        info!("timed out getting deploy for event stream");
-                        deploy_getter.get(deploy_hash).await?
-                    }
-                };
+            let deploy_accepted = get_deploy(deploy_hash, deploy_getter).await?;
+            if !subscription_filter.includes_account(deploy_accepted.header().account()) {
+                return None;
+            }
 
             Some(Ok(WarpServerSentEvent::default()
                 .json_data(&DeployAccepted { deploy_accepted })
@@ -350,6 +459,24 @@ async fn filter_map_server_sent_event(
     }
 }
 
+/// Gets the deploy with the given hash for an event stream.
+pub(super) async fn get_deploy(
+    deploy_hash: DeployHash,
+    deploy_getter: DeployGetter,
+) -> Option<Deploy> {
+    // We try twice to get the deploy since there's a chance that the first attempt could be lost
+    // when the joiner reactor's event queue is purged as we transition to the participating
+    // reactor.  This workaround should no longer be required once the reactor transitions are
+    // handled properly.
+    match time::timeout(GET_DEPLOY_TIMEOUT, deploy_getter.get(deploy_hash)).await {
+        Ok(maybe_deploy) => maybe_deploy,
+        Err(_) => {
+            info!("timed out getting deploy for event stream");
+            deploy_getter.get(deploy_hash).await
+        }
+    }
+}
+
 /// Converts the final URL path element to a slice of `EventFilter`s.
 pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     match path_param {
@@ -360,26 +487,54 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the starting event ID and the subscription filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
-    }
-
-    if query.len() > 1 {
-        return Err(create_422());
+/// All fields are optional.  Returns a 422 response if `query` has any field other than
+/// "start_from" mapped to a value representing an event ID, "event_type" mapped to a
+/// comma-separated list of event types, "account" mapped to a comma-separated list of hex-encoded
+/// public keys, or "key" mapped to a comma-separated list of formatted keys.
+pub(super) fn parse_query(
+    query: HashMap<String, String>,
+) -> Result<(Option<Id>, SubscriptionFilter), Response> {
+    let mut start_from = None;
+    let mut subscription_filter = SubscriptionFilter::default();
+
+    for (field, value) in query {
+        match field.as_str() {
+            QUERY_FIELD => {
+                start_from = Some(value.parse::<Id>().map_err(|_| create_422())?);
+            }
+            EVENT_TYPE_QUERY_FIELD => {
+                let event_types = value
+                    .split(',')
+                    .map(EventFilter::from_name)
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(create_422)?;
+                subscription_filter.event_types = Some(event_types);
+            }
+            ACCOUNT_QUERY_FIELD => {
+                subscription_filter.accounts = value
+                    .split(',')
+                    .map(|hex_public_key| PublicKey::from_hex(hex_public_key).ok())
+                    .collect::<Option<_>>()
+                    .ok_or_else(create_422)?;
+            }
+            KEY_QUERY_FIELD => {
+                subscription_filter.keys = value
+                    .split(',')
+                    .map(|formatted_key| {
+                        Key::from_formatted_str(formatted_key)
+                            .ok()
+                            .map(|key| key.to_formatted_string())
+                    })
+                    .collect::<Option<_>>()
+                    .ok_or_else(create_422)?;
+            }
+            _ => return Err(create_422()),
+        }
     }
 
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
-    }
+    Ok((start_from, subscription_filter))
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -399,8 +554,9 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected optional fields '{}=<EVENT ID>', '{}=<EVENT TYPES>', \
+        '{}=<PUBLIC KEYS>' and '{}=<KEYS>'\n",
+        QUERY_FIELD, EVENT_TYPE_QUERY_FIELD, ACCOUNT_QUERY_FIELD, KEY_QUERY_FIELD
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -408,7 +564,7 @@ fn create_422() -> Response {
 
 /// Creates a 503 response (Service Unavailable) to be returned if the server has too many
 /// subscribers.
-fn create_503() -> Response {
+pub(super) fn create_503() -> Response {
     let mut response = Response::new(Body::from("server has reached limit of subscribers"));
     *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
     response
@@ -417,12 +573,12 @@ fn create_503() -> Response {
 pub(super) struct ChannelsAndFilter {
     pub(super) event_broadcaster: broadcast::Sender<BroadcastChannelMessage>,
     pub(super) new_subscriber_info_receiver: mpsc::UnboundedReceiver<NewSubscriberInfo>,
-    pub(super) sse_filter: BoxedFilter<(Response,)>,
+    pub(super) filter: BoxedFilter<(Response,)>,
 }
 
 impl ChannelsAndFilter {
     /// Creates the message-passing channels required to run the event-stream server and the warp
-    /// filter for the event-stream server.
+    /// filter for the event-stream server, serving both SSE and WebSocket subscribers.
     pub(super) fn new(
         broadcast_channel_size: usize,
         max_concurrent_subscribers: u32,
@@ -436,6 +592,15 @@ impl ChannelsAndFilter {
         // new client subscription.
         let (new_subscriber_info_sender, new_subscriber_info_receiver) = mpsc::unbounded_channel();
 
+        // The WebSocket filter must be tried first, as the SSE filter's path would match its path
+        // too and would respond with a 404.
+        let ws_filter = create_ws_filter(
+            event_broadcaster.clone(),
+            new_subscriber_info_sender.clone(),
+            max_concurrent_subscribers,
+            deploy_getter.clone(),
+        );
+
         let sse_filter = warp::get()
             .and(path(SSE_API_ROOT_PATH))
             .and(path::param::<String>())
@@ -457,8 +622,8 @@ impl ChannelsAndFilter {
                     None => return create_404(),
                 };
 
-                let start_from = match parse_query(query) {
-                    Ok(maybe_id) => maybe_id,
+                let (start_from, subscription_filter) = match parse_query(query) {
+                    Ok(parsed_query) => parsed_query,
                     Err(error_response) => return error_response,
                 };
 
@@ -487,17 +652,19 @@ impl ChannelsAndFilter {
                     initial_events_receiver,
                     ongoing_events_receiver,
                     event_filter,
+                    subscription_filter,
                     deploy_getter.clone(),
                 )))
                 .into_response()
             })
-            .or_else(|_| async move { Ok::<_, Rejection>((create_404(),)) })
-            .boxed();
+            .or_else(|_| async move { Ok::<_, Rejection>((create_404(),)) });
+
+        let filter = ws_filter.or(sse_filter).unify().boxed();
 
         ChannelsAndFilter {
             event_broadcaster,
             new_subscriber_info_receiver,
-            sse_filter,
+            filter,
         }
     }
 }
@@ -505,6 +672,47 @@ impl ChannelsAndFilter {
 /// This takes the two channel receivers and turns them into a stream of SSEs to the subscribed
 /// client.
 ///
+/// The events are provided by `deduplicated_events()`, and are then filtered as dictated by the
+/// given `EventFilter` and `SubscriptionFilter`, causing events to which the client didn't
+/// subscribe to be skipped.
+fn stream_to_client(
+    initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
+    ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
+    event_filter: &'static [EventFilter],
+    subscription_filter: SubscriptionFilter,
+    deploy_getter: DeployGetter,
+) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
+    let subscription_filter = Arc::new(subscription_filter);
+    deduplicated_events(initial_events, ongoing_events).filter_map(move |result| {
+        let cloned_subscription_filter = Arc::clone(&subscription_filter);
+        let cloned_deploy_getter = deploy_getter.clone();
+        async move {
+            match result {
+                Ok(event) => {
+                    filter_map_server_sent_event(
+                        &event,
+                        event_filter,
+                        &cloned_subscription_filter,
+                        cloned_deploy_getter,
+                    )
+                    .await
+                }
+                Err(RecvError::Lagged(amount)) => {
+                    info!(
+                        "client lagged by {} events - dropping event stream connection to client",
+                        amount
+                    );
+                    Some(Err(RecvError::Lagged(amount)))
+                }
+                Err(error) => Some(Err(error)),
+            }
+        }
+    })
+}
+
+/// This takes the two channel receivers and turns them into a single stream of events for a
+/// subscribed client.
+///
 /// The initial events receiver (an mpsc receiver) is exhausted first, and contains an initial
 /// `ApiVersion` message, followed by any historical events the client requested using the query
 /// string.
@@ -512,16 +720,12 @@ impl ChannelsAndFilter {
 /// The ongoing events channel (a broadcast receiver) is then consumed, and will remain in use until
 /// either the client disconnects, or the server shuts down (indicated by sending a `Shutdown`
 /// variant via the channel).  This channel will receive all SSEs created from the moment the client
-/// subscribed to the server's event stream.
-///
-/// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
-fn stream_to_client(
+/// subscribed to the server's event stream.  Any of its events which were already provided via the
+/// initial events receiver are skipped.
+pub(super) fn deduplicated_events(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
-    event_filter: &'static [EventFilter],
-    deploy_getter: DeployGetter,
-) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
+) -> impl Stream<Item = Result<ServerSentEvent, RecvError>> + 'static {
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
     let initial_stream_ids = Arc::new(RwLock::new(HashSet::new()));
     let cloned_initial_ids = Arc::clone(&initial_stream_ids);
@@ -544,10 +748,6 @@ fn stream_to_client(
                     }
                     Ok(BroadcastChannelMessage::Shutdown) => Some(Err(RecvError::Closed)),
                     Err(BroadcastStreamRecvError::Lagged(amount)) => {
-                        info!(
-                            "client lagged by {} events - dropping event stream connection to client",
-                            amount
-                        );
                         Some(Err(RecvError::Lagged(amount)))
                     }
                 }
@@ -555,8 +755,7 @@ fn stream_to_client(
         })
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
-    // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
+    // Serve the initial events followed by the ongoing ones.
     UnboundedReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
@@ -565,24 +764,14 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
-        .filter_map(move |result| {
-            let cloned_deploy_getter = deploy_getter.clone();
-            async move {
-                match result {
-                    Ok(event) => {
-                        filter_map_server_sent_event(&event, event_filter, cloned_deploy_getter)
-                            .await
-                    }
-                    Err(error) => Some(Err(error)),
-                }
-            }
-        })
 }
 
 #[cfg(test)]
 mod tests {
     use std::iter;
 
+    use casper_types::CLValue;
+
     use super::*;
     use crate::{logging, testing::TestRng};
 
@@ -592,9 +781,14 @@ mod tests {
         deploy_getter: DeployGetter,
     ) {
         assert!(
-            filter_map_server_sent_event(event, filter, deploy_getter)
-                .await
-                .is_none(),
+            filter_map_server_sent_event(
+                event,
+                filter,
+                &SubscriptionFilter::default(),
+                deploy_getter
+            )
+            .await
+            .is_none(),
             "should filter out {:?} with {:?}",
             event,
             filter
@@ -607,9 +801,14 @@ mod tests {
         deploy_getter: DeployGetter,
     ) {
         assert!(
-            filter_map_server_sent_event(event, filter, deploy_getter)
-                .await
-                .is_some(),
+            filter_map_server_sent_event(
+                event,
+                filter,
+                &SubscriptionFilter::default(),
+                deploy_getter
+            )
+            .await
+            .is_some(),
             "should not filter out {:?} with {:?}",
             event,
             filter
@@ -647,6 +846,10 @@ mod tests {
             id: Some(rng.gen()),
             data: SseData::random_deploy_expired(&mut rng),
         };
+        let deploy_superseded = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_deploy_superseded(&mut rng),
+        };
         let fault = ServerSentEvent {
             id: Some(rng.gen()),
             data: SseData::random_fault(&mut rng),
@@ -665,6 +868,7 @@ mod tests {
         should_not_filter_out(&block_added, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&deploy_processed, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&deploy_expired, &MAIN_FILTER[..], getter.clone()).await;
+        should_not_filter_out(&deploy_superseded, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&fault, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&step, &MAIN_FILTER[..], getter.clone()).await;
 
@@ -679,6 +883,7 @@ mod tests {
         should_filter_out(&block_added, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&deploy_processed, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&deploy_expired, &DEPLOYS_FILTER[..], getter.clone()).await;
+        should_filter_out(&deploy_superseded, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&fault, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&finality_signature, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&step, &DEPLOYS_FILTER[..], getter.clone()).await;
@@ -692,6 +897,7 @@ mod tests {
         should_filter_out(&deploy_accepted, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&deploy_processed, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&deploy_expired, &SIGNATURES_FILTER[..], getter.clone()).await;
+        should_filter_out(&deploy_superseded, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&fault, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&step, &SIGNATURES_FILTER[..], getter).await;
     }
@@ -727,6 +933,10 @@ mod tests {
             id: None,
             data: SseData::random_deploy_expired(&mut rng),
         };
+        let malformed_deploy_superseded = ServerSentEvent {
+            id: None,
+            data: SseData::random_deploy_superseded(&mut rng),
+        };
         let malformed_fault = ServerSentEvent {
             id: None,
             data: SseData::random_fault(&mut rng),
@@ -750,6 +960,7 @@ mod tests {
             should_filter_out(&malformed_deploy_accepted, filter, getter.clone()).await;
             should_filter_out(&malformed_deploy_processed, filter, getter.clone()).await;
             should_filter_out(&malformed_deploy_expired, filter, getter.clone()).await;
+            should_filter_out(&malformed_deploy_superseded, filter, getter.clone()).await;
             should_filter_out(&malformed_fault, filter, getter.clone()).await;
             should_filter_out(&malformed_finality_signature, filter, getter.clone()).await;
             should_filter_out(&malformed_step, filter, getter.clone()).await;
@@ -870,6 +1081,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                SubscriptionFilter::default(),
                 deploy_getter,
             )
             .collect()
@@ -935,4 +1147,145 @@ mod tests {
     async fn should_filter_duplicate_signature_events() {
         should_filter_duplicate_events(SSE_API_SIGNATURES_PATH).await
     }
+
+    fn query(fields: &[(&str, &str)]) -> HashMap<String, String> {
+        fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_query() {
+        let mut rng = crate::new_rng();
+        let account = PublicKey::random(&mut rng);
+        let key = Key::Hash(rng.gen());
+
+        let (start_from, subscription_filter) = parse_query(query(&[
+            (QUERY_FIELD, "3"),
+            (EVENT_TYPE_QUERY_FIELD, "DeployProcessed,Step"),
+            (ACCOUNT_QUERY_FIELD, &account.to_hex()),
+            (KEY_QUERY_FIELD, &key.to_formatted_string()),
+        ]))
+        .unwrap();
+        assert_eq!(start_from, Some(3));
+        assert_eq!(
+            subscription_filter.event_types,
+            Some(vec![EventFilter::DeployProcessed, EventFilter::Step])
+        );
+        assert_eq!(subscription_filter.accounts, iter::once(account).collect());
+        assert_eq!(
+            subscription_filter.keys,
+            iter::once(key.to_formatted_string()).collect()
+        );
+
+        let (start_from, subscription_filter) = parse_query(HashMap::new()).unwrap();
+        assert!(start_from.is_none());
+        assert_eq!(subscription_filter, SubscriptionFilter::default());
+
+        for bad_query in [
+            query(&[(EVENT_TYPE_QUERY_FIELD, "Step,Unknown")]),
+            query(&[(EVENT_TYPE_QUERY_FIELD, "")]),
+            query(&[(ACCOUNT_QUERY_FIELD, "not-hex")]),
+            query(&[(KEY_QUERY_FIELD, "not-a-key")]),
+            query(&[(QUERY_FIELD, "0"), ("extra", "1")]),
+        ]
+        .iter()
+        {
+            assert!(parse_query(bad_query.clone()).is_err());
+        }
+    }
+
+    /// This test checks that events are filtered by the event types, accounts and keys requested
+    /// by the client.
+    #[tokio::test]
+    async fn should_filter_events_with_subscription_filter() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+
+        let (sse_data, deploy) = SseData::random_deploy_accepted(&mut rng);
+        let deploy_accepted = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: sse_data,
+        };
+        let mut deploys = HashMap::new();
+        let _ = deploys.insert(*deploy.id(), deploy.clone());
+        let getter = DeployGetter::with_deploys(deploys);
+        let deploy_processed = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_deploy_processed(&mut rng),
+        };
+        let block_added = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_block_added(&mut rng),
+        };
+        let all_filter: &[EventFilter] = &[
+            EventFilter::BlockAdded,
+            EventFilter::DeployAccepted,
+            EventFilter::DeployProcessed,
+        ];
+
+        let passes = |event: &ServerSentEvent, subscription_filter: SubscriptionFilter| {
+            let getter = getter.clone();
+            let event = event.clone();
+            async move {
+                filter_map_server_sent_event(&event, all_filter, &subscription_filter, getter)
+                    .await
+                    .is_some()
+            }
+        };
+
+        // Filtering by event type.
+        let subscription_filter = SubscriptionFilter {
+            event_types: Some(vec![EventFilter::BlockAdded]),
+            ..Default::default()
+        };
+        assert!(passes(&block_added, subscription_filter.clone()).await);
+        assert!(!passes(&deploy_processed, subscription_filter.clone()).await);
+        assert!(!passes(&deploy_accepted, subscription_filter).await);
+
+        // Filtering by account only affects deploy events.
+        let processed_account = match &deploy_processed.data {
+            SseData::DeployProcessed { account, .. } => (**account).clone(),
+            _ => unreachable!(),
+        };
+        let subscription_filter = SubscriptionFilter {
+            accounts: iter::once(processed_account).collect(),
+            ..Default::default()
+        };
+        assert!(passes(&block_added, subscription_filter.clone()).await);
+        assert!(passes(&deploy_processed, subscription_filter.clone()).await);
+        assert!(!passes(&deploy_accepted, subscription_filter).await);
+        let subscription_filter = SubscriptionFilter {
+            accounts: iter::once(deploy.header().account().clone()).collect(),
+            ..Default::default()
+        };
+        assert!(!passes(&deploy_processed, subscription_filter.clone()).await);
+        assert!(passes(&deploy_accepted, subscription_filter).await);
+
+        // Filtering by a key which isn't written to excludes the `DeployProcessed` event.
+        let subscription_filter = SubscriptionFilter {
+            keys: iter::once(Key::Hash(rng.gen()).to_formatted_string()).collect(),
+            ..Default::default()
+        };
+        assert!(passes(&block_added, subscription_filter.clone()).await);
+        assert!(!passes(&deploy_processed, subscription_filter.clone()).await);
+
+        // Filtering by the key of a contract which emitted an event includes the event.
+        let emitter = Key::Hash(rng.gen());
+        let mut deploy_processed_with_event = deploy_processed.clone();
+        if let SseData::DeployProcessed { events, .. } = &mut deploy_processed_with_event.data {
+            events.push(ContractEvent {
+                emitter,
+                topic: "transfer".to_string(),
+                payload: CLValue::from_t(1u64).unwrap(),
+            });
+        }
+        assert!(!passes(&deploy_processed_with_event, subscription_filter).await);
+        let subscription_filter = SubscriptionFilter {
+            keys: iter::once(emitter.to_formatted_string()).collect(),
+            ..Default::default()
+        };
+        assert!(passes(&deploy_processed_with_event, subscription_filter).await);
+    }
 }
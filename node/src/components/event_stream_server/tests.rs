@@ -15,22 +15,24 @@ use http::StatusCode;
 use pretty_assertions::assert_eq;
 use reqwest::Response;
 use schemars::schema_for;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tempfile::TempDir;
 use tokio::{
     sync::{Barrier, Notify},
     task::{self, JoinHandle},
     time,
 };
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::debug;
 
 use super::*;
 use crate::{logging, testing::TestRng};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
-    SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    DeployAccepted, Id, ACCOUNT_QUERY_FIELD, EVENT_TYPE_QUERY_FIELD, KEY_QUERY_FIELD, QUERY_FIELD,
+    SSE_API_DEPLOYS_PATH as DEPLOYS_PATH, SSE_API_MAIN_PATH as MAIN_PATH,
+    SSE_API_ROOT_PATH as ROOT_PATH, SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
+use ws_server::WS_API_PATH;
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
 /// initial `ApiVersion` event.
@@ -401,6 +403,29 @@ impl TestFixture {
     fn all_filtered_events(&self, final_path_element: &str) -> (Vec<ReceivedEvent>, Id) {
         self.filtered_events(final_path_element, self.first_event_id)
     }
+
+    /// Returns the JSON envelopes of all the events which would have been received by a WebSocket
+    /// client connected from server startup with no query, including the initial `ApiVersion`
+    /// event.
+    fn all_ws_events(&self) -> Vec<Value> {
+        let api_version_event = json!({ "data": SseData::ApiVersion(self.protocol_version) });
+        iter::once(api_version_event)
+            .chain(self.events.iter().enumerate().map(|(index, event)| {
+                let data = match event {
+                    SseData::DeployAccepted {
+                        deploy: deploy_hash,
+                    } => {
+                        let deploy_accepted =
+                            self.deploy_getter.get_test_deploy(*deploy_hash).unwrap();
+                        serde_json::to_value(&DeployAccepted { deploy_accepted }).unwrap()
+                    }
+                    _ => serde_json::to_value(event).unwrap(),
+                };
+                let id = self.first_event_id.wrapping_add(index as Id);
+                json!({ "id": id, "data": data })
+            }))
+            .collect()
+    }
 }
 
 /// Returns the URL for a client to use to connect to the server at the given address.
@@ -424,6 +449,11 @@ fn url(
     )
 }
 
+/// Returns the URL for a WebSocket client to use to connect to the server at the given address.
+fn ws_url(server_address: SocketAddr) -> String {
+    format!("ws://{}/{}/{}", server_address, ROOT_PATH, WS_API_PATH)
+}
+
 /// The representation of an SSE event as received by a subscribed client.
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct ReceivedEvent {
@@ -474,6 +504,33 @@ async fn subscribe_no_sync(
     handle_response(response, final_event_id, client_id).await
 }
 
+/// Runs a WebSocket client, consuming all events until the server has emitted the event with ID
+/// `final_event_id`, and returns the received events' JSON envelopes.
+///
+/// The client waits at the barrier before connecting to the server, and then again immediately
+/// after connecting to ensure the server doesn't start sending events before the client is
+/// connected.
+async fn subscribe_ws(url: &str, barrier: Arc<Barrier>, final_event_id: Id) -> Vec<Value> {
+    barrier.wait().await;
+    let (mut websocket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    barrier.wait().await;
+
+    let mut received_events = Vec::new();
+    while let Some(message) = websocket.next().await {
+        let text = match message.unwrap() {
+            WsMessage::Text(text) => text,
+            _ => continue,
+        };
+        let event: Value = serde_json::from_str(&text).unwrap();
+        let is_final_event = event["id"] == final_event_id;
+        received_events.push(event);
+        if is_final_event {
+            break;
+        }
+    }
+    received_events
+}
+
 /// Handles a response from the server.
 async fn handle_response(
     response: Response,
@@ -859,6 +916,81 @@ async fn lagging_clients_should_be_disconnected() {
     check_error(result_slow_sigs);
 }
 
+/// Checks that a WebSocket client connected before the first event receives all events.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_all_events_via_websocket() {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    let mut server_behavior = ServerBehavior::new();
+    let barrier = server_behavior.add_client_sync_before_event(0);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    let expected_events = fixture.all_ws_events();
+    let final_id = fixture.first_event_id.wrapping_add(EVENT_COUNT - 1);
+    let received_events = subscribe_ws(&ws_url(server_address), barrier, final_id).await;
+    fixture.stop_server().await;
+
+    assert_eq!(received_events, expected_events);
+}
+
+/// Checks that WebSocket clients which don't consume the events in a timely manner skip the events
+/// they missed, rather than being disconnected as SSE clients are.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lagging_websocket_clients_should_skip_events() {
+    /// The number of events to receive after the skipped ones.
+    const EVENTS_AFTER_SKIPPED: u32 = 10;
+
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    // Start the server, setting it to run with no delay between sending each event, so that the
+    // paused client lags.
+    let mut server_behavior = ServerBehavior::new_for_lagging_test();
+    let barrier = server_behavior.add_client_sync_before_event(0);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    barrier.wait().await;
+    let (mut websocket, _) = tokio_tungstenite::connect_async(ws_url(server_address))
+        .await
+        .unwrap();
+    barrier.wait().await;
+
+    time::sleep(Duration::from_secs(1)).await;
+
+    // Read until the event IDs show that events were skipped, then check that the connection stays
+    // open and further events arrive.
+    let mut maybe_previous_id: Option<Id> = None;
+    let mut skipped_events = false;
+    let mut events_after_skipped = 0;
+    while events_after_skipped < EVENTS_AFTER_SKIPPED {
+        let message = time::timeout(MAX_TEST_TIME, websocket.next())
+            .await
+            .expect("should receive an event in time")
+            .expect("websocket should stay open")
+            .unwrap();
+        let text = match message {
+            WsMessage::Text(text) => text,
+            _ => continue,
+        };
+        let event: Value = serde_json::from_str(&text).unwrap();
+        let id = match event["id"].as_u64() {
+            Some(id) => id as Id,
+            // The initial `ApiVersion` event has no ID.
+            None => continue,
+        };
+        if skipped_events {
+            events_after_skipped += 1;
+        } else if let Some(previous_id) = maybe_previous_id {
+            skipped_events = id != previous_id.wrapping_add(1);
+        }
+        maybe_previous_id = Some(id);
+    }
+
+    drop(websocket);
+    fixture.stop_server().await;
+}
+
 /// Checks that clients using the correct <IP:Port> but wrong path get a helpful error response.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn should_handle_bad_url_path() {
@@ -937,11 +1069,15 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=NotAnEvent", main_url, EVENT_TYPE_QUERY_FIELD),
+        format!("{}?{}=not-hex", deploys_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=not-a-key", main_url, KEY_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected optional fields '{}=<EVENT ID>', '{}=<EVENT TYPES>', \
+        '{}=<PUBLIC KEYS>' and '{}=<KEYS>'",
+        QUERY_FIELD, EVENT_TYPE_QUERY_FIELD, ACCOUNT_QUERY_FIELD, KEY_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
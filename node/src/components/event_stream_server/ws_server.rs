@@ -0,0 +1,448 @@
+//! Types and functions used by the http server to manage WebSocket subscriptions to the
+//! event-stream.
+//!
+//! A WebSocket subscriber receives the same events as an SSE subscriber, each sent as a JSON text
+//! frame of the form `{"id":<EVENT ID>,"data":<EVENT DATA>}` (the `id` being omitted for the initial
+//! `ApiVersion` event).  As with the SSE endpoints, a `start_from` query can be provided to resume
+//! from a given event ID.
+//!
+//! Initially the events are filtered as per the query, in the same way as for an SSE subscriber.  At
+//! any point the subscriber may send a text frame holding a JSON-encoded `WsFilterMessage` to change
+//! the events it receives.
+//!
+//! A subscriber which lags behind the server's event buffer misses the events dropped from the
+//! buffer, but stays connected; the gap is visible in the event IDs.
+
+use std::collections::{HashMap, HashSet};
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    select,
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc,
+    },
+};
+use tracing::{debug, error, info, warn};
+use warp::{
+    filters::BoxedFilter,
+    path,
+    reply::Response,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
+
+use casper_types::{Key, PublicKey};
+
+use super::{
+    sse_server::{
+        create_503, deduplicated_events, get_deploy, parse_query, BroadcastChannelMessage,
+        DeployAccepted, EventFilter, Id, NewSubscriberInfo, ServerSentEvent, SubscriptionFilter,
+        SSE_API_ROOT_PATH,
+    },
+    DeployGetter, SseData,
+};
+
+/// The URL path part to subscribe to events via a WebSocket.
+pub const WS_API_PATH: &str = "ws";
+
+/// A message sent by a WebSocket subscriber to change which events it receives.
+///
+/// Omitted fields leave the corresponding part of the current filter unchanged, while an empty list
+/// of accounts or keys removes filtering on that criterion.
+#[derive(Clone, PartialEq, Eq, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(super) struct WsFilterMessage {
+    /// The event types to receive.
+    #[serde(default)]
+    pub(super) event_types: Option<Vec<EventFilter>>,
+    /// The accounts whose deploy events should be received.
+    #[serde(default)]
+    pub(super) accounts: Option<Vec<PublicKey>>,
+    /// The formatted keys, at least one of which must be written to by an event's execution effects.
+    #[serde(default)]
+    pub(super) keys: Option<Vec<String>>,
+}
+
+impl WsFilterMessage {
+    /// Applies the changes requested by the subscriber to `filter`, leaving it unchanged if any of
+    /// the keys are invalid.
+    pub(super) fn apply_to(self, filter: &mut SubscriptionFilter) -> Result<(), String> {
+        let maybe_keys = match self.keys {
+            Some(keys) => Some(
+                keys.iter()
+                    .map(|formatted_key| {
+                        Key::from_formatted_str(formatted_key)
+                            .map(|key| key.to_formatted_string())
+                            .map_err(|error| format!("invalid key {}: {}", formatted_key, error))
+                    })
+                    .collect::<Result<HashSet<_>, _>>()?,
+            ),
+            None => None,
+        };
+
+        if let Some(event_types) = self.event_types {
+            filter.event_types = Some(event_types);
+        }
+        if let Some(accounts) = self.accounts {
+            filter.accounts = accounts.into_iter().collect();
+        }
+        if let Some(keys) = maybe_keys {
+            filter.keys = keys;
+        }
+        Ok(())
+    }
+}
+
+/// The JSON envelope of each event sent to a WebSocket subscriber.
+#[derive(Serialize)]
+struct WsEvent<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Id>,
+    data: WsEventData<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum WsEventData<'a> {
+    Sse(&'a SseData),
+    DeployAccepted(DeployAccepted),
+}
+
+/// The JSON reply sent to a WebSocket subscriber which sent an invalid message.
+#[derive(Serialize)]
+struct WsError {
+    error: String,
+}
+
+/// Filters the `event`, mapping it to a WebSocket message, or `None` if it should be filtered out.
+async fn filter_map_ws_event(
+    event: &ServerSentEvent,
+    filter: &SubscriptionFilter,
+    deploy_getter: DeployGetter,
+) -> Option<Message> {
+    if !filter.should_include(&event.data) {
+        return None;
+    }
+
+    let data = match &event.data {
+        SseData::DeployAccepted {
+            deploy: deploy_hash,
+        } => {
+            let deploy_accepted = get_deploy(*deploy_hash, deploy_getter).await?;
+            if !filter.includes_account(deploy_accepted.header().account()) {
+                return None;
+            }
+            WsEventData::DeployAccepted(DeployAccepted { deploy_accepted })
+        }
+        data => WsEventData::Sse(data),
+    };
+
+    match serde_json::to_string(&WsEvent { id: event.id, data }) {
+        Ok(json) => Some(Message::text(json)),
+        Err(error) => {
+            warn!(%error, ?event, "failed to jsonify websocket event");
+            None
+        }
+    }
+}
+
+/// Handles a text message sent by the subscriber, returning a reply to send if the message was
+/// invalid.
+fn handle_client_message(text: &str, filter: &mut SubscriptionFilter) -> Option<Message> {
+    let result = serde_json::from_str::<WsFilterMessage>(text)
+        .map_err(|error| error.to_string())
+        .and_then(|filter_message| {
+            debug!(?filter_message, "updating websocket subscriber filter");
+            filter_message.apply_to(filter)
+        });
+    match result {
+        Ok(()) => None,
+        Err(error) => {
+            let reply = WsError {
+                error: format!("invalid filter message: {}", error),
+            };
+            serde_json::to_string(&reply).ok().map(Message::text)
+        }
+    }
+}
+
+/// Serves the initial and then the ongoing events to the subscriber, while handling any filter
+/// messages it sends, until either the subscriber disconnects or the server shuts down.
+///
+/// Events missed due to the subscriber lagging are skipped.
+async fn handle_ws_client(
+    websocket: WebSocket,
+    initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
+    ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
+    mut filter: SubscriptionFilter,
+    deploy_getter: DeployGetter,
+) {
+    let (mut ws_sender, mut ws_receiver) = websocket.split();
+    let mut events = Box::pin(deduplicated_events(initial_events, ongoing_events));
+
+    loop {
+        select! {
+            maybe_message = ws_receiver.next() => {
+                let message = match maybe_message {
+                    Some(Ok(message)) => message,
+                    Some(Err(error)) => {
+                        debug!(%error, "websocket subscriber error");
+                        break;
+                    }
+                    None => break,
+                };
+                if message.is_close() {
+                    break;
+                }
+                // Pings are answered by the websocket implementation, and anything else other than
+                // text is ignored.
+                let text = match message.to_str() {
+                    Ok(text) => text,
+                    Err(()) => continue,
+                };
+                if let Some(reply) = handle_client_message(text, &mut filter) {
+                    if ws_sender.send(reply).await.is_err() {
+                        break;
+                    }
+                }
+            }
+
+            maybe_event = events.next() => {
+                let event = match maybe_event {
+                    Some(Ok(event)) => event,
+                    Some(Err(RecvError::Lagged(amount))) => {
+                        info!(%amount, "websocket subscriber lagged: skipping missed events");
+                        continue;
+                    }
+                    Some(Err(RecvError::Closed)) | None => break,
+                };
+                if let Some(message) =
+                    filter_map_ws_event(&event, &filter, deploy_getter.clone()).await
+                {
+                    if ws_sender.send(message).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    let _ = ws_sender.close().await;
+}
+
+/// Creates the warp filter serving `/events/ws`.
+pub(super) fn create_ws_filter(
+    broadcaster: broadcast::Sender<BroadcastChannelMessage>,
+    new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+    max_concurrent_subscribers: u32,
+    deploy_getter: DeployGetter,
+) -> BoxedFilter<(Response,)> {
+    warp::get()
+        .and(path(SSE_API_ROOT_PATH))
+        .and(path(WS_API_PATH))
+        .and(path::end())
+        .and(warp::query())
+        .and(warp::ws())
+        .map(move |query: HashMap<String, String>, ws: Ws| {
+            // If we already have the maximum number of subscribers, reject this new one.
+            if broadcaster.receiver_count() >= max_concurrent_subscribers as usize {
+                info!(
+                    %max_concurrent_subscribers,
+                    "event stream server has max subscribers: rejecting new one"
+                );
+                return create_503();
+            }
+
+            let (start_from, subscription_filter) = match parse_query(query) {
+                Ok(parsed_query) => parsed_query,
+                Err(error_response) => return error_response,
+            };
+
+            // As for an SSE subscriber, the server provides the initial events via a dedicated
+            // channel, and the ongoing ones via the broadcast channel.
+            let (initial_events_sender, initial_events_receiver) = mpsc::unbounded_channel();
+            let new_subscriber_info = NewSubscriberInfo {
+                start_from,
+                initial_events_sender,
+            };
+            if new_subscriber_info_sender
+                .send(new_subscriber_info)
+                .is_err()
+            {
+                error!("failed to send new subscriber info");
+            }
+            let ongoing_events_receiver = broadcaster.subscribe();
+
+            let deploy_getter = deploy_getter.clone();
+            ws.on_upgrade(move |websocket| {
+                handle_ws_client(
+                    websocket,
+                    initial_events_receiver,
+                    ongoing_events_receiver,
+                    subscription_filter,
+                    deploy_getter,
+                )
+            })
+            .into_response()
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::crypto::AsymmetricKeyExt;
+
+    fn to_json(message: Message) -> serde_json::Value {
+        serde_json::from_str(message.to_str().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_send_all_events_by_default() {
+        let mut rng = crate::new_rng();
+        let filter = SubscriptionFilter::default();
+        let deploy_getter = DeployGetter::with_deploys(HashMap::new());
+
+        let api_version = ServerSentEvent {
+            id: None,
+            data: SseData::random_api_version(&mut rng),
+        };
+        let json = to_json(
+            filter_map_ws_event(&api_version, &filter, deploy_getter.clone())
+                .await
+                .unwrap(),
+        );
+        assert!(json.get("id").is_none());
+        assert_eq!(
+            json["data"],
+            serde_json::to_value(&api_version.data).unwrap()
+        );
+
+        for data in vec![
+            SseData::random_block_added(&mut rng),
+            SseData::random_deploy_processed(&mut rng),
+            SseData::random_deploy_expired(&mut rng),
+            SseData::random_deploy_superseded(&mut rng),
+            SseData::random_fault(&mut rng),
+            SseData::random_finality_signature(&mut rng),
+            SseData::random_step(&mut rng),
+        ] {
+            let event = ServerSentEvent {
+                id: Some(rng.gen()),
+                data,
+            };
+            let json = to_json(
+                filter_map_ws_event(&event, &filter, deploy_getter.clone())
+                    .await
+                    .unwrap(),
+            );
+            assert_eq!(json["id"], event.id.unwrap());
+            assert_eq!(json["data"], serde_json::to_value(&event.data).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_filter_by_event_type() {
+        let mut rng = crate::new_rng();
+        let mut filter = SubscriptionFilter::default();
+        assert!(handle_client_message(r#"{"event_types":["Step"]}"#, &mut filter).is_none());
+        let deploy_getter = DeployGetter::with_deploys(HashMap::new());
+
+        let block_added = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_block_added(&mut rng),
+        };
+        let step = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_step(&mut rng),
+        };
+        assert!(
+            filter_map_ws_event(&block_added, &filter, deploy_getter.clone())
+                .await
+                .is_none()
+        );
+        assert!(filter_map_ws_event(&step, &filter, deploy_getter)
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn should_filter_by_account() {
+        let mut rng = crate::new_rng();
+        let (deploy_accepted, deploy) = SseData::random_deploy_accepted(&mut rng);
+        let account = deploy.header().account().clone();
+        let deploy_getter =
+            DeployGetter::with_deploys(iter::once((*deploy.id(), deploy.clone())).collect());
+        let deploy_accepted = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: deploy_accepted,
+        };
+        let block_added = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_block_added(&mut rng),
+        };
+
+        // Filtering on another account should exclude the deploy, but not the block.
+        let other_account = PublicKey::random(&mut rng);
+        let mut filter = SubscriptionFilter::default();
+        WsFilterMessage {
+            event_types: None,
+            accounts: Some(vec![other_account]),
+            keys: None,
+        }
+        .apply_to(&mut filter)
+        .unwrap();
+        assert!(
+            filter_map_ws_event(&deploy_accepted, &filter, deploy_getter.clone())
+                .await
+                .is_none()
+        );
+        assert!(
+            filter_map_ws_event(&block_added, &filter, deploy_getter.clone())
+                .await
+                .is_some()
+        );
+
+        // Filtering on the deploy's account should include it.
+        WsFilterMessage {
+            event_types: None,
+            accounts: Some(vec![account]),
+            keys: None,
+        }
+        .apply_to(&mut filter)
+        .unwrap();
+        let json = to_json(
+            filter_map_ws_event(&deploy_accepted, &filter, deploy_getter)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(
+            json["data"]["DeployAccepted"],
+            serde_json::to_value(&deploy).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_reply_to_invalid_filter_message() {
+        let mut filter = SubscriptionFilter::default();
+        let reply = handle_client_message(r#"{"event_types":["Unknown"]}"#, &mut filter).unwrap();
+        assert!(to_json(reply)["error"].is_string());
+        assert_eq!(filter, SubscriptionFilter::default());
+
+        assert!(handle_client_message("not json", &mut filter).is_some());
+        assert_eq!(filter, SubscriptionFilter::default());
+
+        let reply = handle_client_message(
+            r#"{"event_types":["Step"],"keys":["not-a-key"]}"#,
+            &mut filter,
+        );
+        assert!(reply.is_some());
+        assert_eq!(filter, SubscriptionFilter::default());
+    }
+}
@@ -173,11 +173,21 @@ where
             Event::NewLinearChainBlock {
                 block,
                 execution_results,
+                contract_events,
             } => {
+                let block_hash = *block.hash();
                 let outcomes = self
                     .linear_chain_state
                     .handle_new_block(block, execution_results);
-                outcomes_to_effects(effect_builder, outcomes)
+                let mut effects = outcomes_to_effects(effect_builder, outcomes);
+                if !contract_events.is_empty() {
+                    effects.extend(
+                        effect_builder
+                            .put_contract_events_to_storage(block_hash, contract_events)
+                            .ignore(),
+                    );
+                }
+                effects
             }
             Event::PutBlockResult { block } => {
                 let completion_duration = block.header().timestamp().elapsed().millis();
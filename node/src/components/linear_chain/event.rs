@@ -3,7 +3,7 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-use casper_types::ExecutionResult;
+use casper_types::{ContractEvent, ExecutionResult};
 use derive_more::From;
 
 use crate::{
@@ -22,6 +22,8 @@ pub(crate) enum Event<I> {
         block: Box<Block>,
         /// The deploys' execution results.
         execution_results: HashMap<DeployHash, ExecutionResult>,
+        /// The events emitted by contracts while executing the deploys.
+        contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
     },
     /// Finality signature received.
     /// Not necessarily _new_ finality signature.
@@ -0,0 +1,101 @@
+//! Errors that the fast sync component may raise.
+
+use casper_execution_engine::core::engine_state::Error as EngineStateError;
+use casper_types::{EraId, U512};
+
+use crate::{
+    crypto,
+    types::{BlockHash, BlockValidationError, DeployConfigurationFailure, DeployHash, Tag},
+};
+
+/// An error raised while fast syncing the linear chain.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    /// None of the connected peers provided a valid item.
+    #[error("could not fetch {tag} with id {id} from any peer")]
+    CouldNotFetch {
+        /// The tag of the item.
+        tag: Tag,
+        /// The ID of the item.
+        id: String,
+    },
+    /// The fetched block header does not extend the previously synced block.
+    #[error(
+        "block {block_hash} at height {height} is not a child of block {expected_parent_hash}"
+    )]
+    NotAChild {
+        /// Hash of the fetched block.
+        block_hash: BlockHash,
+        /// Height of the fetched block.
+        height: u64,
+        /// Hash of the block the fetched block was expected to extend.
+        expected_parent_hash: BlockHash,
+    },
+    /// The finality signatures were created for a different block.
+    #[error("finality signatures for block {signed_block_hash} provided for block {block_hash}")]
+    SignaturesForWrongBlock {
+        /// Hash of the block which should have been signed.
+        block_hash: BlockHash,
+        /// Hash of the block which was signed.
+        signed_block_hash: BlockHash,
+    },
+    /// The finality signatures were created in a different era than the block.
+    #[error("finality signatures for block {block_hash} in era {signed_era_id}, not {era_id}")]
+    SignaturesForWrongEra {
+        /// Hash of the signed block.
+        block_hash: BlockHash,
+        /// Era of the signed block.
+        era_id: EraId,
+        /// Era the signatures claim to be from.
+        signed_era_id: EraId,
+    },
+    /// One of the finality signatures is invalid.
+    #[error("invalid finality signature for block {block_hash}: {error}")]
+    InvalidSignature {
+        /// Hash of the signed block.
+        block_hash: BlockHash,
+        /// The verification error.
+        error: crypto::Error,
+    },
+    /// The signers' combined weight does not exceed the finality threshold.
+    #[error(
+        "insufficient finality signatures for block {block_hash}: signed weight {signed_weight} \
+         of total weight {total_weight}"
+    )]
+    InsufficientSignatures {
+        /// Hash of the signed block.
+        block_hash: BlockHash,
+        /// Combined weight of the validators who signed the block.
+        signed_weight: U512,
+        /// Total weight of the era's validators.
+        total_weight: U512,
+    },
+    /// The validators of an era could not be determined from the synced switch blocks.
+    #[error(
+        "no switch block found for the era before era {era_id}; the trusted block must not be \
+         in the genesis era"
+    )]
+    MissingValidatorWeights {
+        /// The era whose validators are unknown.
+        era_id: EraId,
+    },
+    /// The fetched block's hash or body hash doesn't match its contents.
+    #[error("invalid block {block_hash}: {error}")]
+    InvalidBlock {
+        /// Hash of the requested block.
+        block_hash: BlockHash,
+        /// The validation error.
+        error: Box<BlockValidationError>,
+    },
+    /// The fetched deploy's hashes or approvals are invalid.
+    #[error("invalid deploy {deploy_hash}: {error}")]
+    InvalidDeploy {
+        /// Hash of the requested deploy.
+        deploy_hash: DeployHash,
+        /// The validation error.
+        error: DeployConfigurationFailure,
+    },
+    /// Error storing a trie node in the global state.
+    #[error("failed to store global state trie: {0}")]
+    PutTrie(#[from] EngineStateError),
+}
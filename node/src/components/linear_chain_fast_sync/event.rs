@@ -0,0 +1,31 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::{operations::Outcome, Error};
+
+#[derive(Debug)]
+pub(crate) enum Event {
+    /// The result of the fast sync task.
+    SyncResult(Box<Result<Outcome, Error>>),
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::SyncResult(result) => match &**result {
+                Ok(Outcome::Synced(block_header)) => {
+                    write!(f, "fast sync finished at {}", block_header.hash())
+                }
+                Ok(Outcome::Stopped {
+                    latest_header,
+                    stop_reason,
+                }) => write!(
+                    f,
+                    "fast sync stopped at {}: {:?}",
+                    latest_header.hash(),
+                    stop_reason
+                ),
+                Err(error) => write!(f, "fast sync failed: {}", error),
+            },
+        }
+    }
+}
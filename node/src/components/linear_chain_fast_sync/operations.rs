@@ -0,0 +1,540 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use futures::{
+    future::{BoxFuture, FutureExt},
+    stream::{FuturesUnordered, StreamExt},
+};
+use num::rational::Ratio;
+use tracing::{debug, info, trace, warn};
+
+use casper_execution_engine::storage::trie::Trie;
+use casper_hashing::Digest;
+use casper_types::{Key, ProtocolVersion, PublicKey, StoredValue, U512};
+
+use super::{Error, ReactorEventT};
+use crate::{
+    components::{fetcher::FetchResult, linear_chain_sync::StopReason},
+    effect::{requests::FetcherRequest, EffectBuilder},
+    types::{
+        Block, BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockSignatures, Chainspec, Deploy,
+        Item, NodeId,
+    },
+};
+
+/// How long to wait before asking for connected peers again when there are none.
+const WAIT_FOR_PEERS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many trie nodes to store between two progress reports.
+const TRIE_PROGRESS_INTERVAL: u64 = 10_000;
+
+/// How long to wait before requesting a trie node again that no peer provided the first time.
+const TRIE_RETRY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The longest time to wait before requesting a trie node again.
+const TRIE_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How fast syncing the linear chain ended.
+#[derive(Debug)]
+pub(crate) enum Outcome {
+    /// The global state of the highest block has been synced.
+    Synced(BlockHeader),
+    /// The chain is at a different protocol version than ours, so the node has to be restarted
+    /// with a different version to continue.
+    Stopped {
+        /// The header of the highest block that was synced.
+        latest_header: BlockHeader,
+        /// Whether the node needs to upgrade or downgrade.
+        stop_reason: StopReason,
+    },
+}
+
+/// Fast syncs the linear chain from the trusted block, returning the header of the highest block
+/// whose global state has been synced, unless the node needs to stop for an upgrade or downgrade.
+pub(super) async fn run_fast_sync_task<REv>(
+    effect_builder: EffectBuilder<REv>,
+    chainspec: Arc<Chainspec>,
+    trusted_hash: BlockHash,
+    max_parallel_trie_fetches: usize,
+) -> Result<Outcome, Error>
+where
+    REv: ReactorEventT,
+{
+    let trusted_header = fetch_and_store_block_header(effect_builder, trusted_hash).await?;
+    let trusted_era_validator_weights =
+        sync_ancestors(effect_builder, &chainspec, &trusted_header).await?;
+    let (latest_header, maybe_stop_reason) = sync_descendants(
+        effect_builder,
+        &chainspec,
+        trusted_header,
+        trusted_era_validator_weights,
+    )
+    .await?;
+    if let Some(stop_reason) = maybe_stop_reason {
+        return Ok(Outcome::Stopped {
+            latest_header,
+            stop_reason,
+        });
+    }
+    sync_deploys(effect_builder, &chainspec, &latest_header).await?;
+    sync_trie_store(
+        effect_builder,
+        *latest_header.state_root_hash(),
+        max_parallel_trie_fetches.max(1),
+    )
+    .await?;
+    Ok(Outcome::Synced(latest_header))
+}
+
+/// Returns the connected peers running at least `min_protocol_version` in random order, waiting
+/// until at least one is connected.
+async fn wait_for_peers<REv>(
+    effect_builder: EffectBuilder<REv>,
+    min_protocol_version: ProtocolVersion,
+) -> Vec<NodeId>
+where
+    REv: ReactorEventT,
+{
+    loop {
+        let peers = effect_builder
+            .get_peers_with_protocol_version_in_random_order(min_protocol_version)
+            .await;
+        if !peers.is_empty() {
+            return peers;
+        }
+        debug!(%min_protocol_version, "no suitable peers connected yet, waiting");
+        effect_builder.set_timeout(WAIT_FOR_PEERS_INTERVAL).await;
+    }
+}
+
+/// Fetches the item with the given ID, asking each connected peer that understands the item's tag
+/// in turn until one of them provides an item accepted by `validate`.
+async fn fetch_from_peers<T, REv, F>(
+    effect_builder: EffectBuilder<REv>,
+    id: T::Id,
+    validate: F,
+) -> Result<T, Error>
+where
+    T: Item + 'static,
+    REv: ReactorEventT + From<FetcherRequest<NodeId, T>>,
+    F: Fn(&T) -> Result<(), Error>,
+{
+    for peer in wait_for_peers(effect_builder, T::TAG.min_protocol_version()).await {
+        let item = match effect_builder.fetch::<T, NodeId>(id, peer).await {
+            Some(FetchResult::FromStorage(item)) | Some(FetchResult::FromPeer(item, _)) => item,
+            None => {
+                trace!(tag = %T::TAG, %id, %peer, "peer did not provide item");
+                continue;
+            }
+        };
+        match validate(&*item) {
+            Ok(()) => return Ok(*item),
+            Err(error) => warn!(tag = %T::TAG, %id, %peer, %error, "received invalid item"),
+        }
+    }
+    Err(Error::CouldNotFetch {
+        tag: T::TAG,
+        id: id.to_string(),
+    })
+}
+
+/// Fetches the block header with the given hash and puts it into storage.
+///
+/// The fetcher only accepts a header whose hash matches the requested one, so the header needs no
+/// further validation.
+async fn fetch_and_store_block_header<REv>(
+    effect_builder: EffectBuilder<REv>,
+    block_hash: BlockHash,
+) -> Result<BlockHeader, Error>
+where
+    REv: ReactorEventT,
+{
+    let block_header: BlockHeader =
+        fetch_from_peers(effect_builder, block_hash, |_| Ok(())).await?;
+    effect_builder
+        .put_block_header_to_storage(Box::new(block_header.clone()))
+        .await;
+    Ok(block_header)
+}
+
+/// Fetches the ancestors of the trusted block by following parent hashes, until the switch blocks
+/// the consensus component needs to initialize the recent eras are in storage.
+///
+/// Returns the validator weights of the trusted block's era, unless it is the genesis era.
+async fn sync_ancestors<REv>(
+    effect_builder: EffectBuilder<REv>,
+    chainspec: &Chainspec,
+    trusted_header: &BlockHeader,
+) -> Result<Option<BTreeMap<PublicKey, U512>>, Error>
+where
+    REv: ReactorEventT,
+{
+    // Consensus looks up the key blocks of the last `3 * bonded_eras` eras, and the booking blocks
+    // another `auction_delay + 1` eras before them.
+    let core_config = &chainspec.core_config;
+    let bonded_eras = core_config
+        .unbonding_delay
+        .saturating_sub(core_config.auction_delay);
+    let oldest_era_id = trusted_header.era_id().saturating_sub(
+        bonded_eras
+            .saturating_mul(3)
+            .saturating_add(core_config.auction_delay)
+            .saturating_add(1),
+    );
+
+    let mut validator_weights = None;
+    let mut current_header = trusted_header.clone();
+    while current_header.height() > 0 {
+        let parent_header =
+            fetch_and_store_block_header(effect_builder, *current_header.parent_hash()).await?;
+        if parent_header.is_switch_block() {
+            // The nearest switch block ancestor concludes the era before the trusted block's era.
+            if validator_weights.is_none() {
+                validator_weights = parent_header.next_era_validator_weights().cloned();
+            }
+            if parent_header.era_id() < oldest_era_id {
+                break;
+            }
+        }
+        current_header = parent_header;
+    }
+    info!(
+        height = current_header.height(),
+        "finished syncing ancestors of the trusted block"
+    );
+    Ok(validator_weights)
+}
+
+/// Fetches the descendants of the trusted block with their finality signatures, until no peer
+/// provides a properly signed child of the highest block.  Returns the highest block's header.
+///
+/// If a child has a newer protocol version than ours, it is not stored, and the node has to stop
+/// for an upgrade.  If the highest block is older than our protocol version, and our version is not
+/// activated right after it, the node has to stop for a downgrade.
+async fn sync_descendants<REv>(
+    effect_builder: EffectBuilder<REv>,
+    chainspec: &Chainspec,
+    trusted_header: BlockHeader,
+    trusted_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+) -> Result<(BlockHeader, Option<StopReason>), Error>
+where
+    REv: ReactorEventT,
+{
+    let finality_threshold_fraction = chainspec.highway_config.finality_threshold_fraction;
+    let mut validator_weights = trusted_header
+        .next_era_validator_weights()
+        .cloned()
+        .or(trusted_era_validator_weights);
+    let mut latest_header = trusted_header;
+    loop {
+        let weights = validator_weights
+            .as_ref()
+            .ok_or_else(|| Error::MissingValidatorWeights {
+                era_id: latest_header.next_block_era_id(),
+            })?;
+        let parent_header = &latest_header;
+        let result = fetch_from_peers(
+            effect_builder,
+            latest_header.height() + 1,
+            |item: &BlockHeaderWithMetadata| {
+                validate_child(parent_header, &item.block_header)?;
+                validate_finality_signatures(
+                    &item.block_header,
+                    &item.block_signatures,
+                    weights,
+                    finality_threshold_fraction,
+                )
+            },
+        )
+        .await;
+        let BlockHeaderWithMetadata {
+            block_header,
+            block_signatures,
+        } = match result {
+            Ok(block_header_with_metadata) => block_header_with_metadata,
+            Err(Error::CouldNotFetch { .. }) => break,
+            Err(error) => return Err(error),
+        };
+        if block_header.protocol_version() > chainspec.protocol_version() {
+            info!(
+                block_hash = %block_header.hash(),
+                block_version = %block_header.protocol_version(),
+                our_version = %chainspec.protocol_version(),
+                "found block with a newer protocol version; stopping fast sync to upgrade"
+            );
+            return Ok((latest_header, Some(StopReason::ForUpgrade)));
+        }
+        trace!(
+            height = block_header.height(),
+            "synced descendant of trusted block"
+        );
+        effect_builder
+            .put_block_header_to_storage(Box::new(block_header.clone()))
+            .await;
+        effect_builder
+            .put_signatures_to_storage(block_signatures)
+            .await;
+        if let Some(next_era_validator_weights) = block_header.next_era_validator_weights() {
+            validator_weights = Some(next_era_validator_weights.clone());
+        }
+        latest_header = block_header;
+    }
+    info!(
+        block_hash = %latest_header.hash(),
+        height = latest_header.height(),
+        "finished syncing descendants of the trusted block"
+    );
+    let is_our_version_activated = latest_header.is_switch_block()
+        && chainspec
+            .protocol_config
+            .activation_point
+            .should_upgrade(&latest_header.era_id());
+    if latest_header.protocol_version() < chainspec.protocol_version() && !is_our_version_activated
+    {
+        info!(
+            block_version = %latest_header.protocol_version(),
+            our_version = %chainspec.protocol_version(),
+            "highest block has an older protocol version; stopping fast sync to downgrade"
+        );
+        return Ok((latest_header, Some(StopReason::ForDowngrade)));
+    }
+    Ok((latest_header, None))
+}
+
+/// Fetches the blocks from the last `max_ttl` before the highest synced block, together with their
+/// deploys, and puts them into storage.
+///
+/// Without them, a deploy that is included in one of those blocks and hasn't expired yet could be
+/// included in a new block again, since replay protection only knows about stored blocks.
+async fn sync_deploys<REv>(
+    effect_builder: EffectBuilder<REv>,
+    chainspec: &Chainspec,
+    latest_header: &BlockHeader,
+) -> Result<(), Error>
+where
+    REv: ReactorEventT,
+{
+    let earliest_timestamp = latest_header
+        .timestamp()
+        .saturating_sub(chainspec.deploy_config.max_ttl);
+    let mut current_header = latest_header.clone();
+    let mut block_count: u64 = 0;
+    while current_header.timestamp() >= earliest_timestamp {
+        fetch_and_store_block_with_deploys(effect_builder, current_header.hash()).await?;
+        block_count += 1;
+        if current_header.height() == 0 {
+            break;
+        }
+        let parent_hash = *current_header.parent_hash();
+        current_header = match effect_builder
+            .get_block_header_from_storage(parent_hash)
+            .await
+        {
+            Some(parent_header) => parent_header,
+            None => fetch_and_store_block_header(effect_builder, parent_hash).await?,
+        };
+    }
+    info!(
+        block_count,
+        height = current_header.height(),
+        "finished syncing blocks and deploys within the maximum deploy TTL"
+    );
+    Ok(())
+}
+
+/// Fetches the block with the given hash and all its deploys and transfers, and puts them into
+/// storage.
+async fn fetch_and_store_block_with_deploys<REv>(
+    effect_builder: EffectBuilder<REv>,
+    block_hash: BlockHash,
+) -> Result<(), Error>
+where
+    REv: ReactorEventT,
+{
+    let block: Block = fetch_from_peers(effect_builder, block_hash, |block: &Block| {
+        block.verify().map_err(|error| Error::InvalidBlock {
+            block_hash,
+            error: Box::new(error),
+        })
+    })
+    .await?;
+    for deploy_hash in block.deploy_hashes().iter().chain(block.transfer_hashes()) {
+        let deploy: Deploy = fetch_from_peers(effect_builder, *deploy_hash, |deploy: &Deploy| {
+            deploy
+                .clone()
+                .is_valid()
+                .map_err(|error| Error::InvalidDeploy {
+                    deploy_hash: *deploy_hash,
+                    error,
+                })
+        })
+        .await?;
+        effect_builder.put_deploy_to_storage(Box::new(deploy)).await;
+    }
+    trace!(height = block.height(), "synced block and its deploys");
+    effect_builder.put_block_to_storage(Box::new(block)).await;
+    Ok(())
+}
+
+/// Checks that `block_header` is the child of `parent_header`.
+fn validate_child(parent_header: &BlockHeader, block_header: &BlockHeader) -> Result<(), Error> {
+    let expected_parent_hash = parent_header.hash();
+    if *block_header.parent_hash() != expected_parent_hash
+        || block_header.height() != parent_header.height() + 1
+    {
+        return Err(Error::NotAChild {
+            block_hash: block_header.hash(),
+            height: block_header.height(),
+            expected_parent_hash,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that the finality signatures are valid signatures of the given block, and that the
+/// signers' combined weight exceeds the finality threshold, i.e. at least one of them is honest.
+pub(super) fn validate_finality_signatures(
+    block_header: &BlockHeader,
+    block_signatures: &BlockSignatures,
+    validator_weights: &BTreeMap<PublicKey, U512>,
+    finality_threshold_fraction: Ratio<u64>,
+) -> Result<(), Error> {
+    let block_hash = block_header.hash();
+    if block_signatures.block_hash != block_hash {
+        return Err(Error::SignaturesForWrongBlock {
+            block_hash,
+            signed_block_hash: block_signatures.block_hash,
+        });
+    }
+    if block_signatures.era_id != block_header.era_id() {
+        return Err(Error::SignaturesForWrongEra {
+            block_hash,
+            era_id: block_header.era_id(),
+            signed_era_id: block_signatures.era_id,
+        });
+    }
+    block_signatures
+        .verify()
+        .map_err(|error| Error::InvalidSignature { block_hash, error })?;
+
+    let total_weight = validator_weights
+        .values()
+        .fold(U512::zero(), |sum, weight| sum + *weight);
+    let signed_weight = block_signatures
+        .proofs
+        .keys()
+        .filter_map(|public_key| validator_weights.get(public_key))
+        .fold(U512::zero(), |sum, weight| sum + *weight);
+    if signed_weight * U512::from(*finality_threshold_fraction.denom())
+        <= total_weight * U512::from(*finality_threshold_fraction.numer())
+    {
+        return Err(Error::InsufficientSignatures {
+            block_hash,
+            signed_weight,
+            total_weight,
+        });
+    }
+    Ok(())
+}
+
+/// The result of a step in syncing the trie store.
+enum TrieSyncStep {
+    /// The trie node was stored, and these are its missing descendants.
+    Stored(Vec<Digest>),
+    /// No peer provided the trie node, so it is going to be requested again after a backoff.
+    NotFound { trie_key: Digest, backoff: Duration },
+    /// The backoff of a trie node that was not found has elapsed.
+    Retry { trie_key: Digest, backoff: Duration },
+}
+
+/// Fetches the global state trie under `state_root_hash`, storing each trie node and then fetching
+/// its descendants which are missing from the trie store.  Up to `max_parallel_fetches` trie nodes
+/// are requested concurrently, each from its own randomly ordered list of peers.
+///
+/// Trie nodes that no peer provides are requested again after an exponentially increasing
+/// backoff, until some peer provides them.
+async fn sync_trie_store<REv>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    max_parallel_fetches: usize,
+) -> Result<(), Error>
+where
+    REv: ReactorEventT,
+{
+    info!(%state_root_hash, "syncing global state");
+    let mut missing_trie_keys = vec![(state_root_hash, TRIE_RETRY_INITIAL_BACKOFF)];
+    let mut steps_in_progress: FuturesUnordered<BoxFuture<'static, Result<TrieSyncStep, Error>>> =
+        FuturesUnordered::new();
+    // The number of entries in `steps_in_progress` that are waiting for a backoff to elapse.
+    let mut waiting_count: usize = 0;
+    let mut stored_count: u64 = 0;
+    loop {
+        while steps_in_progress.len() - waiting_count < max_parallel_fetches {
+            match missing_trie_keys.pop() {
+                Some((trie_key, backoff)) => steps_in_progress
+                    .push(fetch_and_store_trie(effect_builder, trie_key, backoff).boxed()),
+                None => break,
+            }
+        }
+        match steps_in_progress.next().await {
+            Some(step) => match step? {
+                TrieSyncStep::Stored(missing_descendants) => {
+                    missing_trie_keys.extend(
+                        missing_descendants
+                            .into_iter()
+                            .map(|trie_key| (trie_key, TRIE_RETRY_INITIAL_BACKOFF)),
+                    );
+                    stored_count += 1;
+                    if stored_count % TRIE_PROGRESS_INTERVAL == 0 {
+                        info!(
+                            stored_count,
+                            pending_count = missing_trie_keys.len() + steps_in_progress.len(),
+                            "syncing global state"
+                        );
+                    }
+                }
+                TrieSyncStep::NotFound { trie_key, backoff } => {
+                    warn!(%trie_key, ?backoff, "no peer provided trie node; retrying later");
+                    waiting_count += 1;
+                    steps_in_progress.push(
+                        async move {
+                            effect_builder.set_timeout(backoff).await;
+                            Ok(TrieSyncStep::Retry { trie_key, backoff })
+                        }
+                        .boxed(),
+                    );
+                }
+                TrieSyncStep::Retry { trie_key, backoff } => {
+                    waiting_count -= 1;
+                    missing_trie_keys.push((trie_key, (backoff * 2).min(TRIE_RETRY_MAX_BACKOFF)));
+                }
+            },
+            None => break,
+        }
+    }
+    info!(%state_root_hash, stored_count, "finished syncing global state");
+    Ok(())
+}
+
+/// Fetches a single trie node and puts it into the trie store, returning the keys of its missing
+/// descendants, or the trie node's key and current backoff if no peer provided it.
+async fn fetch_and_store_trie<REv>(
+    effect_builder: EffectBuilder<REv>,
+    trie_key: Digest,
+    backoff: Duration,
+) -> Result<TrieSyncStep, Error>
+where
+    REv: ReactorEventT,
+{
+    let trie: Trie<Key, StoredValue> =
+        match fetch_from_peers(effect_builder, trie_key, |_| Ok(())).await {
+            Ok(trie) => trie,
+            Err(Error::CouldNotFetch { .. }) => {
+                return Ok(TrieSyncStep::NotFound { trie_key, backoff })
+            }
+            Err(error) => return Err(error),
+        };
+    let missing_descendants = effect_builder
+        .put_trie_and_find_missing_descendant_trie_keys(Box::new(trie))
+        .await?;
+    Ok(TrieSyncStep::Stored(missing_descendants))
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use num::rational::Ratio;
+
+use casper_types::{EraId, ProtocolVersion, PublicKey, SecretKey, U512};
+
+use super::{operations::validate_finality_signatures, Error};
+use crate::{
+    crypto::generate_ed25519_keypair,
+    testing::TestRng,
+    types::{Block, BlockHeader, BlockSignatures, FinalitySignature},
+};
+
+/// Returns three validators with weights 10, 20 and 30.
+fn validators() -> Vec<(SecretKey, PublicKey, U512)> {
+    [10, 20, 30]
+        .iter()
+        .map(|weight| {
+            let (secret_key, public_key) = generate_ed25519_keypair();
+            (secret_key, public_key, U512::from(*weight))
+        })
+        .collect()
+}
+
+fn validator_weights(validators: &[(SecretKey, PublicKey, U512)]) -> BTreeMap<PublicKey, U512> {
+    validators
+        .iter()
+        .map(|(_, public_key, weight)| (public_key.clone(), *weight))
+        .collect()
+}
+
+fn random_block_header(rng: &mut TestRng) -> BlockHeader {
+    Block::random_with_specifics(rng, EraId::new(3), 10, ProtocolVersion::V1_0_0, false)
+        .take_header()
+}
+
+/// Returns the signatures of `block_header` by the given signers.
+fn sign(block_header: &BlockHeader, signers: &[&(SecretKey, PublicKey, U512)]) -> BlockSignatures {
+    let mut block_signatures = BlockSignatures::new(block_header.hash(), block_header.era_id());
+    for (secret_key, public_key, _) in signers {
+        let finality_signature = FinalitySignature::new(
+            block_header.hash(),
+            block_header.era_id(),
+            secret_key,
+            public_key.clone(),
+        );
+        block_signatures.insert_proof(public_key.clone(), finality_signature.signature);
+    }
+    block_signatures
+}
+
+#[test]
+fn should_accept_signatures_exceeding_threshold() {
+    let mut rng = TestRng::new();
+    let validators = validators();
+    let block_header = random_block_header(&mut rng);
+
+    // 30 of 60 exceeds one third.
+    let block_signatures = sign(&block_header, &[&validators[2]]);
+    assert!(validate_finality_signatures(
+        &block_header,
+        &block_signatures,
+        &validator_weights(&validators),
+        Ratio::new(1, 3),
+    )
+    .is_ok());
+}
+
+#[test]
+fn should_reject_signatures_not_exceeding_threshold() {
+    let mut rng = TestRng::new();
+    let validators = validators();
+    let block_header = random_block_header(&mut rng);
+
+    // 20 of 60 doesn't exceed one third.
+    let block_signatures = sign(&block_header, &[&validators[1]]);
+    let result = validate_finality_signatures(
+        &block_header,
+        &block_signatures,
+        &validator_weights(&validators),
+        Ratio::new(1, 3),
+    );
+    assert!(matches!(
+        result,
+        Err(Error::InsufficientSignatures { signed_weight, total_weight, .. })
+            if signed_weight == U512::from(20) && total_weight == U512::from(60)
+    ));
+}
+
+#[test]
+fn should_not_count_signatures_of_non_validators() {
+    let mut rng = TestRng::new();
+    let validators = validators();
+    let block_header = random_block_header(&mut rng);
+
+    // The third validator isn't a validator in the era of the block.
+    let block_signatures = sign(&block_header, &[&validators[0], &validators[2]]);
+    let result = validate_finality_signatures(
+        &block_header,
+        &block_signatures,
+        &validator_weights(&validators[..2]),
+        Ratio::new(1, 3),
+    );
+    assert!(matches!(
+        result,
+        Err(Error::InsufficientSignatures { signed_weight, .. }) if signed_weight == U512::from(10)
+    ));
+}
+
+#[test]
+fn should_reject_signatures_for_another_block() {
+    let mut rng = TestRng::new();
+    let validators = validators();
+    let block_header = random_block_header(&mut rng);
+    let other_block_header = random_block_header(&mut rng);
+
+    let block_signatures = sign(&other_block_header, &[&validators[2]]);
+    let result = validate_finality_signatures(
+        &block_header,
+        &block_signatures,
+        &validator_weights(&validators),
+        Ratio::new(1, 3),
+    );
+    assert!(matches!(result, Err(Error::SignaturesForWrongBlock { .. })));
+}
+
+#[test]
+fn should_reject_invalid_signatures() {
+    let mut rng = TestRng::new();
+    let validators = validators();
+    let block_header = random_block_header(&mut rng);
+
+    // Claim the signature of the largest validator was made by another validator.
+    let mut block_signatures = sign(&block_header, &[&validators[2]]);
+    let signature = block_signatures.proofs.remove(&validators[2].1).unwrap();
+    block_signatures.insert_proof(validators[1].1.clone(), signature);
+    let result = validate_finality_signatures(
+        &block_header,
+        &block_signatures,
+        &validator_weights(&validators),
+        Ratio::new(1, 3),
+    );
+    assert!(matches!(result, Err(Error::InvalidSignature { .. })));
+}
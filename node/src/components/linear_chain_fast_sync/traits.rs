@@ -0,0 +1,40 @@
+use casper_execution_engine::storage::trie::Trie;
+use casper_types::{Key, StoredValue};
+
+use crate::{
+    effect::{
+        announcements::ControlAnnouncement,
+        requests::{ContractRuntimeRequest, FetcherRequest, NetworkInfoRequest, StorageRequest},
+    },
+    types::{Block, BlockHeader, BlockHeaderWithMetadata, Deploy, NodeId},
+};
+
+pub(crate) trait ReactorEventT:
+    From<StorageRequest>
+    + From<FetcherRequest<NodeId, Block>>
+    + From<FetcherRequest<NodeId, Deploy>>
+    + From<FetcherRequest<NodeId, BlockHeader>>
+    + From<FetcherRequest<NodeId, BlockHeaderWithMetadata>>
+    + From<FetcherRequest<NodeId, Trie<Key, StoredValue>>>
+    + From<NetworkInfoRequest<NodeId>>
+    + From<ContractRuntimeRequest>
+    + From<ControlAnnouncement>
+    + Send
+    + 'static
+{
+}
+
+impl<REv> ReactorEventT for REv where
+    REv: From<StorageRequest>
+        + From<FetcherRequest<NodeId, Block>>
+        + From<FetcherRequest<NodeId, Deploy>>
+        + From<FetcherRequest<NodeId, BlockHeader>>
+        + From<FetcherRequest<NodeId, BlockHeaderWithMetadata>>
+        + From<FetcherRequest<NodeId, Trie<Key, StoredValue>>>
+        + From<NetworkInfoRequest<NodeId>>
+        + From<ContractRuntimeRequest>
+        + From<ControlAnnouncement>
+        + Send
+        + 'static
+{
+}
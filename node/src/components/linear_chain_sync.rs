@@ -61,8 +61,8 @@ use crate::{
     NodeRng,
 };
 pub(crate) use config::Config;
-pub(crate) use event::Event;
-use event::{BlockByHeightResult, StopReason};
+use event::BlockByHeightResult;
+pub(crate) use event::{Event, StopReason};
 pub(crate) use metrics::LinearChainSyncMetrics;
 pub(crate) use peers::PeersState;
 pub(crate) use state::State;
@@ -1132,6 +1132,7 @@ async fn execute_block<REv>(
     let BlockAndExecutionEffects {
         block,
         execution_results,
+        contract_events,
         ..
     } = match effect_builder
         .execute_finalized_block(
@@ -1150,6 +1151,6 @@ async fn execute_block<REv>(
         }
     };
     effect_builder
-        .announce_linear_chain_block(block, execution_results)
+        .announce_linear_chain_block(block, execution_results, contract_events)
         .await;
 }
@@ -5,23 +5,45 @@ use std::str::FromStr;
 use crate::types::TimeDiff;
 
 const DEFAULT_SYNC_TIMEOUT: &str = "5min";
+const DEFAULT_MAX_PARALLEL_TRIE_FETCHES: u32 = 64;
 
 /// Configuration options for fetching.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     sync_timeout: TimeDiff,
+    /// Whether to fast sync from the trusted hash instead of downloading and executing every block.
+    #[serde(default)]
+    fast_sync: bool,
+    /// Maximum number of global state trie nodes requested from peers concurrently when fast
+    /// syncing.
+    #[serde(default = "default_max_parallel_trie_fetches")]
+    max_parallel_trie_fetches: u32,
 }
 
 impl Config {
     pub(crate) fn get_sync_timeout(&self) -> TimeDiff {
         self.sync_timeout
     }
+
+    pub(crate) fn fast_sync(&self) -> bool {
+        self.fast_sync
+    }
+
+    pub(crate) fn max_parallel_trie_fetches(&self) -> u32 {
+        self.max_parallel_trie_fetches
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             sync_timeout: TimeDiff::from_str(DEFAULT_SYNC_TIMEOUT).unwrap(),
+            fast_sync: false,
+            max_parallel_trie_fetches: DEFAULT_MAX_PARALLEL_TRIE_FETCHES,
         }
     }
 }
+
+fn default_max_parallel_trie_fetches() -> u32 {
+    DEFAULT_MAX_PARALLEL_TRIE_FETCHES
+}
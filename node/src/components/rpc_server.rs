@@ -38,8 +38,8 @@ use crate::{
     effect::{
         announcements::RpcServerAnnouncement,
         requests::{
-            ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, LinearChainRequest,
-            MetricsRequest, NetworkInfoRequest, RpcRequest, StorageRequest,
+            BlockProposerRequest, ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+            LinearChainRequest, MetricsRequest, NetworkInfoRequest, RpcRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
@@ -55,6 +55,7 @@ pub(crate) trait ReactorEventT:
     From<Event>
     + From<RpcRequest<NodeId>>
     + From<RpcServerAnnouncement>
+    + From<BlockProposerRequest>
     + From<ChainspecLoaderRequest>
     + From<ContractRuntimeRequest>
     + From<ConsensusRequest>
@@ -70,6 +71,7 @@ impl<REv> ReactorEventT for REv where
     REv: From<Event>
         + From<RpcRequest<NodeId>>
         + From<RpcServerAnnouncement>
+        + From<BlockProposerRequest>
         + From<ChainspecLoaderRequest>
         + From<ContractRuntimeRequest>
         + From<ConsensusRequest>
@@ -104,6 +106,10 @@ impl RpcServer {
             effect_builder,
             api_version,
             config.qps_limit,
+            config.client_qps_limit,
+            config.max_batch_size,
+            config.max_body_bytes,
+            config.enable_speculative_exec_trace,
         ));
 
         Ok(RpcServer {
@@ -248,6 +254,24 @@ where
                         main_responder: responder,
                     })
             }
+            Event::RpcRequest(RpcRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                responder,
+            }) => effect_builder
+                .get_keys_by_prefix(get_keys_by_prefix_request)
+                .event(move |result| Event::GetKeysByPrefixResult {
+                    result,
+                    main_responder: responder,
+                }),
+            Event::RpcRequest(RpcRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                responder,
+            }) => effect_builder
+                .get_dictionary_items(get_dictionary_items_request)
+                .event(move |result| Event::GetDictionaryItemsResult {
+                    result,
+                    main_responder: responder,
+                }),
             Event::RpcRequest(RpcRequest::GetBalance {
                 state_root_hash,
                 purse_uref,
@@ -308,6 +332,14 @@ where
                 result,
                 main_responder,
             } => main_responder.respond(result).ignore(),
+            Event::GetKeysByPrefixResult {
+                result,
+                main_responder,
+            } => main_responder.respond(result).ignore(),
+            Event::GetDictionaryItemsResult {
+                result,
+                main_responder,
+            } => main_responder.respond(result).ignore(),
             Event::GetBalanceResult {
                 result,
                 main_responder,
@@ -7,6 +7,12 @@ use serde::{Deserialize, Serialize};
 const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 /// Default rate limit in qps.
 const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default rate limit in qps, per client IP address.
+const DEFAULT_CLIENT_QPS_LIMIT: u64 = 100;
+/// Default maximum number of requests in a single JSON-RPC batch.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 500;
+/// Default maximum size of a request body in bytes.
+const DEFAULT_MAX_BODY_BYTES: u32 = 10 * 1024 * 1024;
 
 /// JSON-RPC HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -18,6 +24,22 @@ pub struct Config {
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Max rate limit in qps, per client IP address.  Each request in a batch counts separately.
+    #[serde(default = "default_client_qps_limit")]
+    pub client_qps_limit: u64,
+
+    /// Maximum number of requests in a single JSON-RPC batch.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: u32,
+
+    /// Maximum size of a request body in bytes.
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: u32,
+
+    /// Whether "speculative_exec" requests may ask for a trace of the execution.
+    #[serde(default)]
+    pub enable_speculative_exec_trace: bool,
 }
 
 impl Config {
@@ -26,6 +48,10 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            client_qps_limit: DEFAULT_CLIENT_QPS_LIMIT,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            enable_speculative_exec_trace: false,
         }
     }
 }
@@ -35,3 +61,15 @@ impl Default for Config {
         Config::new()
     }
 }
+
+fn default_client_qps_limit() -> u64 {
+    DEFAULT_CLIENT_QPS_LIMIT
+}
+
+fn default_max_batch_size() -> u32 {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
+fn default_max_body_bytes() -> u32 {
+    DEFAULT_MAX_BODY_BYTES
+}
@@ -6,7 +6,8 @@ use std::{
 use derive_more::From;
 
 use casper_execution_engine::core::engine_state::{
-    self, BalanceResult, GetBidsResult, GetEraValidatorsError, QueryResult,
+    self, BalanceResult, GetBidsResult, GetDictionaryItemsResult, GetEraValidatorsError,
+    GetKeysByPrefixResult, QueryResult,
 };
 use casper_types::{system::auction::EraValidators, Transfer};
 
@@ -42,6 +43,14 @@ pub(crate) enum Event {
         result: Result<GetBidsResult, engine_state::Error>,
         main_responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    GetKeysByPrefixResult {
+        result: Result<GetKeysByPrefixResult, engine_state::Error>,
+        main_responder: Responder<Result<GetKeysByPrefixResult, engine_state::Error>>,
+    },
+    GetDictionaryItemsResult {
+        result: Result<GetDictionaryItemsResult, engine_state::Error>,
+        main_responder: Responder<Result<GetDictionaryItemsResult, engine_state::Error>>,
+    },
     GetDeployResult {
         hash: DeployHash,
         result: Box<Option<(Deploy, DeployMetadata)>>,
@@ -92,6 +101,12 @@ impl Display for Event {
             Event::GetBidsResult { result, .. } => {
                 write!(formatter, "get bids result: {:?}", result)
             }
+            Event::GetKeysByPrefixResult { result, .. } => {
+                write!(formatter, "get keys by prefix result: {:?}", result)
+            }
+            Event::GetDictionaryItemsResult { result, .. } => {
+                write!(formatter, "get dictionary items result: {:?}", result)
+            }
             Event::GetBalanceResult { result, .. } => {
                 write!(formatter, "balance result: {:?}", result)
             }
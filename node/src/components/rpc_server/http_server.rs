@@ -1,59 +1,381 @@
-use std::{convert::Infallible, time::Duration};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io::Write,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
+use bytes::{Bytes, BytesMut};
+use flate2::{write::GzEncoder, Compression};
 use futures::future;
-use http::{Response, StatusCode};
+use http::{header, HeaderMap, Request, Response, StatusCode};
 use hyper::{
-    server::{conn::AddrIncoming, Builder},
+    body::HttpBody,
+    server::{
+        conn::{AddrIncoming, AddrStream},
+        Builder,
+    },
+    service::Service,
     Body,
 };
 use serde::Serialize;
-use tokio::sync::oneshot;
+use serde_json::Value;
+use tokio::{sync::oneshot, time::Instant};
 use tower::builder::ServiceBuilder;
-use tracing::{info, trace};
+use tracing::{debug, info, trace};
 use warp::{Filter, Rejection};
 
 use casper_types::ProtocolVersion;
 
 use super::{
-    rpcs::{self, RpcWithOptionalParamsExt, RpcWithParamsExt, RpcWithoutParamsExt, RPC_API_PATH},
+    rpcs::{
+        self, ErrorCode, RpcWithOptionalParamsExt, RpcWithParamsExt, RpcWithoutParamsExt,
+        RPC_API_PATH,
+    },
     ReactorEventT,
 };
 use crate::effect::EffectBuilder;
 
+#[derive(Serialize)]
+struct JsonRpcErrorResponse {
+    jsonrpc: String,
+    id: Option<()>,
+    error: warp_json_rpc::Error,
+}
+
+impl JsonRpcErrorResponse {
+    fn new(error: warp_json_rpc::Error) -> Self {
+        JsonRpcErrorResponse {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            error,
+        }
+    }
+}
+
 // This is a workaround for not being able to create a `warp_json_rpc::Response` without a
 // `warp_json_rpc::Builder`.
 fn new_error_response(error: warp_json_rpc::Error) -> Response<Body> {
-    #[derive(Serialize)]
-    struct JsonRpcErrorResponse {
-        jsonrpc: String,
-        id: Option<()>,
-        error: warp_json_rpc::Error,
-    }
+    new_json_response(serde_json::to_vec(&JsonRpcErrorResponse::new(error)).unwrap())
+}
 
-    let json_response = JsonRpcErrorResponse {
-        jsonrpc: "2.0".to_string(),
-        id: None,
-        error,
-    };
+fn new_json_response(body: Vec<u8>) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
 
-    let body = Body::from(serde_json::to_vec(&json_response).unwrap());
+fn new_status_response(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn new_gzip_json_response(body: Vec<u8>) -> Response<Body> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&body).unwrap();
+    let compressed = encoder.finish().unwrap();
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
-        .body(body)
+        .header(header::CONTENT_ENCODING, "gzip")
+        .body(Body::from(compressed))
         .unwrap()
 }
 
+/// Returns whether the client accepts gzip responses, i.e. whether the "accept-encoding" headers
+/// list "gzip" or "*" without a zero quality value.
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|coding| {
+            let mut parts = coding.split(';').map(str::trim);
+            let name = parts.next().unwrap_or_default();
+            if !name.eq_ignore_ascii_case("gzip") && name != "*" {
+                return false;
+            }
+            parts
+                .filter_map(|param| param.strip_prefix("q="))
+                .all(|quality| {
+                    quality
+                        .parse::<f32>()
+                        .map_or(false, |quality| quality > 0.0)
+                })
+        })
+}
+
+/// Reads the request body, returning `None` if it exceeds `max_body_bytes`.
+///
+/// A "content-length" header exceeding the limit leads to rejection without reading the body.
+async fn read_body(
+    headers: &HeaderMap,
+    mut body: Body,
+    max_body_bytes: u32,
+) -> Result<Option<Bytes>, hyper::Error> {
+    let max_body_bytes = max_body_bytes as usize;
+    let exceeds_limit = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map_or(false, |content_length| {
+            content_length > max_body_bytes as u64
+        });
+    if exceeds_limit {
+        return Ok(None);
+    }
+
+    let mut bytes = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > max_body_bytes {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(Some(bytes.freeze()))
+}
+
+/// The time span over which the requests of a client are counted.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// The requests counted against a client's limit in the current window.
+struct RateLimitWindow {
+    start: Instant,
+    request_count: u64,
+}
+
+/// The current windows of all clients.
+struct ClientWindows {
+    windows: HashMap<IpAddr, RateLimitWindow>,
+    /// When expired windows were last removed.
+    last_sweep: Instant,
+}
+
+/// Limits the rate of requests of each client IP address.
+///
+/// Requests exceeding the limit are delayed to the next window.
+#[derive(Clone)]
+struct ClientRateLimiter {
+    qps_limit: u64,
+    windows: Arc<Mutex<ClientWindows>>,
+}
+
+impl ClientRateLimiter {
+    fn new(qps_limit: u64) -> Self {
+        ClientRateLimiter {
+            qps_limit: qps_limit.max(1),
+            windows: Arc::new(Mutex::new(ClientWindows {
+                windows: HashMap::new(),
+                last_sweep: Instant::now(),
+            })),
+        }
+    }
+
+    /// Waits until `request_count` requests of the given client are within the rate limit.
+    ///
+    /// Requests exceeding the remaining capacity of the current window are counted against the
+    /// following windows.
+    async fn acquire(&self, client: IpAddr, mut request_count: u64) {
+        loop {
+            let wait = {
+                let mut client_windows = self.windows.lock().expect("rate limiter lock poisoned");
+                let ClientWindows {
+                    windows,
+                    last_sweep,
+                } = &mut *client_windows;
+                let now = Instant::now();
+                // Forgetting expired windows once per window keeps the map from growing with every
+                // client ever seen.
+                if now.duration_since(*last_sweep) >= RATE_LIMIT_WINDOW {
+                    windows
+                        .retain(|_, window| now.duration_since(window.start) < RATE_LIMIT_WINDOW);
+                    *last_sweep = now;
+                }
+                let window = windows.entry(client).or_insert(RateLimitWindow {
+                    start: now,
+                    request_count: 0,
+                });
+                if now.duration_since(window.start) >= RATE_LIMIT_WINDOW {
+                    *window = RateLimitWindow {
+                        start: now,
+                        request_count: 0,
+                    };
+                }
+                let admitted = request_count.min(self.qps_limit - window.request_count);
+                window.request_count += admitted;
+                request_count -= admitted;
+                if request_count == 0 {
+                    return;
+                }
+                RATE_LIMIT_WINDOW - now.duration_since(window.start)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The limits applied to the requests of a client.
+#[derive(Clone)]
+struct RequestLimits {
+    max_batch_size: u32,
+    max_body_bytes: u32,
+    rate_limiter: ClientRateLimiter,
+}
+
+/// Returns whether the body holds a JSON-RPC batch, i.e. a JSON array.
+fn is_batch(body: &[u8]) -> bool {
+    body.iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .map_or(false, |byte| *byte == b'[')
+}
+
+/// Handles a single JSON-RPC request or a batch of them.
+///
+/// A single request is passed unchanged to `service`.  Each element of a batch is passed to
+/// `service` as a separate request, and the responses are returned in a JSON array, omitting those
+/// for notifications (elements with no "id").
+///
+/// Each single request and each element of a batch counts against the client's rate limit.
+async fn handle_request<S>(
+    mut service: S,
+    limits: RequestLimits,
+    client: IpAddr,
+    request: Request<Body>,
+) -> Result<Response<Body>, S::Error>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send,
+{
+    let (parts, body) = request.into_parts();
+    let body = match read_body(&parts.headers, body, limits.max_body_bytes).await {
+        Ok(Some(body)) => body,
+        Ok(None) => {
+            debug!(%client, "JSON-RPC request body too large");
+            return Ok(new_status_response(StatusCode::PAYLOAD_TOO_LARGE));
+        }
+        Err(error) => {
+            debug!(%error, "failed to read JSON-RPC request body");
+            return Ok(new_error_response(warp_json_rpc::Error::PARSE_ERROR));
+        }
+    };
+
+    if !is_batch(&body) {
+        limits.rate_limiter.acquire(client, 1).await;
+        future::poll_fn(|cx| service.poll_ready(cx)).await?;
+        return service
+            .call(Request::from_parts(parts, Body::from(body)))
+            .await;
+    }
+
+    let batch: Vec<Value> = match serde_json::from_slice(&body) {
+        Ok(batch) => batch,
+        Err(_) => return Ok(new_error_response(warp_json_rpc::Error::PARSE_ERROR)),
+    };
+    if batch.is_empty() {
+        return Ok(new_error_response(warp_json_rpc::Error::INVALID_REQUEST));
+    }
+    if batch.len() > limits.max_batch_size as usize {
+        let error = warp_json_rpc::Error::custom(
+            ErrorCode::BatchTooLarge as i64,
+            format!(
+                "batch of {} requests exceeds the maximum of {}",
+                batch.len(),
+                limits.max_batch_size
+            ),
+        );
+        return Ok(new_error_response(error));
+    }
+    limits
+        .rate_limiter
+        .acquire(client, batch.len() as u64)
+        .await;
+
+    let single_responses = batch.iter().map(|element| {
+        // Each element is handled as a separate request with the same headers, other than those
+        // describing the body, so the responses aren't compressed individually.
+        let mut builder = Request::builder()
+            .method(parts.method.clone())
+            .uri(parts.uri.clone())
+            .version(parts.version);
+        for (name, value) in parts.headers.iter() {
+            if name != header::CONTENT_LENGTH && name != header::ACCEPT_ENCODING {
+                builder = builder.header(name, value);
+            }
+        }
+        let single_request = builder
+            .body(Body::from(serde_json::to_vec(element).unwrap()))
+            .unwrap();
+        let is_notification = element
+            .as_object()
+            .map_or(false, |object| !object.contains_key("id"));
+
+        let mut service = service.clone();
+        async move {
+            future::poll_fn(|cx| service.poll_ready(cx)).await?;
+            let single_response = service.call(single_request).await?;
+            if is_notification {
+                return Ok(None);
+            }
+            let response_value = match hyper::body::to_bytes(single_response.into_body()).await {
+                Ok(bytes) => serde_json::from_slice(&bytes).ok(),
+                Err(_) => None,
+            }
+            .unwrap_or_else(|| {
+                serde_json::to_value(JsonRpcErrorResponse::new(
+                    warp_json_rpc::Error::INTERNAL_ERROR,
+                ))
+                .unwrap()
+            });
+            Ok::<_, S::Error>(Some(response_value))
+        }
+    });
+
+    let responses = future::try_join_all(single_responses)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<Value>>();
+
+    // As per the JSON-RPC spec, a batch consisting only of notifications gets no response.
+    if responses.is_empty() {
+        return Ok(Response::new(Body::empty()));
+    }
+    let body = serde_json::to_vec(&responses).unwrap();
+    if accepts_gzip(&parts.headers) {
+        return Ok(new_gzip_json_response(body));
+    }
+    Ok(new_json_response(body))
+}
+
 /// Run the JSON-RPC server.
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
+    client_qps_limit: u64,
+    max_batch_size: u32,
+    max_body_bytes: u32,
+    enable_speculative_exec_trace: bool,
 ) {
     // RPC filters.
     let rpc_put_deploy = rpcs::account::PutDeploy::create_filter(effect_builder, api_version);
+    let rpc_speculative_exec = rpcs::account::SpeculativeExec::create_filter_with_trace_config(
+        effect_builder,
+        api_version,
+        enable_speculative_exec_trace,
+    );
     let rpc_get_block = rpcs::chain::GetBlock::create_filter(effect_builder, api_version);
+    let rpc_get_blocks = rpcs::chain::GetBlocks::create_filter(effect_builder, api_version);
     let rpc_get_block_transfers =
         rpcs::chain::GetBlockTransfers::create_filter(effect_builder, api_version);
     let rpc_get_state_root_hash =
@@ -65,6 +387,12 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rpc_get_account_info =
         rpcs::state::GetAccountInfo::create_filter(effect_builder, api_version);
     let rpc_get_deploy = rpcs::info::GetDeploy::create_filter(effect_builder, api_version);
+    let rpc_get_deploy_events =
+        rpcs::info::GetDeployEvents::create_filter(effect_builder, api_version);
+    let rpc_get_balance_history =
+        rpcs::info::GetBalanceHistory::create_filter(effect_builder, api_version);
+    let rpc_get_account_deploys =
+        rpcs::info::GetAccountDeploys::create_filter(effect_builder, api_version);
     let rpc_get_peers = rpcs::info::GetPeers::create_filter(effect_builder, api_version);
     let rpc_get_status = rpcs::info::GetStatus::create_filter(effect_builder, api_version);
     let rpc_get_era_info =
@@ -72,8 +400,15 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rpc_get_auction_info =
         rpcs::state::GetAuctionInfo::create_filter(effect_builder, api_version);
     let rpc_get_trie = rpcs::state::GetTrie::create_filter(effect_builder, api_version);
+    let rpc_get_keys_by_prefix =
+        rpcs::state::GetKeysByPrefix::create_filter(effect_builder, api_version);
     let rpcs_get_validator_changes =
         rpcs::info::GetValidatorChanges::create_filter(effect_builder, api_version);
+    let rpc_get_pending_deploys =
+        rpcs::info::GetPendingDeploys::create_filter(effect_builder, api_version);
+    let rpc_get_evidence = rpcs::info::GetEvidence::create_filter(effect_builder, api_version);
+    let rpc_get_consensus_state =
+        rpcs::info::GetConsensusState::create_filter(effect_builder, api_version);
     let rpc_get_rpcs = rpcs::docs::ListRpcs::create_filter(effect_builder, api_version);
     let rpc_get_dictionary_item =
         rpcs::state::GetDictionaryItem::create_filter(effect_builder, api_version);
@@ -94,21 +429,30 @@ pub(super) async fn run<REv: ReactorEventT>(
     });
 
     let service_routes = rpc_put_deploy
+        .or(rpc_speculative_exec)
         .or(rpc_get_block)
+        .or(rpc_get_blocks)
         .or(rpc_get_block_transfers)
         .or(rpc_get_state_root_hash)
         .or(rpc_get_item)
         .or(rpc_get_balance)
         .or(rpc_get_deploy)
+        .or(rpc_get_deploy_events)
+        .or(rpc_get_balance_history)
+        .or(rpc_get_account_deploys)
         .or(rpc_get_peers)
         .or(rpc_get_status)
         .or(rpc_get_era_info)
         .or(rpc_get_auction_info)
         .or(rpc_get_account_info)
         .or(rpcs_get_validator_changes)
+        .or(rpc_get_pending_deploys)
+        .or(rpc_get_evidence)
+        .or(rpc_get_consensus_state)
         .or(rpc_get_rpcs)
         .or(rpc_get_dictionary_item)
         .or(rpc_get_trie)
+        .or(rpc_get_keys_by_prefix)
         .or(rpc_query_global_state)
         .or(unknown_method)
         .or(parse_failure);
@@ -124,9 +468,23 @@ pub(super) async fn run<REv: ReactorEventT>(
     //        update to or move away from warp_json_rpc.
     let service = warp_json_rpc::service(service_routes_gzip.or(service_routes));
 
+    // The rate limiter is shared by all connections, so that clients are limited however many
+    // connections they open.
+    let limits = RequestLimits {
+        max_batch_size,
+        max_body_bytes,
+        rate_limiter: ClientRateLimiter::new(client_qps_limit),
+    };
+
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
-    let make_svc =
-        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+    let make_svc = hyper::service::make_service_fn(move |connection: &AddrStream| {
+        let service = service.clone();
+        let limits = limits.clone();
+        let client = connection.remote_addr().ip();
+        future::ok::<_, Infallible>(hyper::service::service_fn(move |request| {
+            handle_request(service.clone(), limits.clone(), client, request)
+        }))
+    });
 
     let make_svc = ServiceBuilder::new()
         .rate_limit(qps_limit, Duration::from_secs(1))
@@ -150,3 +508,203 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     trace!("JSON-RPC server stopped");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::Ipv4Addr};
+
+    use flate2::read::GzDecoder;
+    use serde_json::json;
+
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    fn limits(max_batch_size: u32, max_body_bytes: u32) -> RequestLimits {
+        RequestLimits {
+            max_batch_size,
+            max_body_bytes,
+            rate_limiter: ClientRateLimiter::new(1_000),
+        }
+    }
+
+    fn post(body: &Value) -> http::request::Builder {
+        Request::post(format!("/{}", RPC_API_PATH)).header(
+            header::CONTENT_LENGTH,
+            serde_json::to_vec(body).unwrap().len(),
+        )
+    }
+
+    /// Returns the response to a request, where each single request is answered with its "method"
+    /// as the "result".
+    async fn send(request: Request<Body>, limits: RequestLimits) -> Response<Body> {
+        let echo_service = hyper::service::service_fn(|request: Request<Body>| async move {
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let response = json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": request["method"],
+            });
+            Ok::<_, Infallible>(new_json_response(serde_json::to_vec(&response).unwrap()))
+        });
+        handle_request(echo_service, limits, CLIENT, request)
+            .await
+            .unwrap()
+    }
+
+    /// Returns the response body for a batch request.
+    async fn batch_response(body: Value, max_batch_size: u32) -> Option<Value> {
+        let request = post(&body)
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let response = send(request, limits(max_batch_size, 1_000)).await;
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        if body.is_empty() {
+            return None;
+        }
+        Some(serde_json::from_slice(&body).unwrap())
+    }
+
+    fn single_request(id: Option<u64>, method: &str) -> Value {
+        match id {
+            Some(id) => json!({ "jsonrpc": "2.0", "id": id, "method": method }),
+            None => json!({ "jsonrpc": "2.0", "method": method }),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_handle_batch() {
+        let batch = json!([
+            single_request(Some(1), "first"),
+            single_request(None, "notification"),
+            single_request(Some(2), "second"),
+        ]);
+        let response = batch_response(batch, 3).await.unwrap();
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 2);
+        assert_eq!(response[0]["id"], 1);
+        assert_eq!(response[0]["result"], "first");
+        assert_eq!(response[1]["id"], 2);
+        assert_eq!(response[1]["result"], "second");
+    }
+
+    #[tokio::test]
+    async fn should_not_respond_to_batch_of_notifications() {
+        let batch = json!([single_request(None, "notification")]);
+        assert!(batch_response(batch, 1).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn should_reject_empty_batch() {
+        let response = batch_response(json!([]), 1).await.unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            json!(warp_json_rpc::Error::INVALID_REQUEST)["code"]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_batch_exceeding_max_size() {
+        let batch = json!([
+            single_request(Some(1), "first"),
+            single_request(Some(2), "second")
+        ]);
+        let response = batch_response(batch, 1).await.unwrap();
+        assert_eq!(response["error"]["code"], ErrorCode::BatchTooLarge as i64);
+    }
+
+    #[tokio::test]
+    async fn should_pass_through_single_request() {
+        let response = batch_response(single_request(Some(1), "single"), 1)
+            .await
+            .unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"], "single");
+    }
+
+    #[tokio::test]
+    async fn should_compress_batch_response() {
+        let batch = json!([
+            single_request(Some(1), "first"),
+            single_request(Some(2), "second")
+        ]);
+        let request = post(&batch)
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::from(serde_json::to_vec(&batch).unwrap()))
+            .unwrap();
+        let response = send(request, limits(2, 1_000)).await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+
+        let compressed = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let mut body = vec![];
+        GzDecoder::new(compressed.as_ref())
+            .read_to_end(&mut body)
+            .unwrap();
+        let response: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response[0]["result"], "first");
+        assert_eq!(response[1]["result"], "second");
+    }
+
+    #[test]
+    fn should_parse_accept_encoding_list() {
+        let accepts = |values: &[&str]| {
+            let mut headers = HeaderMap::new();
+            for value in values {
+                headers.append(header::ACCEPT_ENCODING, value.parse().unwrap());
+            }
+            accepts_gzip(&headers)
+        };
+        assert!(accepts(&["gzip"]));
+        assert!(accepts(&["deflate, gzip;q=0.5, br"]));
+        assert!(accepts(&["deflate", "GZIP"]));
+        assert!(accepts(&["*"]));
+        assert!(!accepts(&[]));
+        assert!(!accepts(&["deflate, br"]));
+        assert!(!accepts(&["gzip;q=0"]));
+    }
+
+    #[tokio::test]
+    async fn should_reject_body_exceeding_max_size() {
+        let request_body = single_request(Some(1), "single");
+        let body_len = serde_json::to_vec(&request_body).unwrap().len() as u32;
+
+        let request = post(&request_body)
+            .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+            .unwrap();
+        let response = send(request, limits(1, body_len)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = post(&request_body)
+            .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+            .unwrap();
+        let response = send(request, limits(1, body_len - 1)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // The limit also applies if the "content-length" header is missing.
+        let request = Request::post(format!("/{}", RPC_API_PATH))
+            .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+            .unwrap();
+        let response = send(request, limits(1, body_len - 1)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn should_rate_limit_per_client() {
+        tokio::time::pause();
+        let rate_limiter = ClientRateLimiter::new(2);
+        let other_client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let start = Instant::now();
+
+        rate_limiter.acquire(CLIENT, 2).await;
+        rate_limiter.acquire(other_client, 2).await;
+        assert_eq!(Instant::now(), start);
+
+        // Requests exceeding the limit are delayed to the following windows.  Timers have
+        // millisecond granularity, so each window may end slightly late.
+        rate_limiter.acquire(CLIENT, 3).await;
+        let elapsed = Instant::now() - start;
+        assert!(elapsed >= RATE_LIMIT_WINDOW * 2);
+        assert!(elapsed < RATE_LIMIT_WINDOW * 3);
+    }
+}
@@ -36,7 +36,7 @@ pub const RPC_API_PATH: &str = "rpc";
 ///
 /// See <https://www.jsonrpc.org/specification#error_object> for details.
 #[repr(i64)]
-enum ErrorCode {
+pub(super) enum ErrorCode {
     NoSuchDeploy = -32000,
     NoSuchBlock = -32001,
     ParseQueryKey = -32002,
@@ -49,6 +49,12 @@ enum ErrorCode {
     NoSuchAccount = -32009,
     FailedToGetDictionaryURef = -32010,
     FailedToGetTrie = -32011,
+    FailedToExecuteSpeculatively = -32012,
+    BatchTooLarge = -32013,
+    FailedToGetKeysByPrefix = -32014,
+    FailedToGetDictionaryItems = -32015,
+    SpeculativeExecTraceDisabled = -32016,
+    ParseBalanceHistoryPurseURef = -32017,
     // Same error code as warp_json INTERNAL_ERROR.
     InternalError = -32063,
 }
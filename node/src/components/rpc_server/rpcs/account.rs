@@ -12,19 +12,22 @@ use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::info;
-use warp_json_rpc::Builder;
+use warp::{filters::BoxedFilter, reject, Filter};
+use warp_json_rpc::{filters, Builder};
 
-use casper_types::ProtocolVersion;
+use casper_execution_engine::core::engine_state::ExecutionTrace;
+use casper_hashing::Digest;
+use casper_types::{ExecutionResult, ProtocolVersion};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
+    Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt, RPC_API_PATH,
 };
 use crate::{
     components::rpc_server::rpcs::ErrorCode,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Deploy, DeployHash},
+    types::{Block, Deploy, DeployHash, Timestamp},
 };
 
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
@@ -34,6 +37,18 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().id(),
 });
+static SPECULATIVE_EXEC_PARAMS: Lazy<SpeculativeExecParams> = Lazy::new(|| SpeculativeExecParams {
+    deploy: Deploy::doc_example().clone(),
+    state_root_hash: Some(*Block::doc_example().header().state_root_hash()),
+    trace: false,
+});
+static SPECULATIVE_EXEC_RESULT: Lazy<SpeculativeExecResult> = Lazy::new(|| SpeculativeExecResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    deploy_hash: *Deploy::doc_example().id(),
+    state_root_hash: *Block::doc_example().header().state_root_hash(),
+    execution_result: ExecutionResult::example().clone(),
+    execution_trace: None,
+});
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -123,3 +138,184 @@ impl RpcWithParamsExt for PutDeploy {
         .boxed()
     }
 }
+
+/// Params for "speculative_exec" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecParams {
+    /// The `Deploy` to execute.  It may be unsigned, in which case it is executed as if approved
+    /// by its account's key.
+    pub deploy: Deploy,
+    /// The state root hash to execute against, which must be that of a stored block.  The deploy
+    /// is executed under that block's protocol version.  Defaults to the state root hash of the
+    /// highest block if omitted.
+    #[serde(default)]
+    pub state_root_hash: Option<Digest>,
+    /// Whether to record a trace of the execution, including the host functions called, the named
+    /// keys read and written, the contracts called and where execution was reverted.  Defaults to
+    /// false if omitted.  Rejected unless the node enables tracing in its config.
+    #[serde(default)]
+    pub trace: bool,
+}
+
+impl DocExample for SpeculativeExecParams {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXEC_PARAMS
+    }
+}
+
+/// Result for "speculative_exec" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The state root hash the deploy was executed against.
+    pub state_root_hash: Digest,
+    /// The result of executing the deploy.  None of its effects have been committed.
+    pub execution_result: ExecutionResult,
+    /// The trace of the execution, if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_trace: Option<ExecutionTrace>,
+}
+
+impl DocExample for SpeculativeExecResult {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXEC_RESULT
+    }
+}
+
+/// "speculative_exec" RPC
+pub struct SpeculativeExec {}
+
+impl RpcWithParams for SpeculativeExec {
+    const METHOD: &'static str = "speculative_exec";
+    type RequestParams = SpeculativeExecParams;
+    type ResponseResult = SpeculativeExecResult;
+}
+
+impl SpeculativeExec {
+    /// Creates the warp filter for this RPC, which rejects requests for a trace of the execution
+    /// unless `trace_enabled` is true.
+    pub(in crate::components::rpc_server) fn create_filter_with_trace_config<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        trace_enabled: bool,
+    ) -> BoxedFilter<(Response<Body>,)> {
+        let with_disabled_trace = warp::path(RPC_API_PATH)
+            .and(filters::json_rpc())
+            .and(filters::method(Self::METHOD))
+            .and(filters::params::<SpeculativeExecParams>())
+            .and_then(
+                move |response_builder: Builder, params: SpeculativeExecParams| async move {
+                    if trace_enabled || !params.trace {
+                        return Err(reject());
+                    }
+                    response_builder
+                        .error(warp_json_rpc::Error::custom(
+                            ErrorCode::SpeculativeExecTraceDisabled as i64,
+                            "speculative-exec tracing is disabled on this node",
+                        ))
+                        .map_err(|_| reject())
+                },
+            );
+        with_disabled_trace
+            .or(Self::create_filter(effect_builder, api_version))
+            .unify()
+            .boxed()
+    }
+}
+
+impl RpcWithParamsExt for SpeculativeExec {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hash = *params.deploy.id();
+
+            // The deploy is executed under the protocol version of the block whose post-state
+            // it is executed against.
+            let maybe_block_header = match params.state_root_hash {
+                Some(state_root_hash) => {
+                    effect_builder
+                        .get_block_header_by_state_root_hash_from_storage(state_root_hash)
+                        .await
+                }
+                None => effect_builder
+                    .get_highest_block_from_storage()
+                    .await
+                    .map(|block| block.take_header()),
+            };
+            let block_header = match maybe_block_header {
+                Some(block_header) => block_header,
+                None => {
+                    let error_msg = match params.state_root_hash {
+                        Some(state_root_hash) => format!(
+                            "speculative-exec failed to get block with state root hash {}",
+                            state_root_hash
+                        ),
+                        None => "speculative-exec failed to get last added block".to_string(),
+                    };
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::NoSuchBlock as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+            let state_root_hash = *block_header.state_root_hash();
+
+            let execution_result = effect_builder
+                .speculative_execute_deploy(
+                    state_root_hash,
+                    Timestamp::now().millis(),
+                    block_header.protocol_version(),
+                    Box::new(params.deploy),
+                    params.trace,
+                )
+                .await;
+
+            match execution_result {
+                Ok(Some((execution_result, execution_trace))) => {
+                    let result = Self::ResponseResult {
+                        api_version,
+                        deploy_hash,
+                        state_root_hash,
+                        execution_result,
+                        execution_trace,
+                    };
+                    Ok(response_builder.success(result)?)
+                }
+                Ok(None) => {
+                    let error_msg = format!(
+                        "speculative-exec returned no execution result for deploy {}",
+                        deploy_hash
+                    );
+                    info!("{}", error_msg);
+                    Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::FailedToExecuteSpeculatively as i64,
+                        error_msg,
+                    ))?)
+                }
+                Err(error) => {
+                    info!(
+                        %deploy_hash,
+                        %error,
+                        "speculative execution failed",
+                    );
+                    Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::FailedToExecuteSpeculatively as i64,
+                        error.to_string(),
+                    ))?)
+                }
+            }
+        }
+        .boxed()
+    }
+}
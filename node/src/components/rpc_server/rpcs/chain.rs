@@ -22,12 +22,16 @@ use casper_types::{Key, ProtocolVersion, Transfer};
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::common,
-    types::{Block, BlockHash, BlockSignatures, Item, JsonBlock},
+    types::{
+        Block, BlockHash, BlockHeaderWithMetadata, BlockSignatures, Item, JsonBlock,
+        JsonBlockHeader, JsonProof,
+    },
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
@@ -39,6 +43,18 @@ static GET_BLOCK_RESULT: Lazy<GetBlockResult> = Lazy::new(|| GetBlockResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     block: Some(JsonBlock::doc_example().clone()),
 });
+static GET_BLOCKS_PARAMS: Lazy<GetBlocksParams> = Lazy::new(|| GetBlocksParams {
+    start_height: Block::doc_example().header().height(),
+    max_count: Some(1),
+    headers_only: false,
+    with_signatures: true,
+});
+static GET_BLOCKS_RESULT: Lazy<GetBlocksResult> = Lazy::new(|| GetBlocksResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    blocks: vec![JsonBlock::doc_example().clone()],
+    block_headers: vec![],
+    next_height: Some(Block::doc_example().header().height() + 1),
+});
 static GET_BLOCK_TRANSFERS_PARAMS: Lazy<GetBlockTransfersParams> =
     Lazy::new(|| GetBlockTransfersParams {
         block_identifier: BlockIdentifier::Hash(Block::doc_example().id()),
@@ -152,6 +168,152 @@ impl RpcWithOptionalParamsExt for GetBlock {
     }
 }
 
+/// The maximum number of blocks returned by a single "chain_get_blocks" request.
+const MAX_BLOCK_RANGE_SIZE: u32 = 100;
+
+/// Params for "chain_get_blocks" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksParams {
+    /// The height of the first block to return.
+    pub start_height: u64,
+    /// The maximum number of consecutive blocks to return.  Defaults to, and is capped at, 100.
+    #[serde(default)]
+    pub max_count: Option<u32>,
+    /// Whether to return only the blocks' headers rather than the full blocks.
+    #[serde(default)]
+    pub headers_only: bool,
+    /// Whether to include the blocks' finality signatures.
+    #[serde(default)]
+    pub with_signatures: bool,
+}
+
+impl DocExample for GetBlocksParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKS_PARAMS
+    }
+}
+
+/// A block header along with its hash and, if requested, its finality signatures.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBlockHeaderWithProofs {
+    /// The block hash.
+    pub hash: BlockHash,
+    /// The block header.
+    pub header: JsonBlockHeader,
+    /// The block's finality signatures, if requested.
+    pub proofs: Vec<JsonProof>,
+}
+
+impl From<BlockHeaderWithMetadata> for JsonBlockHeaderWithProofs {
+    fn from(block_header_with_metadata: BlockHeaderWithMetadata) -> Self {
+        let BlockHeaderWithMetadata {
+            block_header,
+            block_signatures,
+        } = block_header_with_metadata;
+        JsonBlockHeaderWithProofs {
+            hash: block_signatures.block_hash,
+            header: JsonBlockHeader::from(block_header),
+            proofs: block_signatures
+                .proofs
+                .into_iter()
+                .map(JsonProof::from)
+                .collect(),
+        }
+    }
+}
+
+/// Result for "chain_get_blocks" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The consecutive blocks found, unless only headers were requested.
+    pub blocks: Vec<JsonBlock>,
+    /// The consecutive block headers found, if only headers were requested.
+    pub block_headers: Vec<JsonBlockHeaderWithProofs>,
+    /// The height of the first block of the next range, if this range was full.
+    pub next_height: Option<u64>,
+}
+
+impl DocExample for GetBlocksResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKS_RESULT
+    }
+}
+
+/// "chain_get_blocks" RPC.
+pub struct GetBlocks {}
+
+impl RpcWithParams for GetBlocks {
+    const METHOD: &'static str = "chain_get_blocks";
+    type RequestParams = GetBlocksParams;
+    type ResponseResult = GetBlocksResult;
+}
+
+impl RpcWithParamsExt for GetBlocks {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let max_count = params
+                .max_count
+                .unwrap_or(MAX_BLOCK_RANGE_SIZE)
+                .min(MAX_BLOCK_RANGE_SIZE)
+                .max(1);
+
+            let (blocks, block_headers) = if params.headers_only {
+                let block_headers: Vec<JsonBlockHeaderWithProofs> = effect_builder
+                    .get_block_headers_with_metadata_in_range_from_storage(
+                        params.start_height,
+                        max_count,
+                        params.with_signatures,
+                    )
+                    .await
+                    .into_iter()
+                    .map(JsonBlockHeaderWithProofs::from)
+                    .collect();
+                (vec![], block_headers)
+            } else {
+                let blocks: Vec<JsonBlock> = effect_builder
+                    .get_blocks_with_metadata_in_range_from_storage(
+                        params.start_height,
+                        max_count,
+                        params.with_signatures,
+                    )
+                    .await
+                    .into_iter()
+                    .map(|(block, signatures)| JsonBlock::new(block, Some(signatures)))
+                    .collect();
+                (blocks, vec![])
+            };
+            // The range stops early at the first missing block, so it's only worth requesting the
+            // next range if this one is full.
+            let range_len = blocks.len().max(block_headers.len());
+            let next_height = if range_len >= max_count as usize {
+                Some(params.start_height + range_len as u64)
+            } else {
+                None
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                blocks,
+                block_headers,
+                next_height,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Params for "chain_get_block_transfers" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -19,10 +19,13 @@ use warp_json_rpc::Builder;
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetStateRootHash},
-    info::{GetDeploy, GetPeers, GetStatus},
-    state::{GetAuctionInfo, GetBalance, GetItem},
+    account::{PutDeploy, SpeculativeExec},
+    chain::{GetBlock, GetBlockTransfers, GetBlocks, GetStateRootHash},
+    info::{
+        GetAccountDeploys, GetBalanceHistory, GetConsensusState, GetDeploy, GetDeployEvents,
+        GetEvidence, GetPeers, GetPendingDeploys, GetStatus,
+    },
+    state::{GetAuctionInfo, GetBalance, GetItem, GetKeysByPrefix},
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     RpcWithoutParamsExt,
 };
@@ -75,7 +78,16 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     };
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+    schema.push_with_params::<SpeculativeExec>(
+        "executes a Deploy against a given state root hash without committing the effects",
+    );
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+    schema.push_with_params::<GetDeployEvents>(
+        "returns the events emitted by contracts while executing a Deploy",
+    );
+    schema.push_with_params::<GetBalanceHistory>("returns the recorded balance history of a purse");
+    schema
+        .push_with_params::<GetAccountDeploys>("returns the hashes of Deploys sent by an account");
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
     schema.push_with_params::<QueryGlobalState>(
@@ -85,7 +97,17 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
         .push_without_params::<GetValidatorChanges>("returns status changes of active validators");
+    schema.push_without_params::<GetPendingDeploys>(
+        "returns the Deploys held by the node which are not yet included in a Block",
+    );
+    schema.push_with_optional_params::<GetEvidence>(
+        "returns the evidence of equivocation for a given era",
+    );
+    schema.push_without_params::<GetConsensusState>(
+        "returns the node's view of the current consensus state",
+    );
     schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
+    schema.push_with_params::<GetBlocks>("returns a range of Blocks from the network");
     schema.push_with_optional_params::<GetBlockTransfers>(
         "returns all transfers for a Block from the network",
     );
@@ -94,6 +116,9 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     );
     schema.push_with_params::<GetItem>("returns a stored value from the network. This RPC is deprecated, use `query_global_state` instead.");
     schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
+    schema.push_with_params::<GetKeysByPrefix>(
+        "returns the keys in global state sharing a given prefix",
+    );
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
         "returns an EraInfo from the network",
     );
@@ -14,19 +14,29 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_hashing::Digest;
+use casper_types::{
+    account::AccountHash, CLValue, ContractEvent, EraId, ExecutionResult, Key, ProtocolVersion,
+    PublicKey, URef, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt, RpcWithoutParams,
-    RpcWithoutParamsExt,
+    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    RpcWithParams, RpcWithParamsExt, RpcWithoutParams, RpcWithoutParamsExt,
 };
 use crate::{
-    components::consensus::ValidatorChange,
+    components::{
+        block_proposer::{PendingDeploy, PendingDeploys},
+        consensus::{EraEvidence, HighwaySnapshot, ValidatorChange},
+    },
     crypto::AsymmetricKeyExt,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Block, BlockHash, Deploy, DeployHash, GetStatusResult, Item, PeersMap},
+    types::{
+        AccountDeploy, BalanceChange, BalanceChangeKind, Block, BlockHash, Deploy, DeployHash,
+        GetStatusResult, Item, PeersMap, Timestamp,
+    },
 };
 
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
@@ -40,6 +50,22 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
         result: ExecutionResult::example().clone(),
     }],
 });
+static GET_DEPLOY_EVENTS_PARAMS: Lazy<GetDeployEventsParams> =
+    Lazy::new(|| GetDeployEventsParams {
+        deploy_hash: *Deploy::doc_example().id(),
+    });
+static GET_DEPLOY_EVENTS_RESULT: Lazy<GetDeployEventsResult> =
+    Lazy::new(|| GetDeployEventsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        events: vec![JsonContractEvents {
+            block_hash: Block::doc_example().id(),
+            events: vec![ContractEvent {
+                emitter: Key::Hash([44; 32]),
+                topic: "transfer".to_string(),
+                payload: CLValue::from_t(U512::from(1_000)).unwrap(),
+            }],
+        }],
+    });
 static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     peers: GetStatusResult::doc_example().peers.clone(),
@@ -53,6 +79,104 @@ static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new
         changes,
     }
 });
+static GET_BALANCE_HISTORY_PARAMS: Lazy<GetBalanceHistoryParams> =
+    Lazy::new(|| GetBalanceHistoryParams {
+        purse_uref: "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007"
+            .to_string(),
+        start_height: 0,
+        max_count: Some(100),
+    });
+static GET_BALANCE_HISTORY_RESULT: Lazy<GetBalanceHistoryResult> =
+    Lazy::new(|| GetBalanceHistoryResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        balance_changes: vec![BalanceChange {
+            block_height: Block::doc_example().height(),
+            block_hash: *Block::doc_example().hash(),
+            deploy_hash: *Deploy::doc_example().id(),
+            kind: BalanceChangeKind::Added(U512::from(123_456)),
+        }],
+        next_height: None,
+    });
+
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        account_identifier: AccountIdentifier::PublicKey(
+            Deploy::doc_example().header().account().clone(),
+        ),
+        start_height: 0,
+        max_count: Some(100),
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![AccountDeploy {
+            deploy_hash: *Deploy::doc_example().id(),
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().height(),
+        }],
+        next_height: None,
+    });
+static GET_PENDING_DEPLOYS_RESULT: Lazy<GetPendingDeploysResult> = Lazy::new(|| {
+    let header = Deploy::doc_example().header();
+    GetPendingDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        pending_transfers: vec![],
+        pending_deploys: vec![JsonPendingDeploy {
+            deploy_hash: *Deploy::doc_example().id(),
+            account: header.account().clone(),
+            gas_price: header.gas_price(),
+            received_time: header.timestamp(),
+            unresolved_dependencies: header.dependencies().clone(),
+        }],
+        finalized_deploy_count: 0,
+    }
+});
+static GET_EVIDENCE_PARAMS: Lazy<GetEvidenceParams> = Lazy::new(|| GetEvidenceParams {
+    era_id: Some(EraId::new(1)),
+    public_key: Some(PublicKey::doc_example().clone()),
+});
+static GET_EVIDENCE_RESULT: Lazy<GetEvidenceResult> = Lazy::new(|| {
+    let public_key = PublicKey::doc_example().clone();
+    let evidence = EraEvidence::new(
+        EraId::new(1),
+        Digest::from([7; Digest::LENGTH]),
+        public_key.clone(),
+        vec![public_key].into_iter().collect(),
+        &[1, 2, 3],
+    );
+    GetEvidenceResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        evidence: vec![evidence],
+    }
+});
+static GET_CONSENSUS_STATE_RESULT: Lazy<GetConsensusStateResult> =
+    Lazy::new(|| GetConsensusStateResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        consensus_state: Some(HighwaySnapshot::doc_example().clone()),
+    });
+
+/// The maximum number of entries returned by a single request of a paginated RPC.
+const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Returns the number of entries to request for a page, given the requested maximum.
+fn page_size(max_count: Option<u32>) -> u32 {
+    max_count.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE)
+}
+
+/// Returns the start height of the next page, if the current page is full and so there may be
+/// further entries in later blocks.
+fn next_page_height(
+    page_len: usize,
+    page_size: u32,
+    maybe_last_height: Option<u64>,
+) -> Option<u64> {
+    if page_len >= page_size as usize {
+        // There can be no later page if the last entry is at the maximum height.
+        maybe_last_height.and_then(|last_height| last_height.checked_add(1))
+    } else {
+        None
+    }
+}
 
 /// Params for "info_get_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -157,6 +281,82 @@ impl RpcWithParamsExt for GetDeploy {
     }
 }
 
+/// Params for "info_get_deploy_events" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployEventsParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetDeployEventsParams {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOY_EVENTS_PARAMS
+    }
+}
+
+/// The events emitted by contracts while executing a deploy in a single block.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonContractEvents {
+    /// The block hash.
+    pub block_hash: BlockHash,
+    /// The events, in the order in which they were emitted.
+    pub events: Vec<ContractEvent>,
+}
+
+/// Result for "info_get_deploy_events" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployEventsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The events emitted by the deploy, by block.  Blocks in which the deploy emitted no events
+    /// are omitted.
+    pub events: Vec<JsonContractEvents>,
+}
+
+impl DocExample for GetDeployEventsResult {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOY_EVENTS_RESULT
+    }
+}
+
+/// "info_get_deploy_events" RPC.
+pub struct GetDeployEvents {}
+
+impl RpcWithParams for GetDeployEvents {
+    const METHOD: &'static str = "info_get_deploy_events";
+    type RequestParams = GetDeployEventsParams;
+    type ResponseResult = GetDeployEventsResult;
+}
+
+impl RpcWithParamsExt for GetDeployEvents {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let events = effect_builder
+                .get_contract_events_from_storage(params.deploy_hash)
+                .await
+                .into_iter()
+                .map(|(block_hash, events)| JsonContractEvents { block_hash, events })
+                .collect();
+
+            let result = Self::ResponseResult {
+                api_version,
+                events,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Result for "info_get_peers" RPC response.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -342,3 +542,403 @@ impl RpcWithoutParamsExt for GetValidatorChanges {
         .boxed()
     }
 }
+
+/// Params for "info_get_balance_history" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBalanceHistoryParams {
+    /// Formatted URef of the purse.
+    pub purse_uref: String,
+    /// Height of the first block to include; defaults to 0.
+    #[serde(default)]
+    pub start_height: u64,
+    /// Maximum number of balance changes to return.  Changes made in a single block are never
+    /// split across pages, so this may be exceeded slightly.  Defaults to, and is capped at, 1000.
+    #[serde(default)]
+    pub max_count: Option<u32>,
+}
+
+impl DocExample for GetBalanceHistoryParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BALANCE_HISTORY_PARAMS
+    }
+}
+
+/// Result for "info_get_balance_history" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBalanceHistoryResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The balance changes of the purse, in order of block height.
+    pub balance_changes: Vec<BalanceChange>,
+    /// The `start_height` to use to request the next page, if there may be more balance changes.
+    pub next_height: Option<u64>,
+}
+
+impl DocExample for GetBalanceHistoryResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BALANCE_HISTORY_RESULT
+    }
+}
+
+/// "info_get_balance_history" RPC.
+pub struct GetBalanceHistory {}
+
+impl RpcWithParams for GetBalanceHistory {
+    const METHOD: &'static str = "info_get_balance_history";
+    type RequestParams = GetBalanceHistoryParams;
+    type ResponseResult = GetBalanceHistoryResult;
+}
+
+impl RpcWithParamsExt for GetBalanceHistory {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Try to parse the purse's URef from the params.
+            let purse_uref = match URef::from_formatted_str(&params.purse_uref)
+                .map_err(|error| format!("failed to parse purse_uref: {:?}", error))
+            {
+                Ok(uref) => uref,
+                Err(error_msg) => {
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::ParseBalanceHistoryPurseURef as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            let max_count = page_size(params.max_count);
+            let balance_changes = effect_builder
+                .get_balance_history_from_storage(purse_uref.addr(), params.start_height, max_count)
+                .await;
+            let next_height = next_page_height(
+                balance_changes.len(),
+                max_count,
+                balance_changes
+                    .last()
+                    .map(|balance_change| balance_change.block_height),
+            );
+
+            let result = Self::ResponseResult {
+                api_version,
+                balance_changes,
+                next_height,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Identifier of an account.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum AccountIdentifier {
+    /// Identify the account by its public key.
+    PublicKey(PublicKey),
+    /// Identify the account by its account hash.
+    AccountHash(AccountHash),
+}
+
+impl AccountIdentifier {
+    /// Returns the account hash of the identified account.
+    pub fn account_hash(&self) -> AccountHash {
+        match self {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => *account_hash,
+        }
+    }
+}
+
+/// Params for "info_get_account_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The account whose deploys are requested.
+    pub account_identifier: AccountIdentifier,
+    /// Height of the first block to include; defaults to 0.
+    #[serde(default)]
+    pub start_height: u64,
+    /// Maximum number of deploys to return.  Deploys included in a single block are never split
+    /// across pages, so this may be exceeded slightly.  Defaults to, and is capped at, 1000.
+    #[serde(default)]
+    pub max_count: Option<u32>,
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "info_get_account_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys sent by the account, in order of block height.
+    pub deploys: Vec<AccountDeploy>,
+    /// The `start_height` to use to request the next page, if there may be more deploys.
+    pub next_height: Option<u64>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_account_deploys" RPC.
+pub struct GetAccountDeploys {}
+
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "info_get_account_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+}
+
+impl RpcWithParamsExt for GetAccountDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let max_count = page_size(params.max_count);
+            let deploys = effect_builder
+                .get_account_deploys_from_storage(
+                    params.account_identifier.account_hash(),
+                    params.start_height,
+                    max_count,
+                )
+                .await;
+            let next_height = next_page_height(
+                deploys.len(),
+                max_count,
+                deploys.last().map(|deploy| deploy.block_height),
+            );
+
+            let result = Self::ResponseResult {
+                api_version,
+                deploys,
+                next_height,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// A deploy or transfer held by the node's block proposer, awaiting inclusion in a block.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonPendingDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The account which sent the deploy.
+    pub account: PublicKey,
+    /// The gas price offered by the deploy.
+    pub gas_price: u64,
+    /// The time at which the node received the deploy.
+    pub received_time: Timestamp,
+    /// The dependencies of the deploy which have not yet been included in a finalized block.  The
+    /// deploy will not be proposed while this is non-empty.
+    pub unresolved_dependencies: Vec<DeployHash>,
+}
+
+impl From<PendingDeploy> for JsonPendingDeploy {
+    fn from(pending_deploy: PendingDeploy) -> Self {
+        JsonPendingDeploy {
+            deploy_hash: pending_deploy.hash,
+            account: pending_deploy.header.account().clone(),
+            gas_price: pending_deploy.header.gas_price(),
+            received_time: pending_deploy.received_time,
+            unresolved_dependencies: pending_deploy.unresolved_dependencies,
+        }
+    }
+}
+
+/// Result for "info_get_pending_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The pending transfers, in the order in which they are considered for inclusion in a block.
+    pub pending_transfers: Vec<JsonPendingDeploy>,
+    /// The pending deploys, in the order in which they are considered for inclusion in a block.
+    pub pending_deploys: Vec<JsonPendingDeploy>,
+    /// The number of recent deploys and transfers known to have been included in a finalized
+    /// block.
+    pub finalized_deploy_count: u64,
+}
+
+impl GetPendingDeploysResult {
+    pub(crate) fn new(api_version: ProtocolVersion, pending_deploys: PendingDeploys) -> Self {
+        GetPendingDeploysResult {
+            api_version,
+            pending_transfers: pending_deploys
+                .pending_transfers
+                .into_iter()
+                .map(JsonPendingDeploy::from)
+                .collect(),
+            pending_deploys: pending_deploys
+                .pending_deploys
+                .into_iter()
+                .map(JsonPendingDeploy::from)
+                .collect(),
+            finalized_deploy_count: pending_deploys.finalized_deploy_count as u64,
+        }
+    }
+}
+
+impl DocExample for GetPendingDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_PENDING_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_pending_deploys" RPC.
+pub struct GetPendingDeploys {}
+
+impl RpcWithoutParams for GetPendingDeploys {
+    const METHOD: &'static str = "info_get_pending_deploys";
+    type ResponseResult = GetPendingDeploysResult;
+}
+
+impl RpcWithoutParamsExt for GetPendingDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let pending_deploys = effect_builder.get_pending_deploys().await;
+            let result = Self::ResponseResult::new(api_version, pending_deploys);
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "info_get_evidence" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEvidenceParams {
+    /// The era to return evidence for; defaults to all eras the node still holds.
+    #[serde(default)]
+    pub era_id: Option<EraId>,
+    /// The validator to return evidence against; defaults to all faulty validators.
+    #[serde(default)]
+    pub public_key: Option<PublicKey>,
+}
+
+impl DocExample for GetEvidenceParams {
+    fn doc_example() -> &'static Self {
+        &*GET_EVIDENCE_PARAMS
+    }
+}
+
+/// Result for "info_get_evidence" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEvidenceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The evidence against faulty validators, each with the validator set of its era.
+    pub evidence: Vec<EraEvidence>,
+}
+
+impl DocExample for GetEvidenceResult {
+    fn doc_example() -> &'static Self {
+        &*GET_EVIDENCE_RESULT
+    }
+}
+
+/// "info_get_evidence" RPC.
+pub struct GetEvidence {}
+
+impl RpcWithOptionalParams for GetEvidence {
+    const METHOD: &'static str = "info_get_evidence";
+    type OptionalRequestParams = GetEvidenceParams;
+    type ResponseResult = GetEvidenceResult;
+}
+
+impl RpcWithOptionalParamsExt for GetEvidence {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        maybe_params: Option<Self::OptionalRequestParams>,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let (era_id, public_key) =
+                maybe_params.map_or((None, None), |params| (params.era_id, params.public_key));
+            let evidence = effect_builder
+                .get_consensus_evidence(era_id, public_key)
+                .await;
+            let result = Self::ResponseResult {
+                api_version,
+                evidence,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Result for "info_get_consensus_state" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetConsensusStateResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// A snapshot of the current era's Highway protocol state, or `None` if the node is not
+    /// running consensus, e.g. because it is still joining the network.
+    pub consensus_state: Option<HighwaySnapshot>,
+}
+
+impl DocExample for GetConsensusStateResult {
+    fn doc_example() -> &'static Self {
+        &*GET_CONSENSUS_STATE_RESULT
+    }
+}
+
+/// "info_get_consensus_state" RPC.
+pub struct GetConsensusState {}
+
+impl RpcWithoutParams for GetConsensusState {
+    const METHOD: &'static str = "info_get_consensus_state";
+    type ResponseResult = GetConsensusStateResult;
+}
+
+impl RpcWithoutParamsExt for GetConsensusState {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let consensus_state = effect_builder.get_consensus_snapshot().await;
+            let result = Self::ResponseResult {
+                api_version,
+                consensus_state,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
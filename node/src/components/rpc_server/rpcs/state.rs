@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{convert::TryFrom, str};
 
 use futures::{future::BoxFuture, FutureExt};
 use http::Response;
@@ -14,12 +14,16 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use warp_json_rpc::Builder;
 
-use casper_execution_engine::core::engine_state::{BalanceResult, GetBidsResult, QueryResult};
+use casper_execution_engine::core::engine_state::{
+    get_dictionary_items, BalanceResult, GetBidsResult, GetDictionaryItemsRequest,
+    GetDictionaryItemsResult, GetKeysByPrefixRequest,
+    GetKeysByPrefixResult as EngineGetKeysByPrefixResult, KeyPrefix, QueryResult,
+};
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    CLValue, Key, KeyTag, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue,
+    URef, U512,
 };
 
 use super::{
@@ -89,6 +93,7 @@ static GET_DICTIONARY_ITEM_PARAMS: Lazy<GetDictionaryItemParams> =
                 .to_string(),
             dictionary_item_key: "a_unique_entry_identifier".to_string(),
         },
+        list: None,
     });
 static GET_DICTIONARY_ITEM_RESULT: Lazy<GetDictionaryItemResult> =
     Lazy::new(|| GetDictionaryItemResult {
@@ -98,6 +103,8 @@ static GET_DICTIONARY_ITEM_RESULT: Lazy<GetDictionaryItemResult> =
                 .to_string(),
         stored_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
         merkle_proof: MERKLE_PROOF.clone(),
+        items: None,
+        next_cursor: None,
     });
 static QUERY_GLOBAL_STATE_PARAMS: Lazy<QueryGlobalStateParams> =
     Lazy::new(|| QueryGlobalStateParams {
@@ -119,6 +126,32 @@ static GET_TRIE_RESULT: Lazy<GetTrieResult> = Lazy::new(|| GetTrieResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     maybe_trie_bytes: None,
 });
+static GET_KEYS_BY_PREFIX_PARAMS: Lazy<GetKeysByPrefixParams> =
+    Lazy::new(|| GetKeysByPrefixParams {
+        state_root_hash: *Block::doc_example().header().state_root_hash(),
+        prefix: KeyPrefixIdentifier::Tag(KeyTagIdentifier::DeployInfo),
+        cursor: None,
+        limit: None,
+        include_values: true,
+    });
+static GET_KEYS_BY_PREFIX_RESULT: Lazy<GetKeysByPrefixResult> =
+    Lazy::new(|| GetKeysByPrefixResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        entries: vec![KeyEntry {
+            key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1"
+                .to_string(),
+            stored_value: Some(StoredValue::CLValue(CLValue::from_t(1u64).unwrap())),
+        }],
+        next_cursor: Some(
+            "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
+        ),
+    });
+
+/// The maximum number of keys scanned by a single "state_get_keys_by_prefix" RPC request.
+pub const MAX_KEYS_BY_PREFIX_LIMIT: u32 = 1_000;
+
+/// The maximum number of items listed by a single "state_get_dictionary_item" RPC request.
+pub const MAX_DICTIONARY_ITEMS_LIMIT: u32 = 1_000;
 
 /// Params for "state_get_item" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -675,14 +708,36 @@ impl DictionaryIdentifier {
     ) -> Result<Key, Error> {
         match self {
             DictionaryIdentifier::AccountNamedKey {
-                dictionary_name,
                 dictionary_item_key,
                 ..
             }
             | DictionaryIdentifier::ContractNamedKey {
-                dictionary_name,
+                dictionary_item_key,
+                ..
+            }
+            | DictionaryIdentifier::URef {
                 dictionary_item_key,
                 ..
+            } => {
+                let key_bytes = dictionary_item_key.as_str().as_bytes();
+                let seed_uref = self.get_dictionary_seed_uref(maybe_stored_value)?;
+                Ok(Key::dictionary(seed_uref, key_bytes))
+            }
+            DictionaryIdentifier::Dictionary(address) => Key::from_formatted_str(address)
+                .map_err(|_| Error("Failed to parse Dictionary key".to_string())),
+        }
+    }
+
+    fn get_dictionary_seed_uref(
+        &self,
+        maybe_stored_value: Option<DomainStoredValue>,
+    ) -> Result<URef, Error> {
+        match self {
+            DictionaryIdentifier::AccountNamedKey {
+                dictionary_name, ..
+            }
+            | DictionaryIdentifier::ContractNamedKey {
+                dictionary_name, ..
             } => {
                 let named_keys = match &maybe_stored_value {
                     Some(DomainStoredValue::Account(account)) => account.named_keys(),
@@ -696,27 +751,20 @@ impl DictionaryIdentifier {
                     None => return Err(Error("Could not retrieve account".to_string())),
                 };
 
-                let key_bytes = dictionary_item_key.as_str().as_bytes();
-                let seed_uref = match named_keys.get(dictionary_name) {
-                    Some(key) => *key
+                match named_keys.get(dictionary_name) {
+                    Some(key) => key
                         .as_uref()
-                        .ok_or_else(|| Error("Failed to parse key into URef:".to_string()))?,
-                    None => return Err(Error("Failed to get seed Uref".to_string())),
-                };
-
-                Ok(Key::dictionary(seed_uref, key_bytes))
-            }
-            DictionaryIdentifier::URef {
-                seed_uref,
-                dictionary_item_key,
-            } => {
-                let key_bytes = dictionary_item_key.as_str().as_bytes();
-                let seed_uref = URef::from_formatted_str(seed_uref)
-                    .map_err(|_| Error("Failed to parse URef".to_string()))?;
-                Ok(Key::dictionary(seed_uref, key_bytes))
+                        .copied()
+                        .ok_or_else(|| Error("Failed to parse key into URef:".to_string())),
+                    None => Err(Error("Failed to get seed Uref".to_string())),
+                }
             }
-            DictionaryIdentifier::Dictionary(address) => Key::from_formatted_str(address)
-                .map_err(|_| Error("Failed to parse Dictionary key".to_string())),
+            DictionaryIdentifier::URef { seed_uref, .. } => URef::from_formatted_str(seed_uref)
+                .map_err(|_| Error("Failed to parse URef".to_string())),
+            DictionaryIdentifier::Dictionary(_) => Err(Error(
+                "Can't get the seed URef of a dictionary identified by a dictionary key"
+                    .to_string(),
+            )),
         }
     }
 }
@@ -729,6 +777,36 @@ pub struct GetDictionaryItemParams {
     pub state_root_hash: Digest,
     /// The Dictionary query identifier.
     pub dictionary_identifier: DictionaryIdentifier,
+    /// If given, lists a page of the items of the dictionary instead of getting a single item.
+    ///
+    /// The `dictionary_item_key` of the identifier is ignored, and the identifier can't be a
+    /// `Dictionary` key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list: Option<DictionaryListOptions>,
+}
+
+/// Options for listing the items of a dictionary in a "state_get_dictionary_item" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DictionaryListOptions {
+    /// The `next_cursor` of the previous page, if any.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// The maximum number of items to list, at most 1000.  Defaults to 1000.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// A dictionary item listed by a "state_get_dictionary_item" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DictionaryItem {
+    /// The dictionary item key.
+    pub dictionary_item_key: String,
+    /// The key under which the value is stored.
+    pub dictionary_key: String,
+    /// The stored value.
+    pub stored_value: StoredValue,
 }
 
 impl DocExample for GetDictionaryItemParams {
@@ -750,6 +828,15 @@ pub struct GetDictionaryItemResult {
     pub stored_value: StoredValue,
     /// The merkle proof.
     pub merkle_proof: String,
+    /// The listed items in ascending order of their item keys, if listing.
+    ///
+    /// When listing, the key, value and merkle proof above are those of the dictionary's index
+    /// of its item keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<DictionaryItem>>,
+    /// The cursor to pass to list the next page, if listing and there are more items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl DocExample for GetDictionaryItemResult {
@@ -775,7 +862,7 @@ impl RpcWithParamsExt for GetDictionaryItem {
         api_version: ProtocolVersion,
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
-            let dictionary_address = match params.dictionary_identifier {
+            let maybe_base_value = match params.dictionary_identifier {
                 DictionaryIdentifier::AccountNamedKey { .. }
                 | DictionaryIdentifier::ContractNamedKey { .. } => {
                     let base_key = match params.dictionary_identifier.get_dictionary_base_key() {
@@ -821,17 +908,30 @@ impl RpcWithParamsExt for GetDictionaryItem {
                         }
                     };
 
-                    params
-                        .dictionary_identifier
-                        .get_dictionary_address(Some(*value))
-                }
-                DictionaryIdentifier::URef { .. } | DictionaryIdentifier::Dictionary(_) => {
-                    params.dictionary_identifier.get_dictionary_address(None)
+                    Some(*value)
                 }
+                DictionaryIdentifier::URef { .. } | DictionaryIdentifier::Dictionary(_) => None,
             };
 
-            let dictionary_query_key = match dictionary_address {
-                Ok(key) => key,
+            // When listing, query the dictionary's index so that the proof covers its item keys.
+            let dictionary_address = match params.list {
+                None => params
+                    .dictionary_identifier
+                    .get_dictionary_address(maybe_base_value)
+                    .map(|key| (key, None)),
+                Some(_) => params
+                    .dictionary_identifier
+                    .get_dictionary_seed_uref(maybe_base_value)
+                    .map(|seed_uref| {
+                        (
+                            get_dictionary_items::dictionary_index_key(seed_uref),
+                            Some(seed_uref),
+                        )
+                    }),
+            };
+
+            let (dictionary_query_key, maybe_seed_uref) = match dictionary_address {
+                Ok(address) => address,
                 Err(Error(message)) => {
                     return Ok(response_builder.error(warp_json_rpc::Error::custom(
                         ErrorCode::FailedToGetDictionaryURef as i64,
@@ -840,6 +940,75 @@ impl RpcWithParamsExt for GetDictionaryItem {
                 }
             };
 
+            let (items, next_cursor) = match (maybe_seed_uref, params.list.as_ref()) {
+                (Some(seed_uref), Some(list_options)) => {
+                    let limit = list_options
+                        .limit
+                        .unwrap_or(MAX_DICTIONARY_ITEMS_LIMIT)
+                        .min(MAX_DICTIONARY_ITEMS_LIMIT) as usize;
+                    let get_dictionary_items_request = GetDictionaryItemsRequest::new(
+                        params.state_root_hash,
+                        seed_uref,
+                        list_options.cursor.clone(),
+                        limit,
+                    );
+
+                    let get_dictionary_items_result = effect_builder
+                        .make_request(
+                            |responder| RpcRequest::GetDictionaryItems {
+                                get_dictionary_items_request,
+                                responder,
+                            },
+                            QueueKind::Api,
+                        )
+                        .await;
+
+                    let (ee_items, next_cursor) = match get_dictionary_items_result {
+                        Ok(GetDictionaryItemsResult::Success { items, next_cursor }) => {
+                            (items, next_cursor)
+                        }
+                        Ok(result) => {
+                            let error_msg =
+                                format!("listing dictionary items failed: {:?}", result);
+                            info!("{}", error_msg);
+                            return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                                ErrorCode::FailedToGetDictionaryItems as i64,
+                                error_msg,
+                            ))?);
+                        }
+                        Err(error) => {
+                            let error_msg =
+                                format!("listing dictionary items failed to execute: {}", error);
+                            info!("{}", error_msg);
+                            return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                                ErrorCode::FailedToGetDictionaryItems as i64,
+                                error_msg,
+                            ))?);
+                        }
+                    };
+
+                    let mut items = Vec::with_capacity(ee_items.len());
+                    for (dictionary_item_key, key, value) in ee_items {
+                        let stored_value = match StoredValue::try_from(value) {
+                            Ok(stored_value) => stored_value,
+                            Err(error) => {
+                                info!("failed to encode stored value: {:?}", error);
+                                return Ok(
+                                    response_builder.error(warp_json_rpc::Error::INTERNAL_ERROR)?
+                                );
+                            }
+                        };
+                        items.push(DictionaryItem {
+                            dictionary_item_key,
+                            dictionary_key: key.to_formatted_string(),
+                            stored_value,
+                        });
+                    }
+                    (Some(items), next_cursor)
+                }
+                _ => (None, None),
+            };
+
             let query_result = effect_builder
                 .make_request(
                     |responder| RpcRequest::QueryGlobalState {
@@ -866,6 +1035,8 @@ impl RpcWithParamsExt for GetDictionaryItem {
                 dictionary_key: dictionary_query_key.to_formatted_string(),
                 stored_value,
                 merkle_proof: hex::encode(proof_bytes),
+                items,
+                next_cursor,
             };
 
             Ok(response_builder.success(result)?)
@@ -1100,3 +1271,243 @@ impl RpcWithParamsExt for GetTrie {
         .boxed()
     }
 }
+
+/// The variant of the keys to enumerate in a "state_get_keys_by_prefix" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy)]
+pub enum KeyTagIdentifier {
+    /// `Key::Account`.
+    Account,
+    /// `Key::Hash`.
+    Hash,
+    /// `Key::URef`.
+    URef,
+    /// `Key::Transfer`.
+    Transfer,
+    /// `Key::DeployInfo`.
+    DeployInfo,
+    /// `Key::EraInfo`.
+    EraInfo,
+    /// `Key::Balance`.
+    Balance,
+    /// `Key::Bid`.
+    Bid,
+    /// `Key::Withdraw`.
+    Withdraw,
+    /// `Key::Dictionary`.
+    Dictionary,
+    /// `Key::SystemContractRegistry`.
+    SystemContractRegistry,
+}
+
+impl From<KeyTagIdentifier> for KeyTag {
+    fn from(key_tag: KeyTagIdentifier) -> Self {
+        match key_tag {
+            KeyTagIdentifier::Account => KeyTag::Account,
+            KeyTagIdentifier::Hash => KeyTag::Hash,
+            KeyTagIdentifier::URef => KeyTag::URef,
+            KeyTagIdentifier::Transfer => KeyTag::Transfer,
+            KeyTagIdentifier::DeployInfo => KeyTag::DeployInfo,
+            KeyTagIdentifier::EraInfo => KeyTag::EraInfo,
+            KeyTagIdentifier::Balance => KeyTag::Balance,
+            KeyTagIdentifier::Bid => KeyTag::Bid,
+            KeyTagIdentifier::Withdraw => KeyTag::Withdraw,
+            KeyTagIdentifier::Dictionary => KeyTag::Dictionary,
+            KeyTagIdentifier::SystemContractRegistry => KeyTag::SystemContractRegistry,
+        }
+    }
+}
+
+/// The keys to enumerate in a "state_get_keys_by_prefix" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub enum KeyPrefixIdentifier {
+    /// All keys of the given variant.
+    Tag(KeyTagIdentifier),
+    /// All items of the dictionary with the given seed URef, formatted as a string.
+    ///
+    /// All dictionary items in global state are scanned, so a page may hold fewer entries than
+    /// the limit even when `next_cursor` is set.
+    DictionaryItems(String),
+}
+
+impl KeyPrefixIdentifier {
+    fn to_key_prefix(&self) -> Result<KeyPrefix, Error> {
+        match self {
+            KeyPrefixIdentifier::Tag(key_tag) => Ok(KeyPrefix::Tag((*key_tag).into())),
+            KeyPrefixIdentifier::DictionaryItems(seed_uref) => URef::from_formatted_str(seed_uref)
+                .map(KeyPrefix::DictionaryItems)
+                .map_err(|_| Error("Failed to parse URef".to_string())),
+        }
+    }
+}
+
+/// Params for "state_get_keys_by_prefix" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysByPrefixParams {
+    /// Hash of the state root.
+    pub state_root_hash: Digest,
+    /// The keys to enumerate.
+    pub prefix: KeyPrefixIdentifier,
+    /// The `next_cursor` of the previous page as a formatted key, if any.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// The maximum number of keys to scan, at most 1000.  Defaults to 1000.
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Whether to return the values stored under the keys.
+    #[serde(default)]
+    pub include_values: bool,
+}
+
+impl DocExample for GetKeysByPrefixParams {
+    fn doc_example() -> &'static Self {
+        &*GET_KEYS_BY_PREFIX_PARAMS
+    }
+}
+
+/// A key returned by a "state_get_keys_by_prefix" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct KeyEntry {
+    /// The key as a formatted string.
+    pub key: String,
+    /// The value stored under the key, if requested.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub stored_value: Option<StoredValue>,
+}
+
+/// Result for "state_get_keys_by_prefix" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysByPrefixResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The keys, in ascending order of their serialized bytes.
+    pub entries: Vec<KeyEntry>,
+    /// The cursor to pass to get the next page, if there are more keys to scan.
+    pub next_cursor: Option<String>,
+}
+
+impl DocExample for GetKeysByPrefixResult {
+    fn doc_example() -> &'static Self {
+        &*GET_KEYS_BY_PREFIX_RESULT
+    }
+}
+
+/// "state_get_keys_by_prefix" RPC.
+pub struct GetKeysByPrefix {}
+
+impl RpcWithParams for GetKeysByPrefix {
+    const METHOD: &'static str = "state_get_keys_by_prefix";
+    type RequestParams = GetKeysByPrefixParams;
+    type ResponseResult = GetKeysByPrefixResult;
+}
+
+impl RpcWithParamsExt for GetKeysByPrefix {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let prefix = match params.prefix.to_key_prefix() {
+                Ok(prefix) => prefix,
+                Err(Error(error_msg)) => {
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::ParseQueryKey as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            let start_after = match params.cursor.as_deref().map(Key::from_formatted_str) {
+                None => None,
+                Some(Ok(key)) => Some(key),
+                Some(Err(error)) => {
+                    let error_msg = format!("failed to parse cursor: {}", error);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::ParseQueryKey as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            let limit = params
+                .limit
+                .unwrap_or(MAX_KEYS_BY_PREFIX_LIMIT)
+                .min(MAX_KEYS_BY_PREFIX_LIMIT) as usize;
+            let get_keys_by_prefix_request = GetKeysByPrefixRequest::new(
+                params.state_root_hash,
+                prefix,
+                start_after,
+                limit,
+                params.include_values,
+            );
+
+            let get_keys_by_prefix_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetKeysByPrefix {
+                        get_keys_by_prefix_request,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let (ee_entries, next_cursor) = match get_keys_by_prefix_result {
+                Ok(EngineGetKeysByPrefixResult::Success {
+                    entries,
+                    next_cursor,
+                }) => (entries, next_cursor),
+                Ok(EngineGetKeysByPrefixResult::RootNotFound) => {
+                    let error_msg = format!(
+                        "get-keys-by-prefix failed: root not found: {}",
+                        params.state_root_hash
+                    );
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::FailedToGetKeysByPrefix as i64,
+                        error_msg,
+                    ))?);
+                }
+                Err(error) => {
+                    let error_msg = format!("get-keys-by-prefix failed to execute: {}", error);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::FailedToGetKeysByPrefix as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            let mut entries = Vec::with_capacity(ee_entries.len());
+            for (key, maybe_value) in ee_entries {
+                let stored_value = match maybe_value.map(StoredValue::try_from).transpose() {
+                    Ok(stored_value) => stored_value,
+                    Err(error) => {
+                        info!("failed to encode stored value: {:?}", error);
+                        return Ok(response_builder.error(warp_json_rpc::Error::INTERNAL_ERROR)?);
+                    }
+                };
+                entries.push(KeyEntry {
+                    key: key.to_formatted_string(),
+                    stored_value,
+                });
+            }
+
+            let result = Self::ResponseResult {
+                api_version,
+                entries,
+                next_cursor: next_cursor.map(|key| key.to_formatted_string()),
+            };
+
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
@@ -49,7 +49,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use casper_types::{EraId, PublicKey};
+use casper_types::{EraId, ProtocolVersion, PublicKey};
 use datasize::DataSize;
 use futures::{future::BoxFuture, FutureExt};
 use openssl::{error::ErrorStack as OpenSslErrorStack, pkey};
@@ -150,6 +150,8 @@ where
     outgoing_manager: OutgoingManager<OutgoingHandle<P>, ConnectionError>,
     /// Tracks whether a connection is symmetric or not.
     connection_symmetries: HashMap<NodeId, ConnectionSymmetry>,
+    /// The protocol version each peer announced in its most recent handshake.
+    peer_protocol_versions: HashMap<NodeId, ProtocolVersion>,
 
     /// Channel signaling a shutdown of the small network.
     // Note: This channel is closed when `SmallNetwork` is dropped, signalling the receivers that
@@ -267,11 +269,11 @@ where
         let consensus_keys = consensus_cfg
             .map(|cfg| {
                 let root = cfg.dir();
-                cfg.value().load_keys(root)
+                cfg.value().load_signer(root)
             })
             .transpose()
             .map_err(Error::LoadConsensusKeys)?
-            .map(|(secret_key, public_key)| ConsensusKeyPair::new(secret_key, public_key));
+            .map(ConsensusKeyPair::new);
 
         let context = Arc::new(NetworkContext {
             event_queue,
@@ -306,6 +308,7 @@ where
             context,
             outgoing_manager,
             connection_symmetries: HashMap::new(),
+            peer_protocol_versions: HashMap::new(),
             shutdown_sender: Some(server_shutdown_sender),
             shutdown_receiver,
             server_join_handle: Some(server_join_handle),
@@ -441,9 +444,12 @@ where
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version,
                 stream,
             } => {
                 info!(%public_addr, "new incoming connection established");
+                self.peer_protocol_versions
+                    .insert(peer_id, peer_protocol_version);
 
                 // Learn the address the peer gave us.
                 let dial_requests =
@@ -587,9 +593,12 @@ where
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version,
                 sink,
             } => {
                 info!("new outgoing connection established");
+                self.peer_protocol_versions
+                    .insert(peer_id, peer_protocol_version);
 
                 let (sender, receiver) = mpsc::unbounded_channel();
                 let handle = OutgoingHandle { sender, peer_addr };
@@ -863,6 +872,23 @@ where
                     peers_vec.shuffle(rng);
                     responder.respond(peers_vec).ignore()
                 }
+                NetworkInfoRequest::GetPeersWithProtocolVersionInRandomOrder {
+                    min_protocol_version,
+                    responder,
+                } => {
+                    let mut peers_vec: Vec<NodeId> = self
+                        .peers()
+                        .keys()
+                        .filter(|node_id| {
+                            self.peer_protocol_versions
+                                .get(node_id)
+                                .map_or(false, |version| *version >= min_protocol_version)
+                        })
+                        .cloned()
+                        .collect();
+                    peers_vec.shuffle(rng);
+                    responder.respond(peers_vec).ignore()
+                }
             },
             Event::PeerAddressReceived(gossiped_address) => {
                 let requests = self.outgoing_manager.learn_addr(
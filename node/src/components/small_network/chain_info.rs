@@ -7,6 +7,7 @@ use std::net::SocketAddr;
 
 use casper_types::ProtocolVersion;
 use datasize::DataSize;
+use tracing::warn;
 
 use super::{
     counting_format::ConnectionId,
@@ -41,18 +42,24 @@ impl ChainInfo {
     }
 
     /// Create a handshake based on chain identification data.
-    pub(super) fn create_handshake<P>(
+    pub(super) async fn create_handshake<P>(
         &self,
         public_addr: SocketAddr,
         consensus_keys: Option<&ConsensusKeyPair>,
         connection_id: ConnectionId,
     ) -> Message<P> {
+        let consensus_certificate = match consensus_keys {
+            Some(key_pair) => ConsensusCertificate::create(connection_id, key_pair)
+                .await
+                .map_err(|err| warn!(%err, "failed to sign consensus certificate"))
+                .ok(),
+            None => None,
+        };
         Message::Handshake {
             network_name: self.network_name.clone(),
             public_addr,
             protocol_version: self.protocol_version,
-            consensus_certificate: consensus_keys
-                .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
+            consensus_certificate,
         }
     }
 }
@@ -5,7 +5,7 @@ use std::{
     sync::Arc,
 };
 
-use casper_types::PublicKey;
+use casper_types::{ProtocolVersion, PublicKey};
 use derive_more::From;
 use futures::stream::{SplitSink, SplitStream};
 use serde::Serialize;
@@ -176,6 +176,8 @@ pub(crate) enum IncomingConnection<P> {
         peer_id: NodeId,
         /// The public key the peer is validating with, if any.
         peer_consensus_public_key: Option<PublicKey>,
+        /// The protocol version the peer announced in its handshake.
+        peer_protocol_version: ProtocolVersion,
         /// Stream of incoming messages. for incoming connections.
         #[serde(skip_serializing)]
         stream: SplitStream<FramedTransport<P>>,
@@ -199,6 +201,7 @@ impl<P> Display for IncomingConnection<P> {
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version: _,
                 stream: _,
             } => {
                 write!(
@@ -246,6 +249,8 @@ pub(crate) enum OutgoingConnection<P> {
         peer_id: NodeId,
         /// The public key the peer is validating with, if any.
         peer_consensus_public_key: Option<PublicKey>,
+        /// The protocol version the peer announced in its handshake.
+        peer_protocol_version: ProtocolVersion,
         /// Sink for outgoing messages.
         #[serde(skip_serializing)]
         sink: SplitSink<FramedTransport<P>, Arc<Message<P>>>,
@@ -268,6 +273,7 @@ impl<P> Display for OutgoingConnection<P> {
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version: _,
                 sink: _,
             } => {
                 write!(f, "connection established to {}/{}", peer_addr, peer_id)?;
@@ -4,11 +4,11 @@ use std::{
     sync::Arc,
 };
 
-use casper_types::{ProtocolVersion, PublicKey, SecretKey, Signature};
+use casper_types::{ProtocolVersion, PublicKey, Signature};
 use datasize::DataSize;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::crypto;
+use crate::crypto::{self, Signer, SignerError};
 
 use super::counting_format::ConnectionId;
 
@@ -55,24 +55,20 @@ impl<P: Payload> Message<P> {
     }
 }
 
-/// A pair of secret keys used by consensus.
+/// The signer for the keys used by consensus.
 pub(super) struct ConsensusKeyPair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    signer: Arc<dyn Signer>,
 }
 
 impl ConsensusKeyPair {
     /// Creates a new key pair for consensus signing.
-    pub(super) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(super) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
     }
 
     /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
+    async fn sign<T: AsRef<[u8]>>(&self, value: T) -> Result<Signature, SignerError> {
+        self.signer.sign(value.as_ref()).await
     }
 }
 
@@ -85,12 +81,15 @@ pub(crate) struct ConsensusCertificate {
 
 impl ConsensusCertificate {
     /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &ConsensusKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
-            public_key: key_pair.public_key.clone(),
+    pub(super) async fn create(
+        connection_id: ConnectionId,
+        key_pair: &ConsensusKeyPair,
+    ) -> Result<Self, SignerError> {
+        let signature = key_pair.sign(connection_id.as_bytes()).await?;
+        Ok(ConsensusCertificate {
+            public_key: key_pair.signer.public_key().clone(),
             signature,
-        }
+        })
     }
 
     /// Validates a certificate, returning a `PublicKey` if valid.
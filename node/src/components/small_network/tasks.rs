@@ -10,7 +10,7 @@ use std::{
     time::Duration,
 };
 
-use casper_types::PublicKey;
+use casper_types::{ProtocolVersion, PublicKey};
 use futures::{
     future::{self, Either},
     stream::{SplitSink, SplitStream},
@@ -133,7 +133,7 @@ where
 
     // Negotiate the handshake, concluding the incoming connection process.
     match negotiate_handshake(&context, &mut transport, connection_id).await {
-        Ok((public_addr, peer_consensus_public_key)) => {
+        Ok((public_addr, peer_consensus_public_key, peer_protocol_version)) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
@@ -150,6 +150,7 @@ where
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version,
                 sink,
             }
         }
@@ -230,7 +231,7 @@ where
 
     // Negotiate the handshake, concluding the incoming connection process.
     match negotiate_handshake(&context, &mut transport, connection_id).await {
-        Ok((public_addr, peer_consensus_public_key)) => {
+        Ok((public_addr, peer_consensus_public_key, peer_protocol_version)) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
@@ -243,6 +244,7 @@ where
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version,
                 stream,
             }
         }
@@ -320,16 +322,19 @@ async fn negotiate_handshake<P, REv>(
     context: &NetworkContext<REv>,
     transport: &mut FramedTransport<P>,
     connection_id: ConnectionId,
-) -> Result<(SocketAddr, Option<PublicKey>), ConnectionError>
+) -> Result<(SocketAddr, Option<PublicKey>, ProtocolVersion), ConnectionError>
 where
     P: Payload,
 {
     // Send down a handshake and expect one in response.
-    let handshake = context.chain_info.create_handshake(
-        context.public_addr,
-        context.consensus_keys.as_ref(),
-        connection_id,
-    );
+    let handshake = context
+        .chain_info
+        .create_handshake(
+            context.public_addr,
+            context.consensus_keys.as_ref(),
+            connection_id,
+        )
+        .await;
 
     io_timeout(HANDSHAKE_TIMEOUT, transport.send(Arc::new(handshake)))
         .await
@@ -360,7 +365,7 @@ where
             })
             .transpose()?;
 
-        Ok((public_addr, peer_consensus_public_key))
+        Ok((public_addr, peer_consensus_public_key, protocol_version))
     } else {
         // Received a non-handshake, this is an error.
         Err(ConnectionError::DidNotSendHandshake)
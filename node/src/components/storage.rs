@@ -45,7 +45,7 @@ mod tests;
 #[cfg(test)]
 use std::collections::BTreeSet;
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     convert::TryFrom,
     fmt::{self, Display, Formatter},
     fs, io, mem,
@@ -59,7 +59,7 @@ use lmdb::{
     Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
     WriteFlags,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use static_assertions::const_assert;
 #[cfg(test)]
 use tempfile::TempDir;
@@ -68,8 +68,10 @@ use tracing::{debug, error, info, warn};
 
 use casper_hashing::Digest;
 use casper_types::{
+    account::AccountHash,
     bytesrepr::{FromBytes, ToBytes},
-    EraId, ExecutionResult, ProtocolVersion, PublicKey, Transfer, Transform,
+    ContractEvent, EraId, ExecutionResult, ProtocolVersion, PublicKey, Transfer, Transform,
+    URefAddr,
 };
 
 use crate::{
@@ -82,10 +84,10 @@ use crate::{
     fatal,
     reactor::ReactorEvent,
     types::{
-        error::BlockValidationError, Block, BlockBody, BlockHash, BlockHeader,
-        BlockHeaderWithMetadata, BlockSignatures, Deploy, DeployHash, DeployHeader, DeployMetadata,
-        HashingAlgorithmVersion, Item, MerkleBlockBody, MerkleBlockBodyPart, MerkleLinkedListNode,
-        SharedObject, TimeDiff,
+        self, error::BlockValidationError, AccountDeploy, BalanceChange, Block, BlockBody,
+        BlockHash, BlockHeader, BlockHeaderWithMetadata, BlockSignatures, Deploy, DeployHash,
+        DeployHeader, DeployMetadata, HashingAlgorithmVersion, Item, MerkleBlockBody,
+        MerkleBlockBodyPart, MerkleLinkedListNode, SharedObject, Supersession, TimeDiff,
     },
     utils::{display_error, WithDir},
     NodeRng,
@@ -112,7 +114,10 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 11;
+const MAX_DB_COUNT: u32 = 15;
+/// Key in the state store marking that the account deploys database has been populated from the
+/// stored blocks.
+const ACCOUNT_DEPLOYS_POPULATED_KEY: &[u8; 25] = b"account_deploys_populated";
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -346,10 +351,25 @@ pub(crate) struct Storage {
     /// The state storage database.
     #[data_size(skip)]
     state_store_db: Database,
+    /// The purse balance history database.
+    #[data_size(skip)]
+    purse_balance_history_db: Database,
+    /// The account deploys database.
+    #[data_size(skip)]
+    account_deploys_db: Database,
+    /// The contract events database, holding the events emitted by each deploy, by block.
+    #[data_size(skip)]
+    contract_events_db: Database,
+    /// The superseding deploys database, holding the hashes of the deploys superseding each
+    /// superseded deploy.
+    #[data_size(skip)]
+    superseding_deploys_db: Database,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
     switch_block_era_id_index: BTreeMap<EraId, BlockHash>,
+    /// A map of state root hashes to hashes of blocks with them as their post-state.
+    state_root_hash_index: BTreeMap<Digest, BlockHash>,
     /// A map of deploy hashes to hashes of blocks containing them.
     deploy_hash_index: BTreeMap<DeployHash, BlockHash>,
     /// Whether or not memory deduplication is enabled.
@@ -449,11 +469,18 @@ impl Storage {
         let deploy_hashes_db = env.create_db(Some("deploy_hashes"), DatabaseFlags::empty())?;
         let transfer_hashes_db = env.create_db(Some("transfer_hashes"), DatabaseFlags::empty())?;
         let proposer_db = env.create_db(Some("proposers"), DatabaseFlags::empty())?;
+        let purse_balance_history_db =
+            env.create_db(Some("purse_balance_history"), DatabaseFlags::empty())?;
+        let account_deploys_db = env.create_db(Some("account_deploys"), DatabaseFlags::empty())?;
+        let contract_events_db = env.create_db(Some("contract_events"), DatabaseFlags::empty())?;
+        let superseding_deploys_db =
+            env.create_db(Some("superseding_deploys"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("reindexing block store");
         let mut block_height_index = BTreeMap::new();
         let mut switch_block_era_id_index = BTreeMap::new();
+        let mut state_root_hash_index = BTreeMap::new();
         let mut deploy_hash_index = BTreeMap::new();
         let mut block_txn = env.begin_rw_txn()?;
         let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
@@ -494,6 +521,7 @@ impl Storage {
             insert_to_block_header_indices(
                 &mut block_height_index,
                 &mut switch_block_era_id_index,
+                &mut state_root_hash_index,
                 &block_header,
             )?;
 
@@ -523,7 +551,7 @@ impl Storage {
                 Block::new_from_header_and_body(block_header.clone(), block_body.clone())?;
             }
 
-            insert_to_deploy_index(&mut deploy_hash_index, block_header.hash(), &block_body)?;
+            insert_to_deploy_index(&mut deploy_hash_index, &block_header, &block_body, None)?;
         }
         info!("block store reindexing complete");
         drop(cursor);
@@ -557,6 +585,21 @@ impl Storage {
             should_check_integrity,
         )?;
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_block_hashes)?;
+        initialize_contract_events_db(&env, &contract_events_db, &deleted_block_hashes)?;
+        initialize_purse_balance_history_db(
+            &env,
+            &purse_balance_history_db,
+            &deleted_block_hashes,
+        )?;
+        initialize_account_deploys_db(
+            &env,
+            &account_deploys_db,
+            &deploy_db,
+            &state_store_db,
+            &deleted_block_hashes,
+            &deploy_hash_index,
+            &block_height_index,
+        )?;
 
         Ok(Storage {
             root,
@@ -572,8 +615,13 @@ impl Storage {
             deploy_metadata_db,
             transfer_db,
             state_store_db,
+            purse_balance_history_db,
+            account_deploys_db,
+            contract_events_db,
+            superseding_deploys_db,
             block_height_index,
             switch_block_era_id_index,
+            state_root_hash_index,
             deploy_hash_index,
             enable_mem_deduplication: config.enable_mem_deduplication,
             deploy_cache: BlobCache::new(config.mem_pool_prune_interval),
@@ -663,6 +711,12 @@ impl Storage {
             StorageRequest::PutBlock { block, responder } => {
                 responder.respond(self.write_block(&*block)?).ignore()
             }
+            StorageRequest::PutBlockHeader {
+                block_header,
+                responder,
+            } => responder
+                .respond(self.write_block_header(&*block_header)?)
+                .ignore(),
             StorageRequest::GetBlock {
                 block_hash,
                 responder,
@@ -670,6 +724,17 @@ impl Storage {
             StorageRequest::GetBlockHeaderAtHeight { height, responder } => responder
                 .respond(self.get_block_header_by_height(&mut self.env.begin_ro_txn()?, height)?)
                 .ignore(),
+            StorageRequest::GetBlockHeaderByStateRootHash {
+                state_root_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let maybe_block_header = match self.state_root_hash_index.get(&state_root_hash) {
+                    Some(block_hash) => self.get_single_block_header(&mut txn, block_hash)?,
+                    None => None,
+                };
+                responder.respond(maybe_block_header).ignore()
+            }
             StorageRequest::GetBlockAtHeight { height, responder } => responder
                 .respond(self.get_block_by_height(&mut self.env.begin_ro_txn()?, height)?)
                 .ignore(),
@@ -707,9 +772,69 @@ impl Storage {
             } => responder
                 .respond(self.get_transfers(&mut self.env.begin_ro_txn()?, &block_hash)?)
                 .ignore(),
+            StorageRequest::GetBalanceHistory {
+                purse_addr,
+                start_height,
+                max_count,
+                responder,
+            } => responder
+                .respond(self.get_balance_history(
+                    &mut self.env.begin_ro_txn()?,
+                    &purse_addr,
+                    start_height,
+                    max_count,
+                )?)
+                .ignore(),
+            StorageRequest::GetAccountDeploys {
+                account_hash,
+                start_height,
+                max_count,
+                responder,
+            } => responder
+                .respond(self.get_account_deploys(
+                    &mut self.env.begin_ro_txn()?,
+                    &account_hash,
+                    start_height,
+                    max_count,
+                )?)
+                .ignore(),
             StorageRequest::PutDeploy { deploy, responder } => {
                 let mut txn = self.env.begin_rw_txn()?;
                 let outcome = txn.put_value(self.deploy_db, deploy.id(), &deploy, false)?;
+                // The deploy may have been included in a block stored before the deploy itself,
+                // in which case it could not be added to the account deploys index back then.
+                if outcome {
+                    if let Some(block_hash) = self.deploy_hash_index.get(deploy.id()).copied() {
+                        if let Some(block_header) =
+                            self.get_single_block_header(&mut txn, &block_hash)?
+                        {
+                            let account_deploy = AccountDeploy {
+                                deploy_hash: *deploy.id(),
+                                block_hash,
+                                block_height: block_header.height(),
+                            };
+                            put_account_deploy(
+                                &mut txn,
+                                self.deploy_db,
+                                self.account_deploys_db,
+                                &account_deploy,
+                            )?;
+                        }
+                    }
+                    if let Some(supersession) = deploy.header().supersession() {
+                        let superseded_hash = supersession.superseded();
+                        let mut superseding_hashes: Vec<DeployHash> = txn
+                            .get_value(self.superseding_deploys_db, superseded_hash)?
+                            .unwrap_or_default();
+                        superseding_hashes.push(*deploy.id());
+                        txn.put_value(
+                            self.superseding_deploys_db,
+                            superseded_hash,
+                            &superseding_hashes,
+                            true,
+                        )?;
+                    }
+                }
                 txn.commit()?;
                 responder.respond(outcome).ignore()
             }
@@ -719,6 +844,17 @@ impl Storage {
             } => responder
                 .respond(self.get_deploys(&mut self.env.begin_ro_txn()?, deploy_hashes.as_slice())?)
                 .ignore(),
+            StorageRequest::GetSupersessionConflicts {
+                deploy_hash,
+                deploy_header,
+                responder,
+            } => responder
+                .respond(self.get_supersession_conflicts(
+                    &mut self.env.begin_ro_txn()?,
+                    &deploy_hash,
+                    &deploy_header,
+                )?)
+                .ignore(),
             StorageRequest::PutExecutionResults {
                 block_hash,
                 execution_results,
@@ -726,6 +862,16 @@ impl Storage {
             } => {
                 let mut txn = self.env.begin_rw_txn()?;
 
+                // The balance history is keyed by block height, so it can only be updated if the
+                // block itself has already been stored.  Otherwise the balance changes are indexed
+                // once the block is stored.
+                let maybe_block_height = self
+                    .get_single_block_header(&mut txn, &block_hash)?
+                    .map(|block_header| block_header.height());
+                if maybe_block_height.is_none() {
+                    debug!(%block_hash, "block header not found; indexing balance changes later");
+                }
+
                 let mut transfers: Vec<Transfer> = vec![];
 
                 for (deploy_hash, execution_result) in execution_results {
@@ -742,6 +888,17 @@ impl Storage {
                         }
                     }
 
+                    if let Some(block_height) = maybe_block_height {
+                        put_balance_changes(
+                            &mut txn,
+                            self.purse_balance_history_db,
+                            *block_hash,
+                            block_height,
+                            deploy_hash,
+                            &execution_result,
+                        )?;
+                    }
+
                     if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
                         for transform_entry in effect.transforms {
                             if let Transform::WriteTransfer(transfer) = transform_entry.transform {
@@ -778,6 +935,36 @@ impl Storage {
                 txn.commit()?;
                 responder.respond(()).ignore()
             }
+            StorageRequest::PutContractEvents {
+                block_hash,
+                contract_events,
+                responder,
+            } => {
+                let mut txn = self.env.begin_rw_txn()?;
+                for (deploy_hash, events) in contract_events {
+                    let mut deploy_events = self
+                        .get_contract_events(&mut txn, &deploy_hash)?
+                        .unwrap_or_default();
+                    deploy_events.insert(*block_hash, events);
+                    let was_written =
+                        txn.put_value(self.contract_events_db, &deploy_hash, &deploy_events, true)?;
+                    if !was_written {
+                        error!(?block_hash, ?deploy_hash, "failed to write contract events");
+                        debug_assert!(was_written);
+                    }
+                }
+                txn.commit()?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::GetContractEvents {
+                deploy_hash,
+                responder,
+            } => responder
+                .respond(
+                    self.get_contract_events(&mut self.env.begin_ro_txn()?, &deploy_hash)?
+                        .unwrap_or_default(),
+                )
+                .ignore(),
             StorageRequest::GetDeployAndMetadata {
                 deploy_hash,
                 responder,
@@ -840,6 +1027,32 @@ impl Storage {
                 };
                 responder.respond(Some((block, signatures))).ignore()
             }
+            StorageRequest::GetBlocksWithMetadataInRange {
+                start_height,
+                max_count,
+                with_signatures,
+                responder,
+            } => responder
+                .respond(self.get_blocks_with_metadata_in_range(
+                    &mut self.env.begin_ro_txn()?,
+                    start_height,
+                    max_count,
+                    with_signatures,
+                )?)
+                .ignore(),
+            StorageRequest::GetBlockHeadersWithMetadataInRange {
+                start_height,
+                max_count,
+                with_signatures,
+                responder,
+            } => responder
+                .respond(self.get_block_headers_with_metadata_in_range(
+                    &mut self.env.begin_ro_txn()?,
+                    start_height,
+                    max_count,
+                    with_signatures,
+                )?)
+                .ignore(),
             StorageRequest::GetHighestBlockWithMetadata { responder } => {
                 let mut txn = self.env.begin_ro_txn()?;
                 let highest_block: Block = if let Some(block) = self
@@ -887,6 +1100,12 @@ impl Storage {
                 txn.commit()?;
                 responder.respond(outcome).ignore()
             }
+            StorageRequest::GetStateRootHashesToRetain {
+                retained_eras,
+                responder,
+            } => responder
+                .respond(self.read_state_root_hashes_to_retain(retained_eras)?)
+                .ignore(),
             StorageRequest::GetBlockSignatures {
                 block_hash,
                 responder,
@@ -925,6 +1144,92 @@ impl Storage {
         }))
     }
 
+    /// Retrieves up to `max_count` consecutive blocks starting at `start_height`, stopping at the
+    /// first height for which no block is stored.
+    ///
+    /// If `with_signatures` is false, the returned signatures are empty.
+    fn get_blocks_with_metadata_in_range<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        start_height: u64,
+        max_count: u32,
+        with_signatures: bool,
+    ) -> Result<Vec<(Block, BlockSignatures)>, Error> {
+        let mut blocks = Vec::new();
+        if max_count == 0 {
+            return Ok(blocks);
+        }
+        for height in start_height..=start_height.saturating_add(u64::from(max_count - 1)) {
+            let block = match self.get_block_by_height(txn, height)? {
+                Some(block) => block,
+                None => break,
+            };
+            let signatures = self.get_signatures_if_requested(
+                txn,
+                block.hash(),
+                block.header().era_id(),
+                with_signatures,
+            )?;
+            blocks.push((block, signatures));
+        }
+        Ok(blocks)
+    }
+
+    /// Retrieves up to `max_count` consecutive block headers starting at `start_height`, stopping
+    /// at the first height for which no block header is stored.
+    ///
+    /// If `with_signatures` is false, the returned signatures are empty.
+    fn get_block_headers_with_metadata_in_range<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        start_height: u64,
+        max_count: u32,
+        with_signatures: bool,
+    ) -> Result<Vec<BlockHeaderWithMetadata>, Error> {
+        let mut block_headers = Vec::new();
+        if max_count == 0 {
+            return Ok(block_headers);
+        }
+        for height in start_height..=start_height.saturating_add(u64::from(max_count - 1)) {
+            let block_hash = match self.block_height_index.get(&height) {
+                Some(block_hash) => block_hash,
+                None => break,
+            };
+            let block_header = match self.get_single_block_header(txn, block_hash)? {
+                Some(block_header) => block_header,
+                None => break,
+            };
+            let block_signatures = self.get_signatures_if_requested(
+                txn,
+                block_hash,
+                block_header.era_id(),
+                with_signatures,
+            )?;
+            block_headers.push(BlockHeaderWithMetadata {
+                block_header,
+                block_signatures,
+            });
+        }
+        Ok(block_headers)
+    }
+
+    /// Retrieves the finality signatures of the given block if `with_signatures` is true, otherwise
+    /// or if none are stored, returns an empty set of signatures.
+    fn get_signatures_if_requested<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        block_hash: &BlockHash,
+        era_id: EraId,
+        with_signatures: bool,
+    ) -> Result<BlockSignatures, Error> {
+        let maybe_signatures = if with_signatures {
+            self.get_finality_signatures(txn, block_hash)?
+        } else {
+            None
+        };
+        Ok(maybe_signatures.unwrap_or_else(|| BlockSignatures::new(*block_hash, era_id)))
+    }
+
     /// Retrieves a block by hash.
     fn read_block(&self, block_hash: &BlockHash) -> Result<Option<Block>, Error> {
         self.get_single_block(&mut self.env.begin_ro_txn()?, block_hash)
@@ -964,17 +1269,85 @@ impl Storage {
         insert_to_block_header_indices(
             &mut self.block_height_index,
             &mut self.switch_block_era_id_index,
+            &mut self.state_root_hash_index,
             block.header(),
         )?;
         insert_to_deploy_index(
             &mut self.deploy_hash_index,
-            block.header().hash(),
+            block.header(),
             block.body(),
+            Some(AccountDeploysIndex {
+                txn: &mut txn,
+                deploy_db: self.deploy_db,
+                account_deploys_db: self.account_deploys_db,
+            }),
         )?;
+        self.index_stored_balance_changes(&mut txn, block.header(), block.body())?;
         txn.commit()?;
         Ok(true)
     }
 
+    /// Writes a block header without its body, as downloaded when fast syncing.
+    ///
+    /// The header is indexed like the header of a complete block.
+    fn write_block_header(&mut self, block_header: &BlockHeader) -> Result<bool, Error> {
+        let mut txn = self.env.begin_rw_txn()?;
+        if !txn.put_value(
+            self.block_header_db,
+            &block_header.hash(),
+            block_header,
+            true,
+        )? {
+            error!("Could not insert block header: {}", block_header);
+            txn.abort();
+            return Ok(false);
+        }
+        insert_to_block_header_indices(
+            &mut self.block_height_index,
+            &mut self.switch_block_era_id_index,
+            &mut self.state_root_hash_index,
+            block_header,
+        )?;
+        if let Some(block_body) = self.get_body_for_block_header(&mut txn, block_header)? {
+            self.index_stored_balance_changes(&mut txn, block_header, &block_body)?;
+        }
+        txn.commit()?;
+        Ok(true)
+    }
+
+    /// Adds the balance changes of the block's execution results which were stored before the
+    /// block itself to the purse balance history.
+    fn index_stored_balance_changes(
+        &self,
+        txn: &mut RwTransaction,
+        block_header: &BlockHeader,
+        block_body: &BlockBody,
+    ) -> Result<(), Error> {
+        let block_hash = block_header.hash();
+        for deploy_hash in block_body
+            .deploy_hashes()
+            .iter()
+            .chain(block_body.transfer_hashes().iter())
+        {
+            let metadata: DeployMetadata =
+                match txn.get_value(self.deploy_metadata_db, deploy_hash)? {
+                    Some(metadata) => metadata,
+                    None => continue,
+                };
+            if let Some(execution_result) = metadata.execution_results.get(&block_hash) {
+                put_balance_changes(
+                    txn,
+                    self.purse_balance_history_db,
+                    block_hash,
+                    block_header.height(),
+                    *deploy_hash,
+                    execution_result,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     /// Retrieves a block header to handle a network request.
     pub(crate) fn read_block_header_and_finality_signatures_by_height(
         &self,
@@ -1114,6 +1487,42 @@ impl Storage {
         Ok(deploys)
     }
 
+    /// Retrieves the balance changes of the given purse, starting at `start_height`.
+    fn get_balance_history<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        purse_addr: &URefAddr,
+        start_height: u64,
+        max_count: u32,
+    ) -> Result<Vec<BalanceChange>, Error> {
+        get_height_indexed_values(
+            tx,
+            self.purse_balance_history_db,
+            purse_addr,
+            start_height,
+            max_count,
+            |balance_change: &BalanceChange| balance_change.block_height,
+        )
+    }
+
+    /// Retrieves the deploys sent by the given account, starting at `start_height`.
+    fn get_account_deploys<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        account_hash: &AccountHash,
+        start_height: u64,
+        max_count: u32,
+    ) -> Result<Vec<AccountDeploy>, Error> {
+        get_height_indexed_values(
+            tx,
+            self.account_deploys_db,
+            account_hash.as_bytes(),
+            start_height,
+            max_count,
+            |account_deploy: &AccountDeploy| account_deploy.block_height,
+        )
+    }
+
     /// Retrieves the state root hashes from storage to check the integrity of the trie store.
     pub(crate) fn read_state_root_hashes_for_trie_check(&self) -> Result<Vec<Digest>, Error> {
         let mut blake_hashes: Vec<Digest> = Vec::new();
@@ -1131,6 +1540,44 @@ impl Storage {
         Ok(blake_hashes)
     }
 
+    /// Retrieves the state root hashes of all blocks from the switch block `retained_eras` eras
+    /// back onwards, i.e. the global states to retain when pruning the trie store.
+    ///
+    /// If fewer switch blocks are stored, the state root hashes of all blocks are returned.
+    pub(crate) fn read_state_root_hashes_to_retain(
+        &self,
+        retained_eras: u64,
+    ) -> Result<Vec<Digest>, Error> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let oldest_retained_height = match self
+            .switch_block_era_id_index
+            .values()
+            .rev()
+            .nth(retained_eras.saturating_sub(1) as usize)
+        {
+            Some(block_hash) => self
+                .get_single_block_header(&mut txn, block_hash)?
+                .map_or(0, |block_header| block_header.height()),
+            None => 0,
+        };
+        let mut state_root_hashes = Vec::new();
+        for block_hash in self
+            .block_height_index
+            .range(oldest_retained_height..)
+            .map(|(_, block_hash)| block_hash)
+        {
+            if let Some(block_header) = self.get_single_block_header(&mut txn, block_hash)? {
+                state_root_hashes.push(*block_header.state_root_hash());
+            }
+        }
+        txn.commit()?;
+
+        state_root_hashes.sort();
+        state_root_hashes.dedup();
+
+        Ok(state_root_hashes)
+    }
+
     /// Retrieves a single block header in a separate transaction from storage.
     fn get_single_block_header<Tx: Transaction>(
         &self,
@@ -1316,6 +1763,59 @@ impl Storage {
         Ok(maybe_deploy.map(|deploy| deploy.header().clone()))
     }
 
+    /// Retrieves the stored deploys from the same account which supersede the given deploy, i.e.
+    /// cancel it or replace it offering a higher gas price, or which it replaces, together with
+    /// the height of the block including each of them, if any.
+    ///
+    /// Cancelling a deploy which has already been included has no effect, so the deploy a
+    /// cancellation refers to is not considered conflicting.
+    fn get_supersession_conflicts<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        deploy_hash: &DeployHash,
+        deploy_header: &DeployHeader,
+    ) -> Result<Vec<(DeployHash, Option<u64>)>, Error> {
+        let mut conflicts = vec![];
+        let superseding_hashes: Vec<DeployHash> = txn
+            .get_value(self.superseding_deploys_db, deploy_hash)?
+            .unwrap_or_default();
+        for superseding_hash in superseding_hashes {
+            let superseding_header = match self.get_deploy_header(txn, &superseding_hash)? {
+                Some(header) if header.account() == deploy_header.account() => header,
+                _ => continue,
+            };
+            let supersedes = match superseding_header.supersession() {
+                Some(Supersession::Cancel(_)) => true,
+                Some(Supersession::Replace(_)) => {
+                    superseding_header.gas_price() > deploy_header.gas_price()
+                }
+                None => false,
+            };
+            if supersedes {
+                conflicts.push(superseding_hash);
+            }
+        }
+        if let Some(Supersession::Replace(replaced_hash)) = deploy_header.supersession() {
+            if let Some(replaced_header) = self.get_deploy_header(txn, &replaced_hash)? {
+                if replaced_header.account() == deploy_header.account() {
+                    conflicts.push(replaced_hash);
+                }
+            }
+        }
+
+        let mut conflicts_with_heights = vec![];
+        for conflict_hash in conflicts {
+            let maybe_block_height = match self.deploy_hash_index.get(&conflict_hash) {
+                Some(block_hash) => self
+                    .get_single_block_header(txn, block_hash)?
+                    .map(|block_header| block_header.height()),
+                None => None,
+            };
+            conflicts_with_heights.push((conflict_hash, maybe_block_height));
+        }
+        Ok(conflicts_with_heights)
+    }
+
     /// Retrieves deploy metadata associated with deploy.
     ///
     /// If no deploy metadata is stored for the specific deploy, an empty metadata instance will be
@@ -1328,6 +1828,15 @@ impl Storage {
         Ok(tx.get_value(self.deploy_metadata_db, deploy_hash)?)
     }
 
+    /// Retrieves the contract events emitted by a deploy, by block.
+    fn get_contract_events<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<HashMap<BlockHash, Vec<ContractEvent>>>, Error> {
+        Ok(tx.get_value(self.contract_events_db, deploy_hash)?)
+    }
+
     /// Retrieves transfers associated with block.
     ///
     /// If no transfers are stored for the block, an empty transfers instance will be
@@ -1356,6 +1865,7 @@ impl Storage {
 fn insert_to_block_header_indices(
     block_height_index: &mut BTreeMap<u64, BlockHash>,
     switch_block_era_id_index: &mut BTreeMap<EraId, BlockHash>,
+    state_root_hash_index: &mut BTreeMap<Digest, BlockHash>,
     block_header: &BlockHeader,
 ) -> Result<(), Error> {
     let block_hash = block_header.hash();
@@ -1387,17 +1897,30 @@ fn insert_to_block_header_indices(
     }
 
     let _ = block_height_index.insert(block_header.height(), block_hash);
+    let _ = state_root_hash_index.insert(*block_header.state_root_hash(), block_hash);
     Ok(())
 }
 
+/// The transaction and databases required to update the account deploys database.
+struct AccountDeploysIndex<'a, 'txn> {
+    txn: &'a mut RwTransaction<'txn>,
+    deploy_db: Database,
+    account_deploys_db: Database,
+}
+
 /// Inserts the relevant entries to the index.
 ///
+/// If `account_deploys_index` is given, the block's deploys are also added to the account deploys
+/// database.
+///
 /// If a duplicate entry is encountered, index is not updated and an error is returned.
 fn insert_to_deploy_index(
     deploy_hash_index: &mut BTreeMap<DeployHash, BlockHash>,
-    block_hash: BlockHash,
+    block_header: &BlockHeader,
     block_body: &BlockBody,
+    account_deploys_index: Option<AccountDeploysIndex>,
 ) -> Result<(), Error> {
+    let block_hash = block_header.hash();
     if let Some(hash) = block_body
         .deploy_hashes()
         .iter()
@@ -1423,6 +1946,80 @@ fn insert_to_deploy_index(
         deploy_hash_index.insert(*hash, block_hash);
     }
 
+    if let Some(AccountDeploysIndex {
+        txn,
+        deploy_db,
+        account_deploys_db,
+    }) = account_deploys_index
+    {
+        for hash in block_body
+            .deploy_hashes()
+            .iter()
+            .chain(block_body.transfer_hashes().iter())
+        {
+            let account_deploy = AccountDeploy {
+                deploy_hash: *hash,
+                block_hash,
+                block_height: block_header.height(),
+            };
+            put_account_deploy(txn, deploy_db, account_deploys_db, &account_deploy)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds an entry to the account deploys database, keyed by the hash of the account which sent the
+/// deploy.
+///
+/// Deploys missing from the deploy database are skipped, as their account is unknown. They are
+/// indexed once they are stored.
+fn put_account_deploy(
+    txn: &mut RwTransaction,
+    deploy_db: Database,
+    account_deploys_db: Database,
+    account_deploy: &AccountDeploy,
+) -> Result<(), LmdbExtError> {
+    let deploy: Deploy = match txn.get_value(deploy_db, &account_deploy.deploy_hash)? {
+        Some(deploy) => deploy,
+        None => {
+            debug!(
+                deploy_hash = %account_deploy.deploy_hash,
+                "deploy not stored; not adding to account deploys index"
+            );
+            return Ok(());
+        }
+    };
+    let account_hash = deploy.header().account().to_account_hash();
+    let key = height_indexed_key(
+        account_hash.as_bytes(),
+        account_deploy.block_height,
+        &account_deploy.deploy_hash,
+    );
+    txn.put_value(account_deploys_db, &key, account_deploy, true)?;
+    Ok(())
+}
+
+/// Adds an entry to the purse balance history database for each purse balance changed by the given
+/// execution result.
+fn put_balance_changes(
+    txn: &mut RwTransaction,
+    purse_balance_history_db: Database,
+    block_hash: BlockHash,
+    block_height: u64,
+    deploy_hash: DeployHash,
+    execution_result: &ExecutionResult,
+) -> Result<(), LmdbExtError> {
+    for (purse_addr, kind) in types::balance_changes(execution_result) {
+        let balance_change = BalanceChange {
+            block_height,
+            block_hash,
+            deploy_hash,
+            kind,
+        };
+        let key = height_indexed_key(&purse_addr, block_height, &deploy_hash);
+        txn.put_value(purse_balance_history_db, &key, &balance_change, true)?;
+    }
     Ok(())
 }
 
@@ -2030,6 +2627,186 @@ fn initialize_block_metadata_db(
     Ok(())
 }
 
+/// Returns the key under which a per-deploy entry is stored in a height-indexed database.
+///
+/// Keys consist of the given prefix (e.g. a purse address or account hash), followed by the
+/// big-endian block height and the deploy hash, so that all entries sharing a prefix are contiguous
+/// and ordered by block height.
+fn height_indexed_key(prefix: &[u8], block_height: u64, deploy_hash: &DeployHash) -> Vec<u8> {
+    let mut key = Vec::with_capacity(prefix.len() + mem::size_of::<u64>() + Digest::LENGTH);
+    key.extend_from_slice(prefix);
+    key.extend_from_slice(&block_height.to_be_bytes());
+    key.extend_from_slice(deploy_hash.as_ref());
+    key
+}
+
+/// Retrieves the values stored in a height-indexed database under the given prefix, starting at
+/// `start_height`.
+///
+/// Collection stops once at least `max_count` values have been found and all values of the block
+/// at which that limit was reached are included.
+fn get_height_indexed_values<Tx: Transaction, T: DeserializeOwned>(
+    tx: &mut Tx,
+    db: Database,
+    prefix: &[u8],
+    start_height: u64,
+    max_count: u32,
+    block_height: impl Fn(&T) -> u64,
+) -> Result<Vec<T>, Error> {
+    let mut start_key = prefix.to_vec();
+    start_key.extend_from_slice(&start_height.to_be_bytes());
+
+    let mut values: Vec<T> = vec![];
+    let mut cursor = tx.open_ro_cursor(db)?;
+    // Note: `iter_from` panics if there is no key at or after `start_key`, so we position the
+    //       cursor ourselves first.
+    match cursor.get(Some(&start_key), None, lmdb_sys::MDB_SET_RANGE) {
+        Ok(_) => (),
+        Err(lmdb::Error::NotFound) => return Ok(values),
+        Err(error) => return Err(error.into()),
+    }
+    for (raw_key, raw_val) in cursor.iter_from(&start_key) {
+        if !raw_key.starts_with(prefix) {
+            break;
+        }
+        let value: T = lmdb_ext::deserialize(raw_val)?;
+        if values.len() >= max_count as usize
+            && values.last().map(&block_height) != Some(block_height(&value))
+        {
+            break;
+        }
+        values.push(value);
+    }
+    Ok(values)
+}
+
+/// Purges stale entries from the contract events database.
+fn initialize_contract_events_db(
+    env: &Environment,
+    contract_events_db: &Database,
+    deleted_block_hashes: &HashSet<BlockHash>,
+) -> Result<(), LmdbExtError> {
+    if deleted_block_hashes.is_empty() {
+        return Ok(());
+    }
+
+    info!("initializing contract events database");
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.open_rw_cursor(*contract_events_db)?;
+
+    for (raw_key, raw_val) in cursor.iter() {
+        let mut deploy_events: HashMap<BlockHash, Vec<ContractEvent>> =
+            lmdb_ext::deserialize(raw_val)?;
+        let len_before = deploy_events.len();
+        deploy_events.retain(|block_hash, _| !deleted_block_hashes.contains(block_hash));
+
+        if deploy_events.is_empty() {
+            cursor.del(WriteFlags::empty())?;
+        } else if len_before != deploy_events.len() {
+            let buffer = lmdb_ext::serialize(&deploy_events)?;
+            cursor.put(&raw_key, &buffer, WriteFlags::empty())?;
+        }
+    }
+
+    drop(cursor);
+    txn.commit()?;
+
+    info!("contract events database initialized");
+    Ok(())
+}
+
+/// Purges stale entries from the purse balance history database.
+fn initialize_purse_balance_history_db(
+    env: &Environment,
+    purse_balance_history_db: &Database,
+    deleted_block_hashes: &HashSet<BlockHash>,
+) -> Result<(), LmdbExtError> {
+    if deleted_block_hashes.is_empty() {
+        return Ok(());
+    }
+
+    info!("initializing purse balance history database");
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.open_rw_cursor(*purse_balance_history_db)?;
+
+    for (_raw_key, raw_val) in cursor.iter() {
+        let balance_change: BalanceChange = lmdb_ext::deserialize(raw_val)?;
+        if deleted_block_hashes.contains(&balance_change.block_hash) {
+            cursor.del(WriteFlags::empty())?;
+        }
+    }
+
+    drop(cursor);
+    txn.commit()?;
+
+    info!("purse balance history database initialized");
+    Ok(())
+}
+
+/// Purges stale entries from the account deploys database.
+///
+/// If the database has never been populated, e.g. after upgrading from a version without it, it is
+/// populated from the deploys of all stored blocks, and a marker is stored in the state store so
+/// that this only happens once.
+fn initialize_account_deploys_db(
+    env: &Environment,
+    account_deploys_db: &Database,
+    deploy_db: &Database,
+    state_store_db: &Database,
+    deleted_block_hashes: &HashSet<BlockHash>,
+    deploy_hash_index: &BTreeMap<DeployHash, BlockHash>,
+    block_height_index: &BTreeMap<u64, BlockHash>,
+) -> Result<(), LmdbExtError> {
+    info!("initializing account deploys database");
+    let mut txn = env.begin_rw_txn()?;
+
+    if !deleted_block_hashes.is_empty() {
+        let mut cursor = txn.open_rw_cursor(*account_deploys_db)?;
+        for (_raw_key, raw_val) in cursor.iter() {
+            let account_deploy: AccountDeploy = lmdb_ext::deserialize(raw_val)?;
+            if deleted_block_hashes.contains(&account_deploy.block_hash) {
+                cursor.del(WriteFlags::empty())?;
+            }
+        }
+    }
+
+    let is_populated = match txn.get(*state_store_db, ACCOUNT_DEPLOYS_POPULATED_KEY) {
+        Ok(_) => true,
+        Err(lmdb::Error::NotFound) => false,
+        Err(err) => return Err(err.into()),
+    };
+    if !is_populated {
+        info!("populating account deploys database");
+        let block_heights: HashMap<BlockHash, u64> = block_height_index
+            .iter()
+            .map(|(height, block_hash)| (*block_hash, *height))
+            .collect();
+        for (deploy_hash, block_hash) in deploy_hash_index {
+            let block_height = match block_heights.get(block_hash) {
+                Some(block_height) => *block_height,
+                None => continue,
+            };
+            let account_deploy = AccountDeploy {
+                deploy_hash: *deploy_hash,
+                block_hash: *block_hash,
+                block_height,
+            };
+            put_account_deploy(&mut txn, *deploy_db, *account_deploys_db, &account_deploy)?;
+        }
+        txn.put(
+            *state_store_db,
+            ACCOUNT_DEPLOYS_POPULATED_KEY,
+            b"",
+            WriteFlags::empty(),
+        )?;
+    }
+
+    txn.commit()?;
+
+    info!("account deploys database initialized");
+    Ok(())
+}
+
 /// Purges stale entries from the deploy metadata database.
 fn initialize_deploy_metadata_db(
     env: &Environment,
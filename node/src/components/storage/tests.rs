@@ -13,7 +13,10 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use smallvec::smallvec;
 
 use casper_hashing::Digest;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey};
+use casper_types::{
+    account::AccountHash, CLValue, ContractEvent, EraId, ExecutionEffect, ExecutionResult, Key,
+    ProtocolVersion, PublicKey, SecretKey, Transform, TransformEntry, URefAddr, U512,
+};
 
 use super::{
     construct_block_body_to_block_header_reverse_lookup, garbage_collect_block_body_v2_db,
@@ -29,8 +32,9 @@ use crate::{
     },
     testing::{ComponentHarness, TestRng, UnitTestEvent},
     types::{
-        Block, BlockHash, BlockHeader, BlockSignatures, Deploy, DeployHash, DeployMetadata,
-        FinalitySignature, HashingAlgorithmVersion,
+        AccountDeploy, BalanceChange, BalanceChangeKind, Block, BlockHash, BlockHeader,
+        BlockHeaderWithMetadata, BlockPayload, BlockSignatures, Deploy, DeployHash, DeployMetadata,
+        FinalitySignature, FinalizedBlock, HashingAlgorithmVersion, Supersession, Timestamp,
     },
     utils::WithDir,
 };
@@ -152,6 +156,23 @@ fn get_block_header_at_height(
     response
 }
 
+/// Requests the header of a block with the given state root hash from a storage component.
+fn get_block_header_by_state_root_hash(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    state_root_hash: Digest,
+) -> Option<BlockHeader> {
+    let response = harness.send_request(storage, |responder| {
+        StorageRequest::GetBlockHeaderByStateRootHash {
+            state_root_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Requests block at a specific height from a storage component.
 fn get_block_at_height(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -277,6 +298,65 @@ fn put_block(
     response
 }
 
+/// Stores a block header without its body in a storage component.
+fn put_block_header(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_header: Box<BlockHeader>,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutBlockHeader {
+            block_header,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests a range of blocks with their metadata from a storage component.
+fn get_blocks_in_range(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    start_height: u64,
+    max_count: u32,
+    with_signatures: bool,
+) -> Vec<(Block, BlockSignatures)> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlocksWithMetadataInRange {
+            start_height,
+            max_count,
+            with_signatures,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Requests a range of block headers with their metadata from a storage component.
+fn get_block_headers_in_range(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    start_height: u64,
+    max_count: u32,
+    with_signatures: bool,
+) -> Vec<BlockHeaderWithMetadata> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockHeadersWithMetadataInRange {
+            start_height,
+            max_count,
+            with_signatures,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Stores a block's signatures in a storage component.
 fn put_block_signatures(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -326,6 +406,104 @@ fn put_execution_results(
     response
 }
 
+/// Stores contract events in a storage component.
+fn put_contract_events(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+    contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
+) {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutContractEvents {
+            block_hash: Box::new(block_hash),
+            contract_events,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads the contract events emitted by a deploy from a storage component.
+fn get_contract_events(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy_hash: DeployHash,
+) -> HashMap<BlockHash, Vec<ContractEvent>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetContractEvents {
+            deploy_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads the deploys sent by an account from a storage component.
+fn get_account_deploys(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account_hash: AccountHash,
+    start_height: u64,
+    max_count: u32,
+) -> Vec<AccountDeploy> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetAccountDeploys {
+            account_hash,
+            start_height,
+            max_count,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads the supersession conflicts of a deploy from a storage component.
+fn get_supersession_conflicts(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    deploy: &Deploy,
+) -> Vec<(DeployHash, Option<u64>)> {
+    let deploy_hash = *deploy.id();
+    let deploy_header = Box::new(deploy.header().clone());
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetSupersessionConflicts {
+            deploy_hash,
+            deploy_header,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads the balance history of a purse from a storage component.
+fn get_balance_history(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    purse_addr: URefAddr,
+    start_height: u64,
+    max_count: u32,
+) -> Vec<BalanceChange> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBalanceHistory {
+            purse_addr,
+            start_height,
+            max_count,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Saves state from the storage component.
 fn save_state<T>(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -393,6 +571,30 @@ fn can_put_and_get_block() {
     assert_eq!(response.as_ref(), Some(block.header()));
 }
 
+#[test]
+fn can_put_block_header_without_body() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_at_height(&mut harness.rng, 7);
+    let was_new = put_block_header(&mut harness, &mut storage, Box::new(block.header().clone()));
+    assert!(was_new, "putting block header should have returned `true`");
+
+    // The header is indexed by its height, but there is no complete block.
+    assert_eq!(
+        get_block_header_at_height(&mut harness, &mut storage, 7).as_ref(),
+        Some(block.header())
+    );
+    assert!(get_block(&mut harness, &mut storage, *block.hash()).is_none());
+
+    // Storing the complete block afterwards should still work.
+    assert!(put_block(&mut harness, &mut storage, block.clone()));
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+        Some(&*block)
+    );
+}
+
 #[test]
 fn test_get_block_header_and_finality_signatures_by_height() {
     let mut harness = ComponentHarness::default();
@@ -483,6 +685,36 @@ fn test_get_block_header_and_finality_signatures_by_height() {
     }
 }
 
+#[test]
+fn can_retrieve_block_header_by_state_root_hash() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Box::new(Block::random(&mut harness.rng));
+    let state_root_hash = *block.header().state_root_hash();
+    assert!(
+        get_block_header_by_state_root_hash(&mut harness, &mut storage, state_root_hash).is_none()
+    );
+
+    put_block(&mut harness, &mut storage, block.clone());
+    assert_eq!(
+        get_block_header_by_state_root_hash(&mut harness, &mut storage, state_root_hash),
+        Some(block.header().clone())
+    );
+
+    // The index should be restored on restart.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        get_block_header_by_state_root_hash(&mut harness, &mut storage, state_root_hash),
+        Some(block.header().clone())
+    );
+}
+
 #[test]
 fn can_retrieve_block_by_height() {
     let mut harness = ComponentHarness::default();
@@ -642,6 +874,93 @@ fn different_block_at_height_is_fatal() {
     put_block(&mut harness, &mut storage, block_44_b);
 }
 
+#[test]
+fn can_retrieve_blocks_in_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Store blocks at heights 10 to 12 and 14, leaving a gap at 13.
+    let blocks: Vec<Box<Block>> = [10, 11, 12, 14]
+        .iter()
+        .map(|height| random_block_at_height(&mut harness.rng, *height))
+        .collect();
+    for block in &blocks {
+        assert!(put_block(&mut harness, &mut storage, block.clone()));
+    }
+    let signatures = random_signatures(&mut harness.rng, &blocks[1]);
+    assert!(put_block_signatures(
+        &mut harness,
+        &mut storage,
+        signatures.clone()
+    ));
+
+    // The range should stop at the gap.
+    let range = get_blocks_in_range(&mut harness, &mut storage, 10, 10, true);
+    let expected_blocks: Vec<Block> = blocks[..3].iter().map(|block| (**block).clone()).collect();
+    assert_eq!(
+        range
+            .iter()
+            .map(|(block, _)| block.clone())
+            .collect::<Vec<_>>(),
+        expected_blocks
+    );
+    assert!(range[0].1.proofs.is_empty());
+    assert_eq!(range[1].1, signatures);
+    assert!(range[2].1.proofs.is_empty());
+
+    // The range should be limited by `max_count`, and signatures omitted if not requested.
+    let range = get_blocks_in_range(&mut harness, &mut storage, 11, 1, false);
+    assert_eq!(range.len(), 1);
+    assert_eq!(range[0].0, *blocks[1]);
+    assert!(range[0].1.proofs.is_empty());
+
+    // The same applies to block headers.
+    let header_range = get_block_headers_in_range(&mut harness, &mut storage, 10, 10, true);
+    assert_eq!(
+        header_range
+            .iter()
+            .map(|header_with_metadata| header_with_metadata.block_header.clone())
+            .collect::<Vec<_>>(),
+        blocks[..3]
+            .iter()
+            .map(|block| block.header().clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(header_range[1].block_signatures, signatures);
+    let header_range = get_block_headers_in_range(&mut harness, &mut storage, 11, 2, false);
+    assert_eq!(header_range.len(), 2);
+    assert!(header_range[0].block_signatures.proofs.is_empty());
+
+    // Ranges starting at a missing height are empty.
+    assert!(get_blocks_in_range(&mut harness, &mut storage, 13, 10, true).is_empty());
+    assert!(get_block_headers_in_range(&mut harness, &mut storage, 15, 10, true).is_empty());
+    assert_eq!(
+        get_blocks_in_range(&mut harness, &mut storage, 14, 10, true).len(),
+        1
+    );
+
+    // Empty ranges are empty.
+    assert!(get_blocks_in_range(&mut harness, &mut storage, 10, 0, true).is_empty());
+    assert!(get_block_headers_in_range(&mut harness, &mut storage, 10, 0, true).is_empty());
+}
+
+#[test]
+fn can_retrieve_block_range_ending_at_max_height() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_at_height(&mut harness.rng, u64::MAX);
+    assert!(put_block(&mut harness, &mut storage, block.clone()));
+
+    // The range must not overflow past the maximum height.
+    let range = get_blocks_in_range(&mut harness, &mut storage, u64::MAX, 10, false);
+    assert_eq!(range.len(), 1);
+    assert_eq!(range[0].0, *block);
+    let header_range = get_block_headers_in_range(&mut harness, &mut storage, u64::MAX, 10, false);
+    assert_eq!(header_range.len(), 1);
+    assert_eq!(header_range[0].block_header, *block.header());
+}
+
 #[test]
 fn get_vec_of_non_existing_deploy_returns_nones() {
     let mut harness = ComponentHarness::default();
@@ -914,6 +1233,186 @@ fn store_identical_execution_results() {
     put_execution_results(&mut harness, &mut storage, block_hash, exec_result);
 }
 
+/// Creates a successful execution result with the given transforms.
+fn execution_result_with_transforms(transforms: Vec<(Key, Transform)>) -> ExecutionResult {
+    let transforms = transforms
+        .into_iter()
+        .map(|(key, transform)| TransformEntry {
+            key: key.to_formatted_string(),
+            transform,
+        })
+        .collect();
+    ExecutionResult::Success {
+        effect: ExecutionEffect {
+            operations: vec![],
+            transforms,
+        },
+        transfers: vec![],
+        cost: U512::from(1),
+    }
+}
+
+#[test]
+fn store_and_get_contract_events() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy_hash = DeployHash::random(&mut harness.rng);
+    let block_hash_1 = BlockHash::random(&mut harness.rng);
+    let block_hash_2 = BlockHash::random(&mut harness.rng);
+    let event_1 = ContractEvent {
+        emitter: Key::Hash([1; 32]),
+        topic: "transfer".to_string(),
+        payload: CLValue::from_t(U512::from(1_000)).unwrap(),
+    };
+    let event_2 = ContractEvent {
+        emitter: Key::Hash([2; 32]),
+        topic: "mint".to_string(),
+        payload: CLValue::from_t(7u64).unwrap(),
+    };
+
+    assert!(get_contract_events(&mut harness, &mut storage, deploy_hash).is_empty());
+
+    let mut contract_events = HashMap::new();
+    contract_events.insert(deploy_hash, vec![event_1.clone()]);
+    put_contract_events(&mut harness, &mut storage, block_hash_1, contract_events);
+    let mut contract_events = HashMap::new();
+    contract_events.insert(deploy_hash, vec![event_2.clone(), event_1.clone()]);
+    put_contract_events(&mut harness, &mut storage, block_hash_2, contract_events);
+
+    let mut expected = HashMap::new();
+    expected.insert(block_hash_1, vec![event_1.clone()]);
+    expected.insert(block_hash_2, vec![event_2, event_1]);
+    assert_eq!(
+        get_contract_events(&mut harness, &mut storage, deploy_hash),
+        expected
+    );
+}
+
+#[test]
+fn store_and_page_through_balance_history() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let purse_addr: URefAddr = harness.rng.gen();
+    let other_purse_addr: URefAddr = harness.rng.gen();
+    let written_balance = CLValue::from_t(U512::from(5)).unwrap();
+
+    let block_1 = random_block_at_height(&mut harness.rng, 1);
+    let block_2 = random_block_at_height(&mut harness.rng, 2);
+    put_block(&mut harness, &mut storage, block_1.clone());
+    put_block(&mut harness, &mut storage, block_2.clone());
+
+    // Block 1 adds to both purses, block 2 overwrites the first purse's balance.
+    let deploy_hash_1 = DeployHash::random(&mut harness.rng);
+    let mut results_1 = HashMap::new();
+    results_1.insert(
+        deploy_hash_1,
+        execution_result_with_transforms(vec![
+            (
+                Key::Balance(purse_addr),
+                Transform::AddUInt512(U512::from(10)),
+            ),
+            (
+                Key::Balance(other_purse_addr),
+                Transform::AddUInt512(U512::from(20)),
+            ),
+            (Key::Hash(purse_addr), Transform::AddUInt512(U512::from(30))),
+        ]),
+    );
+    put_execution_results(&mut harness, &mut storage, *block_1.hash(), results_1);
+
+    let deploy_hash_2 = DeployHash::random(&mut harness.rng);
+    let mut results_2 = HashMap::new();
+    results_2.insert(
+        deploy_hash_2,
+        execution_result_with_transforms(vec![(
+            Key::Balance(purse_addr),
+            Transform::WriteCLValue(written_balance),
+        )]),
+    );
+    put_execution_results(&mut harness, &mut storage, *block_2.hash(), results_2);
+
+    let change_1 = BalanceChange {
+        block_height: 1,
+        block_hash: *block_1.hash(),
+        deploy_hash: deploy_hash_1,
+        kind: BalanceChangeKind::Added(U512::from(10)),
+    };
+    let change_2 = BalanceChange {
+        block_height: 2,
+        block_hash: *block_2.hash(),
+        deploy_hash: deploy_hash_2,
+        kind: BalanceChangeKind::Written(U512::from(5)),
+    };
+
+    assert_eq!(
+        get_balance_history(&mut harness, &mut storage, purse_addr, 0, 10),
+        vec![change_1.clone(), change_2.clone()]
+    );
+    assert_eq!(
+        get_balance_history(&mut harness, &mut storage, purse_addr, 0, 1),
+        vec![change_1]
+    );
+    assert_eq!(
+        get_balance_history(&mut harness, &mut storage, purse_addr, 2, 10),
+        vec![change_2]
+    );
+    assert!(get_balance_history(&mut harness, &mut storage, purse_addr, 3, 10).is_empty());
+    assert_eq!(
+        get_balance_history(&mut harness, &mut storage, other_purse_addr, 0, 10).len(),
+        1
+    );
+}
+
+#[test]
+fn should_index_balance_changes_stored_before_their_block() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let purse_addr: URefAddr = harness.rng.gen();
+    let deploy_hash = DeployHash::random(&mut harness.rng);
+    let finalized_block = FinalizedBlock::new(
+        BlockPayload::new(vec![], vec![deploy_hash], vec![], false),
+        None,
+        Timestamp::now(),
+        EraId::from(1),
+        7,
+        PublicKey::random(&mut harness.rng),
+    );
+    let block = Block::new(
+        BlockHash::random(&mut harness.rng),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+    )
+    .expect("should create block");
+
+    let mut execution_results = HashMap::new();
+    execution_results.insert(
+        deploy_hash,
+        execution_result_with_transforms(vec![(
+            Key::Balance(purse_addr),
+            Transform::AddUInt512(U512::from(10)),
+        )]),
+    );
+    put_execution_results(&mut harness, &mut storage, *block.hash(), execution_results);
+    assert!(get_balance_history(&mut harness, &mut storage, purse_addr, 0, 10).is_empty());
+
+    put_block(&mut harness, &mut storage, Box::new(block.clone()));
+    assert_eq!(
+        get_balance_history(&mut harness, &mut storage, purse_addr, 0, 10),
+        vec![BalanceChange {
+            block_height: 7,
+            block_hash: *block.hash(),
+            deploy_hash,
+            kind: BalanceChangeKind::Added(U512::from(10)),
+        }]
+    );
+}
+
 /// Example state used in storage.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 struct StateData {
@@ -1063,6 +1562,176 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     );
 }
 
+#[test]
+fn should_index_and_persist_account_deploys() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy_a = Deploy::random(&mut harness.rng);
+    let deploy_b = Deploy::random(&mut harness.rng);
+    put_deploy(&mut harness, &mut storage, Box::new(deploy_a.clone()));
+    put_deploy(&mut harness, &mut storage, Box::new(deploy_b.clone()));
+
+    let finalized_block = FinalizedBlock::new(
+        BlockPayload::new(vec![*deploy_a.id()], vec![*deploy_b.id()], vec![], false),
+        None,
+        Timestamp::now(),
+        EraId::from(1),
+        3,
+        PublicKey::random(&mut harness.rng),
+    );
+    let block = Block::new(
+        BlockHash::random(&mut harness.rng),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+    )
+    .expect("should create block");
+    put_block(&mut harness, &mut storage, Box::new(block.clone()));
+
+    let account_hash_a = deploy_a.header().account().to_account_hash();
+    let expected = vec![AccountDeploy {
+        deploy_hash: *deploy_a.id(),
+        block_hash: *block.hash(),
+        block_height: 3,
+    }];
+    assert_eq!(
+        get_account_deploys(&mut harness, &mut storage, account_hash_a, 0, 10),
+        expected
+    );
+    assert!(get_account_deploys(&mut harness, &mut storage, account_hash_a, 4, 10).is_empty());
+
+    // The index should survive a restart.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+
+    assert_eq!(
+        get_account_deploys(&mut harness, &mut storage, account_hash_a, 0, 10),
+        expected
+    );
+    let account_hash_b = deploy_b.header().account().to_account_hash();
+    assert_eq!(
+        get_account_deploys(&mut harness, &mut storage, account_hash_b, 0, 10)
+            .first()
+            .map(|account_deploy| account_deploy.deploy_hash),
+        Some(*deploy_b.id())
+    );
+}
+
+#[test]
+fn should_index_account_deploy_stored_after_its_block() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Deploy::random(&mut harness.rng);
+    let finalized_block = FinalizedBlock::new(
+        BlockPayload::new(vec![*deploy.id()], vec![], vec![], false),
+        None,
+        Timestamp::now(),
+        EraId::from(1),
+        5,
+        PublicKey::random(&mut harness.rng),
+    );
+    let block = Block::new(
+        BlockHash::random(&mut harness.rng),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+    )
+    .expect("should create block");
+    put_block(&mut harness, &mut storage, Box::new(block.clone()));
+
+    let account_hash = deploy.header().account().to_account_hash();
+    assert!(get_account_deploys(&mut harness, &mut storage, account_hash, 0, 10).is_empty());
+
+    put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+    assert_eq!(
+        get_account_deploys(&mut harness, &mut storage, account_hash, 0, 10),
+        vec![AccountDeploy {
+            deploy_hash: *deploy.id(),
+            block_hash: *block.hash(),
+            block_height: 5,
+        }]
+    );
+}
+
+#[test]
+fn should_index_superseding_deploys() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let deploy =
+        Deploy::random_with_account_and_supersession(&mut harness.rng, &secret_key, 2, None);
+    let deploy_hash = *deploy.id();
+    let cheap_replacement = Deploy::random_with_account_and_supersession(
+        &mut harness.rng,
+        &secret_key,
+        2,
+        Some(Supersession::Replace(deploy_hash)),
+    );
+    let replacement = Deploy::random_with_account_and_supersession(
+        &mut harness.rng,
+        &secret_key,
+        3,
+        Some(Supersession::Replace(deploy_hash)),
+    );
+    let other_secret_key = SecretKey::random(&mut harness.rng);
+    let other_canceller = Deploy::random_with_account_and_supersession(
+        &mut harness.rng,
+        &other_secret_key,
+        1,
+        Some(Supersession::Cancel(deploy_hash)),
+    );
+    for deploy in [&deploy, &cheap_replacement, &replacement, &other_canceller] {
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+    }
+
+    // Only the replacement from the same account offering a higher gas price supersedes the
+    // deploy, and the replacement conflicts with the deploy it replaces.
+    assert_eq!(
+        get_supersession_conflicts(&mut harness, &mut storage, &deploy),
+        vec![(*replacement.id(), None)]
+    );
+    assert_eq!(
+        get_supersession_conflicts(&mut harness, &mut storage, &replacement),
+        vec![(deploy_hash, None)]
+    );
+    assert!(get_supersession_conflicts(&mut harness, &mut storage, &other_canceller).is_empty());
+
+    // Once the replacement is included in a block, its height is returned as well.
+    let finalized_block = FinalizedBlock::new(
+        BlockPayload::new(vec![], vec![*replacement.id()], vec![], false),
+        None,
+        Timestamp::now(),
+        EraId::from(1),
+        7,
+        PublicKey::random(&mut harness.rng),
+    );
+    let block = Block::new(
+        BlockHash::random(&mut harness.rng),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        harness.rng.gen::<[u8; Digest::LENGTH]>().into(),
+        finalized_block,
+        None,
+        ProtocolVersion::V1_0_0,
+    )
+    .expect("should create block");
+    put_block(&mut harness, &mut storage, Box::new(block));
+    assert_eq!(
+        get_supersession_conflicts(&mut harness, &mut storage, &deploy),
+        vec![(*replacement.id(), Some(7))]
+    );
+}
+
 #[test]
 fn should_hard_reset() {
     let blocks_count = 8_usize;
@@ -1502,3 +2171,55 @@ fn can_put_and_get_blocks_v2() {
         );
     }
 }
+
+#[test]
+fn should_read_state_root_hashes_to_retain() {
+    let blocks_per_era = 3;
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Create and store 8 blocks, 0-2 in era 0, 3-5 in era 1, and 6,7 in era 2.
+    let blocks: Vec<Block> = (0..8)
+        .map(|height| {
+            let is_switch = height % blocks_per_era == blocks_per_era - 1;
+            Block::random_with_specifics(
+                &mut harness.rng,
+                EraId::from(height / blocks_per_era),
+                height,
+                ProtocolVersion::V1_0_0,
+                is_switch,
+            )
+        })
+        .collect();
+    for block in &blocks {
+        assert!(put_block(
+            &mut harness,
+            &mut storage,
+            Box::new(block.clone())
+        ));
+    }
+
+    let state_root_hashes = |blocks: &[Block]| {
+        let mut state_root_hashes: Vec<Digest> = blocks
+            .iter()
+            .map(|block| *block.header().state_root_hash())
+            .collect();
+        state_root_hashes.sort();
+        state_root_hashes
+    };
+
+    // Retaining one era keeps the state from the switch block of era 1 onwards.
+    assert_eq!(
+        storage.read_state_root_hashes_to_retain(1).unwrap(),
+        state_root_hashes(&blocks[5..])
+    );
+    assert_eq!(
+        storage.read_state_root_hashes_to_retain(2).unwrap(),
+        state_root_hashes(&blocks[2..])
+    );
+    // With fewer switch blocks than retained eras, all state is retained.
+    assert_eq!(
+        storage.read_state_root_hashes_to_retain(3).unwrap(),
+        state_root_hashes(&blocks)
+    );
+}
@@ -3,6 +3,7 @@
 mod asymmetric_key;
 mod asymmetric_key_ext;
 mod error;
+mod signer;
 
 #[cfg(test)]
 pub(crate) use asymmetric_key::generate_ed25519_keypair;
@@ -10,3 +11,4 @@ pub(crate) use asymmetric_key::{sign, verify};
 pub use asymmetric_key_ext::AsymmetricKeyExt;
 pub use error::Error;
 pub(crate) use error::Result;
+pub(crate) use signer::{InProcessSigner, RemoteSigner, Signer, SignerError};
@@ -0,0 +1,287 @@
+//! Creation of signatures with our validator key, either in-process or by an external daemon.
+//!
+//! The remote signer talks to a signing daemon over a Unix socket, so that the validator's secret
+//! key never has to be stored on the node host. For every signature, it opens a new connection and
+//! sends a single line containing a JSON-encoded [`SignRequest`]. The daemon responds with a single
+//! line containing a JSON-encoded [`SignResponse`], and closes the connection.
+//!
+//! Requests to the daemon are asynchronous, so that waiting for it never blocks the reactor.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+};
+use tracing::warn;
+
+use casper_types::{PublicKey, SecretKey, Signature};
+
+use crate::crypto;
+
+/// An error creating a signature.
+#[derive(Debug, Error)]
+pub(crate) enum SignerError {
+    /// Communication with the signing daemon failed.
+    #[error("could not reach the signing daemon: {0}")]
+    Io(#[from] io::Error),
+    /// The signing daemon didn't respond in time.
+    #[error("the signing daemon timed out")]
+    Timeout,
+    /// The signing daemon's response could not be parsed.
+    #[error("invalid response from the signing daemon: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+    /// The signing daemon refused to sign.
+    #[error("the signing daemon returned an error: {0}")]
+    Refused(String),
+    /// The signing daemon returned a signature that is not valid for our public key.
+    #[error("the signing daemon returned an invalid signature: {0}")]
+    InvalidSignature(crypto::Error),
+}
+
+/// Creates signatures on behalf of our validator.
+#[async_trait]
+pub(crate) trait Signer: Debug + Send + Sync {
+    /// Returns the public key whose secret key is used for signing.
+    fn public_key(&self) -> &PublicKey;
+
+    /// Signs the given data.
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError>;
+
+    /// Signs the given data if that is possible without waiting, i.e. if the secret key is held in
+    /// memory. Otherwise returns `None`, and `sign` has to be used instead.
+    fn try_sign_now(&self, data: &[u8]) -> Option<Signature>;
+}
+
+/// A signer that holds the secret key in memory.
+pub(crate) struct InProcessSigner {
+    secret_key: Arc<SecretKey>,
+    public_key: PublicKey,
+}
+
+impl InProcessSigner {
+    /// Creates a new signer using the given secret key.
+    pub(crate) fn new(secret_key: Arc<SecretKey>) -> Self {
+        let public_key = PublicKey::from(secret_key.as_ref());
+        InProcessSigner {
+            secret_key,
+            public_key,
+        }
+    }
+}
+
+impl Debug for InProcessSigner {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("InProcessSigner")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Signer for InProcessSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        Ok(crypto::sign(data, &self.secret_key, &self.public_key))
+    }
+
+    fn try_sign_now(&self, data: &[u8]) -> Option<Signature> {
+        Some(crypto::sign(data, &self.secret_key, &self.public_key))
+    }
+}
+
+/// A request to the signing daemon.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SignRequest {
+    /// The public key whose secret key should be used.
+    pub(crate) public_key: PublicKey,
+    /// The hex-encoded data to be signed.
+    pub(crate) data: String,
+}
+
+/// The signing daemon's response.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SignResponse {
+    /// The requested signature.
+    Signature(Signature),
+    /// The reason why the daemon didn't sign.
+    Error(String),
+}
+
+/// A signer that requests signatures from an external signing daemon over a Unix socket.
+#[derive(Debug)]
+pub(crate) struct RemoteSigner {
+    /// The path to the signing daemon's socket.
+    socket_path: PathBuf,
+    /// The public key the daemon signs with.
+    public_key: PublicKey,
+    /// The timeout for each request.
+    request_timeout: Duration,
+    /// The number of times a request is attempted before giving up.
+    max_attempts: u32,
+}
+
+impl RemoteSigner {
+    /// Creates a new remote signer using the daemon listening at `socket_path`.
+    pub(crate) fn new(
+        socket_path: PathBuf,
+        public_key: PublicKey,
+        request_timeout: Duration,
+        max_attempts: u32,
+    ) -> Self {
+        RemoteSigner {
+            socket_path,
+            public_key,
+            request_timeout,
+            max_attempts: max_attempts.max(1),
+        }
+    }
+
+    /// Sends a single signing request to the daemon and verifies the returned signature.
+    async fn request_signature(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        let mut stream = UnixStream::connect(&self.socket_path).await?;
+        let request = SignRequest {
+            public_key: self.public_key.clone(),
+            data: hex::encode(data),
+        };
+        let mut bytes = serde_json::to_vec(&request)?;
+        bytes.push(b'\n');
+        stream.write_all(&bytes).await?;
+        let mut line = String::new();
+        let _ = BufReader::new(stream).read_line(&mut line).await?;
+        match serde_json::from_str(&line)? {
+            SignResponse::Signature(signature) => {
+                crypto::verify(data, &signature, &self.public_key)
+                    .map_err(SignerError::InvalidSignature)?;
+                Ok(signature)
+            }
+            SignResponse::Error(message) => Err(SignerError::Refused(message)),
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        let mut attempt = 1;
+        loop {
+            let result = tokio::time::timeout(self.request_timeout, self.request_signature(data))
+                .await
+                .unwrap_or(Err(SignerError::Timeout));
+            match result {
+                Ok(signature) => return Ok(signature),
+                Err(err) if attempt < self.max_attempts => {
+                    warn!(%err, %attempt, socket_path = ?self.socket_path, "signing request failed");
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn try_sign_now(&self, _data: &[u8]) -> Option<Signature> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        os::unix::net::UnixListener,
+        thread,
+    };
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::crypto::generate_ed25519_keypair;
+
+    /// Runs a signing daemon that handles `count` requests using `secret_key`.
+    fn run_daemon(
+        listener: UnixListener,
+        secret_key: SecretKey,
+        count: usize,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let stream = stream.unwrap();
+                let mut line = String::new();
+                BufReader::new(&stream).read_line(&mut line).unwrap();
+                let request: SignRequest = serde_json::from_str(&line).unwrap();
+                let data = hex::decode(request.data).unwrap();
+                let response =
+                    SignResponse::Signature(crypto::sign(data, &secret_key, &request.public_key));
+                let mut bytes = serde_json::to_vec(&response).unwrap();
+                bytes.push(b'\n');
+                (&stream).write_all(&bytes).unwrap();
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn remote_signer_signs_via_daemon() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let (secret_key, public_key) = generate_ed25519_keypair();
+        let daemon = run_daemon(listener, secret_key, 1);
+
+        let signer = RemoteSigner::new(socket_path, public_key, Duration::from_secs(5), 1);
+        assert!(signer.try_sign_now(b"block").is_none());
+        let signature = signer.sign(b"block").await.expect("should sign");
+        crypto::verify(b"block", &signature, signer.public_key()).expect("should be valid");
+        daemon.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn remote_signer_rejects_signature_with_wrong_key() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let (secret_key, _) = generate_ed25519_keypair();
+        let (_, other_public_key) = generate_ed25519_keypair();
+        let daemon = run_daemon(listener, secret_key, 2);
+
+        let signer = RemoteSigner::new(socket_path, other_public_key, Duration::from_secs(5), 2);
+        match signer.sign(b"block").await {
+            Err(SignerError::InvalidSignature(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+        daemon.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn remote_signer_fails_without_daemon() {
+        let dir = tempdir().unwrap();
+        let (_, public_key) = generate_ed25519_keypair();
+        let signer = RemoteSigner::new(
+            dir.path().join("missing.sock"),
+            public_key,
+            Duration::from_secs(5),
+            3,
+        );
+        assert!(matches!(
+            signer.sign(b"block").await,
+            Err(SignerError::Io(_))
+        ));
+    }
+}
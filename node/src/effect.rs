@@ -87,21 +87,26 @@ use casper_execution_engine::{
         era_validators::GetEraValidatorsError,
         genesis::GenesisSuccess,
         upgrade::{UpgradeConfig, UpgradeSuccess},
-        BalanceRequest, BalanceResult, GetBidsRequest, GetBidsResult, QueryRequest, QueryResult,
+        BalanceRequest, BalanceResult, ExecutionTrace, GetBidsRequest, GetBidsResult,
+        GetDictionaryItemsRequest, GetDictionaryItemsResult, GetKeysByPrefixRequest,
+        GetKeysByPrefixResult, QueryRequest, QueryResult,
     },
     storage::trie::Trie,
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::Account, system::auction::EraValidators, Contract, ContractPackage, EraId,
-    ExecutionResult, Key, ProtocolVersion, PublicKey, StoredValue, Transfer, URef, U512,
+    account::{Account, AccountHash},
+    system::auction::EraValidators,
+    Contract, ContractEvent, ContractPackage, EraId, ExecutionResult, Key, ProtocolVersion,
+    PublicKey, StoredValue, Transfer, URef, URefAddr, U512,
 };
 
 use crate::{
     components::{
+        block_proposer::PendingDeploys,
         block_validator::ValidatingBlock,
         chainspec_loader::{CurrentRunInfo, NextUpgrade},
-        consensus::{BlockContext, ClContext, ValidatorChange},
+        consensus::{BlockContext, ClContext, EraEvidence, HighwaySnapshot, ValidatorChange},
         contract_runtime::EraValidatorsRequest,
         deploy_acceptor,
         fetcher::FetchResult,
@@ -109,9 +114,10 @@ use crate::{
     },
     reactor::{EventQueueHandle, QueueKind},
     types::{
-        Block, BlockByHeight, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Chainspec,
-        ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalitySignature,
-        FinalizedBlock, Item, TimeDiff, Timestamp,
+        AccountDeploy, BalanceChange, Block, BlockByHeight, BlockHash, BlockHeader,
+        BlockHeaderWithMetadata, BlockPayload, BlockSignatures, Chainspec, ChainspecInfo, Deploy,
+        DeployHash, DeployHeader, DeployMetadata, FinalitySignature, FinalizedBlock, Item,
+        TimeDiff, Timestamp,
     },
     utils::Source,
 };
@@ -601,6 +607,26 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the current network peers running at least the given protocol version, in a random
+    /// order.
+    pub(crate) async fn get_peers_with_protocol_version_in_random_order<I>(
+        self,
+        min_protocol_version: ProtocolVersion,
+    ) -> Vec<I>
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetPeersWithProtocolVersionInRandomOrder {
+                min_protocol_version,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Announces which deploys have expired.
     pub(crate) async fn announce_expired_deploys(self, hashes: Vec<DeployHash>)
     where
@@ -614,6 +640,25 @@ impl<REv> EffectBuilder<REv> {
             .await;
     }
 
+    /// Announces that a pending deploy was replaced or cancelled by a later deploy.
+    pub(crate) async fn announce_deploy_superseded(
+        self,
+        superseded: DeployHash,
+        superseded_by: DeployHash,
+    ) where
+        REv: From<BlockProposerAnnouncement>,
+    {
+        self.0
+            .schedule(
+                BlockProposerAnnouncement::DeploySuperseded {
+                    superseded,
+                    superseded_by,
+                },
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
     /// Announces that a network message has been received.
     pub(crate) async fn announce_message_received<I, P>(self, sender: I, payload: P)
     where
@@ -736,12 +781,17 @@ impl<REv> EffectBuilder<REv> {
         self,
         block: Block,
         execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+        contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
     ) where
         REv: From<ContractRuntimeAnnouncement>,
     {
         self.0
             .schedule(
-                ContractRuntimeAnnouncement::linear_chain_block(block, execution_results),
+                ContractRuntimeAnnouncement::linear_chain_block(
+                    block,
+                    execution_results,
+                    contract_events,
+                ),
                 QueueKind::Regular,
             )
             .await
@@ -772,6 +822,21 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Puts the given block header into the linear block store, without the block's body.
+    pub(crate) async fn put_block_header_to_storage(self, block_header: Box<BlockHeader>) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutBlockHeader {
+                block_header,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the requested block from the linear block store.
     pub(crate) async fn get_block_from_storage(self, block_hash: BlockHash) -> Option<Block>
     where
@@ -788,7 +853,6 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Gets the requested block header from the linear block store.
-    #[allow(unused)]
     pub(crate) async fn get_block_header_from_storage(
         self,
         block_hash: BlockHash,
@@ -857,6 +921,52 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets up to `max_count` recorded balance changes of the given purse from storage, starting
+    /// at `start_height`.
+    pub(crate) async fn get_balance_history_from_storage(
+        self,
+        purse_addr: URefAddr,
+        start_height: u64,
+        max_count: u32,
+    ) -> Vec<BalanceChange>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBalanceHistory {
+                purse_addr,
+                start_height,
+                max_count,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Gets up to `max_count` deploys sent by the given account from storage, starting at
+    /// `start_height`.
+    pub(crate) async fn get_account_deploys_from_storage(
+        self,
+        account_hash: AccountHash,
+        start_height: u64,
+        max_count: u32,
+    ) -> Vec<AccountDeploy>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetAccountDeploys {
+                account_hash,
+                start_height,
+                max_count,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Requests the block header at the given height.
     pub(crate) async fn get_block_header_at_height_from_storage(
         self,
@@ -872,6 +982,24 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the header of a block with the given post-state hash.
+    pub(crate) async fn get_block_header_by_state_root_hash_from_storage(
+        self,
+        state_root_hash: Digest,
+    ) -> Option<BlockHeader>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeaderByStateRootHash {
+                state_root_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Requests the header of the block containing the given deploy.
     pub(crate) async fn get_block_header_for_deploy_from_storage(
         self,
@@ -929,6 +1057,25 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the state root hashes of all blocks from the switch block `retained_eras` eras back
+    /// onwards.
+    pub(crate) async fn get_state_root_hashes_to_retain_from_storage(
+        self,
+        retained_eras: u64,
+    ) -> Vec<Digest>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetStateRootHashesToRetain {
+                retained_eras,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Requests the key block header for the given era ID, ie. the header of the switch block at
     /// the era before (if one exists).
     pub(crate) async fn get_key_block_header_for_era_id_from_storage(
@@ -962,7 +1109,6 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Puts a trie into the trie store and asynchronously returns any missing descendant trie keys.
-    #[allow(unused)]
     pub(crate) async fn put_trie_and_find_missing_descendant_trie_keys(
         self,
         trie: Box<Trie<Key, StoredValue>>,
@@ -977,6 +1123,35 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Executes the given deploy against the given state root hash without committing any of its
+    /// effects to global state.
+    ///
+    /// If `trace` is true, a trace of the execution is returned along with the result.
+    pub(crate) async fn speculative_execute_deploy(
+        self,
+        state_root_hash: Digest,
+        block_time: u64,
+        protocol_version: ProtocolVersion,
+        deploy: Box<Deploy>,
+        trace: bool,
+    ) -> Result<Option<(ExecutionResult, Option<ExecutionTrace>)>, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
+                state_root_hash,
+                block_time,
+                protocol_version,
+                deploy,
+                trace,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Puts the given deploy into the deploy store.
     pub(crate) async fn put_deploy_to_storage(self, deploy: Box<Deploy>) -> bool
     where
@@ -1007,6 +1182,27 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the stored deploys from the same account which supersede the given deploy or which it
+    /// replaces, together with the heights of the blocks including them, if any.
+    pub(crate) async fn get_supersession_conflicts_from_storage(
+        self,
+        deploy_hash: DeployHash,
+        deploy_header: DeployHeader,
+    ) -> Vec<(DeployHash, Option<u64>)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetSupersessionConflicts {
+                deploy_hash,
+                deploy_header: Box::new(deploy_header),
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Stores the given execution results for the deploys in the given block in the linear block
     /// store.
     pub(crate) async fn put_execution_results_to_storage(
@@ -1027,6 +1223,43 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Stores the given contract events emitted by the deploys in the given block.
+    pub(crate) async fn put_contract_events_to_storage(
+        self,
+        block_hash: BlockHash,
+        contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
+    ) where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutContractEvents {
+                block_hash: Box::new(block_hash),
+                contract_events,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the contract events emitted by the given deploy, by block, from storage.
+    pub(crate) async fn get_contract_events_from_storage(
+        self,
+        deploy_hash: DeployHash,
+    ) -> HashMap<BlockHash, Vec<ContractEvent>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetContractEvents {
+                deploy_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Gets the requested deploys from the deploy store.
     pub(crate) async fn get_deploy_and_metadata_from_storage(
         self,
@@ -1063,6 +1296,52 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets up to `max_count` consecutive blocks and their associated metadata, starting at the
+    /// given height.
+    pub(crate) async fn get_blocks_with_metadata_in_range_from_storage(
+        self,
+        start_height: u64,
+        max_count: u32,
+        with_signatures: bool,
+    ) -> Vec<(Block, BlockSignatures)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlocksWithMetadataInRange {
+                start_height,
+                max_count,
+                with_signatures,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Gets up to `max_count` consecutive block headers and their associated metadata, starting at
+    /// the given height.
+    pub(crate) async fn get_block_headers_with_metadata_in_range_from_storage(
+        self,
+        start_height: u64,
+        max_count: u32,
+        with_signatures: bool,
+    ) -> Vec<BlockHeaderWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeadersWithMetadataInRange {
+                start_height,
+                max_count,
+                with_signatures,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Gets the requested block by hash with its associated metadata.
     pub(crate) async fn get_block_with_metadata_from_storage(
         self,
@@ -1158,6 +1437,24 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the requested item from the given peer using the item's fetcher.
+    pub(crate) async fn fetch<T, I>(self, id: T::Id, peer: I) -> Option<FetchResult<T, I>>
+    where
+        REv: From<FetcherRequest<I, T>>,
+        T: Item + 'static,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest::Fetch {
+                id,
+                peer,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Passes the timestamp of a future block for which deploys are to be proposed.
     pub(crate) async fn request_block_payload(
         self,
@@ -1184,6 +1481,15 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Returns a snapshot of the deploys currently held by the block proposer.
+    pub(crate) async fn get_pending_deploys(self) -> PendingDeploys
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(BlockProposerRequest::GetPendingDeploys, QueueKind::Regular)
+            .await
+    }
+
     /// Executes a finalized block.
     pub(crate) async fn execute_finalized_block(
         self,
@@ -1482,6 +1788,29 @@ impl<REv> EffectBuilder<REv> {
         }
     }
 
+    /// Retrieves the nonce of the latest deploy executed by an account, or 0 if there is none.
+    pub(crate) async fn get_account_nonce_from_global_state(
+        self,
+        prestate_hash: Digest,
+        account_hash: AccountHash,
+    ) -> u64
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        let query_request = QueryRequest::new(
+            prestate_hash,
+            engine_state::account_nonce_key(account_hash),
+            vec![],
+        );
+        match self.query_global_state(query_request).await {
+            Ok(QueryResult::Success { value, .. }) => value
+                .as_cl_value()
+                .and_then(|cl_value| cl_value.clone().into_t().ok())
+                .unwrap_or_default(),
+            Ok(_) | Err(_) => 0,
+        }
+    }
+
     /// Retrieves the balance of a purse, returns `None` if no purse is present.
     pub(crate) async fn check_purse_balance(
         self,
@@ -1590,6 +1919,42 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests a page of the keys matching a prefix from the Contract Runtime component.
+    pub(crate) async fn get_keys_by_prefix(
+        self,
+        get_keys_by_prefix_request: GetKeysByPrefixRequest,
+    ) -> Result<GetKeysByPrefixResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests a page of the items of a dictionary from the Contract Runtime component.
+    pub(crate) async fn get_dictionary_items(
+        self,
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+    ) -> Result<GetDictionaryItemsResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the correct era validators set for the given era.
     /// Takes emergency restarts into account based on the information from the chainspec loader.
     pub(crate) async fn get_era_validators(self, era_id: EraId) -> Option<BTreeMap<PublicKey, U512>>
@@ -1734,6 +2099,36 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Returns the evidence against faulty validators, optionally restricted to a single era and
+    /// validator.
+    pub(crate) async fn get_consensus_evidence(
+        self,
+        era_id: Option<EraId>,
+        public_key: Option<PublicKey>,
+    ) -> Vec<EraEvidence>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(
+            |responder| ConsensusRequest::Evidence {
+                era_id,
+                public_key,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Returns a snapshot of the current era's Highway protocol state, if there is one.
+    pub(crate) async fn get_consensus_snapshot(self) -> Option<HighwaySnapshot>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::Snapshot, QueueKind::Regular)
+            .await
+    }
+
     /// Collects the key blocks for the eras identified by provided era IDs. Returns
     /// `Some(HashMap(era_id → block_header))` if all the blocks have been read correctly, and
     /// `None` if at least one was missing. The header for EraId `n` is from the key block for that
@@ -11,7 +11,7 @@ use std::{
 use itertools::Itertools;
 use serde::Serialize;
 
-use casper_types::{EraId, ExecutionResult, PublicKey};
+use casper_types::{ContractEvent, EraId, ExecutionResult, PublicKey};
 
 use crate::{
     components::{
@@ -161,6 +161,13 @@ impl<I: Display> Display for DeployAcceptorAnnouncement<I> {
 pub(crate) enum BlockProposerAnnouncement {
     /// Hashes of the deploys that expired.
     DeploysExpired(Vec<DeployHash>),
+    /// A pending deploy was replaced or cancelled by a later deploy from the same account.
+    DeploySuperseded {
+        /// The hash of the deploy which was removed from the buffer.
+        superseded: DeployHash,
+        /// The hash of the replacing or cancelling deploy.
+        superseded_by: DeployHash,
+    },
 }
 
 impl Display for BlockProposerAnnouncement {
@@ -169,6 +176,12 @@ impl Display for BlockProposerAnnouncement {
             BlockProposerAnnouncement::DeploysExpired(hashes) => {
                 write!(f, "pruned hashes: {}", hashes.iter().join(", "))
             }
+            BlockProposerAnnouncement::DeploySuperseded {
+                superseded,
+                superseded_by,
+            } => {
+                write!(f, "deploy {} superseded by {}", superseded, superseded_by)
+            }
         }
     }
 }
@@ -240,6 +253,8 @@ pub(crate) struct LinearChainBlock {
     pub(crate) block: Block,
     /// The results of executing the deploys in this block.
     pub(crate) execution_results: HashMap<DeployHash, (DeployHeader, ExecutionResult)>,
+    /// The events emitted by contracts while executing the deploys in this block.
+    pub(crate) contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
 }
 
 /// A Gossiper announcement.
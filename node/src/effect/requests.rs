@@ -21,8 +21,11 @@ use casper_execution_engine::{
         self,
         balance::{BalanceRequest, BalanceResult},
         era_validators::GetEraValidatorsError,
+        execution_trace::ExecutionTrace,
         genesis::GenesisSuccess,
         get_bids::{GetBidsRequest, GetBidsResult},
+        get_dictionary_items::{GetDictionaryItemsRequest, GetDictionaryItemsResult},
+        get_keys_by_prefix::{GetKeysByPrefixRequest, GetKeysByPrefixResult},
         query::{QueryRequest, QueryResult},
         upgrade::{UpgradeConfig, UpgradeSuccess},
     },
@@ -30,15 +33,16 @@ use casper_execution_engine::{
 };
 use casper_hashing::Digest;
 use casper_types::{
-    system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion, PublicKey,
-    StoredValue, Transfer, URef,
+    account::AccountHash, system::auction::EraValidators, ContractEvent, EraId, ExecutionResult,
+    Key, ProtocolVersion, PublicKey, StoredValue, Transfer, URef, URefAddr,
 };
 
 use crate::{
     components::{
+        block_proposer::PendingDeploys,
         block_validator::ValidatingBlock,
         chainspec_loader::CurrentRunInfo,
-        consensus::{BlockContext, ClContext, ValidatorChange},
+        consensus::{BlockContext, ClContext, EraEvidence, HighwaySnapshot, ValidatorChange},
         contract_runtime::{
             BlockAndExecutionEffects, BlockExecutionError, EraValidatorsRequest, ExecutionPreState,
         },
@@ -48,9 +52,9 @@ use crate::{
     effect::Responder,
     rpcs::{chain::BlockIdentifier, docs::OpenRpcSchema},
     types::{
-        Block, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Chainspec, ChainspecInfo,
-        Deploy, DeployHash, DeployHeader, DeployMetadata, FinalizedBlock, Item, NodeId, StatusFeed,
-        TimeDiff,
+        AccountDeploy, BalanceChange, Block, BlockHash, BlockHeader, BlockHeaderWithMetadata,
+        BlockPayload, BlockSignatures, Chainspec, ChainspecInfo, Deploy, DeployHash, DeployHeader,
+        DeployMetadata, FinalizedBlock, Item, NodeId, StatusFeed, TimeDiff,
     },
     utils::DisplayIter,
 };
@@ -191,6 +195,13 @@ pub(crate) enum NetworkInfoRequest<I> {
         /// Responds with a vector in a random order.
         responder: Responder<Vec<I>>,
     },
+    /// Get the peers running at least the given protocol version, in random order.
+    GetPeersWithProtocolVersionInRandomOrder {
+        /// The lowest protocol version the peers must have announced in their handshake.
+        min_protocol_version: ProtocolVersion,
+        /// Responder to be called with the matching connected peers, in a random order.
+        responder: Responder<Vec<I>>,
+    },
 }
 
 impl<I> Display for NetworkInfoRequest<I>
@@ -203,6 +214,14 @@ where
             NetworkInfoRequest::GetPeersInRandomOrder { responder: _ } => {
                 write!(formatter, "get peers in random order")
             }
+            NetworkInfoRequest::GetPeersWithProtocolVersionInRandomOrder {
+                min_protocol_version,
+                responder: _,
+            } => write!(
+                formatter,
+                "get peers with protocol version {} or later in random order",
+                min_protocol_version
+            ),
         }
     }
 }
@@ -220,6 +239,14 @@ pub(crate) enum StorageRequest {
         /// attempt or false if it was previously stored.
         responder: Responder<bool>,
     },
+    /// Store given block header, without the block's body.
+    PutBlockHeader {
+        /// Block header to be stored.
+        block_header: Box<BlockHeader>,
+        /// Responder to call with the result.  Returns true if the block header was stored on this
+        /// attempt or false if it was previously stored.
+        responder: Responder<bool>,
+    },
     /// Retrieve block with given hash.
     GetBlock {
         /// Hash of block to be retrieved.
@@ -235,6 +262,13 @@ pub(crate) enum StorageRequest {
         /// Responder.
         responder: Responder<Option<BlockHeader>>,
     },
+    /// Retrieve the header of a block with the given post-state hash.
+    GetBlockHeaderByStateRootHash {
+        /// The state root hash.
+        state_root_hash: Digest,
+        /// Responder.
+        responder: Responder<Option<BlockHeader>>,
+    },
     /// Retrieve block with given height.
     GetBlockAtHeight {
         /// Height of the block.
@@ -277,6 +311,34 @@ pub(crate) enum StorageRequest {
         /// local storage under the block_hash provided.
         responder: Responder<Option<Vec<Transfer>>>,
     },
+    /// Retrieve the recorded balance changes of a purse, in order of block height.
+    ///
+    /// At most `max_count` changes are returned, except that the changes of the last block
+    /// included are never split up.
+    GetBalanceHistory {
+        /// Address of the purse.
+        purse_addr: URefAddr,
+        /// Height of the first block to consider.
+        start_height: BlockHeight,
+        /// Maximum number of balance changes to return.
+        max_count: u32,
+        /// Responder to call with the results.
+        responder: Responder<Vec<BalanceChange>>,
+    },
+    /// Retrieve the deploys sent by an account, in order of block height.
+    ///
+    /// At most `max_count` deploys are returned, except that the deploys of the last block
+    /// included are never split up.
+    GetAccountDeploys {
+        /// Hash of the account.
+        account_hash: AccountHash,
+        /// Height of the first block to consider.
+        start_height: BlockHeight,
+        /// Maximum number of deploys to return.
+        max_count: u32,
+        /// Responder to call with the results.
+        responder: Responder<Vec<AccountDeploy>>,
+    },
     /// Store given deploy.
     PutDeploy {
         /// Deploy to store.
@@ -292,6 +354,16 @@ pub(crate) enum StorageRequest {
         /// Responder to call with the results.
         responder: Responder<Vec<Option<Deploy>>>,
     },
+    /// Retrieve the stored deploys from the same account which supersede the given deploy, or
+    /// which it replaces, together with the height of the block including each of them, if any.
+    GetSupersessionConflicts {
+        /// Hash of the deploy.
+        deploy_hash: DeployHash,
+        /// Header of the deploy.
+        deploy_header: Box<DeployHeader>,
+        /// Responder to call with the results.
+        responder: Responder<Vec<(DeployHash, Option<BlockHeight>)>>,
+    },
     /// Retrieve deploys that are finalized and whose TTL hasn't expired yet.
     GetFinalizedDeploys {
         /// Maximum TTL of block we're interested in.
@@ -315,6 +387,22 @@ pub(crate) enum StorageRequest {
         /// Responder to call when done storing.
         responder: Responder<()>,
     },
+    /// Store the contract events emitted by a set of deploys of a single block.
+    PutContractEvents {
+        /// Hash of block.
+        block_hash: Box<BlockHash>,
+        /// Mapping of deploys to the events they emitted in the block.
+        contract_events: HashMap<DeployHash, Vec<ContractEvent>>,
+        /// Responder to call when done storing.
+        responder: Responder<()>,
+    },
+    /// Retrieve the contract events emitted by a deploy, by block.
+    GetContractEvents {
+        /// Hash of deploy whose events are to be retrieved.
+        deploy_hash: DeployHash,
+        /// Responder to call with the results.
+        responder: Responder<HashMap<BlockHash, Vec<ContractEvent>>>,
+    },
     /// Retrieve deploy and its metadata.
     GetDeployAndMetadata {
         /// Hash of deploy to be retrieved.
@@ -336,6 +424,30 @@ pub(crate) enum StorageRequest {
         /// The responder to call with the results.
         responder: Responder<Option<(Block, BlockSignatures)>>,
     },
+    /// Retrieve up to `max_count` consecutive blocks and their metadata, starting at the given
+    /// height.
+    GetBlocksWithMetadataInRange {
+        /// The height of the first block.
+        start_height: BlockHeight,
+        /// Maximum number of blocks to return.
+        max_count: u32,
+        /// Whether to retrieve the blocks' finality signatures.
+        with_signatures: bool,
+        /// The responder to call with the results.
+        responder: Responder<Vec<(Block, BlockSignatures)>>,
+    },
+    /// Retrieve up to `max_count` consecutive block headers and their metadata, starting at the
+    /// given height.
+    GetBlockHeadersWithMetadataInRange {
+        /// The height of the first block.
+        start_height: BlockHeight,
+        /// Maximum number of block headers to return.
+        max_count: u32,
+        /// Whether to retrieve the blocks' finality signatures.
+        with_signatures: bool,
+        /// The responder to call with the results.
+        responder: Responder<Vec<BlockHeaderWithMetadata>>,
+    },
     /// Get the highest block and its metadata.
     GetHighestBlockWithMetadata {
         /// The responder to call the results with.
@@ -356,16 +468,34 @@ pub(crate) enum StorageRequest {
         /// stored.
         responder: Responder<bool>,
     },
+    /// Retrieve the state root hashes of all stored blocks from the switch block
+    /// `retained_eras` eras back onwards.
+    GetStateRootHashesToRetain {
+        /// The number of most recent eras whose global state is retained.
+        retained_eras: u64,
+        /// Responder to call with the results.
+        responder: Responder<Vec<Digest>>,
+    },
 }
 
 impl Display for StorageRequest {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             StorageRequest::PutBlock { block, .. } => write!(formatter, "put {}", block),
+            StorageRequest::PutBlockHeader { block_header, .. } => {
+                write!(formatter, "put {}", block_header)
+            }
             StorageRequest::GetBlock { block_hash, .. } => write!(formatter, "get {}", block_hash),
             StorageRequest::GetBlockHeaderAtHeight { height, .. } => {
                 write!(formatter, "get block header at height {}", height)
             }
+            StorageRequest::GetBlockHeaderByStateRootHash {
+                state_root_hash, ..
+            } => write!(
+                formatter,
+                "get block header with state root hash {}",
+                state_root_hash
+            ),
             StorageRequest::GetBlockAtHeight { height, .. } => {
                 write!(formatter, "get block at height {}", height)
             }
@@ -382,13 +512,41 @@ impl Display for StorageRequest {
             StorageRequest::GetBlockTransfers { block_hash, .. } => {
                 write!(formatter, "get transfers for {}", block_hash)
             }
+            StorageRequest::GetBalanceHistory {
+                purse_addr,
+                start_height,
+                ..
+            } => write!(
+                formatter,
+                "get balance history for purse {} from height {}",
+                HexFmt(purse_addr),
+                start_height
+            ),
+            StorageRequest::GetAccountDeploys {
+                account_hash,
+                start_height,
+                ..
+            } => write!(
+                formatter,
+                "get deploys of account {} from height {}",
+                account_hash, start_height
+            ),
             StorageRequest::PutDeploy { deploy, .. } => write!(formatter, "put {}", deploy),
             StorageRequest::GetDeploys { deploy_hashes, .. } => {
                 write!(formatter, "get {}", DisplayIter::new(deploy_hashes.iter()))
             }
+            StorageRequest::GetSupersessionConflicts { deploy_hash, .. } => {
+                write!(formatter, "get supersession conflicts of {}", deploy_hash)
+            }
             StorageRequest::PutExecutionResults { block_hash, .. } => {
                 write!(formatter, "put execution results for {}", block_hash)
             }
+            StorageRequest::PutContractEvents { block_hash, .. } => {
+                write!(formatter, "put contract events for {}", block_hash)
+            }
+            StorageRequest::GetContractEvents { deploy_hash, .. } => {
+                write!(formatter, "get contract events for {}", deploy_hash)
+            }
             StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
                 write!(formatter, "get deploy and metadata for {}", deploy_hash)
             }
@@ -406,6 +564,24 @@ impl Display for StorageRequest {
                     block_height
                 )
             }
+            StorageRequest::GetBlocksWithMetadataInRange {
+                start_height,
+                max_count,
+                ..
+            } => write!(
+                formatter,
+                "get up to {} blocks and metadata from height {}",
+                max_count, start_height
+            ),
+            StorageRequest::GetBlockHeadersWithMetadataInRange {
+                start_height,
+                max_count,
+                ..
+            } => write!(
+                formatter,
+                "get up to {} block headers and metadata from height {}",
+                max_count, start_height
+            ),
             StorageRequest::GetHighestBlockWithMetadata { .. } => {
                 write!(formatter, "get highest block with metadata")
             }
@@ -422,6 +598,11 @@ impl Display for StorageRequest {
             StorageRequest::GetFinalizedDeploys { ttl, .. } => {
                 write!(formatter, "get finalized deploys, ttl: {:?}", ttl)
             }
+            StorageRequest::GetStateRootHashesToRetain { retained_eras, .. } => write!(
+                formatter,
+                "get state root hashes of the last {} eras",
+                retained_eras
+            ),
         }
     }
 }
@@ -488,6 +669,8 @@ pub(crate) struct BlockPayloadRequest {
 pub(crate) enum BlockProposerRequest {
     /// Request a list of deploys to propose in a new block.
     RequestBlockPayload(BlockPayloadRequest),
+    /// Request a snapshot of the deploys currently held by the block proposer.
+    GetPendingDeploys(Responder<PendingDeploys>),
 }
 
 impl Display for BlockProposerRequest {
@@ -506,6 +689,7 @@ impl Display for BlockProposerRequest {
                 context.height(),
                 next_finalized
             ),
+            BlockProposerRequest::GetPendingDeploys(_) => write!(formatter, "get pending deploys"),
         }
     }
 }
@@ -566,6 +750,20 @@ pub(crate) enum RpcRequest<I> {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Get a page of the keys matching a prefix at a given root hash.
+    GetKeysByPrefix {
+        /// Get keys by prefix request.
+        get_keys_by_prefix_request: GetKeysByPrefixRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetKeysByPrefixResult, engine_state::Error>>,
+    },
+    /// Get a page of the items of a dictionary at a given root hash.
+    GetDictionaryItems {
+        /// Get dictionary items request.
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetDictionaryItemsResult, engine_state::Error>>,
+    },
     /// Query the global state at the given root hash.
     GetBalance {
         /// The state root hash.
@@ -629,6 +827,22 @@ impl<I> Display for RpcRequest<I> {
             } => {
                 write!(formatter, "bids {}", state_root_hash)
             }
+            RpcRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                ..
+            } => write!(
+                formatter,
+                "keys by prefix {}",
+                get_keys_by_prefix_request.state_hash()
+            ),
+            RpcRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                ..
+            } => write!(
+                formatter,
+                "dictionary items {}",
+                get_dictionary_items_request.state_hash()
+            ),
             RpcRequest::GetBalance {
                 state_root_hash,
                 purse_uref,
@@ -740,6 +954,22 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Return a page of the keys matching a prefix at a given state root hash.
+    GetKeysByPrefix {
+        /// Get keys by prefix request.
+        #[serde(skip_serializing)]
+        get_keys_by_prefix_request: GetKeysByPrefixRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetKeysByPrefixResult, engine_state::Error>>,
+    },
+    /// Return a page of the items of a dictionary at a given state root hash.
+    GetDictionaryItems {
+        /// Get dictionary items request.
+        #[serde(skip_serializing)]
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetDictionaryItemsResult, engine_state::Error>>,
+    },
     /// Check if validator is bonded in the future era (identified by `era_id`).
     IsBonded {
         /// State root hash of the LFB.
@@ -785,6 +1015,23 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<BlockAndExecutionEffects, BlockExecutionError>>,
     },
+    /// Execute a single deploy against the given state root without committing its effects.
+    SpeculativeDeployExecution {
+        /// The state root hash against which the deploy will be executed.
+        state_root_hash: Digest,
+        /// The block time to use for the execution, in milliseconds since the Unix epoch.
+        block_time: u64,
+        /// The protocol version to use for the execution.
+        protocol_version: ProtocolVersion,
+        /// The deploy to execute.
+        deploy: Box<Deploy>,
+        /// Whether to record a trace of the execution.
+        trace: bool,
+        /// Responder to call with the result, and the trace of the execution if requested.
+        responder: Responder<
+            Result<Option<(ExecutionResult, Option<ExecutionTrace>)>, engine_state::Error>,
+        >,
+    },
 }
 
 impl Display for ContractRuntimeRequest {
@@ -827,6 +1074,28 @@ impl Display for ContractRuntimeRequest {
                 write!(formatter, "get bids request: {:?}", get_bids_request)
             }
 
+            ContractRuntimeRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get keys by prefix request: {:?}",
+                    get_keys_by_prefix_request
+                )
+            }
+
+            ContractRuntimeRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get dictionary items request: {:?}",
+                    get_dictionary_items_request
+                )
+            }
+
             ContractRuntimeRequest::IsBonded {
                 public_key, era_id, ..
             } => {
@@ -843,6 +1112,18 @@ impl Display for ContractRuntimeRequest {
             } => {
                 write!(formatter, "Execute finalized block: {}", finalized_block)
             }
+            ContractRuntimeRequest::SpeculativeDeployExecution {
+                state_root_hash,
+                deploy,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "speculatively execute deploy {} against state root {}",
+                    deploy.id(),
+                    state_root_hash
+                )
+            }
         }
     }
 }
@@ -923,6 +1204,18 @@ pub(crate) enum ConsensusRequest {
     Status(Responder<Option<(PublicKey, Option<TimeDiff>)>>),
     /// Request for a list of validator status changes, by public key.
     ValidatorChanges(Responder<BTreeMap<PublicKey, Vec<(EraId, ValidatorChange)>>>),
+    /// Request for the evidence against faulty validators, optionally restricted to a single era
+    /// and validator.
+    Evidence {
+        /// The era to return evidence for, or `None` for all active eras.
+        era_id: Option<EraId>,
+        /// The validator to return evidence against, or `None` for all faulty validators.
+        public_key: Option<PublicKey>,
+        /// Responder to call with the evidence.
+        responder: Responder<Vec<EraEvidence>>,
+    },
+    /// Request for a snapshot of the current era's Highway protocol state.
+    Snapshot(Responder<Option<HighwaySnapshot>>),
 }
 
 /// ChainspecLoader component requests.
@@ -37,7 +37,10 @@ pub(crate) mod utils;
 pub mod cli;
 pub mod crypto;
 pub mod types;
-pub use components::rpc_server::rpcs;
+pub use components::{
+    consensus::{verify_evidence, EraEvidence, EvidenceVerificationError},
+    rpc_server::rpcs,
+};
 
 use std::sync::{
     atomic::{AtomicBool, AtomicUsize},
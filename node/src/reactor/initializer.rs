@@ -217,6 +217,12 @@ impl Reactor {
             &chainspec_loader.chainspec().network_config.name,
         )?;
 
+        if let Some(retained_eras) = config.value().contract_runtime.prune_retained_eras() {
+            contract_runtime::check_prune_retained_eras(
+                retained_eras,
+                chainspec_loader.chainspec(),
+            )?;
+        }
         let contract_runtime = ContractRuntime::new(
             chainspec_loader.chainspec().protocol_config.version,
             storage.root_path(),
@@ -236,7 +242,11 @@ impl Reactor {
         // on restarts (online checks are an alternative).
         if crashed {
             info!("running trie-store integrity check, this may take a while");
-            let state_roots = storage.read_state_root_hashes_for_trie_check()?;
+            let state_roots = match config.value().contract_runtime.prune_retained_eras() {
+                // The global state of older blocks may have been pruned.
+                Some(retained_eras) => storage.read_state_root_hashes_to_retain(retained_eras)?,
+                None => storage.read_state_root_hashes_for_trie_check()?,
+            };
             let missing_trie_keys = contract_runtime.trie_store_check(state_roots.clone())?;
             if !missing_trie_keys.is_empty() {
                 return Err(Error::MissingTrieKeys {
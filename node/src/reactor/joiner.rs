@@ -18,6 +18,9 @@ use reactor::ReactorEvent;
 use serde::Serialize;
 use tracing::{debug, error, info, warn};
 
+use casper_execution_engine::storage::trie::Trie;
+use casper_types::{Key, StoredValue};
+
 #[cfg(test)]
 use crate::testing::network::NetworkedReactor;
 use crate::{
@@ -31,6 +34,7 @@ use crate::{
         fetcher::{self, Fetcher},
         gossiper::{self, Gossiper},
         linear_chain,
+        linear_chain_fast_sync::{self, LinearChainFastSync},
         linear_chain_sync::{self, LinearChainSync},
         metrics::Metrics,
         rest_server::{self, RestServer},
@@ -115,6 +119,20 @@ pub(crate) enum JoinerEvent {
     #[from]
     DeployFetcher(#[serde(skip_serializing)] fetcher::Event<Deploy>),
 
+    /// Block header (by hash) fetcher event.
+    #[from]
+    BlockHeaderFetcher(#[serde(skip_serializing)] fetcher::Event<BlockHeader>),
+
+    /// Block header with metadata (by height) fetcher event.
+    #[from]
+    BlockHeaderWithMetadataFetcher(
+        #[serde(skip_serializing)] fetcher::Event<BlockHeaderWithMetadata>,
+    ),
+
+    /// Global state trie fetcher event.
+    #[from]
+    TrieFetcher(#[serde(skip_serializing)] fetcher::Event<Trie<Key, StoredValue>>),
+
     /// Deploy acceptor event.
     #[from]
     DeployAcceptor(#[serde(skip_serializing)] deploy_acceptor::Event),
@@ -127,6 +145,10 @@ pub(crate) enum JoinerEvent {
     #[from]
     LinearChainSync(#[serde(skip_serializing)] linear_chain_sync::Event<NodeId>),
 
+    /// Linear chain fast sync event.
+    #[from]
+    LinearChainFastSync(#[serde(skip_serializing)] linear_chain_fast_sync::Event),
+
     /// Contract Runtime event.
     #[from]
     ContractRuntime(#[serde(skip_serializing)] ContractRuntimeRequest),
@@ -152,6 +174,20 @@ pub(crate) enum JoinerEvent {
     #[from]
     DeployFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Deploy>),
 
+    /// Block header (by hash) fetcher request.
+    #[from]
+    BlockHeaderFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, BlockHeader>),
+
+    /// Block header with metadata (by height) fetcher request.
+    #[from]
+    BlockHeaderWithMetadataFetcherRequest(
+        #[serde(skip_serializing)] FetcherRequest<NodeId, BlockHeaderWithMetadata>,
+    ),
+
+    /// Global state trie fetcher request.
+    #[from]
+    TrieFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Trie<Key, StoredValue>>),
+
     /// Block validation request.
     #[from]
     BlockValidatorRequest(#[serde(skip_serializing)] BlockValidationRequest<NodeId>),
@@ -220,15 +256,24 @@ impl ReactorEvent for JoinerEvent {
             JoinerEvent::BlockFetcher(_) => "BlockFetcher",
             JoinerEvent::BlockByHeightFetcher(_) => "BlockByHeightFetcher",
             JoinerEvent::DeployFetcher(_) => "DeployFetcher",
+            JoinerEvent::BlockHeaderFetcher(_) => "BlockHeaderFetcher",
+            JoinerEvent::BlockHeaderWithMetadataFetcher(_) => "BlockHeaderWithMetadataFetcher",
+            JoinerEvent::TrieFetcher(_) => "TrieFetcher",
             JoinerEvent::DeployAcceptor(_) => "DeployAcceptor",
             JoinerEvent::BlockValidator(_) => "BlockValidator",
             JoinerEvent::LinearChainSync(_) => "LinearChainSync",
+            JoinerEvent::LinearChainFastSync(_) => "LinearChainFastSync",
             JoinerEvent::ContractRuntime(_) => "ContractRuntime",
             JoinerEvent::LinearChain(_) => "LinearChain",
             JoinerEvent::AddressGossiper(_) => "AddressGossiper",
             JoinerEvent::BlockFetcherRequest(_) => "BlockFetcherRequest",
             JoinerEvent::BlockByHeightFetcherRequest(_) => "BlockByHeightFetcherRequest",
             JoinerEvent::DeployFetcherRequest(_) => "DeployFetcherRequest",
+            JoinerEvent::BlockHeaderFetcherRequest(_) => "BlockHeaderFetcherRequest",
+            JoinerEvent::BlockHeaderWithMetadataFetcherRequest(_) => {
+                "BlockHeaderWithMetadataFetcherRequest"
+            }
+            JoinerEvent::TrieFetcherRequest(_) => "TrieFetcherRequest",
             JoinerEvent::BlockValidatorRequest(_) => "BlockValidatorRequest",
             JoinerEvent::BlockProposerRequest(_) => "BlockProposerRequest",
             JoinerEvent::StateStoreRequest(_) => "StateStoreRequest",
@@ -300,12 +345,31 @@ impl Display for JoinerEvent {
                 write!(f, "deploy fetcher request: {}", request)
             }
             JoinerEvent::LinearChainSync(event) => write!(f, "linear chain: {}", event),
+            JoinerEvent::LinearChainFastSync(event) => {
+                write!(f, "linear chain fast sync: {}", event)
+            }
             JoinerEvent::BlockFetcher(event) => write!(f, "block fetcher: {}", event),
             JoinerEvent::BlockByHeightFetcherRequest(request) => {
                 write!(f, "block by height fetcher request: {}", request)
             }
             JoinerEvent::BlockValidator(event) => write!(f, "block validator event: {}", event),
             JoinerEvent::DeployFetcher(event) => write!(f, "deploy fetcher event: {}", event),
+            JoinerEvent::BlockHeaderFetcher(event) => {
+                write!(f, "block header fetcher event: {}", event)
+            }
+            JoinerEvent::BlockHeaderWithMetadataFetcher(event) => {
+                write!(f, "block header with metadata fetcher event: {}", event)
+            }
+            JoinerEvent::TrieFetcher(event) => write!(f, "trie fetcher event: {}", event),
+            JoinerEvent::BlockHeaderFetcherRequest(request) => {
+                write!(f, "block header fetcher request: {}", request)
+            }
+            JoinerEvent::BlockHeaderWithMetadataFetcherRequest(request) => {
+                write!(f, "block header with metadata fetcher request: {}", request)
+            }
+            JoinerEvent::TrieFetcherRequest(request) => {
+                write!(f, "trie fetcher request: {}", request)
+            }
             JoinerEvent::BlockProposerRequest(req) => write!(f, "block proposer request: {}", req),
             JoinerEvent::ContractRuntime(event) => write!(f, "contract runtime event: {:?}", event),
             JoinerEvent::LinearChain(event) => write!(f, "linear chain event: {}", event),
@@ -349,6 +413,8 @@ pub(crate) struct Reactor {
     contract_runtime: ContractRuntime,
     linear_chain_fetcher: Fetcher<Block>,
     linear_chain_sync: LinearChainSync<NodeId>,
+    // Present only if the node is configured to fast sync from a trusted hash.
+    linear_chain_fast_sync: Option<LinearChainFastSync>,
     block_validator: BlockValidator<NodeId>,
     deploy_fetcher: Fetcher<Deploy>,
     linear_chain: linear_chain::LinearChainComponent<NodeId>,
@@ -356,6 +422,7 @@ pub(crate) struct Reactor {
     block_by_height_fetcher: Fetcher<BlockByHeight>,
     pub(super) block_header_by_hash_fetcher: Fetcher<BlockHeader>,
     pub(super) block_header_with_metadata_fetcher: Fetcher<BlockHeaderWithMetadata>,
+    trie_fetcher: Fetcher<Trie<Key, StoredValue>>,
     #[data_size(skip)]
     deploy_acceptor: DeployAcceptor,
     #[data_size(skip)]
@@ -481,6 +548,8 @@ impl reactor::Reactor for Reactor {
         let block_header_by_hash_fetcher: Fetcher<BlockHeader> =
             Fetcher::new("block_header_by_hash", config.fetcher, registry)?;
 
+        let trie_fetcher = Fetcher::new("trie", config.fetcher, registry)?;
+
         let deploy_acceptor = DeployAcceptor::new(
             config.deploy_acceptor,
             &*chainspec_loader.chainspec(),
@@ -511,10 +580,37 @@ impl reactor::Reactor for Reactor {
             config.linear_chain_sync,
         )?;
 
-        effects.extend(reactor::wrap_effects(
-            JoinerEvent::LinearChainSync,
-            init_sync_effects,
-        ));
+        let fast_sync_trusted_hash = if config.linear_chain_sync.fast_sync() {
+            if trusted_hash.is_none() {
+                warn!("fast sync requires a trusted hash; synchronizing linear chain instead");
+            }
+            trusted_hash
+        } else {
+            None
+        };
+        let linear_chain_fast_sync = match fast_sync_trusted_hash {
+            Some(trusted_hash) => {
+                let (linear_chain_fast_sync, fast_sync_effects) = LinearChainFastSync::new(
+                    effect_builder,
+                    Arc::clone(chainspec_loader.chainspec()),
+                    trusted_hash,
+                    config.linear_chain_sync,
+                );
+                effects.extend(reactor::wrap_effects(
+                    JoinerEvent::LinearChainFastSync,
+                    fast_sync_effects,
+                ));
+                Some(linear_chain_fast_sync)
+            }
+            None => {
+                // The regular linear chain sync only runs if we're not fast syncing.
+                effects.extend(reactor::wrap_effects(
+                    JoinerEvent::LinearChainSync,
+                    init_sync_effects,
+                ));
+                None
+            }
+        };
         effects.extend(reactor::wrap_effects(
             JoinerEvent::ChainspecLoader,
             chainspec_loader.start_checking_for_upgrades(effect_builder),
@@ -531,6 +627,7 @@ impl reactor::Reactor for Reactor {
                 storage,
                 contract_runtime,
                 linear_chain_sync,
+                linear_chain_fast_sync,
                 linear_chain_fetcher,
                 block_validator,
                 deploy_fetcher,
@@ -539,6 +636,7 @@ impl reactor::Reactor for Reactor {
                 block_header_by_hash_fetcher,
                 block_header_with_metadata_fetcher:
                     block_header_and_finality_signatures_by_height_fetcher,
+                trie_fetcher,
                 deploy_acceptor,
                 event_queue_metrics,
                 rest_server,
@@ -564,6 +662,12 @@ impl reactor::Reactor for Reactor {
             JoinerEvent::ControlAnnouncement(ctrl_ann) => {
                 unreachable!("unhandled control announcement: {}", ctrl_ann)
             }
+            JoinerEvent::NetworkAnnouncement(NetworkAnnouncement::NewPeer(_))
+                if self.linear_chain_fast_sync.is_some() =>
+            {
+                // Fast sync asks the network for connected peers itself.
+                Effects::new()
+            }
             JoinerEvent::NetworkAnnouncement(NetworkAnnouncement::NewPeer(id)) => {
                 reactor::wrap_effects(
                     JoinerEvent::LinearChainSync,
@@ -651,6 +755,64 @@ impl reactor::Reactor for Reactor {
                     });
                     self.dispatch_event(effect_builder, rng, event)
                 }
+                Message::GetResponse {
+                    tag: Tag::BlockHeaderByHash,
+                    serialized_item,
+                } => {
+                    let block_header = match bincode::deserialize(&serialized_item) {
+                        Ok(block_header) => Box::new(block_header),
+                        Err(err) => {
+                            error!("failed to decode block header from {}: {}", sender, err);
+                            return Effects::new();
+                        }
+                    };
+                    let event = fetcher::Event::GotRemotely {
+                        item: block_header,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(effect_builder, rng, JoinerEvent::BlockHeaderFetcher(event))
+                }
+                Message::GetResponse {
+                    tag: Tag::BlockHeaderAndFinalitySignaturesByHeight,
+                    serialized_item,
+                } => {
+                    let block_header_with_metadata = match bincode::deserialize(&serialized_item) {
+                        Ok(block_header_with_metadata) => Box::new(block_header_with_metadata),
+                        Err(err) => {
+                            error!(
+                                "failed to decode block header with metadata from {}: {}",
+                                sender, err
+                            );
+                            return Effects::new();
+                        }
+                    };
+                    let event = fetcher::Event::GotRemotely {
+                        item: block_header_with_metadata,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(
+                        effect_builder,
+                        rng,
+                        JoinerEvent::BlockHeaderWithMetadataFetcher(event),
+                    )
+                }
+                Message::GetResponse {
+                    tag: Tag::Trie,
+                    serialized_item,
+                } => {
+                    let trie = match bincode::deserialize(&serialized_item) {
+                        Ok(trie) => Box::new(trie),
+                        Err(err) => {
+                            error!("failed to decode trie from {}: {}", sender, err);
+                            return Effects::new();
+                        }
+                    };
+                    let event = fetcher::Event::GotRemotely {
+                        item: trie,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(effect_builder, rng, JoinerEvent::TrieFetcher(event))
+                }
                 Message::AddressGossiper(message) => {
                     let event = JoinerEvent::AddressGossiper(gossiper::Event::MessageReceived {
                         sender,
@@ -747,6 +909,45 @@ impl reactor::Reactor for Reactor {
                 rng,
                 JoinerEvent::BlockByHeightFetcher(request.into()),
             ),
+            JoinerEvent::BlockHeaderFetcher(event) => reactor::wrap_effects(
+                JoinerEvent::BlockHeaderFetcher,
+                self.block_header_by_hash_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
+            JoinerEvent::BlockHeaderWithMetadataFetcher(event) => reactor::wrap_effects(
+                JoinerEvent::BlockHeaderWithMetadataFetcher,
+                self.block_header_with_metadata_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
+            JoinerEvent::TrieFetcher(event) => reactor::wrap_effects(
+                JoinerEvent::TrieFetcher,
+                self.trie_fetcher.handle_event(effect_builder, rng, event),
+            ),
+            JoinerEvent::BlockHeaderFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                JoinerEvent::BlockHeaderFetcher(request.into()),
+            ),
+            JoinerEvent::BlockHeaderWithMetadataFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                JoinerEvent::BlockHeaderWithMetadataFetcher(request.into()),
+            ),
+            JoinerEvent::TrieFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                JoinerEvent::TrieFetcher(request.into()),
+            ),
+            JoinerEvent::LinearChainFastSync(event) => match self.linear_chain_fast_sync.as_mut() {
+                Some(linear_chain_fast_sync) => reactor::wrap_effects(
+                    JoinerEvent::LinearChainFastSync,
+                    linear_chain_fast_sync.handle_event(effect_builder, rng, event),
+                ),
+                None => {
+                    error!(%event, "linear chain fast sync event while not fast syncing");
+                    Effects::new()
+                }
+            },
             JoinerEvent::ContractRuntime(event) => reactor::wrap_effects(
                 JoinerEvent::ContractRuntime,
                 self.contract_runtime
@@ -758,6 +959,7 @@ impl reactor::Reactor for Reactor {
                 let LinearChainBlock {
                     block,
                     execution_results,
+                    mut contract_events,
                 } = *linear_chain_block;
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
@@ -770,6 +972,7 @@ impl reactor::Reactor for Reactor {
                             .iter()
                             .map(|(hash, (_header, results))| (*hash, results.clone()))
                             .collect(),
+                        contract_events: contract_events.clone(),
                     });
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
 
@@ -781,6 +984,7 @@ impl reactor::Reactor for Reactor {
                             deploy_header: Box::new(deploy_header),
                             block_hash,
                             execution_result: Box::new(execution_result),
+                            events: contract_events.remove(&deploy_hash).unwrap_or_default(),
                         },
                     );
                     effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
@@ -915,10 +1119,29 @@ impl reactor::Reactor for Reactor {
                 // no consensus, respond with empty map
                 responder.respond(BTreeMap::new()).ignore()
             }
+            JoinerEvent::ConsensusRequest(ConsensusRequest::Evidence { responder, .. }) => {
+                // no consensus, respond with no evidence
+                responder.respond(Vec::new()).ignore()
+            }
+            JoinerEvent::ConsensusRequest(ConsensusRequest::Snapshot(responder)) => {
+                // no consensus, respond with None
+                responder.respond(None).ignore()
+            }
         }
     }
 
     fn maybe_exit(&self) -> Option<ReactorExit> {
+        if let Some(linear_chain_fast_sync) = &self.linear_chain_fast_sync {
+            return if linear_chain_fast_sync.stopped_for_upgrade() {
+                Some(ReactorExit::ProcessShouldExit(ExitCode::Success))
+            } else if linear_chain_fast_sync.stopped_for_downgrade() {
+                Some(ReactorExit::ProcessShouldExit(ExitCode::DowngradeVersion))
+            } else if linear_chain_fast_sync.is_synced() {
+                Some(ReactorExit::ProcessShouldContinue)
+            } else {
+                None
+            };
+        }
         if self.linear_chain_sync.stopped_for_upgrade() {
             Some(ReactorExit::ProcessShouldExit(ExitCode::Success))
         } else if self.linear_chain_sync.stopped_for_downgrade() {
@@ -942,7 +1165,10 @@ impl Reactor {
     /// the network, closing all incoming and outgoing connections, and frees up the listening
     /// socket.
     pub(crate) async fn into_participating_config(self) -> Result<ParticipatingInitConfig, Error> {
-        let maybe_latest_block_header = self.linear_chain_sync.into_maybe_latest_block_header();
+        let maybe_latest_block_header = match self.linear_chain_fast_sync {
+            Some(linear_chain_fast_sync) => linear_chain_fast_sync.into_maybe_latest_block_header(),
+            None => self.linear_chain_sync.into_maybe_latest_block_header(),
+        };
         // Clean the state of the linear_chain_sync before shutting it down.
         #[cfg(not(feature = "fast-sync"))]
         linear_chain_sync::clean_linear_chain_state(
@@ -22,6 +22,8 @@ use reactor::ReactorEvent;
 use serde::Serialize;
 use tracing::{debug, error, trace, warn};
 
+use casper_hashing::Digest;
+
 #[cfg(test)]
 use crate::testing::network::NetworkedReactor;
 
@@ -30,7 +32,7 @@ use crate::{
         block_proposer::{self, BlockProposer},
         block_validator::{self, BlockValidator},
         chainspec_loader::{self, ChainspecLoader},
-        consensus::{self, EraSupervisor, HighwayProtocol},
+        consensus::{self, EraSupervisor},
         contract_runtime::{ContractRuntime, ContractRuntimeAnnouncement, ExecutionPreState},
         deploy_acceptor::{self, DeployAcceptor},
         event_stream_server::{self, EventStreamServer},
@@ -545,7 +547,6 @@ impl reactor::Reactor for Reactor {
             maybe_latest_block_header.as_ref(),
             maybe_next_activation_point,
             registry,
-            Box::new(HighwayProtocol::new_boxed),
         )?;
         effects.extend(reactor::wrap_effects(
             ParticipatingEvent::Consensus,
@@ -898,6 +899,38 @@ impl reactor::Reactor for Reactor {
                                 }
                             }
                         }
+                        Tag::Trie => {
+                            let trie_key: Digest = match bincode::deserialize(&serialized_id) {
+                                Ok(trie_key) => trie_key,
+                                Err(error) => {
+                                    error!(
+                                        "failed to decode {:?} from {}: {}",
+                                        serialized_id, sender, error
+                                    );
+                                    return Effects::new();
+                                }
+                            };
+                            return async move {
+                                match effect_builder.get_trie(trie_key).await {
+                                    Ok(Some(trie)) => match Message::new_get_response(&trie) {
+                                        Ok(message) => {
+                                            effect_builder.send_message(sender, message).await
+                                        }
+                                        Err(error) => {
+                                            error!("failed to create get-response: {}", error)
+                                        }
+                                    },
+                                    Ok(None) => {
+                                        debug!("failed to get trie {} for {}", trie_key, sender)
+                                    }
+                                    Err(error) => error!(
+                                        "failed to get trie {} for {}: {}",
+                                        trie_key, sender, error
+                                    ),
+                                }
+                            }
+                            .ignore();
+                        }
                     },
                     Message::GetResponse {
                         tag,
@@ -953,6 +986,10 @@ impl reactor::Reactor for Reactor {
                             );
                             return Effects::new();
                         }
+                        Tag::Trie => {
+                            error!("cannot handle get response for trie from {}", sender);
+                            return Effects::new();
+                        }
                     },
                     Message::FinalitySignature(fs) => ParticipatingEvent::LinearChain(
                         linear_chain::Event::FinalitySignatureReceived(fs, true),
@@ -1085,6 +1122,7 @@ impl reactor::Reactor for Reactor {
                 let LinearChainBlock {
                     block,
                     execution_results,
+                    mut contract_events,
                 } = *linear_chain_block;
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
@@ -1097,6 +1135,7 @@ impl reactor::Reactor for Reactor {
                             .iter()
                             .map(|(hash, (_header, results))| (*hash, results.clone()))
                             .collect(),
+                        contract_events: contract_events.clone(),
                     });
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
 
@@ -1108,6 +1147,7 @@ impl reactor::Reactor for Reactor {
                             deploy_header: Box::new(deploy_header),
                             block_hash,
                             execution_result: Box::new(execution_result),
+                            events: contract_events.remove(&deploy_hash).unwrap_or_default(),
                         },
                     );
                     effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
@@ -1179,6 +1219,20 @@ impl reactor::Reactor for Reactor {
                 );
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
+            ParticipatingEvent::BlockProposerAnnouncement(
+                BlockProposerAnnouncement::DeploySuperseded {
+                    superseded,
+                    superseded_by,
+                },
+            ) => {
+                let reactor_event = ParticipatingEvent::EventStreamServer(
+                    event_stream_server::Event::DeploySuperseded {
+                        superseded,
+                        superseded_by,
+                    },
+                );
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
             ParticipatingEvent::LinearChainAnnouncement(
                 LinearChainAnnouncement::NewFinalitySignature(fs),
             ) => {
@@ -1,4 +1,9 @@
-use std::{collections::BTreeMap, iter, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    iter,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::bail;
 use either::Either;
@@ -6,10 +11,12 @@ use log::info;
 use num::Zero;
 use num_rational::Ratio;
 use rand::Rng;
+use serde_json::{json, Value};
 use tempfile::TempDir;
 use tokio::time;
 
 use casper_execution_engine::core::engine_state::GetBidsRequest;
+use casper_hashing::Digest;
 use casper_types::{
     system::auction::{Bids, DelegationRate},
     EraId, Motes, ProtocolVersion, PublicKey, SecretKey, U512,
@@ -28,8 +35,8 @@ use crate::{
         self, filter_reactor::FilterReactor, network::Network, ConditionCheckReactor, TestRng,
     },
     types::{
-        chainspec::{AccountConfig, AccountsConfig, ValidatorConfig},
-        ActivationPoint, BlockHeader, Chainspec, ExitCode, Timestamp,
+        chainspec::{AccountConfig, AccountsConfig, ConsensusProtocolName, ValidatorConfig},
+        ActivationPoint, BlockHeader, Chainspec, Deploy, ExitCode, Timestamp,
     },
     utils::{External, Loadable, WithDir, RESOURCES_PATH},
     NodeRng,
@@ -40,6 +47,8 @@ struct TestChain {
     keys: Vec<Arc<SecretKey>>,
     storages: Vec<TempDir>,
     chainspec: Arc<Chainspec>,
+    // The local ports the nodes' JSON-RPC servers are bound to.
+    rpc_ports: Vec<u16>,
 }
 
 type Nodes = crate::testing::network::Nodes<FilterReactor<participating::Reactor>>;
@@ -111,6 +120,7 @@ impl TestChain {
             keys,
             chainspec: Arc::new(chainspec),
             storages: Vec::new(),
+            rpc_ports: Vec::new(),
         }
     }
 
@@ -145,6 +155,11 @@ impl TestChain {
         self.storages.push(temp_dir);
         cfg.storage = storage_cfg;
 
+        // Bind the JSON-RPC server to a known port, so tests can send requests to it.
+        let rpc_port = testing::unused_port_on_localhost();
+        cfg.rpc_server.address = format!("127.0.0.1:{}", rpc_port);
+        self.rpc_ports.push(rpc_port);
+
         cfg
     }
 
@@ -289,6 +304,28 @@ async fn run_participating_network() {
         .await;
 }
 
+#[tokio::test]
+async fn run_round_robin_network() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+
+    const NETWORK_SIZE: usize = 5;
+    let mut chain = TestChain::new(&mut rng, NETWORK_SIZE);
+    chain.chainspec_mut().core_config.consensus_protocol = ConsensusProtocolName::RoundRobin;
+
+    let mut net = chain
+        .create_initialized_network(&mut rng)
+        .await
+        .expect("network initialization failed");
+
+    net.settle_on(&mut rng, is_in_era(EraId::from(1)), Duration::from_secs(90))
+        .await;
+
+    net.settle_on(&mut rng, is_in_era(EraId::from(2)), Duration::from_secs(60))
+        .await;
+}
+
 #[tokio::test]
 async fn run_equivocator_network() {
     testing::init_logging();
@@ -486,3 +523,178 @@ async fn dont_upgrade_without_switch_block() {
         );
     }
 }
+
+/// Sends a JSON-RPC request to the node whose server is bound to `rpc_port`, and runs the network
+/// until the response has been received.
+async fn rpc_request(
+    net: &mut Network<FilterReactor<participating::Reactor>>,
+    rng: &mut NodeRng,
+    rpc_port: u16,
+    method: &str,
+    params: Value,
+) -> Value {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let maybe_response = Arc::new(Mutex::new(None));
+    let maybe_response_clone = Arc::clone(&maybe_response);
+    let _ = tokio::spawn(async move {
+        let response = reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{}/rpc", rpc_port))
+            .header("content-type", "application/json")
+            .body(serde_json::to_vec(&body).unwrap())
+            .send()
+            .await
+            .expect("should send RPC request");
+        let bytes = response.bytes().await.expect("should read RPC response");
+        let response: Value = serde_json::from_slice(&bytes).expect("should parse RPC response");
+        *maybe_response_clone.lock().unwrap() = Some(response);
+    });
+    net.settle_on(
+        rng,
+        |_| maybe_response.lock().unwrap().is_some(),
+        Duration::from_secs(30),
+    )
+    .await;
+    let response = maybe_response.lock().unwrap().take().unwrap();
+    response
+}
+
+#[tokio::test]
+async fn speculative_exec_rpc() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+
+    // Set up a network with a single validator, whose account can afford a transfer.
+    let alice_sk = Arc::new(SecretKey::random(&mut rng));
+    let alice_pk = PublicKey::from(&*alice_sk);
+    let keys: Vec<Arc<SecretKey>> = vec![Arc::clone(&alice_sk)];
+    let stakes: BTreeMap<PublicKey, U512> =
+        iter::once((alice_pk.clone(), U512::from(100))).collect();
+    let mut chain = TestChain::new_with_keys(&mut rng, keys, stakes);
+    let transfer_amount = U512::from(2_500_000_000u64);
+    let alice_account = AccountConfig::new(
+        alice_pk,
+        Motes::new(transfer_amount * 1000),
+        Some(ValidatorConfig::new(
+            Motes::new(U512::from(100)),
+            DelegationRate::zero(),
+        )),
+    );
+    chain.chainspec_mut().network_config.accounts_config =
+        AccountsConfig::new(vec![alice_account], vec![]);
+    let chain_name = chain.chainspec.network_config.name.clone();
+
+    let mut net = chain
+        .create_initialized_network(&mut rng)
+        .await
+        .expect("network initialization failed");
+    let rpc_port = chain.rpc_ports[0];
+
+    // Wait for the first block, so that there is a state root to execute against.
+    net.settle_on(
+        &mut rng,
+        |nodes| {
+            nodes.values().all(|runner| {
+                runner
+                    .participating()
+                    .storage()
+                    .read_block_header_and_finality_signatures_by_height(0)
+                    .expect("failed to read from storage")
+                    .is_some()
+            })
+        },
+        Duration::from_secs(90),
+    )
+    .await;
+
+    let deploy =
+        Deploy::random_native_transfer_from(&mut rng, &alice_sk, &chain_name, transfer_amount);
+    let deploy_hash = *deploy.id();
+    let mut unsigned_deploy =
+        Deploy::random_native_transfer_from(&mut rng, &alice_sk, &chain_name, transfer_amount);
+    unsigned_deploy.remove_approvals();
+
+    // A signed deploy is executed against the highest block's state.
+    let response = rpc_request(
+        &mut net,
+        &mut rng,
+        rpc_port,
+        "speculative_exec",
+        json!({ "deploy": deploy }),
+    )
+    .await;
+    let result = &response["result"];
+    assert_eq!(json!(deploy_hash), result["deploy_hash"], "{}", response);
+    assert!(
+        result["execution_result"]["Success"].is_object(),
+        "{}",
+        response
+    );
+
+    // An unsigned deploy is executed as if signed by its account's key.
+    let response = rpc_request(
+        &mut net,
+        &mut rng,
+        rpc_port,
+        "speculative_exec",
+        json!({ "deploy": unsigned_deploy }),
+    )
+    .await;
+    assert!(
+        response["result"]["execution_result"]["Success"].is_object(),
+        "{}",
+        response
+    );
+
+    // A deploy can be executed against the state root hash of a given block.
+    let state_root_hash = *net
+        .nodes()
+        .values()
+        .next()
+        .expect("should have a node")
+        .participating()
+        .storage()
+        .read_block_header_and_finality_signatures_by_height(0)
+        .expect("failed to read from storage")
+        .expect("should have the first block")
+        .block_header
+        .state_root_hash();
+    let response = rpc_request(
+        &mut net,
+        &mut rng,
+        rpc_port,
+        "speculative_exec",
+        json!({ "deploy": deploy, "state_root_hash": state_root_hash }),
+    )
+    .await;
+    let result = &response["result"];
+    assert_eq!(
+        json!(state_root_hash),
+        result["state_root_hash"],
+        "{}",
+        response
+    );
+    assert!(
+        result["execution_result"]["Success"].is_object(),
+        "{}",
+        response
+    );
+
+    // A state root hash which isn't that of a stored block is an error.
+    let unknown_state_root_hash = Digest::hash(b"unknown state root");
+    let response = rpc_request(
+        &mut net,
+        &mut rng,
+        rpc_port,
+        "speculative_exec",
+        json!({ "deploy": deploy, "state_root_hash": unknown_state_root_hash }),
+    )
+    .await;
+    // The error code for a missing block.
+    assert_eq!(json!(-32001), response["error"]["code"], "{}", response);
+}
@@ -1,6 +1,7 @@
 //! Common types used across multiple components.
 
 pub(crate) mod appendable_block;
+mod balance_change;
 mod block;
 pub mod chainspec;
 mod deploy;
@@ -19,8 +20,10 @@ use rand::{CryptoRng, RngCore};
 #[cfg(not(test))]
 use rand_chacha::ChaCha20Rng;
 
+pub(crate) use balance_change::balance_changes;
+pub use balance_change::{BalanceChange, BalanceChangeKind};
 pub use block::{
-    json_compatibility::{JsonBlock, JsonBlockHeader},
+    json_compatibility::{JsonBlock, JsonBlockHeader, JsonProof},
     Block, BlockBody, BlockHash, BlockHeader, BlockSignatures, FinalitySignature,
     HashingAlgorithmVersion, MerkleBlockBody, MerkleBlockBodyPart, MerkleLinkedListNode,
 };
@@ -29,8 +32,10 @@ pub(crate) use chainspec::ActivationPoint;
 pub use chainspec::Chainspec;
 pub use datasize::DataSize;
 pub use deploy::{
-    Approval, Deploy, DeployConfigurationFailure, DeployHash, DeployHeader, DeployMetadata,
-    DeployOrTransferHash, Error as DeployError, ExcessiveSizeError as ExcessiveSizeDeployError,
+    AccountDeploy, Approval, Deploy, DeployConfigurationFailure, DeployHash, DeployHeader,
+    DeployMetadata, DeployOrTransferHash, Error as DeployError,
+    ExcessiveSizeError as ExcessiveSizeDeployError, Supersession,
+    DEPLOY_SUPERSESSION_PROTOCOL_VERSION,
 };
 pub use error::BlockValidationError;
 pub use exit_code::ExitCode;
@@ -22,6 +22,10 @@ pub(crate) enum AddError {
     BlockSize,
     #[error("duplicate deploy")]
     Duplicate,
+    #[error("deploy nonce already used by its account in this block")]
+    DuplicateNonce,
+    #[error("deploy supersedes or is superseded by another deploy in this block")]
+    SupersessionConflict,
     #[error("payment amount could not be converted to gas")]
     InvalidGasAmount,
     #[error("deploy is not valid in this context")]
@@ -35,6 +39,12 @@ pub struct AppendableBlock {
     deploy_hashes: Vec<DeployHash>,
     transfer_hashes: Vec<DeployHash>,
     deploy_and_transfer_set: HashSet<DeployHash>,
+    /// The nonces used so far, together with the accounts using them.
+    account_nonces: HashSet<(PublicKey, u64)>,
+    /// The deploys and transfers added so far, together with their accounts.
+    account_deploys: HashSet<(PublicKey, DeployHash)>,
+    /// The deploys superseded by the ones added so far, together with the superseding accounts.
+    account_superseded: HashSet<(PublicKey, DeployHash)>,
     timestamp: Timestamp,
     #[data_size(skip)]
     total_gas: Gas,
@@ -50,6 +60,9 @@ impl AppendableBlock {
             transfer_hashes: Vec::new(),
             timestamp,
             deploy_and_transfer_set: HashSet::new(),
+            account_nonces: HashSet::new(),
+            account_deploys: HashSet::new(),
+            account_superseded: HashSet::new(),
             total_gas: Gas::zero(),
             total_size: 0,
         }
@@ -73,6 +86,12 @@ impl AppendableBlock {
         if self.deploy_and_transfer_set.contains(&hash) {
             return Err(AddError::Duplicate);
         }
+        if self.contains_nonce(deploy_info) {
+            return Err(AddError::DuplicateNonce);
+        }
+        if self.conflicts_with_supersession(hash, deploy_info) {
+            return Err(AddError::SupersessionConflict);
+        }
         if !deploy_info
             .header
             .is_valid(&self.deploy_config, self.timestamp)
@@ -84,6 +103,8 @@ impl AppendableBlock {
         }
         self.transfer_hashes.push(hash);
         self.deploy_and_transfer_set.insert(hash);
+        self.insert_nonce(deploy_info);
+        self.insert_supersession(hash, deploy_info);
         Ok(())
     }
 
@@ -100,6 +121,12 @@ impl AppendableBlock {
         if self.deploy_and_transfer_set.contains(&hash) {
             return Err(AddError::Duplicate);
         }
+        if self.contains_nonce(deploy_info) {
+            return Err(AddError::DuplicateNonce);
+        }
+        if self.conflicts_with_supersession(hash, deploy_info) {
+            return Err(AddError::SupersessionConflict);
+        }
         if !deploy_info
             .header
             .is_valid(&self.deploy_config, self.timestamp)
@@ -126,6 +153,8 @@ impl AppendableBlock {
         self.total_gas = new_total_gas;
         self.total_size = new_total_size;
         self.deploy_and_transfer_set.insert(hash);
+        self.insert_nonce(deploy_info);
+        self.insert_supersession(hash, deploy_info);
         Ok(())
     }
 
@@ -144,6 +173,43 @@ impl AppendableBlock {
         BlockPayload::new(deploy_hashes, transfer_hashes, accusations, random_bit)
     }
 
+    /// Returns `true` if the deploy's account already used the deploy's nonce in this block.
+    fn contains_nonce(&self, deploy_info: &DeployInfo) -> bool {
+        deploy_info.nonce.map_or(false, |nonce| {
+            self.account_nonces
+                .contains(&(deploy_info.header.account().clone(), nonce))
+        })
+    }
+
+    /// Records the deploy's nonce, if any, as used by its account.
+    fn insert_nonce(&mut self, deploy_info: &DeployInfo) {
+        if let Some(nonce) = deploy_info.nonce {
+            self.account_nonces
+                .insert((deploy_info.header.account().clone(), nonce));
+        }
+    }
+
+    /// Returns `true` if the deploy supersedes one from the same account already in this block, or
+    /// is itself superseded by one.
+    fn conflicts_with_supersession(&self, hash: DeployHash, deploy_info: &DeployInfo) -> bool {
+        let account = deploy_info.header.account();
+        self.account_superseded.contains(&(account.clone(), hash))
+            || deploy_info.supersession.map_or(false, |supersession| {
+                self.account_deploys
+                    .contains(&(account.clone(), *supersession.superseded()))
+            })
+    }
+
+    /// Records the deploy, and the deploy it supersedes if any, under the deploy's account.
+    fn insert_supersession(&mut self, hash: DeployHash, deploy_info: &DeployInfo) {
+        let account = deploy_info.header.account();
+        self.account_deploys.insert((account.clone(), hash));
+        if let Some(supersession) = deploy_info.supersession {
+            self.account_superseded
+                .insert((account.clone(), *supersession.superseded()));
+        }
+    }
+
     /// Returns `true` if the number of transfers is already the maximum allowed count, i.e. no
     /// more transfers can be added to this block.
     fn has_max_transfer_count(&self) -> bool {
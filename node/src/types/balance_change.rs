@@ -0,0 +1,59 @@
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{ExecutionResult, Key, Transform, URefAddr, U512};
+
+use crate::types::{BlockHash, DeployHash};
+
+/// The way in which executing a deploy changed a purse's balance.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum BalanceChangeKind {
+    /// The balance was overwritten with the given value.
+    Written(U512),
+    /// The given amount was added to the balance.
+    Added(U512),
+}
+
+/// A single change to a purse's balance caused by executing a deploy.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceChange {
+    /// The height of the block in which the deploy was executed.
+    pub block_height: u64,
+    /// The hash of the block in which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// The hash of the deploy which changed the balance.
+    pub deploy_hash: DeployHash,
+    /// The change made to the balance.
+    pub kind: BalanceChangeKind,
+}
+
+/// Extracts the purse balance changes recorded in the transforms of an execution result.
+///
+/// Only `Key::Balance` entries with a `WriteCLValue` holding a `U512` or an `AddUInt512` transform
+/// are considered; any other transforms are ignored.
+pub(crate) fn balance_changes(
+    execution_result: &ExecutionResult,
+) -> impl Iterator<Item = (URefAddr, BalanceChangeKind)> + '_ {
+    let effect = match execution_result {
+        ExecutionResult::Success { effect, .. } | ExecutionResult::Failure { effect, .. } => effect,
+    };
+    effect.transforms.iter().filter_map(|transform_entry| {
+        let purse_addr = match Key::from_formatted_str(&transform_entry.key) {
+            Ok(Key::Balance(purse_addr)) => purse_addr,
+            _ => return None,
+        };
+        let kind = match &transform_entry.transform {
+            Transform::WriteCLValue(cl_value) => {
+                BalanceChangeKind::Written(cl_value.clone().into_t().ok()?)
+            }
+            Transform::AddUInt512(amount) => BalanceChangeKind::Added(*amount),
+            _ => return None,
+        };
+        Some((purse_addr, kind))
+    })
+}
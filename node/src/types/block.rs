@@ -34,7 +34,7 @@ use casper_types::{
 use super::{Item, Tag, Timestamp};
 use crate::{
     components::consensus,
-    crypto::{self, AsymmetricKeyExt},
+    crypto::{self, AsymmetricKeyExt, Signer, SignerError},
     rpcs::docs::DocExample,
     types::{
         error::{BlockCreationError, BlockValidationError},
@@ -1682,6 +1682,15 @@ pub(crate) mod json_compatibility {
         next_era_validator_weights: Vec<ValidatorWeight>,
     }
 
+    impl JsonEraEnd {
+        /// Returns the validators of the next era, with their weights.
+        pub fn next_era_validator_weights(&self) -> impl Iterator<Item = (&PublicKey, &U512)> {
+            self.next_era_validator_weights
+                .iter()
+                .map(|validator_weight| (&validator_weight.validator, &validator_weight.weight))
+        }
+    }
+
     impl From<EraEnd> for JsonEraEnd {
         fn from(data: EraEnd) -> Self {
             let json_era_end = JsonEraReport::from(data.era_report);
@@ -1925,8 +1934,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -1936,14 +1944,36 @@ impl FinalitySignature {
         }
     }
 
+    /// Create an instance of `FinalitySignature`, signed by the given signer.
+    pub(crate) async fn create(
+        block_hash: BlockHash,
+        era_id: EraId,
+        signer: &dyn Signer,
+    ) -> Result<Self, SignerError> {
+        let signature = signer
+            .sign(&Self::bytes_to_sign(&block_hash, era_id))
+            .await?;
+        Ok(FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key: signer.public_key().clone(),
+        })
+    }
+
     /// Verifies whether the signature is correct.
     pub fn verify(&self) -> crypto::Result<()> {
-        // NOTE: This needs to be in sync with the `new` constructor.
-        let mut bytes = self.block_hash.inner().into_vec();
-        bytes.extend_from_slice(&self.era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
         crypto::verify(bytes, &self.signature, &self.public_key)
     }
 
+    /// Returns the data that is signed by a finality signature.
+    fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     #[cfg(test)]
     pub fn random_for_block(block_hash: BlockHash, era_id: u64) -> Self {
         let (sec_key, pub_key) = generate_ed25519_keypair();
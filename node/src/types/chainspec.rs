@@ -34,9 +34,14 @@ use casper_types::{
 pub(crate) use self::accounts_config::{AccountConfig, ValidatorConfig};
 pub use self::error::Error;
 pub(crate) use self::{
-    accounts_config::AccountsConfig, activation_point::ActivationPoint, core_config::CoreConfig,
-    deploy_config::DeployConfig, global_state_update::GlobalStateUpdate,
-    highway_config::HighwayConfig, network_config::NetworkConfig, protocol_config::ProtocolConfig,
+    accounts_config::AccountsConfig,
+    activation_point::ActivationPoint,
+    core_config::{ConsensusProtocolName, CoreConfig},
+    deploy_config::DeployConfig,
+    global_state_update::GlobalStateUpdate,
+    highway_config::HighwayConfig,
+    network_config::NetworkConfig,
+    protocol_config::ProtocolConfig,
 };
 #[cfg(test)]
 use crate::testing::TestRng;
@@ -135,6 +140,11 @@ impl ToBytes for Chainspec {
         buffer.extend(self.deploy_config.to_bytes()?);
         buffer.extend(self.wasm_config.to_bytes()?);
         buffer.extend(self.system_costs_config.to_bytes()?);
+        // Appended last and only if it isn't Highway, so that the hashes of existing chainspecs
+        // don't change.
+        if !self.core_config.consensus_protocol.is_highway() {
+            buffer.extend(self.core_config.consensus_protocol.to_bytes()?);
+        }
         Ok(buffer)
     }
 
@@ -146,6 +156,11 @@ impl ToBytes for Chainspec {
             + self.deploy_config.serialized_length()
             + self.wasm_config.serialized_length()
             + self.system_costs_config.serialized_length()
+            + if !self.core_config.consensus_protocol.is_highway() {
+                self.core_config.consensus_protocol.serialized_length()
+            } else {
+                0
+            }
     }
 }
 
@@ -153,11 +168,16 @@ impl FromBytes for Chainspec {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
         let (protocol_config, remainder) = ProtocolConfig::from_bytes(bytes)?;
         let (network_config, remainder) = NetworkConfig::from_bytes(remainder)?;
-        let (core_config, remainder) = CoreConfig::from_bytes(remainder)?;
+        let (mut core_config, remainder) = CoreConfig::from_bytes(remainder)?;
         let (highway_config, remainder) = HighwayConfig::from_bytes(remainder)?;
         let (deploy_config, remainder) = DeployConfig::from_bytes(remainder)?;
         let (wasm_config, remainder) = WasmConfig::from_bytes(remainder)?;
-        let (system_costs_config, remainder) = SystemConfig::from_bytes(remainder)?;
+        let (system_costs_config, mut remainder) = SystemConfig::from_bytes(remainder)?;
+        if !remainder.is_empty() {
+            let (consensus_protocol, rem) = ConsensusProtocolName::from_bytes(remainder)?;
+            core_config.consensus_protocol = consensus_protocol;
+            remainder = rem;
+        }
         let chainspec = Chainspec {
             protocol_config,
             network_config,
@@ -264,6 +284,8 @@ mod tests {
             remove_contract_user_group_urefs: HostFunction::new(131, [0, 1, 2, 3, 4, 5]),
             print: HostFunction::new(123, [0, 1]),
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
+            emit_event: HostFunctionCosts::default().emit_event,
+            dictionary_iter: HostFunctionCosts::default().dictionary_iter,
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
@@ -349,6 +371,10 @@ mod tests {
 
         assert_eq!(spec.core_config.era_duration, TimeDiff::from(180000));
         assert_eq!(spec.core_config.minimum_era_height, 9);
+        assert_eq!(
+            spec.core_config.consensus_protocol,
+            ConsensusProtocolName::Highway
+        );
         assert_eq!(
             spec.highway_config.finality_threshold_fraction,
             Ratio::new(2, 25)
@@ -389,6 +415,21 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&chainspec);
     }
 
+    #[test]
+    fn should_only_serialize_consensus_protocol_other_than_highway() {
+        let mut rng = crate::new_rng();
+        let mut chainspec = Chainspec::random(&mut rng);
+        chainspec.core_config.consensus_protocol = ConsensusProtocolName::Highway;
+        let highway_bytes = chainspec.to_bytes().unwrap();
+        bytesrepr::test_serialization_roundtrip(&chainspec);
+
+        chainspec.core_config.consensus_protocol = ConsensusProtocolName::RoundRobin;
+        let round_robin_bytes = chainspec.to_bytes().unwrap();
+        assert_eq!(round_robin_bytes.len(), highway_bytes.len() + 1);
+        assert_eq!(round_robin_bytes[..highway_bytes.len()], highway_bytes[..]);
+        bytesrepr::test_serialization_roundtrip(&chainspec);
+    }
+
     #[ignore = "We probably need to reconsider our approach here"]
     #[test]
     fn should_have_deterministic_chainspec_hash() {
@@ -30,6 +30,62 @@ pub struct CoreConfig {
     pub(crate) round_seigniorage_rate: Ratio<u64>,
     /// Maximum number of associated keys for a single account.
     pub(crate) max_associated_keys: u32,
+    /// The consensus protocol run by the validators in each era.
+    ///
+    /// Not included in the serialization of the core config itself: the chainspec appends it, and
+    /// only if it isn't Highway, so that the hashes of existing chainspecs don't change.
+    #[serde(default, skip_serializing_if = "ConsensusProtocolName::is_highway")]
+    pub(crate) consensus_protocol: ConsensusProtocolName,
+}
+
+/// The name of a consensus protocol the validators can run.
+#[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum ConsensusProtocolName {
+    /// The Highway protocol.
+    Highway,
+    /// A simple round-robin, single-leader BFT protocol, intended for small private networks.
+    RoundRobin,
+}
+
+impl ConsensusProtocolName {
+    /// Returns `true` if this is the Highway protocol.
+    pub(crate) fn is_highway(&self) -> bool {
+        *self == ConsensusProtocolName::Highway
+    }
+}
+
+impl Default for ConsensusProtocolName {
+    fn default() -> Self {
+        ConsensusProtocolName::Highway
+    }
+}
+
+const HIGHWAY_TAG: u8 = 0;
+const ROUND_ROBIN_TAG: u8 = 1;
+
+impl ToBytes for ConsensusProtocolName {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let tag = match self {
+            ConsensusProtocolName::Highway => HIGHWAY_TAG,
+            ConsensusProtocolName::RoundRobin => ROUND_ROBIN_TAG,
+        };
+        Ok(vec![tag])
+    }
+
+    fn serialized_length(&self) -> usize {
+        1
+    }
+}
+
+impl FromBytes for ConsensusProtocolName {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            HIGHWAY_TAG => Ok((ConsensusProtocolName::Highway, remainder)),
+            ROUND_ROBIN_TAG => Ok((ConsensusProtocolName::RoundRobin, remainder)),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -47,6 +103,11 @@ impl CoreConfig {
             rng.gen_range(1..1_000_000_000),
         );
         let max_associated_keys = rng.gen();
+        let consensus_protocol = if rng.gen() {
+            ConsensusProtocolName::Highway
+        } else {
+            ConsensusProtocolName::RoundRobin
+        };
 
         CoreConfig {
             era_duration,
@@ -57,6 +118,7 @@ impl CoreConfig {
             unbonding_delay,
             round_seigniorage_rate,
             max_associated_keys,
+            consensus_protocol,
         }
     }
 }
@@ -106,6 +168,7 @@ impl FromBytes for CoreConfig {
             unbonding_delay,
             round_seigniorage_rate,
             max_associated_keys,
+            consensus_protocol: ConsensusProtocolName::Highway,
         };
         Ok((config, remainder))
     }
@@ -118,7 +181,9 @@ mod tests {
     #[test]
     fn bytesrepr_roundtrip() {
         let mut rng = crate::new_rng();
-        let config = CoreConfig::random(&mut rng);
+        let mut config = CoreConfig::random(&mut rng);
+        // The consensus protocol is serialized as part of the chainspec.
+        config.consensus_protocol = ConsensusProtocolName::Highway;
         bytesrepr::test_serialization_roundtrip(&config);
     }
 
@@ -17,7 +17,11 @@ use once_cell::sync::Lazy;
 #[cfg(test)]
 use rand::{Rng, RngCore};
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{Error as SerdeError, SeqAccess, Visitor},
+    ser::{SerializeStruct, SerializeTuple},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use thiserror::Error;
 use tracing::{info, warn};
 
@@ -30,10 +34,11 @@ use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::bytesrepr::Bytes;
 use casper_types::{
-    bytesrepr::{self, FromBytes, ToBytes},
+    bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     runtime_args,
     system::standard_payment::ARG_AMOUNT,
-    AsymmetricType, ExecutionResult, Motes, PublicKey, RuntimeArgs, SecretKey, Signature, U512,
+    AsymmetricType, ExecutionResult, Motes, ProtocolVersion, PublicKey, RuntimeArgs, SecretKey,
+    Signature, U512,
 };
 
 use super::{BlockHash, Item, Tag, TimeDiff, Timestamp};
@@ -69,6 +74,8 @@ static DEPLOY: Lazy<Deploy> = Lazy::new(|| {
         body_hash,
         dependencies: vec![DeployHash::new(Digest::from([1u8; Digest::LENGTH]))],
         chain_name: String::from("casper-example"),
+        nonce: None,
+        supersession: None,
     };
     let serialized_header = serialize_header(&header);
     let hash = DeployHash::new(Digest::hash(&serialized_header));
@@ -209,6 +216,15 @@ pub enum DeployConfigurationFailure {
         /// The chainspec limit for max_associated_keys.
         max_associated_keys: u32,
     },
+
+    /// The deploy supersedes another one, but supersession is not supported by the current
+    /// protocol version.
+    #[error("deploy supersession is not supported by the current protocol version")]
+    SupersessionNotSupported,
+
+    /// The deploy has a nonce, but nonces are not supported by the current protocol version.
+    #[error("deploy nonces are not supported by the current protocol version")]
+    NonceNotSupported,
 }
 
 /// Error returned when a Deploy is too large.
@@ -237,6 +253,91 @@ pub enum Error {
     InvalidPayment,
 }
 
+/// The first protocol version under which a deploy header's supersession is honoured.
+pub const DEPLOY_SUPERSESSION_PROTOCOL_VERSION: ProtocolVersion =
+    ProtocolVersion::from_parts(1, 5, 0);
+
+const SUPERSESSION_REPLACE_TAG: u8 = 0;
+const SUPERSESSION_CANCEL_TAG: u8 = 1;
+
+/// A request carried by a deploy to supersede an earlier deploy from the same account which has
+/// not yet been included in a block.
+///
+/// A superseded deploy is never included in the chain together with or after a deploy superseding
+/// it, and a replacement is never included after the deploy it replaces.
+#[derive(
+    Copy,
+    Clone,
+    DataSize,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Debug,
+    JsonSchema,
+)]
+pub enum Supersession {
+    /// The earlier deploy is replaced by this one, which must offer a higher gas price.
+    Replace(DeployHash),
+    /// The earlier deploy is cancelled.  This deploy is still proposed and executed itself, so
+    /// that the account is charged for the cancellation.
+    Cancel(DeployHash),
+}
+
+impl Supersession {
+    /// Returns the hash of the earlier deploy which is superseded.
+    pub fn superseded(&self) -> &DeployHash {
+        match self {
+            Supersession::Replace(deploy_hash) | Supersession::Cancel(deploy_hash) => deploy_hash,
+        }
+    }
+}
+
+impl Display for Supersession {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Supersession::Replace(deploy_hash) => write!(formatter, "replaces {}", deploy_hash),
+            Supersession::Cancel(deploy_hash) => write!(formatter, "cancels {}", deploy_hash),
+        }
+    }
+}
+
+impl ToBytes for Supersession {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            Supersession::Replace(deploy_hash) => {
+                buffer.push(SUPERSESSION_REPLACE_TAG);
+                buffer.extend(deploy_hash.to_bytes()?);
+            }
+            Supersession::Cancel(deploy_hash) => {
+                buffer.push(SUPERSESSION_CANCEL_TAG);
+                buffer.extend(deploy_hash.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH + self.superseded().serialized_length()
+    }
+}
+
+impl FromBytes for Supersession {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        let (deploy_hash, remainder) = DeployHash::from_bytes(remainder)?;
+        match tag {
+            SUPERSESSION_REPLACE_TAG => Ok((Supersession::Replace(deploy_hash), remainder)),
+            SUPERSESSION_CANCEL_TAG => Ok((Supersession::Cancel(deploy_hash), remainder)),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
 impl From<FromHexError> for Error {
     fn from(error: FromHexError) -> Self {
         Error::DecodeFromJson(Box::new(error))
@@ -378,10 +479,25 @@ impl From<DeployOrTransferHash> for DeployHash {
     }
 }
 
+/// The first byte of the binary encodings of a versioned [`DeployHeader`].
+///
+/// A header which only has the fields of the original layout is encoded unversioned, starting with
+/// its account, so that its hash and the stored encoding of its deploy are unchanged.  A public key
+/// tag is never this value.
+const VERSIONED_DEPLOY_HEADER_TAG: u8 = u8::MAX;
+
+/// The version of the binary encodings of a [`DeployHeader`] which has a nonce or a supersession.
+const DEPLOY_HEADER_V2: u8 = 2;
+
+/// The number of fields in the original layout of a [`DeployHeader`].
+const UNVERSIONED_DEPLOY_HEADER_FIELD_COUNT: usize = 7;
+
+/// The number of elements in the non-human-readable serde encoding of a versioned
+/// [`DeployHeader`]: the version, the original fields, the nonce and the supersession.
+const VERSIONED_DEPLOY_HEADER_ELEMENT_COUNT: usize = UNVERSIONED_DEPLOY_HEADER_FIELD_COUNT + 3;
+
 /// The header portion of a [`Deploy`](struct.Deploy.html).
-#[derive(
-    Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, JsonSchema,
-)]
+#[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DeployHeader {
     account: PublicKey,
@@ -391,6 +507,10 @@ pub struct DeployHeader {
     body_hash: Digest,
     dependencies: Vec<DeployHash>,
     chain_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    supersession: Option<Supersession>,
 }
 
 impl DeployHeader {
@@ -435,6 +555,22 @@ impl DeployHeader {
         &self.chain_name
     }
 
+    /// The sequence number of this deploy among those sent by its account, if it has one.
+    pub fn nonce(&self) -> Option<u64> {
+        self.nonce
+    }
+
+    /// The earlier pending deploy from the same account which this deploy replaces or cancels, if
+    /// any.
+    pub fn supersession(&self) -> Option<Supersession> {
+        self.supersession
+    }
+
+    /// Returns `true` if this header has fields which the original header layout can't hold.
+    fn is_versioned(&self) -> bool {
+        self.nonce.is_some() || self.supersession.is_some()
+    }
+
     /// Determine if this deploy header has valid values based on a `DeployConfig` and timestamp.
     pub fn is_valid(&self, deploy_config: &DeployConfig, current_timestamp: Timestamp) -> bool {
         let ttl_valid = self.ttl() <= deploy_config.max_ttl;
@@ -455,6 +591,10 @@ impl DeployHeader {
 impl ToBytes for DeployHeader {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut buffer = bytesrepr::allocate_buffer(self)?;
+        if self.is_versioned() {
+            buffer.push(VERSIONED_DEPLOY_HEADER_TAG);
+            buffer.push(DEPLOY_HEADER_V2);
+        }
         buffer.extend(self.account.to_bytes()?);
         buffer.extend(self.timestamp.to_bytes()?);
         buffer.extend(self.ttl.to_bytes()?);
@@ -462,29 +602,59 @@ impl ToBytes for DeployHeader {
         buffer.extend(self.body_hash.to_bytes()?);
         buffer.extend(self.dependencies.to_bytes()?);
         buffer.extend(self.chain_name.to_bytes()?);
+        if self.is_versioned() {
+            buffer.extend(self.nonce.to_bytes()?);
+            buffer.extend(self.supersession.to_bytes()?);
+        }
         Ok(buffer)
     }
 
     fn serialized_length(&self) -> usize {
-        self.account.serialized_length()
+        let unversioned_length = self.account.serialized_length()
             + self.timestamp.serialized_length()
             + self.ttl.serialized_length()
             + self.gas_price.serialized_length()
             + self.body_hash.serialized_length()
             + self.dependencies.serialized_length()
-            + self.chain_name.serialized_length()
+            + self.chain_name.serialized_length();
+        if self.is_versioned() {
+            VERSIONED_DEPLOY_HEADER_TAG.serialized_length()
+                + DEPLOY_HEADER_V2.serialized_length()
+                + unversioned_length
+                + self.nonce.serialized_length()
+                + self.supersession.serialized_length()
+        } else {
+            unversioned_length
+        }
     }
 }
 
 impl FromBytes for DeployHeader {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
-        let (account, remainder) = PublicKey::from_bytes(bytes)?;
+        let (is_versioned, remainder) = match bytes.split_first() {
+            Some((&VERSIONED_DEPLOY_HEADER_TAG, remainder)) => {
+                let (version, remainder) = u8::from_bytes(remainder)?;
+                if version != DEPLOY_HEADER_V2 {
+                    return Err(bytesrepr::Error::Formatting);
+                }
+                (true, remainder)
+            }
+            _ => (false, bytes),
+        };
+        let (account, remainder) = PublicKey::from_bytes(remainder)?;
         let (timestamp, remainder) = Timestamp::from_bytes(remainder)?;
         let (ttl, remainder) = TimeDiff::from_bytes(remainder)?;
         let (gas_price, remainder) = u64::from_bytes(remainder)?;
         let (body_hash, remainder) = Digest::from_bytes(remainder)?;
         let (dependencies, remainder) = Vec::<DeployHash>::from_bytes(remainder)?;
         let (chain_name, remainder) = String::from_bytes(remainder)?;
+        let (nonce, supersession, remainder) = if is_versioned {
+            let (nonce, remainder) = Option::<u64>::from_bytes(remainder)?;
+            let (supersession, remainder) = Option::<Supersession>::from_bytes(remainder)?;
+            (nonce, supersession, remainder)
+        } else {
+            (None, None, remainder)
+        };
         let deploy_header = DeployHeader {
             account,
             timestamp,
@@ -493,11 +663,188 @@ impl FromBytes for DeployHeader {
             body_hash,
             dependencies,
             chain_name,
+            nonce,
+            supersession,
         };
+        // Each header has a single encoding: a versioned one must need the versioned layout.
+        if is_versioned != deploy_header.is_versioned() {
+            return Err(bytesrepr::Error::Formatting);
+        }
         Ok((deploy_header, remainder))
     }
 }
 
+/// The first element of the non-human-readable serde encoding of a [`DeployHeader`].
+///
+/// The first three variants match the encoding of the account of an unversioned header, as
+/// produced by `PublicKey`'s serde implementation.
+#[derive(Serialize, Deserialize)]
+enum BinaryDeployHeaderLead {
+    System,
+    Ed25519(Vec<u8>),
+    Secp256k1(Vec<u8>),
+    Versioned(u8),
+}
+
+/// The human-readable serde encoding of a [`DeployHeader`], used for deserialization.
+#[derive(Deserialize)]
+#[serde(rename = "DeployHeader", deny_unknown_fields)]
+struct HumanReadableDeployHeader {
+    account: PublicKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    gas_price: u64,
+    body_hash: Digest,
+    dependencies: Vec<DeployHash>,
+    chain_name: String,
+    #[serde(default)]
+    nonce: Option<u64>,
+    #[serde(default)]
+    supersession: Option<Supersession>,
+}
+
+impl From<HumanReadableDeployHeader> for DeployHeader {
+    fn from(header: HumanReadableDeployHeader) -> Self {
+        DeployHeader {
+            account: header.account,
+            timestamp: header.timestamp,
+            ttl: header.ttl,
+            gas_price: header.gas_price,
+            body_hash: header.body_hash,
+            dependencies: header.dependencies,
+            chain_name: header.chain_name,
+            nonce: header.nonce,
+            supersession: header.supersession,
+        }
+    }
+}
+
+impl Serialize for DeployHeader {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            // Some formats, e.g. MessagePack, encode struct fields by position, so the nonce can
+            // only be skipped if no later field is present.
+            let include_nonce = self.is_versioned();
+            let field_count = UNVERSIONED_DEPLOY_HEADER_FIELD_COUNT
+                + usize::from(include_nonce)
+                + self.supersession.iter().count();
+            let mut state = serializer.serialize_struct("DeployHeader", field_count)?;
+            state.serialize_field("account", &self.account)?;
+            state.serialize_field("timestamp", &self.timestamp)?;
+            state.serialize_field("ttl", &self.ttl)?;
+            state.serialize_field("gas_price", &self.gas_price)?;
+            state.serialize_field("body_hash", &self.body_hash)?;
+            state.serialize_field("dependencies", &self.dependencies)?;
+            state.serialize_field("chain_name", &self.chain_name)?;
+            if include_nonce {
+                state.serialize_field("nonce", &self.nonce)?;
+            }
+            if let Some(supersession) = &self.supersession {
+                state.serialize_field("supersession", supersession)?;
+            }
+            return state.end();
+        }
+
+        if !self.is_versioned() {
+            let mut state = serializer.serialize_tuple(UNVERSIONED_DEPLOY_HEADER_FIELD_COUNT)?;
+            self.serialize_unversioned_fields(&mut state)?;
+            return state.end();
+        }
+        let mut state = serializer.serialize_tuple(VERSIONED_DEPLOY_HEADER_ELEMENT_COUNT)?;
+        state.serialize_element(&BinaryDeployHeaderLead::Versioned(DEPLOY_HEADER_V2))?;
+        self.serialize_unversioned_fields(&mut state)?;
+        state.serialize_element(&self.nonce)?;
+        state.serialize_element(&self.supersession)?;
+        state.end()
+    }
+}
+
+impl DeployHeader {
+    fn serialize_unversioned_fields<S: SerializeTuple>(
+        &self,
+        state: &mut S,
+    ) -> Result<(), S::Error> {
+        state.serialize_element(&self.account)?;
+        state.serialize_element(&self.timestamp)?;
+        state.serialize_element(&self.ttl)?;
+        state.serialize_element(&self.gas_price)?;
+        state.serialize_element(&self.body_hash)?;
+        state.serialize_element(&self.dependencies)?;
+        state.serialize_element(&self.chain_name)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeployHeader {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return HumanReadableDeployHeader::deserialize(deserializer).map(DeployHeader::from);
+        }
+        // Only as many elements as the lead calls for are read, so an unversioned header is read
+        // from its original encoding.
+        deserializer.deserialize_tuple(
+            VERSIONED_DEPLOY_HEADER_ELEMENT_COUNT,
+            BinaryDeployHeaderVisitor,
+        )
+    }
+}
+
+struct BinaryDeployHeaderVisitor;
+
+impl<'de> Visitor<'de> for BinaryDeployHeaderVisitor {
+    type Value = DeployHeader;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a deploy header")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<DeployHeader, A::Error> {
+        fn next<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(seq: &mut A) -> Result<T, A::Error> {
+            seq.next_element()?
+                .ok_or_else(|| A::Error::custom("deploy header is missing a field"))
+        }
+
+        let (is_versioned, account) = match next(&mut seq)? {
+            BinaryDeployHeaderLead::System => (false, PublicKey::System),
+            BinaryDeployHeaderLead::Ed25519(bytes) => (
+                false,
+                PublicKey::ed25519_from_bytes(bytes).map_err(A::Error::custom)?,
+            ),
+            BinaryDeployHeaderLead::Secp256k1(bytes) => (
+                false,
+                PublicKey::secp256k1_from_bytes(bytes).map_err(A::Error::custom)?,
+            ),
+            BinaryDeployHeaderLead::Versioned(DEPLOY_HEADER_V2) => (true, next(&mut seq)?),
+            BinaryDeployHeaderLead::Versioned(version) => {
+                return Err(A::Error::custom(format!(
+                    "unknown deploy header version {}",
+                    version
+                )))
+            }
+        };
+        let mut deploy_header = DeployHeader {
+            account,
+            timestamp: next(&mut seq)?,
+            ttl: next(&mut seq)?,
+            gas_price: next(&mut seq)?,
+            body_hash: next(&mut seq)?,
+            dependencies: next(&mut seq)?,
+            chain_name: next(&mut seq)?,
+            nonce: None,
+            supersession: None,
+        };
+        if is_versioned {
+            deploy_header.nonce = next(&mut seq)?;
+            deploy_header.supersession = next(&mut seq)?;
+            if !deploy_header.is_versioned() {
+                return Err(A::Error::custom(
+                    "versioned deploy header has no versioned fields",
+                ));
+            }
+        }
+        Ok(deploy_header)
+    }
+}
+
 impl Display for DeployHeader {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(
@@ -534,6 +881,11 @@ impl Approval {
     pub fn signature(&self) -> &Signature {
         &self.signature
     }
+
+    /// Verifies that the approval signature is a valid signature of `deploy_hash` by the signer.
+    pub fn verify(&self, deploy_hash: &DeployHash) -> Result<(), crypto::Error> {
+        crypto::verify(deploy_hash, &self.signature, &self.signer)
+    }
 }
 
 impl Display for Approval {
@@ -592,6 +944,8 @@ impl Deploy {
         session: ExecutableDeployItem,
         secret_key: &SecretKey,
         account: Option<PublicKey>,
+        nonce: Option<u64>,
+        supersession: Option<Supersession>,
     ) -> Deploy {
         let serialized_body = serialize_body(&payment, &session);
         let body_hash = Digest::hash(&serialized_body);
@@ -608,6 +962,8 @@ impl Deploy {
             body_hash,
             dependencies,
             chain_name,
+            nonce,
+            supersession,
         };
         let serialized_header = serialize_header(&header);
         let hash = DeployHash::new(Digest::hash(&serialized_header));
@@ -633,6 +989,18 @@ impl Deploy {
         self.approvals.push(approval);
     }
 
+    /// Adds the given approvals to this deploy, skipping any which are already present.
+    ///
+    /// The approvals are not checked here; call `is_valid()` to verify them.
+    pub fn add_approvals<I: IntoIterator<Item = Approval>>(&mut self, approvals: I) {
+        for approval in approvals {
+            if !self.approvals.contains(&approval) {
+                self.approvals.push(approval);
+            }
+        }
+        self.is_valid = None;
+    }
+
     /// Returns the `DeployHash` identifying this `Deploy`.
     pub fn id(&self) -> &DeployHash {
         &self.hash
@@ -700,6 +1068,8 @@ impl Deploy {
             header,
             payment_amount,
             size,
+            supersession: self.header.supersession(),
+            nonce: self.header.nonce(),
         })
     }
 
@@ -927,6 +1297,8 @@ impl Deploy {
             session,
             &secret_key,
             None,
+            None,
+            None,
         )
     }
 
@@ -964,9 +1336,55 @@ impl Deploy {
             session,
             &secret_key,
             None,
+            None,
+            None,
+        )
+    }
+
+    /// Creates a native transfer of `amount` motes from the account of `secret_key` to a random
+    /// account, for the chain with the given name.
+    pub(crate) fn random_native_transfer_from(
+        rng: &mut TestRng,
+        secret_key: &SecretKey,
+        chain_name: &str,
+        amount: U512,
+    ) -> Self {
+        let transfer_args = runtime_args! {
+            "amount" => amount,
+            "target" => PublicKey::random(rng).to_account_hash(),
+            "id" => Some(rng.gen::<u64>()),
+        };
+        let payment_args = runtime_args! {
+            "amount" => U512::from(10),
+        };
+        let session = ExecutableDeployItem::Transfer {
+            args: transfer_args,
+        };
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: payment_args,
+        };
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from_seconds(60),
+            1,
+            vec![],
+            chain_name.to_string(),
+            payment,
+            session,
+            secret_key,
+            None,
+            None,
+            None,
         )
     }
 
+    /// Removes all approvals, turning `self` into an unsigned deploy.
+    pub(crate) fn remove_approvals(&mut self) {
+        self.approvals.clear();
+        self.is_valid = None;
+    }
+
     pub(crate) fn random_without_payment_amount(rng: &mut TestRng) -> Self {
         let payment = ExecutableDeployItem::ModuleBytes {
             module_bytes: Bytes::new(),
@@ -986,6 +1404,70 @@ impl Deploy {
         Self::random_transfer_with_payment(rng, payment)
     }
 
+    pub(crate) fn random_with_nonce(rng: &mut TestRng, nonce: u64) -> Self {
+        let deploy = Self::random_valid_native_transfer(rng);
+        let secret_key = SecretKey::random(rng);
+
+        Deploy::new(
+            deploy.header.timestamp,
+            deploy.header.ttl,
+            deploy.header.gas_price,
+            deploy.header.dependencies,
+            deploy.header.chain_name,
+            deploy.payment,
+            deploy.session,
+            &secret_key,
+            None,
+            Some(nonce),
+            None,
+        )
+    }
+
+    pub(crate) fn random_with_supersession(rng: &mut TestRng) -> Self {
+        let deploy = Self::random_valid_native_transfer(rng);
+        let secret_key = SecretKey::random(rng);
+        let supersession = Supersession::Cancel(DeployHash::random(rng));
+
+        Deploy::new(
+            deploy.header.timestamp,
+            deploy.header.ttl,
+            deploy.header.gas_price,
+            deploy.header.dependencies,
+            deploy.header.chain_name,
+            deploy.payment,
+            deploy.session,
+            &secret_key,
+            None,
+            None,
+            Some(supersession),
+        )
+    }
+
+    /// Creates a deploy from the account of `secret_key` with the given gas price and
+    /// supersession.
+    pub(crate) fn random_with_account_and_supersession(
+        rng: &mut TestRng,
+        secret_key: &SecretKey,
+        gas_price: u64,
+        supersession: Option<Supersession>,
+    ) -> Self {
+        let deploy = Self::random_valid_native_transfer(rng);
+
+        Deploy::new(
+            deploy.header.timestamp,
+            deploy.header.ttl,
+            gas_price,
+            deploy.header.dependencies,
+            deploy.header.chain_name,
+            deploy.payment,
+            deploy.session,
+            secret_key,
+            None,
+            None,
+            supersession,
+        )
+    }
+
     pub(crate) fn random_with_valid_custom_payment_contract_by_name(rng: &mut TestRng) -> Self {
         let payment = ExecutableDeployItem::StoredContractByName {
             name: "Test".to_string(),
@@ -1173,6 +1655,8 @@ impl Deploy {
             deploy.session,
             &secret_key,
             None,
+            None,
+            None,
         )
     }
 
@@ -1190,6 +1674,8 @@ impl Deploy {
             session,
             &secret_key,
             None,
+            None,
+            None,
         )
     }
 }
@@ -1240,7 +1726,7 @@ fn validate_deploy(deploy: &Deploy) -> Result<(), DeployConfigurationFailure> {
     }
 
     for (index, approval) in deploy.approvals.iter().enumerate() {
-        if let Err(error) = crypto::verify(&deploy.hash, &approval.signature, &approval.signer) {
+        if let Err(error) = approval.verify(&deploy.hash) {
             warn!(?deploy, "failed to verify approval {}: {}", index, error);
             return Err(DeployConfigurationFailure::InvalidApproval {
                 index,
@@ -1293,6 +1779,7 @@ impl From<Deploy> for DeployItem {
             deploy.header().gas_price(),
             authorization_keys,
             casper_types::DeployHash::new(deploy.id().inner().value()),
+            deploy.header().nonce(),
         )
     }
 }
@@ -1308,6 +1795,18 @@ pub struct DeployMetadata {
     pub execution_results: HashMap<BlockHash, ExecutionResult>,
 }
 
+/// A deploy sent by an account, along with the block in which it was included.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AccountDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The hash of the block containing the deploy.
+    pub block_hash: BlockHash,
+    /// The height of the block containing the deploy.
+    pub block_height: u64,
+}
+
 impl ToBytes for Deploy {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut buffer = bytesrepr::allocate_buffer(self)?;
@@ -1388,6 +1887,114 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&deploy);
     }
 
+    /// The layout of a `DeployHeader` before versioned fields were introduced.
+    #[derive(Serialize, Deserialize)]
+    struct LegacyDeployHeader {
+        account: PublicKey,
+        timestamp: Timestamp,
+        ttl: TimeDiff,
+        gas_price: u64,
+        body_hash: Digest,
+        dependencies: Vec<DeployHash>,
+        chain_name: String,
+    }
+
+    impl From<&DeployHeader> for LegacyDeployHeader {
+        fn from(header: &DeployHeader) -> Self {
+            LegacyDeployHeader {
+                account: header.account.clone(),
+                timestamp: header.timestamp,
+                ttl: header.ttl,
+                gas_price: header.gas_price,
+                body_hash: header.body_hash,
+                dependencies: header.dependencies.clone(),
+                chain_name: header.chain_name.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn unversioned_header_encoding_unchanged() {
+        let mut rng = crate::new_rng();
+        let header = Deploy::random(&mut rng).header().clone();
+        assert!(header.nonce().is_none());
+        let legacy_header = LegacyDeployHeader::from(&header);
+
+        let legacy_bytes = bincode::serialize(&legacy_header).unwrap();
+        assert_eq!(bincode::serialize(&header).unwrap(), legacy_bytes);
+        assert_eq!(
+            bincode::deserialize::<DeployHeader>(&legacy_bytes).unwrap(),
+            header
+        );
+
+        let legacy_bytes = rmp_serde::to_vec(&legacy_header).unwrap();
+        assert_eq!(rmp_serde::to_vec(&header).unwrap(), legacy_bytes);
+        assert_eq!(
+            rmp_serde::from_read_ref::<_, DeployHeader>(&legacy_bytes).unwrap(),
+            header
+        );
+
+        let legacy_json = serde_json::to_value(&legacy_header).unwrap();
+        assert_eq!(serde_json::to_value(&header).unwrap(), legacy_json);
+
+        let mut legacy_bytes = vec![];
+        legacy_bytes.extend(header.account.to_bytes().unwrap());
+        legacy_bytes.extend(header.timestamp.to_bytes().unwrap());
+        legacy_bytes.extend(header.ttl.to_bytes().unwrap());
+        legacy_bytes.extend(header.gas_price.to_bytes().unwrap());
+        legacy_bytes.extend(header.body_hash.to_bytes().unwrap());
+        legacy_bytes.extend(header.dependencies.to_bytes().unwrap());
+        legacy_bytes.extend(header.chain_name.to_bytes().unwrap());
+        assert_eq!(header.to_bytes().unwrap(), legacy_bytes);
+    }
+
+    #[test]
+    fn versioned_header_roundtrip() {
+        let mut rng = crate::new_rng();
+        let nonce = rng.gen();
+        let deploy = Deploy::random_with_nonce(&mut rng, nonce);
+        let header = deploy.header();
+        assert!(header.nonce().is_some());
+
+        bytesrepr::test_serialization_roundtrip(header);
+        bytesrepr::test_serialization_roundtrip(&deploy);
+
+        let serialized = bincode::serialize(&deploy).unwrap();
+        assert_eq!(deploy, bincode::deserialize(&serialized).unwrap());
+
+        let serialized = rmp_serde::to_vec(&deploy).unwrap();
+        assert_eq!(deploy, rmp_serde::from_read_ref(&serialized).unwrap());
+
+        let json_string = serde_json::to_string_pretty(&deploy).unwrap();
+        assert_eq!(deploy, serde_json::from_str(&json_string).unwrap());
+    }
+
+    #[test]
+    fn should_reject_non_canonical_header_encodings() {
+        let mut rng = crate::new_rng();
+        let header = Deploy::random(&mut rng).header().clone();
+
+        // A versioned encoding of a header without versioned fields.
+        let mut bytes = vec![VERSIONED_DEPLOY_HEADER_TAG, DEPLOY_HEADER_V2];
+        bytes.extend(header.to_bytes().unwrap());
+        bytes.extend(Option::<u64>::None.to_bytes().unwrap());
+        bytes.extend(Option::<Supersession>::None.to_bytes().unwrap());
+        assert_eq!(
+            bytesrepr::deserialize::<DeployHeader>(bytes).unwrap_err(),
+            bytesrepr::Error::Formatting
+        );
+
+        // An unknown version.
+        let mut bytes = vec![VERSIONED_DEPLOY_HEADER_TAG, DEPLOY_HEADER_V2 + 1];
+        bytes.extend(header.to_bytes().unwrap());
+        bytes.extend(Some(1u64).to_bytes().unwrap());
+        bytes.extend(Option::<Supersession>::None.to_bytes().unwrap());
+        assert_eq!(
+            bytesrepr::deserialize::<DeployHeader>(bytes).unwrap_err(),
+            bytesrepr::Error::Formatting
+        );
+    }
+
     fn create_deploy(
         rng: &mut TestRng,
         ttl: TimeDiff,
@@ -1420,6 +2027,8 @@ mod tests {
             },
             &secret_key,
             None,
+            None,
+            None,
         )
     }
 
@@ -1788,6 +2397,8 @@ mod tests {
             },
             &secret_key,
             None,
+            None,
+            None,
         );
 
         assert_eq!(
@@ -1904,4 +2515,42 @@ mod tests {
             deploy.is_config_compliant(chain_name, &deploy_config, DEFAULT_MAX_ASSOCIATED_KEYS)
         )
     }
+
+    #[test]
+    fn supersession_carried_in_header() {
+        let mut rng = crate::new_rng();
+        let superseded = DeployHash::random(&mut rng);
+        for supersession in [
+            Supersession::Replace(superseded),
+            Supersession::Cancel(superseded),
+        ] {
+            bytesrepr::test_serialization_roundtrip(&supersession);
+
+            let deploy = Deploy::random_valid_native_transfer(&mut rng);
+            let deploy = Deploy::new(
+                deploy.header.timestamp,
+                deploy.header.ttl,
+                deploy.header.gas_price,
+                deploy.header.dependencies,
+                deploy.header.chain_name,
+                deploy.payment,
+                deploy.session,
+                &SecretKey::random(&mut rng),
+                None,
+                None,
+                Some(supersession),
+            );
+            assert_eq!(deploy.header().supersession(), Some(supersession));
+            let deploy_info = deploy.deploy_info().expect("should get deploy info");
+            assert_eq!(deploy_info.supersession, Some(supersession));
+
+            bytesrepr::test_serialization_roundtrip(&deploy);
+            let serialized = bincode::serialize(&deploy).unwrap();
+            assert_eq!(deploy, bincode::deserialize(&serialized).unwrap());
+            let serialized = rmp_serde::to_vec(&deploy).unwrap();
+            assert_eq!(deploy, rmp_serde::from_read_ref(&serialized).unwrap());
+            let json_string = serde_json::to_string_pretty(&deploy).unwrap();
+            assert_eq!(deploy, serde_json::from_str(&json_string).unwrap());
+        }
+    }
 }
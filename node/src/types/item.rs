@@ -9,7 +9,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use casper_execution_engine::storage::trie::Trie;
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::ToBytes, Key, StoredValue};
+use casper_types::{bytesrepr::ToBytes, Key, ProtocolVersion, StoredValue};
 
 use crate::types::{BlockHash, BlockHeader, BlockHeaderWithMetadata};
 
@@ -42,6 +42,26 @@ pub enum Tag {
     BlockHeaderByHash,
     /// A block header and its finality signatures requested by its height in the linear chain.
     BlockHeaderAndFinalitySignaturesByHeight,
+    /// A trie node of the global state requested by its hash.
+    Trie,
+}
+
+/// The protocol version from which on nodes understand requests with `Tag::Trie`.
+pub(crate) const TRIE_TAG_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::from_parts(1, 5, 0);
+
+impl Tag {
+    /// Returns the lowest protocol version of peers that can be asked for items with this tag.
+    pub(crate) fn min_protocol_version(self) -> ProtocolVersion {
+        match self {
+            Tag::Trie => TRIE_TAG_PROTOCOL_VERSION,
+            Tag::Deploy
+            | Tag::Block
+            | Tag::GossipedAddress
+            | Tag::BlockByHeight
+            | Tag::BlockHeaderByHash
+            | Tag::BlockHeaderAndFinalitySignaturesByHeight => ProtocolVersion::V1_0_0,
+        }
+    }
 }
 
 /// A trait which allows an implementing type to be used by the gossiper and fetcher components, and
@@ -61,7 +81,7 @@ pub trait Item: Clone + Serialize + DeserializeOwned + Send + Sync + Debug + Dis
 
 impl Item for Trie<Key, StoredValue> {
     type Id = Digest;
-    const TAG: Tag = Tag::Deploy;
+    const TAG: Tag = Tag::Trie;
     const ID_IS_COMPLETE_ITEM: bool = false;
 
     fn id(&self) -> Self::Id {
@@ -64,6 +64,21 @@ pub struct Account {
     action_thresholds: ActionThresholds,
 }
 
+impl Account {
+    /// Returns the associated keys of this account along with their weights.
+    pub fn associated_keys(&self) -> impl Iterator<Item = (&AccountHash, u8)> {
+        self.associated_keys
+            .iter()
+            .map(|associated_key| (&associated_key.account_hash, associated_key.weight))
+    }
+
+    /// Returns the weight which the signers of a deploy must meet or exceed for it to be executed
+    /// under this account.
+    pub fn deployment_threshold(&self) -> u8 {
+        self.action_thresholds.deployment
+    }
+}
+
 impl From<&ExecutionEngineAccount> for Account {
     fn from(ee_account: &ExecutionEngineAccount) -> Self {
         Account {
@@ -19,7 +19,7 @@ use openssl::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use casper_types::SecretKey;
+use casper_types::{PublicKey, SecretKey};
 
 use super::{read_file, ReadFileError};
 use crate::{crypto, crypto::AsymmetricKeyExt, tls};
@@ -154,6 +154,14 @@ impl Loadable for Arc<SecretKey> {
     }
 }
 
+impl Loadable for PublicKey {
+    type Error = crypto::Error;
+
+    fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Self::Error> {
+        AsymmetricKeyExt::from_file(path)
+    }
+}
+
 impl Loadable for Vec<u8> {
     type Error = ReadFileError;
 
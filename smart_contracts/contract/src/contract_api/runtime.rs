@@ -6,11 +6,12 @@ use core::mem::MaybeUninit;
 use casper_types::{
     account::AccountHash,
     api_error,
-    bytesrepr::{self, FromBytes},
+    bytesrepr::{self, FromBytes, ToBytes},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
     ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
-    RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
+    RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, EVENT_TOPIC_MAX_LENGTH,
+    PHASE_SERIALIZED_LENGTH,
 };
 
 use crate::{contract_api, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
@@ -296,6 +297,28 @@ pub fn list_named_keys() -> NamedKeys {
     bytesrepr::deserialize(bytes).unwrap_or_revert()
 }
 
+/// Emits an event with the given `topic` and `payload`.
+///
+/// The event is recorded in the execution results of the deploy, along with the key of the
+/// account or contract which emitted it.  The topic must be non-empty and no longer than
+/// [`EVENT_TOPIC_MAX_LENGTH`] bytes.
+pub fn emit_event<T: CLTyped + ToBytes>(topic: &str, payload: T) {
+    if topic.len() > EVENT_TOPIC_MAX_LENGTH {
+        revert(ApiError::EventTopicExceedsLength)
+    }
+
+    let cl_value = CLValue::from_t(payload).unwrap_or_revert();
+    let (payload_ptr, payload_size, _bytes) = contract_api::to_ptr(cl_value);
+
+    let result = unsafe {
+        let ret =
+            ext_ffi::casper_emit_event(topic.as_ptr(), topic.len(), payload_ptr, payload_size);
+        api_error::result_from(ret)
+    };
+
+    result.unwrap_or_revert()
+}
+
 /// Validates uref against named keys.
 pub fn is_valid_uref(uref: URef) -> bool {
     let (uref_ptr, uref_size, _bytes) = contract_api::to_ptr(uref);
@@ -422,3 +422,37 @@ pub fn dictionary_put<V: CLTyped + ToBytes>(
 
     result.unwrap_or_revert()
 }
+
+/// Returns up to `limit` item keys of the dictionary accessed by `dictionary_seed_uref` in
+/// ascending order, starting after `start_after` if given.
+///
+/// Pass the last returned item key as `start_after` to read the next page.  Reverts with
+/// [`ApiError::DictionaryNotEnumerable`] if the dictionary was created without an index of its
+/// item keys.
+pub fn dictionary_iter(
+    dictionary_seed_uref: URef,
+    start_after: Option<&str>,
+    limit: u32,
+) -> Vec<String> {
+    let (uref_ptr, uref_size, _bytes1) = contract_api::to_ptr(dictionary_seed_uref);
+    let (cursor_ptr, cursor_size, _bytes2) = contract_api::to_ptr(start_after.map(String::from));
+
+    let value_size = {
+        let mut value_size = MaybeUninit::uninit();
+        let ret = unsafe {
+            ext_ffi::casper_dictionary_iter(
+                uref_ptr,
+                uref_size,
+                cursor_ptr,
+                cursor_size,
+                limit,
+                value_size.as_mut_ptr(),
+            )
+        };
+        api_error::result_from(ret).unwrap_or_revert();
+        unsafe { value_size.assume_init() }
+    };
+
+    let value_bytes = runtime::read_host_buffer(value_size).unwrap_or_revert();
+    bytesrepr::deserialize(value_bytes).unwrap_or_revert()
+}
@@ -762,4 +762,41 @@ extern "C" {
         value_ptr: *const u8,
         value_size: usize,
     ) -> i32;
+    /// Emits an event with the given topic and payload.  The event is recorded in the execution
+    /// results of the deploy, along with the key of the account or contract which emitted it.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_ptr` - pointer to the UTF-8 bytes of the topic
+    /// * `topic_size` - size of the topic (in bytes)
+    /// * `payload_ptr` - pointer to bytes representing the serialized `CLValue` payload
+    /// * `payload_size` - size of the payload (in bytes)
+    pub fn casper_emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        payload_ptr: *const u8,
+        payload_size: usize,
+    ) -> i32;
+    /// Reads up to `limit` item keys of the dictionary accessed by the given seed `URef`, in
+    /// ascending order after the given cursor.  The item keys are serialized as a `Vec<String>`
+    /// and buffered in the runtime.  This result can be obtained via the
+    /// [`casper_read_host_buffer`] function.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to bytes representing the seed `URef` of the dictionary
+    /// * `uref_size` - size of the `URef` (in bytes)
+    /// * `cursor_ptr` - pointer to bytes representing the serialized `Option<String>` item key to
+    ///   start after
+    /// * `cursor_size` - size of the cursor (in bytes)
+    /// * `limit` - maximum number of item keys to read
+    /// * `output_size` - pointer to a value where host will write size of bytes of the item keys
+    pub fn casper_dictionary_iter(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        cursor_ptr: *const u8,
+        cursor_size: usize,
+        limit: u32,
+        output_size_ptr: *mut usize,
+    ) -> i32;
 }
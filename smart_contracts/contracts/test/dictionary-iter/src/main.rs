@@ -0,0 +1,43 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec::Vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+const DICTIONARY_NAME: &str = "dictionary";
+const ITEM_KEYS_NAME: &str = "item_keys";
+const ARG_ITEM_COUNT: &str = "item_count";
+const ARG_PAGE_SIZE: &str = "page_size";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let item_count: u32 = runtime::get_named_arg(ARG_ITEM_COUNT);
+    let page_size: u32 = runtime::get_named_arg(ARG_PAGE_SIZE);
+
+    let dictionary_seed_uref = storage::new_dictionary(DICTIONARY_NAME).unwrap_or_revert();
+    // Put the items in descending order so the index has to keep them sorted.
+    for index in (0..item_count).rev() {
+        storage::dictionary_put(dictionary_seed_uref, &format!("item-{:04}", index), index);
+    }
+
+    let mut item_keys: Vec<String> = Vec::new();
+    loop {
+        let page = storage::dictionary_iter(
+            dictionary_seed_uref,
+            item_keys.last().map(String::as_str),
+            page_size,
+        );
+        if page.is_empty() {
+            break;
+        }
+        item_keys.extend(page);
+    }
+
+    runtime::put_key(ITEM_KEYS_NAME, storage::new_uref(item_keys).into());
+}
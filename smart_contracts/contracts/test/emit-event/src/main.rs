@@ -0,0 +1,19 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::contract_api::runtime;
+use casper_types::U512;
+
+const ARG_TOPIC: &str = "topic";
+const ARG_PAYLOAD: &str = "payload";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let topic: String = runtime::get_named_arg(ARG_TOPIC);
+    let payload: U512 = runtime::get_named_arg(ARG_PAYLOAD);
+    runtime::emit_event(&topic, payload);
+}
@@ -338,6 +338,24 @@ pub enum ApiError {
     /// assert_eq!(ApiError::from(38), ApiError::MissingSystemContractHash);
     /// ```
     MissingSystemContractHash,
+    /// The event topic length exceeds the maximum length.
+    /// ```
+    /// # use casper_types::ApiError;
+    /// assert_eq!(ApiError::from(39), ApiError::EventTopicExceedsLength);
+    /// ```
+    EventTopicExceedsLength,
+    /// The event topic is invalid.
+    /// ```
+    /// # use casper_types::ApiError;
+    /// assert_eq!(ApiError::from(40), ApiError::InvalidEventTopic);
+    /// ```
+    InvalidEventTopic,
+    /// The dictionary was created without an index of its item keys, so they can't be listed.
+    /// ```
+    /// # use casper_types::ApiError;
+    /// assert_eq!(ApiError::from(41), ApiError::DictionaryNotEnumerable);
+    /// ```
+    DictionaryNotEnumerable,
     /// Error specific to Auction contract. See
     /// [casper_types::system::auction::Error](crate::system::auction::Error).
     /// ```
@@ -525,6 +543,9 @@ impl From<ApiError> for u32 {
             ApiError::DictionaryItemKeyExceedsLength => 36,
             ApiError::InvalidDictionaryItemKey => 37,
             ApiError::MissingSystemContractHash => 38,
+            ApiError::EventTopicExceedsLength => 39,
+            ApiError::InvalidEventTopic => 40,
+            ApiError::DictionaryNotEnumerable => 41,
             ApiError::AuctionError(value) => AUCTION_ERROR_OFFSET + u32::from(value),
             ApiError::ContractHeader(value) => HEADER_ERROR_OFFSET + u32::from(value),
             ApiError::Mint(value) => MINT_ERROR_OFFSET + u32::from(value),
@@ -575,6 +596,9 @@ impl From<u32> for ApiError {
             36 => ApiError::DictionaryItemKeyExceedsLength,
             37 => ApiError::InvalidDictionaryItemKey,
             38 => ApiError::MissingSystemContractHash,
+            39 => ApiError::EventTopicExceedsLength,
+            40 => ApiError::InvalidEventTopic,
+            41 => ApiError::DictionaryNotEnumerable,
             USER_ERROR_MIN..=USER_ERROR_MAX => ApiError::User(value as u16),
             HP_ERROR_MIN..=HP_ERROR_MAX => ApiError::HandlePayment(value as u8),
             MINT_ERROR_MIN..=MINT_ERROR_MAX => ApiError::Mint(value as u8),
@@ -630,6 +654,9 @@ impl Debug for ApiError {
             }
             ApiError::InvalidDictionaryItemKey => write!(f, "ApiError::InvalidDictionaryItemKey")?,
             ApiError::MissingSystemContractHash => write!(f, "ApiError::MissingContractHash")?,
+            ApiError::EventTopicExceedsLength => write!(f, "ApiError::EventTopicExceedsLength")?,
+            ApiError::InvalidEventTopic => write!(f, "ApiError::InvalidEventTopic")?,
+            ApiError::DictionaryNotEnumerable => write!(f, "ApiError::DictionaryNotEnumerable")?,
             ApiError::AuctionError(value) => write!(
                 f,
                 "ApiError::AuctionError({:?})",
@@ -837,6 +864,9 @@ mod tests {
         round_trip(Err(ApiError::HostBufferEmpty));
         round_trip(Err(ApiError::HostBufferFull));
         round_trip(Err(ApiError::AllocLayout));
+        round_trip(Err(ApiError::EventTopicExceedsLength));
+        round_trip(Err(ApiError::InvalidEventTopic));
+        round_trip(Err(ApiError::DictionaryNotEnumerable));
         round_trip(Err(ApiError::ContractHeader(0)));
         round_trip(Err(ApiError::ContractHeader(u8::MAX)));
         round_trip(Err(ApiError::Mint(0)));
@@ -15,6 +15,8 @@ use alloc::{
     vec::Vec,
 };
 
+#[cfg(feature = "datasize")]
+use datasize::DataSize;
 #[cfg(feature = "json-schema")]
 use once_cell::sync::Lazy;
 use rand::{
@@ -32,7 +34,7 @@ use crate::{
     account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     system::auction::{Bid, EraInfo, UnbondingPurse},
-    CLValue, DeployInfo, NamedKey, Transfer, TransferAddr, U128, U256, U512,
+    CLValue, DeployInfo, Key, NamedKey, Transfer, TransferAddr, U128, U256, U512,
 };
 
 /// Constants to track ExecutionResult serialization.
@@ -65,6 +67,9 @@ const TRANSFORM_ADD_UINT512_TAG: u8 = 15;
 const TRANSFORM_ADD_KEYS_TAG: u8 = 16;
 const TRANSFORM_FAILURE_TAG: u8 = 17;
 
+/// The maximum length in bytes of the topic of a [`ContractEvent`].
+pub const EVENT_TOPIC_MAX_LENGTH: usize = 64;
+
 #[cfg(feature = "json-schema")]
 static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
     let operations = vec![
@@ -323,6 +328,50 @@ impl FromBytes for ExecutionEffect {
     }
 }
 
+/// An event emitted by a contract while executing a deploy.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractEvent {
+    /// The key of the account or contract which emitted the event.
+    pub emitter: Key,
+    /// The topic of the event.
+    pub topic: String,
+    /// The payload of the event.
+    pub payload: CLValue,
+}
+
+impl ToBytes for ContractEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.emitter.to_bytes()?);
+        buffer.extend(self.topic.to_bytes()?);
+        buffer.extend(self.payload.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.emitter.serialized_length()
+            + self.topic.serialized_length()
+            + self.payload.serialized_length()
+    }
+}
+
+impl FromBytes for ContractEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (emitter, remainder) = Key::from_bytes(bytes)?;
+        let (topic, remainder) = String::from_bytes(remainder)?;
+        let (payload, remainder) = CLValue::from_bytes(remainder)?;
+        let contract_event = ContractEvent {
+            emitter,
+            topic,
+            payload,
+        };
+        Ok((contract_event, remainder))
+    }
+}
+
 /// An operation performed while executing a deploy.
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
@@ -688,4 +737,15 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn bytesrepr_test_contract_event() {
+        let mut rng = get_rng();
+        let contract_event = ContractEvent {
+            emitter: Key::Hash(rng.gen()),
+            topic: "transfer".to_string(),
+            payload: CLValue::from_t(rng.gen::<u64>()).unwrap(),
+        };
+        bytesrepr::test_serialization_roundtrip(&contract_event);
+    }
 }
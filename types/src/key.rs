@@ -23,6 +23,8 @@ use rand::{
     distributions::{Distribution, Standard},
     Rng,
 };
+#[cfg(feature = "json-schema")]
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
@@ -657,6 +659,7 @@ mod serde_helpers {
     use super::*;
 
     #[derive(Serialize, Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
     pub(super) enum HumanReadable {
         Account(String),
         Hash(String),
@@ -795,6 +798,25 @@ impl Serialize for Key {
     }
 }
 
+/// We need to implement `JsonSchema` for `Key` as though it is its human-readable serde helper.
+#[cfg(feature = "json-schema")]
+impl JsonSchema for Key {
+    fn schema_name() -> String {
+        String::from("Key")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let schema = serde_helpers::HumanReadable::json_schema(gen);
+        let mut schema_object = schema.into_object();
+        schema_object.metadata().description = Some(
+            "The key under which data is stored in global state, as its formatted string tagged \
+            with its variant"
+                .to_string(),
+        );
+        schema_object.into()
+    }
+}
+
 impl<'de> Deserialize<'de> for Key {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         if deserializer.is_human_readable() {
@@ -63,7 +63,8 @@ pub use contracts::{
 pub use crypto::*;
 pub use deploy_info::DeployInfo;
 pub use execution_result::{
-    ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
+    ContractEvent, ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
+    EVENT_TOPIC_MAX_LENGTH,
 };
 pub use gas::Gas;
 pub use json_pretty_printer::json_pretty_print;
@@ -86,7 +87,9 @@ pub use transfer::{
     TRANSFER_ADDR_LENGTH,
 };
 pub use transfer_result::{TransferResult, TransferredTo};
-pub use uref::{FromStrError as URefFromStrError, URef, UREF_ADDR_LENGTH, UREF_SERIALIZED_LENGTH};
+pub use uref::{
+    FromStrError as URefFromStrError, URef, URefAddr, UREF_ADDR_LENGTH, UREF_SERIALIZED_LENGTH,
+};
 
 pub use crate::{
     era_id::EraId,